@@ -4,8 +4,10 @@ use crate::{
 };
 use dbsp::{
     algebra::ZRingValue,
-    operator::{DelayedFeedback, NeighborhoodDescr},
-    CollectionHandle, InputHandle, RootCircuit, Stream, UpsertHandle, ZSet,
+    circuit::operator_traits::Data,
+    operator::{communication::new_exchange_operators, DelayedFeedback, NeighborhoodDescr},
+    trace::Rkyv,
+    Circuit, CollectionHandle, InputHandle, RootCircuit, Runtime, Stream, UpsertHandle, ZSet,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -226,16 +228,39 @@ impl Catalog {
     {
         let circuit = stream.circuit();
 
-        // Create handle for the stream itself.
-        let delta_handle = stream.output();
+        // Create handle for the stream itself.  In a multihost pipeline, output
+        // endpoints only run on the first host, so collect changes from all hosts
+        // at worker 0.
+        let multihost = Runtime::runtime()
+            .map(|runtime| runtime.layout().other_hosts().next().is_some())
+            .unwrap_or(false);
+        let delta_handle = if multihost {
+            stream.gather(0).output()
+        } else {
+            stream.output()
+        };
 
         // Improve the odds that `integrate_trace` below reuses the trace of `stream`
         // if one exists.
         let stream = stream.try_sharded_version();
 
-        // Create handles for the neighborhood query.
-        let (neighborhood_descr_stream, neighborhood_descr_handle) =
+        // Create handles for the neighborhood query.  Queries are only submitted
+        // to the first host of a multihost pipeline, but all hosts compute
+        // their share of the result.
+        let (neighborhood_descr_input, neighborhood_descr_handle) =
             circuit.add_input_stream::<(bool, Option<NeighborhoodDescr<D, ()>>)>();
+        let neighborhood_descr_stream = neighborhood_descr_input.apply(|(reset, descr)| {
+            // Convert anchor of type `D` into `Z::Key`.
+            let descr = descr.clone().map(|descr| {
+                NeighborhoodDescr::new(descr.anchor.map(From::from), (), descr.before, descr.after)
+            });
+            (*reset, descr)
+        });
+        let neighborhood_descr_stream = if multihost {
+            broadcast_from_worker0(&neighborhood_descr_stream)
+        } else {
+            neighborhood_descr_stream
+        };
         let neighborhood_stream = {
             // Create a feedback loop to latch the latest neighborhood descriptor
             // when `reset=true`.
@@ -248,15 +273,7 @@ impl Catalog {
                     .stream()
                     .apply2(&neighborhood_descr_stream, |old, (reset, new)| {
                         if *reset {
-                            // Convert anchor of type `D` into `Z::Key`.
-                            new.clone().map(|new| {
-                                NeighborhoodDescr::new(
-                                    new.anchor.map(From::from),
-                                    (),
-                                    new.before,
-                                    new.after,
-                                )
-                            })
+                            new.clone()
                         } else {
                             old.clone()
                         }
@@ -276,6 +293,11 @@ impl Catalog {
 
         // Handle for the quantiles query.
         let (num_quantiles_stream, num_quantiles_handle) = circuit.add_input_stream::<usize>();
+        let num_quantiles_stream = if multihost {
+            broadcast_from_worker0(&num_quantiles_stream)
+        } else {
+            num_quantiles_stream
+        };
 
         // Output of the quantiles query, only produced when `num_quantiles>0`.
         let quantiles_stream = stream
@@ -342,3 +364,34 @@ impl Catalog {
             })
     }
 }
+
+/// Replaces the value of `stream` in every worker with its value in worker 0.
+///
+/// Used in multihost pipelines, where query parameters are only pushed to the
+/// input handles of the first host, to make them visible to workers on all
+/// hosts.
+fn broadcast_from_worker0<T>(stream: &Stream<RootCircuit, T>) -> Stream<RootCircuit, T>
+where
+    T: Data + Default + Send + Rkyv,
+{
+    let runtime = Runtime::runtime().unwrap();
+    let workers = runtime.num_workers();
+    let worker = Runtime::worker_index();
+
+    let (sender, receiver) = new_exchange_operators(
+        &runtime,
+        worker,
+        None,
+        move |value: T, values: &mut Vec<Option<T>>| {
+            let value = (worker == 0).then_some(value);
+            values.extend((0..workers).map(|_| value.clone()));
+        },
+        |result: &mut T, value: Option<T>| {
+            if let Some(value) = value {
+                *result = value;
+            }
+        },
+    );
+
+    stream.circuit().add_exchange(sender, receiver, stream)
+}
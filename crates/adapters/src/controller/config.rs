@@ -7,9 +7,10 @@
 
 use crate::{ControllerError, InputFormat, OutputFormat, OutputQuery};
 use actix_web::HttpRequest;
+use dbsp::circuit::Layout;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value as YamlValue;
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{borrow::Cow, collections::BTreeMap, net::SocketAddr, time::Duration};
 use utoipa::ToSchema;
 
 /// Default value of `InputEndpointConfig::max_buffered_records`.
@@ -24,6 +25,26 @@ const fn default_workers() -> u16 {
     1
 }
 
+/// Default value of `RestartPolicy::max_retries`.
+const fn default_restart_max_retries() -> u32 {
+    3
+}
+
+/// Default value of `RestartPolicy::initial_backoff_ms`.
+const fn default_restart_initial_backoff_ms() -> u64 {
+    1_000
+}
+
+/// Default value of `RestartPolicy::max_backoff_ms`.
+const fn default_restart_max_backoff_ms() -> u64 {
+    60_000
+}
+
+/// Default value of `RestartPolicy::reset_after_ms`.
+const fn default_restart_reset_after_ms() -> u64 {
+    600_000
+}
+
 /// Pipeline configuration specified by the user when creating
 /// a new pipeline instance.
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -48,6 +69,9 @@ pub struct PipelineConfig {
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
 pub struct RuntimeConfig {
     /// Number of DBSP worker threads.
+    ///
+    /// Ignored in multihost pipelines, where the number of workers is
+    /// specified for each host in `multihost.hosts`.
     #[serde(default = "default_workers")]
     pub workers: u16,
 
@@ -69,6 +93,29 @@ pub struct RuntimeConfig {
     /// get buffered by the controller, defaults to 0.
     #[serde(default)]
     pub max_buffering_delay_usecs: u64,
+
+    /// Multihost layout.
+    ///
+    /// When specified, the pipeline runs as a single DBSP circuit distributed
+    /// across several processes, typically on different machines.  Each
+    /// process is started with the same configuration, except for
+    /// `multihost.local_host`.
+    #[serde(default)]
+    pub multihost: Option<MultihostConfig>,
+
+    /// Export circuit steps, operator evaluations, and connector reads as
+    /// OpenTelemetry-compatible tracing spans.
+    ///
+    /// Disabled by default.
+    #[serde(default)]
+    pub tracing: Option<TracingConfig>,
+
+    /// Policy for restarting the pipeline after it fails.
+    ///
+    /// Applied by the pipeline manager; the pipeline itself ignores this
+    /// setting.  By default, failed pipelines are not restarted.
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
 }
 
 impl RuntimeConfig {
@@ -79,6 +126,209 @@ impl RuntimeConfig {
     pub fn to_yaml(config: &Self) -> String {
         serde_yaml::to_string(config).unwrap()
     }
+
+    /// Index of the current host in a multihost pipeline; 0 in a single-host
+    /// pipeline.
+    pub fn local_host(&self) -> usize {
+        self.multihost
+            .as_ref()
+            .map(|multihost| multihost.local_host)
+            .unwrap_or(0)
+    }
+
+    /// Number of hosts that run the pipeline.
+    pub fn num_hosts(&self) -> usize {
+        self.multihost
+            .as_ref()
+            .map(|multihost| multihost.hosts.len())
+            .unwrap_or(1)
+    }
+
+    /// Layout of the DBSP circuit described by this configuration.
+    pub fn layout(&self) -> Result<Layout, ControllerError> {
+        match &self.multihost {
+            None => {
+                if self.workers == 0 {
+                    return Err(ControllerError::invalid_layout(
+                        "the number of workers must be positive",
+                    ));
+                }
+                Ok(Layout::new_solo(self.workers as usize))
+            }
+            Some(multihost) => multihost.layout(),
+        }
+    }
+}
+
+/// Layout of a pipeline that runs on multiple hosts.
+///
+/// The first host in `hosts` is the coordinator.  It decides when the circuit
+/// performs a step and runs all output endpoints.  Each input endpoint runs
+/// on the host specified in its
+/// [`host`](`InputEndpointConfig::host`) attribute.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct MultihostConfig {
+    /// Hosts that run the pipeline.  Worker threads are numbered
+    /// sequentially across hosts, in this order.
+    pub hosts: Vec<HostConfig>,
+
+    /// Index of the current host in `hosts`.
+    #[serde(default)]
+    pub local_host: usize,
+
+    /// Address where the controller on the first host accepts connections
+    /// from controllers on other hosts.
+    #[schema(value_type = String)]
+    pub coordinator_address: SocketAddr,
+}
+
+impl MultihostConfig {
+    /// Layout of the DBSP circuit described by this configuration.
+    pub fn layout(&self) -> Result<Layout, ControllerError> {
+        if self.hosts.is_empty() {
+            return Err(ControllerError::invalid_layout(
+                "the list of hosts is empty",
+            ));
+        }
+        if self.local_host >= self.hosts.len() {
+            return Err(ControllerError::invalid_layout(&format!(
+                "local host index {} is out of range for a layout with {} hosts",
+                self.local_host,
+                self.hosts.len()
+            )));
+        }
+        if let Some(host) = self.hosts.iter().find(|host| host.workers == 0) {
+            return Err(ControllerError::invalid_layout(&format!(
+                "host {} has no workers",
+                host.address
+            )));
+        }
+
+        let params = self
+            .hosts
+            .iter()
+            .map(|host| (host.address, host.workers as usize))
+            .collect();
+        Layout::new_multihost(&params, self.hosts[self.local_host].address)
+            .map_err(|e| ControllerError::invalid_layout(&e))
+    }
+}
+
+/// Destination of the tracing spans produced by a pipeline (see
+/// [`RuntimeConfig::tracing`]).
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "exporter", rename_all = "snake_case")]
+pub enum TracingConfig {
+    /// Send spans to an OpenTelemetry collector using OTLP over gRPC.
+    Otlp {
+        /// Collector endpoint, e.g., `http://localhost:4317`.
+        endpoint: String,
+    },
+    /// Append a JSON record to a local file for every closed span.
+    JsonFile {
+        /// Path to the output file.
+        path: String,
+    },
+}
+
+/// Policy for restarting a failed pipeline (see
+/// [`RuntimeConfig::restart_policy`]).
+///
+/// Restarts are delayed with exponential backoff: the first restart happens
+/// `initial_backoff_ms` milliseconds after the failure, and the delay doubles
+/// with every subsequent restart, up to `max_backoff_ms`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct RestartPolicy {
+    /// When to restart the pipeline.
+    #[serde(default)]
+    pub mode: RestartMode,
+
+    /// Maximal number of restarts in the `on_failure` mode.
+    #[serde(default = "default_restart_max_retries")]
+    pub max_retries: u32,
+
+    /// Delay before the first restart, in milliseconds.
+    #[serde(default = "default_restart_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+
+    /// Maximal delay between restarts, in milliseconds.
+    #[serde(default = "default_restart_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+
+    /// Time in milliseconds a restarted pipeline must run without failing
+    /// before its restart count is reset.  Afterwards, the next failure is
+    /// treated like the first one: it counts towards `max_retries` from
+    /// zero and is retried after `initial_backoff_ms`.
+    #[serde(default = "default_restart_reset_after_ms")]
+    pub reset_after_ms: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::default(),
+            max_retries: default_restart_max_retries(),
+            initial_backoff_ms: default_restart_initial_backoff_ms(),
+            max_backoff_ms: default_restart_max_backoff_ms(),
+            reset_after_ms: default_restart_reset_after_ms(),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Whether a pipeline that has already been restarted `restarts` times
+    /// should be restarted after another failure.
+    pub fn allows_restart(&self, restarts: u32) -> bool {
+        match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => restarts < self.max_retries,
+            RestartMode::Always => true,
+        }
+    }
+
+    /// Delay before restarting a pipeline that has already been restarted
+    /// `restarts` times.
+    pub fn backoff(&self, restarts: u32) -> Duration {
+        let factor = 1u64.checked_shl(restarts).unwrap_or(u64::MAX);
+        Duration::from_millis(
+            self.initial_backoff_ms
+                .saturating_mul(factor)
+                .min(self.max_backoff_ms),
+        )
+    }
+
+    /// Time a restarted pipeline must run without failing before its
+    /// restart count is reset.
+    pub fn reset_after(&self) -> Duration {
+        Duration::from_millis(self.reset_after_ms)
+    }
+}
+
+/// When to restart a failed pipeline.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    /// Leave the pipeline in the `Failed` state until the user shuts it
+    /// down.
+    #[default]
+    Never,
+    /// Restart the pipeline up to `max_retries` times.
+    OnFailure,
+    /// Restart the pipeline every time it fails.
+    Always,
+}
+
+/// A host in a [`MultihostConfig`].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct HostConfig {
+    /// Address used by the DBSP runtime on this host to exchange data with
+    /// other hosts.
+    #[schema(value_type = String)]
+    pub address: SocketAddr,
+
+    /// Number of DBSP worker threads on this host.
+    #[serde(default = "default_workers")]
+    pub workers: u16,
 }
 
 /// Describes an input connector configuration
@@ -88,6 +338,11 @@ pub struct InputEndpointConfig {
     /// connected to.
     pub stream: Cow<'static, str>,
 
+    /// Index of the host that runs this endpoint in a multihost pipeline
+    /// (see [`MultihostConfig`]).  Ignored by single-host pipelines.
+    #[serde(default)]
+    pub host: usize,
+
     /// Connector configuration.
     #[serde(flatten)]
     pub connector_config: ConnectorConfig,
@@ -211,3 +466,63 @@ impl FormatConfig {
         })
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{RestartMode, RestartPolicy};
+    use std::time::Duration;
+
+    fn policy(mode: RestartMode) -> RestartPolicy {
+        RestartPolicy {
+            mode,
+            max_retries: 3,
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            reset_after_ms: 5_000,
+        }
+    }
+
+    #[test]
+    fn restart_modes() {
+        let never = policy(RestartMode::Never);
+        assert!(!never.allows_restart(0));
+
+        let on_failure = policy(RestartMode::OnFailure);
+        assert!(on_failure.allows_restart(0));
+        assert!(on_failure.allows_restart(2));
+        assert!(!on_failure.allows_restart(3));
+        assert!(!on_failure.allows_restart(u32::MAX));
+
+        let always = policy(RestartMode::Always);
+        assert!(always.allows_restart(0));
+        assert!(always.allows_restart(u32::MAX));
+    }
+
+    #[test]
+    fn restart_backoff() {
+        let policy = policy(RestartMode::Always);
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(800));
+        // Capped at `max_backoff_ms`.
+        assert_eq!(policy.backoff(4), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(63), Duration::from_millis(1_000));
+        // The shift overflows.
+        assert_eq!(policy.backoff(64), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(1_000));
+
+        let uncapped = RestartPolicy {
+            max_backoff_ms: u64::MAX,
+            ..policy
+        };
+        assert_eq!(uncapped.backoff(u32::MAX), Duration::from_millis(u64::MAX));
+    }
+
+    #[test]
+    fn restart_reset_after() {
+        assert_eq!(
+            policy(RestartMode::OnFailure).reset_after(),
+            Duration::from_millis(5_000)
+        );
+    }
+}
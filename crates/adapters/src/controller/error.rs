@@ -75,6 +75,9 @@ pub enum ConfigError {
         endpoint_name: String,
         stream_name: String,
     },
+
+    /// The configuration specifies an invalid single- or multihost layout.
+    InvalidLayout { error: String },
 }
 
 impl StdError for ConfigError {}
@@ -93,6 +96,7 @@ impl DetailedError for ConfigError {
             Self::UnknownOutputTransport { .. } => Cow::from("UnknownOutputTransport"),
             Self::UnknownInputStream { .. } => Cow::from("UnknownInputStream"),
             Self::UnknownOutputStream { .. } => Cow::from("UnknownOutputStream"),
+            Self::InvalidLayout { .. } => Cow::from("InvalidLayout"),
         }
     }
 }
@@ -168,6 +172,9 @@ impl Display for ConfigError {
             } => {
                 write!(f, "Output endpoint '{endpoint_name}' specifies unknown output table or view '{stream_name}'")
             }
+            Self::InvalidLayout { error } => {
+                write!(f, "Invalid pipeline layout: {error}")
+            }
         }
     }
 }
@@ -257,6 +264,15 @@ impl ConfigError {
             stream_name: stream_name.to_owned(),
         }
     }
+
+    pub fn invalid_layout<E>(error: &E) -> Self
+    where
+        E: ToString + ?Sized,
+    {
+        Self::InvalidLayout {
+            error: error.to_string(),
+        }
+    }
 }
 
 /// Controller error.
@@ -321,6 +337,9 @@ pub enum ControllerError {
     /// Error inside the Prometheus module.
     PrometheusError { error: String },
 
+    /// Error setting up the export of tracing spans.
+    TracingError { error: String },
+
     // TODO: we currently don't have a way to include more info about the panic.
     /// Panic inside the DBSP runtime.
     DbspPanic,
@@ -409,6 +428,7 @@ impl DetailedError for ControllerError {
             Self::InputTransportError { .. } => Cow::from("InputTransportError"),
             Self::OutputTransportError { .. } => Cow::from("OutputTransportError"),
             Self::PrometheusError { .. } => Cow::from("PrometheusError"),
+            Self::TracingError { .. } => Cow::from("TracingError"),
             Self::DbspError { error } => error.error_code(),
             Self::DbspPanic => Cow::from("DbspPanic"),
             Self::ControllerPanic => Cow::from("ControllerPanic"),
@@ -475,6 +495,9 @@ impl Display for ControllerError {
             Self::PrometheusError { error } => {
                 write!(f, "Error in the Prometheus metrics module: '{error}'")
             }
+            Self::TracingError { error } => {
+                write!(f, "Error initializing span export: '{error}'")
+            }
             Self::DbspError { error } => {
                 write!(f, "DBSP error: {error}")
             }
@@ -581,6 +604,15 @@ impl ControllerError {
         }
     }
 
+    pub fn invalid_layout<E>(error: &E) -> Self
+    where
+        E: ToString + ?Sized,
+    {
+        Self::Config {
+            config_error: ConfigError::invalid_layout(error),
+        }
+    }
+
     pub fn input_transport_error(endpoint_name: &str, fatal: bool, error: AnyError) -> Self {
         Self::InputTransportError {
             endpoint_name: endpoint_name.to_owned(),
@@ -620,6 +652,15 @@ impl ControllerError {
         }
     }
 
+    pub fn tracing_error<E>(error: &E) -> Self
+    where
+        E: ToString,
+    {
+        Self::TracingError {
+            error: error.to_string(),
+        }
+    }
+
     pub fn dbsp_error(error: DBSPError) -> Self {
         Self::DbspError { error }
     }
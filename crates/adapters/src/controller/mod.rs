@@ -32,6 +32,16 @@
 //! The probe passes the data through to the parser, while counting the number
 //! of transmitted bytes and records and updating respective performance
 //! counters in the controller.
+//!
+//! # Multihost pipelines
+//!
+//! A pipeline whose [`RuntimeConfig`] specifies a
+//! [multihost layout](`MultihostConfig`) runs one controller per host.  The
+//! controller on the first host (the coordinator) decides when all hosts
+//! perform a step and runs all output endpoints, whose outputs are gathered
+//! from all hosts.  Controllers on other hosts (followers) run the input
+//! endpoints assigned to them and step when instructed by the coordinator
+//! (see the `multihost` module).
 
 use crate::{
     Catalog, Encoder, InputConsumer, InputEndpoint, InputFormat, InputTransport, OutputConsumer,
@@ -43,29 +53,42 @@ use crossbeam::{
     queue::SegQueue,
     sync::{Parker, ShardedLock, Unparker},
 };
-use dbsp::DBSPHandle;
+use dbsp::{
+    circuit::GlobalNodeId,
+    profile::{GraphProfile, OperatorMemoryProfile},
+    DBSPHandle, Error as DBSPError, RuntimeError,
+};
 use log::{debug, error, info};
 use std::{
-    collections::{BTreeMap, BTreeSet, HashSet},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashSet},
+    fs,
+    mem::take,
+    path::Path,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{sync_channel, Receiver, SyncSender},
         Arc, Mutex,
     },
     thread::{spawn, JoinHandle},
     time::{Duration, Instant},
 };
+use tracing::{field, info_span, Span};
 
 mod config;
 mod error;
+mod multihost;
 mod stats;
 
 pub use config::{
-    ConnectorConfig, FormatConfig, InputEndpointConfig, OutputEndpointConfig, PipelineConfig,
-    RuntimeConfig, TransportConfig,
+    ConnectorConfig, FormatConfig, HostConfig, InputEndpointConfig, MultihostConfig,
+    OutputEndpointConfig, PipelineConfig, RestartMode, RestartPolicy, RuntimeConfig, TracingConfig,
+    TransportConfig,
 };
 pub use error::{ConfigError, ControllerError};
 pub use stats::{ControllerStatus, InputEndpointStatus, OutputEndpointStatus};
 
+use multihost::{Command, Follower, MultihostRole};
+
 /// Maximal number of concurrent API connections per circuit
 /// (including both input and output connecions).
 // TODO: make this configurable.
@@ -105,7 +128,9 @@ impl Controller {
     /// * `catalog` - A catalog of input and output streams of the circuit.
     ///
     /// * `config` - Controller configuration, including global config settings
-    ///   and individual endpoint configs.
+    ///   and individual endpoint configs.  In a multihost pipeline, only
+    ///   input endpoints assigned to the local host are connected, and output
+    ///   endpoints are only connected on the first host.
     ///
     /// * `error_cb` - Error callback.  The controller doesn't implement its own
     ///   error handling policy, but simply forwards most errors to this
@@ -131,9 +156,12 @@ impl Controller {
         let backpressure_thread_parker = Parker::new();
         let backpressure_thread_unparker = backpressure_thread_parker.unparker().clone();
 
+        let multihost = MultihostRole::new(&config.global, &circuit_thread_unparker)?;
+
         let inner = Arc::new(ControllerInner::new(
             catalog,
             &config.global,
+            multihost,
             circuit_thread_unparker,
             backpressure_thread_unparker,
             error_cb,
@@ -145,6 +173,12 @@ impl Controller {
                 .map_err(ControllerError::dbsp_error)?;
         }
 
+        if config.global.tracing.is_some() {
+            circuit
+                .enable_span_tracer()
+                .map_err(ControllerError::dbsp_error)?;
+        }
+
         let backpressure_thread_handle = {
             let inner = inner.clone();
             spawn(move || Self::backpressure_thread(inner, backpressure_thread_parker))
@@ -152,15 +186,24 @@ impl Controller {
 
         let circuit_thread_handle = {
             let inner = inner.clone();
-            spawn(move || Self::circuit_thread(circuit, inner, circuit_thread_parker))
+            spawn(move || {
+                let result = Self::circuit_thread(circuit, inner.clone(), circuit_thread_parker);
+                inner.close_circuit_requests();
+                result
+            })
         };
 
+        let local_host = config.global.local_host();
         for (input_name, input_config) in config.inputs.iter() {
-            inner.connect_input(input_name, input_config)?;
+            if config.global.multihost.is_none() || input_config.host == local_host {
+                inner.connect_input(input_name, input_config)?;
+            }
         }
 
-        for (output_name, output_config) in config.outputs.iter() {
-            inner.connect_output(output_name, output_config)?;
+        if local_host == 0 {
+            for (output_name, output_config) in config.outputs.iter() {
+                inner.connect_output(output_name, output_config)?;
+            }
         }
 
         Ok(Self {
@@ -323,6 +366,44 @@ impl Controller {
         self.inner.dump_profile();
     }
 
+    /// Requests the memory used by each stateful operator in the circuit,
+    /// summed across all worker threads.
+    ///
+    /// The profile is collected by the circuit thread between steps, so the
+    /// response is only ready once the current step, if any, completes.  Its
+    /// cost is proportional to the size of the circuit's state.  In a
+    /// multihost pipeline, only covers the workers of the local host.
+    pub fn memory_profile(
+        &self,
+    ) -> Result<CircuitResponse<Vec<OperatorMemoryProfile>>, ControllerError> {
+        self.inner.circuit_request(CircuitRequest::MemoryProfile)
+    }
+
+    /// Returns the last memory profile collected by the circuit thread
+    /// without waiting for the circuit.
+    ///
+    /// If the profile is older than `max_age` or was never collected, asks
+    /// the circuit thread to collect a new one after the current step, which
+    /// subsequent calls will return.  Returns `None` until the first profile
+    /// is available.
+    pub fn cached_memory_profile(
+        &self,
+        max_age: Duration,
+    ) -> Option<Arc<Vec<OperatorMemoryProfile>>> {
+        self.inner.cached_memory_profile(max_age)
+    }
+
+    /// Requests the circuit graph annotated with per-operator statistics for
+    /// each worker thread.
+    ///
+    /// CPU usage statistics are only available if the pipeline was started
+    /// with [`RuntimeConfig::cpu_profiler`] enabled.  Like
+    /// [`Self::memory_profile`], the response is ready once the current step
+    /// completes.
+    pub fn graph_profile(&self) -> Result<CircuitResponse<Vec<GraphProfile>>, ControllerError> {
+        self.inner.circuit_request(CircuitRequest::GraphProfile)
+    }
+
     /// Terminate the controller, stop all input endpoints and destroy the
     /// circuit.
     pub fn stop(self) -> Result<(), ControllerError> {
@@ -361,6 +442,10 @@ impl Controller {
         controller: Arc<ControllerInner>,
         parker: Parker,
     ) -> Result<(), ControllerError> {
+        if let Some(MultihostRole::Follower(follower)) = &controller.multihost {
+            return Self::follower_circuit_thread(circuit, &controller, follower, parker);
+        }
+
         let mut start: Option<Instant> = None;
 
         let max_buffering_delay =
@@ -368,24 +453,17 @@ impl Controller {
         let min_batch_size_records = controller.status.global_config.min_batch_size_records;
 
         loop {
-            let dump_profile = controller
-                .dump_profile_request
-                .swap(false, Ordering::AcqRel);
-            if dump_profile {
-                // Generate profile in the current working directory.
-                // TODO: make this location configurable.
-                match circuit.dump_profile("profile") {
-                    Ok(path) => {
-                        info!(
-                            "circuit profile dump created in '{}'",
-                            path.canonicalize().unwrap_or_default().display()
-                        );
-                    }
-                    Err(e) => {
-                        error!("failed to dump circuit profile: {e}");
-                    }
+            Self::dump_profile_if_requested(&mut circuit, &controller);
+            Self::process_circuit_requests(&mut circuit, &controller);
+
+            // The pipeline cannot make progress after losing a host.
+            if let Some(MultihostRole::Coordinator(coordinator)) = &controller.multihost {
+                if let Some(error) = coordinator.take_error() {
+                    controller.error(error);
+                    controller.stop();
                 }
             }
+
             match controller.state() {
                 PipelineState::Running | PipelineState::Paused => {
                     // Backpressure in the output pipeline: wait for room in output buffers to
@@ -397,119 +475,30 @@ impl Controller {
                         continue;
                     }
 
-                    let buffered_records = controller.status.num_buffered_input_records();
+                    // In a multihost pipeline, also account for inputs buffered by
+                    // other hosts.
+                    let (buffered_records, remote_step_requested) = match &controller.multihost {
+                        Some(MultihostRole::Coordinator(coordinator)) => (
+                            controller.status.num_buffered_input_records()
+                                + coordinator.buffered_records(),
+                            coordinator.take_step_requested(),
+                        ),
+                        _ => (controller.status.num_buffered_input_records(), false),
+                    };
 
                     // We have sufficient buffered inputs or the buffering delay has expired or
                     // the client explicitly requested the circuit to run -- kick the circuit to
                     // consume buffered data.
                     // Use strict inequality in case `min_batch_size_records` is 0.
                     if controller.status.step_requested()
+                        || remote_step_requested
                         || buffered_records > min_batch_size_records
                         || start
                             .map(|start| start.elapsed() >= max_buffering_delay)
                             .unwrap_or(false)
                     {
                         start = None;
-                        // Reset all counters of buffered records and bytes to 0.
-                        controller.status.consume_buffered_inputs();
-
-                        // All input records accumulated so far (and possibly some more) will
-                        // be fully processed after the `step()` call returns.
-                        let processed_records = controller.status.num_total_input_records();
-
-                        // Wake up the backpressure thread to unpause endpoints blocked due to
-                        // backpressure.
-                        controller.unpark_backpressure();
-                        debug!("circuit thread: calling 'circuit.step'");
-                        circuit
-                            .step()
-                            .unwrap_or_else(|e| controller.error(ControllerError::dbsp_error(e)));
-                        debug!("circuit thread: 'circuit.step' returned");
-
-                        controller
-                            .status
-                            .set_num_total_processed_records(processed_records);
-
-                        // Push output batches to output pipelines.
-                        let outputs = controller.outputs.read().unwrap();
-                        for ((_stream, _query), (output_handles, endpoints)) in
-                            outputs.iter_by_stream()
-                        {
-                            // TODO: add an endpoint config option to consolidate output batches.
-
-                            let mut delta_batch = output_handles
-                                .delta
-                                .as_ref()
-                                .map(|handle| handle.take_from_all());
-                            let num_delta_records = delta_batch
-                                .as_ref()
-                                .map(|batch| batch.iter().map(|b| b.len()).sum());
-
-                            let mut snapshot_batch = output_handles
-                                .snapshot
-                                .as_ref()
-                                .map(|handle| handle.take_from_all());
-                            let num_snapshot_records = snapshot_batch
-                                .as_ref()
-                                .map(|batch| batch.iter().map(|b| b.len()).sum());
-
-                            for (i, endpoint_id) in endpoints.iter().enumerate() {
-                                let endpoint = outputs.lookup_by_id(endpoint_id).unwrap();
-
-                                // If the endpoint has a snapshot stream associated with it,
-                                // then the first output sent to this endpoint must be
-                                // the snapshot.  Subsequent outputs are deltas on top of
-                                // the snapshot.
-                                if !endpoint.snapshot_sent.load(Ordering::Acquire)
-                                    && output_handles.snapshot.is_some()
-                                {
-                                    if snapshot_batch
-                                        .as_ref()
-                                        .map(|batches| !batches.is_empty())
-                                        .unwrap_or(false)
-                                    {
-                                        // Increment stats first, so we don't end up with negative
-                                        // counts.
-                                        controller.status.enqueue_batch(
-                                            *endpoint_id,
-                                            num_snapshot_records.unwrap(),
-                                        );
-
-                                        // Send the batch without cloning to the last consumer.
-                                        let batch = if i == endpoints.len() - 1 {
-                                            snapshot_batch.take().unwrap()
-                                        } else {
-                                            snapshot_batch.as_ref().unwrap().clone()
-                                        };
-
-                                        // Associate the input frontier with the batch.  Once the
-                                        // batch has
-                                        // been sent to the output endpoint, the endpoint will get
-                                        // labeled with this
-                                        // frontier.
-                                        endpoint.queue.push((batch, processed_records));
-                                        endpoint.snapshot_sent.store(true, Ordering::Release);
-                                    }
-                                } else if delta_batch.is_some() {
-                                    controller
-                                        .status
-                                        .enqueue_batch(*endpoint_id, num_delta_records.unwrap());
-
-                                    let batch = if i == endpoints.len() - 1 {
-                                        delta_batch.take().unwrap()
-                                    } else {
-                                        delta_batch.as_ref().unwrap().clone()
-                                    };
-
-                                    endpoint.queue.push((batch, processed_records));
-                                }
-
-                                // Wake up the output thread.  We're not trying to be smart here and
-                                // wake up the thread conditionally if it was previously idle, as I
-                                // don't expect this to make any real difference.
-                                endpoint.unparker.unpark();
-                            }
-                        }
+                        Self::step_circuit(&mut circuit, &controller);
                     } else if buffered_records > 0 {
                         // We have some buffered data, but less than `min_batch_size_records` --
                         // wait up to `max_buffering_delay` for more data to
@@ -532,6 +521,264 @@ impl Controller {
         }
     }
 
+    /// Circuit thread function of a follower in a multihost pipeline:
+    /// executes commands received from the coordinator and reports the
+    /// number of buffered input records to it.
+    fn follower_circuit_thread(
+        mut circuit: DBSPHandle,
+        controller: &Arc<ControllerInner>,
+        follower: &Follower,
+        parker: Parker,
+    ) -> Result<(), ControllerError> {
+        // The last number of buffered records reported to the coordinator.
+        let mut reported_records = 0;
+        let mut step_request_sent = false;
+
+        loop {
+            Self::dump_profile_if_requested(&mut circuit, controller);
+            Self::process_circuit_requests(&mut circuit, controller);
+
+            if let Some(command) = follower.next_command() {
+                match command {
+                    Command::Step => {
+                        Self::step_circuit(&mut circuit, controller);
+                        // The coordinator assumes that the step consumed all
+                        // inputs we reported.
+                        reported_records = 0;
+                        step_request_sent = false;
+                    }
+                    Command::Start => controller.start(),
+                    Command::Pause => controller.pause(),
+                    Command::Terminate => controller.stop(),
+                }
+                continue;
+            }
+
+            if controller.state() == PipelineState::Terminated {
+                circuit.kill().map_err(|_| ControllerError::dbsp_panic())?;
+                return Ok(());
+            }
+
+            let buffered_records = controller.status.num_buffered_input_records();
+            if buffered_records != reported_records {
+                follower
+                    .report_buffered_records(buffered_records)
+                    .unwrap_or_else(|e| controller.error(e));
+                reported_records = buffered_records;
+            }
+
+            if controller.status.step_requested() && !step_request_sent {
+                follower
+                    .request_step()
+                    .unwrap_or_else(|e| controller.error(e));
+                step_request_sent = true;
+            }
+
+            parker.park();
+        }
+    }
+
+    fn dump_profile_if_requested(circuit: &mut DBSPHandle, controller: &ControllerInner) {
+        let dump_profile = controller
+            .dump_profile_request
+            .swap(false, Ordering::AcqRel);
+        if dump_profile {
+            // Generate profile in the current working directory.
+            // TODO: make this location configurable.
+            match circuit.dump_profile("profile") {
+                Ok(path) => {
+                    info!(
+                        "circuit profile dump created in '{}'",
+                        path.canonicalize().unwrap_or_default().display()
+                    );
+                    Self::dump_memory_profile(circuit, &path);
+                }
+                Err(e) => {
+                    error!("failed to dump circuit profile: {e}");
+                }
+            }
+        }
+    }
+
+    /// Write per-worker memory profiles to `memory.json` next to the graphviz
+    /// profile dump.
+    fn dump_memory_profile(circuit: &mut DBSPHandle, path: &Path) {
+        let result = circuit
+            .memory_profile()
+            .map_err(|e| e.to_string())
+            .and_then(|profile| serde_json::to_vec(&profile).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(path.join("memory.json"), json).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            error!("failed to dump memory profile: {e}");
+        }
+    }
+
+    fn process_circuit_requests(circuit: &mut DBSPHandle, controller: &ControllerInner) {
+        let requests = match &mut *controller.circuit_requests.lock().unwrap() {
+            Some(requests) => take(requests),
+            None => return,
+        };
+
+        // Concurrent memory profile requests share one profile.
+        let mut memory_profile = None;
+        for request in requests {
+            match request {
+                CircuitRequest::MemoryProfile(sender) => {
+                    let profile = match &memory_profile {
+                        Some(profile) => Ok(Arc::clone(profile)),
+                        None => Self::collect_memory_profile(circuit, controller),
+                    };
+                    let _ = sender.send(profile.map(|profile| {
+                        memory_profile = Some(Arc::clone(&profile));
+                        profile.as_ref().clone()
+                    }));
+                }
+                CircuitRequest::RefreshMemoryProfile => {
+                    if memory_profile.is_none() {
+                        match Self::collect_memory_profile(circuit, controller) {
+                            Ok(profile) => memory_profile = Some(profile),
+                            Err(e) => error!("failed to collect memory profile: {e}"),
+                        }
+                    }
+                }
+                CircuitRequest::GraphProfile(sender) => {
+                    let _ =
+                        sender.send(circuit.graph_profile().map_err(ControllerError::dbsp_error));
+                }
+            }
+        }
+    }
+
+    /// Collects the memory profile of the circuit and caches it for
+    /// [`Self::cached_memory_profile`].
+    fn collect_memory_profile(
+        circuit: &mut DBSPHandle,
+        controller: &ControllerInner,
+    ) -> Result<Arc<Vec<OperatorMemoryProfile>>, ControllerError> {
+        let result = circuit
+            .memory_profile()
+            .map(|workers| Arc::new(aggregate_memory_profile(workers)))
+            .map_err(ControllerError::dbsp_error);
+
+        let mut cache = controller.memory_profile_cache.lock().unwrap();
+        cache.refresh_requested = false;
+        if let Ok(profile) = &result {
+            cache.profile = Some((Instant::now(), Arc::clone(profile)));
+        }
+        result
+    }
+
+    /// Consume buffered inputs by performing a step of the circuit; push
+    /// output batches produced by the circuit to output pipelines.
+    fn step_circuit(circuit: &mut DBSPHandle, controller: &ControllerInner) {
+        // Reset all counters of buffered records and bytes to 0.
+        controller.status.consume_buffered_inputs();
+
+        // All input records accumulated so far (and possibly some more) will
+        // be fully processed after the `step()` call returns.
+        let processed_records = controller.status.num_total_input_records();
+
+        // Wake up the backpressure thread to unpause endpoints blocked due to
+        // backpressure.
+        controller.unpark_backpressure();
+
+        // All hosts of a multihost pipeline must step together.
+        if let Some(MultihostRole::Coordinator(coordinator)) = &controller.multihost {
+            coordinator
+                .broadcast(Command::Step)
+                .unwrap_or_else(|e| controller.error(e));
+        }
+
+        debug!("circuit thread: calling 'circuit.step'");
+        circuit
+            .step()
+            .unwrap_or_else(|e| controller.error(ControllerError::dbsp_error(e)));
+        debug!("circuit thread: 'circuit.step' returned");
+
+        controller
+            .status
+            .set_num_total_processed_records(processed_records);
+
+        // Push output batches to output pipelines.
+        let outputs = controller.outputs.read().unwrap();
+        for ((_stream, _query), (output_handles, endpoints)) in outputs.iter_by_stream() {
+            // TODO: add an endpoint config option to consolidate output batches.
+
+            let mut delta_batch = output_handles
+                .delta
+                .as_ref()
+                .map(|handle| handle.take_from_all());
+            let num_delta_records = delta_batch
+                .as_ref()
+                .map(|batch| batch.iter().map(|b| b.len()).sum());
+
+            let mut snapshot_batch = output_handles
+                .snapshot
+                .as_ref()
+                .map(|handle| handle.take_from_all());
+            let num_snapshot_records = snapshot_batch
+                .as_ref()
+                .map(|batch| batch.iter().map(|b| b.len()).sum());
+
+            for (i, endpoint_id) in endpoints.iter().enumerate() {
+                let endpoint = outputs.lookup_by_id(endpoint_id).unwrap();
+
+                // If the endpoint has a snapshot stream associated with it,
+                // then the first output sent to this endpoint must be
+                // the snapshot.  Subsequent outputs are deltas on top of
+                // the snapshot.
+                if !endpoint.snapshot_sent.load(Ordering::Acquire)
+                    && output_handles.snapshot.is_some()
+                {
+                    if snapshot_batch
+                        .as_ref()
+                        .map(|batches| !batches.is_empty())
+                        .unwrap_or(false)
+                    {
+                        // Increment stats first, so we don't end up with negative
+                        // counts.
+                        controller
+                            .status
+                            .enqueue_batch(*endpoint_id, num_snapshot_records.unwrap());
+
+                        // Send the batch without cloning to the last consumer.
+                        let batch = if i == endpoints.len() - 1 {
+                            snapshot_batch.take().unwrap()
+                        } else {
+                            snapshot_batch.as_ref().unwrap().clone()
+                        };
+
+                        // Associate the input frontier with the batch.  Once the
+                        // batch has
+                        // been sent to the output endpoint, the endpoint will get
+                        // labeled with this
+                        // frontier.
+                        endpoint.queue.push((batch, processed_records));
+                        endpoint.snapshot_sent.store(true, Ordering::Release);
+                    }
+                } else if delta_batch.is_some() {
+                    controller
+                        .status
+                        .enqueue_batch(*endpoint_id, num_delta_records.unwrap());
+
+                    let batch = if i == endpoints.len() - 1 {
+                        delta_batch.take().unwrap()
+                    } else {
+                        delta_batch.as_ref().unwrap().clone()
+                    };
+
+                    endpoint.queue.push((batch, processed_records));
+                }
+
+                // Wake up the output thread.  We're not trying to be smart here and
+                // wake up the thread conditionally if it was previously idle, as I
+                // don't expect this to make any real difference.
+                endpoint.unparker.unpark();
+            }
+        }
+    }
+
     /// Backpressure thread function.
     fn backpressure_thread(controller: Arc<ControllerInner>, parker: Parker) {
         // `global_pause` flag is `true` when the entire controller is paused
@@ -742,6 +989,66 @@ impl OutputEndpoints {
     }
 }
 
+type ResponseSender<T> = SyncSender<Result<T, ControllerError>>;
+
+/// A request served by the circuit thread, which owns the circuit.
+enum CircuitRequest {
+    MemoryProfile(ResponseSender<Vec<OperatorMemoryProfile>>),
+    /// Update the cached memory profile without responding.
+    RefreshMemoryProfile,
+    GraphProfile(ResponseSender<Vec<GraphProfile>>),
+}
+
+/// Response of the circuit thread to a request.
+///
+/// Obtaining the response blocks until the circuit thread serves the request,
+/// which happens between steps.
+pub struct CircuitResponse<T> {
+    receiver: Receiver<Result<T, ControllerError>>,
+}
+
+impl<T> CircuitResponse<T> {
+    /// Blocks until the circuit thread serves the request.
+    pub fn wait(self) -> Result<T, ControllerError> {
+        // The sender is dropped without a response if the circuit thread
+        // terminates first.
+        self.receiver
+            .recv()
+            .unwrap_or_else(|_| Err(circuit_terminated_error()))
+    }
+}
+
+fn circuit_terminated_error() -> ControllerError {
+    ControllerError::dbsp_error(DBSPError::Runtime(RuntimeError::Terminated))
+}
+
+/// The memory profile last collected by the circuit thread.
+#[derive(Default)]
+struct MemoryProfileCache {
+    /// The profile and the time it was collected.
+    profile: Option<(Instant, Arc<Vec<OperatorMemoryProfile>>)>,
+    /// A `RefreshMemoryProfile` request is pending.
+    refresh_requested: bool,
+}
+
+/// Sums up per-worker memory profiles by operator.
+fn aggregate_memory_profile(
+    workers: Vec<Vec<OperatorMemoryProfile>>,
+) -> Vec<OperatorMemoryProfile> {
+    let mut operators = BTreeMap::<GlobalNodeId, OperatorMemoryProfile>::new();
+
+    for profile in workers.into_iter().flatten() {
+        match operators.entry(profile.node_id.clone()) {
+            Entry::Occupied(mut entry) => entry.get_mut().usage += profile.usage,
+            Entry::Vacant(entry) => {
+                entry.insert(profile);
+            }
+        }
+    }
+
+    operators.into_values().collect()
+}
+
 /// Controller state sharable across threads.
 ///
 /// A reference to this struct is held by each input probe and by both
@@ -750,9 +1057,14 @@ struct ControllerInner {
     status: Arc<ControllerStatus>,
     num_api_connections: AtomicU64,
     dump_profile_request: AtomicBool,
+    /// Pending requests to the circuit thread; `None` after the circuit
+    /// thread has terminated.
+    circuit_requests: Mutex<Option<Vec<CircuitRequest>>>,
+    memory_profile_cache: Mutex<MemoryProfileCache>,
     catalog: Arc<Mutex<Catalog>>,
     inputs: Mutex<BTreeMap<EndpointId, InputEndpointDescr>>,
     outputs: ShardedLock<OutputEndpoints>,
+    multihost: Option<MultihostRole>,
     circuit_thread_unparker: Unparker,
     backpressure_thread_unparker: Unparker,
     error_cb: Box<dyn Fn(ControllerError) + Send + Sync>,
@@ -762,6 +1074,7 @@ impl ControllerInner {
     fn new(
         catalog: Catalog,
         global_config: &RuntimeConfig,
+        multihost: Option<MultihostRole>,
         circuit_thread_unparker: Unparker,
         backpressure_thread_unparker: Unparker,
         error_cb: Box<dyn Fn(ControllerError) + Send + Sync>,
@@ -773,9 +1086,12 @@ impl ControllerInner {
             status,
             num_api_connections: AtomicU64::new(0),
             dump_profile_request,
+            circuit_requests: Mutex::new(Some(Vec::new())),
+            memory_profile_cache: Mutex::new(MemoryProfileCache::default()),
             catalog: Arc::new(Mutex::new(catalog)),
             inputs: Mutex::new(BTreeMap::new()),
             outputs: ShardedLock::new(OutputEndpoints::new()),
+            multihost,
             circuit_thread_unparker,
             backpressure_thread_unparker,
             error_cb,
@@ -1108,11 +1424,23 @@ impl ControllerInner {
     fn start(self: &Arc<Self>) {
         self.status.set_state(PipelineState::Running);
         self.unpark_backpressure();
+        self.broadcast(Command::Start);
     }
 
     fn pause(self: &Arc<Self>) {
         self.status.set_state(PipelineState::Paused);
         self.unpark_backpressure();
+        self.broadcast(Command::Pause);
+    }
+
+    /// Forward `command` to other hosts if this is the coordinator of a
+    /// multihost pipeline.
+    fn broadcast(&self, command: Command) {
+        if let Some(MultihostRole::Coordinator(coordinator)) = &self.multihost {
+            coordinator
+                .broadcast(command)
+                .unwrap_or_else(|e| self.error(e));
+        }
     }
 
     fn stop(self: &Arc<Self>) {
@@ -1124,6 +1452,7 @@ impl ControllerInner {
         inputs.clear();
 
         self.status.set_state(PipelineState::Terminated);
+        self.broadcast(Command::Terminate);
 
         self.unpark_circuit();
         self.unpark_backpressure();
@@ -1134,6 +1463,49 @@ impl ControllerInner {
         self.unpark_circuit();
     }
 
+    /// Sends a request to the circuit thread without waiting for the
+    /// response.
+    fn circuit_request<T>(
+        &self,
+        request: impl FnOnce(ResponseSender<T>) -> CircuitRequest,
+    ) -> Result<CircuitResponse<T>, ControllerError> {
+        let (sender, receiver) = sync_channel(1);
+        self.send_circuit_request(request(sender))?;
+        Ok(CircuitResponse { receiver })
+    }
+
+    fn send_circuit_request(&self, request: CircuitRequest) -> Result<(), ControllerError> {
+        match &mut *self.circuit_requests.lock().unwrap() {
+            Some(requests) => requests.push(request),
+            None => return Err(circuit_terminated_error()),
+        }
+        self.unpark_circuit();
+        Ok(())
+    }
+
+    fn cached_memory_profile(&self, max_age: Duration) -> Option<Arc<Vec<OperatorMemoryProfile>>> {
+        let mut cache = self.memory_profile_cache.lock().unwrap();
+
+        let fresh = matches!(&cache.profile, Some((time, _)) if time.elapsed() <= max_age);
+        if !fresh
+            && !cache.refresh_requested
+            && self
+                .send_circuit_request(CircuitRequest::RefreshMemoryProfile)
+                .is_ok()
+        {
+            cache.refresh_requested = true;
+        }
+
+        cache
+            .profile
+            .as_ref()
+            .map(|(_, profile)| Arc::clone(profile))
+    }
+
+    fn close_circuit_requests(&self) {
+        self.circuit_requests.lock().unwrap().take();
+    }
+
     fn error(&self, error: ControllerError) {
         (self.error_cb)(error);
     }
@@ -1220,14 +1592,30 @@ impl InputProbe {
             backpressure_thread_unparker,
         }
     }
+
+    /// Span covering the parsing of `bytes` bytes of input received by the
+    /// endpoint.  The number of parsed records is recorded in the span once
+    /// known.
+    fn read_span(&self, bytes: usize) -> Span {
+        info_span!(
+            "connector_read",
+            endpoint = %self.endpoint_name,
+            bytes,
+            records = field::Empty
+        )
+    }
 }
 
 /// `InputConsumer` interface exposed to the transport endpoint.
 impl InputConsumer for InputProbe {
     fn input_fragment(&mut self, data: &[u8]) -> Vec<ParseError> {
         // println!("input consumer {} bytes", data.len());
+        let span = self.read_span(data.len());
+        let _guard = span.enter();
+
         // Pass input buffer to the parser.
         let (num_records, errors) = self.parser.input_fragment(data);
+        span.record("records", num_records);
 
         for error in errors.iter() {
             self.controller
@@ -1246,7 +1634,11 @@ impl InputConsumer for InputProbe {
     }
 
     fn input_chunk(&mut self, data: &[u8]) -> Vec<ParseError> {
+        let span = self.read_span(data.len());
+        let _guard = span.enter();
+
         let (num_records, errors) = self.parser.input_chunk(data);
+        span.record("records", num_records);
 
         for error in errors.iter() {
             self.controller
@@ -1365,16 +1757,61 @@ impl OutputConsumer for OutputProbe {
 
 #[cfg(test)]
 mod test {
+    use super::CircuitRequest;
     use crate::{
         test::{generate_test_batch, test_circuit, wait, TestStruct},
         Controller, PipelineConfig,
     };
     use csv::{ReaderBuilder as CsvReaderBuilder, WriterBuilder as CsvWriterBuilder};
-    use std::fs::remove_file;
+    use std::{fs::remove_file, sync::Arc, time::Duration};
     use tempfile::NamedTempFile;
 
     use proptest::prelude::*;
 
+    #[test]
+    fn test_memory_profile() {
+        let (circuit, catalog) = test_circuit(2);
+        let config: PipelineConfig = serde_yaml::from_str("name: test\ninputs: {}").unwrap();
+        let controller = Controller::with_config(
+            circuit,
+            catalog,
+            &config,
+            Box::new(|e| panic!("error: {e}")),
+        )
+        .unwrap();
+
+        let profile = controller.memory_profile().unwrap().wait().unwrap();
+        assert!(!profile.is_empty());
+
+        // The cached profile is collected in the background, after which it
+        // stays cached until it becomes stale.
+        let mut cached = None;
+        wait(
+            || {
+                cached = controller.cached_memory_profile(Duration::from_secs(3600));
+                cached.is_some()
+            },
+            Some(10_000),
+        )
+        .unwrap();
+        let cached = cached.unwrap();
+        assert_eq!(cached.len(), profile.len());
+        assert!(Arc::ptr_eq(
+            &cached,
+            &controller
+                .cached_memory_profile(Duration::from_secs(3600))
+                .unwrap()
+        ));
+
+        let inner = controller.inner.clone();
+        controller.stop().unwrap();
+
+        // Requests fail once the circuit has terminated, but the last profile
+        // remains available.
+        assert!(inner.circuit_request(CircuitRequest::GraphProfile).is_err());
+        assert!(inner.cached_memory_profile(Duration::ZERO).is_some());
+    }
+
     // TODO: Parameterize this with config string, so we can test different
     // input/output formats and transports when we support more than one.
     proptest! {
@@ -0,0 +1,717 @@
+//! Coordination between controllers of a multihost pipeline.
+//!
+//! All hosts in a multihost pipeline run replicas of the same DBSP circuit,
+//! which must call `DBSPHandle::step` in lockstep.  The controller on the
+//! first host (the coordinator) decides when the circuit performs a step.
+//! Controllers on other hosts (followers) connect to the coordinator on
+//! startup, report the number of input records buffered by their endpoints,
+//! and perform a step when instructed by the coordinator.  The coordinator
+//! also forwards `start`, `pause`, and shutdown requests to followers.
+//!
+//! Messages are exchanged as newline-delimited JSON over TCP:
+//!
+//! ```text
+//!  coordinator                              follower
+//!       │◄───────────── Hello{host} ────────────┤
+//!       │◄────────── BufferedRecords(n) ────────┤
+//!       │◄──────────── StepRequested ───────────┤
+//!       ├──────────────── Step ────────────────►│
+//!       ├──────── Start/Pause/Terminate ───────►│
+//!       │◄────────────── Heartbeat ────────────►│
+//! ```
+//!
+//! Both ends send a heartbeat every [`Timeouts::heartbeat_period`] and consider
+//! a peer that has not sent any message for [`Timeouts::liveness`] to be
+//! dead.  The coordinator reports a dead follower to the controller, which
+//! stops the pipeline.  A follower that loses the coordinator terminates.
+
+use crate::{ControllerError, RuntimeConfig};
+use crossbeam::{queue::SegQueue, sync::Unparker};
+use log::{error, info, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{BufRead, BufReader, Error as IOError, ErrorKind, Result as IOResult, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread::{sleep, spawn},
+    time::{Duration, Instant},
+};
+
+/// Timeouts used by the connections between controllers.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Timeouts {
+    /// Max time to wait for all followers to connect to the coordinator, and
+    /// for the coordinator to start accepting connections.
+    pub connect: Duration,
+
+    /// Max time to wait for a follower to identify itself after connecting.
+    pub handshake: Duration,
+
+    /// How often to send heartbeats.
+    pub heartbeat_period: Duration,
+
+    /// Max time without receiving any message before considering a peer dead.
+    pub liveness: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self {
+            connect: Duration::from_secs(60),
+            handshake: Duration::from_secs(10),
+            heartbeat_period: Duration::from_secs(1),
+            liveness: Duration::from_secs(10),
+        }
+    }
+}
+
+/// How often to poll for incoming or outgoing connections.
+const CONNECT_RETRY_PERIOD: Duration = Duration::from_millis(100);
+
+/// Command sent by the coordinator to followers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Command {
+    /// Perform a step of the circuit.
+    Step,
+
+    /// Start all input endpoints.
+    Start,
+
+    /// Pause all input endpoints.
+    Pause,
+
+    /// Terminate the pipeline.
+    Terminate,
+}
+
+/// Message sent by a follower to the coordinator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Report {
+    /// First message sent by the follower after connecting to the coordinator.
+    Hello { host: usize },
+
+    /// Number of input records currently buffered by the follower.
+    BufferedRecords(u64),
+
+    /// The follower received an explicit step request.
+    StepRequested,
+
+    /// Sent periodically to show that the follower is alive.
+    Heartbeat,
+}
+
+/// Message sent by the coordinator to followers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Instruction {
+    Command(Command),
+
+    /// Sent periodically to show that the coordinator is alive.
+    Heartbeat,
+}
+
+fn send_message<T: Serialize>(stream: &mut TcpStream, message: &T) -> IOResult<()> {
+    let mut line = serde_json::to_vec(message).unwrap();
+    line.push(b'\n');
+    stream.write_all(&line)
+}
+
+/// Reads the next message; returns `None` when the peer closes the connection.
+///
+/// Fails with [`ErrorKind::WouldBlock`] or [`ErrorKind::TimedOut`] (depending
+/// on the platform) if the stream has a read timeout that expires.
+fn read_message<T: DeserializeOwned>(reader: &mut BufReader<TcpStream>) -> IOResult<Option<T>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    serde_json::from_str(&line)
+        .map(Some)
+        .map_err(|e| IOError::new(ErrorKind::InvalidData, e))
+}
+
+/// Configures `stream` to fail reads and writes that block for longer than
+/// `timeout`.
+fn set_timeouts(stream: &TcpStream, timeout: Duration) -> IOResult<()> {
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))
+}
+
+/// Calls `send_heartbeat` every `period` until `stop` is set.
+///
+/// Failures are detected by the reader of each connection, so
+/// `send_heartbeat` should ignore errors.
+fn spawn_heartbeat<F>(period: Duration, stop: Arc<AtomicBool>, mut send_heartbeat: F)
+where
+    F: FnMut() + Send + 'static,
+{
+    spawn(move || loop {
+        sleep(period);
+        if stop.load(Ordering::Acquire) {
+            return;
+        }
+        send_heartbeat();
+    });
+}
+
+/// Role of the local controller in a multihost pipeline.
+pub(crate) enum MultihostRole {
+    Coordinator(Coordinator),
+    Follower(Follower),
+}
+
+impl MultihostRole {
+    /// Connects the local controller to the other hosts of the pipeline.
+    ///
+    /// Returns `None` for single-host pipelines.  The coordinator blocks until
+    /// all followers have connected, and fails if some follower doesn't connect
+    /// within [`Timeouts::connect`].
+    pub(crate) fn new(
+        config: &RuntimeConfig,
+        circuit_thread_unparker: &Unparker,
+    ) -> Result<Option<Self>, ControllerError> {
+        let multihost = match &config.multihost {
+            Some(multihost) if multihost.hosts.len() > 1 => multihost,
+            _ => return Ok(None),
+        };
+
+        let timeouts = Timeouts::default();
+        let role = if multihost.local_host == 0 {
+            Self::Coordinator(Coordinator::new(
+                multihost.coordinator_address,
+                multihost.hosts.len(),
+                timeouts,
+                circuit_thread_unparker,
+            )?)
+        } else {
+            Self::Follower(Follower::new(
+                multihost.coordinator_address,
+                multihost.local_host,
+                timeouts,
+                circuit_thread_unparker,
+            )?)
+        };
+
+        Ok(Some(role))
+    }
+}
+
+/// Coordinator end of the connections between controllers.
+pub(crate) struct Coordinator {
+    /// Connections to followers; the follower on host `i` is at index `i-1`.
+    followers: Arc<Mutex<Vec<TcpStream>>>,
+
+    /// Number of input records buffered by each follower as of its last
+    /// report.
+    buffered_records: Arc<Vec<AtomicU64>>,
+
+    /// Set when a follower requests a step.
+    step_requested: Arc<AtomicBool>,
+
+    /// Errors of follower connections, e.g., a follower that disconnected or
+    /// stopped sending heartbeats.
+    errors: Arc<SegQueue<ControllerError>>,
+
+    /// Set once the pipeline terminates, after which followers are expected
+    /// to disconnect.
+    terminated: Arc<AtomicBool>,
+}
+
+impl Coordinator {
+    fn new(
+        address: SocketAddr,
+        num_hosts: usize,
+        timeouts: Timeouts,
+        circuit_thread_unparker: &Unparker,
+    ) -> Result<Self, ControllerError> {
+        let listener = TcpListener::bind(address).map_err(|e| {
+            ControllerError::io_error(format!("binding coordinator address '{address}'"), e)
+        })?;
+        // Poll for connections, so that we can give up after `timeouts.connect`.
+        listener.set_nonblocking(true).map_err(|e| {
+            ControllerError::io_error("configuring coordinator socket".to_string(), e)
+        })?;
+
+        info!(
+            "waiting for {} hosts to connect to coordinator at '{address}'",
+            num_hosts - 1
+        );
+
+        let buffered_records: Arc<Vec<AtomicU64>> =
+            Arc::new((1..num_hosts).map(|_| AtomicU64::new(0)).collect());
+        let step_requested = Arc::new(AtomicBool::new(false));
+        let errors = Arc::new(SegQueue::new());
+        let terminated = Arc::new(AtomicBool::new(false));
+        let mut followers: Vec<Option<TcpStream>> = (1..num_hosts).map(|_| None).collect();
+
+        let start = Instant::now();
+        while followers.iter().any(Option::is_none) {
+            let (stream, peer) = match listener.accept() {
+                Ok(connection) => connection,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if start.elapsed() >= timeouts.connect {
+                        let missing: Vec<usize> = (1..num_hosts)
+                            .filter(|host| followers[host - 1].is_none())
+                            .collect();
+                        return Err(ControllerError::io_error(
+                            format!("waiting for hosts {missing:?} to connect to coordinator"),
+                            IOError::new(ErrorKind::TimedOut, "connection timeout expired"),
+                        ));
+                    }
+                    sleep(CONNECT_RETRY_PERIOD);
+                    continue;
+                }
+                Err(e) => {
+                    return Err(ControllerError::io_error(
+                        "accepting connection from a follower".to_string(),
+                        e,
+                    ))
+                }
+            };
+
+            // A follower that fails to identify itself in time doesn't hold
+            // up startup; it can reconnect until the connection timeout
+            // expires.
+            let configured = stream
+                .set_nonblocking(false)
+                .and_then(|()| set_timeouts(&stream, timeouts.handshake))
+                .and_then(|()| stream.try_clone());
+            let mut reader = match configured {
+                Ok(stream) => BufReader::new(stream),
+                Err(e) => {
+                    error!("failed to configure connection from '{peer}': {e}");
+                    continue;
+                }
+            };
+
+            // The first message identifies the follower.
+            let host = match read_message(&mut reader) {
+                Ok(Some(Report::Hello { host }))
+                    if host > 0 && host < num_hosts && followers[host - 1].is_none() =>
+                {
+                    host
+                }
+                message => {
+                    error!("unexpected handshake from '{peer}': {message:?}");
+                    continue;
+                }
+            };
+            if let Err(e) = set_timeouts(&stream, timeouts.liveness) {
+                error!("failed to configure connection from host {host}: {e}");
+                continue;
+            }
+            info!("host {host} connected to coordinator from '{peer}'");
+            followers[host - 1] = Some(stream);
+
+            let buffered_records = buffered_records.clone();
+            let step_requested = step_requested.clone();
+            let errors = errors.clone();
+            let terminated = terminated.clone();
+            let unparker = circuit_thread_unparker.clone();
+            spawn(move || loop {
+                let error = match read_message(&mut reader) {
+                    Ok(Some(Report::BufferedRecords(n))) => {
+                        buffered_records[host - 1].store(n, Ordering::Release);
+                        unparker.unpark();
+                        continue;
+                    }
+                    Ok(Some(Report::StepRequested)) => {
+                        step_requested.store(true, Ordering::Release);
+                        unparker.unpark();
+                        continue;
+                    }
+                    Ok(Some(Report::Heartbeat)) => continue,
+                    Ok(Some(message)) => {
+                        error!("unexpected message from host {host}: {message:?}");
+                        continue;
+                    }
+                    Ok(None) => IOError::new(ErrorKind::UnexpectedEof, "connection closed"),
+                    Err(e) => e,
+                };
+
+                // Followers disconnect when the pipeline terminates.
+                if terminated.load(Ordering::Acquire) {
+                    info!("host {host} disconnected from coordinator");
+                } else {
+                    error!("lost connection to host {host}: {error}");
+                    errors.push(ControllerError::io_error(
+                        format!("connection to host {host}"),
+                        error,
+                    ));
+                    unparker.unpark();
+                }
+                return;
+            });
+        }
+
+        let followers: Arc<Mutex<Vec<TcpStream>>> = Arc::new(Mutex::new(
+            followers.into_iter().map(Option::unwrap).collect(),
+        ));
+        spawn_heartbeat(timeouts.heartbeat_period, terminated.clone(), {
+            let followers = followers.clone();
+            move || {
+                for stream in followers.lock().unwrap().iter_mut() {
+                    let _ = send_message(stream, &Instruction::Heartbeat);
+                }
+            }
+        });
+
+        Ok(Self {
+            followers,
+            buffered_records,
+            step_requested,
+            errors,
+            terminated,
+        })
+    }
+
+    /// Total number of input records buffered by all followers.
+    pub(crate) fn buffered_records(&self) -> u64 {
+        self.buffered_records
+            .iter()
+            .map(|n| n.load(Ordering::Acquire))
+            .sum()
+    }
+
+    /// Returns `true` if a follower has requested a step since the last call.
+    pub(crate) fn take_step_requested(&self) -> bool {
+        self.step_requested.swap(false, Ordering::AcqRel)
+    }
+
+    /// Returns the next error of a follower connection, if any.
+    ///
+    /// The circuit thread is unparked when an error occurs.  The pipeline
+    /// cannot make progress without all of its hosts.
+    pub(crate) fn take_error(&self) -> Option<ControllerError> {
+        self.errors.pop()
+    }
+
+    /// Sends `command` to all followers.
+    ///
+    /// Attempts to send the command to every follower and returns the first
+    /// error, if any.
+    pub(crate) fn broadcast(&self, command: Command) -> Result<(), ControllerError> {
+        if command == Command::Step {
+            // Followers consume all their buffered inputs during the step.  A
+            // report that was in flight when we sent the command can trigger
+            // an extra step, which is harmless.
+            for n in self.buffered_records.iter() {
+                n.store(0, Ordering::Release);
+            }
+        }
+
+        if command == Command::Terminate {
+            self.terminated.store(true, Ordering::Release);
+        }
+
+        let mut result = Ok(());
+        let mut followers = self.followers.lock().unwrap();
+        for (i, stream) in followers.iter_mut().enumerate() {
+            if let Err(e) = send_message(stream, &Instruction::Command(command)) {
+                let error = ControllerError::io_error(
+                    format!("sending '{command:?}' command to host {}", i + 1),
+                    e,
+                );
+                if result.is_ok() {
+                    result = Err(error);
+                } else {
+                    warn!("{error}");
+                }
+            }
+        }
+        result
+    }
+}
+
+impl Drop for Coordinator {
+    fn drop(&mut self) {
+        // Stop the heartbeat thread.
+        self.terminated.store(true, Ordering::Release);
+    }
+}
+
+/// Follower end of the connection between controllers.
+pub(crate) struct Follower {
+    /// Connection to the coordinator.
+    stream: Arc<Mutex<TcpStream>>,
+
+    /// Commands received from the coordinator and not yet executed.
+    commands: Arc<SegQueue<Command>>,
+
+    /// Set when the follower is dropped to stop the heartbeat thread.
+    stopped: Arc<AtomicBool>,
+}
+
+impl Follower {
+    fn new(
+        coordinator_address: SocketAddr,
+        host: usize,
+        timeouts: Timeouts,
+        circuit_thread_unparker: &Unparker,
+    ) -> Result<Self, ControllerError> {
+        let start = Instant::now();
+        let mut stream = loop {
+            match TcpStream::connect(coordinator_address) {
+                Ok(stream) => break stream,
+                Err(_) if start.elapsed() < timeouts.connect => sleep(CONNECT_RETRY_PERIOD),
+                Err(e) => {
+                    return Err(ControllerError::io_error(
+                        format!("connecting to coordinator at '{coordinator_address}'"),
+                        e,
+                    ))
+                }
+            }
+        };
+
+        // The coordinator starts sending heartbeats once all hosts have
+        // connected, so we wait longer for its first message.
+        set_timeouts(&stream, timeouts.connect + timeouts.liveness).map_err(|e| {
+            ControllerError::io_error("configuring coordinator connection".to_string(), e)
+        })?;
+        send_message(&mut stream, &Report::Hello { host }).map_err(|e| {
+            ControllerError::io_error("sending handshake to coordinator".to_string(), e)
+        })?;
+        info!("connected to coordinator at '{coordinator_address}'");
+
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| {
+            ControllerError::io_error("cloning coordinator connection".to_string(), e)
+        })?);
+        let commands = Arc::new(SegQueue::new());
+
+        {
+            let commands = commands.clone();
+            let unparker = circuit_thread_unparker.clone();
+            let mut connected = false;
+            spawn(move || loop {
+                let message = read_message(&mut reader);
+                if !connected && matches!(message, Ok(Some(_))) {
+                    connected = true;
+                    if let Err(e) = reader.get_ref().set_read_timeout(Some(timeouts.liveness)) {
+                        error!("failed to configure coordinator connection: {e}");
+                    }
+                }
+                match message {
+                    Ok(Some(Instruction::Command(command))) => commands.push(command),
+                    Ok(Some(Instruction::Heartbeat)) => continue,
+                    result => {
+                        // The pipeline cannot make progress without the coordinator.
+                        if let Err(e) = result {
+                            error!("lost connection to coordinator: {e}");
+                        }
+                        commands.push(Command::Terminate);
+                        unparker.unpark();
+                        return;
+                    }
+                }
+                unparker.unpark();
+            });
+        }
+
+        let stream = Arc::new(Mutex::new(stream));
+        let stopped = Arc::new(AtomicBool::new(false));
+        spawn_heartbeat(timeouts.heartbeat_period, stopped.clone(), {
+            let stream = stream.clone();
+            move || {
+                let _ = send_message(&mut stream.lock().unwrap(), &Report::Heartbeat);
+            }
+        });
+
+        Ok(Self {
+            stream,
+            commands,
+            stopped,
+        })
+    }
+
+    /// Returns the next command received from the coordinator, if any.
+    pub(crate) fn next_command(&self) -> Option<Command> {
+        self.commands.pop()
+    }
+
+    /// Reports the number of input records buffered by this host.
+    pub(crate) fn report_buffered_records(&self, n: u64) -> Result<(), ControllerError> {
+        self.send(Report::BufferedRecords(n))
+    }
+
+    /// Asks the coordinator to perform a step.
+    pub(crate) fn request_step(&self) -> Result<(), ControllerError> {
+        self.send(Report::StepRequested)
+    }
+
+    fn send(&self, report: Report) -> Result<(), ControllerError> {
+        send_message(&mut self.stream.lock().unwrap(), &report).map_err(|e| {
+            ControllerError::io_error(format!("sending '{report:?}' to coordinator"), e)
+        })
+    }
+}
+
+impl Drop for Follower {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{send_message, Command, Coordinator, Follower, Report, Timeouts};
+    use crossbeam::sync::Parker;
+    use std::{
+        net::{SocketAddr, TcpListener, TcpStream},
+        thread::spawn,
+        time::{Duration, Instant},
+    };
+
+    fn local_address() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    fn test_timeouts() -> Timeouts {
+        Timeouts {
+            connect: Duration::from_secs(1),
+            handshake: Duration::from_millis(200),
+            heartbeat_period: Duration::from_millis(50),
+            liveness: Duration::from_millis(500),
+        }
+    }
+
+    #[test]
+    fn coordinator_follower() {
+        let address = local_address();
+        let timeouts = test_timeouts();
+
+        let coordinator_parker = Parker::new();
+        let coordinator_unparker = coordinator_parker.unparker().clone();
+        let coordinator =
+            spawn(move || Coordinator::new(address, 3, timeouts, &coordinator_unparker).unwrap());
+
+        // Followers retry until the coordinator starts listening.
+        let follower_parker = Parker::new();
+        let followers: Vec<Follower> = (1..3)
+            .map(|host| Follower::new(address, host, timeouts, follower_parker.unparker()).unwrap())
+            .collect();
+        let coordinator = coordinator.join().unwrap();
+
+        followers[0].report_buffered_records(10).unwrap();
+        followers[1].report_buffered_records(5).unwrap();
+        followers[1].request_step().unwrap();
+
+        let mut step_requested = false;
+        while coordinator.buffered_records() != 15 || !step_requested {
+            coordinator_parker.park_timeout(Duration::from_millis(10));
+            step_requested |= coordinator.take_step_requested();
+        }
+        assert!(!coordinator.take_step_requested());
+
+        // Heartbeats keep idle connections alive.
+        coordinator_parker.park_timeout(timeouts.liveness * 2);
+        assert!(coordinator.take_error().is_none());
+        assert!(followers.iter().all(|f| f.next_command().is_none()));
+
+        coordinator.broadcast(Command::Step).unwrap();
+        assert_eq!(coordinator.buffered_records(), 0);
+        coordinator.broadcast(Command::Terminate).unwrap();
+
+        for follower in followers.iter() {
+            for expected in [Command::Step, Command::Terminate] {
+                let command = loop {
+                    if let Some(command) = follower.next_command() {
+                        break command;
+                    }
+                    follower_parker.park_timeout(Duration::from_millis(10));
+                };
+                assert_eq!(command, expected);
+            }
+        }
+
+        // Followers disconnecting after termination is not an error.
+        drop(followers);
+        coordinator_parker.park_timeout(timeouts.liveness * 2);
+        assert!(coordinator.take_error().is_none());
+    }
+
+    /// The coordinator gives up if a follower never connects.
+    #[test]
+    fn connect_timeout() {
+        let address = local_address();
+        let timeouts = test_timeouts();
+
+        let parker = Parker::new();
+        let coordinator = spawn({
+            let unparker = parker.unparker().clone();
+            move || Coordinator::new(address, 3, timeouts, &unparker)
+        });
+        let _follower = Follower::new(address, 1, timeouts, parker.unparker()).unwrap();
+
+        // A peer that connects but never identifies itself doesn't count.
+        let _silent = loop {
+            match TcpStream::connect(address) {
+                Ok(stream) => break stream,
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        };
+
+        let error = coordinator.join().unwrap().err().unwrap();
+        assert!(error.to_string().contains("[2]"), "{error}");
+    }
+
+    /// The coordinator detects a follower that stops sending messages.
+    #[test]
+    fn silent_follower() {
+        let address = local_address();
+        let timeouts = test_timeouts();
+
+        let parker = Parker::new();
+        let coordinator = spawn({
+            let unparker = parker.unparker().clone();
+            move || Coordinator::new(address, 2, timeouts, &unparker).unwrap()
+        });
+
+        // Complete the handshake but never send a heartbeat.
+        let mut stream = loop {
+            match TcpStream::connect(address) {
+                Ok(stream) => break stream,
+                Err(_) => std::thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        send_message(&mut stream, &Report::Hello { host: 1 }).unwrap();
+        let coordinator = coordinator.join().unwrap();
+
+        let start = Instant::now();
+        let error = loop {
+            if let Some(error) = coordinator.take_error() {
+                break error;
+            }
+            assert!(start.elapsed() < timeouts.liveness * 10);
+            parker.park_timeout(Duration::from_millis(10));
+        };
+        assert!(start.elapsed() >= timeouts.liveness / 2);
+        assert!(error.to_string().contains("host 1"), "{error}");
+    }
+
+    /// A follower terminates when the coordinator stops sending messages.
+    #[test]
+    fn silent_coordinator() {
+        let address = local_address();
+        let timeouts = test_timeouts();
+        let listener = TcpListener::bind(address).unwrap();
+
+        let parker = Parker::new();
+        let follower = Follower::new(address, 1, timeouts, parker.unparker()).unwrap();
+        let (_stream, _) = listener.accept().unwrap();
+
+        let start = Instant::now();
+        let command = loop {
+            if let Some(command) = follower.next_command() {
+                break command;
+            }
+            assert!(start.elapsed() < timeouts.liveness * 10);
+            parker.park_timeout(Duration::from_millis(10));
+        };
+        assert_eq!(command, Command::Terminate);
+    }
+}
@@ -151,8 +151,9 @@ pub use format::{Encoder, InputFormat, OutputConsumer, OutputFormat, ParseError,
 pub use seroutput::{SerBatch, SerCursor, SerOutputBatchHandle, SerOutputBatchHandleImpl};
 
 pub use controller::{
-    ConfigError, ConnectorConfig, Controller, ControllerError, ControllerStatus, FormatConfig,
-    InputEndpointConfig, OutputEndpointConfig, PipelineConfig, RuntimeConfig, TransportConfig,
+    CircuitResponse, ConfigError, ConnectorConfig, Controller, ControllerError, ControllerStatus,
+    FormatConfig, HostConfig, InputEndpointConfig, MultihostConfig, OutputEndpointConfig,
+    PipelineConfig, RestartMode, RestartPolicy, RuntimeConfig, TracingConfig, TransportConfig,
 };
 pub use transport::{
     AsyncErrorCallback, FileInputTransport, InputConsumer, InputEndpoint, InputTransport,
@@ -17,7 +17,7 @@ use actix_web::{
 use actix_web_static_files::ResourceFiles;
 use clap::Parser;
 use colored::Colorize;
-use dbsp::{operator::sample::MAX_QUANTILES, DBSPHandle};
+use dbsp::{circuit::Layout, operator::sample::MAX_QUANTILES, DBSPHandle};
 use env_logger::Env;
 use erased_serde::Deserializer as ErasedDeserializer;
 use log::{debug, error, info, warn};
@@ -42,9 +42,11 @@ use uuid::Uuid;
 
 pub mod error;
 mod prometheus;
+mod telemetry;
 
 pub use self::error::{ErrorResponse, PipelineError, MAX_REPORTED_PARSE_ERRORS};
 use self::prometheus::PrometheusMetrics;
+use self::telemetry::{init_tracing, shutdown_tracing};
 
 /// Tracks the health of the pipeline.
 ///
@@ -139,12 +141,11 @@ pub const SERVER_PORT_FILE: &str = "port";
 ///
 /// # Arguments
 ///
-/// * `circuit_factory` - a function that creates a circuit and builds an
-///   input/output stream
-/// catalog.
+/// * `circuit_factory` - a function that creates a circuit with the specified
+///   layout and builds an input/output stream catalog.
 pub fn server_main<F>(circuit_factory: F) -> Result<(), ControllerError>
 where
-    F: Fn(usize) -> (DBSPHandle, Catalog) + Send + 'static,
+    F: Fn(Layout) -> (DBSPHandle, Catalog) + Send + 'static,
 {
     let args = Args::try_parse().map_err(|e| ControllerError::cli_args_error(&e))?;
 
@@ -159,7 +160,7 @@ where
 
 fn run_server<F>(args: Args, circuit_factory: F) -> Result<(), ControllerError>
 where
-    F: Fn(usize) -> (DBSPHandle, Catalog) + Send + 'static,
+    F: Fn(Layout) -> (DBSPHandle, Catalog) + Send + 'static,
 {
     let bind_address = args.bind_address.clone();
     let port = args.default_port.unwrap_or(0);
@@ -221,6 +222,8 @@ where
             .map_err(|e| ControllerError::io_error("in the HTTP server".to_string(), e))
     })?;
 
+    shutdown_tracing();
+
     Ok(())
 }
 
@@ -250,7 +253,7 @@ fn bootstrap<F>(
     state: WebData<ServerState>,
     loginit_sender: StdSender<()>,
 ) where
-    F: Fn(usize) -> (DBSPHandle, Catalog),
+    F: Fn(Layout) -> (DBSPHandle, Catalog),
 {
     do_bootstrap(args, circuit_factory, &state, loginit_sender).unwrap_or_else(|e| {
         // Store error in `state.phase`, so that it can be
@@ -311,7 +314,7 @@ fn do_bootstrap<F>(
     loginit_sender: StdSender<()>,
 ) -> Result<(), ControllerError>
 where
-    F: Fn(usize) -> (DBSPHandle, Catalog),
+    F: Fn(Layout) -> (DBSPHandle, Catalog),
 {
     // Print error directly to stdout until we've initialized the logger.
     let config = parse_config(&args.config_file).map_err(|e| {
@@ -340,6 +343,13 @@ where
         });
     let _ = loginit_sender.send(());
 
+    if let Some(tracing_config) = &config.global.tracing {
+        init_tracing(
+            tracing_config,
+            config.name.as_deref().unwrap_or("feldera-pipeline"),
+        )?;
+    }
+
     *state.metadata.write().unwrap() = match args.metadata_file {
         None => String::new(),
         Some(metadata_file) => {
@@ -355,7 +365,7 @@ where
         }
     };
 
-    let (circuit, catalog) = circuit_factory(config.global.workers as usize);
+    let (circuit, catalog) = circuit_factory(config.global.layout()?);
 
     let weak_state_ref = Arc::downgrade(state);
 
@@ -409,6 +419,8 @@ where
         .service(metrics)
         .service(metadata)
         .service(dump_profile)
+        .service(heap_profile)
+        .service(circuit_graph)
         .service(input_endpoint)
         .service(output_endpoint)
 }
@@ -489,6 +501,50 @@ async fn dump_profile(state: WebData<ServerState>) -> impl Responder {
     }
 }
 
+/// Returns the memory used by each stateful operator of the circuit, summed
+/// across all workers.
+#[get("/heap_profile")]
+async fn heap_profile(state: WebData<ServerState>) -> impl Responder {
+    // Only hold the controller lock while sending the request to the circuit,
+    // and wait for the response on a blocking thread.
+    let response = match &*state.controller.lock().unwrap() {
+        Some(controller) => controller.memory_profile(),
+        None => return Err(missing_controller_error(&state)),
+    };
+    let profile = web::block(move || response?.wait()).await.unwrap()?;
+
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+/// Returns the circuit graph annotated with per-operator statistics.
+///
+/// The response contains the graph, the number of steps performed by the
+/// circuit, and for each worker, statistics for each node of the graph.
+#[get("/circuit_graph")]
+async fn circuit_graph(state: WebData<ServerState>) -> impl Responder {
+    let response = match &*state.controller.lock().unwrap() {
+        Some(controller) => controller.graph_profile(),
+        None => return Err(missing_controller_error(&state)),
+    };
+    let profiles = web::block(move || response?.wait()).await.unwrap()?;
+
+    let steps = profiles.first().map(|profile| profile.steps);
+
+    // All workers run the same circuit, so we only return one graph.
+    let mut graph = None;
+    let mut workers = Vec::with_capacity(profiles.len());
+    for profile in profiles {
+        graph.get_or_insert(profile.graph);
+        workers.push(profile.nodes);
+    }
+
+    Ok(HttpResponse::Ok().json(json!({
+        "graph": graph,
+        "steps": steps,
+        "workers": workers,
+    })))
+}
+
 #[get("/shutdown")]
 async fn shutdown(state: WebData<ServerState>) -> impl Responder {
     let controller = state.controller.lock().unwrap().take();
@@ -549,6 +605,7 @@ async fn input_endpoint(
     // Create endpoint config.
     let config = InputEndpointConfig {
         stream: Cow::from(table_name),
+        host: 0,
         connector_config: ConnectorConfig {
             transport: HttpInputTransport::config(),
             format: FormatConfig::parser_config_from_http_request(
@@ -1086,6 +1143,37 @@ outputs:
 
         sleep(Duration::from_millis(5000));
 
+        // Request per-operator memory usage.
+        println!("/heap_profile");
+        let heap_profile = server
+            .get("/heap_profile")
+            .send()
+            .await
+            .unwrap()
+            .json::<JsonValue>()
+            .await
+            .unwrap();
+        assert!(!heap_profile.as_array().unwrap().is_empty());
+
+        // Request the circuit graph.
+        println!("/circuit_graph");
+        let circuit_graph = server
+            .get("/circuit_graph")
+            .send()
+            .await
+            .unwrap()
+            .json::<JsonValue>()
+            .limit(1 << 24)
+            .await
+            .unwrap();
+        assert!(circuit_graph.get("graph").unwrap().is_object());
+        assert!(!circuit_graph
+            .get("workers")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .is_empty());
+
         // Request quantiles.
         let mut quantiles_resp1 = server
             .post("/egress/test_output1?mode=snapshot&query=quantiles")
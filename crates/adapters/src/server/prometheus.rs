@@ -3,25 +3,41 @@ use crate::{
     Controller,
 };
 use anyhow::{Error as AnyError, Result as AnyResult};
-use prometheus::{Encoder, IntGauge, Opts, Registry, TextEncoder};
-use std::{collections::BTreeMap, sync::atomic::Ordering};
+use dbsp::profile::OperatorMemoryProfile;
+use prometheus::{Encoder, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::{
+    collections::BTreeMap,
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::Duration,
+};
+
+/// Maximal age of the memory profile reported to Prometheus.
+const MEMORY_PROFILE_MAX_AGE: Duration = Duration::from_secs(10);
 
 /// Prometheus metrics of the controller.
 ///
 /// The primary metrics are stored in `controller.status` and are mirrored
 /// to Prometheus metrics on demand.
+///
+/// Per-operator memory metrics mirror the memory profile last collected by
+/// the circuit thread (see [`Controller::cached_memory_profile`]).
 pub(crate) struct PrometheusMetrics {
     registry: Registry,
     input_metrics: BTreeMap<EndpointId, InputMetrics>,
     output_metrics: BTreeMap<EndpointId, OutputMetrics>,
+    operator_metrics: OperatorMetrics,
 }
 
 impl PrometheusMetrics {
     pub(crate) fn new(controller: &Controller) -> AnyResult<Self> {
+        let registry = Registry::new();
+        let operator_metrics = OperatorMetrics::new(&registry)?;
+
         let mut result = Self {
-            registry: Registry::new(),
+            registry,
             input_metrics: BTreeMap::new(),
             output_metrics: BTreeMap::new(),
+            operator_metrics,
         };
 
         let status = controller.status();
@@ -170,6 +186,13 @@ impl PrometheusMetrics {
             self.update_output_metrics(*endpoint_id, endpoint_status)?;
         }
 
+        // Collecting a memory profile traverses the state of the whole circuit,
+        // so scrapes don't wait for it.  They report the last profile and ask
+        // the circuit thread to refresh it once it becomes stale.
+        if let Some(profile) = controller.cached_memory_profile(MEMORY_PROFILE_MAX_AGE) {
+            self.operator_metrics.update(profile);
+        }
+
         let mut buffer = vec![];
         let encoder = TextEncoder::new();
         let metric_families = self.registry.gather();
@@ -204,3 +227,65 @@ struct OutputMetrics {
     num_transport_errors: IntGauge,
     num_encode_errors: IntGauge,
 }
+
+/// Per-operator memory usage, labelled by operator name and global node id
+/// (operator names are not unique within a circuit).
+struct OperatorMetrics {
+    allocated_bytes: IntGaugeVec,
+    used_bytes: IntGaugeVec,
+    shared_bytes: IntGaugeVec,
+    batches: IntGaugeVec,
+    /// The profile the gauges currently reflect.
+    profile: Mutex<Option<Arc<Vec<OperatorMemoryProfile>>>>,
+}
+
+impl OperatorMetrics {
+    fn new(registry: &Registry) -> AnyResult<Self> {
+        Ok(Self {
+            allocated_bytes: Self::create_gauge_vec(registry, "operator_allocated_bytes")?,
+            used_bytes: Self::create_gauge_vec(registry, "operator_used_bytes")?,
+            shared_bytes: Self::create_gauge_vec(registry, "operator_shared_bytes")?,
+            batches: Self::create_gauge_vec(registry, "operator_batches")?,
+            profile: Mutex::new(None),
+        })
+    }
+
+    fn update(&self, profile: Arc<Vec<OperatorMemoryProfile>>) {
+        let mut current = self.profile.lock().unwrap();
+        if matches!(&*current, Some(current) if Arc::ptr_eq(current, &profile)) {
+            return;
+        }
+
+        // Drop the gauges of operators that are no longer in the profile.
+        self.allocated_bytes.reset();
+        self.used_bytes.reset();
+        self.shared_bytes.reset();
+        self.batches.reset();
+
+        for operator in profile.iter() {
+            let node_id = operator.node_id.to_string();
+            let labels = [operator.name.as_str(), node_id.as_str()];
+
+            self.allocated_bytes
+                .with_label_values(&labels)
+                .set(operator.usage.allocated_bytes as i64);
+            self.used_bytes
+                .with_label_values(&labels)
+                .set(operator.usage.used_bytes as i64);
+            self.shared_bytes
+                .with_label_values(&labels)
+                .set(operator.usage.shared_bytes as i64);
+            self.batches
+                .with_label_values(&labels)
+                .set(operator.usage.batches as i64);
+        }
+        *current = Some(profile);
+    }
+
+    fn create_gauge_vec(registry: &Registry, name: &str) -> AnyResult<IntGaugeVec> {
+        let gauge = IntGaugeVec::new(Opts::new(name, name), &["operator", "node"])?;
+        registry.register(Box::new(gauge.clone()))?;
+
+        Ok(gauge)
+    }
+}
@@ -0,0 +1,92 @@
+//! Export of `tracing` spans to an OpenTelemetry collector or a local file.
+//!
+//! When [`RuntimeConfig::tracing`](`crate::RuntimeConfig::tracing`) is set,
+//! the controller asks DBSP to report circuit steps, operator evaluations, and
+//! waits as spans, and input endpoints report every buffer they parse.  This
+//! module installs the global subscriber that ships these spans to their
+//! destination.
+
+use crate::{ControllerError, TracingConfig};
+use once_cell::sync::OnceCell;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{trace, Resource};
+use std::{fs::OpenOptions, sync::Mutex};
+use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+use tracing_subscriber::{fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt};
+
+/// Runtime used by the OTLP exporter to send spans in the background.
+///
+/// Spans are produced by DBSP workers and connector threads, which don't run
+/// inside a Tokio runtime, so the exporter gets a runtime of its own.
+static EXPORTER_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+/// Install a global `tracing` subscriber that exports spans to the
+/// destination specified in `config`.
+///
+/// `service_name` identifies the pipeline in OpenTelemetry traces.
+pub(crate) fn init_tracing(
+    config: &TracingConfig,
+    service_name: &str,
+) -> Result<(), ControllerError> {
+    match config {
+        TracingConfig::Otlp { endpoint } => {
+            let exporter_runtime = EXPORTER_RUNTIME
+                .get_or_try_init(|| {
+                    RuntimeBuilder::new_multi_thread()
+                        .worker_threads(1)
+                        .thread_name("otlp-exporter")
+                        .enable_all()
+                        .build()
+                })
+                .map_err(|e| {
+                    ControllerError::io_error("creating OTLP exporter runtime".to_string(), e)
+                })?;
+            let _guard = exporter_runtime.enter();
+
+            let tracer = opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(endpoint),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", service_name.to_string()),
+                ])))
+                .install_batch(opentelemetry_sdk::runtime::Tokio)
+                .map_err(|e| ControllerError::tracing_error(&e))?;
+
+            tracing_subscriber::registry()
+                .with(tracing_opentelemetry::layer().with_tracer(tracer))
+                .try_init()
+                .map_err(|e| ControllerError::tracing_error(&e))
+        }
+        TracingConfig::JsonFile { path } => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| {
+                    ControllerError::io_error(format!("opening trace file '{path}'"), e)
+                })?;
+
+            tracing_subscriber::registry()
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .json()
+                        .with_span_events(FmtSpan::CLOSE)
+                        .with_span_list(true)
+                        .with_writer(Mutex::new(file)),
+                )
+                .try_init()
+                .map_err(|e| ControllerError::tracing_error(&e))
+        }
+    }
+}
+
+/// Flush spans buffered by the OTLP exporter.  Does nothing if the exporter
+/// was not installed.
+pub(crate) fn shutdown_tracing() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
@@ -4,7 +4,7 @@ use crate::{
     controller::InputEndpointConfig, Catalog, FormatConfig, InputEndpoint, InputTransport,
 };
 use anyhow::Result as AnyResult;
-use dbsp::{DBSPHandle, Runtime};
+use dbsp::{circuit::IntoLayout, DBSPHandle, Runtime};
 use log::{Log, Metadata, Record};
 use serde::Deserialize;
 use std::{
@@ -118,8 +118,8 @@ where
 /// Create a simple test circuit that passes the input stream right through to
 /// the output.
 // TODO: parameterize with the number (and types?) of input and output streams.
-pub fn test_circuit(workers: usize) -> (DBSPHandle, Catalog) {
-    Runtime::init_circuit(workers, |circuit| {
+pub fn test_circuit(layout: impl IntoLayout) -> (DBSPHandle, Catalog) {
+    Runtime::init_circuit(layout, |circuit| {
         let mut catalog = Catalog::new();
         let (input, hinput) = circuit.add_input_zset::<TestStruct, i32>();
 
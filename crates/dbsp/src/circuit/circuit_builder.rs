@@ -35,15 +35,15 @@ use crate::{
 use crate::{
     circuit::{
         cache::{CircuitCache, CircuitStoreMarker},
-        metadata::OperatorMeta,
+        metadata::{MemoryUsage, OperatorMeta, RecordCounts},
         operator_traits::{
             BinaryOperator, BinarySinkOperator, Data, ImportOperator, NaryOperator,
             QuaternaryOperator, SinkOperator, SourceOperator, StrictUnaryOperator, TernaryOperator,
             UnaryOperator,
         },
         schedule::{
-            DynamicScheduler, Error as SchedulerError, Executor, IterativeExecutor, OnceExecutor,
-            Scheduler,
+            DynamicScheduler, Error as SchedulerError, Executor, IterationLimits,
+            IterativeExecutor, OnceExecutor, Scheduler,
         },
         trace::{CircuitEvent, SchedulerEvent},
     },
@@ -53,6 +53,7 @@ use crate::{
     Error as DBSPError, Runtime,
 };
 use anyhow::Error as AnyError;
+use itertools::Itertools;
 use serde::Serialize;
 use std::{
     borrow::Cow,
@@ -556,7 +557,8 @@ pub struct Stream<C, D> {
     /// circuits are synchronous).
     /// We use `UnsafeCell` instead of `RefCell` to avoid runtime ownership
     /// tests. We enforce unique ownership by making sure that at most one
-    /// operator can run (and access the stream) at any time.
+    /// operator can access the stream at any time (see
+    /// [`Circuit::eval_nodes`]).
     val: Rc<UnsafeCell<StreamValue<D>>>,
 }
 
@@ -740,6 +742,11 @@ pub trait Node {
     /// `false` for synchronous operators and subcircuits.
     fn is_async(&self) -> bool;
 
+    /// `true` if the node is a nested circuit.
+    fn is_circuit(&self) -> bool {
+        false
+    }
+
     /// `true` if the node is ready to execute (see
     /// [`Operator::ready()`](super::operator_traits::Operator::ready)).
     /// Always returns `true` for synchronous operators and subcircuits.
@@ -757,7 +764,8 @@ pub trait Node {
     /// # Safety
     ///
     /// Only one node may be scheduled at any given time (a node cannot invoke
-    /// another node).
+    /// another node), unless the nodes meet the requirements of
+    /// [`Circuit::eval_nodes`].
     unsafe fn eval(&mut self) -> Result<(), SchedulerError>;
 
     /// Notify the node about start of a clock epoch.
@@ -787,6 +795,16 @@ pub trait Node {
 
     fn metadata(&self, output: &mut OperatorMeta);
 
+    /// Memory used by the node's operator (see
+    /// [`Operator::memory_usage()`](super::operator_traits::Operator::memory_usage)).
+    /// Always returns `None` for subcircuits.
+    fn memory_usage(&self) -> Option<MemoryUsage>;
+
+    /// Records consumed and produced by the node's operator (see
+    /// [`Operator::record_counts()`](super::operator_traits::Operator::record_counts)).
+    /// Always returns `None` for subcircuits.
+    fn record_counts(&self) -> Option<RecordCounts>;
+
     fn fixedpoint(&self, scope: Scope) -> bool;
 
     fn map_nodes_recursive(&self, _f: &mut dyn FnMut(&dyn Node)) {}
@@ -1189,11 +1207,38 @@ pub trait Circuit: WithClock + Clone + 'static {
 
     fn is_async_node(&self, id: NodeId) -> bool;
 
+    /// `true` if the specified node is a nested circuit.
+    fn is_circuit_node(&self, id: NodeId) -> bool;
+
+    /// The number of operators in this circuit that
+    /// [`ParallelScheduler`](`crate::circuit::schedule::ParallelScheduler`)
+    /// may evaluate concurrently (see [`ChildCircuit::set_parallelism`]).
+    fn parallelism(&self) -> usize;
+
     /// Evaluate operator with the given id.
     ///
     /// This method should only be used by schedulers.
     fn eval_node(&self, id: NodeId) -> Result<(), SchedulerError>;
 
+    /// Evaluate several operators concurrently.
+    ///
+    /// Wraps the evaluation of each node in `ids` in a job and passes the
+    /// jobs to `run`, which must run all of them to completion before
+    /// returning.  Returns the first error reported by any of the nodes.
+    ///
+    /// This method should only be used by schedulers.
+    ///
+    /// # Safety
+    ///
+    /// The nodes in `ids` must be distinct synchronous operators (not async
+    /// operators or subcircuits) whose inputs are all available.  None of them
+    /// may depend on another one, and no two of them may read the same
+    /// stream.  The operators of the circuit must satisfy the contract of
+    /// [`ChildCircuit::set_parallelism`].
+    unsafe fn eval_nodes<F>(&self, ids: &[NodeId], run: F) -> Result<(), SchedulerError>
+    where
+        F: for<'a> FnOnce(Vec<Box<dyn FnOnce() + Send + 'a>>);
+
     /// Evaluate closure `f` inside a new circuit region.
     ///
     /// A region is a logical grouping of circuit nodes.  Regions are used
@@ -1733,6 +1778,8 @@ where
     circuit_event_handlers: CircuitEventHandlers,
     scheduler_event_handlers: SchedulerEventHandlers,
     store: CircuitCache,
+    iteration_limits: IterationLimits,
+    parallelism: usize,
 }
 
 impl<P> CircuitInner<P>
@@ -1757,6 +1804,8 @@ where
             circuit_event_handlers,
             scheduler_event_handlers,
             store: TypedMap::new(),
+            iteration_limits: IterationLimits::new(),
+            parallelism: 1,
         }
     }
 
@@ -1887,6 +1936,35 @@ where
     fn inner(&self) -> Ref<'_, CircuitInner<P>> {
         self.inner.borrow()
     }
+
+    /// Allow up to `threads` operators of this circuit to be evaluated
+    /// concurrently.
+    ///
+    /// Only affects circuits evaluated by [`ParallelScheduler`], e.g., ones
+    /// created by [`RootCircuit::build_with_scheduler`] or
+    /// [`Circuit::iterate_with_scheduler`], which evaluate one operator at a
+    /// time by default.  Must be called from the constructor closure that
+    /// builds the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is 0.
+    ///
+    /// # Safety
+    ///
+    /// [`ParallelScheduler`] evaluates operators on threads other than the
+    /// worker thread that owns the circuit.  The caller must guarantee that
+    /// the operators of the circuit, including any closures and data they
+    /// capture, are safe to evaluate this way: operators must not share state
+    /// that is not thread-safe (e.g., `Rc` or `RefCell`) with each other, other
+    /// than through the streams that connect them, and values sent through
+    /// streams must be safe to move across threads.
+    ///
+    /// [`ParallelScheduler`]: crate::circuit::schedule::ParallelScheduler
+    pub unsafe fn set_parallelism(&self, threads: usize) {
+        assert!(threads > 0, "circuit parallelism must be at least 1");
+        self.inner_mut().parallelism = threads;
+    }
 }
 
 impl RootCircuit {
@@ -2074,6 +2152,41 @@ where
     pub fn is_child_of(&self, other: &P) -> bool {
         P::ptr_eq(&self.inner().parent, other)
     }
+
+    /// Bound the iterations performed by this circuit in each clock cycle of
+    /// the parent circuit.
+    ///
+    /// Only affects iterative circuits, e.g., those created by
+    /// [`Circuit::iterate`] or [`Circuit::fixedpoint`].  Must be called from
+    /// the constructor closure that builds the circuit.
+    pub fn set_iteration_limits(&self, limits: IterationLimits) {
+        self.inner_mut().iteration_limits = limits;
+    }
+
+    /// Create an executor that evaluates `self` until `termination_check`
+    /// returns `true`, subject to the limits configured via
+    /// [`Self::set_iteration_limits`].
+    fn iterative_executor<F, S>(
+        &self,
+        termination_check: F,
+    ) -> Result<IterativeExecutor<F, S>, SchedulerError>
+    where
+        S: Scheduler,
+    {
+        let limits = self.inner().iteration_limits.clone();
+        let circuit = self.clone();
+        let state_size = Box::new(move || {
+            let mut used_bytes = 0;
+            circuit.map_nodes_recursive(&mut |node: &dyn Node| {
+                if let Some(usage) = node.memory_usage() {
+                    used_bytes += usage.used_bytes;
+                }
+            });
+            used_bytes
+        });
+
+        IterativeExecutor::new(self, termination_check, limits, state_size)
+    }
 }
 
 // Internal API.
@@ -2327,6 +2440,14 @@ where
         self.inner().nodes[id.0].is_async()
     }
 
+    fn is_circuit_node(&self, id: NodeId) -> bool {
+        self.inner().nodes[id.0].is_circuit()
+    }
+
+    fn parallelism(&self) -> usize {
+        self.inner().parallelism
+    }
+
     fn eval_node(&self, id: NodeId) -> Result<(), SchedulerError> {
         let mut circuit = self.inner_mut();
         debug_assert!(id.0 < circuit.nodes.len());
@@ -2350,6 +2471,40 @@ where
         Ok(())
     }
 
+    unsafe fn eval_nodes<F>(&self, ids: &[NodeId], run: F) -> Result<(), SchedulerError>
+    where
+        F: for<'a> FnOnce(Vec<Box<dyn FnOnce() + Send + 'a>>),
+    {
+        let mut circuit = self.inner_mut();
+        debug_assert!(ids.iter().all(|id| id.0 < circuit.nodes.len()));
+        debug_assert!(ids.iter().all_unique());
+
+        for id in ids {
+            circuit.log_scheduler_event(&SchedulerEvent::eval_start(circuit.nodes[id.0].as_ref()));
+        }
+
+        let mut results = vec![Ok(()); ids.len()];
+        let nodes = circuit.nodes.as_mut_ptr();
+        let jobs = ids
+            .iter()
+            .zip(results.iter_mut())
+            .map(|(id, result)| {
+                // Safety: `ids` are distinct, so each job gets exclusive access
+                // to its node, and the caller guarantees that the nodes don't
+                // share any streams.
+                let node = SendNode(&mut **nodes.add(id.0) as *mut dyn Node);
+                Box::new(move || *result = node.eval()) as Box<dyn FnOnce() + Send + '_>
+            })
+            .collect();
+        run(jobs);
+
+        for id in ids {
+            circuit.log_scheduler_event(&SchedulerEvent::eval_end(circuit.nodes[id.0].as_ref()));
+        }
+
+        results.into_iter().collect()
+    }
+
     #[track_caller]
     fn region<F, T>(&self, name: &str, f: F) -> T
     where
@@ -2908,7 +3063,7 @@ where
     {
         self.subcircuit(true, |child| {
             let (termination_check, res) = constructor(child)?;
-            let executor = <IterativeExecutor<_, S>>::new(child, termination_check)?;
+            let executor = child.iterative_executor::<_, S>(termination_check)?;
             Ok((res, executor))
         })
     }
@@ -2949,9 +3104,7 @@ where
                         // Send local fixed point status to all peers.
                         let local_fixedpoint = child_clone.inner().fixedpoint(0);
                         while !exchange.try_send_all(worker_index, &mut repeat(local_fixedpoint)) {
-                            if Runtime::kill_in_progress() {
-                                return Err(SchedulerError::Killed);
-                            }
+                            Runtime::check_interrupted()?;
                             Runtime::parker().with(|parker| parker.park());
                         }
                         // Receive the fixed point status of each peer, compute global fixedpoint
@@ -2959,15 +3112,13 @@ where
                         let mut global_fixedpoint = true;
                         while !exchange.try_receive_all(worker_index, |fp| global_fixedpoint &= fp)
                         {
-                            if Runtime::kill_in_progress() {
-                                return Err(SchedulerError::Killed);
-                            }
+                            Runtime::check_interrupted()?;
                             // Sleep if other threads are still working.
                             Runtime::parker().with(|parker| parker.park());
                         }
                         Ok(global_fixedpoint)
                     };
-                    let executor = <IterativeExecutor<_, S>>::new(child, termination_check)?;
+                    let executor = child.iterative_executor::<_, S>(termination_check)?;
                     Ok((res, executor))
                 })
             }
@@ -2976,7 +3127,7 @@ where
                 let child_clone = child.clone();
 
                 let termination_check = move || Ok(child_clone.inner().fixedpoint(0));
-                let executor = <IterativeExecutor<_, S>>::new(child, termination_check)?;
+                let executor = child.iterative_executor::<_, S>(termination_check)?;
                 Ok((res, executor))
             }),
         }
@@ -3160,6 +3311,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3236,6 +3395,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3318,6 +3485,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3393,6 +3568,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3488,6 +3671,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3603,6 +3794,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3722,6 +3921,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3859,6 +4066,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -3981,6 +4196,14 @@ where
         self.operator.metadata(output);
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        self.operator.memory_usage()
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        self.operator.record_counts()
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.operator.fixedpoint(scope)
     }
@@ -4078,6 +4301,14 @@ where
         unsafe { (*self.operator.get()).metadata(output) }
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        unsafe { (*self.operator.get()).memory_usage() }
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        unsafe { (*self.operator.get()).record_counts() }
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         unsafe { (*self.operator.get()).fixedpoint(scope) }
     }
@@ -4154,6 +4385,16 @@ where
         unsafe { (*self.operator.get()).metadata(output) }
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        // The operator is shared with the matching `FeedbackOutputNode`, which
+        // reports its memory usage.
+        None
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        None
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         unsafe { (*self.operator.get()).fixedpoint(scope) }
     }
@@ -4218,6 +4459,18 @@ where
     }
 }
 
+/// A node evaluated on another thread by [`Circuit::eval_nodes`].
+struct SendNode(*mut dyn Node);
+
+// Safety: see the safety requirements of `Circuit::eval_nodes`.
+unsafe impl Send for SendNode {}
+
+impl SendNode {
+    unsafe fn eval(self) -> Result<(), SchedulerError> {
+        (*self.0).eval()
+    }
+}
+
 // A nested circuit instantiated as a node in a parent circuit.
 struct ChildNode<P>
 where
@@ -4275,6 +4528,10 @@ where
         false
     }
 
+    fn is_circuit(&self) -> bool {
+        true
+    }
+
     fn ready(&self) -> bool {
         true
     }
@@ -4291,7 +4548,17 @@ where
         self.circuit.clock_end(scope + 1);
     }
 
-    fn metadata(&self, _meta: &mut OperatorMeta) {}
+    fn metadata(&self, meta: &mut OperatorMeta) {
+        self.executor.metadata(meta);
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        None
+    }
+
+    fn record_counts(&self) -> Option<RecordCounts> {
+        None
+    }
 
     fn fixedpoint(&self, scope: Scope) -> bool {
         self.circuit.inner().fixedpoint(scope + 1)
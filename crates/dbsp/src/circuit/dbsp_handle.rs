@@ -1,10 +1,12 @@
 use crate::{
-    circuit::runtime::RuntimeHandle, profile::Profiler, Error as DBSPError, RootCircuit, Runtime,
-    RuntimeError, SchedulerError,
+    circuit::runtime::{CancellationToken, RuntimeHandle, StepCancelled},
+    profile::{GraphProfile, OperatorMemoryProfile, Profiler},
+    Error as DBSPError, RootCircuit, Runtime, RuntimeError, SchedulerError,
 };
 use anyhow::Error as AnyError;
 use core::fmt;
-use crossbeam::channel::{bounded, Receiver, Sender, TryRecvError};
+use crossbeam::channel::{bounded, Receiver, Select, SelectTimeoutError, Sender, TryRecvError};
+use futures::task::AtomicWaker;
 use itertools::Either;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -13,10 +15,16 @@ use std::{
     fmt::{Debug, Display, Error as FmtError, Formatter},
     fs,
     fs::create_dir_all,
+    future::Future,
     iter::empty,
+    mem::replace,
     net::SocketAddr,
     ops::Range,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
     path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
     thread::Result as ThreadResult,
     time::Instant,
 };
@@ -276,9 +284,19 @@ impl Runtime {
         let (status_senders, status_receivers): (Vec<_>, Vec<_>) =
             (0..nworkers).map(|_| bounded(1)).unzip();
 
+        // Woken up by workers when they complete a step, to drive
+        // `DBSPHandle::step_async`.
+        let status_waker = Arc::new(AtomicWaker::new());
+        let worker_status_waker = status_waker.clone();
+
         let runtime = Self::run(layout, move || {
             let worker_index = Runtime::worker_index() - worker_ofs;
 
+            // Declared before `status_sender`, so that it is dropped after it:
+            // if the worker exits or panics, a pending `step_async` future
+            // gets woken up and observes the disconnected channel.
+            let status_waker = WakeOnDrop(worker_status_waker);
+
             // Drop all but one channels.  This makes sure that if one of the worker panics
             // or exits, its channel will become disconnected.
             let init_sender = init_senders.into_iter().nth(worker_index).unwrap();
@@ -309,11 +327,20 @@ impl Runtime {
                 match command_receiver.try_recv() {
                     Ok(Command::Step) => {
                         //moregc = true;
-                        let status = circuit.step().map(|_| Response::Unit);
+                        let status = match catch_unwind(AssertUnwindSafe(|| circuit.step())) {
+                            Ok(status) => status,
+                            // Code that cannot return an error, e.g., a trace merge, has
+                            // abandoned the step (see `Runtime::abort_if_cancelled`).
+                            Err(payload) if payload.is::<StepCancelled>() => {
+                                Err(SchedulerError::Cancelled)
+                            }
+                            Err(payload) => resume_unwind(payload),
+                        };
                         // Send response.
-                        if status_sender.send(status).is_err() {
+                        if status_sender.send(status.map(|_| Response::Unit)).is_err() {
                             return;
                         }
+                        status_waker.0.wake();
                     }
                     Ok(Command::EnableProfiler) => {
                         profiler.enable_cpu_profiler();
@@ -322,6 +349,12 @@ impl Runtime {
                             return;
                         }
                     }
+                    Ok(Command::EnableSpanTracer) => {
+                        profiler.enable_span_tracer();
+                        if status_sender.send(Ok(Response::Unit)).is_err() {
+                            return;
+                        }
+                    }
                     Ok(Command::DumpProfile) => {
                         if status_sender
                             .send(Ok(Response::Profile(profiler.dump_profile())))
@@ -330,6 +363,22 @@ impl Runtime {
                             return;
                         }
                     }
+                    Ok(Command::MemoryProfile) => {
+                        if status_sender
+                            .send(Ok(Response::MemoryProfile(profiler.memory_profile())))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Ok(Command::GraphProfile) => {
+                        if status_sender
+                            .send(Ok(Response::GraphProfile(profiler.graph_profile())))
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
                     // Nothing to do: do some housekeeping and relinquish the CPU if there's none
                     // left.
                     Err(TryRecvError::Empty) => {
@@ -376,7 +425,7 @@ impl Runtime {
             return Err(error);
         }
 
-        let dbsp = DBSPHandle::new(runtime, command_senders, status_receivers);
+        let dbsp = DBSPHandle::new(runtime, command_senders, status_receivers, status_waker);
 
         let result = init_status[0].take();
 
@@ -390,12 +439,26 @@ impl Runtime {
 enum Command {
     Step,
     EnableProfiler,
+    EnableSpanTracer,
     DumpProfile,
+    MemoryProfile,
+    GraphProfile,
 }
 
 enum Response {
     Unit,
     Profile(String),
+    MemoryProfile(Vec<OperatorMemoryProfile>),
+    GraphProfile(GraphProfile),
+}
+
+/// Wakes up the task waiting for a worker when the worker thread exits.
+struct WakeOnDrop(Arc<AtomicWaker>);
+
+impl Drop for WakeOnDrop {
+    fn drop(&mut self) {
+        self.0.wake();
+    }
 }
 
 /// A handle to control the execution of a circuit in a multithreaded runtime.
@@ -409,6 +472,12 @@ pub struct DBSPHandle {
     // Channels used to receive command completion status from
     // workers.
     status_receivers: Vec<Receiver<Result<Response, SchedulerError>>>,
+    // Woken up by workers after sending step completion status.
+    status_waker: Arc<AtomicWaker>,
+    cancellation_token: CancellationToken,
+    // Workers that have not yet responded to a step whose `StepFuture` was
+    // dropped before completion.
+    abandoned_step: Option<Vec<bool>>,
 }
 
 impl DBSPHandle {
@@ -416,12 +485,18 @@ impl DBSPHandle {
         runtime: RuntimeHandle,
         command_senders: Vec<Sender<Command>>,
         status_receivers: Vec<Receiver<Result<Response, SchedulerError>>>,
+        status_waker: Arc<AtomicWaker>,
     ) -> Self {
+        let cancellation_token = runtime.cancellation_token();
+
         Self {
             start_time: Instant::now(),
             runtime: Some(runtime),
             command_senders,
             status_receivers,
+            status_waker,
+            cancellation_token,
+            abandoned_step: None,
         }
     }
 
@@ -435,11 +510,77 @@ impl DBSPHandle {
     where
         F: FnMut(Response),
     {
+        self.send_command(command)?;
+
+        let mut responded = vec![false; self.status_receivers.len()];
+        let mut responses: Vec<Option<Response>> = responded.iter().map(|_| None).collect();
+        self.receive_responses(&mut responded, None, |worker, response| {
+            responses[worker] = Some(response);
+        })?;
+
+        for response in responses {
+            handler(response.unwrap());
+        }
+
+        Ok(())
+    }
+
+    /// Receives responses from the workers that have not `responded` yet,
+    /// passing them to `handler` along with the index of the worker, until
+    /// all workers have responded or `deadline` passes.  A deadline in the
+    /// past receives the responses that are ready without blocking.
+    ///
+    /// Returns `true` if all workers have responded.
+    fn receive_responses<F>(
+        &mut self,
+        responded: &mut [bool],
+        deadline: Option<Instant>,
+        mut handler: F,
+    ) -> Result<bool, DBSPError>
+    where
+        F: FnMut(usize, Response),
+    {
+        // Receive responses in the order workers complete.  A worker that
+        // fails may leave its peers waiting for it, e.g., in a fixedpoint
+        // exchange, so we must kill the runtime as soon as any worker reports
+        // an error rather than waiting for workers in order.
+        let receivers = self.status_receivers.clone();
+        let mut select = Select::new();
+        // Worker index for each operation in `select`.
+        let mut workers = Vec::with_capacity(receivers.len());
+        for (worker, receiver) in receivers.iter().enumerate() {
+            if !responded[worker] {
+                select.recv(receiver);
+                workers.push(worker);
+            }
+        }
+
+        for _ in 0..workers.len() {
+            let operation = match deadline {
+                None => select.select(),
+                Some(deadline) => match select.select_deadline(deadline) {
+                    Ok(operation) => operation,
+                    Err(SelectTimeoutError) => return Ok(false),
+                },
+            };
+            let index = operation.index();
+            let worker = workers[index];
+            let response = operation.recv(&receivers[worker]).ok();
+            select.remove(index);
+            responded[worker] = true;
+            handler(worker, self.check_response(worker, response)?);
+        }
+
+        Ok(true)
+    }
+
+    fn send_command(&mut self, command: Command) -> Result<(), DBSPError> {
         if self.runtime.is_none() {
             return Err(DBSPError::Runtime(RuntimeError::Terminated));
         }
 
-        // Send command.
+        self.finish_abandoned_step()?;
+
         for (worker, sender) in self.command_senders.iter().enumerate() {
             if matches!(sender.send(command.clone()), Err(_)) {
                 let _ = self.kill_inner();
@@ -448,19 +589,35 @@ impl DBSPHandle {
             self.runtime.as_ref().unwrap().unpark_worker(worker);
         }
 
-        // Receive responses.
-        for (worker, receiver) in self.status_receivers.iter().enumerate() {
-            match receiver.recv() {
-                Err(_) => {
-                    let _ = self.kill_inner();
-                    return Err(DBSPError::Runtime(RuntimeError::WorkerPanic { worker }));
-                }
-                Ok(Err(e)) => {
-                    let _ = self.kill_inner();
-                    return Err(DBSPError::Scheduler(e));
-                }
-                Ok(Ok(resp)) => handler(resp),
+        Ok(())
+    }
+
+    /// Kill the runtime if `worker` failed to execute a command.  `response`
+    /// is `None` if the worker has exited without responding.
+    fn check_response(
+        &mut self,
+        worker: usize,
+        response: Option<Result<Response, SchedulerError>>,
+    ) -> Result<Response, DBSPError> {
+        match response {
+            None => {
+                let _ = self.kill_inner();
+                Err(DBSPError::Runtime(RuntimeError::WorkerPanic { worker }))
             }
+            Some(Err(e)) => {
+                let _ = self.kill_inner();
+                Err(DBSPError::Scheduler(e))
+            }
+            Some(Ok(resp)) => Ok(resp),
+        }
+    }
+
+    /// Wait for workers to complete a step abandoned by a dropped
+    /// [`StepFuture`].
+    fn finish_abandoned_step(&mut self) -> Result<(), DBSPError> {
+        if let Some(mut responded) = self.abandoned_step.take() {
+            self.receive_responses(&mut responded, None, |_, _| {})?;
+            self.cancellation_token.reset();
         }
 
         Ok(())
@@ -468,7 +625,62 @@ impl DBSPHandle {
 
     /// Evaluate the circuit for one clock cycle.
     pub fn step(&mut self) -> Result<(), DBSPError> {
-        self.broadcast_command(Command::Step, |_| {})
+        self.broadcast_command(Command::Step, |_| {})?;
+        self.cancellation_token.reset();
+        Ok(())
+    }
+
+    /// Evaluate the circuit for one clock cycle, giving up at `deadline`.
+    ///
+    /// If the step is still running at `deadline`, cancels it (see
+    /// [`CancellationToken`]) and, once all workers have stopped, terminates
+    /// the runtime and returns [`RuntimeError::DeadlineExceeded`].  Like
+    /// other cancelled steps, a step that completes before the workers notice
+    /// the cancellation succeeds.
+    pub fn step_with_deadline(&mut self, deadline: Instant) -> Result<(), DBSPError> {
+        self.send_command(Command::Step)?;
+
+        let mut responded = vec![false; self.status_receivers.len()];
+        if !self.receive_responses(&mut responded, Some(deadline), |_, _| {})? {
+            self.cancellation_token.cancel();
+            match self.receive_responses(&mut responded, None, |_, _| {}) {
+                Err(DBSPError::Scheduler(SchedulerError::Cancelled)) => {
+                    return Err(DBSPError::Runtime(RuntimeError::DeadlineExceeded));
+                }
+                result => {
+                    result?;
+                }
+            }
+        }
+
+        self.cancellation_token.reset();
+        Ok(())
+    }
+
+    /// Evaluate the circuit for one clock cycle without blocking the caller.
+    ///
+    /// Returns a future that completes when all workers have finished the
+    /// step.  Dropping the future before it completes cancels the step (see
+    /// [`CancellationToken`]); the next command issued through this handle
+    /// waits for the workers to stop and reports the outcome of the
+    /// cancelled step.  Combined with a timer, e.g., `tokio::time::timeout`,
+    /// this implements an asynchronous step deadline.
+    pub fn step_async(&mut self) -> StepFuture<'_> {
+        let state = match self.send_command(Command::Step) {
+            Ok(()) => StepState::Running(vec![false; self.status_receivers.len()]),
+            Err(e) => StepState::Failed(e),
+        };
+
+        StepFuture {
+            handle: self,
+            state,
+        }
+    }
+
+    /// Returns a token that can be used to cancel circuit steps from another
+    /// thread.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation_token.clone()
     }
 
     /// Enable CPU profiler.
@@ -481,6 +693,17 @@ impl DBSPHandle {
         self.broadcast_command(Command::EnableProfiler, |_| {})
     }
 
+    /// Report circuit activity as [`tracing`] spans.
+    ///
+    /// Once enabled, every worker emits spans for circuit steps, operator
+    /// evaluations, and waits for async operators such as exchanges (see
+    /// [`SpanTracer`](`crate::profile::SpanTracer`)).  The spans are delivered
+    /// to the application's `tracing` subscriber, e.g., an OpenTelemetry
+    /// exporter.
+    pub fn enable_span_tracer(&mut self) -> Result<(), DBSPError> {
+        self.broadcast_command(Command::EnableSpanTracer, |_| {})
+    }
+
     /// Dump profiling information to the specified directory.
     ///
     /// Creates `dir_path` if it doesn't exist.  For each worker thread, creates
@@ -509,6 +732,41 @@ impl DBSPHandle {
         Ok(dir_path)
     }
 
+    /// Collect memory usage of all stateful operators in the circuit.
+    ///
+    /// Returns one vector of per-operator reports for each worker thread
+    /// managed by this handle, indexed by worker.  Unlike CPU profiling, memory
+    /// accounting does not need to be enabled in advance, but its cost is
+    /// proportional to the size of the circuit's state.
+    pub fn memory_profile(&mut self) -> Result<Vec<Vec<OperatorMemoryProfile>>, DBSPError> {
+        let mut profiles = Vec::with_capacity(self.status_receivers.len());
+
+        self.broadcast_command(Command::MemoryProfile, |resp| {
+            if let Response::MemoryProfile(profile) = resp {
+                profiles.push(profile);
+            }
+        })?;
+
+        Ok(profiles)
+    }
+
+    /// Returns the circuit graph annotated with per-operator statistics.
+    ///
+    /// Returns one profile for each worker thread managed by this handle,
+    /// indexed by worker.  CPU usage statistics are only included if CPU
+    /// profiling was enabled (see [`Self::enable_cpu_profiler`]).
+    pub fn graph_profile(&mut self) -> Result<Vec<GraphProfile>, DBSPError> {
+        let mut profiles = Vec::with_capacity(self.status_receivers.len());
+
+        self.broadcast_command(Command::GraphProfile, |resp| {
+            if let Response::GraphProfile(profile) = resp {
+                profiles.push(profile);
+            }
+        })?;
+
+        Ok(profiles)
+    }
+
     /// Terminate the execution of the circuit, exiting all worker threads.
     ///
     /// If one or more of the worker threads panics, returns the argument the
@@ -534,10 +792,76 @@ impl Drop for DBSPHandle {
     }
 }
 
+enum StepState {
+    // Workers that have responded so far.
+    Running(Vec<bool>),
+    Failed(DBSPError),
+    Done,
+}
+
+/// Future returned by [`DBSPHandle::step_async`].
+pub struct StepFuture<'a> {
+    handle: &'a mut DBSPHandle,
+    state: StepState,
+}
+
+impl<'a> Future for StepFuture<'a> {
+    type Output = Result<(), DBSPError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        let mut responded = match replace(&mut this.state, StepState::Done) {
+            StepState::Running(responded) => responded,
+            StepState::Failed(e) => return Poll::Ready(Err(e)),
+            StepState::Done => panic!("StepFuture polled after completion"),
+        };
+
+        // Register before checking the channels, so that a worker that
+        // responds after the check wakes us up.
+        this.handle.status_waker.register(cx.waker());
+
+        match this
+            .handle
+            .receive_responses(&mut responded, Some(Instant::now()), |_, _| {})
+        {
+            Err(e) => Poll::Ready(Err(e)),
+            Ok(true) => {
+                this.handle.cancellation_token.reset();
+                Poll::Ready(Ok(()))
+            }
+            Ok(false) => {
+                this.state = StepState::Running(responded);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<'a> Drop for StepFuture<'a> {
+    fn drop(&mut self) {
+        if let StepState::Running(responded) = replace(&mut self.state, StepState::Done) {
+            // The caller is no longer interested in the step.  Stop it, and
+            // collect the outstanding responses before the next command.
+            self.handle.cancellation_token.cancel();
+            self.handle.abandoned_step = Some(responded);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{operator::Generator, Circuit, Error as DBSPError, Runtime, RuntimeError};
+    use crate::{
+        circuit::metadata::{MetaItem, RecordCounts},
+        operator::{FilterMap, Generator},
+        Circuit, DBSPHandle, Error as DBSPError, Runtime, RuntimeError, SchedulerError,
+    };
     use anyhow::anyhow;
+    use futures::executor::block_on;
+    use std::{
+        thread::{sleep, spawn},
+        time::{Duration, Instant},
+    };
 
     // Panic during initialization in worker thread.
     #[test]
@@ -628,6 +952,225 @@ mod tests {
         handle.kill().unwrap();
     }
 
+    // Collect per-operator memory usage.
+    #[test]
+    fn test_memory_profile() {
+        let (mut handle, input) = Runtime::init_circuit(2, |circuit| {
+            let (stream, handle) = circuit.add_input_zset::<usize, isize>();
+            stream.integrate_trace();
+            Ok(handle)
+        })
+        .unwrap();
+
+        for i in 0..1000 {
+            input.push(i, 1);
+        }
+        handle.step().unwrap();
+
+        let profile = handle.memory_profile().unwrap();
+        assert_eq!(profile.len(), 2);
+
+        for worker_profile in profile {
+            let trace = worker_profile
+                .iter()
+                .find(|op| op.name == "Z1 (trace)")
+                .unwrap();
+            assert!(trace.usage.used_bytes > 0);
+            assert!(trace.usage.batches > 0);
+        }
+
+        handle.kill().unwrap();
+    }
+
+    // Request the circuit graph with statistics.
+    #[test]
+    fn test_graph_profile() {
+        let (mut handle, input) = Runtime::init_circuit(2, |circuit| {
+            let (stream, handle) = circuit.add_input_zset::<usize, isize>();
+            stream.filter(|x| x % 2 == 0).integrate_trace();
+            Ok(handle)
+        })
+        .unwrap();
+
+        handle.enable_cpu_profiler().unwrap();
+        for i in 0..1000 {
+            input.push(i, 1);
+        }
+        handle.step().unwrap();
+        handle.step().unwrap();
+
+        let profiles = handle.graph_profile().unwrap();
+        assert_eq!(profiles.len(), 2);
+
+        let mut filter_records = RecordCounts::default();
+        for profile in profiles {
+            assert_eq!(profile.steps, 2);
+            let filter = profile
+                .nodes
+                .iter()
+                .find(|node| node.records.is_some())
+                .unwrap();
+            filter_records += filter.records.unwrap();
+
+            assert!(profile
+                .nodes
+                .iter()
+                .any(|node| node.invocations == Some(2) && node.total_time.is_some()));
+            assert!(profile.nodes.iter().any(|node| node.memory.is_some()));
+            assert!(!profile.graph.to_dot().is_empty());
+        }
+
+        // Inputs are distributed across workers.
+        assert_eq!(
+            filter_records,
+            RecordCounts {
+                input_records: 1000,
+                output_records: 500
+            }
+        );
+
+        // Sizes are reported as numbers of bytes.
+        assert_eq!(
+            serde_json::to_value(MetaItem::bytes(1536)).unwrap(),
+            serde_json::json!(1536)
+        );
+
+        handle.kill().unwrap();
+    }
+
+    // A circuit whose nested iteration never terminates.
+    fn divergent_circuit(nworkers: usize) -> DBSPHandle {
+        let (handle, _) = Runtime::init_circuit(nworkers, |circuit| {
+            circuit.iterate(|child| {
+                child.add_source(Generator::new(|| 5usize));
+                Ok((|| Ok(false), ()))
+            })
+        })
+        .unwrap();
+        handle
+    }
+
+    // Cancel a runaway step from another thread.
+    #[test]
+    fn test_cancel1() {
+        test_cancel(1);
+    }
+
+    #[test]
+    fn test_cancel4() {
+        test_cancel(4);
+    }
+
+    fn test_cancel(nworkers: usize) {
+        let mut handle = divergent_circuit(nworkers);
+
+        let token = handle.cancellation_token();
+        let canceller = spawn(move || {
+            sleep(Duration::from_millis(100));
+            token.cancel();
+        });
+
+        assert!(matches!(
+            handle.step(),
+            Err(DBSPError::Scheduler(SchedulerError::Cancelled))
+        ));
+        canceller.join().unwrap();
+
+        // Cancellation is fatal.
+        assert!(matches!(
+            handle.step(),
+            Err(DBSPError::Runtime(RuntimeError::Terminated))
+        ));
+    }
+
+    // Step with a deadline.
+    #[test]
+    fn test_step_deadline1() {
+        test_step_deadline(1);
+    }
+
+    #[test]
+    fn test_step_deadline4() {
+        test_step_deadline(4);
+    }
+
+    fn test_step_deadline(nworkers: usize) {
+        let (mut handle, _) = Runtime::init_circuit(nworkers, |circuit| {
+            circuit.add_source(Generator::new(|| 5usize));
+            Ok(())
+        })
+        .unwrap();
+
+        handle
+            .step_with_deadline(Instant::now() + Duration::from_secs(60))
+            .unwrap();
+        handle.kill().unwrap();
+
+        let mut handle = divergent_circuit(nworkers);
+        assert!(matches!(
+            handle.step_with_deadline(Instant::now() + Duration::from_millis(100)),
+            Err(DBSPError::Runtime(RuntimeError::DeadlineExceeded))
+        ));
+    }
+
+    // A failing worker ends the step while its peers are still running.
+    #[test]
+    fn test_step_deadline_worker_panic() {
+        let (mut handle, _) = Runtime::init_circuit(4, |circuit| {
+            circuit.add_source(Generator::new(|| {
+                if Runtime::worker_index() == 3 {
+                    panic!()
+                }
+                // Only stops when the runtime is killed.
+                while Runtime::worker_index() == 0 && !Runtime::kill_in_progress() {
+                    sleep(Duration::from_millis(1));
+                }
+                5usize
+            }));
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(matches!(
+            handle.step_with_deadline(Instant::now() + Duration::from_secs(60)),
+            Err(DBSPError::Runtime(RuntimeError::WorkerPanic { worker: 3 }))
+        ));
+    }
+
+    // Asynchronous step.
+    #[test]
+    fn test_step_async1() {
+        test_step_async(1);
+    }
+
+    #[test]
+    fn test_step_async4() {
+        test_step_async(4);
+    }
+
+    fn test_step_async(nworkers: usize) {
+        let (mut handle, _) = Runtime::init_circuit(nworkers, |circuit| {
+            circuit.add_source(Generator::new(|| 5usize));
+            Ok(())
+        })
+        .unwrap();
+
+        for _ in 0..3 {
+            block_on(handle.step_async()).unwrap();
+        }
+
+        handle.kill().unwrap();
+
+        // Dropping the future cancels the step.  The next command reports the
+        // outcome of the abandoned step.
+        let mut handle = divergent_circuit(nworkers);
+        drop(handle.step_async());
+        assert!(matches!(
+            handle.step(),
+            Err(DBSPError::Scheduler(SchedulerError::Cancelled))
+        ));
+    }
+
     // Drop the runtime.
     #[test]
     fn test_drop1() {
@@ -1,8 +1,9 @@
+use serde::{ser::SerializeMap, Serialize, Serializer};
 use size_of::{HumanBytes, TotalSize};
 use std::{
     borrow::Cow,
     fmt::{self, Write},
-    ops::{Deref, DerefMut},
+    ops::{AddAssign, Deref, DerefMut},
     panic::Location,
     time::Duration,
 };
@@ -35,6 +36,20 @@ impl OperatorMeta {
     }
 }
 
+/// Serializes metadata as a map from labels to items.
+impl Serialize for OperatorMeta {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (label, item) in self.entries.iter() {
+            map.serialize_entry(label, item)?;
+        }
+        map.end()
+    }
+}
+
 impl Deref for OperatorMeta {
     type Target = Vec<(MetaLabel, MetaItem)>;
 
@@ -133,6 +148,24 @@ impl MetaItem {
     }
 }
 
+impl Serialize for MetaItem {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Int(int) => int.serialize(serializer),
+            Self::Percent(percent) => percent.serialize(serializer),
+            Self::String(string) => string.serialize(serializer),
+            Self::Array(array) => array.serialize(serializer),
+            Self::Map(map) => map.serialize(serializer),
+            // The raw number of bytes, so that consumers can process it.
+            Self::Bytes(bytes) => bytes.bytes.serialize(serializer),
+            Self::Duration(duration) => duration.serialize(serializer),
+        }
+    }
+}
+
 impl Default for MetaItem {
     fn default() -> Self {
         Self::String(String::new())
@@ -183,3 +216,87 @@ impl From<TotalSize> for MetaItem {
         )
     }
 }
+
+/// Memory used by the state of an operator, e.g., the traces and batches it
+/// maintains across clock cycles.
+///
+/// See [`Operator::memory_usage`](crate::circuit::operator_traits::Operator::memory_usage).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct MemoryUsage {
+    /// Total number of bytes allocated by the operator.
+    pub allocated_bytes: usize,
+    /// Number of allocated bytes that are in use.
+    pub used_bytes: usize,
+    /// Number of bytes in allocations shared with other values.
+    pub shared_bytes: usize,
+    /// Number of distinct allocations.
+    pub allocations: usize,
+    /// Number of batches that make up the operator's state, e.g., the number
+    /// of batches in a [`Spine`](crate::trace::Spine).
+    pub batches: usize,
+}
+
+impl MemoryUsage {
+    /// Create a `MemoryUsage` from a `size_of` report for a state consisting
+    /// of `batches` batches.
+    pub fn new(size: TotalSize, batches: usize) -> Self {
+        Self {
+            allocated_bytes: size.total_bytes(),
+            used_bytes: size.used_bytes(),
+            shared_bytes: size.shared_bytes(),
+            allocations: size.distinct_allocations(),
+            batches,
+        }
+    }
+
+    /// Metadata entries describing this memory usage, in the format used by
+    /// operator [metadata](crate::circuit::operator_traits::Operator::metadata).
+    pub fn metadata(&self) -> [(MetaLabel, MetaItem); 5] {
+        metadata! {
+            "allocated bytes" => MetaItem::bytes(self.allocated_bytes),
+            "used bytes" => MetaItem::bytes(self.used_bytes),
+            "allocations" => self.allocations,
+            "shared bytes" => MetaItem::bytes(self.shared_bytes),
+            "batches" => self.batches,
+        }
+    }
+}
+
+impl AddAssign for MemoryUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.allocated_bytes += other.allocated_bytes;
+        self.used_bytes += other.used_bytes;
+        self.shared_bytes += other.shared_bytes;
+        self.allocations += other.allocations;
+        self.batches += other.batches;
+    }
+}
+
+/// Number of records consumed and produced by an operator, summed across all
+/// evaluations of the operator.
+///
+/// Records are counted as the number of updates in input and output batches.
+/// See [`Operator::record_counts`](crate::circuit::operator_traits::Operator::record_counts).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct RecordCounts {
+    /// Number of records in the operator's inputs.
+    pub input_records: usize,
+    /// Number of records in the operator's outputs.
+    pub output_records: usize,
+}
+
+impl RecordCounts {
+    /// Account for an evaluation of the operator that consumed
+    /// `input_records` and produced `output_records` records.
+    pub fn add(&mut self, input_records: usize, output_records: usize) {
+        self.input_records += input_records;
+        self.output_records += output_records;
+    }
+}
+
+impl AddAssign for RecordCounts {
+    fn add_assign(&mut self, other: Self) {
+        self.input_records += other.input_records;
+        self.output_records += other.output_records;
+    }
+}
@@ -32,7 +32,9 @@ pub use circuit_builder::{
     ChildCircuit, Circuit, CircuitHandle, ExportId, ExportStream, FeedbackConnector, GlobalNodeId,
     NodeId, OwnershipPreference, RootCircuit, Scope, Stream, WithClock,
 };
-pub use dbsp_handle::{DBSPHandle, Host, IntoLayout, Layout};
-pub use runtime::{Error as RuntimeError, LocalStore, LocalStoreMarker, Runtime, RuntimeHandle};
+pub use dbsp_handle::{DBSPHandle, Host, IntoLayout, Layout, StepFuture};
+pub use runtime::{
+    CancellationToken, Error as RuntimeError, LocalStore, LocalStoreMarker, Runtime, RuntimeHandle,
+};
 
-pub use schedule::Error as SchedulerError;
+pub use schedule::{Error as SchedulerError, IterationLimits};
@@ -4,7 +4,7 @@
 //! consumes one or more input streams and produces an output stream.
 
 use crate::circuit::{
-    metadata::{OperatorLocation, OperatorMeta},
+    metadata::{MemoryUsage, OperatorLocation, OperatorMeta, RecordCounts},
     OwnershipPreference, Scope,
 };
 use std::borrow::Cow;
@@ -27,6 +27,24 @@ pub trait Operator: 'static {
     /// Collects metadata about the current operator
     fn metadata(&self, _meta: &mut OperatorMeta) {}
 
+    /// Reports the memory used by the operator's state.
+    ///
+    /// Stateful operators, e.g., operators that maintain traces, should
+    /// override this method.  Returns `None` for operators that don't keep
+    /// any state across clock cycles.
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        None
+    }
+
+    /// Reports the number of records the operator has consumed and produced.
+    ///
+    /// Implemented by operators over batches, which count the updates in
+    /// their input and output batches.  Returns `None` for operators that
+    /// don't keep count.
+    fn record_counts(&self) -> Option<RecordCounts> {
+        None
+    }
+
     /// Notify the operator about the start of a new clock epoch.
     ///
     /// `clock_start` and `clock_end` methods support the nested circuit
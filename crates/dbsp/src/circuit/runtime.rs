@@ -1,7 +1,7 @@
 //! A multithreaded runtime for evaluating DBSP circuits in a data-parallel
 //! fashion.
 
-use crate::DetailedError;
+use crate::{DetailedError, SchedulerError};
 use crossbeam::channel::bounded;
 use crossbeam_utils::sync::{Parker, Unparker};
 use serde::Serialize;
@@ -11,6 +11,7 @@ use std::{
     error::Error as StdError,
     fmt,
     fmt::{Debug, Display, Error as FmtError, Formatter},
+    panic::resume_unwind,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -28,6 +29,9 @@ pub enum Error {
         worker: usize,
     },
     Terminated,
+    /// The step did not complete before its deadline (see
+    /// [`DBSPHandle::step_with_deadline`](`crate::DBSPHandle::step_with_deadline`)).
+    DeadlineExceeded,
 }
 
 impl DetailedError for Error {
@@ -35,6 +39,7 @@ impl DetailedError for Error {
         match self {
             Self::WorkerPanic { .. } => Cow::from("WorkerPanic"),
             Self::Terminated => Cow::from("Terminated"),
+            Self::DeadlineExceeded => Cow::from("DeadlineExceeded"),
         }
     }
 }
@@ -46,6 +51,7 @@ impl Display for Error {
                 write!(f, "worker thread '{worker}' panicked")
             }
             Self::Terminated => f.write_str("circuit terminated by the user"),
+            Self::DeadlineExceeded => f.write_str("circuit step exceeded its deadline"),
         }
     }
 }
@@ -62,6 +68,11 @@ thread_local! {
     // Schedulers must check this signal before evaluating each operator
     // and exit immediately returning `SchedulerError::Terminated`.
     static KILL_SIGNAL: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+
+    // Set to `true` by `CancellationToken::cancel`.
+    // Schedulers check this signal along with `KILL_SIGNAL` and abandon
+    // the current step, returning `SchedulerError::Cancelled`.
+    static CANCEL_SIGNAL: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
 }
 
 // Thread-local variables used to store per-worker context.
@@ -176,12 +187,13 @@ impl Runtime {
                     RUNTIME.with(|rt| *rt.borrow_mut() = Some(runtime));
                     WORKER_INDEX.with(|idx| idx.set(worker_index));
 
-                    // Send the main thread our parker and kill signal
+                    // Send the main thread our parker, kill signal, and cancel signal
                     // TODO: Share a single kill signal across all workers
                     init_sender
                         .send((
                             PARKER.with(|parker| parker.unparker().clone()),
                             KILL_SIGNAL.with(|s| s.clone()),
+                            CANCEL_SIGNAL.with(|s| s.clone()),
                         ))
                         .unwrap();
 
@@ -197,8 +209,8 @@ impl Runtime {
 
         let mut workers = Vec::with_capacity(nworkers);
         workers.extend(handles.into_iter().map(|(handle, recv)| {
-            let (unparker, kill_signal) = recv.recv().unwrap();
-            WorkerHandle::new(handle, unparker, kill_signal)
+            let (unparker, kill_signal, cancel_signal) = recv.recv().unwrap();
+            WorkerHandle::new(handle, unparker, kill_signal, cancel_signal)
         }));
 
         RuntimeHandle::new(runtime, workers)
@@ -225,6 +237,19 @@ impl Runtime {
         WORKER_INDEX.with(|index| index.get())
     }
 
+    /// Make the current thread act on behalf of worker `worker_index` of
+    /// `runtime`.
+    ///
+    /// Used by the threads of
+    /// [`ParallelScheduler`](`crate::circuit::schedule::ParallelScheduler`),
+    /// so that operators they evaluate observe the same [`Runtime::runtime`]
+    /// and [`Runtime::worker_index`] as the worker thread that owns the
+    /// circuit.
+    pub(crate) fn enter_worker(runtime: Option<Runtime>, worker_index: usize) {
+        RUNTIME.with(|rt| *rt.borrow_mut() = runtime);
+        WORKER_INDEX.with(|idx| idx.set(worker_index));
+    }
+
     fn inner(&self) -> &RuntimeInner {
         &self.0
     }
@@ -287,6 +312,94 @@ impl Runtime {
     pub fn kill_in_progress() -> bool {
         KILL_SIGNAL.with(|signal| signal.load(Ordering::SeqCst))
     }
+
+    /// `true` if the step currently evaluated by the worker thread has been
+    /// cancelled via a [`CancellationToken`].
+    pub fn step_cancelled() -> bool {
+        CANCEL_SIGNAL.with(|signal| signal.load(Ordering::Relaxed))
+    }
+
+    /// Returns an error if the current worker thread has received a kill
+    /// signal or its current step has been cancelled.
+    ///
+    /// Schedulers and other loops that may run for a long time within a
+    /// step, e.g., fixedpoint iterations, must call this method before
+    /// evaluating the next operator and after parking, and propagate the
+    /// error to the caller.
+    pub fn check_interrupted() -> Result<(), SchedulerError> {
+        if Self::kill_in_progress() {
+            Err(SchedulerError::Killed)
+        } else if Self::step_cancelled() {
+            Err(SchedulerError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Abandon the current step by unwinding the worker's stack if it has
+    /// been cancelled.
+    ///
+    /// This is the cancellation mechanism for code that cannot return an
+    /// error, such as trace merges.  The unwind does not invoke the panic
+    /// hook.  It is caught by the worker thread created by
+    /// [`Runtime::init_circuit`] and reported as
+    /// [`SchedulerError::Cancelled`].  Cancellation can only be requested
+    /// through a [`DBSPHandle`](`crate::DBSPHandle`), so this method never
+    /// unwinds in threads that run circuits in other ways.
+    pub(crate) fn abort_if_cancelled() {
+        if Self::step_cancelled() {
+            resume_unwind(Box::new(StepCancelled));
+        }
+    }
+}
+
+/// Payload of the unwind used by [`Runtime::abort_if_cancelled`].
+pub(crate) struct StepCancelled;
+
+/// Cooperative cancellation of circuit steps.
+///
+/// Cancelling the token interrupts the step that the circuit is evaluating
+/// or, if no step is in progress, the next step.  Workers observe
+/// cancellation before evaluating each operator, between iterations of
+/// nested circuits, while waiting for exchanges with other workers, and
+/// periodically during long trace merges.
+///
+/// A cancelled step leaves operator state partially updated, so the
+/// [`DBSPHandle`](`crate::DBSPHandle`) treats cancellation as a fatal error:
+/// it reports [`SchedulerError::Cancelled`] and terminates the runtime.  A
+/// step that completes before any worker observes the cancellation succeeds
+/// normally.
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    workers: Arc<[(Arc<AtomicBool>, Unparker)]>,
+}
+
+impl CancellationToken {
+    /// Cancel the current step.
+    pub fn cancel(&self) {
+        for (cancel_signal, unparker) in self.workers.iter() {
+            cancel_signal.store(true, Ordering::Relaxed);
+            // Wake up workers that are waiting for an exchange, so they
+            // notice the cancellation.
+            unparker.unpark();
+        }
+    }
+
+    /// `true` if [`Self::cancel`] has been called since the last completed
+    /// step.
+    pub fn is_cancelled(&self) -> bool {
+        self.workers
+            .iter()
+            .any(|(cancel_signal, _)| cancel_signal.load(Ordering::Relaxed))
+    }
+
+    /// Clear the cancellation signal once the step it was meant for has
+    /// completed.
+    pub(super) fn reset(&self) {
+        for (cancel_signal, _) in self.workers.iter() {
+            cancel_signal.store(false, Ordering::Relaxed);
+        }
+    }
 }
 
 /// Per-worker controls.
@@ -295,14 +408,21 @@ struct WorkerHandle {
     join_handle: JoinHandle<()>,
     unparker: Unparker,
     kill_signal: Arc<AtomicBool>,
+    cancel_signal: Arc<AtomicBool>,
 }
 
 impl WorkerHandle {
-    fn new(join_handle: JoinHandle<()>, unparker: Unparker, kill_signal: Arc<AtomicBool>) -> Self {
+    fn new(
+        join_handle: JoinHandle<()>,
+        unparker: Unparker,
+        kill_signal: Arc<AtomicBool>,
+        cancel_signal: Arc<AtomicBool>,
+    ) -> Self {
         Self {
             join_handle,
             unparker,
             kill_signal,
+            cancel_signal,
         }
     }
 
@@ -337,6 +457,17 @@ impl RuntimeHandle {
         &self.runtime
     }
 
+    /// Returns a token that cancels steps in all workers of the runtime.
+    pub(super) fn cancellation_token(&self) -> CancellationToken {
+        CancellationToken {
+            workers: self
+                .workers
+                .iter()
+                .map(|worker| (worker.cancel_signal.clone(), worker.unparker.clone()))
+                .collect(),
+        }
+    }
+
     /// Terminate the runtime and all worker threads without waiting for any
     /// in-progress computation to complete.
     ///
@@ -379,11 +510,20 @@ impl TypedMapKey<LocalStoreMarker> for WorkerId {
 mod tests {
     use super::Runtime;
     use crate::{
-        circuit::schedule::{DynamicScheduler, Scheduler, StaticScheduler},
+        circuit::schedule::{DynamicScheduler, ParallelScheduler, Scheduler, StaticScheduler},
         operator::Generator,
         Circuit, RootCircuit,
     };
-    use std::{cell::RefCell, rc::Rc, thread::sleep, time::Duration};
+    use std::{
+        cell::RefCell,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread::sleep,
+        time::Duration,
+    };
 
     #[test]
     #[cfg_attr(miri, ignore)]
@@ -397,6 +537,12 @@ mod tests {
         test_runtime::<DynamicScheduler>();
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_runtime_parallel() {
+        test_runtime::<ParallelScheduler>();
+    }
+
     fn test_runtime<S>()
     where
         S: Scheduler + 'static,
@@ -439,6 +585,12 @@ mod tests {
         test_kill::<DynamicScheduler>();
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_kill_parallel() {
+        test_kill::<ParallelScheduler>();
+    }
+
     // Test `RuntimeHandle::kill`.
     fn test_kill<S>()
     where
@@ -474,4 +626,74 @@ mod tests {
         sleep(Duration::from_millis(100));
         hruntime.kill().unwrap();
     }
+
+    // Evaluate independent branches of each worker's circuit concurrently.
+    // Operators evaluated by the scheduler's thread pool must observe the
+    // index of the worker that owns the circuit.
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_parallel_operators() {
+        const BRANCHES: usize = 4;
+
+        let hruntime = Runtime::run(2, || {
+            let worker_index = Runtime::worker_index();
+            let running = Arc::new(AtomicUsize::new(0));
+            let max_running = Arc::new(AtomicUsize::new(0));
+
+            let root = RootCircuit::build_with_scheduler::<_, _, ParallelScheduler>(|circuit| {
+                // Safety: operators only share thread-safe state.
+                unsafe { circuit.set_parallelism(BRANCHES) };
+
+                for branch in 0..BRANCHES {
+                    let running = running.clone();
+                    let max_running = max_running.clone();
+
+                    circuit
+                        .add_source(Generator::new(move || branch))
+                        .apply(move |n: &usize| {
+                            assert_eq!(Runtime::worker_index(), worker_index);
+                            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                            max_running.fetch_max(now_running, Ordering::SeqCst);
+                            sleep(Duration::from_millis(10));
+                            running.fetch_sub(1, Ordering::SeqCst);
+                            n + 1
+                        })
+                        .inspect(move |n: &usize| assert_eq!(*n, branch + 1));
+                }
+                Ok(())
+            })
+            .unwrap()
+            .0;
+
+            for _ in 0..10 {
+                root.step().unwrap();
+            }
+
+            assert!(max_running.load(Ordering::SeqCst) > 1);
+        });
+
+        hruntime.join().unwrap();
+    }
+
+    // A panic in an operator evaluated by the thread pool is propagated to the
+    // thread that steps the circuit.
+    #[test]
+    #[should_panic(expected = "operator failed")]
+    fn test_parallel_panic() {
+        let root = RootCircuit::build_with_scheduler::<_, _, ParallelScheduler>(|circuit| {
+            // Safety: operators don't share any state.
+            unsafe { circuit.set_parallelism(2) };
+
+            for _ in 0..2 {
+                circuit
+                    .add_source(Generator::new(|| 0usize))
+                    .inspect(|_: &usize| panic!("operator failed"));
+            }
+            Ok(())
+        })
+        .unwrap()
+        .0;
+
+        root.step().unwrap();
+    }
 }
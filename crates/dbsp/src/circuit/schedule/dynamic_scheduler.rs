@@ -98,16 +98,16 @@ struct Task {
 /// The set of async nodes for which the scheduler has received ready
 /// notifications.
 #[derive(Clone)]
-struct Notifications {
+pub(super) struct Notifications {
     /// Nodes that received notifications.
-    nodes: Arc<Mutex<HashSet<NodeId>>>,
+    pub(super) nodes: Arc<Mutex<HashSet<NodeId>>>,
 
     /// Handle to wake up the scheduler thread when a notification arrives.
     unparker: Unparker,
 }
 
 impl Notifications {
-    fn new(size: usize, unparker: Unparker) -> Self {
+    pub(super) fn new(size: usize, unparker: Unparker) -> Self {
         Self {
             nodes: Arc::new(Mutex::new(HashSet::with_capacity(size))),
             unparker,
@@ -115,7 +115,7 @@ impl Notifications {
     }
 
     /// Add a new notification.
-    fn notify(&self, node_id: NodeId) {
+    pub(super) fn notify(&self, node_id: NodeId) {
         self.nodes.lock().unwrap().insert(node_id);
         self.unparker.unpark();
     }
@@ -345,9 +345,7 @@ impl Inner {
         }
 
         while completed_tasks < self.tasks.len() {
-            if Runtime::kill_in_progress() {
-                return Err(Error::Killed);
-            }
+            Runtime::check_interrupted()?;
 
             match self.dequeue_next_task() {
                 None => {
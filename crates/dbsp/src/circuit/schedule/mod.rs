@@ -1,11 +1,13 @@
 //! The scheduling framework controls the execution of a circuit at runtime.
 
-use super::{trace::SchedulerEvent, Circuit, GlobalNodeId};
-use crate::DetailedError;
+use super::{metadata::OperatorMeta, trace::SchedulerEvent, Circuit, GlobalNodeId};
+use crate::{DetailedError, Runtime};
 use itertools::Itertools;
 use serde::Serialize;
 use std::{
     borrow::Cow,
+    cell::RefCell,
+    cmp::max,
     error::Error as StdError,
     fmt::{Display, Error as FmtError, Formatter},
     string::ToString,
@@ -17,6 +19,9 @@ pub use static_scheduler::StaticScheduler;
 mod dynamic_scheduler;
 pub use dynamic_scheduler::DynamicScheduler;
 
+mod parallel_scheduler;
+pub use parallel_scheduler::ParallelScheduler;
+
 /// Scheduler errors.
 #[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 #[serde(untagged)]
@@ -33,6 +38,23 @@ pub enum Error {
     /// Execution of the circuit interrupted by the user (via
     /// [`RuntimeHandle::kill`](`crate::circuit::RuntimeHandle::kill`)).
     Killed,
+    /// The step was abandoned via a
+    /// [`CancellationToken`](`crate::circuit::CancellationToken`).
+    Cancelled,
+    /// Iterative subcircuit `circuit_id` did not terminate within the maximum
+    /// number of iterations configured via [`IterationLimits`].
+    IterationLimitExceeded {
+        circuit_id: GlobalNodeId,
+        iterations: usize,
+    },
+    /// The state of iterative subcircuit `circuit_id` grew beyond the limit
+    /// configured via [`IterationLimits::with_max_growth`], suggesting that
+    /// the iteration diverges.
+    DivergentIteration {
+        circuit_id: GlobalNodeId,
+        iterations: usize,
+        used_bytes: usize,
+    },
 }
 
 impl DetailedError for Error {
@@ -41,6 +63,9 @@ impl DetailedError for Error {
             Self::OwnershipConflict { .. } => Cow::from("OwnershipConflict"),
             Self::CyclicCircuit { .. } => Cow::from("CyclicCircuit"),
             Self::Killed => Cow::from("Killed"),
+            Self::Cancelled => Cow::from("Cancelled"),
+            Self::IterationLimitExceeded { .. } => Cow::from("IterationLimitExceeded"),
+            Self::DivergentIteration { .. } => Cow::from("DivergentIteration"),
         }
     }
 }
@@ -56,6 +81,20 @@ impl Display for Error {
                 write!(f, "unschedulable circuit due to a cyclic topology: cycle through node '{node_id}'")
             }
             Self::Killed => f.write_str("circuit has been killed by the user"),
+            Self::Cancelled => f.write_str("circuit step has been cancelled"),
+            Self::IterationLimitExceeded {
+                circuit_id,
+                iterations,
+            } => {
+                write!(f, "iterative subcircuit '{circuit_id}' did not terminate after {iterations} iterations")
+            }
+            Self::DivergentIteration {
+                circuit_id,
+                iterations,
+                used_bytes,
+            } => {
+                write!(f, "iterative subcircuit '{circuit_id}' appears to diverge: its state has grown to {used_bytes} bytes after {iterations} iterations")
+            }
         }
     }
 }
@@ -112,6 +151,88 @@ where
 /// some termination condition is reached.
 pub trait Executor<C>: 'static {
     fn run(&self, circuit: &C) -> Result<(), Error>;
+
+    /// Report executor statistics as metadata of the node that contains the
+    /// circuit.
+    fn metadata(&self, _meta: &mut OperatorMeta) {}
+}
+
+/// Limits on the iterations performed by an iterative subcircuit, e.g., one
+/// created by [`Stream::recursive`](`crate::Stream::recursive`), within a
+/// single clock cycle of the parent circuit.
+///
+/// A subcircuit that exceeds a limit fails with
+/// [`Error::IterationLimitExceeded`] or [`Error::DivergentIteration`].
+/// Limits are configured by calling
+/// [`ChildCircuit::set_iteration_limits`](`crate::ChildCircuit::set_iteration_limits`)
+/// from the subcircuit constructor.  By default, subcircuits iterate without
+/// bounds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IterationLimits {
+    max_iterations: Option<usize>,
+    max_growth: Option<f64>,
+    growth_check_interval: usize,
+}
+
+impl IterationLimits {
+    /// The default value of [`Self::with_growth_check_interval`].
+    pub const DEFAULT_GROWTH_CHECK_INTERVAL: usize = 64;
+
+    /// Create limits that allow unbounded iteration.
+    pub fn new() -> Self {
+        Self {
+            max_iterations: None,
+            max_growth: None,
+            growth_check_interval: Self::DEFAULT_GROWTH_CHECK_INTERVAL,
+        }
+    }
+
+    /// Fail after `max_iterations` iterations without reaching the
+    /// termination condition.
+    pub fn with_max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    /// Fail if the state of the subcircuit grows by more than a factor of
+    /// `max_growth` within a clock cycle.
+    ///
+    /// The size of the state is first measured after
+    /// [`growth_check_interval`](`Self::with_growth_check_interval`)
+    /// iterations and then again every `growth_check_interval` iterations.
+    /// The check fails if the state has grown by more than `max_growth` since
+    /// the first measurement.  Measuring the state requires traversing all of
+    /// it, so the interval should not be too small.
+    pub fn with_max_growth(mut self, max_growth: f64) -> Self {
+        self.max_growth = Some(max_growth);
+        self
+    }
+
+    /// Set the number of iterations between consecutive growth checks.
+    pub fn with_growth_check_interval(mut self, interval: usize) -> Self {
+        self.growth_check_interval = max(interval, 1);
+        self
+    }
+}
+
+impl Default for IterationLimits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Iteration counts of an iterative subcircuit, reported as subcircuit
+/// metadata.
+#[derive(Default)]
+struct IterationStats {
+    /// The number of clock cycles of the parent circuit.
+    epochs: usize,
+    /// The total number of iterations across all epochs.
+    total_iterations: usize,
+    /// The largest number of iterations in an epoch.
+    max_iterations: usize,
+    /// The number of iterations in the most recent epoch.
+    last_iterations: usize,
 }
 
 /// An iterative executor evaluates the circuit until the `termination_check`
@@ -119,13 +240,25 @@ pub trait Executor<C>: 'static {
 /// the `clock_start` notification to all operators in the circuit. It then
 /// evaluates the circuit until the termination condition is satisfied (but at
 /// least once), and finally calls `clock_end` on it.
+///
+/// The executor fails if the circuit exceeds its [`IterationLimits`].  The
+/// `state_size` callback returns the number of bytes used by the state of the
+/// circuit for growth checks.
 pub(crate) struct IterativeExecutor<F, S> {
     termination_check: F,
     scheduler: S,
+    limits: IterationLimits,
+    state_size: Box<dyn Fn() -> usize>,
+    stats: RefCell<IterationStats>,
 }
 
 impl<F, S> IterativeExecutor<F, S> {
-    pub(crate) fn new<C>(circuit: &C, termination_check: F) -> Result<Self, Error>
+    pub(crate) fn new<C>(
+        circuit: &C,
+        termination_check: F,
+        limits: IterationLimits,
+        state_size: Box<dyn Fn() -> usize>,
+    ) -> Result<Self, Error>
     where
         C: Circuit,
         S: Scheduler,
@@ -133,8 +266,57 @@ impl<F, S> IterativeExecutor<F, S> {
         Ok(Self {
             termination_check,
             scheduler: <S as Scheduler>::prepare(circuit)?,
+            limits,
+            state_size,
+            stats: RefCell::new(IterationStats::default()),
         })
     }
+
+    /// Fails if the circuit, which has not reached its termination condition
+    /// after `iterations` iterations, exceeds its limits.  `baseline` is the
+    /// size of the state at the first growth check in the current epoch.
+    fn check_limits(
+        &self,
+        circuit_id: &GlobalNodeId,
+        iterations: usize,
+        baseline: &mut Option<usize>,
+    ) -> Result<(), Error> {
+        if let Some(max_iterations) = self.limits.max_iterations {
+            if iterations >= max_iterations {
+                return Err(Error::IterationLimitExceeded {
+                    circuit_id: circuit_id.clone(),
+                    iterations,
+                });
+            }
+        }
+
+        if let Some(max_growth) = self.limits.max_growth {
+            if iterations % self.limits.growth_check_interval == 0 {
+                let used_bytes = (self.state_size)();
+                match *baseline {
+                    None => *baseline = Some(used_bytes),
+                    Some(baseline) if used_bytes as f64 > max(baseline, 1) as f64 * max_growth => {
+                        return Err(Error::DivergentIteration {
+                            circuit_id: circuit_id.clone(),
+                            iterations,
+                            used_bytes,
+                        });
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_epoch(&self, iterations: usize) {
+        let mut stats = self.stats.borrow_mut();
+        stats.epochs += 1;
+        stats.total_iterations += iterations;
+        stats.max_iterations = max(stats.max_iterations, iterations);
+        stats.last_iterations = iterations;
+    }
 }
 
 impl<C, F, S> Executor<C> for IterativeExecutor<F, S>
@@ -147,17 +329,38 @@ where
         circuit.log_scheduler_event(&SchedulerEvent::clock_start());
         circuit.clock_start(0);
 
+        let mut iterations = 0;
+        let mut baseline = None;
+
         loop {
+            // Check between iterations, so that a circuit that never reaches
+            // its termination condition can still be interrupted.
+            Runtime::check_interrupted()?;
             self.scheduler.step(circuit)?;
+            iterations += 1;
             if (self.termination_check)()? {
                 break;
             }
+            self.check_limits(&circuit.global_id(), iterations, &mut baseline)?;
         }
 
+        self.record_epoch(iterations);
+
         circuit.log_scheduler_event(&SchedulerEvent::clock_end());
         circuit.clock_end(0);
         Ok(())
     }
+
+    fn metadata(&self, meta: &mut OperatorMeta) {
+        let stats = self.stats.borrow();
+
+        meta.extend(metadata! {
+            "epochs" => stats.epochs,
+            "total iterations" => stats.total_iterations,
+            "max iterations" => stats.max_iterations,
+            "last iterations" => stats.last_iterations,
+        });
+    }
 }
 
 /// An executor that evaluates the circuit exactly once every time it is
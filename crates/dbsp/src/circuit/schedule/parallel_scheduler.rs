@@ -0,0 +1,547 @@
+//! Parallel scheduler.
+//!
+//! The parallel scheduler is a variant of the
+//! [`DynamicScheduler`](`crate::circuit::schedule::DynamicScheduler`) that
+//! evaluates independent operators of a circuit concurrently.  The number of
+//! operators evaluated at the same time is configured per circuit via
+//! [`ChildCircuit::set_parallelism`](`crate::ChildCircuit::set_parallelism`).
+//! With the default parallelism of 1, the scheduler behaves like the dynamic
+//! scheduler.
+//!
+//! # Design
+//!
+//! ## Batches
+//!
+//! Like the dynamic scheduler, the parallel scheduler tracks for each node
+//! the number of predecessors that are yet to be evaluated and, for async
+//! nodes, their ready status, and keeps runnable nodes in a priority queue.
+//! Instead of dequeuing one node at a time, it dequeues a batch of up to
+//! `parallelism` runnable nodes, evaluates them concurrently, and waits for
+//! all of them to finish before dequeuing the next batch.
+//!
+//! All predecessors of a runnable node have been evaluated, so nodes in a
+//! batch never depend on each other.  In addition, no two nodes in a batch
+//! read from the same stream, because consumers of a stream share its value
+//! and the last consumer takes ownership of it.  A node that conflicts with
+//! the batch stays in the run queue until the next batch.
+//!
+//! Async operators and subcircuits are always evaluated alone, on the worker
+//! thread: the former may block waiting for data from other workers, and the
+//! latter run their own scheduler, which relies on the thread-local state of
+//! the worker thread.
+//!
+//! ## Thread pool
+//!
+//! The scheduler owns a pool of `parallelism - 1` threads.  The first node in
+//! a batch is evaluated on the worker thread, the rest are evaluated by the
+//! pool.  Pool threads observe the same
+//! [`Runtime::runtime`](`crate::Runtime::runtime`) and
+//! [`Runtime::worker_index`](`crate::Runtime::worker_index`) as the worker
+//! thread.  A panic in an operator evaluated by the pool is propagated to the
+//! worker thread once the batch completes.
+
+use std::{
+    any::Any,
+    cell::{RefCell, RefMut},
+    collections::HashMap,
+    mem::transmute,
+    ops::Deref,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread::{Builder, JoinHandle},
+};
+
+use crate::circuit::{
+    runtime::Runtime,
+    schedule::{
+        dynamic_scheduler::Notifications,
+        util::{circuit_graph, ownership_constraints},
+        Error, Scheduler,
+    },
+    trace::SchedulerEvent,
+    Circuit, GlobalNodeId, NodeId,
+};
+use crossbeam::channel::{unbounded, Sender};
+use crossbeam_utils::sync::WaitGroup;
+use petgraph::algo::toposort;
+use priority_queue::PriorityQueue;
+
+/// A task is a unit of work scheduled by the parallel scheduler.
+/// It contains a reference to a node in the circuit and associated metadata.
+struct Task {
+    // Immutable fields (initialized once when preparing the scheduler).
+    /// Circuit node to be scheduled.
+    node_id: NodeId,
+
+    /// The number of predecessors of the node in the circuit graph.
+    /// All predecessors must be evaluated before the node can be evaluated.
+    num_predecessors: usize,
+
+    /// Successors of the node in the circuit graph.
+    successors: Vec<NodeId>,
+
+    /// Streams read by the node, as indexes into `Inner::busy_streams`.
+    inputs: Vec<usize>,
+
+    /// Scheduling priority.  The scheduler picks the top priority nodes out
+    /// of all runnable nodes in the current state.
+    priority: isize,
+
+    /// `true` if this is an async node.  The node can only be evaluated in a
+    /// ready state.
+    is_async: bool,
+
+    /// `true` if the node must be evaluated alone on the worker thread
+    /// (async nodes and subcircuits).
+    exclusive: bool,
+
+    // Mutable fields.
+    /// Number of predecessors not yet evaluated.  Set to `num_predecessors`
+    /// at the start of each step.
+    unsatisfied_dependencies: usize,
+
+    /// `true` if the async node is known to be in a ready state.  Always
+    /// `true` for non-async nodes.
+    is_ready: bool,
+
+    /// Task has been scheduled (put on the run queue) in the current clock
+    /// cycle.
+    scheduled: bool,
+}
+
+impl Task {
+    /// Add the task to the runnable queue.
+    fn schedule(&mut self, runnable: &mut PriorityQueue<NodeId, isize>) {
+        debug_assert!(self.unsatisfied_dependencies == 0);
+        debug_assert!(self.is_ready);
+        debug_assert!(!self.scheduled);
+
+        runnable.push(self.node_id, self.priority);
+        self.scheduled = true;
+    }
+}
+
+/// A job evaluated by the thread pool.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Threads that evaluate operators on behalf of a worker thread.
+struct ThreadPool {
+    /// Sends jobs to the pool; `None` once the pool is shutting down.
+    sender: Option<Sender<Job>>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Spawn `size` threads that act on behalf of the current worker thread.
+    fn new(size: usize) -> Self {
+        let runtime = Runtime::runtime();
+        let worker_index = Runtime::worker_index();
+        let (sender, receiver) = unbounded::<Job>();
+
+        let threads = (0..size)
+            .map(|thread_index| {
+                let runtime = runtime.clone();
+                let receiver = receiver.clone();
+                Builder::new()
+                    .name(format!("dbsp-worker-{worker_index}-{thread_index}"))
+                    .spawn(move || {
+                        Runtime::enter_worker(runtime, worker_index);
+                        for job in receiver {
+                            job();
+                        }
+                    })
+                    .unwrap_or_else(|error| {
+                        panic!("failed to spawn scheduler thread {thread_index}: {error}");
+                    })
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    /// Run `jobs` to completion, evaluating the first job on the current
+    /// thread and sending the rest to the pool.
+    ///
+    /// If any of the jobs panics, resumes the panic once all jobs have
+    /// completed.
+    fn run(&self, jobs: Vec<Box<dyn FnOnce() + Send + '_>>) {
+        let panic: Arc<Mutex<Option<Box<dyn Any + Send>>>> = Arc::new(Mutex::new(None));
+        let wait_group = WaitGroup::new();
+
+        let mut jobs = jobs.into_iter();
+        let first = jobs.next();
+
+        for job in jobs {
+            let panic = panic.clone();
+            let wait_group = wait_group.clone();
+            let job: Box<dyn FnOnce() + Send + '_> = Box::new(move || {
+                if let Err(error) = catch_unwind(AssertUnwindSafe(job)) {
+                    panic.lock().unwrap().get_or_insert(error);
+                }
+                drop(wait_group);
+            });
+
+            // Safety: we wait for all jobs to complete below, so the job
+            // cannot outlive the data it borrows.  Sending cannot fail, as
+            // pool threads hold on to the receiver until the pool is dropped.
+            let job: Job = unsafe { transmute(job) };
+            self.sender.as_ref().unwrap().send(job).unwrap();
+        }
+
+        if let Some(first) = first {
+            if let Err(error) = catch_unwind(AssertUnwindSafe(first)) {
+                panic.lock().unwrap().get_or_insert(error);
+            }
+        }
+        wait_group.wait();
+
+        // Bind the payload first to release the lock before unwinding.
+        let payload = panic.lock().unwrap().take();
+        if let Some(payload) = payload {
+            resume_unwind(payload);
+        }
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // Closing the channel terminates the pool threads.
+        self.sender = None;
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Parallel scheduler internals.
+struct Inner {
+    // Immutable fields (initialized once when preparing the scheduler).
+    /// List of tasks that must be evaluated at each clock cycle.
+    /// Tasks are stored in the same order as nodes in the circuit and
+    /// task index is equal to the node id.
+    tasks: Vec<Task>,
+
+    /// Maximal number of nodes evaluated concurrently.
+    parallelism: usize,
+
+    /// Threads that evaluate all but the first node of each batch, or `None`
+    /// if `parallelism` is 1.
+    pool: Option<ThreadPool>,
+
+    // Mutable fields.
+    /// Ready notifications received while the scheduler was busy or sleeping.
+    notifications: Notifications,
+
+    /// Tasks that are ready to be executed.
+    runnable: PriorityQueue<NodeId, isize>,
+
+    /// Streams read by nodes in the batch being assembled, indexed by
+    /// `Task::inputs`.
+    busy_streams: Vec<bool>,
+}
+
+impl Inner {
+    /// Dequeue a batch of up to `parallelism` highest-priority runnable tasks
+    /// that can be evaluated concurrently into `batch`.  Update all successors
+    /// of the tasks, reducing their unsatisfied dependencies by 1.  Move
+    /// successors to the runnable queue when possible.
+    fn dequeue_batch(&mut self, batch: &mut Vec<NodeId>) {
+        debug_assert!(batch.is_empty());
+
+        let mut deferred = Vec::new();
+        while batch.len() < self.parallelism {
+            let Some((node_id, priority)) = self.runnable.pop() else {
+                break;
+            };
+            let task = &self.tasks[node_id.id()];
+
+            if task.exclusive {
+                // Exclusive tasks run in batches of their own.
+                if batch.is_empty() {
+                    batch.push(node_id);
+                    break;
+                }
+                deferred.push((node_id, priority));
+            } else if task.inputs.iter().any(|&input| self.busy_streams[input]) {
+                deferred.push((node_id, priority));
+            } else {
+                for &input in task.inputs.iter() {
+                    self.busy_streams[input] = true;
+                }
+                batch.push(node_id);
+            }
+        }
+
+        for (node_id, priority) in deferred {
+            self.runnable.push(node_id, priority);
+        }
+
+        for node_id in batch.iter() {
+            for &input in self.tasks[node_id.id()].inputs.iter() {
+                self.busy_streams[input] = false;
+            }
+        }
+
+        // Update successor dependencies.  Successors only become runnable in
+        // the next batch.
+        for node_id in batch.iter() {
+            let id = node_id.id();
+
+            // Don't use iterator, as we will borrow `tasks` again below.
+            for i in 0..self.tasks[id].successors.len() {
+                let succ_id = self.tasks[id].successors[i];
+                debug_assert!(succ_id.id() < self.tasks.len());
+                let successor = &mut self.tasks[succ_id.id()];
+                debug_assert!(successor.unsatisfied_dependencies != 0);
+                successor.unsatisfied_dependencies -= 1;
+                if successor.unsatisfied_dependencies == 0 && successor.is_ready {
+                    successor.schedule(&mut self.runnable);
+                }
+            }
+        }
+    }
+
+    /// Process and dequeue new notifications.
+    fn process_notifications<C>(&mut self, circuit: &C)
+    where
+        C: Circuit,
+    {
+        let nodes: Vec<NodeId> = self.notifications.nodes.lock().unwrap().drain().collect();
+
+        for id in nodes {
+            let task = &mut self.tasks[id.id()];
+            debug_assert!(task.is_async);
+
+            // Ignore duplicate notifications.
+            if task.is_ready {
+                continue;
+            }
+
+            // Ignore spurious notifications.
+            if circuit.ready(id) {
+                task.is_ready = true;
+
+                // We can see a notification for an already scheduled task
+                // indicating that it's become ready again.
+                // This notification should take effect at the next clock
+                // cycle.
+                if task.unsatisfied_dependencies == 0 && !task.scheduled {
+                    task.schedule(&mut self.runnable);
+                }
+            }
+        }
+    }
+
+    fn prepare<C>(circuit: &C) -> Result<Self, Error>
+    where
+        C: Circuit,
+    {
+        // Check that ownership constraints don't introduce cycles.
+        let mut g = circuit_graph(circuit);
+
+        let extra_constraints = ownership_constraints(circuit)?;
+
+        for (from, to) in extra_constraints.iter() {
+            g.add_edge(*from, *to, ());
+        }
+
+        // `toposort` fails if the graph contains cycles.
+        toposort(&g, None).map_err(|e| Error::CyclicCircuit {
+            node_id: GlobalNodeId::child_of(circuit, e.node_id()),
+        })?;
+
+        let num_nodes = circuit.num_nodes();
+        let mut successors: HashMap<NodeId, Vec<NodeId>> = HashMap::with_capacity(num_nodes);
+        let mut predecessors: HashMap<NodeId, Vec<NodeId>> = HashMap::with_capacity(num_nodes);
+        let mut inputs: HashMap<NodeId, Vec<usize>> = HashMap::with_capacity(num_nodes);
+        let mut streams: HashMap<GlobalNodeId, usize> = HashMap::new();
+
+        for edge in circuit.edges().iter() {
+            successors
+                .entry(edge.from)
+                .or_insert_with(Vec::new)
+                .push(edge.to);
+
+            predecessors
+                .entry(edge.to)
+                .or_insert_with(Vec::new)
+                .push(edge.from);
+
+            // Dependency edges don't carry a stream.
+            if edge.is_stream() {
+                let num_streams = streams.len();
+                let stream = *streams.entry(edge.origin.clone()).or_insert(num_streams);
+                inputs.entry(edge.to).or_insert_with(Vec::new).push(stream);
+            }
+        }
+
+        // Add ownership constraints to the graph.
+        for (from, to) in extra_constraints.into_iter() {
+            successors.entry(from).or_insert_with(Vec::new).push(to);
+            predecessors.entry(to).or_insert_with(Vec::new).push(from);
+        }
+
+        let mut tasks = Vec::with_capacity(num_nodes);
+        let mut num_async_nodes = 0;
+
+        for (i, node_id) in circuit.node_ids().into_iter().enumerate() {
+            // We rely on node id to be equal to its index.
+            assert!(i == node_id.id());
+
+            // Same heuristic as the dynamic scheduler.
+            let num_predecessors = predecessors.entry(node_id).or_default().len();
+            let num_successors = successors.entry(node_id).or_default().len();
+            let priority = num_predecessors as isize - num_successors as isize;
+
+            let is_async = circuit.is_async_node(node_id);
+            if is_async {
+                num_async_nodes += 1;
+            }
+
+            tasks.push(Task {
+                node_id,
+                num_predecessors,
+                successors: successors.entry(node_id).or_default().clone(),
+                inputs: inputs.remove(&node_id).unwrap_or_default(),
+                priority,
+                is_async,
+                exclusive: is_async || circuit.is_circuit_node(node_id),
+                unsatisfied_dependencies: num_predecessors,
+                is_ready: !is_async,
+                scheduled: false,
+            });
+        }
+
+        let parallelism = circuit.parallelism();
+        let unparker = Runtime::parker().with(|parker| parker.unparker().clone());
+        let scheduler = Self {
+            tasks,
+            parallelism,
+            pool: (parallelism > 1).then(|| ThreadPool::new(parallelism - 1)),
+            notifications: Notifications::new(num_async_nodes, unparker),
+            runnable: PriorityQueue::with_capacity(num_nodes),
+            busy_streams: vec![false; streams.len()],
+        };
+
+        // Setup scheduler callbacks.
+        for node_id in circuit.node_ids().into_iter() {
+            if circuit.is_async_node(node_id) {
+                let notifications = scheduler.notifications.clone();
+                circuit.register_ready_callback(
+                    node_id,
+                    Box::new(move || notifications.notify(node_id)),
+                );
+
+                // Since we missed any earlier notifications, generate one for
+                // each ready node.
+                if circuit.ready(node_id) {
+                    scheduler.notifications.notify(node_id);
+                }
+            }
+        }
+
+        Ok(scheduler)
+    }
+
+    fn step<C>(&mut self, circuit: &C) -> Result<(), Error>
+    where
+        C: Circuit,
+    {
+        circuit.log_scheduler_event(&SchedulerEvent::step_start(circuit.global_id().deref()));
+
+        let mut completed_tasks = 0;
+        let mut batch = Vec::with_capacity(self.parallelism);
+
+        // Reset unsatisfied dependencies, initialize runnable queue.
+        for task in self.tasks.iter_mut() {
+            task.unsatisfied_dependencies = task.num_predecessors;
+            task.scheduled = false;
+
+            if task.unsatisfied_dependencies == 0 && task.is_ready {
+                task.schedule(&mut self.runnable);
+            }
+        }
+
+        while completed_tasks < self.tasks.len() {
+            Runtime::check_interrupted()?;
+
+            self.dequeue_batch(&mut batch);
+            match batch.as_slice() {
+                [] => {
+                    // No more tasks in the run queue -- try to add some by
+                    // processing notifications.
+                    self.process_notifications(circuit);
+
+                    // Still nothing to do -- sleep waiting for a notification to
+                    // unpark us.
+                    if self.runnable.is_empty() {
+                        circuit.log_scheduler_event(&SchedulerEvent::wait_start(
+                            circuit.global_id().deref(),
+                        ));
+                        Runtime::parker().with(|parker| parker.park());
+                        circuit.log_scheduler_event(&SchedulerEvent::wait_end(
+                            circuit.global_id().deref(),
+                        ));
+                    }
+                }
+
+                [node_id] => circuit.eval_node(*node_id)?,
+
+                node_ids => {
+                    let pool = self.pool.as_ref().unwrap();
+
+                    // Safety: `dequeue_batch` only batches distinct,
+                    // non-exclusive runnable nodes that don't read the same
+                    // stream, and `circuit.parallelism() > 1` means that the
+                    // circuit has opted into concurrent evaluation.
+                    unsafe { circuit.eval_nodes(node_ids, |jobs| pool.run(jobs))? };
+                }
+            }
+
+            for node_id in batch.drain(..) {
+                let task = &mut self.tasks[node_id.id()];
+                if task.is_async {
+                    task.is_ready = false;
+                }
+
+                completed_tasks += 1;
+            }
+        }
+        circuit.tick();
+
+        circuit.log_scheduler_event(&SchedulerEvent::step_end(circuit.global_id().deref()));
+        Ok(())
+    }
+}
+
+/// Scheduler that evaluates independent operators of a circuit concurrently.
+///
+/// See the [module documentation](self) for details.  The circuit must opt
+/// into concurrent evaluation via
+/// [`ChildCircuit::set_parallelism`](`crate::ChildCircuit::set_parallelism`).
+pub struct ParallelScheduler(RefCell<Inner>);
+
+impl ParallelScheduler {
+    fn inner_mut(&self) -> RefMut<'_, Inner> {
+        self.0.borrow_mut()
+    }
+}
+
+impl Scheduler for ParallelScheduler {
+    fn prepare<C>(circuit: &C) -> Result<Self, Error>
+    where
+        C: Circuit,
+    {
+        Ok(Self(RefCell::new(Inner::prepare(circuit)?)))
+    }
+
+    fn step<C>(&self, circuit: &C) -> Result<(), Error>
+    where
+        C: Circuit,
+    {
+        self.inner_mut().step(circuit)
+    }
+}
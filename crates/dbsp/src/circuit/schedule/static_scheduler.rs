@@ -57,15 +57,11 @@ impl Scheduler for StaticScheduler {
 
         for (node_id, is_async) in self.schedule.iter() {
             if !is_async {
-                if Runtime::kill_in_progress() {
-                    return Err(Error::Killed);
-                }
+                Runtime::check_interrupted()?;
                 circuit.eval_node(*node_id)?;
             } else {
                 loop {
-                    if Runtime::kill_in_progress() {
-                        return Err(Error::Killed);
-                    }
+                    Runtime::check_interrupted()?;
                     if circuit.ready(*node_id) {
                         circuit.eval_node(*node_id)?;
                         break;
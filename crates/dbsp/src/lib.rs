@@ -62,8 +62,8 @@ pub use crate::time::Timestamp;
 
 pub use algebra::{IndexedZSet, ZSet};
 pub use circuit::{
-    ChildCircuit, Circuit, CircuitHandle, DBSPHandle, RootCircuit, Runtime, RuntimeError,
-    SchedulerError, Stream,
+    CancellationToken, ChildCircuit, Circuit, CircuitHandle, DBSPHandle, IterationLimits,
+    RootCircuit, Runtime, RuntimeError, SchedulerError, Stream,
 };
 pub use operator::{CollectionHandle, InputHandle, OutputHandle, UpsertHandle};
 pub use trace::ord::{OrdIndexedZSet, OrdZSet};
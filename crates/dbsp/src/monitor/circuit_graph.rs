@@ -106,14 +106,18 @@ impl Region {
             nodes.push(VisNode::Cluster(child.visualize(scope, "", annotate)));
         }
 
+        // The root region of a circuit represents the circuit node itself.
+        let node_id = if self.id == RegionId::root() {
+            Some(scope.id.clone())
+        } else {
+            None
+        };
+
         ClusterNode::new(
             Self::region_identifier(&scope.id, &self.id),
-            format!(
-                "{}{}{}",
-                label(&self.name, self.location),
-                if annotation.is_empty() { "" } else { "\\l" },
-                annotation
-            ),
+            node_id,
+            label(&self.name, self.location),
+            annotation.to_owned(),
             nodes,
         )
     }
@@ -305,12 +309,9 @@ impl Node {
         match &self.kind {
             NodeKind::Operator => Some(VisNode::Simple(SimpleNode::new(
                 Self::node_identifier(&self.id),
-                format!(
-                    "{}{}{}",
-                    label(&self.name, self.location),
-                    if annotation.is_empty() { "" } else { "\\l" },
-                    annotation
-                ),
+                self.id.clone(),
+                label(&self.name, self.location),
+                annotation,
             ))),
 
             NodeKind::Circuit { region, .. } => Some(VisNode::Cluster(region.visualize(
@@ -319,15 +320,15 @@ impl Node {
                 annotate,
             ))),
 
-            NodeKind::StrictInput { output } => Some(VisNode::Simple(SimpleNode::new(
-                Self::node_identifier(&self.id.parent_id().unwrap().child(*output)),
-                format!(
-                    "{}{}{}",
+            NodeKind::StrictInput { output } => {
+                let output_id = self.id.parent_id().unwrap().child(*output);
+                Some(VisNode::Simple(SimpleNode::new(
+                    Self::node_identifier(&output_id),
+                    output_id,
                     label(&self.name, self.location),
-                    if annotation.is_empty() { "" } else { "\\l" },
-                    annotation
-                ),
-            ))),
+                    annotation,
+                )))
+            }
             NodeKind::StrictOutput => None,
         }
     }
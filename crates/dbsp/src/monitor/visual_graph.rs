@@ -1,6 +1,8 @@
 //! Intermediate representation of a circuit graph suitable for
-//! conversion to a visual format like dot.
+//! conversion to a visual format like dot or JSON.
 
+use crate::circuit::GlobalNodeId;
+use serde::Serialize;
 use std::fmt::{self, Write};
 
 type Id = String;
@@ -8,6 +10,9 @@ type Id = String;
 /// Visual representation of a circuit graph.
 ///
 /// The graph consists of a tree of cluster nodes populated with simple nodes.
+/// It can be converted to dot using [`Graph::to_dot`] or serialized, e.g., to
+/// JSON, for rendering by other tools.
+#[derive(Debug, Clone, Serialize)]
 pub struct Graph {
     nodes: ClusterNode,
     edges: Vec<Edge>,
@@ -37,18 +42,33 @@ impl Graph {
     }
 }
 
-pub(super) struct SimpleNode {
+/// A simple node represents an operator.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimpleNode {
     id: Id,
+    /// Global id of the operator.
+    node_id: GlobalNodeId,
     label: String,
+    annotation: String,
 }
 
 impl SimpleNode {
-    pub(super) fn new(id: Id, label: String) -> Self {
-        Self { id, label }
+    pub(super) fn new(id: Id, node_id: GlobalNodeId, label: String, annotation: String) -> Self {
+        Self {
+            id,
+            node_id,
+            label,
+            annotation,
+        }
     }
 
     fn to_dot(&self, output: &mut dyn Write) -> fmt::Result {
-        writeln!(output, "{}[label=\"{}\"]", self.id, self.label)
+        writeln!(
+            output,
+            "{}[label=\"{}\"]",
+            self.id,
+            dot_label(&self.label, &self.annotation)
+        )
     }
 }
 
@@ -56,15 +76,32 @@ impl SimpleNode {
 // TODO:
 // * Visually distinguish subcircuits from regions (e.g., dashed vs solid
 //   boundaries).
-pub(super) struct ClusterNode {
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterNode {
     id: Id,
+    /// Global id of the subcircuit if this is the root region of a
+    /// subcircuit; `None` for regions nested inside a circuit.
+    node_id: Option<GlobalNodeId>,
     label: String,
+    annotation: String,
     nodes: Vec<Node>,
 }
 
 impl ClusterNode {
-    pub(super) fn new(id: Id, label: String, nodes: Vec<Node>) -> Self {
-        Self { id, label, nodes }
+    pub(super) fn new(
+        id: Id,
+        node_id: Option<GlobalNodeId>,
+        label: String,
+        annotation: String,
+        nodes: Vec<Node>,
+    ) -> Self {
+        Self {
+            id,
+            node_id,
+            label,
+            annotation,
+            nodes,
+        }
     }
 
     // TODO: We add a pair of enter/exit nodes to each cluster and connect all
@@ -74,7 +111,11 @@ impl ClusterNode {
     // builder API.
     fn to_dot(&self, output: &mut dyn Write) -> fmt::Result {
         writeln!(output, "subgraph cluster_{} {{", &self.id)?;
-        writeln!(output, "label=\"{}\"", self.label)?;
+        writeln!(
+            output,
+            "label=\"{}\"",
+            dot_label(&self.label, &self.annotation)
+        )?;
         writeln!(output, "enter_{}[style=invis]", self.id)?;
         writeln!(output, "exit_{}[style=invis]", self.id)?;
         for node in self.nodes.iter() {
@@ -86,7 +127,9 @@ impl ClusterNode {
     }
 }
 
-pub(super) enum Node {
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Node {
     Simple(SimpleNode),
     Cluster(ClusterNode),
 }
@@ -107,7 +150,9 @@ impl Node {
     }
 }
 
-pub(super) struct Edge {
+/// A stream connecting two nodes.
+#[derive(Debug, Clone, Serialize)]
+pub struct Edge {
     from_node: Id,
     // Is `from_node` a cluster?
     from_cluster: bool,
@@ -138,3 +183,13 @@ impl Edge {
         writeln!(output, "{}", self.to_node)
     }
 }
+
+/// Label of a node in the dot format: the node label followed by its
+/// annotation on a separate left-justified line.
+fn dot_label(label: &str, annotation: &str) -> String {
+    if annotation.is_empty() {
+        label.to_owned()
+    } else {
+        format!("{label}\\l{annotation}")
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{
+    algebra::Semigroup, operator::aggregate::Aggregator, trace::Cursor, DBData, DBWeight, Timestamp,
+};
+use rkyv::{Archive, Deserialize, Serialize};
+use size_of::SizeOf;
+use std::hash::{Hash, Hasher};
+use xxhash_rust::xxh3::Xxh3;
+
+// Seed used to hash values inserted in a HyperLogLog sketch.  Different from
+// the seed used to shard records across workers (see `crate::hash`), so that
+// the two hash functions are not correlated.
+const HLL_SEED: u64 = 0x2c3b_8e6d_1f0a_5947u64;
+
+/// HyperLogLog sketch that estimates the number of distinct values inserted
+/// in it.
+///
+/// The sketch consists of `2^precision` registers, and estimates the number
+/// of distinct values with standard error of approximately
+/// `1.04 / sqrt(2^precision)`, e.g., 1.6% for the default precision of 12.
+///
+/// Sketches with the same precision can be merged using
+/// [`merge`](`Self::merge`), which takes the point-wise maximum of their
+/// registers.  Merging is associative, commutative, and idempotent, and
+/// produces exactly the same sketch as inserting all values in a single
+/// sketch.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, SizeOf, Archive, Serialize, Deserialize,
+)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Smallest supported precision.
+    pub const MIN_PRECISION: u8 = 4;
+
+    /// Largest supported precision.
+    pub const MAX_PRECISION: u8 = 18;
+
+    /// Default precision.
+    pub const DEFAULT_PRECISION: u8 = 12;
+
+    /// Create an empty sketch with `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is not in the range
+    /// [`MIN_PRECISION`](`Self::MIN_PRECISION`)`..=`[`MAX_PRECISION`](`Self::MAX_PRECISION`).
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (Self::MIN_PRECISION..=Self::MAX_PRECISION).contains(&precision),
+            "HyperLogLog precision must be between {} and {}, got {precision}",
+            Self::MIN_PRECISION,
+            Self::MAX_PRECISION
+        );
+
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Returns the precision of the sketch.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns `true` if no values have been inserted in the sketch.
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|r| *r == 0)
+    }
+
+    /// Insert a value in the sketch.
+    pub fn insert<T>(&mut self, value: &T)
+    where
+        T: Hash + ?Sized,
+    {
+        let mut hasher = Xxh3::with_seed(HLL_SEED);
+        value.hash(&mut hasher);
+        self.insert_hash(hasher.finish());
+    }
+
+    fn insert_hash(&mut self, hash: u64) {
+        let precision = self.precision as u32;
+
+        // The first `precision` bits select the register; the number of
+        // leading zeros in the remaining bits is the observed rank.
+        let index = (hash >> (64 - precision)) as usize;
+        let rank = ((hash << precision).leading_zeros()).min(64 - precision) + 1;
+
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank as u8);
+    }
+
+    /// Merge `other` into `self`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sketches have different precisions.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "cannot merge HyperLogLog sketches with different precisions"
+        );
+
+        for (register, other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    /// Estimate the number of distinct values inserted in the sketch.
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+
+        let mut sum = 0.0;
+        let mut zeros = 0usize;
+        for register in self.registers.iter() {
+            sum += 2f64.powi(-(*register as i32));
+            if *register == 0 {
+                zeros += 1;
+            }
+        }
+
+        let raw_estimate = alpha * m * m / sum;
+
+        // Use linear counting for small cardinalities, where the raw estimate
+        // is known to be biased.  We use 64-bit hashes, so no correction is
+        // needed for large cardinalities.
+        let estimate = if raw_estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw_estimate
+        };
+
+        estimate.round() as u64
+    }
+}
+
+/// Semigroup structure over [`HyperLogLog`] sketches that merges sketches.
+#[derive(Clone)]
+pub struct HyperLogLogSemigroup;
+
+impl Semigroup<HyperLogLog> for HyperLogLogSemigroup {
+    fn combine(left: &HyperLogLog, right: &HyperLogLog) -> HyperLogLog {
+        let mut result = left.clone();
+        result.merge(right);
+        result
+    }
+}
+
+/// An [aggregator](`crate::operator::Aggregator`) that estimates the number of
+/// distinct values with positive weights using a [`HyperLogLog`] sketch.
+///
+/// This is an approximate alternative to
+/// [`Stream::distinct_count`](`crate::Stream::distinct_count`), whose state is
+/// proportional to the number of distinct values.  The accumulator of this
+/// aggregator is a fixed-size sketch, and sketches computed over different
+/// subsets of the input are combined using [`HyperLogLogSemigroup`], so the
+/// aggregator can be used with
+/// [`Stream::partitioned_rolling_aggregate`](`crate::Stream::partitioned_rolling_aggregate`).
+///
+/// # Retractions
+///
+/// HyperLogLog sketches do not support deletion.  Instead, retractions are
+/// handled by bounded recomputation: DBSP aggregation operators invoke the
+/// aggregator on the consolidated contents of each group affected by an
+/// update, and the sketch is rebuilt from values whose total weight is
+/// positive.  The rolling aggregate operator only recomputes the radix tree
+/// nodes on the path to modified values and combines sketches of unmodified
+/// subtrees.
+#[derive(Clone)]
+pub struct ApproxCountDistinct {
+    precision: u8,
+}
+
+impl ApproxCountDistinct {
+    /// Create an aggregator that uses sketches with `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is outside the range supported by
+    /// [`HyperLogLog::new`].
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (HyperLogLog::MIN_PRECISION..=HyperLogLog::MAX_PRECISION).contains(&precision),
+            "HyperLogLog precision must be between {} and {}, got {precision}",
+            HyperLogLog::MIN_PRECISION,
+            HyperLogLog::MAX_PRECISION
+        );
+
+        Self { precision }
+    }
+}
+
+impl Default for ApproxCountDistinct {
+    fn default() -> Self {
+        Self::new(HyperLogLog::DEFAULT_PRECISION)
+    }
+}
+
+impl<V, T, R> Aggregator<V, T, R> for ApproxCountDistinct
+where
+    V: DBData,
+    T: Timestamp,
+    R: DBWeight,
+{
+    type Accumulator = HyperLogLog;
+    type Output = u64;
+    type Semigroup = HyperLogLogSemigroup;
+
+    fn aggregate<C>(&self, cursor: &mut C) -> Option<Self::Accumulator>
+    where
+        C: Cursor<V, (), T, R>,
+    {
+        let mut sketch = HyperLogLog::new(self.precision);
+        let mut non_empty = false;
+
+        while cursor.key_valid() {
+            let weight = cursor.fold_times(R::zero(), |mut acc, _, weight| {
+                acc.add_assign_by_ref(weight);
+                acc
+            });
+
+            if !weight.is_zero() {
+                non_empty = true;
+                if weight > R::zero() {
+                    sketch.insert(cursor.key());
+                }
+            }
+
+            cursor.step_key();
+        }
+
+        non_empty.then_some(sketch)
+    }
+
+    fn finalize(&self, accumulator: Self::Accumulator) -> Self::Output {
+        accumulator.estimate()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApproxCountDistinct, HyperLogLog, HyperLogLogSemigroup};
+    use crate::{
+        algebra::Semigroup, indexed_zset, operator::Aggregator, trace::BatchReader, zset, Runtime,
+    };
+
+    fn assert_close(estimate: u64, expected: u64, rel_error: f64) {
+        let error = (estimate as f64 - expected as f64).abs() / expected as f64;
+        assert!(
+            error <= rel_error,
+            "estimate {estimate} is too far from {expected}"
+        );
+    }
+
+    #[test]
+    fn hll_estimate() {
+        assert_eq!(HyperLogLog::new(12).estimate(), 0);
+
+        for n in [10u64, 1_000, 100_000] {
+            let mut sketch = HyperLogLog::new(12);
+            for i in 0..n {
+                sketch.insert(&i);
+                // Duplicates must not affect the estimate.
+                sketch.insert(&i);
+            }
+            assert_close(sketch.estimate(), n, 0.05);
+        }
+    }
+
+    #[test]
+    fn hll_merge() {
+        let mut left = HyperLogLog::new(10);
+        let mut right = HyperLogLog::new(10);
+        let mut all = HyperLogLog::new(10);
+
+        for i in 0..20_000u64 {
+            if i % 3 == 0 {
+                left.insert(&i);
+            } else {
+                right.insert(&i);
+            }
+            all.insert(&i);
+        }
+
+        assert_eq!(HyperLogLogSemigroup::combine(&left, &right), all);
+        assert_eq!(HyperLogLogSemigroup::combine(&right, &left), all);
+    }
+
+    #[test]
+    fn approx_count_distinct_test() {
+        let (mut dbsp, (input_handle, output_handle)) = Runtime::init_circuit(4, |circuit| {
+            let (input, input_handle) = circuit.add_input_indexed_zset::<u64, u64, i64>();
+            let output_handle = input
+                .aggregate(ApproxCountDistinct::new(12))
+                .integrate()
+                .output();
+            Ok((input_handle, output_handle))
+        })
+        .unwrap();
+
+        // Sketches are deterministic, so we can compute expected estimates
+        // using reference sketches.
+        let estimate = |values: &[u64]| {
+            let mut sketch = HyperLogLog::new(12);
+            for v in values {
+                sketch.insert(v);
+            }
+            sketch.estimate()
+        };
+        let estimate1 = estimate(&(0..1_000).collect::<Vec<_>>());
+        let estimate2 = estimate(&(0..10).collect::<Vec<_>>());
+        assert_close(estimate1, 1_000, 0.05);
+
+        for v in 0..1_000 {
+            input_handle.push(1, (v, 1));
+            input_handle.push(2, (v % 10, 1));
+        }
+        dbsp.step().unwrap();
+
+        assert_eq!(
+            output_handle.consolidate(),
+            indexed_zset! {1 => {estimate1 => 1}, 2 => {estimate2 => 1}}
+        );
+
+        // Retract all values of key 2 but one.
+        for v in 1..10 {
+            input_handle.push(2, (v, -100));
+        }
+        dbsp.step().unwrap();
+
+        assert_eq!(
+            output_handle.consolidate(),
+            indexed_zset! {1 => {estimate1 => 1}, 2 => {estimate(&[0]) => 1}}
+        );
+
+        dbsp.kill().unwrap();
+    }
+
+    #[test]
+    fn approx_count_distinct_ignores_negative_weights() {
+        let mut cursor = zset! { 1u64 => 1i64, 2 => -1, 3 => 2 }.cursor();
+        let sketch = ApproxCountDistinct::default()
+            .aggregate(&mut cursor)
+            .unwrap();
+
+        let mut expected = HyperLogLog::new(HyperLogLog::DEFAULT_PRECISION);
+        expected.insert(&1u64);
+        expected.insert(&3u64);
+        assert_eq!(sketch, expected);
+    }
+}
@@ -0,0 +1,543 @@
+use crate::{
+    algebra::{Semigroup, F64},
+    operator::aggregate::Aggregator,
+    trace::Cursor,
+    DBData, DBWeight, Timestamp,
+};
+use num::ToPrimitive;
+use rkyv::{Archive, Deserialize, Serialize};
+use size_of::SizeOf;
+use std::{cmp::Ordering, f64::consts::PI};
+
+/// A cluster of values in a [`TDigest`], summarized by their mean and total
+/// weight.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    SizeOf,
+    Archive,
+    Serialize,
+    Deserialize,
+)]
+pub struct Centroid {
+    mean: F64,
+    weight: u64,
+}
+
+impl Centroid {
+    /// Create a centroid with the given `mean` and `weight`.
+    pub const fn new(mean: f64, weight: u64) -> Self {
+        Self {
+            mean: F64::new(mean),
+            weight,
+        }
+    }
+
+    /// Returns the mean of values in the centroid.
+    pub fn mean(&self) -> f64 {
+        self.mean.into_inner()
+    }
+
+    /// Returns the total weight of values in the centroid.
+    pub fn weight(&self) -> u64 {
+        self.weight
+    }
+
+    fn absorb(&mut self, other: &Self) {
+        let weight = self.weight + other.weight;
+        let mean = self.mean() + (other.mean() - self.mean()) * other.weight as f64 / weight as f64;
+
+        self.mean = F64::new(mean);
+        self.weight = weight;
+    }
+}
+
+/// t-digest sketch that estimates quantiles of a weighted set of values.
+///
+/// A t-digest summarizes a distribution as a sorted list of [`Centroid`]s.
+/// Centroids near the tails of the distribution are kept small, so quantile
+/// estimates are most accurate for extreme quantiles (e.g., p99).  The number
+/// of centroids is bounded by `compression`; larger values improve accuracy
+/// at the cost of memory.
+///
+/// This is the "merging digest" variant of t-digest: values are buffered and
+/// merged into the centroid list in batches, and two digests are combined
+/// with [`merge`](`Self::merge`) by merging their centroid lists.  Unlike
+/// [`HyperLogLog`](`crate::operator::HyperLogLog`), merging is only
+/// approximately associative and commutative: combining the same set of
+/// digests in a different order can produce slightly different centroids,
+/// within the error bounds of the sketch.
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, SizeOf, Archive, Serialize, Deserialize,
+)]
+pub struct TDigest {
+    compression: u32,
+    centroids: Vec<Centroid>,
+    count: u64,
+    min: F64,
+    max: F64,
+}
+
+impl TDigest {
+    /// Default compression.
+    pub const DEFAULT_COMPRESSION: u32 = 100;
+
+    /// Create an empty digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `compression` is zero.
+    pub fn new(compression: u32) -> Self {
+        assert!(compression > 0, "t-digest compression must be positive");
+
+        Self {
+            compression,
+            centroids: Vec::new(),
+            count: 0,
+            min: F64::new(f64::INFINITY),
+            max: F64::new(f64::NEG_INFINITY),
+        }
+    }
+
+    /// Returns the compression parameter of the digest.
+    pub fn compression(&self) -> u32 {
+        self.compression
+    }
+
+    /// Returns `true` if the digest does not contain any values.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the total weight of values in the digest.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the smallest value in the digest or `None` if it is empty.
+    pub fn min(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.min.into_inner())
+    }
+
+    /// Returns the largest value in the digest or `None` if it is empty.
+    pub fn max(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.max.into_inner())
+    }
+
+    /// Returns centroids of the digest ordered by their means.
+    pub fn centroids(&self) -> &[Centroid] {
+        &self.centroids
+    }
+
+    /// Insert `(value, weight)` pairs in the digest.
+    ///
+    /// NaN values and values with zero weights are ignored.
+    pub fn extend<I>(&mut self, values: I)
+    where
+        I: IntoIterator<Item = (f64, u64)>,
+    {
+        let buffer_size = self.buffer_size();
+        let mut buffer = Vec::with_capacity(buffer_size);
+
+        for (value, weight) in values {
+            if value.is_nan() || weight == 0 {
+                continue;
+            }
+
+            self.min = self.min.min(F64::new(value));
+            self.max = self.max.max(F64::new(value));
+            buffer.push(Centroid::new(value, weight));
+
+            if buffer.len() == buffer_size {
+                self.merge_centroids(&mut buffer);
+            }
+        }
+
+        self.merge_centroids(&mut buffer);
+    }
+
+    /// Merge `other` into `self`.
+    pub fn merge(&mut self, other: &Self) {
+        if other.is_empty() {
+            return;
+        }
+
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.merge_centroids(&mut other.centroids.clone());
+    }
+
+    /// Estimate the `q`-quantile of the values in the digest, where `q` is in
+    /// the range `[0, 1]`.
+    ///
+    /// Returns `None` if the digest is empty or `q` is NaN.  Other values of
+    /// `q` are clamped to `[0, 1]`.
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        if self.is_empty() || q.is_nan() {
+            return None;
+        }
+
+        let min = self.min.into_inner();
+        let max = self.max.into_inner();
+        let q = q.clamp(0.0, 1.0);
+
+        if self.centroids.len() == 1 || min == max {
+            return Some(self.centroids[0].mean().clamp(min, max));
+        }
+
+        // Each centroid is assumed to be centered at its mean, with half of
+        // its weight on either side.  Interpolate linearly between the
+        // centers of adjacent centroids, and between the extreme centroids
+        // and `min`/`max`.
+        let target = q * self.count as f64;
+        if target <= 0.0 {
+            return Some(min);
+        } else if target >= self.count as f64 {
+            return Some(max);
+        }
+
+        let first = &self.centroids[0];
+        let first_center = first.weight as f64 / 2.0;
+        if target < first_center {
+            return Some(interpolate(0.0, min, first_center, first.mean(), target));
+        }
+
+        let mut cumulative = 0.0;
+        for pair in self.centroids.windows(2) {
+            let left_center = cumulative + pair[0].weight as f64 / 2.0;
+            let right_center = cumulative + pair[0].weight as f64 + pair[1].weight as f64 / 2.0;
+
+            if target < right_center {
+                return Some(interpolate(
+                    left_center,
+                    pair[0].mean(),
+                    right_center,
+                    pair[1].mean(),
+                    target,
+                ));
+            }
+
+            cumulative += pair[0].weight as f64;
+        }
+
+        let last = self.centroids.last().unwrap();
+        let last_center = self.count as f64 - last.weight as f64 / 2.0;
+        Some(interpolate(
+            last_center,
+            last.mean(),
+            self.count as f64,
+            max,
+            target,
+        ))
+    }
+
+    /// Number of values to buffer before merging them into centroids.
+    fn buffer_size(&self) -> usize {
+        (self.compression as usize).saturating_mul(8).max(32)
+    }
+
+    /// Merge a sorted or unsorted list of centroids into the digest, leaving
+    /// `centroids` empty.
+    fn merge_centroids(&mut self, centroids: &mut Vec<Centroid>) {
+        if centroids.is_empty() {
+            return;
+        }
+
+        centroids.append(&mut self.centroids);
+        centroids.sort_unstable_by(|c1, c2| c1.mean.cmp(&c2.mean).then(c1.weight.cmp(&c2.weight)));
+
+        self.count = centroids.iter().map(|c| c.weight).sum();
+        let total = self.count as f64;
+        let compression = self.compression as f64;
+
+        // Scale function `k(q) = compression / (2 * PI) * asin(2q - 1)`.  A
+        // centroid can only span a range of quantiles `[q0, q1]` such that
+        // `k(q1) - k(q0) <= 1`.
+        let q_limit = |q0: f64| -> f64 {
+            let k = compression / (2.0 * PI) * (2.0 * q0.clamp(0.0, 1.0) - 1.0).asin() + 1.0;
+            if k >= compression / 4.0 {
+                1.0
+            } else {
+                ((2.0 * PI * k / compression).sin() + 1.0) / 2.0
+            }
+        };
+
+        let mut result = Vec::with_capacity(self.compression as usize);
+        let mut iter = centroids.drain(..);
+        let mut current = iter.next().unwrap();
+        let mut weight_so_far = 0.0;
+        let mut weight_limit = total * q_limit(0.0);
+
+        for centroid in iter {
+            let proposed_weight = weight_so_far + (current.weight + centroid.weight) as f64;
+
+            if proposed_weight <= weight_limit {
+                current.absorb(&centroid);
+            } else {
+                weight_so_far += current.weight as f64;
+                weight_limit = total * q_limit(weight_so_far / total);
+                result.push(current);
+                current = centroid;
+            }
+        }
+
+        result.push(current);
+        self.centroids = result;
+    }
+}
+
+fn interpolate(x0: f64, y0: f64, x1: f64, y1: f64, x: f64) -> f64 {
+    match x0.partial_cmp(&x1) {
+        Some(Ordering::Less) => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+        _ => y0,
+    }
+}
+
+/// Semigroup structure over [`TDigest`] sketches that merges digests.
+#[derive(Clone)]
+pub struct TDigestSemigroup;
+
+impl Semigroup<TDigest> for TDigestSemigroup {
+    fn combine(left: &TDigest, right: &TDigest) -> TDigest {
+        let mut result = left.clone();
+        result.merge(right);
+        result
+    }
+}
+
+/// An [aggregator](`crate::operator::Aggregator`) that estimates a percentile
+/// of values with positive weights using a [`TDigest`] sketch.
+///
+/// `value_func` maps each value in the input Z-set to a floating point
+/// number.  Each value contributes to the distribution with its weight, e.g.,
+/// a value with weight 3 is counted three times.  The aggregator outputs
+/// `None` if no value has a positive weight.
+///
+/// The accumulator of this aggregator is a digest with a bounded number of
+/// centroids, and digests computed over different subsets of the input are
+/// combined using [`TDigestSemigroup`], so the aggregator can be used with
+/// [`Stream::partitioned_rolling_aggregate`](`crate::Stream::partitioned_rolling_aggregate`).
+///
+/// # Retractions
+///
+/// t-digest does not support deletion.  Instead, retractions are handled by
+/// bounded recomputation: DBSP aggregation operators invoke the aggregator on
+/// the consolidated contents of each group affected by an update, and the
+/// digest is rebuilt from values whose total weight is positive.  The rolling
+/// aggregate operator only recomputes the radix tree nodes on the path to
+/// modified values and combines digests of unmodified subtrees.
+///
+/// # Example
+///
+/// ```
+/// use dbsp::{operator::ApproxPercentile, Runtime};
+///
+/// let (_dbsp, (_input_handle, _median_handle)) = Runtime::init_circuit(2, |circuit| {
+///     let (input, input_handle) = circuit.add_input_indexed_zset::<u64, i64, i64>();
+///
+///     // Median latency per endpoint.
+///     let median = input
+///         .aggregate(ApproxPercentile::new(0.5, |latency: &i64| *latency as f64))
+///         .integrate()
+///         .output();
+///     Ok((input_handle, median))
+/// })
+/// .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ApproxPercentile<F> {
+    percentile: f64,
+    compression: u32,
+    value_func: F,
+}
+
+impl<F> ApproxPercentile<F> {
+    /// Create an aggregator that estimates the `percentile`-quantile, where
+    /// `percentile` is in the range `[0, 1]`, using digests with default
+    /// compression.
+    pub fn new(percentile: f64, value_func: F) -> Self {
+        Self::with_compression(percentile, TDigest::DEFAULT_COMPRESSION, value_func)
+    }
+
+    /// Create an aggregator that estimates the `percentile`-quantile using
+    /// digests with the specified `compression`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `percentile` is not in the range `[0, 1]` or `compression` is
+    /// zero.
+    pub fn with_compression(percentile: f64, compression: u32, value_func: F) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&percentile),
+            "percentile must be in the range [0, 1], got {percentile}"
+        );
+        assert!(compression > 0, "t-digest compression must be positive");
+
+        Self {
+            percentile,
+            compression,
+            value_func,
+        }
+    }
+}
+
+impl<V, T, R, F> Aggregator<V, T, R> for ApproxPercentile<F>
+where
+    V: DBData,
+    T: Timestamp,
+    R: DBWeight + ToPrimitive,
+    F: Fn(&V) -> f64 + Clone + 'static,
+{
+    type Accumulator = TDigest;
+    type Output = Option<F64>;
+    type Semigroup = TDigestSemigroup;
+
+    fn aggregate<C>(&self, cursor: &mut C) -> Option<Self::Accumulator>
+    where
+        C: Cursor<V, (), T, R>,
+    {
+        let mut digest = TDigest::new(self.compression);
+        let mut values = Vec::new();
+        let mut non_empty = false;
+
+        while cursor.key_valid() {
+            let weight = cursor.fold_times(R::zero(), |mut acc, _, weight| {
+                acc.add_assign_by_ref(weight);
+                acc
+            });
+
+            if !weight.is_zero() {
+                non_empty = true;
+                if weight > R::zero() {
+                    let weight = weight.to_u64().unwrap_or(u64::MAX);
+                    values.push(((self.value_func)(cursor.key()), weight));
+                }
+            }
+
+            cursor.step_key();
+        }
+
+        digest.extend(values);
+        non_empty.then_some(digest)
+    }
+
+    fn finalize(&self, accumulator: Self::Accumulator) -> Self::Output {
+        accumulator.quantile(self.percentile).map(F64::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApproxPercentile, TDigest, TDigestSemigroup};
+    use crate::{
+        algebra::{Semigroup, F64},
+        indexed_zset, Runtime,
+    };
+
+    fn assert_quantile(digest: &TDigest, q: f64, expected: f64, error: f64) {
+        let estimate = digest.quantile(q).unwrap();
+        assert!(
+            (estimate - expected).abs() <= error,
+            "estimated {q}-quantile {estimate} is too far from {expected}"
+        );
+    }
+
+    #[test]
+    fn tdigest_quantiles() {
+        assert_eq!(TDigest::new(100).quantile(0.5), None);
+
+        let mut digest = TDigest::new(100);
+        digest.extend((0..100_000).map(|v| (v as f64, 1)));
+
+        assert_eq!(digest.count(), 100_000);
+        assert_eq!(digest.min(), Some(0.0));
+        assert_eq!(digest.max(), Some(99_999.0));
+
+        assert_eq!(digest.quantile(0.0), Some(0.0));
+        assert_eq!(digest.quantile(1.0), Some(99_999.0));
+        for q in [0.01, 0.1, 0.25, 0.5, 0.75, 0.9, 0.99] {
+            assert_quantile(&digest, q, q * 100_000.0, 1_000.0);
+        }
+    }
+
+    #[test]
+    fn tdigest_weights() {
+        // 1 with weight 9, 100 with weight 1.
+        let mut digest = TDigest::new(100);
+        digest.extend([(1.0, 9), (100.0, 1)]);
+
+        assert_eq!(digest.quantile(0.0), Some(1.0));
+        assert_eq!(digest.quantile(0.3), Some(1.0));
+        assert_eq!(digest.quantile(1.0), Some(100.0));
+    }
+
+    #[test]
+    fn tdigest_merge() {
+        let mut left = TDigest::new(100);
+        let mut right = TDigest::new(100);
+
+        left.extend((0..50_000).map(|v| (v as f64, 1)));
+        right.extend((50_000..100_000).map(|v| (v as f64, 1)));
+
+        let digest = TDigestSemigroup::combine(&left, &right);
+
+        assert_eq!(digest.count(), 100_000);
+        for q in [0.01, 0.25, 0.5, 0.75, 0.99] {
+            assert_quantile(&digest, q, q * 100_000.0, 1_000.0);
+        }
+    }
+
+    #[test]
+    fn approx_percentile_test() {
+        let (mut dbsp, (input_handle, output_handle)) = Runtime::init_circuit(4, |circuit| {
+            let (input, input_handle) = circuit.add_input_indexed_zset::<u64, i64, i64>();
+            let output_handle = input
+                .aggregate(ApproxPercentile::new(0.5, |v: &i64| *v as f64))
+                .integrate()
+                .output();
+            Ok((input_handle, output_handle))
+        })
+        .unwrap();
+
+        input_handle.push(1, (10, 1));
+        input_handle.push(1, (20, 1));
+        input_handle.push(1, (30, 1));
+        input_handle.push(2, (5, 3));
+        dbsp.step().unwrap();
+
+        assert_eq!(
+            output_handle.consolidate(),
+            indexed_zset! {1 => {Some(F64::new(20.0)) => 1}, 2 => {Some(F64::new(5.0)) => 1}}
+        );
+
+        // Retract the smallest value of key 1; retract key 2 partially and
+        // add a negative weight that must be ignored.
+        input_handle.push(1, (10, -1));
+        input_handle.push(1, (0, -1));
+        input_handle.push(2, (5, -2));
+        dbsp.step().unwrap();
+
+        assert_eq!(
+            output_handle.consolidate(),
+            indexed_zset! {1 => {Some(F64::new(25.0)) => 1}, 2 => {Some(F64::new(5.0)) => 1}}
+        );
+
+        // A group whose values all have negative weights has no percentile.
+        input_handle.push(2, (5, -2));
+        dbsp.step().unwrap();
+
+        assert_eq!(
+            output_handle.consolidate(),
+            indexed_zset! {1 => {Some(F64::new(25.0)) => 1}, 2 => {None => 1}}
+        );
+
+        dbsp.kill().unwrap();
+    }
+}
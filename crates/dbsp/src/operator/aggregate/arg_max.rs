@@ -0,0 +1,80 @@
+use crate::{
+    algebra::MonoidValue,
+    operator::aggregate::{Aggregator, MaxSemigroup},
+    trace::Cursor,
+    DBData, Timestamp,
+};
+
+/// An [aggregator](`crate::operator::Aggregator`) that returns the value
+/// with non-zero weight for which `key_func` returns the largest key.
+///
+/// Ties are broken by picking the largest value among values with the
+/// largest key.
+///
+/// This aggregator scans all values in the group.  See
+/// [`Stream::aggregate_indexed`](`crate::Stream::aggregate_indexed`) for an
+/// implementation that avoids rescanning large groups.
+#[derive(Clone)]
+pub struct ArgMax<F> {
+    key_func: F,
+}
+
+impl<F> ArgMax<F> {
+    /// Create an `ArgMax` aggregator that orders values by `key_func`.
+    pub fn new(key_func: F) -> Self {
+        Self { key_func }
+    }
+
+    pub(super) fn key<V, K>(&self, value: &V) -> K
+    where
+        F: Fn(&V) -> K,
+    {
+        (self.key_func)(value)
+    }
+}
+
+impl<V, T, R, K, F> Aggregator<V, T, R> for ArgMax<F>
+where
+    V: DBData,
+    T: Timestamp,
+    R: MonoidValue,
+    K: DBData,
+    F: Fn(&V) -> K + Clone + 'static,
+{
+    type Accumulator = (K, V);
+    type Output = V;
+    type Semigroup = MaxSemigroup<(K, V)>;
+
+    fn aggregate<C>(&self, cursor: &mut C) -> Option<Self::Accumulator>
+    where
+        C: Cursor<V, (), T, R>,
+    {
+        let mut result: Option<(K, V)> = None;
+
+        cursor.fast_forward_keys();
+
+        while cursor.key_valid() {
+            let mut weight = R::zero();
+
+            cursor.map_times(|_t, w| weight.add_assign_by_ref(w));
+
+            if !weight.is_zero() {
+                let key = (self.key_func)(cursor.key());
+
+                // Values are visited in descending order, so the first value
+                // with the largest key wins ties.
+                if result.as_ref().map_or(true, |(max_key, _)| &key > max_key) {
+                    result = Some((key, cursor.key().clone()));
+                }
+            }
+
+            cursor.step_key_reverse();
+        }
+
+        result
+    }
+
+    fn finalize(&self, accumulator: Self::Accumulator) -> Self::Output {
+        accumulator.1
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{
+    algebra::MonoidValue,
+    operator::aggregate::{Aggregator, MinSemigroup},
+    trace::Cursor,
+    DBData, Timestamp,
+};
+
+/// An [aggregator](`crate::operator::Aggregator`) that returns the value
+/// with non-zero weight for which `key_func` returns the smallest key.
+///
+/// Ties are broken by picking the smallest value among values with the
+/// smallest key.
+///
+/// This aggregator scans all values in the group.  See
+/// [`Stream::aggregate_indexed`](`crate::Stream::aggregate_indexed`) for an
+/// implementation that avoids rescanning large groups.
+#[derive(Clone)]
+pub struct ArgMin<F> {
+    key_func: F,
+}
+
+impl<F> ArgMin<F> {
+    /// Create an `ArgMin` aggregator that orders values by `key_func`.
+    pub fn new(key_func: F) -> Self {
+        Self { key_func }
+    }
+
+    pub(super) fn key<V, K>(&self, value: &V) -> K
+    where
+        F: Fn(&V) -> K,
+    {
+        (self.key_func)(value)
+    }
+}
+
+impl<V, T, R, K, F> Aggregator<V, T, R> for ArgMin<F>
+where
+    V: DBData,
+    T: Timestamp,
+    R: MonoidValue,
+    K: DBData,
+    F: Fn(&V) -> K + Clone + 'static,
+{
+    type Accumulator = (K, V);
+    type Output = V;
+    type Semigroup = MinSemigroup<(K, V)>;
+
+    fn aggregate<C>(&self, cursor: &mut C) -> Option<Self::Accumulator>
+    where
+        C: Cursor<V, (), T, R>,
+    {
+        let mut result: Option<(K, V)> = None;
+
+        while cursor.key_valid() {
+            let mut weight = R::zero();
+
+            cursor.map_times(|_t, w| weight.add_assign_by_ref(w));
+
+            if !weight.is_zero() {
+                let key = (self.key_func)(cursor.key());
+
+                // Values are visited in ascending order, so the first value
+                // with the smallest key wins ties.
+                if result.as_ref().map_or(true, |(min_key, _)| &key < min_key) {
+                    result = Some((key, cursor.key().clone()));
+                }
+            }
+
+            cursor.step_key();
+        }
+
+        result
+    }
+
+    fn finalize(&self, accumulator: Self::Accumulator) -> Self::Output {
+        accumulator.1
+    }
+}
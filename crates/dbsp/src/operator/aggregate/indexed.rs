@@ -0,0 +1,367 @@
+use crate::{
+    algebra::{AddAssignByRef, HasZero, IndexedZSet, ZRingValue},
+    circuit::{
+        operator_traits::{Operator, UnaryOperator},
+        Scope,
+    },
+    operator::aggregate::{ArgMax, ArgMin, Max, Min},
+    trace::{Batch, BatchReader, Cursor},
+    Circuit, DBData, OrdIndexedZSet, RootCircuit, Stream,
+};
+use std::{
+    borrow::Cow,
+    collections::{btree_map::Entry, BTreeMap},
+    marker::PhantomData,
+};
+
+/// An aggregator that selects the smallest or the largest value in a group
+/// according to an ordering key, and can be evaluated by
+/// [`Stream::aggregate_indexed`].
+///
+/// Values are ordered by `(key(value), value)` tuples, i.e., ties between
+/// values with the same key are broken by the ordering of values.
+///
+/// [`Min`], [`Max`], [`ArgMin`], and [`ArgMax`] implement this trait.
+pub trait IndexedAggregator<V>: Clone + 'static {
+    /// Key used to order values in the group.
+    type Key: DBData;
+
+    /// `true` if the aggregator selects the largest value, `false` if it
+    /// selects the smallest one.
+    const LARGEST: bool;
+
+    /// Compute the ordering key of `value`.
+    fn key(&self, value: &V) -> Self::Key;
+}
+
+impl<V> IndexedAggregator<V> for Min
+where
+    V: DBData,
+{
+    type Key = ();
+    const LARGEST: bool = false;
+
+    fn key(&self, _value: &V) -> Self::Key {}
+}
+
+impl<V> IndexedAggregator<V> for Max
+where
+    V: DBData,
+{
+    type Key = ();
+    const LARGEST: bool = true;
+
+    fn key(&self, _value: &V) -> Self::Key {}
+}
+
+impl<V, K, F> IndexedAggregator<V> for ArgMin<F>
+where
+    V: DBData,
+    K: DBData,
+    F: Fn(&V) -> K + Clone + 'static,
+{
+    type Key = K;
+    const LARGEST: bool = false;
+
+    fn key(&self, value: &V) -> Self::Key {
+        ArgMin::key(self, value)
+    }
+}
+
+impl<V, K, F> IndexedAggregator<V> for ArgMax<F>
+where
+    V: DBData,
+    K: DBData,
+    F: Fn(&V) -> K + Clone + 'static,
+{
+    type Key = K;
+    const LARGEST: bool = true;
+
+    fn key(&self, value: &V) -> Self::Key {
+        ArgMax::key(self, value)
+    }
+}
+
+impl<Z> Stream<RootCircuit, Z>
+where
+    Z: IndexedZSet + Send,
+{
+    /// Incrementally compute a min/max-style aggregate of each group using
+    /// an ordered index.
+    ///
+    /// Computes the same output as [`Stream::aggregate`] for [`Min`], [`Max`],
+    /// [`ArgMin`], and [`ArgMax`] aggregators, but instead of rescanning the
+    /// input trace of every modified group, the operator maintains an ordered
+    /// index of values with non-zero weights for each group.  Each input
+    /// update costs `O(log n)`, where `n` is the size of the group, even when
+    /// it deletes the current minimum or maximum.
+    ///
+    /// The index stores a copy of every value in the input collection, so
+    /// this operator trades memory for update cost.  It is most useful for
+    /// large groups with frequent deletions of the current extremum;
+    /// otherwise, [`Stream::aggregate`] is usually cheaper.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dbsp::{operator::ArgMax, Runtime};
+    ///
+    /// let (_dbsp, (_input_handle, _output_handle)) = Runtime::init_circuit(2, |circuit| {
+    ///     // (user, (score, game)) tuples.
+    ///     let (input, input_handle) = circuit.add_input_indexed_zset::<u64, (i64, u64), i64>();
+    ///
+    ///     // Best game of each user.
+    ///     let best_game = input
+    ///         .aggregate_indexed(ArgMax::new(|(score, _game): &(i64, u64)| *score))
+    ///         .integrate()
+    ///         .output();
+    ///     Ok((input_handle, best_game))
+    /// })
+    /// .unwrap();
+    /// ```
+    #[allow(clippy::type_complexity)]
+    pub fn aggregate_indexed<A>(
+        &self,
+        aggregator: A,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<Z::Key, Z::Val, Z::R>>
+    where
+        A: IndexedAggregator<Z::Val>,
+        Z::R: ZRingValue,
+    {
+        self.aggregate_indexed_generic(aggregator)
+    }
+
+    /// Like [`Self::aggregate_indexed`], but can return any batch type.
+    pub fn aggregate_indexed_generic<A, O>(&self, aggregator: A) -> Stream<RootCircuit, O>
+    where
+        A: IndexedAggregator<Z::Val>,
+        O: Batch<Key = Z::Key, Val = Z::Val, Time = ()>,
+        O::R: ZRingValue,
+    {
+        // We construct the following circuit:
+        //
+        // ```
+        //  stream  ┌─────┐     ┌────────────────┐     ┌──────┐
+        // ────────►│shard├────►│IndexedAggregate├────►│upsert├──────►
+        //          └─────┘     └────────────────┘     └──────┘
+        // ```
+        self.circuit()
+            .add_unary_operator(IndexedAggregate::new(aggregator), &self.shard())
+            .upsert::<O>()
+            .mark_sharded()
+    }
+}
+
+/// Incremental min/max aggregation operator that maintains an ordered index
+/// of values for each group.
+///
+/// The operator outputs an upsert for every key whose aggregate has changed,
+/// including `None` for keys whose groups became empty.
+struct IndexedAggregate<Z, A>
+where
+    Z: IndexedZSet,
+    A: IndexedAggregator<Z::Val>,
+{
+    aggregator: A,
+    // Values with non-zero weights in each group, ordered by
+    // `(aggregator.key(value), value)`.
+    groups: BTreeMap<Z::Key, BTreeMap<(A::Key, Z::Val), Z::R>>,
+    _type: PhantomData<Z>,
+}
+
+impl<Z, A> IndexedAggregate<Z, A>
+where
+    Z: IndexedZSet,
+    A: IndexedAggregator<Z::Val>,
+{
+    fn new(aggregator: A) -> Self {
+        Self {
+            aggregator,
+            groups: BTreeMap::new(),
+            _type: PhantomData,
+        }
+    }
+
+    /// Returns the value selected by the aggregator in `group`.
+    fn select(group: &BTreeMap<(A::Key, Z::Val), Z::R>) -> Option<&Z::Val> {
+        let entry = if A::LARGEST {
+            group.keys().next_back()
+        } else {
+            group.keys().next()
+        };
+
+        entry.map(|(_, value)| value)
+    }
+}
+
+impl<Z, A> Operator for IndexedAggregate<Z, A>
+where
+    Z: IndexedZSet,
+    A: IndexedAggregator<Z::Val>,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from("IndexedAggregate")
+    }
+
+    fn fixedpoint(&self, _scope: Scope) -> bool {
+        true
+    }
+}
+
+impl<Z, A> UnaryOperator<Z, Vec<(Z::Key, Option<Z::Val>)>> for IndexedAggregate<Z, A>
+where
+    Z: IndexedZSet,
+    A: IndexedAggregator<Z::Val>,
+{
+    fn eval(&mut self, delta: &Z) -> Vec<(Z::Key, Option<Z::Val>)> {
+        let mut result = Vec::with_capacity(delta.key_count());
+        let mut cursor = delta.cursor();
+
+        while cursor.key_valid() {
+            let group = self.groups.entry(cursor.key().clone()).or_default();
+            let old = Self::select(group).cloned();
+
+            while cursor.val_valid() {
+                let value = cursor.val().clone();
+                let weight = cursor.weight();
+
+                match group.entry((self.aggregator.key(&value), value)) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(weight);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        entry.get_mut().add_assign_by_ref(&weight);
+                        if entry.get().is_zero() {
+                            entry.remove();
+                        }
+                    }
+                }
+
+                cursor.step_val();
+            }
+
+            let new = Self::select(group).cloned();
+
+            if group.is_empty() {
+                self.groups.remove(cursor.key());
+            }
+
+            if new != old {
+                result.push((cursor.key().clone(), new));
+            }
+
+            cursor.step_key();
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        indexed_zset,
+        operator::{ArgMax, ArgMin, Max, Min},
+        trace::test_batch::assert_batch_eq,
+        CollectionHandle, OrdIndexedZSet, OutputHandle, RootCircuit, Runtime,
+    };
+    use anyhow::Result as AnyResult;
+    use proptest::{collection::vec, prelude::*};
+
+    type Outputs = Vec<(
+        OutputHandle<OrdIndexedZSet<i32, i32, i32>>,
+        OutputHandle<OrdIndexedZSet<i32, i32, i32>>,
+    )>;
+
+    // Returns pairs of output handles of `aggregate` and `aggregate_indexed`
+    // for the same aggregator.
+    fn test_circuit(
+        circuit: &mut RootCircuit,
+    ) -> AnyResult<(CollectionHandle<i32, (i32, i32)>, Outputs)> {
+        let (input, input_handle) = circuit.add_input_indexed_zset::<i32, i32, i32>();
+
+        let key_func = |v: &i32| v % 7;
+
+        let outputs = vec![
+            (
+                input.aggregate(Min).integrate().output(),
+                input.aggregate_indexed(Min).integrate().output(),
+            ),
+            (
+                input.aggregate(Max).integrate().output(),
+                input.aggregate_indexed(Max).integrate().output(),
+            ),
+            (
+                input.aggregate(ArgMin::new(key_func)).integrate().output(),
+                input
+                    .aggregate_indexed(ArgMin::new(key_func))
+                    .integrate()
+                    .output(),
+            ),
+            (
+                input.aggregate(ArgMax::new(key_func)).integrate().output(),
+                input
+                    .aggregate_indexed(ArgMax::new(key_func))
+                    .integrate()
+                    .output(),
+            ),
+        ];
+
+        Ok((input_handle, outputs))
+    }
+
+    #[test]
+    fn aggregate_indexed_test() {
+        let (mut dbsp, (input_handle, outputs)) = Runtime::init_circuit(4, test_circuit).unwrap();
+
+        input_handle.push(1, (10, 1));
+        input_handle.push(1, (15, 1));
+        input_handle.push(1, (20, 1));
+        input_handle.push(2, (5, 1));
+        dbsp.step().unwrap();
+
+        let expected = [
+            indexed_zset! {1 => {10 => 1}, 2 => {5 => 1}},
+            indexed_zset! {1 => {20 => 1}, 2 => {5 => 1}},
+            // 15 % 7 == 1 is the smallest key.
+            indexed_zset! {1 => {15 => 1}, 2 => {5 => 1}},
+            // 20 % 7 == 6 is the largest key.
+            indexed_zset! {1 => {20 => 1}, 2 => {5 => 1}},
+        ];
+        for ((_, indexed), expected) in outputs.iter().zip(expected.iter()) {
+            assert_eq!(&indexed.consolidate(), expected);
+        }
+
+        // Delete current extrema and the only value of key 2.
+        input_handle.push(1, (10, -1));
+        input_handle.push(1, (20, -1));
+        input_handle.push(2, (5, -1));
+        dbsp.step().unwrap();
+
+        for (_, indexed) in outputs.iter() {
+            assert_eq!(indexed.consolidate(), indexed_zset! {1 => {15 => 1}});
+        }
+
+        dbsp.kill().unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_aggregate_indexed(trace in vec(vec((0..5i32, 0..100i32, -1..2i32), 0..100), 0..20)) {
+            let (mut dbsp, (input_handle, outputs)) = Runtime::init_circuit(4, test_circuit).unwrap();
+
+            for batch in trace.into_iter() {
+                for (k, v, r) in batch.into_iter() {
+                    input_handle.push(k, (v, r));
+                }
+                dbsp.step().unwrap();
+
+                for (scan, indexed) in outputs.iter() {
+                    assert_batch_eq(&indexed.consolidate(), &scan.consolidate());
+                }
+            }
+
+            dbsp.kill().unwrap();
+        }
+    }
+}
@@ -14,6 +14,7 @@ use crate::{
         PartialOrder, Semigroup, ZRingValue,
     },
     circuit::{
+        metadata::RecordCounts,
         operator_traits::{BinaryOperator, Operator, UnaryOperator},
         Circuit, Scope, Stream, WithClock,
     },
@@ -26,13 +27,23 @@ use crate::{
 };
 
 // Some standard aggregators.
+mod approx_count_distinct;
+mod approx_percentile;
+mod arg_max;
+mod arg_min;
 mod average;
 mod fold;
+mod indexed;
 mod max;
 mod min;
 
+pub use approx_count_distinct::{ApproxCountDistinct, HyperLogLog, HyperLogLogSemigroup};
+pub use approx_percentile::{ApproxPercentile, Centroid, TDigest, TDigestSemigroup};
+pub use arg_max::ArgMax;
+pub use arg_min::ArgMin;
 pub use average::Avg;
 pub use fold::Fold;
+pub use indexed::IndexedAggregator;
 pub use max::{Max, MaxSemigroup};
 pub use min::{Min, MinSemigroup};
 
@@ -236,6 +247,9 @@ where
     /// key in the input.
     ///
     /// [`Min`], [`Max`], and [`Fold`] are provided as example `Aggregator`s.
+    ///
+    /// See [`Stream::aggregate_indexed`] for an alternative implementation of
+    /// min/max-style aggregates that avoids rescanning modified groups.
     #[allow(clippy::type_complexity)]
     pub fn aggregate<A>(&self, aggregator: A) -> Stream<C, OrdIndexedZSet<Z::Key, A::Output, Z::R>>
     where
@@ -370,6 +384,7 @@ where
 /// Non-incremental aggregation operator.
 struct Aggregate<Z, A, O> {
     aggregator: A,
+    records: RecordCounts,
     _type: PhantomData<(Z, O)>,
 }
 
@@ -377,6 +392,7 @@ impl<Z, A, O> Aggregate<Z, A, O> {
     pub fn new(aggregator: A) -> Self {
         Self {
             aggregator,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -391,6 +407,9 @@ where
     fn name(&self) -> Cow<'static, str> {
         Cow::from("Aggregate")
     }
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -416,7 +435,9 @@ where
             }
             cursor.step_key();
         }
-        builder.done()
+        let output = builder.done();
+        self.records.add(i.len(), output.len());
+        output
     }
 }
 
@@ -463,6 +484,7 @@ where
     empty_output: bool,
     // Keys that may need updating at future times.
     keys_of_interest: BTreeMap<IT::Time, BTreeSet<IT::Key>>,
+    records: RecordCounts,
     // Buffer used in computing per-key outputs.
     // Keep it here to reuse allocation across multiple operations.
     _type: PhantomData<(Z, IT)>,
@@ -482,6 +504,7 @@ where
             empty_input: false,
             empty_output: false,
             keys_of_interest: BTreeMap::new(),
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -602,6 +625,12 @@ where
         Cow::from("AggregateIncremental")
     }
 
+    // Counts the changes in the first input, not the trace, and the updated
+    // keys in the output.
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn clock_start(&mut self, scope: Scope) {
         if scope == 0 {
             self.empty_input = false;
@@ -724,6 +753,7 @@ where
         }
 
         self.empty_output = result.is_empty();
+        self.records.add(delta.len(), result.len());
         result
     }
 }
@@ -0,0 +1,579 @@
+//! ASOF join operator.
+
+use crate::{
+    algebra::{ZRingValue, ZSet},
+    circuit::{
+        operator_traits::{Operator, QuaternaryOperator},
+        Scope,
+    },
+    operator::time_series::{PartitionedBatchReader, PartitionedIndexedZSet},
+    trace::{cursor::CursorPair, BatchReader, Cursor},
+    DBData, OrdZSet, RootCircuit, Stream,
+};
+use std::{borrow::Cow, marker::PhantomData};
+
+/// Half-open range of timestamps `[from, to)`.  `to = None` denotes a range
+/// that extends to infinity.
+type TsRange<TS> = (TS, Option<TS>);
+
+impl<B> Stream<RootCircuit, B> {
+    /// ASOF join of two partitioned time series.
+    ///
+    /// Both input streams contain records of the form `(p, (ts, v))`, where
+    /// `p` is the partition key and `ts` is the timestamp of the record
+    /// within the partition.  For each record `(p, (ts1, v1))` in `self`,
+    /// finds the record `(p, (ts2, v2))` in `other` with the largest
+    /// timestamp `ts2 <= ts1` in the same partition, and outputs
+    /// `join_func(p, (ts1, v1), (ts2, v2))`.  Records in `self` that don't
+    /// have such a match produce no output (see
+    /// [`asof_left_join`](`Self::asof_left_join`) for the left outer
+    /// version of this operator).
+    ///
+    /// When multiple records in `other` share the largest timestamp, the
+    /// one with the largest value is selected.  Records in `other` with
+    /// non-positive weights are ignored.  Each output record has the weight
+    /// of the matching record in `self`.
+    ///
+    /// This operator is incremental: inserting or deleting a record with
+    /// timestamp `ts` in `other` only affects records in `self` with
+    /// timestamps in the range between `ts` and the next timestamp in the
+    /// same partition of `other`.  The operator retracts previous outputs
+    /// for these records and reissues them with updated matches.
+    ///
+    /// # Example
+    ///
+    /// Match each trade with the latest quote at or before its timestamp:
+    ///
+    /// ```text
+    /// trades: (symbol, (ts, price))   quotes: (symbol, (ts, bid))
+    ///
+    /// trades.asof_join(&quotes, |symbol, (ts, price), (_quote_ts, bid)| {
+    ///     (symbol.clone(), *ts, *price, *bid)
+    /// })
+    /// ```
+    pub fn asof_join<TS, V1, V2, B2, F, O>(
+        &self,
+        other: &Stream<RootCircuit, B2>,
+        join_func: F,
+    ) -> Stream<RootCircuit, OrdZSet<O, B::R>>
+    where
+        B: PartitionedIndexedZSet<TS, V1>,
+        B::R: ZRingValue,
+        B2: PartitionedIndexedZSet<TS, V2, Key = B::Key, R = B::R>,
+        TS: DBData,
+        V1: DBData,
+        V2: DBData,
+        O: DBData,
+        F: Fn(&B::Key, &(TS, V1), &(TS, V2)) -> O + 'static,
+    {
+        self.asof_join_generic(other, move |k, v1, v2| v2.map(|v2| join_func(k, v1, v2)))
+    }
+
+    /// Left outer ASOF join of two partitioned time series.
+    ///
+    /// Like [`asof_join`](`Self::asof_join`), but also outputs records in
+    /// `self` that don't have a matching record in `other`.  `join_func`
+    /// receives `None` for such records.
+    pub fn asof_left_join<TS, V1, V2, B2, F, O>(
+        &self,
+        other: &Stream<RootCircuit, B2>,
+        join_func: F,
+    ) -> Stream<RootCircuit, OrdZSet<O, B::R>>
+    where
+        B: PartitionedIndexedZSet<TS, V1>,
+        B::R: ZRingValue,
+        B2: PartitionedIndexedZSet<TS, V2, Key = B::Key, R = B::R>,
+        TS: DBData,
+        V1: DBData,
+        V2: DBData,
+        O: DBData,
+        F: Fn(&B::Key, &(TS, V1), Option<&(TS, V2)>) -> O + 'static,
+    {
+        self.asof_join_generic(other, move |k, v1, v2| Some(join_func(k, v1, v2)))
+    }
+
+    /// Like [`asof_left_join`](`Self::asof_left_join`), but can return any
+    /// Z-set type.  Records for which `join_func` returns `None` are dropped.
+    pub fn asof_join_generic<TS, V1, V2, B2, F, Z>(
+        &self,
+        other: &Stream<RootCircuit, B2>,
+        join_func: F,
+    ) -> Stream<RootCircuit, Z>
+    where
+        B: PartitionedIndexedZSet<TS, V1>,
+        B::R: ZRingValue,
+        B2: PartitionedIndexedZSet<TS, V2, Key = B::Key, R = B::R>,
+        TS: DBData,
+        V1: DBData,
+        V2: DBData,
+        Z: ZSet<R = B::R>,
+        F: Fn(&B::Key, &(TS, V1), Option<&(TS, V2)>) -> Option<Z::Key> + 'static,
+    {
+        // ```
+        //          ┌─────────────────────────┐ left_trace
+        //      ┌──►│integrate().delay_trace()├────────────┐
+        //      │   └─────────────────────────┘            ▼
+        // self │                                     ┌────────┐ output
+        // ─────┴────────────────────────────────────►│        ├────────►
+        //                                            │AsofJoin│
+        // other                                      │        │
+        // ─────┬────────────────────────────────────►│        │
+        //      │   ┌─────────────────────────┐       └────────┘
+        //      └──►│integrate().delay_trace()├────────────▲
+        //          └─────────────────────────┘ right_trace
+        // ```
+        self.circuit().region("asof_join", || {
+            let left = self.shard();
+            let right = other.shard();
+
+            let left_trace = left.integrate_trace().delay_trace();
+            let right_trace = right.integrate_trace().delay_trace();
+
+            self.circuit().add_quaternary_operator(
+                AsofJoin::new(join_func),
+                &left,
+                &left_trace,
+                &right,
+                &right_trace,
+            )
+        })
+    }
+}
+
+/// Quaternary operator that implements the internals of `asof_join`.
+///
+/// * Input stream 1: updates to the left time series.
+/// * Input stream 2: trace containing the accumulated left time series as of
+///   the previous clock cycle.
+/// * Input stream 3: updates to the right time series.
+/// * Input stream 4: trace containing the accumulated right time series as of
+///   the previous clock cycle.
+///
+/// For each partition affected by either input delta, the operator computes
+/// the set of left timestamps whose outputs may have changed, evaluates the
+/// join over these timestamps using old contents of both collections and
+/// retracts the result, and evaluates it again using new contents and
+/// inserts the result.
+struct AsofJoin<TS, V1, V2, F> {
+    join_func: F,
+    phantom: PhantomData<(TS, V1, V2)>,
+}
+
+impl<TS, V1, V2, F> AsofJoin<TS, V1, V2, F> {
+    fn new(join_func: F) -> Self {
+        Self {
+            join_func,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<TS, V1, V2, F> Operator for AsofJoin<TS, V1, V2, F>
+where
+    TS: 'static,
+    V1: 'static,
+    V2: 'static,
+    F: 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from("AsofJoin")
+    }
+
+    fn fixedpoint(&self, _scope: Scope) -> bool {
+        true
+    }
+}
+
+impl<TS, V1, V2, F> AsofJoin<TS, V1, V2, F>
+where
+    TS: DBData,
+    V1: DBData,
+    V2: DBData,
+{
+    /// Evaluate the join for records in the current partition of `left` with
+    /// timestamps in `ranges`.
+    ///
+    /// `ranges` must be sorted and non-overlapping.  Both cursors must point
+    /// to the current partition; `right` is `None` if the partition is empty
+    /// in the right collection.  Invokes `output_cb` for each output record.
+    fn eval_ranges<K, R, O, C1, C2, CB>(
+        &self,
+        key: &K,
+        ranges: &[TsRange<TS>],
+        left: &mut C1,
+        mut right: Option<&mut C2>,
+        mut output_cb: CB,
+    ) where
+        R: ZRingValue,
+        C1: Cursor<K, (TS, V1), (), R>,
+        C2: Cursor<K, (TS, V2), (), R>,
+        F: Fn(&K, &(TS, V1), Option<&(TS, V2)>) -> Option<O>,
+        CB: FnMut(O, R),
+    {
+        // Iterate over both cursors backward, so that we can find the latest
+        // record in `right` preceding each left timestamp without scanning the
+        // partition from the start.
+        left.fast_forward_vals();
+        if let Some(right) = right.as_mut() {
+            right.fast_forward_vals();
+        }
+
+        for (from, to) in ranges.iter().rev() {
+            left.seek_val_with_reverse(|(ts, _)| to.as_ref().map_or(true, |to| ts < to));
+
+            while left.val_valid() && &left.val().0 >= from {
+                let w = left.weight();
+
+                if !w.is_zero() {
+                    let matching = right.as_mut().and_then(|right| {
+                        let ts1 = &left.val().0;
+                        right.seek_val_with_reverse(|(ts2, _)| ts2 <= ts1);
+                        while right.val_valid() && right.weight().le0() {
+                            right.step_val_reverse();
+                        }
+                        right.get_val()
+                    });
+
+                    if let Some(output) = (self.join_func)(key, left.val(), matching) {
+                        output_cb(output, w);
+                    }
+                }
+
+                left.step_val_reverse();
+            }
+        }
+    }
+}
+
+/// For each distinct timestamp `ts` in the current partition of `delta`,
+/// pushes the range `[ts, next)` to `ranges`, where `next` is the smallest
+/// timestamp greater than `ts` that either occurs in `delta` or has a record
+/// with positive weight in `all`.
+///
+/// Assuming that `all` contains the new contents of the collection, this is
+/// the range of left timestamps whose matches may be affected by a change at
+/// time `ts`: any left timestamp past `next` is matched with a record at
+/// `next` or later both before and after the change.
+fn affected_ranges<K, TS, V, R, C1, C2>(delta: &mut C1, all: &mut C2, ranges: &mut Vec<TsRange<TS>>)
+where
+    TS: DBData,
+    R: ZRingValue,
+    C1: Cursor<K, (TS, V), (), R>,
+    C2: Cursor<K, (TS, V), (), R>,
+{
+    while delta.val_valid() {
+        let ts = delta.val().0.clone();
+        delta.seek_val_with(|(ts2, _)| ts2 > &ts);
+
+        all.seek_val_with(|(ts2, _)| ts2 > &ts);
+        while all.val_valid() && all.weight().le0() {
+            all.step_val();
+        }
+
+        let next = match (delta.get_val(), all.get_val()) {
+            (None, None) => None,
+            (Some((next, _)), None) | (None, Some((next, _))) => Some(next.clone()),
+            (Some((next1, _)), Some((next2, _))) => Some(next1.min(next2).clone()),
+        };
+
+        ranges.push((ts, next));
+    }
+}
+
+/// Sort `ranges` and merge overlapping and adjacent ranges.
+fn merge_ranges<TS>(mut ranges: Vec<TsRange<TS>>) -> Vec<TsRange<TS>>
+where
+    TS: Ord,
+{
+    ranges.sort_by(|(from1, _), (from2, _)| from1.cmp(from2));
+
+    let mut result: Vec<TsRange<TS>> = Vec::with_capacity(ranges.len());
+
+    for (from, to) in ranges {
+        if let Some((_, last_to)) = result.last_mut() {
+            let overlaps = match &*last_to {
+                None => true,
+                Some(last_to) => &from <= last_to,
+            };
+
+            if overlaps {
+                let extends = match (&*last_to, &to) {
+                    (None, _) => false,
+                    (Some(_), None) => true,
+                    (Some(last_to), Some(to)) => to > last_to,
+                };
+                if extends {
+                    *last_to = to;
+                }
+                continue;
+            }
+        }
+
+        result.push((from, to));
+    }
+
+    result
+}
+
+impl<TS, V1, V2, F, B1, T1, B2, T2, Z> QuaternaryOperator<B1, T1, B2, T2, Z>
+    for AsofJoin<TS, V1, V2, F>
+where
+    TS: DBData,
+    V1: DBData,
+    V2: DBData,
+    B1: PartitionedBatchReader<TS, V1> + Clone,
+    B1::R: ZRingValue,
+    T1: PartitionedBatchReader<TS, V1, Key = B1::Key, R = B1::R> + Clone,
+    B2: PartitionedBatchReader<TS, V2, Key = B1::Key, R = B1::R> + Clone,
+    T2: PartitionedBatchReader<TS, V2, Key = B1::Key, R = B1::R> + Clone,
+    Z: ZSet<R = B1::R>,
+    F: Fn(&B1::Key, &(TS, V1), Option<&(TS, V2)>) -> Option<Z::Key> + 'static,
+{
+    fn eval<'a>(
+        &mut self,
+        left_delta: Cow<'a, B1>,
+        left_trace: Cow<'a, T1>,
+        right_delta: Cow<'a, B2>,
+        right_trace: Cow<'a, T2>,
+    ) -> Z {
+        // Cursors used to iterate over affected partitions.
+        let mut left_delta_cursor = left_delta.cursor();
+        let mut right_delta_cursor = right_delta.cursor();
+
+        // Cursors over the new contents of both collections.
+        let mut left_delta_cursor2 = left_delta.cursor();
+        let mut left_trace_cursor = left_trace.cursor();
+        let mut right_delta_cursor2 = right_delta.cursor();
+        let mut right_trace_cursor = right_trace.cursor();
+
+        // Cursors over the old contents of both collections.
+        let mut left_old_cursor = left_trace.cursor();
+        let mut right_old_cursor = right_trace.cursor();
+
+        let mut tuples = Vec::with_capacity(left_delta.len() + right_delta.len());
+
+        loop {
+            let key = match (left_delta_cursor.get_key(), right_delta_cursor.get_key()) {
+                (None, None) => break,
+                (Some(key), None) | (None, Some(key)) => key.clone(),
+                (Some(key1), Some(key2)) => key1.min(key2).clone(),
+            };
+
+            left_delta_cursor2.seek_key(&key);
+            left_trace_cursor.seek_key(&key);
+            let mut left_new = CursorPair::new(&mut left_delta_cursor2, &mut left_trace_cursor);
+            let left_new_valid = left_new.key_valid() && left_new.key() == &key;
+
+            right_delta_cursor2.seek_key(&key);
+            right_trace_cursor.seek_key(&key);
+            let mut right_new = CursorPair::new(&mut right_delta_cursor2, &mut right_trace_cursor);
+            let right_new_valid = right_new.key_valid() && right_new.key() == &key;
+
+            // Compute the ranges of left timestamps affected by changes to
+            // either collection.  A change to the left collection at time
+            // `ts` only affects records at `ts`; a change to the right
+            // collection at time `ts` affects all left records up to the next
+            // right timestamp.
+            let mut ranges = Vec::new();
+
+            if left_delta_cursor.get_key() == Some(&key) {
+                affected_ranges(&mut left_delta_cursor, &mut left_new, &mut ranges);
+                left_delta_cursor.step_key();
+            }
+
+            if right_delta_cursor.get_key() == Some(&key) {
+                affected_ranges(&mut right_delta_cursor, &mut right_new, &mut ranges);
+                right_delta_cursor.step_key();
+            }
+
+            let ranges = merge_ranges(ranges);
+
+            // Retract old outputs.
+            left_old_cursor.seek_key(&key);
+            right_old_cursor.seek_key(&key);
+
+            if left_old_cursor.get_key() == Some(&key) {
+                let right_old_valid = right_old_cursor.get_key() == Some(&key);
+
+                self.eval_ranges(
+                    &key,
+                    &ranges,
+                    &mut left_old_cursor,
+                    right_old_valid.then_some(&mut right_old_cursor),
+                    |output, w| tuples.push((output, w.neg())),
+                );
+            }
+
+            // Compute new outputs.
+            if left_new_valid {
+                self.eval_ranges(
+                    &key,
+                    &ranges,
+                    &mut left_new,
+                    right_new_valid.then_some(&mut right_new),
+                    |output, w| tuples.push((output, w)),
+                );
+            }
+        }
+
+        Z::from_keys((), tuples)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        algebra::ZRingValue, trace::Batch, zset, CollectionHandle, OrdZSet, OutputHandle,
+        RootCircuit, Runtime,
+    };
+    use anyhow::Result as AnyResult;
+    use proptest::{collection::vec, prelude::*};
+    use std::collections::BTreeMap;
+
+    type Input = CollectionHandle<i32, ((i32, i32), i32)>;
+    type Output = OutputHandle<OrdZSet<(i32, i32, i32, Option<(i32, i32)>), i32>>;
+
+    fn asof_test_circuit(circuit: &mut RootCircuit) -> AnyResult<(Input, Input, Output, Output)> {
+        let (left, left_handle) = circuit.add_input_indexed_zset::<i32, (i32, i32), i32>();
+        let (right, right_handle) = circuit.add_input_indexed_zset::<i32, (i32, i32), i32>();
+
+        let inner_handle = left
+            .asof_join(&right, |k, (ts, v), r| (*k, *ts, *v, Some(r.clone())))
+            .integrate()
+            .output();
+        let left_outer_handle = left
+            .asof_left_join(&right, |k, (ts, v), r| (*k, *ts, *v, r.cloned()))
+            .integrate()
+            .output();
+
+        Ok((left_handle, right_handle, inner_handle, left_outer_handle))
+    }
+
+    /// Reference implementation of `asof_left_join`.
+    fn asof_left_join_slow<R>(
+        left: &BTreeMap<(i32, (i32, i32)), R>,
+        right: &BTreeMap<(i32, (i32, i32)), R>,
+        outer: bool,
+    ) -> OrdZSet<(i32, i32, i32, Option<(i32, i32)>), R>
+    where
+        R: ZRingValue,
+    {
+        let mut result = Vec::new();
+
+        for ((k, (ts, v)), w) in left.iter() {
+            if w.is_zero() {
+                continue;
+            }
+            let matching = right
+                .range((*k, (i32::MIN, i32::MIN))..=(*k, (*ts, i32::MAX)))
+                .rev()
+                .find(|(_, w)| !w.le0())
+                .map(|((_, v2), _)| *v2);
+            if matching.is_some() || outer {
+                result.push(((*k, *ts, *v, matching), w.clone()));
+            }
+        }
+
+        OrdZSet::from_keys((), result)
+    }
+
+    #[test]
+    fn asof_join_test() {
+        let (mut dbsp, (left, right, inner, outer)) =
+            Runtime::init_circuit(2, asof_test_circuit).unwrap();
+
+        // Trades at times 10 and 20, no quotes yet.
+        left.push(1, ((10, 100), 1));
+        left.push(1, ((20, 200), 1));
+        dbsp.step().unwrap();
+        assert_eq!(inner.consolidate(), zset! {});
+        assert_eq!(
+            outer.consolidate(),
+            zset! { (1, 10, 100, None) => 1, (1, 20, 200, None) => 1 }
+        );
+
+        // Quote at time 15 matches the second trade only.
+        right.push(1, ((15, 1), 1));
+        dbsp.step().unwrap();
+        assert_eq!(
+            inner.consolidate(),
+            zset! { (1, 20, 200, Some((15, 1))) => 1 }
+        );
+
+        // An earlier quote matches the first trade.
+        right.push(1, ((5, 2), 1));
+        dbsp.step().unwrap();
+        assert_eq!(
+            inner.consolidate(),
+            zset! { (1, 10, 100, Some((5, 2))) => 1, (1, 20, 200, Some((15, 1))) => 1 }
+        );
+
+        // Deleting the quote at time 15 exposes the quote at time 5.
+        right.push(1, ((15, 1), -1));
+        dbsp.step().unwrap();
+        assert_eq!(
+            inner.consolidate(),
+            zset! { (1, 10, 100, Some((5, 2))) => 1, (1, 20, 200, Some((5, 2))) => 1 }
+        );
+
+        // Quotes in other partitions don't affect the result.
+        right.push(2, ((1, 3), 1));
+        left.push(1, ((20, 200), -1));
+        dbsp.step().unwrap();
+        assert_eq!(
+            outer.consolidate(),
+            zset! { (1, 10, 100, Some((5, 2))) => 1 }
+        );
+    }
+
+    #[test]
+    fn asof_join_test_partition_only_in_right() {
+        let (mut dbsp, (left, right, inner, _outer)) =
+            Runtime::init_circuit(1, asof_test_circuit).unwrap();
+
+        left.push(2, ((10, 1), 1));
+        right.push(1, ((5, 1), 1));
+        dbsp.step().unwrap();
+        assert_eq!(inner.consolidate(), zset! {});
+
+        right.push(2, ((5, 1), 1));
+        dbsp.step().unwrap();
+        assert_eq!(inner.consolidate(), zset! { (2, 10, 1, Some((5, 1))) => 1 });
+    }
+
+    fn input_trace(
+        max_key: i32,
+        max_ts: i32,
+        max_batch_size: usize,
+        max_batches: usize,
+    ) -> impl Strategy<Value = Vec<(Vec<(i32, i32, i32, i32)>, Vec<(i32, i32, i32, i32)>)>> {
+        let batch = || vec((0..max_key, 0..max_ts, 0..3, -1..2), 0..max_batch_size);
+        vec((batch(), batch()), 0..max_batches)
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_asof_join(trace in input_trace(5, 50, 50, 20)) {
+            let (mut dbsp, (left, right, inner, outer)) =
+                Runtime::init_circuit(4, asof_test_circuit).unwrap();
+
+            let mut ref_left = BTreeMap::new();
+            let mut ref_right = BTreeMap::new();
+
+            for (left_batch, right_batch) in trace.into_iter() {
+                for (k, ts, v, w) in left_batch.into_iter() {
+                    *ref_left.entry((k, (ts, v))).or_insert(0) += w;
+                    left.push(k, ((ts, v), w));
+                }
+                for (k, ts, v, w) in right_batch.into_iter() {
+                    *ref_right.entry((k, (ts, v))).or_insert(0) += w;
+                    right.push(k, ((ts, v), w));
+                }
+                dbsp.step().unwrap();
+
+                assert_eq!(inner.consolidate(), asof_left_join_slow(&ref_left, &ref_right, false));
+                assert_eq!(outer.consolidate(), asof_left_join_slow(&ref_left, &ref_right, true));
+            }
+        }
+    }
+}
@@ -1027,7 +1027,7 @@ mod tests {
     use super::Exchange;
     use crate::{
         circuit::{
-            schedule::{DynamicScheduler, Scheduler, StaticScheduler},
+            schedule::{DynamicScheduler, ParallelScheduler, Scheduler, StaticScheduler},
             Runtime,
         },
         operator::{communication::new_exchange_operators, Generator},
@@ -1091,6 +1091,12 @@ mod tests {
         test_exchange_operators::<DynamicScheduler>();
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn test_exchange_operators_parallel() {
+        test_exchange_operators::<ParallelScheduler>();
+    }
+
     // Create a circuit with `WORKERS` concurrent workers with the following
     // structure: `Generator - ExchangeSender -> ExchangeReceiver -> Inspect`.
     // `Generator` - yields sequential numbers 0, 1, 2, ...
@@ -5,6 +5,7 @@ use crate::{
         GlobalNodeId, OwnershipPreference, Scope,
     },
     circuit_cache_key,
+    operator::communication::exchange::new_exchange_operators,
     trace::{spine_fueled::Spine, Batch, Trace},
     Circuit, Runtime, Stream,
 };
@@ -37,6 +38,10 @@ where
     /// The output stream in `receiver_worker` will contain a union of all
     /// input batches across all workers. The output streams in all other
     /// workers will contain empty batches.
+    ///
+    /// In a multihost [`Layout`](`crate::circuit::Layout`), batches produced
+    /// by workers on other hosts are sent to `receiver_worker` over the
+    /// network.
     #[track_caller]
     pub fn gather(&self, receiver_worker: usize) -> Stream<C, B>
     where
@@ -53,6 +58,32 @@ where
 
                 if workers == 1 {
                     self.clone()
+                } else if runtime.layout().other_hosts().next().is_some() {
+                    self.circuit()
+                        .cache_get_or_insert_with(
+                            GatherId::new((self.origin_node_id().clone(), receiver_worker)),
+                            move || {
+                                // The shared-memory gather below only connects workers within
+                                // one process; use a network exchange instead, sending the
+                                // whole batch to `receiver_worker` and empty batches to
+                                // everyone else.
+                                let (sender, receiver) = new_exchange_operators(
+                                    &runtime,
+                                    Runtime::worker_index(),
+                                    Some(location),
+                                    move |batch: B, batches: &mut Vec<B>| {
+                                        batches.extend((0..workers).map(|_| B::empty(())));
+                                        batches[receiver_worker] = batch;
+                                    },
+                                    |trace: &mut Spine<B>, batch: B| trace.insert(batch),
+                                );
+
+                                self.circuit()
+                                    .add_exchange(sender, receiver, self)
+                                    .consolidate()
+                            },
+                        )
+                        .clone()
                 } else {
                     self.circuit()
                         .cache_get_or_insert_with(
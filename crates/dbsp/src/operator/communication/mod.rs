@@ -1,6 +1,9 @@
 mod exchange;
 mod gather;
 mod shard;
+mod skew;
 
 pub(crate) use exchange::Exchange;
 pub use exchange::{new_exchange_operators, ExchangeReceiver, ExchangeSender};
+pub use shard::reshard;
+pub use skew::SkewConfig;
@@ -85,6 +85,10 @@ where
     /// on a busy CPU core or sharing the core with other workers) or uneven
     /// sharding can slow down the whole system and reduce gains from
     /// parallelization.
+    ///
+    /// Joins whose inputs are dominated by a few heavy-hitter keys can use
+    /// [`Stream::join_skewed`] instead, which spreads such keys across
+    /// multiple workers.
     #[track_caller]
     pub fn shard(&self) -> Stream<C, IB>
     where
@@ -131,7 +135,7 @@ where
                                 Runtime::worker_index(),
                                 Some(location),
                                 move |batch: IB, batches: &mut Vec<OB>| {
-                                    Self::shard_batch(&batch, num_workers, &mut builders, batches);
+                                    shard_batch(&batch, num_workers, &mut builders, batches);
                                 },
                                 |trace: &mut Spine<OB>, batch: OB| trace.insert(batch),
                             );
@@ -160,43 +164,6 @@ where
             }
         })
     }
-
-    // Partitions the batch into `nshards` partitions based on the hash of the key.
-    fn shard_batch<OB>(
-        batch: &IB,
-        shards: usize,
-        builders: &mut Vec<OB::Builder>,
-        outputs: &mut Vec<OB>,
-    ) where
-        OB: Batch<Key = IB::Key, Val = IB::Val, Time = (), R = IB::R>,
-    {
-        builders.clear();
-
-        for _ in 0..shards {
-            // We iterate over tuples in the batch in order; hence tuples added
-            // to each shard are also ordered, so we can use the more efficient
-            // `Builder` API (instead of `Batcher`) to construct output batches.
-            builders.push(OB::Builder::with_capacity((), batch.len() / shards));
-        }
-
-        let mut cursor = batch.cursor();
-
-        while cursor.key_valid() {
-            let batch_index = default_hash(cursor.key()) as usize % shards;
-            while cursor.val_valid() {
-                builders[batch_index].push((
-                    OB::item_from(cursor.key().clone(), cursor.val().clone()),
-                    cursor.weight(),
-                ));
-                cursor.step_val();
-            }
-            cursor.step_key();
-        }
-
-        for builder in builders.drain(..) {
-            outputs.push(builder.done());
-        }
-    }
 }
 
 impl<C, T> Stream<C, T>
@@ -253,8 +220,83 @@ where
     }
 }
 
+/// Redistributes the contents of a sharded collection across a different
+/// number of workers.
+///
+/// `shards` contains the state of a collection partitioned by
+/// [`Stream::shard`] across the workers of a runtime, e.g., the per-worker
+/// traces of the collection.  Returns `workers` batches, where batch `i`
+/// contains exactly the tuples that [`Stream::shard`] sends to worker `i` in
+/// a runtime with `workers` worker threads.  The number of input shards does
+/// not need to match `workers`, so this function can be used to restart a
+/// circuit from per-worker state with more or fewer workers without
+/// reprocessing its input history.
+///
+/// # Panics
+///
+/// Panics if `workers` is 0.
+pub fn reshard<IB, OB>(shards: &[IB], workers: usize) -> Vec<OB>
+where
+    IB: BatchReader<Time = ()>,
+    OB: Batch<Key = IB::Key, Val = IB::Val, Time = (), R = IB::R>,
+{
+    assert_ne!(workers, 0, "cannot reshard a collection to 0 workers");
+
+    let mut builders = Vec::with_capacity(workers);
+    let mut partitions = Vec::with_capacity(workers);
+    let mut outputs: Vec<OB> = (0..workers).map(|_| OB::empty(())).collect();
+
+    for shard in shards {
+        shard_batch(shard, workers, &mut builders, &mut partitions);
+        for (output, partition) in outputs.iter_mut().zip(partitions.drain(..)) {
+            *output = output.merge(&partition);
+        }
+    }
+
+    outputs
+}
+
+// Partitions the batch into `shards` partitions based on the hash of the key.
+fn shard_batch<IB, OB>(
+    batch: &IB,
+    shards: usize,
+    builders: &mut Vec<OB::Builder>,
+    outputs: &mut Vec<OB>,
+) where
+    IB: BatchReader<Time = ()>,
+    OB: Batch<Key = IB::Key, Val = IB::Val, Time = (), R = IB::R>,
+{
+    builders.clear();
+
+    for _ in 0..shards {
+        // We iterate over tuples in the batch in order; hence tuples added
+        // to each shard are also ordered, so we can use the more efficient
+        // `Builder` API (instead of `Batcher`) to construct output batches.
+        builders.push(OB::Builder::with_capacity((), batch.len() / shards));
+    }
+
+    let mut cursor = batch.cursor();
+
+    while cursor.key_valid() {
+        let batch_index = default_hash(cursor.key()) as usize % shards;
+        while cursor.val_valid() {
+            builders[batch_index].push((
+                OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                cursor.weight(),
+            ));
+            cursor.step_val();
+        }
+        cursor.step_key();
+    }
+
+    for builder in builders.drain(..) {
+        outputs.push(builder.done());
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::reshard;
     use crate::{
         operator::Generator,
         trace::{Batch, BatchReader},
@@ -268,6 +310,105 @@ mod tests {
         do_test_shard(16);
     }
 
+    #[test]
+    fn test_reshard() {
+        let data = test_data(0, 1);
+
+        // Shard the collection across 3 workers, then rescale to 5 and back to 2.
+        let three: Vec<OrdIndexedZSet<usize, usize, isize>> = reshard(&[data.clone()], 3);
+        let five: Vec<OrdIndexedZSet<usize, usize, isize>> = reshard(&three, 5);
+        let two: Vec<OrdIndexedZSet<usize, usize, isize>> = reshard(&five, 2);
+
+        assert_eq!(three.len(), 3);
+        assert_eq!(five.len(), 5);
+        assert_eq!(two.len(), 2);
+
+        // Rescaling must produce the same partitioning as sharding from scratch.
+        assert_eq!(
+            five,
+            reshard::<_, OrdIndexedZSet<_, _, _>>(&[data.clone()], 5)
+        );
+        assert_eq!(
+            two,
+            reshard::<_, OrdIndexedZSet<_, _, _>>(&[data.clone()], 2)
+        );
+
+        // ...and must not lose or duplicate any tuples.
+        let union = five
+            .iter()
+            .fold(OrdIndexedZSet::empty(()), |acc, batch| acc.merge(batch));
+        assert_eq!(union, data);
+
+        // Resharded state matches what the `shard` operator sends to each worker.
+        let expected = five.clone();
+        Runtime::run(5, move || {
+            let expected = expected.clone();
+            let circuit = RootCircuit::build(move |circuit| {
+                let input = circuit.add_source(Generator::new(|| {
+                    let worker_index = Runtime::worker_index();
+                    let num_workers = Runtime::runtime().unwrap().num_workers();
+                    test_data(worker_index, num_workers)
+                }));
+                input
+                    .shard()
+                    .inspect(move |batch: &OrdIndexedZSet<usize, usize, isize>| {
+                        assert_eq!(batch, &expected[Runtime::worker_index()]);
+                    });
+                Ok(())
+            })
+            .unwrap()
+            .0;
+
+            circuit.step().unwrap();
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn test_restart_with_more_workers() {
+        type Data = OrdIndexedZSet<usize, usize, isize>;
+
+        let history = test_data(0, 1);
+
+        // Integrate a sharded collection with 3 workers and save the state of
+        // each worker.
+        let (mut dbsp, (input, state)) = Runtime::init_circuit(3, |circuit| {
+            let (input, input_handle) = circuit.add_input_stream::<Data>();
+            let state = input.shard().integrate().output();
+            Ok((input_handle, state))
+        })
+        .unwrap();
+        input.set_for_worker(0, history.clone());
+        dbsp.step().unwrap();
+        let saved = state.take_from_all();
+        assert_eq!(saved.len(), 3);
+        dbsp.kill().unwrap();
+
+        // Restart with 5 workers from the saved state and feed new input.
+        let update = Data::from_tuples((), vec![((2000, 1), 1), ((2001, 1), 1), ((0, 0), -1)]);
+        let expected = history.merge(&update);
+        let per_worker: Vec<Data> = reshard(&[expected.clone()], 5);
+        let (mut dbsp, (restored, input, state)) = Runtime::init_circuit(5, move |circuit| {
+            let (restored, restored_handle) = circuit.add_input_stream::<Data>();
+            let (input, input_handle) = circuit.add_input_stream::<Data>();
+            let integral = restored.mark_sharded().plus(&input.shard()).integrate();
+            integral.inspect(move |batch: &Data| {
+                assert_eq!(batch, &per_worker[Runtime::worker_index()]);
+            });
+            Ok((restored_handle, input_handle, integral.output()))
+        })
+        .unwrap();
+        restored.set_resharded(&saved);
+        input.set_for_worker(0, update);
+        dbsp.step().unwrap();
+
+        // The new circuit has the state it would have after processing the
+        // whole history.
+        assert_eq!(state.consolidate(), expected);
+        dbsp.kill().unwrap();
+    }
+
     fn test_data(worker_index: usize, num_workers: usize) -> OrdIndexedZSet<usize, usize, isize> {
         let tuples: Vec<_> = (0..1000)
             .filter(|n| n % num_workers == worker_index)
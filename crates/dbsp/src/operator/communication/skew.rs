@@ -0,0 +1,737 @@
+//! Skew-aware sharding of join inputs.
+//!
+//! [`Stream::shard`] sends all tuples with the same key to the same worker.
+//! When a small number of keys account for a large fraction of the input,
+//! the workers that own these keys become stragglers that slow down the
+//! entire circuit.  The operators in this module detect such heavy hitters
+//! at runtime and split their tuples across several workers, replicating
+//! tuples from the other side of the join for those keys.
+
+use crate::{
+    algebra::{IndexedZSet, MulByRef, ZRingValue},
+    circuit::{
+        metadata::OperatorMeta,
+        operator_traits::{BinaryOperator, Operator},
+        OwnershipPreference, Scope,
+    },
+    default_hash,
+    operator::{
+        communication::new_exchange_operators,
+        trace::{TraceBounds, TraceFeedback},
+    },
+    trace::{cursor::Cursor, Batch, BatchReader, Builder, Spine, Trace},
+    Circuit, DBData, DBWeight, OrdIndexedZSet, OrdZSet, RootCircuit, Runtime, Stream,
+};
+use std::{
+    borrow::Cow, cmp::max, collections::BTreeSet, hash::Hash, iter::once, marker::PhantomData,
+    panic::Location, sync::Arc,
+};
+
+/// Configuration of skew-aware sharding used by [`Stream::join_skewed`].
+#[derive(Clone, Debug)]
+pub struct SkewConfig {
+    /// Maximal number of tuples sampled from the input batch of each worker
+    /// at every step.
+    pub sample_size: usize,
+
+    /// A key is considered hot if it accounts for at least this fraction of
+    /// the tuples sampled by some worker.
+    pub hot_key_threshold: f64,
+
+    /// Keys with fewer than this many (estimated) tuples in the input batch
+    /// of a worker are never considered hot.  Prevents small batches from
+    /// marking all their keys as hot.
+    pub min_hot_key_tuples: usize,
+
+    /// Number of workers each hot key is spread across.  Values larger than
+    /// the number of workers are truncated to the number of workers.
+    pub fanout: usize,
+}
+
+impl Default for SkewConfig {
+    fn default() -> Self {
+        Self {
+            sample_size: 1024,
+            hot_key_threshold: 0.05,
+            min_hot_key_tuples: 1024,
+            fanout: usize::MAX,
+        }
+    }
+}
+
+/// The set of hot keys at the current step.  Identical at all workers.
+#[derive(Clone)]
+struct HotKeys<K> {
+    /// All keys detected as hot so far, including at the current step.
+    keys: Arc<BTreeSet<K>>,
+
+    /// Keys that became hot at the current step.
+    new_keys: Arc<BTreeSet<K>>,
+
+    /// Number of workers each hot key is spread across.
+    fanout: usize,
+}
+
+impl<K> HotKeys<K>
+where
+    K: Ord + Hash,
+{
+    /// Worker that owns `key` under regular hash-based sharding.
+    fn owner(key: &K, workers: usize) -> usize {
+        default_hash(key) as usize % workers
+    }
+
+    /// Workers that `key` is spread across if it is hot, starting from its
+    /// owner.
+    fn targets(&self, key: &K, workers: usize) -> impl Iterator<Item = usize> {
+        let owner = Self::owner(key, workers);
+        (0..self.fanout).map(move |i| (owner + i) % workers)
+    }
+}
+
+impl<I1> Stream<RootCircuit, I1>
+where
+    I1: IndexedZSet + Send,
+    I1::R: ZRingValue,
+{
+    /// Like [`Stream::join`], but detects keys that dominate the input of
+    /// `self` and spreads them across multiple workers.
+    ///
+    /// [`Stream::join`] shards both inputs by key, so all tuples with the
+    /// same key are processed by the same worker.  A single heavy-hitter key
+    /// can therefore turn one worker into a bottleneck.  This operator
+    /// samples up to `config.sample_size` tuples from the input batch of
+    /// each worker at every step and marks keys that account for at least
+    /// `config.hot_key_threshold` of the sample as hot.  Tuples in `self`
+    /// with a hot key are spread across `config.fanout` workers based on the
+    /// hash of their value, and tuples in `other` with the same key are
+    /// replicated to all of these workers.  All other keys are sharded as
+    /// usual.
+    ///
+    /// Skew is only detected in `self`, which should be the larger input
+    /// whose keys are skewed.  Replication multiplies the size of the
+    /// matching tuples in `other` by the fanout.
+    ///
+    /// Hot keys are sticky: once a key is detected as hot, it remains hot
+    /// for the lifetime of the circuit.  When a key becomes hot, the tuples
+    /// in `other` accumulated by its owner are copied to the other workers
+    /// in its fanout.  Tuples in `self` that were inserted before the key
+    /// became hot stay at the owner, so retracting them leaves records in
+    /// the traces of two workers that cancel out in the output but are not
+    /// consolidated.
+    ///
+    /// Per-worker load is reported in the metadata of the `ShardStats`
+    /// operators in the `join_skewed` region of the circuit.
+    ///
+    /// Falls back to [`Stream::join`] when the circuit runs with a single
+    /// worker.
+    #[track_caller]
+    pub fn join_skewed<I2, F, V>(
+        &self,
+        other: &Stream<RootCircuit, I2>,
+        join_func: F,
+        config: SkewConfig,
+    ) -> Stream<RootCircuit, OrdZSet<V, I1::R>>
+    where
+        I2: IndexedZSet<Key = I1::Key, R = I1::R> + Send,
+        F: Fn(&I1::Key, &I1::Val, &I2::Val) -> V + Clone + 'static,
+        V: DBData,
+    {
+        self.join_skewed_generic(
+            other,
+            move |k, v1, v2| once((join_func(k, v1, v2), ())),
+            config,
+        )
+    }
+
+    /// Like [`Self::join_skewed`], but can return any indexed Z-set type.
+    #[track_caller]
+    pub fn join_skewed_generic<I2, F, Z, It>(
+        &self,
+        other: &Stream<RootCircuit, I2>,
+        join_func: F,
+        config: SkewConfig,
+    ) -> Stream<RootCircuit, Z>
+    where
+        I2: IndexedZSet<Key = I1::Key, R = I1::R> + Send,
+        Z: IndexedZSet<R = I1::R>,
+        Z::R: MulByRef<Output = Z::R>,
+        F: Fn(&I1::Key, &I1::Val, &I2::Val) -> It + Clone + 'static,
+        It: IntoIterator<Item = (Z::Key, Z::Val)> + 'static,
+    {
+        let location = Location::caller();
+
+        let runtime = match Runtime::runtime() {
+            Some(runtime) if runtime.num_workers() > 1 => runtime,
+            _ => return self.join_generic(other, join_func),
+        };
+        let workers = runtime.num_workers();
+        let worker = Runtime::worker_index();
+
+        // We construct the following circuit, where `hot` is the stream of
+        // hot keys consumed by all operators marked with `*`:
+        //
+        // ```
+        //         ┌────────┐  hot
+        //  self ─►│hot_keys├──────►
+        //    │    └────────┘
+        //    │    ┌──────┐     ┌───────────┐
+        //    └───►│split*├────►│ShardStats*├─────────────────────►┌────┐
+        //         └──────┘     └───────────┘                      │join├──►
+        //         ┌──────────┐     ┌───────────┐  right           │    │
+        //  other─►│replicate*├────►│ShardStats*├──────┬──────────►└────┘
+        //         └──────────┘     └───────────┘      │
+        //              ▲                              ▼
+        //         ┌────┴─────┐  delayed_trace   ┌───────────┐
+        //         │ migrate* │◄─────────────────┤   trace   │
+        //         └──────────┘                  └───────────┘
+        // ```
+        //
+        // `split` and `replicate` are exchange operators.  Both join inputs
+        // are marked as sharded, so the join uses them directly and reuses
+        // the trace of `right`.
+        self.circuit().region("join_skewed", || {
+            let fanout = config.fanout.clamp(1, workers);
+
+            // Detect candidate hot keys at each worker and broadcast them to
+            // all peers.
+            let local_candidates = self.apply(move |batch: &I1| sample_hot_keys(batch, &config));
+            let (sender, receiver) = new_exchange_operators(
+                &runtime,
+                worker,
+                Some(location),
+                move |candidates: OrdZSet<I1::Key, isize>, outputs: &mut Vec<_>| {
+                    for _ in 0..workers {
+                        outputs.push(candidates.clone());
+                    }
+                },
+                |keys: &mut Vec<I1::Key>, candidates: OrdZSet<I1::Key, isize>| {
+                    let mut cursor = candidates.cursor();
+                    while cursor.key_valid() {
+                        keys.push(cursor.key().clone());
+                        cursor.step_key();
+                    }
+                },
+            );
+
+            let mut keys = Arc::new(BTreeSet::new());
+            let hot = self
+                .circuit()
+                .add_exchange(sender, receiver, &local_candidates)
+                .apply(move |candidates: &Vec<I1::Key>| {
+                    let new_keys: BTreeSet<I1::Key> = candidates
+                        .iter()
+                        .filter(|key| !keys.contains(*key))
+                        .cloned()
+                        .collect();
+
+                    if !new_keys.is_empty() {
+                        Arc::make_mut(&mut keys).extend(new_keys.iter().cloned());
+                    }
+
+                    HotKeys {
+                        keys: keys.clone(),
+                        new_keys: Arc::new(new_keys),
+                        fanout,
+                    }
+                });
+
+            // Split tuples with hot keys in `self` across their fanout.
+            let mut builders = Vec::with_capacity(workers);
+            let (sender, receiver) = new_exchange_operators(
+                &runtime,
+                worker,
+                Some(location),
+                move |(batch, hot): (I1, HotKeys<I1::Key>),
+                      outputs: &mut Vec<OrdIndexedZSet<I1::Key, I1::Val, I1::R>>| {
+                    split_batch(&batch, &hot, workers, &mut builders, outputs);
+                },
+                |trace: &mut Spine<OrdIndexedZSet<I1::Key, I1::Val, I1::R>>,
+                 batch: OrdIndexedZSet<I1::Key, I1::Val, I1::R>| {
+                    trace.insert(batch)
+                },
+            );
+            let left = self.circuit().add_exchange(
+                sender,
+                receiver,
+                &self.apply2_owned(&hot, |batch, hot| (batch, hot.clone())),
+            );
+            let left = self
+                .circuit()
+                .add_binary_operator_with_preference(
+                    ShardStats::new(),
+                    (&left.consolidate(), OwnershipPreference::PREFER_OWNED),
+                    (&hot, OwnershipPreference::INDIFFERENT),
+                )
+                .mark_sharded();
+
+            // Replicate tuples with hot keys in `other` across the fanout of
+            // the key, and migrate tuples that the owner of a newly detected
+            // hot key has accumulated so far to the other workers in its
+            // fanout.
+            let feedback = self
+                .circuit()
+                .add_integrate_trace_feedback::<Spine<OrdIndexedZSet<I1::Key, I2::Val, I1::R>>>(
+                    TraceBounds::unbounded(),
+                );
+            let migration = feedback.delayed_trace.apply2(&hot, move |trace, hot| {
+                migrated_tuples(trace, hot, workers, worker)
+            });
+
+            let mut builders = Vec::with_capacity(workers);
+            let (sender, receiver) = new_exchange_operators(
+                &runtime,
+                worker,
+                Some(location),
+                move |(batch, migration, hot): (
+                    I2,
+                    OrdIndexedZSet<I1::Key, I2::Val, I1::R>,
+                    HotKeys<I1::Key>,
+                ),
+                      outputs: &mut Vec<OrdIndexedZSet<I1::Key, I2::Val, I1::R>>| {
+                    replicate_batch(
+                        &batch,
+                        &migration,
+                        &hot,
+                        workers,
+                        worker,
+                        &mut builders,
+                        outputs,
+                    );
+                },
+                |trace: &mut Spine<OrdIndexedZSet<I1::Key, I2::Val, I1::R>>,
+                 batch: OrdIndexedZSet<I1::Key, I2::Val, I1::R>| {
+                    trace.insert(batch)
+                },
+            );
+            let right = self.circuit().add_exchange(
+                sender,
+                receiver,
+                &other.apply3(&migration, &hot, |batch, migration, hot| {
+                    (batch.into_owned(), migration.into_owned(), hot.into_owned())
+                }),
+            );
+            let right = self
+                .circuit()
+                .add_binary_operator_with_preference(
+                    ShardStats::new(),
+                    (&right.consolidate(), OwnershipPreference::PREFER_OWNED),
+                    (&hot, OwnershipPreference::INDIFFERENT),
+                )
+                .mark_sharded();
+            feedback.connect(&right);
+
+            left.join_generic(&right, join_func)
+        })
+    }
+}
+
+/// Returns keys that account for a large fraction of a systematic sample of
+/// tuples in `batch`, weighted by the number of sampled tuples.
+fn sample_hot_keys<B>(batch: &B, config: &SkewConfig) -> OrdZSet<B::Key, isize>
+where
+    B: BatchReader<Time = ()>,
+{
+    let len = batch.len();
+    if config.sample_size == 0 || len < max(config.min_hot_key_tuples, 1) {
+        return OrdZSet::empty(());
+    }
+
+    // Sample every `stride`'th tuple.
+    let stride = max(len / config.sample_size, 1);
+    let mut counts = Vec::new();
+    let mut sampled = 0;
+    let mut index = 0;
+
+    let mut cursor = batch.cursor();
+    while cursor.key_valid() {
+        let mut count = 0;
+        while cursor.val_valid() {
+            if index % stride == 0 {
+                count += 1;
+            }
+            index += 1;
+            cursor.step_val();
+        }
+        if count > 0 {
+            sampled += count;
+            counts.push((cursor.key().clone(), count));
+        }
+        cursor.step_key();
+    }
+
+    let threshold = max(
+        (config.hot_key_threshold * sampled as f64).ceil() as isize,
+        (config.min_hot_key_tuples / stride) as isize,
+    );
+
+    // Keys are visited in order, so we can use the builder API.
+    let mut builder = <OrdZSet<B::Key, isize> as Batch>::Builder::new_builder(());
+    for (key, count) in counts {
+        if count >= max(threshold, 1) {
+            builder.push((key, count));
+        }
+    }
+    builder.done()
+}
+
+/// Partitions `batch` across workers: tuples with hot keys are spread across
+/// the fanout of the key based on the hash of the value, all other tuples
+/// are sent to the owner of the key.
+fn split_batch<B, OB>(
+    batch: &B,
+    hot: &HotKeys<B::Key>,
+    workers: usize,
+    builders: &mut Vec<OB::Builder>,
+    outputs: &mut Vec<OB>,
+) where
+    B: BatchReader<Time = ()>,
+    OB: Batch<Key = B::Key, Val = B::Val, Time = (), R = B::R>,
+{
+    builders.clear();
+    for _ in 0..workers {
+        builders.push(OB::Builder::with_capacity((), batch.len() / workers));
+    }
+
+    let mut cursor = batch.cursor();
+    while cursor.key_valid() {
+        let owner = HotKeys::owner(cursor.key(), workers);
+
+        if hot.keys.contains(cursor.key()) {
+            while cursor.val_valid() {
+                let target = (owner + default_hash(cursor.val()) as usize % hot.fanout) % workers;
+                builders[target].push((
+                    OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                    cursor.weight(),
+                ));
+                cursor.step_val();
+            }
+        } else {
+            while cursor.val_valid() {
+                builders[owner].push((
+                    OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                    cursor.weight(),
+                ));
+                cursor.step_val();
+            }
+        }
+        cursor.step_key();
+    }
+
+    for builder in builders.drain(..) {
+        outputs.push(builder.done());
+    }
+}
+
+/// Returns the tuples in `trace` whose keys became hot at the current step
+/// and are owned by `worker`.  These tuples must be copied to the other
+/// workers in the fanout of the key.
+fn migrated_tuples<K, V, R>(
+    trace: &Spine<OrdIndexedZSet<K, V, R>>,
+    hot: &HotKeys<K>,
+    workers: usize,
+    worker: usize,
+) -> OrdIndexedZSet<K, V, R>
+where
+    K: DBData,
+    V: DBData,
+    R: DBWeight,
+{
+    let mut builder = <OrdIndexedZSet<K, V, R> as Batch>::Builder::new_builder(());
+    if hot.new_keys.is_empty() {
+        return builder.done();
+    }
+
+    let mut cursor = trace.cursor();
+    for key in hot.new_keys.iter() {
+        if HotKeys::owner(key, workers) != worker {
+            continue;
+        }
+
+        cursor.seek_key(key);
+        if cursor.get_key() == Some(key) {
+            while cursor.val_valid() {
+                let weight = cursor.weight();
+                builder.push((
+                    <OrdIndexedZSet<K, V, R> as Batch>::item_from(
+                        key.clone(),
+                        cursor.val().clone(),
+                    ),
+                    weight,
+                ));
+                cursor.step_val();
+            }
+        }
+    }
+    builder.done()
+}
+
+/// Partitions `batch` across workers, replicating tuples with hot keys to
+/// all workers in the fanout of the key, and sends `migration` to all
+/// workers in the fanout except `worker` itself.
+#[allow(clippy::too_many_arguments)]
+fn replicate_batch<B, OB>(
+    batch: &B,
+    migration: &OB,
+    hot: &HotKeys<B::Key>,
+    workers: usize,
+    worker: usize,
+    builders: &mut Vec<OB::Builder>,
+    outputs: &mut Vec<OB>,
+) where
+    B: BatchReader<Time = ()>,
+    OB: Batch<Key = B::Key, Val = B::Val, Time = (), R = B::R>,
+{
+    builders.clear();
+    for _ in 0..workers {
+        builders.push(OB::Builder::with_capacity((), batch.len() / workers));
+    }
+
+    let mut cursor = batch.cursor();
+    while cursor.key_valid() {
+        if hot.keys.contains(cursor.key()) {
+            for target in hot.targets(cursor.key(), workers) {
+                while cursor.val_valid() {
+                    builders[target].push((
+                        OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                        cursor.weight(),
+                    ));
+                    cursor.step_val();
+                }
+                cursor.rewind_vals();
+            }
+        } else {
+            let owner = HotKeys::owner(cursor.key(), workers);
+            while cursor.val_valid() {
+                builders[owner].push((
+                    OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                    cursor.weight(),
+                ));
+                cursor.step_val();
+            }
+        }
+        cursor.step_key();
+    }
+
+    outputs.extend(builders.drain(..).map(|builder| builder.done()));
+
+    if migration.is_empty() {
+        return;
+    }
+
+    // Tuples in `migration` are ordered, but they interleave with tuples
+    // in `batch`, so we assemble them into separate batches and merge them
+    // with the outputs.
+    for _ in 0..workers {
+        builders.push(OB::Builder::new_builder(()));
+    }
+
+    let mut cursor = migration.cursor();
+    while cursor.key_valid() {
+        for target in hot.targets(cursor.key(), workers) {
+            if target == worker {
+                continue;
+            }
+            while cursor.val_valid() {
+                builders[target].push((
+                    OB::item_from(cursor.key().clone(), cursor.val().clone()),
+                    cursor.weight(),
+                ));
+                cursor.step_val();
+            }
+            cursor.rewind_vals();
+        }
+        cursor.step_key();
+    }
+
+    for (output, builder) in outputs.iter_mut().zip(builders.drain(..)) {
+        let migrated = builder.done();
+        if !migrated.is_empty() {
+            *output = output.merge(&migrated);
+        }
+    }
+}
+
+/// Passes sharded batches through unmodified, recording the load of the
+/// local worker for operator metadata.
+struct ShardStats<B, K> {
+    hot_keys: usize,
+    last_tuples: usize,
+    total_tuples: usize,
+    _phantom: PhantomData<(B, K)>,
+}
+
+impl<B, K> ShardStats<B, K> {
+    fn new() -> Self {
+        Self {
+            hot_keys: 0,
+            last_tuples: 0,
+            total_tuples: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<B, K> ShardStats<B, K>
+where
+    B: BatchReader,
+{
+    fn update(&mut self, batch: &B, hot: &HotKeys<K>) {
+        self.hot_keys = hot.keys.len();
+        self.last_tuples = batch.len();
+        self.total_tuples += batch.len();
+    }
+}
+
+impl<B, K> Operator for ShardStats<B, K>
+where
+    B: 'static,
+    K: 'static,
+{
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from("ShardStats")
+    }
+
+    fn metadata(&self, meta: &mut OperatorMeta) {
+        meta.extend(metadata! {
+            "hot keys" => self.hot_keys,
+            "tuples in last batch" => self.last_tuples,
+            "total tuples" => self.total_tuples,
+        });
+    }
+
+    fn fixedpoint(&self, _scope: Scope) -> bool {
+        true
+    }
+}
+
+impl<B, K> BinaryOperator<B, HotKeys<K>, B> for ShardStats<B, K>
+where
+    B: BatchReader + Clone + 'static,
+    K: Clone + 'static,
+{
+    fn eval(&mut self, batch: &B, hot: &HotKeys<K>) -> B {
+        self.update(batch, hot);
+        batch.clone()
+    }
+
+    fn eval_owned(&mut self, batch: B, hot: HotKeys<K>) -> B {
+        self.update(&batch, &hot);
+        batch
+    }
+
+    fn eval_owned_and_ref(&mut self, batch: B, hot: &HotKeys<K>) -> B {
+        self.update(&batch, hot);
+        batch
+    }
+
+    fn input_preference(&self) -> (OwnershipPreference, OwnershipPreference) {
+        (
+            OwnershipPreference::PREFER_OWNED,
+            OwnershipPreference::INDIFFERENT,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SkewConfig;
+    use crate::{
+        trace::{test_batch::assert_batch_eq, Batch},
+        CollectionHandle, OrdZSet, OutputHandle, RootCircuit, Runtime,
+    };
+    use anyhow::Result as AnyResult;
+    use proptest::{collection::vec, prelude::*};
+
+    type Handles = (
+        CollectionHandle<u64, i64, i64>,
+        CollectionHandle<u64, i64, i64>,
+        OutputHandle<OrdZSet<(u64, i64, i64), i64>>,
+        OutputHandle<OrdZSet<(u64, i64, i64), i64>>,
+    );
+
+    fn test_circuit(circuit: &mut RootCircuit) -> AnyResult<Handles> {
+        let (left, left_handle) = circuit.add_input_indexed_zset::<u64, i64, i64>();
+        let (right, right_handle) = circuit.add_input_indexed_zset::<u64, i64, i64>();
+
+        let config = SkewConfig {
+            sample_size: 16,
+            hot_key_threshold: 0.25,
+            min_hot_key_tuples: 8,
+            fanout: 3,
+        };
+
+        let expected = left
+            .join(&right, |k, v1, v2| (*k, *v1, *v2))
+            .integrate()
+            .output();
+        let skewed = left
+            .join_skewed(&right, |k, v1, v2| (*k, *v1, *v2), config)
+            .integrate()
+            .output();
+
+        Ok((left_handle, right_handle, expected, skewed))
+    }
+
+    #[test]
+    fn join_skewed_test() {
+        let (mut dbsp, (left, right, expected, skewed)) =
+            Runtime::init_circuit(4, test_circuit).unwrap();
+
+        right.push(0, (1000, 1));
+        right.push(1, (2000, 1));
+        dbsp.step().unwrap();
+
+        // Key 0 becomes hot, its existing right-hand tuples must be migrated.
+        for v in 0..200 {
+            left.push(0, (v, 1));
+        }
+        left.push(1, (0, 1));
+        dbsp.step().unwrap();
+        assert_batch_eq(&skewed.consolidate(), &expected.consolidate());
+
+        // New right-hand tuples for the hot key are replicated.
+        right.push(0, (1001, 1));
+        right.push(0, (1000, -1));
+        dbsp.step().unwrap();
+        assert_batch_eq(&skewed.consolidate(), &expected.consolidate());
+
+        // Retract a mix of tuples inserted before and after key 0 became hot.
+        for v in 0..100 {
+            left.push(0, (v, -1));
+        }
+        left.push(1, (0, -1));
+        dbsp.step().unwrap();
+        assert_batch_eq(&skewed.consolidate(), &expected.consolidate());
+
+        assert_eq!(
+            skewed.consolidate(),
+            OrdZSet::from_keys((), (100..200).map(|v| ((0, v, 1001), 1)).collect())
+        );
+
+        dbsp.kill().unwrap();
+    }
+
+    proptest! {
+        #[test]
+        fn proptest_join_skewed(trace in vec((vec((0..20u64, 0..50i64, -1..2i64), 0..100), vec((0..20u64, 0..10i64, -1..2i64), 0..20)), 0..20)) {
+            let (mut dbsp, (left, right, expected, skewed)) =
+                Runtime::init_circuit(4, test_circuit).unwrap();
+
+            for (left_batch, right_batch) in trace.into_iter() {
+                // Skew the left input: most keys map to 0.
+                for (k, v, r) in left_batch.into_iter() {
+                    left.push(if k < 15 { 0 } else { k }, (v, r));
+                }
+                for (k, v, r) in right_batch.into_iter() {
+                    right.push(if k < 10 { 0 } else { k }, (v, r));
+                }
+                dbsp.step().unwrap();
+
+                assert_batch_eq(&skewed.consolidate(), &expected.consolidate());
+            }
+
+            dbsp.kill().unwrap();
+        }
+    }
+}
@@ -141,7 +141,7 @@ impl<C> Condition<C> {
 #[cfg(test)]
 mod test {
     use crate::{
-        circuit::schedule::{DynamicScheduler, Scheduler, StaticScheduler},
+        circuit::schedule::{DynamicScheduler, ParallelScheduler, Scheduler, StaticScheduler},
         monitor::TraceMonitor,
         operator::{DelayedFeedback, FilterMap, Generator},
         trace::ord::{OrdIndexedZSet, OrdZSet},
@@ -158,6 +158,11 @@ mod test {
         iterate_with_conditions::<DynamicScheduler>();
     }
 
+    #[test]
+    fn iterate_with_conditions_parallel() {
+        iterate_with_conditions::<ParallelScheduler>();
+    }
+
     fn iterate_with_conditions<S>()
     where
         S: Scheduler + 'static,
@@ -3,7 +3,7 @@
 use crate::{
     algebra::{AddByRef, HasOne, HasZero, IndexedZSet, Lattice, PartialOrder, Present, ZRingValue},
     circuit::{
-        metadata::{MetaItem, OperatorMeta},
+        metadata::{MemoryUsage, MetaItem, OperatorMeta, RecordCounts},
         operator_traits::{BinaryOperator, Operator, UnaryOperator},
         Circuit, GlobalNodeId, Scope, Stream, WithClock,
     },
@@ -155,12 +155,16 @@ where
 
 /// `Distinct` operator changes all weights in the support of a Z-set to 1.
 pub struct Distinct<Z> {
+    records: RecordCounts,
     _type: PhantomData<Z>,
 }
 
 impl<Z> Distinct<Z> {
     pub fn new() -> Self {
-        Self { _type: PhantomData }
+        Self {
+            records: RecordCounts::default(),
+            _type: PhantomData,
+        }
     }
 }
 
@@ -178,6 +182,10 @@ where
         Cow::from("Distinct")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -189,11 +197,16 @@ where
     Z::R: ZRingValue,
 {
     fn eval(&mut self, input: &Z) -> Z {
-        input.distinct()
+        let output = input.distinct();
+        self.records.add(input.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, input: Z) -> Z {
-        input.distinct_owned()
+        let input_records = input.len();
+        let output = input.distinct_owned();
+        self.records.add(input_records, output.len());
+        output
     }
 }
 
@@ -205,12 +218,16 @@ where
 /// `distinct(A) - distinct(z^-1(A))` incrementally, by only considering
 /// values in the support of `a`.
 struct DistinctIncrementalTotal<Z, I> {
+    records: RecordCounts,
     _type: PhantomData<(Z, I)>,
 }
 
 impl<Z, I> DistinctIncrementalTotal<Z, I> {
     pub fn new() -> Self {
-        Self { _type: PhantomData }
+        Self {
+            records: RecordCounts::default(),
+            _type: PhantomData,
+        }
     }
 }
 
@@ -229,6 +246,11 @@ where
         Cow::from("DistinctIncrementalTotal")
     }
 
+    // Only counts the changes in the first input, not the integral.
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -297,7 +319,9 @@ where
             delta_cursor.step_key();
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(delta.len(), output.len());
+        output
     }
 
     // TODO: owned implementation.
@@ -334,6 +358,8 @@ where
     // Used in computing partial derivatives
     // (we keep it here to reuse allocations across `eval_keyval` calls).
     distinct_vals: Vec<(Option<T::Time>, Z::R)>,
+    #[size_of(skip)]
+    records: RecordCounts,
     _type: PhantomData<(Z, T)>,
 }
 
@@ -353,6 +379,7 @@ where
             empty_input: false,
             empty_output: false,
             distinct_vals: vec![(None, HasZero::zero()); 2 << depth],
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -565,6 +592,17 @@ where
         });
     }
 
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        // The operator doesn't own any batches: its inputs are traces
+        // maintained by other operators.
+        Some(MemoryUsage::new(self.size_of(), 0))
+    }
+
+    // Only counts the changes in the first input, not the trace.
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn clock_start(&mut self, scope: Scope) {
         if scope == 0 {
             self.empty_input = false;
@@ -767,6 +805,7 @@ where
 
         let result = result_builder.done();
         self.empty_output = result.is_empty();
+        self.records.add(delta.len(), result.len());
 
         result
     }
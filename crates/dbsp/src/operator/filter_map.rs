@@ -2,6 +2,7 @@
 
 use crate::{
     circuit::{
+        metadata::RecordCounts,
         operator_traits::{Operator, UnaryOperator},
         Circuit, OwnershipPreference, Scope, Stream,
     },
@@ -278,6 +279,7 @@ where
 /// Internal implementation for filtering [`BatchReader`]s
 pub struct FilterKeys<CI, CO, F> {
     filter: F,
+    records: RecordCounts,
     _type: PhantomData<*const (CI, CO)>,
 }
 
@@ -285,6 +287,7 @@ impl<CI, CO, F> FilterKeys<CI, CO, F> {
     pub fn new(filter: F) -> Self {
         Self {
             filter,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -332,6 +335,10 @@ where
         Cow::Borrowed("FilterKeys")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -367,14 +374,18 @@ where
             cursor.step_key();
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(input.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, input: CI) -> CO {
+        let input_records = input.len();
+
         // Bootleg specialization, we can do filtering in-place when the input and
         // output types are `OrdZSet`. I'd prefer to do this with "real" specialization
         // of some kind, but this'll work for now
-        if TypeId::of::<CI>() == TypeId::of::<OrdZSet<CI::Key, CI::R>>()
+        let output = if TypeId::of::<CI>() == TypeId::of::<OrdZSet<CI::Key, CI::R>>()
             && TypeId::of::<CO>() == TypeId::of::<OrdZSet<CI::Key, CI::R>>()
         {
             // Safety: We've ensured that `CI` is an `OrdZSet`
@@ -394,7 +405,10 @@ where
         } else {
             // Use a generic filter implementation
             self.filter_owned_generic(input)
-        }
+        };
+
+        self.records.add(input_records, output.len());
+        output
     }
 
     // Filtering *wants* owned values, but it's not critical to performance
@@ -417,6 +431,7 @@ where
     F: 'static,
 {
     filter: F,
+    records: RecordCounts,
     _type: PhantomData<(CI, CO)>,
 }
 
@@ -427,6 +442,7 @@ where
     pub fn new(filter: F) -> Self {
         Self {
             filter,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -442,6 +458,10 @@ where
         Cow::Borrowed("FilterVals")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -477,11 +497,14 @@ where
             cursor.step_key();
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(input.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, input: CI) -> CO {
-        let mut builder = CO::Builder::with_capacity((), input.len());
+        let input_records = input.len();
+        let mut builder = CO::Builder::with_capacity((), input_records);
 
         let mut consumer = input.consumer();
         while consumer.key_valid() {
@@ -496,7 +519,9 @@ where
             }
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(input_records, output.len());
+        output
     }
 
     fn input_preference(&self) -> OwnershipPreference {
@@ -508,6 +533,7 @@ where
 /// `OrdIndexedZSet::map_index`.
 pub struct Map<CI, CO, F> {
     map: F,
+    records: RecordCounts,
     _type: PhantomData<(CI, CO)>,
 }
 
@@ -518,6 +544,7 @@ where
     pub fn new(map: F) -> Self {
         Self {
             map,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -533,6 +560,10 @@ where
         Cow::Borrowed("Map")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -557,7 +588,9 @@ where
             cursor.step_key();
         }
 
-        CO::from_tuples((), batch)
+        let output = CO::from_tuples((), batch);
+        self.records.add(i.len(), output.len());
+        output
     }
 }
 
@@ -565,6 +598,7 @@ where
 pub struct MapKeys<CI, CO, FB, FO> {
     map_borrowed: FB,
     map_owned: FO,
+    records: RecordCounts,
     _type: PhantomData<(CI, CO)>,
 }
 
@@ -573,6 +607,7 @@ impl<CI, CO, FB, FO> MapKeys<CI, CO, FB, FO> {
         Self {
             map_borrowed,
             map_owned,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -589,6 +624,10 @@ where
         Cow::Borrowed("MapKeys")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -616,11 +655,14 @@ where
             cursor.step_key();
         }
 
-        CO::from_tuples((), batch)
+        let output = CO::from_tuples((), batch);
+        self.records.add(input.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, input: CI) -> CO {
-        let mut batch = Vec::with_capacity(input.len());
+        let input_records = input.len();
+        let mut batch = Vec::with_capacity(input_records);
 
         let mut consumer = input.consumer();
         while consumer.key_valid() {
@@ -635,7 +677,9 @@ where
             }
         }
 
-        CO::from_tuples((), batch)
+        let output = CO::from_tuples((), batch);
+        self.records.add(input_records, output.len());
+        output
     }
 
     fn input_preference(&self) -> OwnershipPreference {
@@ -646,6 +690,7 @@ where
 /// Internal implementation of `flat_map` methods.
 pub struct FlatMap<CI, CO, F, I> {
     map_func: F,
+    records: RecordCounts,
     _type: PhantomData<(CI, CO, I)>,
 }
 
@@ -653,6 +698,7 @@ impl<CI, CO, F, I> FlatMap<CI, CO, F, I> {
     pub fn new(map_func: F) -> Self {
         Self {
             map_func,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -669,6 +715,10 @@ where
         Cow::Borrowed("FlatMap")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -704,7 +754,9 @@ where
             cursor.step_key();
         }
 
-        CO::from_tuples((), batch)
+        let output = CO::from_tuples((), batch);
+        self.records.add(i.len(), output.len());
+        output
     }
 }
 
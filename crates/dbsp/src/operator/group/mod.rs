@@ -17,6 +17,7 @@ use crate::{
 use std::{borrow::Cow, marker::PhantomData, ops::Neg};
 
 mod lag;
+mod rank;
 mod topk;
 
 #[cfg(test)]
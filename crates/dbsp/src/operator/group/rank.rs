@@ -0,0 +1,568 @@
+use super::{GroupTransformer, Monotonicity};
+use crate::{
+    algebra::ZRingValue,
+    trace::{
+        cursor::{CursorPair, ReverseKeyCursor},
+        Cursor,
+    },
+    DBData, DBWeight, IndexedZSet, OrdIndexedZSet, RootCircuit, Stream,
+};
+use num::ToPrimitive;
+use std::{cmp::Ordering, marker::PhantomData};
+
+impl<B> Stream<RootCircuit, B>
+where
+    B: IndexedZSet + Send,
+{
+    /// Number values in each group in ascending order.
+    ///
+    /// Implements the SQL `ROW_NUMBER()` window function.  For each key in
+    /// the input stream, outputs each associated value along with its
+    /// 1-based position in the ascending order of values.  A value with
+    /// weight `n` occurs `n` times in the output with consecutive row
+    /// numbers.  Values with non-positive weights are ignored.
+    #[allow(clippy::type_complexity)]
+    pub fn row_number_asc(&self) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+    {
+        self.group_transform(Rank::new(RankMode::RowNumber, usize::MAX, true, |_, _| {
+            false
+        }))
+    }
+
+    /// Number values in each group in descending order.
+    ///
+    /// Like [`row_number_asc`](`Self::row_number_asc`), but assigns row
+    /// numbers in the descending order of values.
+    #[allow(clippy::type_complexity)]
+    pub fn row_number_desc(
+        &self,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+    {
+        self.group_transform(Rank::new(RankMode::RowNumber, usize::MAX, false, |_, _| {
+            false
+        }))
+    }
+
+    /// Rank values in each group in ascending order.
+    ///
+    /// Implements the SQL `RANK()` window function.  For each key in the
+    /// input stream, outputs each associated value along with its rank,
+    /// equal to one plus the number of rows that precede its peers in the
+    /// ascending order of values.  Two values are peers if `rank_eq_func`
+    /// returns `true` for them.  Peers must be adjacent in the order of
+    /// values, e.g., `rank_eq_func` can compare a prefix of the value that
+    /// the value type is ordered by.
+    ///
+    /// This operator is incremental: a change to the group only causes
+    /// outputs for the suffix of the group starting from the first changed
+    /// value (or the start of its tie group) to be recomputed.
+    #[allow(clippy::type_complexity)]
+    pub fn rank_asc<EF>(
+        &self,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(RankMode::Rank, usize::MAX, true, rank_eq_func))
+    }
+
+    /// Rank values in each group in descending order.
+    ///
+    /// Like [`rank_asc`](`Self::rank_asc`), but ranks values in the
+    /// descending order.
+    #[allow(clippy::type_complexity)]
+    pub fn rank_desc<EF>(
+        &self,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(RankMode::Rank, usize::MAX, false, rank_eq_func))
+    }
+
+    /// Dense-rank values in each group in ascending order.
+    ///
+    /// Implements the SQL `DENSE_RANK()` window function.  Like
+    /// [`rank_asc`](`Self::rank_asc`), but assigns consecutive ranks to
+    /// consecutive groups of peers, i.e., the rank of a value is one plus
+    /// the number of distinct groups of peers that precede it.
+    #[allow(clippy::type_complexity)]
+    pub fn dense_rank_asc<EF>(
+        &self,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(
+            RankMode::DenseRank,
+            usize::MAX,
+            true,
+            rank_eq_func,
+        ))
+    }
+
+    /// Dense-rank values in each group in descending order.
+    ///
+    /// Like [`dense_rank_asc`](`Self::dense_rank_asc`), but ranks values in
+    /// the descending order.
+    #[allow(clippy::type_complexity)]
+    pub fn dense_rank_desc<EF>(
+        &self,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(
+            RankMode::DenseRank,
+            usize::MAX,
+            false,
+            rank_eq_func,
+        ))
+    }
+
+    /// Pick values with `k` smallest ranks in each group.
+    ///
+    /// Unlike [`topk_asc`](`Self::topk_asc`), which outputs exactly `k`
+    /// values, this operator keeps ties: it outputs all values whose
+    /// [`rank_asc`](`Self::rank_asc`) does not exceed `k`, along with their
+    /// ranks.  The output can contain more than `k` values if there are
+    /// peers at rank `k`.
+    #[allow(clippy::type_complexity)]
+    pub fn topk_rank_asc<EF>(
+        &self,
+        k: usize,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(RankMode::Rank, k, true, rank_eq_func))
+    }
+
+    /// Pick values with `k` largest ranks in each group.
+    ///
+    /// Like [`topk_rank_asc`](`Self::topk_rank_asc`), but ranks values in
+    /// the descending order.
+    #[allow(clippy::type_complexity)]
+    pub fn topk_rank_desc<EF>(
+        &self,
+        k: usize,
+        rank_eq_func: EF,
+    ) -> Stream<RootCircuit, OrdIndexedZSet<B::Key, (B::Val, i64), B::R>>
+    where
+        B::R: ZRingValue + ToPrimitive,
+        EF: Fn(&B::Val, &B::Val) -> bool + 'static,
+    {
+        self.group_transform(Rank::new(RankMode::Rank, k, false, rank_eq_func))
+    }
+}
+
+/// Ranking function computed by the [`Rank`] transformer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum RankMode {
+    /// Consecutive numbers; peers are numbered in the order of values.
+    RowNumber,
+    /// Peers share the same rank; there are gaps in ranks after peers.
+    Rank,
+    /// Peers share the same rank; there are no gaps in ranks.
+    DenseRank,
+}
+
+/// Implements `row_number`, `rank`, `dense_rank` and `topk_rank` operators.
+///
+/// The ranks of values in a group only depend on values that precede them.
+/// Given a change to the group, the transformer finds the earliest position
+/// whose rank can be affected by the change, reads the ranking state at this
+/// position from the output trace, and recomputes outputs for the suffix of
+/// the group that follows it, merging them with retractions of the previous
+/// outputs for the same suffix.
+struct Rank<I, R, EF> {
+    name: String,
+    mode: RankMode,
+    /// Largest rank to output.
+    k: i64,
+    /// `true` for ascending order, `false` for descending order.
+    asc: bool,
+    /// Returns `true` for peers.
+    rank_eq_func: EF,
+    _phantom: PhantomData<(I, R)>,
+}
+
+/// State of the ranking computation at a position in the group.
+struct RankState<I> {
+    /// Number of rows that precede the current position.
+    count: i64,
+    /// Rank of the current group of peers.
+    rank: i64,
+    /// The last value with positive weight; used to detect peers.
+    prev: Option<I>,
+}
+
+impl<I> RankState<I> {
+    fn new(count: i64, rank: i64) -> Self {
+        Self {
+            count,
+            rank,
+            prev: None,
+        }
+    }
+}
+
+impl<I, R, EF> Rank<I, R, EF>
+where
+    I: DBData,
+    R: DBWeight + ZRingValue + ToPrimitive,
+    EF: Fn(&I, &I) -> bool + 'static,
+{
+    fn new(mode: RankMode, k: usize, asc: bool, rank_eq_func: EF) -> Self {
+        let mode_name = match mode {
+            RankMode::RowNumber => "row_number",
+            RankMode::Rank => "rank",
+            RankMode::DenseRank => "dense_rank",
+        };
+        let order = if asc { "asc" } else { "desc" };
+        let name = if k == usize::MAX {
+            format!("{mode_name}-{order}")
+        } else {
+            format!("top-{k}-{mode_name}-{order}")
+        };
+
+        Self {
+            name,
+            mode,
+            k: k.try_into().unwrap_or(i64::MAX),
+            asc,
+            rank_eq_func,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Compare values in the order in which the transformer ranks them.
+    fn cmp(&self, v1: &I, v2: &I) -> Ordering {
+        if self.asc {
+            v1.cmp(v2)
+        } else {
+            v2.cmp(v1)
+        }
+    }
+
+    /// Compare output tuples in the order in which the transformer produces
+    /// them.
+    fn output_cmp(&self, o1: &(I, i64), o2: &(I, i64)) -> Ordering {
+        if self.asc {
+            o1.cmp(o2)
+        } else {
+            o2.cmp(o1)
+        }
+    }
+
+    /// Find the position from which outputs must be recomputed after a change
+    /// to the group, the earliest changed value being `first`.
+    ///
+    /// Returns `None` if the change cannot affect the output, because all
+    /// values starting from `first` have ranks larger than `k`.  Otherwise,
+    /// returns the value to start recomputing from (`None` for the start of
+    /// the group) along with the ranking state at this value.
+    ///
+    /// Cursors iterate over values in the order in which they are ranked.
+    fn resume_point<C2, C3>(
+        &self,
+        first: &I,
+        input_trace: &mut C2,
+        output_trace: &mut C3,
+    ) -> Option<(Option<I>, RankState<I>)>
+    where
+        C2: Cursor<I, (), (), R>,
+        C3: Cursor<(I, i64), (), (), R>,
+    {
+        // Find the last value that precedes `first`.
+        input_trace.fast_forward_keys();
+        input_trace.seek_key_with_reverse(|v| self.cmp(v, first) == Ordering::Less);
+        skip_non_positive_reverse(input_trace);
+
+        if !input_trace.key_valid() {
+            return Some((None, RankState::new(0, 0)));
+        }
+
+        let prev = input_trace.key().clone();
+
+        if self.mode == RankMode::RowNumber {
+            // Row numbers of values preceding `first` don't change.  Resume
+            // from `first`, with the largest row number assigned to `prev`.
+            output_trace.seek_key_with(|(v, _)| self.cmp(v, &prev) != Ordering::Less);
+
+            let mut count = None;
+            while output_trace.key_valid() && output_trace.key().0 == prev {
+                if !output_trace.weight().is_zero() {
+                    count = count.max(Some(output_trace.key().1));
+                }
+                output_trace.step_key();
+            }
+
+            match count {
+                Some(count) if count < self.k => {
+                    Some((Some(first.clone()), RankState::new(count, 0)))
+                }
+                _ => None,
+            }
+        } else {
+            // The rank of `first` may depend on the group of peers of `prev`.
+            // Resume from the first value in this group, whose rank doesn't
+            // change.
+            let mut from = prev.clone();
+
+            input_trace.step_key_reverse();
+            skip_non_positive_reverse(input_trace);
+            while input_trace.key_valid() && (self.rank_eq_func)(input_trace.key(), &prev) {
+                from = input_trace.key().clone();
+                input_trace.step_key_reverse();
+                skip_non_positive_reverse(input_trace);
+            }
+
+            output_trace.seek_key_with(|(v, _)| self.cmp(v, &from) != Ordering::Less);
+            while output_trace.key_valid() && output_trace.key().0 == from {
+                if !output_trace.weight().is_zero() {
+                    // Ranks are computed when entering a new group of peers:
+                    // initialize the state as of the end of the previous group.
+                    let rank = output_trace.key().1;
+                    return Some((Some(from), RankState::new(rank - 1, rank - 1)));
+                }
+                output_trace.step_key();
+            }
+
+            // `from` is not in the output, because its rank exceeds `k`.
+            None
+        }
+    }
+
+    /// Recompute outputs starting from value `from` (or the start of the
+    /// group if `from` is `None`).
+    ///
+    /// Merges new outputs with retractions of old outputs in the output trace
+    /// for the same suffix of the group.
+    ///
+    /// # Arguments
+    ///
+    /// * `input_cursor` - cursor over the new contents of the input group.
+    /// * `output_trace` - cursor over the old contents of the output group.
+    fn compute_updates<C1, C3, CB>(
+        &self,
+        from: Option<I>,
+        mut state: RankState<I>,
+        input_cursor: &mut C1,
+        output_trace: &mut C3,
+        mut output_cb: CB,
+    ) where
+        C1: Cursor<I, (), (), R>,
+        C3: Cursor<(I, i64), (), (), R>,
+        CB: FnMut((I, i64), R),
+    {
+        input_cursor.rewind_keys();
+        output_trace.rewind_keys();
+
+        if let Some(from) = &from {
+            input_cursor.seek_key_with(|v| self.cmp(v, from) != Ordering::Less);
+            output_trace.seek_key_with(|(v, _)| self.cmp(v, from) != Ordering::Less);
+        }
+
+        while input_cursor.key_valid() {
+            let w = input_cursor.weight();
+
+            if !w.le0() {
+                let val = input_cursor.key().clone();
+                let n = w.to_i64().unwrap_or(i64::MAX);
+
+                match self.mode {
+                    RankMode::RowNumber => {
+                        let rows = state.count + 1..=(state.count.saturating_add(n)).min(self.k);
+
+                        // Produce outputs in the same order as the output trace.
+                        if self.asc {
+                            for row in rows {
+                                self.push_output(
+                                    output_trace,
+                                    (val.clone(), row),
+                                    R::one(),
+                                    &mut output_cb,
+                                );
+                            }
+                        } else {
+                            for row in rows.rev() {
+                                self.push_output(
+                                    output_trace,
+                                    (val.clone(), row),
+                                    R::one(),
+                                    &mut output_cb,
+                                );
+                            }
+                        }
+                    }
+                    RankMode::Rank | RankMode::DenseRank => {
+                        let new_group = match &state.prev {
+                            None => true,
+                            Some(prev) => !(self.rank_eq_func)(prev, &val),
+                        };
+
+                        if new_group {
+                            state.rank = if self.mode == RankMode::Rank {
+                                state.count + 1
+                            } else {
+                                state.rank + 1
+                            };
+                        }
+
+                        if state.rank > self.k {
+                            break;
+                        }
+
+                        self.push_output(
+                            output_trace,
+                            (val.clone(), state.rank),
+                            w,
+                            &mut output_cb,
+                        );
+                        state.prev = Some(val);
+                    }
+                }
+
+                state.count = state.count.saturating_add(n);
+
+                // Peers of the last value can still have ranks not exceeding
+                // `k`, so only row numbering can stop here.
+                if self.mode == RankMode::RowNumber && state.count >= self.k {
+                    break;
+                }
+            }
+
+            input_cursor.step_key();
+        }
+
+        // Retract remaining old outputs.
+        while output_trace.key_valid() {
+            let w = output_trace.weight();
+            if !w.is_zero() {
+                output_cb(output_trace.key().clone(), w.neg());
+            }
+            output_trace.step_key();
+        }
+    }
+
+    /// Output `(output, w)`, preceded by retractions of all old outputs in
+    /// `output_trace` that precede it.
+    fn push_output<C3, CB>(&self, output_trace: &mut C3, output: (I, i64), w: R, output_cb: &mut CB)
+    where
+        C3: Cursor<(I, i64), (), (), R>,
+        CB: FnMut((I, i64), R),
+    {
+        while output_trace.key_valid()
+            && self.output_cmp(output_trace.key(), &output) == Ordering::Less
+        {
+            let old_w = output_trace.weight();
+            if !old_w.is_zero() {
+                output_cb(output_trace.key().clone(), old_w.neg());
+            }
+            output_trace.step_key();
+        }
+
+        if output_trace.key_valid() && output_trace.key() == &output {
+            let w = w + output_trace.weight().neg();
+            if !w.is_zero() {
+                output_cb(output, w);
+            }
+            output_trace.step_key();
+        } else {
+            output_cb(output, w);
+        }
+    }
+}
+
+impl<I, R, EF> GroupTransformer<I, (I, i64), R> for Rank<I, R, EF>
+where
+    I: DBData,
+    R: DBWeight + ZRingValue + ToPrimitive,
+    EF: Fn(&I, &I) -> bool + 'static,
+{
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn monotonicity(&self) -> Monotonicity {
+        if self.asc {
+            Monotonicity::Ascending
+        } else {
+            Monotonicity::Descending
+        }
+    }
+
+    fn transform<C1, C2, C3, CB>(
+        &mut self,
+        input_delta: &mut C1,
+        input_trace: &mut C2,
+        output_trace: &mut C3,
+        output_cb: CB,
+    ) where
+        C1: Cursor<I, (), (), R>,
+        C2: Cursor<I, (), (), R>,
+        C3: Cursor<(I, i64), (), (), R>,
+        CB: FnMut((I, i64), R),
+    {
+        if self.asc {
+            let first = match input_delta.get_key() {
+                Some(first) => first.clone(),
+                None => return,
+            };
+
+            if let Some((from, state)) = self.resume_point(&first, input_trace, output_trace) {
+                self.compute_updates(
+                    from,
+                    state,
+                    &mut CursorPair::new(input_delta, input_trace),
+                    output_trace,
+                    output_cb,
+                );
+            }
+        } else {
+            let first = match ReverseKeyCursor::new(input_delta).get_key() {
+                Some(first) => first.clone(),
+                None => return,
+            };
+
+            if let Some((from, state)) = self.resume_point(
+                &first,
+                &mut ReverseKeyCursor::new(input_trace),
+                &mut ReverseKeyCursor::new(output_trace),
+            ) {
+                self.compute_updates(
+                    from,
+                    state,
+                    &mut ReverseKeyCursor::new(&mut CursorPair::new(input_delta, input_trace)),
+                    &mut ReverseKeyCursor::new(output_trace),
+                    output_cb,
+                );
+            }
+        }
+    }
+}
+
+fn skip_non_positive_reverse<C, I, R>(cursor: &mut C)
+where
+    C: Cursor<I, (), (), R>,
+    R: ZRingValue,
+{
+    while cursor.key_valid() && cursor.weight().le0() {
+        cursor.step_key_reverse();
+    }
+}
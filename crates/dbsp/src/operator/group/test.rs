@@ -126,6 +126,86 @@ where
     }
 }
 
+impl TestBatch<i32, i32, (), i32> {
+    /// Values with positive weights in each group, in ascending or descending
+    /// order.
+    fn positive_groups(&self, asc: bool) -> Vec<(i32, Vec<(i32, i32)>)> {
+        let mut result = Vec::new();
+        let mut cursor = self.cursor();
+
+        while cursor.key_valid() {
+            let mut vals = Vec::new();
+
+            while cursor.val_valid() {
+                let w = cursor.weight();
+                if w > 0 {
+                    vals.push((*cursor.val(), w));
+                }
+                cursor.step_val();
+            }
+
+            if !asc {
+                vals.reverse();
+            }
+
+            result.push((*cursor.key(), vals));
+            cursor.step_key();
+        }
+
+        result
+    }
+
+    fn row_number(&self, asc: bool) -> TestBatch<i32, (i32, i64), (), i32> {
+        let mut result = Vec::new();
+
+        for (k, vals) in self.positive_groups(asc) {
+            let mut row = 0;
+            for (v, w) in vals {
+                for _ in 0..w {
+                    row += 1;
+                    result.push(((k, (v, row), ()), 1));
+                }
+            }
+        }
+
+        TestBatch::from_data(&result)
+    }
+
+    fn rank(
+        &self,
+        asc: bool,
+        dense: bool,
+        k: i64,
+        rank_eq: fn(&i32, &i32) -> bool,
+    ) -> TestBatch<i32, (i32, i64), (), i32> {
+        let mut result = Vec::new();
+
+        for (key, vals) in self.positive_groups(asc) {
+            let mut count = 0;
+            let mut rank = 0;
+            let mut prev = None;
+
+            for (v, w) in vals {
+                if prev.map_or(true, |prev| !rank_eq(&prev, &v)) {
+                    rank = if dense { rank + 1 } else { count + 1 };
+                }
+                if rank > k {
+                    break;
+                }
+                result.push(((key, (v, rank), ()), w));
+                count += w as i64;
+                prev = Some(v);
+            }
+        }
+
+        TestBatch::from_data(&result)
+    }
+}
+
+fn rank_eq(v1: &i32, v2: &i32) -> bool {
+    v1 / 10 == v2 / 10
+}
+
 fn topk_test_circuit(
     circuit: &mut RootCircuit,
 ) -> AnyResult<(
@@ -167,6 +247,29 @@ fn lead_test_circuit(
     Ok((input_handle, lead_handle))
 }
 
+#[allow(clippy::type_complexity)]
+fn rank_test_circuit(
+    circuit: &mut RootCircuit,
+) -> AnyResult<(
+    CollectionHandle<i32, (i32, i32)>,
+    Vec<OutputHandle<OrdIndexedZSet<i32, (i32, i64), i32>>>,
+)> {
+    let (input_stream, input_handle) = circuit.add_input_indexed_zset::<i32, i32, i32>();
+
+    let handles = vec![
+        input_stream.row_number_asc().integrate().output(),
+        input_stream.row_number_desc().integrate().output(),
+        input_stream.rank_asc(rank_eq).integrate().output(),
+        input_stream.rank_desc(rank_eq).integrate().output(),
+        input_stream.dense_rank_asc(rank_eq).integrate().output(),
+        input_stream.dense_rank_desc(rank_eq).integrate().output(),
+        input_stream.topk_rank_asc(5, rank_eq).integrate().output(),
+        input_stream.topk_rank_desc(5, rank_eq).integrate().output(),
+    ];
+
+    Ok((input_handle, handles))
+}
+
 proptest! {
     #[test]
     fn test_topk(trace in input_trace(5, 1_000, 200, 20)) {
@@ -243,4 +346,38 @@ proptest! {
             assert_batch_eq(&lead_result, &ref_lead);
         }
     }
+
+    #[test]
+    fn test_rank(trace in input_trace(5, 100, 200, 20)) {
+        let (mut dbsp, (input_handle, handles)) = Runtime::init_circuit(4, rank_test_circuit).unwrap();
+
+        let mut ref_trace = TestBatch::new(None);
+
+        for batch in trace.into_iter() {
+            let records = batch.iter().map(|(k, v, r)| ((*k, *v, ()), *r)).collect::<Vec<_>>();
+
+            let ref_batch = TestBatch::from_data(&records);
+            ref_trace.insert(ref_batch);
+
+            for (k, v, r) in batch.into_iter() {
+                input_handle.push(k, (v, r));
+            }
+            dbsp.step().unwrap();
+
+            let expected = [
+                ref_trace.row_number(true),
+                ref_trace.row_number(false),
+                ref_trace.rank(true, false, i64::MAX, rank_eq),
+                ref_trace.rank(false, false, i64::MAX, rank_eq),
+                ref_trace.rank(true, true, i64::MAX, rank_eq),
+                ref_trace.rank(false, true, i64::MAX, rank_eq),
+                ref_trace.rank(true, false, 5, rank_eq),
+                ref_trace.rank(false, false, 5, rank_eq),
+            ];
+
+            for (handle, expected) in handles.iter().zip(expected.iter()) {
+                assert_batch_eq(&handle.consolidate(), expected);
+            }
+        }
+    }
 }
@@ -2,6 +2,7 @@
 
 use crate::{
     circuit::{
+        metadata::RecordCounts,
         operator_traits::{Operator, UnaryOperator},
         Circuit, GlobalNodeId, OwnershipPreference, Scope, Stream,
     },
@@ -92,12 +93,16 @@ where
 /// * `CI` - input batch type.
 /// * `CO` - output batch type.
 pub struct Index<CI, CO> {
+    records: RecordCounts,
     _type: PhantomData<(CI, CO)>,
 }
 
 impl<CI, CO> Index<CI, CO> {
     pub fn new() -> Self {
-        Self { _type: PhantomData }
+        Self {
+            records: RecordCounts::default(),
+            _type: PhantomData,
+        }
     }
 }
 
@@ -115,6 +120,9 @@ where
     fn name(&self) -> Cow<'static, str> {
         Cow::from("Index")
     }
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -137,11 +145,14 @@ where
             cursor.step_key();
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(input.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, input: CI) -> CO {
-        let mut builder = <CO as Batch>::Builder::with_capacity((), input.len());
+        let input_records = input.len();
+        let mut builder = <CO as Batch>::Builder::with_capacity((), input_records);
 
         let mut consumer = input.consumer();
         while consumer.key_valid() {
@@ -153,7 +164,9 @@ where
             builder.push((CO::item_from(key, value), weight));
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(input_records, output.len());
+        output
     }
 
     fn input_preference(&self) -> OwnershipPreference {
@@ -177,6 +190,7 @@ where
 ///   value) pair.
 pub struct IndexWith<CI, CO, F> {
     index_func: F,
+    records: RecordCounts,
     _type: PhantomData<(CI, CO)>,
 }
 
@@ -184,6 +198,7 @@ impl<CI, CO, F> IndexWith<CI, CO, F> {
     pub fn new(index_func: F) -> Self {
         Self {
             index_func,
+            records: RecordCounts::default(),
             _type: PhantomData,
         }
     }
@@ -199,6 +214,10 @@ where
         Cow::from("IndexWith")
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -222,7 +241,9 @@ where
             cursor.step_key();
         }
 
-        CO::from_tuples((), tuples)
+        let output = CO::from_tuples((), tuples);
+        self.records.add(i.len(), output.len());
+        output
     }
 
     fn eval_owned(&mut self, i: CI) -> CO {
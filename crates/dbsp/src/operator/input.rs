@@ -5,7 +5,8 @@ use crate::{
         LocalStoreMarker, RootCircuit, Scope,
     },
     default_hash,
-    trace::Batch,
+    operator::communication::reshard,
+    trace::{Batch, BatchReader},
     Circuit, DBData, DBWeight, OrdIndexedZSet, OrdZSet, Runtime, Stream,
 };
 use std::{
@@ -494,6 +495,8 @@ where
 struct InputHandleInternal<T> {
     mailbox: Vec<Mailbox<T>>,
     offset: usize,
+    /// Number of workers in the runtime, across all hosts.
+    num_workers: usize,
 }
 
 impl<T> InputHandleInternal<T>
@@ -501,12 +504,13 @@ where
     T: Default + Clone,
 {
     // Returns a new `InputHandleInternal` for workers with indexes in the range
-    // of `workers`.
-    fn new(workers: Range<usize>) -> Self {
+    // of `workers`, out of `num_workers` workers.
+    fn new(workers: Range<usize>, num_workers: usize) -> Self {
         assert!(!workers.is_empty());
         Self {
             mailbox: workers.clone().map(|_| Mailbox::new()).collect(),
             offset: workers.start,
+            num_workers,
         }
     }
 
@@ -564,7 +568,7 @@ where
 {
     fn new() -> Self {
         match Runtime::runtime() {
-            None => Self(Arc::new(InputHandleInternal::new(0..1))),
+            None => Self(Arc::new(InputHandleInternal::new(0..1, 1))),
             Some(runtime) => {
                 let input_id = runtime.sequence_next(Runtime::worker_index());
 
@@ -574,6 +578,7 @@ where
                     .or_insert_with(|| {
                         Self(Arc::new(InputHandleInternal::new(
                             runtime.layout().local_workers(),
+                            runtime.num_workers(),
                         )))
                     })
                     .value()
@@ -618,6 +623,41 @@ where
     }
 }
 
+impl<B> InputHandle<B>
+where
+    B: Batch<Time = ()> + Default,
+{
+    /// Write the state of a sharded collection, saved from a runtime with
+    /// any number of workers, to the mailboxes of this runtime's workers.
+    ///
+    /// `shards` contains the per-worker state of a collection sharded with
+    /// [`Stream::shard`], e.g., the integral of a sharded stream read with
+    /// [`OutputHandle::take_from_all`](`crate::OutputHandle::take_from_all`).
+    /// The state is re-partitioned across the workers of this runtime with
+    /// [`reshard`], and each worker on this host receives the tuples that
+    /// `shard` assigns to it, overwriting any previous value in its mailbox.
+    ///
+    /// This lets a circuit restarted with more or fewer workers pick up the
+    /// state of the previous circuit in a single clock cycle instead of
+    /// reprocessing its input history.  Apply [`Stream::mark_sharded`] to
+    /// the input stream, so that downstream operators don't shard the state
+    /// again.
+    pub fn set_resharded<IB>(&self, shards: &[IB])
+    where
+        IB: BatchReader<Key = B::Key, Val = B::Val, Time = (), R = B::R>,
+    {
+        let workers = self.workers();
+        for (worker, partition) in reshard::<IB, B>(shards, self.0.num_workers)
+            .into_iter()
+            .enumerate()
+        {
+            if workers.contains(&worker) {
+                self.set_for_worker(worker, partition);
+            }
+        }
+    }
+}
+
 /// A handle used to write data to an input stream created by
 /// [`add_input_zset`](`RootCircuit::add_input_zset`),
 /// and [`add_input_indexed_zset`](`RootCircuit::add_input_indexed_zset`)
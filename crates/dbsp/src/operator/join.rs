@@ -3,7 +3,7 @@
 use crate::{
     algebra::{IndexedZSet, Lattice, MulByRef, PartialOrder, ZRingValue, ZSet},
     circuit::{
-        metadata::{MetaItem, OperatorLocation, OperatorMeta},
+        metadata::{MemoryUsage, MetaItem, OperatorLocation, OperatorMeta, RecordCounts},
         operator_traits::{BinaryOperator, Operator},
         Circuit, GlobalNodeId, RootCircuit, Scope, Stream, WithClock,
     },
@@ -399,6 +399,7 @@ where
 pub struct Join<F, I1, I2, Z> {
     join_func: F,
     location: &'static Location<'static>,
+    records: RecordCounts,
     _types: PhantomData<(I1, I2, Z)>,
 }
 
@@ -407,6 +408,7 @@ impl<F, I1, I2, Z> Join<F, I1, I2, Z> {
         Self {
             join_func,
             location,
+            records: RecordCounts::default(),
             _types: PhantomData,
         }
     }
@@ -427,6 +429,10 @@ where
         Some(self.location)
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -476,7 +482,9 @@ where
             }
         }
 
-        Z::from_keys((), batch)
+        let output = Z::from_keys((), batch);
+        self.records.add(i1.len() + i2.len(), output.len());
+        output
     }
 
     // TODO: Impls using consumers
@@ -485,6 +493,7 @@ where
 pub struct MonotonicJoin<F, I1, I2, Z> {
     join_func: F,
     location: &'static Location<'static>,
+    records: RecordCounts,
     _types: PhantomData<(I1, I2, Z)>,
 }
 
@@ -493,6 +502,7 @@ impl<F, I1, I2, Z> MonotonicJoin<F, I1, I2, Z> {
         Self {
             join_func,
             location,
+            records: RecordCounts::default(),
             _types: PhantomData,
         }
     }
@@ -513,6 +523,10 @@ where
         Some(self.location)
     }
 
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(self.records)
+    }
+
     fn fixedpoint(&self, _scope: Scope) -> bool {
         true
     }
@@ -562,7 +576,9 @@ where
             }
         }
 
-        builder.done()
+        let output = builder.done();
+        self.records.add(i1.len() + i2.len(), output.len());
+        output
     }
 }
 
@@ -678,14 +694,7 @@ where
                 .collect(),
         );
 
-        let bytes = {
-            let mut context = Context::new();
-            for batcher in self.output_batchers.values() {
-                batcher.size_of_with_context(&mut context);
-            }
-
-            context.size_of()
-        };
+        let bytes = self.memory_usage().unwrap_or_default();
 
         // Find the percentage of consolidated outputs
         let mut output_redundancy = ((self.stats.output_tuples as f64
@@ -701,9 +710,9 @@ where
         meta.extend(metadata! {
             "total size" => total_size,
             "batch sizes" => batch_sizes,
-            "used bytes" => MetaItem::bytes(bytes.used_bytes()),
-            "allocations" => bytes.distinct_allocations(),
-            "shared bytes" => MetaItem::bytes(bytes.shared_bytes()),
+            "used bytes" => MetaItem::bytes(bytes.used_bytes),
+            "allocations" => bytes.allocations,
+            "shared bytes" => MetaItem::bytes(bytes.shared_bytes),
             "left inputs" => self.stats.lhs_tuples,
             "right inputs" => self.stats.rhs_tuples,
             "computed outputs" => self.stats.output_tuples,
@@ -712,6 +721,27 @@ where
         });
     }
 
+    // The right input is the integral of the other side of the join, which we
+    // don't count as input records.
+    fn record_counts(&self) -> Option<RecordCounts> {
+        Some(RecordCounts {
+            input_records: self.stats.lhs_tuples,
+            output_records: self.stats.produced_tuples,
+        })
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let mut context = Context::new();
+        for batcher in self.output_batchers.values() {
+            batcher.size_of_with_context(&mut context);
+        }
+
+        Some(MemoryUsage::new(
+            context.total_size(),
+            self.output_batchers.len(),
+        ))
+    }
+
     fn fixedpoint(&self, scope: Scope) -> bool {
         let epoch_end = self.clock.time().epoch_end(scope);
         // We're in a stable state if input and output at the current clock cycle are
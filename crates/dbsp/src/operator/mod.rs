@@ -10,6 +10,7 @@ pub(crate) mod inspect;
 pub(crate) mod upsert;
 
 mod aggregate;
+mod asof_join;
 mod condition;
 mod consolidate;
 mod count;
@@ -40,7 +41,11 @@ mod z1;
 
 #[cfg(feature = "with-csv")]
 pub use self::csv::CsvSource;
-pub use aggregate::{Aggregator, Avg, Fold, Max, MaxSemigroup, Min, MinSemigroup};
+pub use aggregate::{
+    Aggregator, ApproxCountDistinct, ApproxPercentile, ArgMax, ArgMin, Avg, Centroid, Fold,
+    HyperLogLog, HyperLogLogSemigroup, IndexedAggregator, Max, MaxSemigroup, Min, MinSemigroup,
+    TDigest, TDigestSemigroup,
+};
 pub use apply::Apply;
 pub use condition::Condition;
 pub use delta0::Delta0;
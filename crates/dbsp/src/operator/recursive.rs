@@ -297,9 +297,13 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
+        circuit::{
+            circuit_builder::Node,
+            metadata::{MetaItem, OperatorMeta},
+        },
         operator::{FilterMap, Generator},
         trace::ord::OrdZSet,
-        zset, Circuit, RootCircuit, Stream,
+        zset, Circuit, CircuitHandle, IterationLimits, RootCircuit, SchedulerError, Stream,
     };
     use std::vec;
 
@@ -433,4 +437,99 @@ mod test {
             root.step().unwrap();
         }
     }
+
+    // Recursive query that never reaches a fixed point: counts up from 0.
+    fn counter(limits: IterationLimits) -> (CircuitHandle, RootCircuit) {
+        RootCircuit::build(move |circuit| {
+            let seed = circuit.add_source(Generator::new(|| zset! { 0usize => 1 }));
+
+            circuit
+                .recursive(|child, counts: Stream<_, OrdZSet<usize, isize>>| {
+                    child.set_iteration_limits(limits);
+                    let seed = seed.delta0(child);
+                    Ok(seed.plus(&counts.map(|x| x + 1)))
+                })
+                .unwrap();
+
+            Ok(circuit.clone())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn iteration_limit() {
+        let (root, _) = counter(IterationLimits::new().with_max_iterations(100));
+
+        match root.step() {
+            Err(SchedulerError::IterationLimitExceeded { iterations, .. }) => {
+                assert_eq!(iterations, 100)
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn divergent_iteration() {
+        let (root, _) = counter(
+            IterationLimits::new()
+                .with_max_growth(2.0)
+                .with_growth_check_interval(16)
+                .with_max_iterations(100_000),
+        );
+
+        match root.step() {
+            Err(SchedulerError::DivergentIteration {
+                iterations,
+                used_bytes,
+                ..
+            }) => {
+                assert!(iterations > 16);
+                assert!(used_bytes > 0);
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+    }
+
+    // Iteration counts are reported as subcircuit metadata.
+    #[test]
+    fn iteration_metadata() {
+        let (root, circuit) = RootCircuit::build(move |circuit| {
+            let edges = circuit.add_source(Generator::new(|| zset! { (1, 2) => 1, (2, 3) => 1 }));
+
+            circuit
+                .recursive(|child, paths: Stream<_, OrdZSet<(usize, usize), isize>>| {
+                    let edges = edges.delta0(child);
+
+                    let paths_indexed = paths.index_with(|&(x, y)| (y, x));
+                    let edges_indexed = edges.index();
+
+                    Ok(edges
+                        .plus(&paths_indexed.join(&edges_indexed, |_via, from, to| (*from, *to))))
+                })
+                .unwrap();
+
+            Ok(circuit.clone())
+        })
+        .unwrap();
+
+        for _ in 0..3 {
+            root.step().unwrap();
+        }
+
+        let mut meta = OperatorMeta::new();
+        circuit.map_nodes_recursive(&mut |node: &dyn Node| {
+            if node.name() == "Subcircuit" {
+                node.metadata(&mut meta);
+            }
+        });
+
+        let item = |label: &str| {
+            meta.iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, item)| item)
+                .unwrap()
+        };
+        assert!(matches!(item("epochs"), MetaItem::Int(3)));
+        assert!(matches!(item("max iterations"), MetaItem::Int(n) if *n > 1));
+    }
 }
@@ -1,8 +1,13 @@
 use crate::{
-    operator::communication::new_exchange_operators,
-    trace::{cursor::Cursor, BatchReader, Rkyv},
-    Circuit, NumEntries, RootCircuit, Runtime, Stream,
+    algebra::{AddByRef, ZRingValue},
+    operator::{
+        communication::new_exchange_operators,
+        trace::{TraceBounds, TraceFeedback},
+    },
+    trace::{cursor::Cursor, Batch, BatchReader, Builder, Rkyv, Spine},
+    Circuit, DBData, NumEntries, RootCircuit, Runtime, Stream,
 };
+use num::PrimInt;
 use size_of::SizeOf;
 use std::{cmp::max, panic::Location};
 
@@ -76,9 +81,178 @@ where
     }
 }
 
+impl<B> Stream<RootCircuit, B>
+where
+    B: Batch<Time = ()>,
+{
+    /// Split a time series into on-time and late records based on a
+    /// watermark.
+    ///
+    /// A record is late if its event time, computed by applying `ts_func` to
+    /// its key, is below the lower bound computed by
+    /// [`lateness_bound`](`Stream::lateness_bound`) from `watermark` and
+    /// `allowed_lateness`, i.e., below the largest watermark observed so
+    /// far minus `allowed_lateness`.  Because this bound only grows, the
+    /// on-time output stream keeps the following promise: once the bound
+    /// reaches `b`, the stream never contains insertions with timestamps
+    /// smaller than `b` (see below for retractions).  Downstream operators
+    /// that take a lower bound on input timestamps, e.g.,
+    /// [`partitioned_rolling_aggregate_with_watermark`](`Stream::partitioned_rolling_aggregate_with_watermark`),
+    /// can use `watermark.lateness_bound(allowed_lateness)` as this bound for
+    /// the on-time stream, at the cost of ignoring retractions below the
+    /// bound.
+    ///
+    /// The bound at each step is computed from `watermark` at the same step,
+    /// so records that advance the watermark are compared against the new
+    /// watermark.
+    ///
+    /// Retractions are routed by the timeliness of the record they retract:
+    /// a retraction of a record that was output as on-time goes to the
+    /// on-time stream even if it arrives after the bound has passed the
+    /// record's timestamp, so that the integral of the on-time stream never
+    /// holds records that were deleted from the input.  Only the part of a
+    /// retraction that exceeds the weight of the record in the on-time stream
+    /// is late.  Consequently, the on-time stream may contain retractions
+    /// below the current bound, but never insertions.  To route retractions,
+    /// the operator keeps an integral of the on-time stream.
+    ///
+    /// Returns a pair of streams `(on_time, late)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `watermark` - watermark of the time series, e.g., computed by
+    ///   [`watermark_monotonic`](`Stream::watermark_monotonic`).
+    /// * `allowed_lateness` - how far behind the watermark a record can be
+    ///   and still be considered on-time.
+    /// * `ts_func` - extracts event time from a record.
+    #[allow(clippy::type_complexity)]
+    pub fn split_late<TS, F>(
+        &self,
+        watermark: &Stream<RootCircuit, TS>,
+        allowed_lateness: TS,
+        ts_func: F,
+    ) -> (Stream<RootCircuit, B>, Stream<RootCircuit, B>)
+    where
+        TS: DBData + PrimInt + NumEntries,
+        F: Fn(&B::Key) -> TS + 'static,
+        B::R: ZRingValue,
+        Spine<B>: SizeOf,
+    {
+        self.circuit().region("split_late", || {
+            let bound = watermark.lateness_bound(allowed_lateness);
+
+            // A retraction must be routed by the same worker that saw the
+            // record it retracts.
+            let stream = self.shard();
+
+            let feedback = self
+                .circuit()
+                .add_integrate_trace_feedback::<Spine<B>>(TraceBounds::unbounded());
+
+            let split = stream.apply3(
+                &bound,
+                &feedback.delayed_trace,
+                move |batch, bound, trace| {
+                    split_updates(&batch, &trace, |key| ts_func(key) >= *bound)
+                },
+            );
+
+            // Late records are usually rare, so we clone them and let the
+            // on-time projection take ownership of the pair.
+            let late = split.apply(|(_, late)| late.clone());
+            late.mark_sharded_if(&stream);
+
+            let on_time = split.apply_owned(|(on_time, _)| on_time);
+            on_time.mark_sharded_if(&stream);
+            feedback.connect(&on_time);
+
+            (on_time, late)
+        })
+    }
+}
+
+impl<TS> Stream<RootCircuit, TS>
+where
+    TS: DBData + PrimInt + NumEntries,
+{
+    /// Compute a monotonically growing lower bound on on-time event
+    /// timestamps from a watermark stream and an allowed lateness.
+    ///
+    /// The output at each step is the largest value of `watermark -
+    /// allowed_lateness` observed so far (saturating at the smallest value of
+    /// `TS`).  See [`split_late`](`Stream::split_late`).
+    pub fn lateness_bound(&self, allowed_lateness: TS) -> Stream<RootCircuit, TS> {
+        self.stream_fold(TS::min_value(), move |bound, watermark| {
+            max(bound, watermark.saturating_sub(allowed_lateness))
+        })
+    }
+}
+
+/// Splits `batch` into on-time and late updates.
+///
+/// Updates to keys that satisfy `on_time` are on-time.  For other keys, only
+/// retractions of records accumulated in `on_time_trace` are on-time, up to
+/// the weight of the record in the trace; the rest is late.
+fn split_updates<B, P>(batch: &B, on_time_trace: &Spine<B>, on_time: P) -> (B, B)
+where
+    B: Batch<Time = ()>,
+    B::R: ZRingValue,
+    P: Fn(&B::Key) -> bool,
+{
+    let mut on_time_builder = B::Builder::with_capacity((), batch.len());
+    let mut late_builder = B::Builder::with_capacity((), 0);
+    let mut cursor = batch.cursor();
+    let mut trace_cursor = on_time_trace.cursor();
+
+    while cursor.key_valid() {
+        let key_on_time = on_time(cursor.key());
+        if !key_on_time {
+            trace_cursor.seek_key(cursor.key());
+        }
+        let in_trace =
+            !key_on_time && trace_cursor.key_valid() && trace_cursor.key() == cursor.key();
+
+        while cursor.val_valid() {
+            let weight = cursor.weight();
+            let item = || B::item_from(cursor.key().clone(), cursor.val().clone());
+
+            if key_on_time {
+                on_time_builder.push((item(), weight));
+            } else {
+                // Weight of the record in the on-time stream.
+                let mut on_time_weight = None;
+                if in_trace && !weight.ge0() {
+                    trace_cursor.seek_val(cursor.val());
+                    if trace_cursor.val_valid() && trace_cursor.val() == cursor.val() {
+                        on_time_weight = Some(trace_cursor.weight()).filter(|w| !w.le0());
+                    }
+                }
+
+                match on_time_weight {
+                    // Retract at most `on_time_weight` from the on-time stream.
+                    Some(on_time_weight) => {
+                        let remainder = weight.add_by_ref(&on_time_weight);
+                        if remainder.ge0() {
+                            on_time_builder.push((item(), weight));
+                        } else {
+                            on_time_builder.push((item(), -on_time_weight));
+                            late_builder.push((item(), remainder));
+                        }
+                    }
+                    None => late_builder.push((item(), weight)),
+                }
+            }
+            cursor.step_val();
+        }
+        cursor.step_key();
+    }
+
+    (on_time_builder.done(), late_builder.done())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Runtime;
+    use crate::{zset, Runtime};
 
     fn test_watermark_monotonic(workers: usize) {
         let mut expected_watermarks = vec![115, 115, 125, 145].into_iter();
@@ -120,4 +294,71 @@ mod tests {
     fn test_watermark_monotonic4() {
         test_watermark_monotonic(4);
     }
+
+    fn test_split_late(workers: usize) {
+        let mut expected_on_time = vec![
+            zset! { 100 => 1, 110 => 1 },
+            zset! { 110 => 1 },
+            zset! { 100 => -1, 120 => 1, 130 => 1 },
+            zset! { 110 => -2 },
+        ]
+        .into_iter();
+        let mut expected_late = vec![
+            zset! { 50 => 1 },
+            zset! { 90 => 1, 99 => 1 },
+            zset! {},
+            zset! { 50 => -1, 99 => -1, 110 => -1 },
+        ]
+        .into_iter();
+
+        let (mut dbsp, input_handle) = Runtime::init_circuit(workers, move |circuit| {
+            let (stream, handle) = circuit.add_input_zset::<i32, i32>();
+            let watermark = stream.watermark_monotonic(|ts| *ts);
+            let (on_time, late) = stream.split_late(&watermark, 10, |ts| *ts);
+
+            // Check the union of outputs across workers.
+            on_time.gather(0).inspect(move |batch| {
+                if Runtime::worker_index() == 0 {
+                    assert_eq!(batch, &expected_on_time.next().unwrap());
+                }
+            });
+            late.gather(0).inspect(move |batch| {
+                if Runtime::worker_index() == 0 {
+                    assert_eq!(batch, &expected_late.next().unwrap());
+                }
+            });
+            Ok(handle)
+        })
+        .unwrap();
+
+        // Watermark: 110, bound: 100.
+        input_handle.append(&mut vec![(100, 1), (110, 1), (50, 1)]);
+        dbsp.step().unwrap();
+
+        // Watermark: 110, bound: 100.
+        input_handle.append(&mut vec![(90, 1), (99, 1), (110, 1)]);
+        dbsp.step().unwrap();
+
+        // Watermark: 130, bound: 120.  The retraction of a record that was on
+        // time is on time, even though the record is now below the bound.
+        input_handle.append(&mut vec![(120, 1), (130, 1), (100, -1)]);
+        dbsp.step().unwrap();
+
+        // Retractions of late records are late.  Only the part of a retraction
+        // that exceeds the on-time weight of the record (2 for 110) is late.
+        input_handle.append(&mut vec![(50, -1), (99, -1), (110, -3)]);
+        dbsp.step().unwrap();
+
+        dbsp.kill().unwrap();
+    }
+
+    #[test]
+    fn test_split_late1() {
+        test_split_late(1);
+    }
+
+    #[test]
+    fn test_split_late4() {
+        test_split_late(4);
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     circuit::{
-        metadata::{MetaItem, OperatorMeta},
+        metadata::{MemoryUsage, OperatorMeta},
         operator_traits::{BinaryOperator, Operator, StrictOperator, StrictUnaryOperator},
         Circuit, ExportId, ExportStream, FeedbackConnector, GlobalNodeId, OwnershipPreference,
         Scope, Stream, WithClock,
@@ -10,7 +10,12 @@ use crate::{
     DBData, Timestamp,
 };
 use size_of::SizeOf;
-use std::{borrow::Cow, cell::RefCell, marker::PhantomData, ops::DerefMut, rc::Rc};
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    ops::DerefMut,
+    sync::{Arc, Mutex},
+};
 
 circuit_cache_key!(TraceId<B, D, K, V>(GlobalNodeId => (Stream<B, D>, TraceBounds<K, V>)));
 circuit_cache_key!(DelayedTraceId<B, D>(GlobalNodeId => Stream<B, D>));
@@ -23,13 +28,17 @@ circuit_cache_key!(DelayedTraceId<B, D>(GlobalNodeId => Stream<B, D>));
 ///
 /// The writer can update the value of the bound at each clock
 /// cycle.  The bound can only increase monotonically.
-#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// The writer and the trace are different operators, which
+/// [`ParallelScheduler`](`crate::circuit::schedule::ParallelScheduler`) may
+/// evaluate on different threads, so the bound is protected by a mutex.
+#[derive(Clone)]
 #[repr(transparent)]
-pub struct TraceBound<T>(Rc<RefCell<Option<T>>>);
+pub struct TraceBound<T>(Arc<Mutex<Option<T>>>);
 
 impl<K> Default for TraceBound<K> {
     fn default() -> Self {
-        Self(Rc::new(RefCell::new(None)))
+        Self(Arc::new(Mutex::new(None)))
     }
 }
 
@@ -43,8 +52,9 @@ where
 
     /// Set the new value of the bound.
     pub fn set(&self, bound: K) {
-        debug_assert!(self.0.borrow().as_ref() <= Some(&bound));
-        *self.0.borrow_mut() = Some(bound);
+        let mut current = self.0.lock().unwrap();
+        debug_assert!(current.as_ref() <= Some(&bound));
+        *current = Some(bound);
     }
 
     /// Get the current value of the bound.
@@ -52,14 +62,14 @@ where
     where
         K: Clone,
     {
-        self.0.borrow().clone()
+        self.0.lock().unwrap().clone()
     }
 }
 
 /// Data structure that tracks key and value bounds supplied by all
 /// downstream consumers of the trace.
 #[derive(Clone)]
-pub struct TraceBounds<K, V>(Rc<RefCell<TraceBoundsInner<K, V>>>);
+pub struct TraceBounds<K, V>(Arc<Mutex<TraceBoundsInner<K, V>>>);
 
 impl<K, V> TraceBounds<K, V>
 where
@@ -71,7 +81,7 @@ where
     /// The caller must add at least one key and one value bound before
     /// running the circuit.
     pub(crate) fn new() -> Self {
-        Self(Rc::new(RefCell::new(TraceBoundsInner {
+        Self(Arc::new(Mutex::new(TraceBoundsInner {
             key_bounds: Vec::new(),
             val_bounds: Vec::new(),
         })))
@@ -80,38 +90,40 @@ where
     /// Returns `TraceBounds` that prevent any values in the trace from
     /// ever being truncated.
     pub(crate) fn unbounded() -> Self {
-        Self(Rc::new(RefCell::new(TraceBoundsInner {
+        Self(Arc::new(Mutex::new(TraceBoundsInner {
             key_bounds: vec![TraceBound::new()],
             val_bounds: vec![TraceBound::new()],
         })))
     }
 
     pub(crate) fn add_key_bound(&self, bound: TraceBound<K>) {
-        self.0.borrow_mut().key_bounds.push(bound);
+        self.0.lock().unwrap().key_bounds.push(bound);
     }
 
     pub(crate) fn add_val_bound(&self, bound: TraceBound<V>) {
-        self.0.borrow_mut().val_bounds.push(bound);
+        self.0.lock().unwrap().val_bounds.push(bound);
     }
 
     pub(crate) fn effective_key_bound(&self) -> Option<K> {
         self.0
-            .borrow()
+            .lock()
+            .unwrap()
             .key_bounds
             .iter()
+            .map(TraceBound::get)
             .min()
             .expect("At least one trace bound must be set")
-            .get()
     }
 
     pub(crate) fn effective_val_bound(&self) -> Option<V> {
         self.0
-            .borrow()
+            .lock()
+            .unwrap()
             .val_bounds
             .iter()
+            .map(TraceBound::get)
             .min()
             .expect("At least one trace bound must be set")
-            .get()
     }
 }
 
@@ -627,19 +639,17 @@ where
             .map(|trace| trace.num_entries_deep())
             .unwrap_or(0);
 
-        let bytes = self
-            .trace
-            .as_ref()
-            .map(|trace| trace.size_of())
-            .unwrap_or_default();
-
-        meta.extend(metadata! {
-            "total size" => total_size,
-            "allocated bytes" => MetaItem::bytes(bytes.total_bytes()),
-            "used bytes" => MetaItem::bytes(bytes.used_bytes()),
-            "allocations" => bytes.distinct_allocations(),
-            "shared bytes" => MetaItem::bytes(bytes.shared_bytes()),
-        });
+        meta.extend(metadata! { "total size" => total_size });
+        meta.extend(self.memory_usage().unwrap_or_default().metadata());
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        Some(
+            self.trace
+                .as_ref()
+                .map(|trace| MemoryUsage::new(trace.size_of(), trace.num_batches()))
+                .unwrap_or_default(),
+        )
     }
 
     fn fixedpoint(&self, scope: Scope) -> bool {
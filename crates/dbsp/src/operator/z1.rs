@@ -3,7 +3,7 @@
 use crate::{
     algebra::HasZero,
     circuit::{
-        metadata::{MetaItem, OperatorMeta},
+        metadata::{MemoryUsage, MetaItem, OperatorMeta},
         operator_traits::{Operator, StrictOperator, StrictUnaryOperator, UnaryOperator},
         Circuit, ExportId, ExportStream, FeedbackConnector, GlobalNodeId, OwnershipPreference,
         Scope, Stream,
@@ -195,14 +195,12 @@ where
     }
 
     fn metadata(&self, meta: &mut OperatorMeta) {
-        let bytes = self.values.size_of();
-        meta.extend(metadata! {
-            "total size" => self.values.num_entries_deep(),
-            "allocated bytes" => MetaItem::bytes(bytes.total_bytes()),
-            "used bytes" => MetaItem::bytes(bytes.used_bytes()),
-            "allocations" => bytes.distinct_allocations(),
-            "shared bytes" => MetaItem::bytes(bytes.shared_bytes()),
-        });
+        meta.extend(metadata! { "total size" => self.values.num_entries_deep() });
+        meta.extend(self.memory_usage().unwrap_or_default().metadata());
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        Some(MemoryUsage::new(self.values.size_of(), 1))
     }
 
     fn fixedpoint(&self, scope: Scope) -> bool {
@@ -352,22 +350,20 @@ where
             .map(|batch| MetaItem::Int(batch.num_entries_deep()))
             .collect();
 
-        let total_bytes = {
-            let mut context = Context::new();
-            for value in &self.values {
-                value.size_of_with_context(&mut context);
-            }
-            context.total_size()
-        };
-
         meta.extend(metadata! {
             "total size" => total_size,
             "batch sizes" => MetaItem::Array(batch_sizes),
-            "allocated bytes" => MetaItem::bytes(total_bytes.total_bytes()),
-            "used bytes" => MetaItem::bytes(total_bytes.used_bytes()),
-            "allocations" => total_bytes.distinct_allocations(),
-            "shared bytes" => MetaItem::bytes(total_bytes.shared_bytes()),
         });
+        meta.extend(self.memory_usage().unwrap_or_default().metadata());
+    }
+
+    fn memory_usage(&self) -> Option<MemoryUsage> {
+        let mut context = Context::new();
+        for value in &self.values {
+            value.size_of_with_context(&mut context);
+        }
+
+        Some(MemoryUsage::new(context.total_size(), self.values.len()))
     }
 
     fn fixedpoint(&self, scope: Scope) -> bool {
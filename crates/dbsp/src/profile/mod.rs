@@ -3,16 +3,66 @@
 use crate::{
     circuit::{
         circuit_builder::Node,
-        metadata::{MetaItem, OperatorMeta},
+        metadata::{MemoryUsage, MetaItem, OperatorMeta, RecordCounts},
+        trace::SchedulerEvent,
         GlobalNodeId,
     },
-    monitor::TraceMonitor,
+    monitor::{visual_graph::Graph, TraceMonitor},
     RootCircuit,
 };
-use std::{borrow::Cow, collections::HashMap, fmt::Write};
+use serde::Serialize;
+use std::{borrow::Cow, cell::Cell, collections::HashMap, fmt::Write, rc::Rc, time::Duration};
 
 mod cpu;
+mod spans;
 pub use cpu::CPUProfiler;
+pub use spans::SpanTracer;
+
+/// Memory used by a single operator in one worker.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperatorMemoryProfile {
+    /// Global id of the operator.
+    pub node_id: GlobalNodeId,
+    /// Operator name.
+    pub name: String,
+    /// Memory used by the operator's state.
+    #[serde(flatten)]
+    pub usage: MemoryUsage,
+}
+
+/// Statistics collected by the profiler for a single node of the circuit
+/// graph in one worker.
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeProfile {
+    /// Global id of the node.
+    pub node_id: GlobalNodeId,
+    /// Number of times the node was evaluated, if CPU profiling is enabled.
+    pub invocations: Option<usize>,
+    /// Total time spent evaluating the node, if CPU profiling is enabled.
+    pub total_time: Option<Duration>,
+    /// Memory used by the node's state, if it is a stateful operator.
+    pub memory: Option<MemoryUsage>,
+    /// Records consumed and produced by the node across all steps, if its
+    /// operator counts them.
+    pub records: Option<RecordCounts>,
+    /// Operator-specific metadata, e.g., the number of inputs and outputs
+    /// processed by a join.
+    pub metadata: OperatorMeta,
+}
+
+/// Circuit graph annotated with per-node statistics, for rendering by
+/// external tools.
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphProfile {
+    /// Circuit topology.  Nodes in the graph carry the global ids that
+    /// `nodes` are keyed by.
+    pub graph: Graph,
+    /// The number of steps performed by the root circuit.  Dividing node
+    /// times and record counts by this number yields per-step values.
+    pub steps: usize,
+    /// Per-node statistics.
+    pub nodes: Vec<NodeProfile>,
+}
 
 /// Rudimentary circuit profiler.
 ///
@@ -20,6 +70,7 @@ pub use cpu::CPUProfiler;
 /// dumps them in graphviz (dot) format.
 pub struct Profiler {
     cpu_profiler: CPUProfiler,
+    steps: Rc<Cell<usize>>,
     monitor: TraceMonitor,
     circuit: RootCircuit,
 }
@@ -34,8 +85,21 @@ impl Profiler {
         let monitor = TraceMonitor::new_panic_on_error();
         monitor.attach_circuit_events(circuit, "monitor");
 
+        let steps = Rc::new(Cell::new(0));
+        {
+            let steps = steps.clone();
+            circuit.register_scheduler_event_handler("step_counter", move |event| {
+                if let SchedulerEvent::StepEnd { circuit_id } = event {
+                    if circuit_id.path().is_empty() {
+                        steps.set(steps.get() + 1);
+                    }
+                }
+            });
+        }
+
         Self {
             cpu_profiler,
+            steps,
             monitor,
             circuit: circuit.clone(),
         }
@@ -46,6 +110,62 @@ impl Profiler {
         self.cpu_profiler.attach(&self.circuit, "cpu_profiler");
     }
 
+    /// Report circuit steps, operator evaluations, and waits as `tracing`
+    /// spans (see [`SpanTracer`]).
+    pub fn enable_span_tracer(&self) {
+        SpanTracer::new().attach(&self.circuit, "span_tracer");
+    }
+
+    /// Collect memory usage reports from all stateful operators in the
+    /// circuit, including operators in nested circuits.
+    ///
+    /// Computing the size of operator state requires traversing all of it, so
+    /// the cost of this method is proportional to the size of the state.
+    pub fn memory_profile(&self) -> Vec<OperatorMemoryProfile> {
+        let mut profile = Vec::new();
+
+        self.circuit.map_nodes_recursive(&mut |node: &dyn Node| {
+            if let Some(usage) = node.memory_usage() {
+                profile.push(OperatorMemoryProfile {
+                    node_id: node.global_id().clone(),
+                    name: node.name().into_owned(),
+                    usage,
+                });
+            }
+        });
+
+        profile
+    }
+
+    /// Returns the circuit graph along with CPU, memory, record count and
+    /// operator metadata statistics for each node.
+    pub fn graph_profile(&self) -> GraphProfile {
+        let mut nodes = Vec::new();
+
+        self.circuit.map_nodes_recursive(&mut |node: &dyn Node| {
+            let node_id = node.global_id().clone();
+            let cpu_profile = self.cpu_profiler.operator_profile(&node_id);
+
+            let mut metadata = OperatorMeta::new();
+            node.metadata(&mut metadata);
+
+            nodes.push(NodeProfile {
+                invocations: cpu_profile.as_ref().map(|profile| profile.invocations()),
+                total_time: cpu_profile.as_ref().map(|profile| profile.total_time()),
+                memory: node.memory_usage(),
+                records: node.record_counts(),
+                metadata,
+                node_id,
+            });
+        });
+
+        GraphProfile {
+            graph: self.monitor.visualize_circuit(),
+            steps: self.steps.get(),
+            nodes,
+        }
+    }
+
     /// Dump profile in graphviz format.
     pub fn dump_profile(&self) -> String {
         let mut metadata = HashMap::<GlobalNodeId, OperatorMeta>::new();
@@ -0,0 +1,208 @@
+//! Export of scheduler events as [`tracing`] spans.
+//!
+//! [`SpanTracer`] turns circuit steps, operator evaluations, and waits for
+//! async operators (e.g., exchange receivers waiting for data from other
+//! workers) into `tracing` spans.  The spans are nested the same way the
+//! events are: operator evaluations and waits are children of the step of the
+//! circuit that performs them, and steps of nested circuits are children of
+//! the evaluation of the corresponding subcircuit node.
+//!
+//! The tracer does not install a subscriber.  Spans go to whatever subscriber
+//! the application has set up, e.g., one that forwards them to OpenTelemetry.
+//! When no subscriber is interested in the spans, the overhead of the tracer
+//! is a few hash table operations per event.
+
+use crate::{
+    circuit::{trace::SchedulerEvent, GlobalNodeId, RootCircuit},
+    Runtime,
+};
+use hashbrown::HashMap;
+use std::{cell::RefCell, rc::Rc};
+use tracing::{info_span, span::EnteredSpan};
+
+#[derive(Default)]
+struct SpanTracerInner {
+    worker: usize,
+    steps: usize,
+    step_spans: HashMap<GlobalNodeId, EnteredSpan>,
+    eval_spans: HashMap<GlobalNodeId, EnteredSpan>,
+    wait_spans: HashMap<GlobalNodeId, EnteredSpan>,
+}
+
+impl SpanTracerInner {
+    fn scheduler_event(&mut self, event: &SchedulerEvent) {
+        match event {
+            SchedulerEvent::StepStart { circuit_id } => {
+                let span = if circuit_id.path().is_empty() {
+                    self.steps += 1;
+                    info_span!(
+                        "dbsp_step",
+                        worker = self.worker,
+                        step = self.steps,
+                        circuit = %circuit_id
+                    )
+                } else {
+                    info_span!("dbsp_step", worker = self.worker, circuit = %circuit_id)
+                };
+                self.step_spans
+                    .insert((*circuit_id).clone(), span.entered());
+            }
+            SchedulerEvent::StepEnd { circuit_id } => {
+                self.step_spans.remove(*circuit_id);
+            }
+            SchedulerEvent::EvalStart { node } => {
+                let span = info_span!(
+                    "dbsp_eval",
+                    worker = self.worker,
+                    node = %node.global_id(),
+                    operator = %node.name()
+                );
+                self.eval_spans
+                    .insert(node.global_id().clone(), span.entered());
+            }
+            SchedulerEvent::EvalEnd { node } => {
+                self.eval_spans.remove(node.global_id());
+            }
+            SchedulerEvent::WaitStart { circuit_id } => {
+                let span = info_span!("dbsp_wait", worker = self.worker, circuit = %circuit_id);
+                self.wait_spans
+                    .insert((*circuit_id).clone(), span.entered());
+            }
+            SchedulerEvent::WaitEnd { circuit_id } => {
+                self.wait_spans.remove(*circuit_id);
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Scheduler event handler that reports circuit activity as `tracing` spans.
+///
+/// Span names and fields:
+///
+/// * `dbsp_step` (`worker`, `circuit`, and, for the root circuit, `step`) - a
+///   single step of a circuit.
+/// * `dbsp_eval` (`worker`, `node`, `operator`) - evaluation of an operator or
+///   subcircuit.
+/// * `dbsp_wait` (`worker`, `circuit`) - the scheduler of `circuit` blocked
+///   waiting for an async operator, such as an exchange, to become ready.
+///
+/// All spans are created at the `INFO` level.
+#[repr(transparent)]
+#[derive(Clone)]
+pub struct SpanTracer(Rc<RefCell<SpanTracerInner>>);
+
+impl Default for SpanTracer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SpanTracer {
+    /// Create a new tracer instance.
+    pub fn new() -> Self {
+        Self(Rc::new(RefCell::new(SpanTracerInner {
+            worker: Runtime::worker_index(),
+            ..Default::default()
+        })))
+    }
+
+    /// Attach the tracer to a circuit.  The tracer will start emitting spans
+    /// for the circuit's scheduler events.
+    pub fn attach(&self, circuit: &RootCircuit, handler_name: &str) {
+        let self_clone = self.clone();
+
+        circuit.register_scheduler_event_handler(handler_name, move |event| {
+            if let Ok(mut this) = self_clone.0.try_borrow_mut() {
+                this.scheduler_event(event);
+            };
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpanTracer;
+    use crate::{operator::Generator, RootCircuit};
+    use std::sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    };
+    use tracing::{
+        span::{Attributes, Id, Record},
+        subscriber::with_default,
+        Event, Metadata, Subscriber,
+    };
+
+    /// Subscriber that records the names of created spans and counts
+    /// unbalanced enters.
+    #[derive(Default)]
+    struct SpanRecorder {
+        next_id: AtomicU64,
+        spans: Mutex<Vec<&'static str>>,
+        entered: Arc<AtomicU64>,
+    }
+
+    impl Subscriber for SpanRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.spans.lock().unwrap().push(span.metadata().name());
+            Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, _event: &Event<'_>) {}
+
+        fn enter(&self, _span: &Id) {
+            self.entered.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn exit(&self, _span: &Id) {
+            self.entered.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn span_tracer() {
+        let (circuit, ()) = RootCircuit::build(|circuit| {
+            SpanTracer::new().attach(circuit, "span_tracer");
+
+            let mut n = 0;
+            circuit
+                .add_source(Generator::new(move || {
+                    n += 1;
+                    n
+                }))
+                .integrate();
+            Ok(())
+        })
+        .unwrap();
+
+        let recorder = SpanRecorder::default();
+        let entered = recorder.entered.clone();
+        let spans = with_default(recorder, || {
+            for _ in 0..3 {
+                circuit.step().unwrap();
+            }
+            tracing::dispatcher::get_default(|dispatch| {
+                dispatch
+                    .downcast_ref::<SpanRecorder>()
+                    .unwrap()
+                    .spans
+                    .lock()
+                    .unwrap()
+                    .clone()
+            })
+        });
+
+        assert_eq!(entered.load(Ordering::Relaxed), 0);
+        assert_eq!(spans.iter().filter(|name| **name == "dbsp_step").count(), 3);
+        assert!(spans.iter().filter(|name| **name == "dbsp_eval").count() >= 6);
+    }
+}
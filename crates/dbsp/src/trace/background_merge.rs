@@ -0,0 +1,232 @@
+//! Merging large [`Spine`](crate::trace::spine_fueled::Spine) batches on
+//! background threads.
+//!
+//! By default, a spine merges batches inline, spending an amount of work
+//! proportional to each inserted batch on in-progress merges (see
+//! [`Trace::exert`](crate::trace::Trace::exert)).  Merging large batches this
+//! way inflates the latency of the steps that happen to perform the work.
+//! Once enabled for a runtime with [`Runtime::enable_background_merges`],
+//! merges whose inputs contain at least `min_len` updates are instead handed
+//! off to a pool of background threads owned by the runtime.  The spine keeps
+//! serving cursors over the two input batches, which it shares with the
+//! background thread, until the merged batch is ready, at which point the
+//! next operation on the spine swaps it in.
+//!
+//! Spines that don't belong to a runtime, e.g., in circuits that run in the
+//! client thread, always merge inline.
+
+use crate::{
+    circuit::{LocalStoreMarker, Runtime},
+    trace::{Batch, Merger},
+};
+use crossbeam::channel::{bounded, unbounded, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use size_of::{Context, SizeOf};
+use std::{
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+use typedmap::TypedMapKey;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The amount of fuel a background merge consumes before it first reports
+/// its size.  Subsequent reports happen at geometrically increasing
+/// intervals, so that tracking the size costs a constant factor of the merge.
+const SIZE_REPORT_FUEL: isize = 100_000;
+
+/// Pool of threads that merge batches for all spines in a runtime.
+///
+/// The threads exit once the runtime and all in-progress merges have been
+/// dropped.
+pub(crate) struct BackgroundMerger {
+    /// Minimal combined length of two batches merged in the background, or
+    /// `usize::MAX` if background merging is disabled.
+    min_len: AtomicUsize,
+    sender: Sender<Job>,
+}
+
+impl BackgroundMerger {
+    fn new(threads: usize) -> Self {
+        let (sender, receiver) = unbounded::<Job>();
+        for index in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("dbsp-merger-{index}"))
+                .spawn(move || {
+                    while let Ok(job) = receiver.recv() {
+                        // A panicking merge drops its result channel, which
+                        // reports the failure to the spine.
+                        let _ = catch_unwind(AssertUnwindSafe(job));
+                    }
+                })
+                .expect("failed to spawn background merge thread");
+        }
+
+        Self {
+            min_len: AtomicUsize::new(usize::MAX),
+            sender,
+        }
+    }
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct BackgroundMergerId;
+
+impl TypedMapKey<LocalStoreMarker> for BackgroundMergerId {
+    type Value = Arc<BackgroundMerger>;
+}
+
+impl Runtime {
+    /// Merge batches with at least `min_len` updates between them on a pool
+    /// of `threads` background threads.
+    ///
+    /// Applies to merges started after the call in all spines in the
+    /// runtime.  The thread pool is created on the first call; `threads` is
+    /// ignored by subsequent calls.
+    pub fn enable_background_merges(&self, min_len: usize, threads: usize) {
+        self.local_store()
+            .entry(BackgroundMergerId)
+            .or_insert_with(|| Arc::new(BackgroundMerger::new(threads)))
+            .min_len
+            .store(min_len, Ordering::Relaxed);
+    }
+
+    /// Stop handing off new merges to background threads.  Merges already in
+    /// progress complete in the background.
+    pub fn disable_background_merges(&self) {
+        if let Some(merger) = self.local_store().get(&BackgroundMergerId) {
+            merger.min_len.store(usize::MAX, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Returns the merge thread pool of the current worker's runtime if a merge
+/// of batches with `len` updates between them should run in the background.
+pub(crate) fn background_merger(len: usize) -> Option<Arc<BackgroundMerger>> {
+    Runtime::runtime()?
+        .local_store()
+        .get(&BackgroundMergerId)
+        .filter(|merger| len >= merger.min_len.load(Ordering::Relaxed))
+        .map(|merger| Arc::clone(&*merger))
+}
+
+/// State shared between a [`BackgroundMerge`] and the thread running it.
+#[derive(Default)]
+struct Progress {
+    /// Size in bytes of the partially merged batch, or of the merged batch
+    /// once the merge completes.
+    bytes: AtomicUsize,
+
+    /// Set when the spine no longer needs the result of the merge.
+    abandoned: AtomicBool,
+}
+
+/// Handle to a merge running on a background thread.
+///
+/// Dropping the handle abandons the merge.
+pub struct BackgroundMerge<B> {
+    receiver: Receiver<B>,
+    progress: Arc<Progress>,
+}
+
+impl<B> BackgroundMerge<B>
+where
+    B: Batch,
+{
+    /// Start merging `batch1` and `batch2` on one of the threads of `pool`,
+    /// dropping values below `lower_val_bound`.
+    pub(crate) fn new(
+        pool: &BackgroundMerger,
+        batch1: &Arc<B>,
+        batch2: &Arc<B>,
+        lower_val_bound: &Option<B::Val>,
+    ) -> Self {
+        let (sender, receiver) = bounded(1);
+        let progress = Arc::new(Progress::default());
+
+        let job = {
+            let (batch1, batch2) = (batch1.clone(), batch2.clone());
+            let lower_val_bound = lower_val_bound.clone();
+            let progress = progress.clone();
+
+            Box::new(move || {
+                let mut merger = batch1.begin_merge(&batch2);
+                let mut report_at = SIZE_REPORT_FUEL;
+                loop {
+                    if progress.abandoned.load(Ordering::Relaxed) {
+                        return;
+                    }
+
+                    let mut fuel = report_at;
+                    merger.work(&batch1, &batch2, &lower_val_bound, &mut fuel);
+                    if fuel > 0 {
+                        break;
+                    }
+                    progress
+                        .bytes
+                        .store(merger.size_of().total_bytes(), Ordering::Relaxed);
+                    report_at = report_at.saturating_mul(2);
+                }
+
+                let merged = merger.done();
+                progress
+                    .bytes
+                    .store(merged.size_of().total_bytes(), Ordering::Relaxed);
+                // Release our references to the inputs before the spine swaps
+                // in the result, so that it can free them.
+                drop((batch1, batch2));
+                let _ = sender.send(merged);
+            })
+        };
+        pool.sender
+            .send(job)
+            .expect("background merge threads have exited");
+
+        Self { receiver, progress }
+    }
+
+    /// Returns the merged batch if the merge has completed.
+    pub(crate) fn try_complete(&self) -> Option<B> {
+        match self.receiver.try_recv() {
+            Ok(batch) => Some(batch),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => panic!("background merge failed"),
+        }
+    }
+
+    /// Waits for the merge to complete and returns the merged batch.
+    ///
+    /// Observes step cancellation while waiting (see
+    /// [`Runtime::abort_if_cancelled`]).
+    pub(crate) fn complete(self) -> B {
+        loop {
+            match self.receiver.recv_timeout(Duration::from_millis(10)) {
+                Ok(batch) => return batch,
+                Err(RecvTimeoutError::Timeout) => Runtime::abort_if_cancelled(),
+                Err(RecvTimeoutError::Disconnected) => panic!("background merge failed"),
+            }
+        }
+    }
+}
+
+impl<B> Drop for BackgroundMerge<B> {
+    fn drop(&mut self) {
+        self.progress.abandoned.store(true, Ordering::Relaxed);
+    }
+}
+
+// The input batches are accounted for by the spine, which shares them with
+// the background thread.  Here we only report the batch being built.
+impl<B> SizeOf for BackgroundMerge<B> {
+    fn size_of_children(&self, context: &mut Context) {
+        let bytes = self.progress.bytes.load(Ordering::Relaxed);
+        if bytes != 0 {
+            context.add(bytes).add_distinct_allocation();
+        }
+    }
+}
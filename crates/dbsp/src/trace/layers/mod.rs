@@ -248,6 +248,8 @@ pub trait OrdOffset:
     + SizeOf
     + Sized
     + Rkyv
+    + Send
+    + Sync
     + 'static
 {
     fn from_usize(offset: usize) -> Self;
@@ -267,6 +269,8 @@ where
         + SizeOf
         + Sized
         + Rkyv
+        + Send
+        + Sync
         + 'static,
     <O as TryInto<usize>>::Error: Debug,
     <O as TryFrom<usize>>::Error: Debug,
@@ -33,6 +33,7 @@
 //! and upper bounds, one for each category of incomparable time, in an
 //! [`Antichain`](crate::time::Antichain).
 
+pub mod background_merge;
 pub mod consolidation;
 pub mod cursor;
 pub mod layers;
@@ -71,8 +72,15 @@ use std::{fmt::Debug, hash::Hash};
 /// must be generic over any relational data, it is sufficient to impose
 /// `DBData` as a trait bound on types.  Conversely, a trait bound of the form
 /// `B: BatchReader` implies `B::Key: DBData` and `B::Val: DBData`.
-pub trait DBData: Clone + Eq + Ord + Hash + SizeOf + Send + Debug + Rkyv + 'static {}
-impl<T> DBData for T where T: Clone + Eq + Ord + Hash + SizeOf + Send + Debug + Rkyv + 'static {}
+///
+/// Data must be `Send + Sync` because batches can be shared with background
+/// merge threads (see
+/// [`Runtime::enable_background_merges`](crate::circuit::Runtime::enable_background_merges)).
+/// Spines are generic over the batch type, so the bound is required even if
+/// background merges are never enabled.
+pub trait DBData: Clone + Eq + Ord + Hash + SizeOf + Send + Sync + Debug + Rkyv + 'static {}
+impl<T> DBData for T where T: Clone + Eq + Ord + Hash + SizeOf + Send + Sync + Debug + Rkyv + 'static
+{}
 
 /// Trait for data that can be serialized and deserialized with [`rkyv`].
 pub trait Rkyv: Archive + Serialize<Serializer> + Deserializable {}
@@ -187,6 +195,12 @@ pub trait Trace: BatchReader {
     /// Returns the value of the dirty flag.
     fn dirty(&self) -> bool;
 
+    /// Returns the number of batches the trace currently consists of.
+    ///
+    /// This is a measure of how much merging work is outstanding: a fully
+    /// merged trace consists of at most one batch.
+    fn num_batches(&self) -> usize;
+
     /// Informs the trace that values smaller than `lower_bound` are no longer
     /// used and can be removed from the trace.
     ///
@@ -321,8 +335,12 @@ where
 /// See [crate documentation](crate::trace) for more information on batches and
 /// traces.
 ///
+/// Batches are `Send` and `Sync`, so that they can be merged on background
+/// threads while the trace keeps reading them (see
+/// [`Runtime::enable_background_merges`](crate::circuit::Runtime::enable_background_merges)).
+///
 /// [`recede_to`]: Self::recede_to
-pub trait Batch: BatchReader + Clone
+pub trait Batch: BatchReader + Clone + Send + Sync
 where
     Self: Sized,
 {
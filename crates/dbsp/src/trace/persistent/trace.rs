@@ -581,6 +581,11 @@ where
         self.dirty
     }
 
+    fn num_batches(&self) -> usize {
+        // All updates are stored in a single RocksDB column family.
+        1
+    }
+
     fn truncate_values_below(&mut self, lower_bound: &Self::Val) {
         self.lower_val_bound = Some(if let Some(bound) = &self.lower_val_bound {
             max(bound, lower_bound).clone()
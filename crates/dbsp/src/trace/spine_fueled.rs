@@ -87,9 +87,10 @@
 
 use crate::{
     algebra::HasZero,
-    circuit::Activator,
+    circuit::{Activator, Runtime},
     time::{Antichain, AntichainRef, Timestamp},
     trace::{
+        background_merge::{background_merger, BackgroundMerge},
         cursor::{Cursor, CursorList},
         Batch, BatchReader, Consumer, Merger, Trace, ValueConsumer,
     },
@@ -99,10 +100,11 @@ use rand::Rng;
 use rkyv::{ser::Serializer, Archive, Archived, Deserialize, Fallible, Serialize};
 use size_of::SizeOf;
 use std::{
-    cmp::max,
+    cmp::{max, min},
     fmt::{self, Debug, Display, Formatter, Write},
     marker::PhantomData,
     mem::replace,
+    sync::Arc,
 };
 use textwrap::indent;
 
@@ -260,6 +262,15 @@ where
                         cursors.push(batch2.cursor());
                     }
                 }
+                MergeState::Double(MergeVariant::Background(batch1, batch2, _)) => {
+                    if !batch1.is_empty() {
+                        cursors.push(batch1.cursor());
+                    }
+
+                    if !batch2.is_empty() {
+                        cursors.push(batch2.cursor());
+                    }
+                }
 
                 MergeState::Double(MergeVariant::Complete(Some(batch)))
                 | MergeState::Single(Some(batch)) => {
@@ -274,7 +285,15 @@ where
             }
         }
 
-        SpineCursor::new(cursors)
+        // Batches in merges that were in progress when keys were last
+        // truncated may still contain keys below the bound.
+        let lower_key_bound = if self.merging.iter().any(MergeState::is_inprogress) {
+            self.lower_key_bound.as_ref()
+        } else {
+            None
+        };
+
+        SpineCursor::new(cursors, lower_key_bound)
     }
 
     fn consumer(self) -> Self::Consumer {
@@ -282,15 +301,23 @@ where
     }
 
     fn truncate_keys_below(&mut self, lower_bound: &Self::Key) {
-        self.complete_merges();
-
         let bound = if let Some(bound) = &self.lower_key_bound {
             max(bound, lower_bound).clone()
         } else {
             lower_bound.clone()
         };
-        self.lower_key_bound = Some(bound.clone());
-        self.map_batches_mut(|batch| batch.truncate_keys_below(&bound));
+
+        // Truncate merged batches right away.  Merges in progress truncate
+        // their output when they complete (see `MergeVariant::work`); until
+        // then, cursors skip keys below the bound in their inputs.
+        for merge_state in self.merging.iter_mut() {
+            match merge_state {
+                MergeState::Double(MergeVariant::Complete(Some(batch)))
+                | MergeState::Single(Some(batch)) => batch.truncate_keys_below(&bound),
+                _ => {}
+            }
+        }
+        self.lower_key_bound = Some(bound);
     }
 
     fn sample_keys<RG>(&self, rng: &mut RG, sample_size: usize, sample: &mut Vec<Self::Key>)
@@ -359,6 +386,14 @@ where
                     ))
                     .unwrap();
                 }
+                MergeState::Double(MergeVariant::Background(batch1, batch2, _)) => {
+                    s.write_fmt(format_args!(
+                        "[{}+{}],",
+                        batch1.num_entries_deep(),
+                        batch2.num_entries_deep()
+                    ))
+                    .unwrap();
+                }
                 MergeState::Double(MergeVariant::Complete(Some(batch))) => {
                     s.write_fmt(format_args!("[{}],", batch.num_entries_deep()))
                         .unwrap();
@@ -393,6 +428,10 @@ where
                     map(batch1);
                     map(batch2);
                 }
+                MergeState::Double(MergeVariant::Background(batch1, batch2, _)) => {
+                    map(batch1);
+                    map(batch2);
+                }
                 MergeState::Double(MergeVariant::Complete(Some(batch))) => map(batch),
                 MergeState::Single(Some(batch)) => map(batch),
                 _ => {}
@@ -412,6 +451,10 @@ where
                     let acc = fold(acc, batch1);
                     fold(acc, batch2)
                 }
+                MergeState::Double(MergeVariant::Background(batch1, batch2, _)) => {
+                    let acc = fold(acc, batch1);
+                    fold(acc, batch2)
+                }
                 MergeState::Double(MergeVariant::Complete(Some(batch))) => fold(acc, batch),
                 MergeState::Single(Some(batch)) => fold(acc, batch),
                 _ => acc,
@@ -431,6 +474,10 @@ where
                     let acc = fold(acc, batch1)?;
                     fold(acc, batch2)
                 }
+                MergeState::Double(MergeVariant::Background(batch1, batch2, _)) => {
+                    let acc = fold(acc, batch1)?;
+                    fold(acc, batch2)
+                }
                 MergeState::Double(MergeVariant::Complete(Some(batch))) => fold(acc, batch),
                 MergeState::Single(Some(batch)) => fold(acc, batch),
                 _ => Ok(acc),
@@ -441,6 +488,9 @@ where
 pub struct SpineCursor<'s, B: Batch + 's> {
     #[allow(clippy::type_complexity)]
     cursor: CursorList<B::Key, B::Val, B::Time, B::R, B::Cursor<'s>>,
+    /// Keys below this bound have been truncated from the spine but may
+    /// still be present in the inputs of in-progress merges.
+    lower_key_bound: Option<&'s B::Key>,
 }
 
 impl<'s, B: Batch> SpineCursor<'s, B>
@@ -448,9 +498,22 @@ where
     B::Key: Ord,
     B::Val: Ord,
 {
-    fn new(cursors: Vec<B::Cursor<'s>>) -> Self {
-        Self {
+    fn new(cursors: Vec<B::Cursor<'s>>, lower_key_bound: Option<&'s B::Key>) -> Self {
+        let mut result = Self {
             cursor: CursorList::new(cursors),
+            lower_key_bound,
+        };
+        result.skip_truncated_keys();
+        result
+    }
+
+    /// Moves the cursor to the first key that has not been truncated if it
+    /// points below the lower key bound.
+    fn skip_truncated_keys(&mut self) {
+        if let Some(bound) = self.lower_key_bound {
+            if self.cursor.key_valid() && self.cursor.key() < bound {
+                self.cursor.seek_key(bound);
+            }
         }
     }
 }
@@ -461,11 +524,15 @@ where
     B::Val: Ord,
 {
     fn key_valid(&self) -> bool {
+        // Stepping backwards can move the cursor below the lower key bound.
         self.cursor.key_valid()
+            && self
+                .lower_key_bound
+                .map_or(true, |bound| self.cursor.key() >= bound)
     }
 
     fn val_valid(&self) -> bool {
-        self.cursor.val_valid()
+        self.key_valid() && self.cursor.val_valid()
     }
 
     fn key(&self) -> &B::Key {
@@ -520,14 +587,23 @@ where
     }
 
     fn seek_key(&mut self, key: &B::Key) {
-        self.cursor.seek_key(key);
+        match self.lower_key_bound {
+            Some(bound) => self.cursor.seek_key(max(key, bound)),
+            None => self.cursor.seek_key(key),
+        }
     }
 
     fn seek_key_with<P>(&mut self, predicate: P)
     where
         P: Fn(&B::Key) -> bool + Clone,
     {
-        self.cursor.seek_key_with(predicate);
+        self.cursor.seek_key_with(predicate.clone());
+        if let Some(bound) = self.lower_key_bound {
+            if self.cursor.key_valid() && self.cursor.key() < bound {
+                self.cursor.seek_key(bound);
+                self.cursor.seek_key_with(predicate);
+            }
+        }
     }
 
     fn seek_key_with_reverse<P>(&mut self, predicate: P)
@@ -558,6 +634,7 @@ where
 
     fn rewind_keys(&mut self) {
         self.cursor.rewind_keys();
+        self.skip_truncated_keys();
     }
 
     fn fast_forward_keys(&mut self) {
@@ -705,6 +782,9 @@ where
         // Merge batches until there is nothing left to merge.
         let mut fuel = isize::max_value();
         while !self.reduced() {
+            // Wait for background merges rather than spinning until they
+            // complete.
+            self.complete_merges();
             self.exert(&mut fuel);
         }
         // Return the sole remaining batch (if one exists).
@@ -762,6 +842,10 @@ where
         self.dirty
     }
 
+    fn num_batches(&self) -> usize {
+        self.fold_batches(0, |acc, _| acc + 1)
+    }
+
     fn truncate_values_below(&mut self, lower_bound: &Self::Val) {
         // Set the bound, we postpone the actual GC till when we merge batches
         // in the trace.
@@ -984,7 +1068,7 @@ where
             // Give each level independent fuel, for now.
             let mut fuel = *fuel;
             // Pass along various logging stuffs, in case we need to report success.
-            self.merging[index].work(&self.lower_val_bound, &mut fuel);
+            self.merging[index].work(&self.lower_key_bound, &self.lower_val_bound, &mut fuel);
             // `fuel` could have a deficit at this point, meaning we over-spent when
             // we took a merge step. We could ignore this, or maintain the deficit
             // and account future fuel against it before spending again. It isn't
@@ -1019,7 +1103,16 @@ where
                 self.merging[index] = MergeState::Single(batch);
             }
             MergeState::Single(old) => {
-                self.merging[index] = MergeState::begin_merge(old, batch);
+                self.merging[index] = MergeState::begin_merge(old, batch, &self.lower_val_bound);
+            }
+            // Background merges are not paced by fuel, so they may still be
+            // running when the layer is needed.  Wait for the merge and
+            // promote its result to the next layer.
+            state @ MergeState::Double(MergeVariant::Background(..)) => {
+                self.merging[index] = state;
+                let merged = self.complete_at(index);
+                self.insert_at(merged, index + 1);
+                self.merging[index] = MergeState::Single(batch);
             }
             MergeState::Double(_) => {
                 panic!("Attempted to insert batch into incomplete merge!")
@@ -1029,7 +1122,7 @@ where
 
     /// Completes and extracts what ever is at layer `index`.
     fn complete_at(&mut self, index: usize) -> Option<B> {
-        self.merging[index].complete(&self.lower_val_bound)
+        self.merging[index].complete(&self.lower_key_bound, &self.lower_val_bound)
     }
 
     /// Attempts to draw down large layers to size appropriate layers.
@@ -1097,9 +1190,8 @@ where
     /// Complete all in-progress merges (without starting any new ones).
     fn complete_merges(&mut self) {
         for merge_state in self.merging.iter_mut() {
-            if merge_state.is_inprogress() {
-                let mut fuel = isize::max_value();
-                merge_state.work(&self.lower_val_bound, &mut fuel);
+            if let MergeState::Double(variant) = merge_state {
+                variant.finish(&self.lower_key_bound, &self.lower_val_bound);
             }
         }
         assert!(self.merging.iter().all(|m| !m.is_inprogress()));
@@ -1110,7 +1202,7 @@ where
     fn map_batches_mut<F: FnMut(&mut <Self as Trace>::Batch)>(&mut self, mut f: F) {
         for batch in self.merging.iter_mut().rev() {
             match batch {
-                MergeState::Double(MergeVariant::InProgress(_batch1, _batch2, _)) => {
+                MergeState::Double(MergeVariant::InProgress(..) | MergeVariant::Background(..)) => {
                     panic!("map_batches_mut called on an in-progress batch")
                 }
                 MergeState::Double(MergeVariant::Complete(Some(batch))) => {
@@ -1154,6 +1246,7 @@ where
         match self {
             MergeState::Single(Some(b)) => b.len(),
             MergeState::Double(MergeVariant::InProgress(b1, b2, _)) => b1.len() + b2.len(),
+            MergeState::Double(MergeVariant::Background(b1, b2, _)) => b1.len() + b2.len(),
             MergeState::Double(MergeVariant::Complete(Some(b))) => b.len(),
             _ => 0,
         }
@@ -1182,11 +1275,15 @@ where
     /// which should be done with the `is_complete()` method.
     ///
     /// There is the additional option of input batches.
-    fn complete(&mut self, lower_val_bound: &Option<B::Val>) -> Option<B> {
+    fn complete(
+        &mut self,
+        lower_key_bound: &Option<B::Key>,
+        lower_val_bound: &Option<B::Val>,
+    ) -> Option<B> {
         match replace(self, MergeState::Vacant) {
             MergeState::Vacant => None,
             MergeState::Single(batch) => batch,
-            MergeState::Double(variant) => variant.complete(lower_val_bound),
+            MergeState::Double(variant) => variant.complete(lower_key_bound, lower_val_bound),
         }
     }
 
@@ -1197,7 +1294,10 @@ where
 
     /// True iff the layer is an in-progress merge.
     fn is_inprogress(&self) -> bool {
-        matches!(self, MergeState::Double(MergeVariant::InProgress(..)))
+        matches!(
+            self,
+            MergeState::Double(MergeVariant::InProgress(..) | MergeVariant::Background(..))
+        )
     }
 
     /// Performs a bounded amount of work towards a merge.
@@ -1205,10 +1305,15 @@ where
     /// If the merge completes, the resulting batch is returned.
     /// If a batch is returned, it is the obligation of the caller
     /// to correctly install the result.
-    fn work(&mut self, lower_val_bound: &Option<B::Val>, fuel: &mut isize) {
+    fn work(
+        &mut self,
+        lower_key_bound: &Option<B::Key>,
+        lower_val_bound: &Option<B::Val>,
+        fuel: &mut isize,
+    ) {
         // We only perform work for merges in progress.
         if let MergeState::Double(layer) = self {
-            layer.work(lower_val_bound, fuel)
+            layer.work(lower_key_bound, lower_val_bound, fuel)
         }
     }
 
@@ -1228,14 +1333,27 @@ where
     /// empty batch whose upper and lower froniers are equal. This
     /// option exists purely for bookkeeping purposes, and no computation
     /// is performed to merge the two batches.
-    fn begin_merge(batch1: Option<B>, batch2: Option<B>) -> MergeState<B> {
+    ///
+    /// Large merges run on a background thread if enabled (see
+    /// [`Runtime::enable_background_merges`]).
+    fn begin_merge(
+        batch1: Option<B>,
+        batch2: Option<B>,
+        lower_val_bound: &Option<B::Val>,
+    ) -> MergeState<B> {
         let variant = match (batch1, batch2) {
             (Some(batch1), Some(batch2)) => {
                 // Leonid: we do not require batch bounds to grow monotonically.
                 //assert!(batch1.upper() == batch2.lower());
 
-                let begin_merge = <B as Batch>::begin_merge(&batch1, &batch2);
-                MergeVariant::InProgress(batch1, batch2, begin_merge)
+                if let Some(merger) = background_merger(batch1.len() + batch2.len()) {
+                    let (batch1, batch2) = (Arc::new(batch1), Arc::new(batch2));
+                    let merge = BackgroundMerge::new(&merger, &batch1, &batch2, lower_val_bound);
+                    MergeVariant::Background(batch1, batch2, merge)
+                } else {
+                    let begin_merge = <B as Batch>::begin_merge(&batch1, &batch2);
+                    MergeVariant::InProgress(batch1, batch2, begin_merge)
+                }
             }
             (batch @ Some(_), None) | (None, batch @ Some(_)) => MergeVariant::Complete(batch),
             (None, None) => MergeVariant::Complete(None),
@@ -1259,6 +1377,10 @@ where
     }
 }
 
+/// The amount of fuel a merge consumes between checks for step cancellation
+/// (see [`Runtime::abort_if_cancelled`]).
+const MERGE_FUEL_QUANTUM: isize = 100_000;
+
 #[derive(SizeOf)]
 pub enum MergeVariant<B>
 where
@@ -1266,6 +1388,10 @@ where
 {
     /// Describes an actual in-progress merge between two non-trivial batches.
     InProgress(B, B, <B as Batch>::Merger),
+    /// A merge between two non-trivial batches running on a background
+    /// thread.  The batches, which are shared with the background thread,
+    /// serve reads until the merge completes.
+    Background(Arc<B>, Arc<B>, BackgroundMerge<B>),
     /// A merge that requires no further work. May or may not represent a
     /// non-trivial batch.
     Complete(Option<B>),
@@ -1279,9 +1405,12 @@ where
     ///
     /// The result is either `None`, for structurally empty batches,
     /// or a batch and optionally input batches from which it derived.
-    fn complete(mut self, lower_val_bound: &Option<B::Val>) -> Option<B> {
-        let mut fuel = isize::max_value();
-        self.work(lower_val_bound, &mut fuel);
+    fn complete(
+        mut self,
+        lower_key_bound: &Option<B::Key>,
+        lower_val_bound: &Option<B::Val>,
+    ) -> Option<B> {
+        self.finish(lower_key_bound, lower_val_bound);
         if let MergeVariant::Complete(batch) = self {
             batch
         } else {
@@ -1289,19 +1418,67 @@ where
         }
     }
 
+    /// Runs the merge to completion, waiting for it if it runs in the
+    /// background.
+    fn finish(&mut self, lower_key_bound: &Option<B::Key>, lower_val_bound: &Option<B::Val>) {
+        match replace(self, MergeVariant::Complete(None)) {
+            MergeVariant::Background(_batch1, _batch2, merge) => {
+                *self = Self::merged(merge.complete(), lower_key_bound);
+            }
+            variant => {
+                *self = variant;
+                let mut fuel = isize::max_value();
+                self.work(lower_key_bound, lower_val_bound, &mut fuel);
+            }
+        }
+    }
+
+    /// A completed merge with result `batch`, dropping keys below
+    /// `lower_key_bound`, which may have been raised while the merge was in
+    /// progress.
+    fn merged(mut batch: B, lower_key_bound: &Option<B::Key>) -> Self {
+        if let Some(bound) = lower_key_bound {
+            batch.truncate_keys_below(bound);
+        }
+        MergeVariant::Complete(Some(batch))
+    }
+
     /// Applies some amount of work, potentially completing the merge.
     ///
     /// In case the work completes, the source batches are returned.
     /// This allows the caller to manage the released resources.
-    fn work(&mut self, lower_val_bound: &Option<B::Val>, fuel: &mut isize) {
+    ///
+    /// Background merges don't consume fuel; this method only checks whether
+    /// they have completed.
+    fn work(
+        &mut self,
+        lower_key_bound: &Option<B::Key>,
+        lower_val_bound: &Option<B::Val>,
+        fuel: &mut isize,
+    ) {
         let variant = replace(self, MergeVariant::Complete(None));
         if let MergeVariant::InProgress(b1, b2, mut merge) = variant {
-            merge.work(&b1, &b2, lower_val_bound, fuel);
-            if *fuel > 0 {
-                *self = MergeVariant::Complete(Some(merge.done()));
-            } else {
-                *self = MergeVariant::InProgress(b1, b2, merge);
+            // Spend `fuel` in bounded quanta, so that a large merge doesn't
+            // delay the cancellation of the step that triggered it.
+            loop {
+                let budget = min(*fuel, MERGE_FUEL_QUANTUM);
+                let mut quantum = budget;
+                merge.work(&b1, &b2, lower_val_bound, &mut quantum);
+                *fuel -= budget - quantum;
+                if quantum > 0 {
+                    *self = Self::merged(merge.done(), lower_key_bound);
+                    break;
+                } else if *fuel <= 0 {
+                    *self = MergeVariant::InProgress(b1, b2, merge);
+                    break;
+                }
+                Runtime::abort_if_cancelled();
             }
+        } else if let MergeVariant::Background(b1, b2, merge) = variant {
+            *self = match merge.try_complete() {
+                Some(merged) => Self::merged(merged, lower_key_bound),
+                None => MergeVariant::Background(b1, b2, merge),
+            };
         } else {
             *self = variant;
         }
@@ -1321,6 +1498,11 @@ where
                 .field(batch2)
                 .field(merger)
                 .finish(),
+            Self::Background(batch1, batch2, _) => f
+                .debug_tuple("Background")
+                .field(batch1)
+                .field(batch2)
+                .finish(),
             Self::Complete(batch) => f.debug_tuple("Complete").field(batch).finish(),
         }
     }
@@ -1329,6 +1511,7 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
+        circuit::Runtime,
         trace::{
             cursor::CursorPair,
             ord::{OrdKeyBatch, OrdValBatch},
@@ -1539,5 +1722,43 @@ mod test {
                 assert_batch_cursors_eq(trace.cursor(), &ref_trace, seed);
             }
         }
+
+        #[test]
+        fn test_indexed_zset_spine_background_merge(batches in kvr_batches(1000, 10, 2, 3000, 20), seed in 0..u64::max_value()) {
+            Runtime::run(1, move || {
+                // The threshold is above the size of the traces in tests
+                // that bound memory usage.
+                Runtime::runtime().unwrap().enable_background_merges(5000, 2);
+
+                let mut trace: Spine<OrdIndexedZSet<i32, i32, i32>> = Spine::new(None);
+                let mut ref_trace: TestBatch<i32, i32, (), i32> = TestBatch::new(None);
+
+                for (tuples, key_bound, val_bound) in batches.into_iter() {
+                    let batch = OrdIndexedZSet::from_tuples((), tuples.clone());
+                    let ref_batch = TestBatch::from_tuples((), tuples);
+
+                    ref_trace.insert(ref_batch);
+                    trace.insert(batch);
+
+                    assert_trace_eq(&trace, &ref_trace);
+                    assert_batch_cursors_eq(trace.cursor(), &ref_trace, seed);
+
+                    // Truncation doesn't wait for merges running in the
+                    // background.
+                    trace.truncate_keys_below(&key_bound);
+                    ref_trace.truncate_keys_below(&key_bound);
+
+                    trace.truncate_values_below(&val_bound);
+                    ref_trace.truncate_values_below(&val_bound);
+
+                    assert_trace_eq(&trace, &ref_trace);
+                    assert_batch_cursors_eq(trace.cursor(), &ref_trace, seed);
+                }
+
+                trace.consolidate();
+            })
+            .join()
+            .unwrap();
+        }
     }
 }
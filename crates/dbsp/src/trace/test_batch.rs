@@ -908,6 +908,10 @@ where
         todo!()
     }
 
+    fn num_batches(&self) -> usize {
+        1
+    }
+
     fn truncate_values_below(&mut self, lower_bound: &Self::Val) {
         let bound = if let Some(bound) = &self.lower_val_bound {
             max(bound, lower_bound).clone()
@@ -0,0 +1,19 @@
+-- Metadata used to manage API keys through the REST API.
+ALTER TABLE api_key
+ADD COLUMN id uuid,
+ADD COLUMN name varchar,
+-- Time when the key was created.
+ADD COLUMN created bigint,
+-- Time when the key was last used to authenticate a request.
+ADD COLUMN last_used bigint;
+
+-- Keys created before this migration are named after their ids.
+UPDATE api_key SET id = gen_random_uuid(), created = extract(epoch from now());
+UPDATE api_key SET name = id::text;
+
+ALTER TABLE api_key
+ALTER COLUMN id SET NOT NULL,
+ALTER COLUMN name SET NOT NULL,
+ALTER COLUMN created SET NOT NULL,
+ADD CONSTRAINT api_key_id_key UNIQUE (id),
+ADD CONSTRAINT api_key_tenant_id_name_key UNIQUE (tenant_id, name);
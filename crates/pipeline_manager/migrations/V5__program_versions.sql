@@ -0,0 +1,52 @@
+-- Every version of a program's code, along with the schema the SQL compiler
+-- derived from it (once available). Used to list and diff the history of a
+-- program and to roll back to earlier versions.
+CREATE TABLE program_version (
+    program_id uuid NOT NULL,
+    version bigint NOT NULL,
+    tenant_id uuid NOT NULL,
+    code varchar NOT NULL,
+    schema varchar,
+    -- Time when the version was created.
+    created bigint NOT NULL,
+    PRIMARY KEY (program_id, version),
+    FOREIGN KEY (program_id) REFERENCES program(id) ON DELETE CASCADE,
+    FOREIGN KEY (tenant_id) REFERENCES tenant(id) ON DELETE CASCADE
+);
+
+-- Seed the table with the versions we still know about: the current version
+-- of each program and the versions captured by pipeline revisions.
+INSERT INTO program_version (program_id, version, tenant_id, code, schema, created)
+SELECT id, version, tenant_id, code, schema, extract(epoch from status_since)
+FROM program;
+
+INSERT INTO program_version (program_id, version, tenant_id, code, schema, created)
+SELECT DISTINCT ON (ph.id, ph.version) ph.id, ph.version, ph.tenant_id, ph.code, ph.schema, extract(epoch from ph.status_since)
+FROM program_history ph, program p
+WHERE ph.id = p.id
+ORDER BY ph.id, ph.version, ph.status_since
+ON CONFLICT DO NOTHING;
+
+-- Record a new version whenever a program is created or its code changes,
+-- and keep the schema of the current version up to date. The schema of a
+-- version is only ever filled in: recompiling a version resets the schema
+-- of the program but the previous one remains valid for the same code.
+CREATE OR REPLACE FUNCTION record_program_version() RETURNS trigger AS $$
+    BEGIN
+        INSERT INTO program_version (program_id, version, tenant_id, code, schema, created)
+        VALUES (NEW.id, NEW.version, NEW.tenant_id, NEW.code, NEW.schema, extract(epoch from now()))
+        ON CONFLICT (program_id, version) DO UPDATE
+        SET schema = COALESCE(EXCLUDED.schema, program_version.schema);
+        RETURN NEW;
+    END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER program_version_record
+AFTER INSERT OR UPDATE OF version, code, schema ON program
+FOR EACH ROW EXECUTE PROCEDURE record_program_version();
+
+-- Revision ids do not reliably order revisions committed within the same
+-- millisecond, so record the order in which pipeline revisions are committed.
+-- New revisions copy all columns of `pipeline` and get the next value by
+-- default.
+ALTER TABLE pipeline_history ADD COLUMN commit_order bigserial;
@@ -0,0 +1,7 @@
+-- Automatic restarts of failed pipelines (see `RestartPolicy`).
+ALTER TABLE pipeline_runtime_state
+-- Number of times the runner has restarted the pipeline since it was
+-- last deployed.
+ADD COLUMN restart_count bigint NOT NULL DEFAULT 0,
+-- Error that caused the last restart of the pipeline.
+ADD COLUMN last_exit_reason varchar;
@@ -0,0 +1,5 @@
+-- Captured pipeline logs.
+ALTER TABLE pipeline_runtime_state
+-- Location where the runner serves the log of the pipeline,
+-- e.g., a URL.
+ADD COLUMN log_location varchar;
@@ -0,0 +1,5 @@
+-- Blue/green pipeline upgrades.
+ALTER TABLE pipeline_runtime_state
+-- Revision of the pipeline that the running instance of the pipeline
+-- was started from.
+ADD COLUMN running_revision uuid;
@@ -55,13 +55,15 @@ use uuid::{uuid, Uuid};
 pub(crate) use crate::compiler::ProgramStatus;
 pub(crate) use crate::config::ApiServerConfig;
 use crate::db::{
-    storage::Storage, AttachedConnector, AttachedConnectorId, ConnectorId, DBError, PipelineId,
-    PipelineRevision, PipelineStatus, ProgramDescr, ProgramId, ProjectDB, Version,
+    storage::Storage, ApiKeyId, ApiPermission, AttachedConnector, AttachedConnectorId, ConnectorId,
+    DBError, PipelineId, PipelineRevision, PipelineStatus, ProgramDescr, ProgramId, ProgramSchema,
+    ProjectBundle, ProjectDB, Version,
 };
 pub use crate::error::ManagerError;
+use crate::pipeline_logs::parse_log_level;
 use crate::runner::{RunnerApi, RunnerError};
 
-use crate::auth::TenantId;
+use crate::auth::{authorize, generate_api_key, Access, TenantId};
 
 struct ServerAddon;
 
@@ -130,40 +132,72 @@ request is rejected."
         new_program,
         update_program,
         compile_program,
+        cancel_compile,
         delete_program,
+        list_program_versions,
+        program_diff,
+        rollback_program,
         new_pipeline,
         update_pipeline,
         list_pipelines,
         pipeline_stats,
+        pipeline_logs,
         get_pipeline,
         get_pipeline_config,
         pipeline_validate,
         pipeline_action,
         pipeline_deployed,
         pipeline_delete,
+        list_pipeline_versions,
         list_connectors,
         get_connector,
         new_connector,
         update_connector,
         delete_connector,
+        list_connector_versions,
         http_input,
         http_output,
+        new_api_key,
+        list_api_keys,
+        delete_api_key,
+        export_project,
+        import_project,
+        apply_project,
     ),
     components(schemas(
         crate::compiler::SqlCompilerMessage,
+        crate::compiler::RustCompilerMessage,
+        crate::compiler::SourceSpan,
         crate::db::AttachedConnector,
         crate::db::ProgramDescr,
+        crate::db::ProgramVersionDescr,
         crate::db::ProgramSchema,
         crate::db::Relation,
         crate::db::Field,
         crate::db::ColumnType,
         crate::db::ConnectorDescr,
+        crate::db::ConnectorHistoryEntry,
         crate::db::Pipeline,
         crate::db::PipelineRuntimeState,
         crate::db::PipelineDescr,
         crate::db::PipelineRevision,
+        crate::db::PipelineHistoryEntry,
         crate::db::Revision,
         crate::db::PipelineStatus,
+        crate::db::ApiPermission,
+        crate::db::ApiKeyDescr,
+        crate::db::ProjectBundle,
+        crate::db::BundledProgram,
+        crate::db::BundledConnector,
+        crate::db::BundledPipeline,
+        crate::db::BundledAttachedConnector,
+        crate::db::ImportAction,
+        crate::db::ImportedObject,
+        crate::db::ImportReport,
+        crate::db::ObjectKind,
+        crate::db::ApplyAction,
+        crate::db::ApplyStep,
+        crate::db::ApplyPlan,
         dbsp_adapters::EgressMode,
         dbsp_adapters::PipelineConfig,
         dbsp_adapters::InputEndpointConfig,
@@ -173,6 +207,11 @@ request is rejected."
         dbsp_adapters::TransportConfig,
         dbsp_adapters::FormatConfig,
         dbsp_adapters::RuntimeConfig,
+        dbsp_adapters::MultihostConfig,
+        dbsp_adapters::HostConfig,
+        dbsp_adapters::TracingConfig,
+        dbsp_adapters::RestartPolicy,
+        dbsp_adapters::RestartMode,
         dbsp_adapters::ConnectorConfig,
         dbsp_adapters::TransportConfig,
         dbsp_adapters::FormatConfig,
@@ -192,6 +231,7 @@ request is rejected."
         PipelineId,
         ConnectorId,
         AttachedConnectorId,
+        ApiKeyId,
         Version,
         ProgramStatus,
         ErrorResponse,
@@ -201,6 +241,7 @@ request is rejected."
         UpdateProgramRequest,
         UpdateProgramResponse,
         CompileProgramRequest,
+        ProgramDiffResponse,
         NewPipelineRequest,
         NewPipelineResponse,
         UpdatePipelineRequest,
@@ -209,11 +250,16 @@ request is rejected."
         NewConnectorResponse,
         UpdateConnectorRequest,
         UpdateConnectorResponse,
+        NewApiKeyRequest,
+        NewApiKeyResponse,
+        BundleFormat,
     ),),
     tags(
         (name = "Programs", description = "Manage programs"),
         (name = "Pipelines", description = "Manage pipelines"),
         (name = "Connectors", description = "Manage data connectors"),
+        (name = "API keys", description = "Manage API keys"),
+        (name = "Projects", description = "Export and import projects"),
     ),
 )]
 pub struct ApiDoc;
@@ -338,24 +384,37 @@ fn api_scope() -> Scope {
         .service(new_program)
         .service(update_program)
         .service(compile_program)
+        .service(cancel_compile)
         .service(delete_program)
+        .service(list_program_versions)
+        .service(program_diff)
+        .service(rollback_program)
         .service(new_pipeline)
         .service(update_pipeline)
         .service(list_pipelines)
         .service(pipeline_stats)
+        .service(pipeline_logs)
         .service(get_pipeline)
         .service(get_pipeline_config)
         .service(pipeline_action)
         .service(pipeline_validate)
         .service(pipeline_deployed)
         .service(pipeline_delete)
+        .service(list_pipeline_versions)
         .service(list_connectors)
         .service(get_connector)
         .service(new_connector)
         .service(update_connector)
         .service(delete_connector)
+        .service(list_connector_versions)
         .service(http_input)
         .service(http_output)
+        .service(new_api_key)
+        .service(list_api_keys)
+        .service(delete_api_key)
+        .service(export_project)
+        .service(import_project)
+        .service(apply_project)
 }
 
 // Example errors for use in OpenApi docs.
@@ -441,6 +500,13 @@ fn example_outdated_program_version() -> ErrorResponse {
     })
 }
 
+fn example_unknown_program_version() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&DBError::UnknownProgramVersion {
+        program_id: ProgramId(uuid!("67e55044-10b1-426f-9247-bb680e5fe0c8")),
+        version: Version(5),
+    })
+}
+
 fn example_unknown_pipeline() -> ErrorResponse {
     ErrorResponse::from_error_nolog(&DBError::UnknownPipeline {
         pipeline_id: PipelineId(uuid!("2e79afe1-ff4d-44d3-af5f-9397de7746c0")),
@@ -453,6 +519,18 @@ fn example_unknown_connector() -> ErrorResponse {
     })
 }
 
+fn example_unknown_api_key() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&DBError::UnknownApiKey {
+        api_key_id: ApiKeyId(uuid!("d764b9e2-19f2-4572-ba20-8b42641b07c4")),
+    })
+}
+
+fn example_permission_denied() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&ManagerError::PermissionDenied {
+        operation: Access::Write.to_string(),
+    })
+}
+
 fn example_unknown_name() -> ErrorResponse {
     ErrorResponse::from_error_nolog(&DBError::UnknownName {
         name: "unknown_name".to_string(),
@@ -513,6 +591,24 @@ fn example_program_has_errors() -> ErrorResponse {
     ErrorResponse::from_error_nolog(&DBError::ProgramFailedToCompile)
 }
 
+fn example_invalid_project_bundle() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&DBError::InvalidProjectBundle {
+        reason: "pipeline 'my_pipeline' refers to unknown program 'my_program'".to_string(),
+    })
+}
+
+fn example_apply_conflict() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&DBError::ApplyConflict {
+        reason: "pipeline 'my_pipeline' must be shut down before it can be deleted".to_string(),
+    })
+}
+
+fn example_program_not_compiling() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&DBError::ProgramNotCompiling {
+        program_id: ProgramId(uuid!("2e79afe1-ff4d-44d3-af5f-9397de7746c0")),
+    })
+}
+
 fn example_pipeline_invalid_input_ac() -> ErrorResponse {
     ErrorResponse::from_error_nolog(&DBError::TablesNotInSchema {
         missing: vec![("ac_name".to_string(), "my_table".to_string())],
@@ -575,6 +671,22 @@ fn parse_uuid_param(req: &HttpRequest, param_name: &'static str) -> Result<Uuid,
     }
 }
 
+fn parse_version_param(
+    req: &HttpRequest,
+    param_name: &'static str,
+) -> Result<Version, ManagerError> {
+    match req.match_info().get(param_name) {
+        None => Err(ManagerError::MissingUrlEncodedParam { param: param_name }),
+        Some(version) => match version.parse::<i64>() {
+            Err(e) => Err(ManagerError::InvalidVersionParam {
+                value: version.to_string(),
+                error: e.to_string(),
+            }),
+            Ok(version) => Ok(Version(version)),
+        },
+    }
+}
+
 fn parse_pipeline_action(req: &HttpRequest) -> Result<&str, ManagerError> {
     match req.match_info().get("action") {
         None => Err(ManagerError::MissingUrlEncodedParam { param: "action" }),
@@ -611,9 +723,11 @@ struct ProgramCodeResponse {
 async fn get_programs(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: web::Query<ProgramIdOrNameQuery>,
     with_code: web::Query<WithCodeQuery>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let with_code = with_code.with_code.unwrap_or(false);
     if let Some(id) = req.id {
         let program = state
@@ -668,9 +782,11 @@ async fn get_programs(
 async fn get_program(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
     query: web::Query<WithCodeQuery>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let program_id = ProgramId(parse_uuid_param(&req, "program_id")?);
     let with_code = query.with_code.unwrap_or(false);
     let program = state
@@ -726,9 +842,11 @@ struct NewProgramResponse {
 async fn new_program(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     request: web::Json<NewProgramRequest>,
-) -> Result<HttpResponse, DBError> {
-    do_new_program(state, tenant_id, request).await
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    Ok(do_new_program(state, tenant_id, request).await?)
 }
 
 async fn do_new_program(
@@ -813,9 +931,11 @@ struct UpdateProgramResponse {
 async fn update_program(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     request: HttpRequest,
     body: web::Json<UpdateProgramRequest>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let program_id = ProgramId(parse_uuid_param(&request, "program_id")?);
     let version = state
         .db
@@ -873,9 +993,11 @@ struct CompileProgramRequest {
 async fn compile_program(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     request: HttpRequest,
     body: web::Json<CompileProgramRequest>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let program_id = ProgramId(parse_uuid_param(&request, "program_id")?);
     state
         .db
@@ -890,6 +1012,55 @@ async fn compile_program(
     Ok(HttpResponse::Accepted().finish())
 }
 
+/// Cancel the compilation of a program.
+///
+/// Removes the program from the compilation queue or aborts the running
+/// compiler, and resets the program status to `None`.
+#[utoipa::path(
+    request_body = CompileProgramRequest,
+    responses(
+        (status = ACCEPTED, description = "Compilation cancelled."),
+        (status = BAD_REQUEST
+            , description = "The program is not queued or being compiled."
+            , body = ErrorResponse
+            , example = json!(example_program_not_compiling())),
+        (status = NOT_FOUND
+            , description = "Specified program id does not exist."
+            , body = ErrorResponse
+            , example = json!(example_unknown_program())),
+        (status = CONFLICT
+            , description = "Program version specified in the request doesn't match the latest program version in the database."
+            , body = ErrorResponse
+            , example = json!(example_outdated_program_version())),
+    ),
+    params(
+        ("program_id" = Uuid, Path, description = "Unique program identifier")
+    ),
+    tag = "Programs"
+)]
+#[post("/programs/{program_id}/cancel_compile")]
+async fn cancel_compile(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    request: HttpRequest,
+    body: web::Json<CompileProgramRequest>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    let program_id = ProgramId(parse_uuid_param(&request, "program_id")?);
+    state
+        .db
+        .lock()
+        .await
+        .cancel_program_compilation(*tenant_id, program_id, body.version)
+        .await?;
+    info!(
+        "Compilation of program {program_id} version {} cancelled (tenant:{})",
+        body.version, *tenant_id
+    );
+    Ok(HttpResponse::Accepted().finish())
+}
+
 /// Delete a program.
 ///
 /// Deletion fails if there is at least one pipeline associated with the program.
@@ -922,8 +1093,10 @@ async fn compile_program(
 async fn delete_program(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let program_id = ProgramId(parse_uuid_param(&req, "program_id")?);
     let resp = do_delete_program(state, *tenant_id, program_id).await?;
     info!("Deleted program {program_id} (tenant:{})", *tenant_id);
@@ -941,6 +1114,193 @@ async fn do_delete_program(
         .map(|_| HttpResponse::Ok().finish())
 }
 
+/// List all versions of a program, oldest first.
+///
+/// A new version is recorded every time the program's code changes.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Program versions retrieved successfully.", body = [ProgramVersionDescr]),
+        (status = NOT_FOUND
+            , description = "Specified program id does not exist."
+            , body = ErrorResponse
+            , example = json!(example_unknown_program())),
+    ),
+    params(
+        ("program_id" = Uuid, Path, description = "Unique program identifier"),
+        WithCodeQuery
+    ),
+    tag = "Programs"
+)]
+#[get("/programs/{program_id}/versions")]
+async fn list_program_versions(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+    query: web::Query<WithCodeQuery>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let program_id = ProgramId(parse_uuid_param(&req, "program_id")?);
+    let with_code = query.with_code.unwrap_or(false);
+    let versions = state
+        .db
+        .lock()
+        .await
+        .list_program_versions(*tenant_id, program_id, with_code)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&versions))
+}
+
+/// Response to a program diff request.
+#[derive(Serialize, ToSchema)]
+struct ProgramDiffResponse {
+    /// Version the diff is computed from.
+    from: Version,
+    /// Version the diff is computed to.
+    to: Version,
+    /// Line-by-line diff of the SQL code.  Every line is prefixed with `' '`
+    /// if it is present in both versions, `'-'` if it was removed and `'+'`
+    /// if it was added.
+    #[schema(
+        example = " CREATE TABLE Example(name varchar);\n-CREATE VIEW V AS SELECT * FROM Example;\n+CREATE VIEW V AS SELECT name FROM Example;\n"
+    )]
+    code: String,
+    /// Line-by-line diff of the program schemas rendered as JSON, in the
+    /// same format as `code`.  A version that has not been compiled yet has
+    /// an empty schema.
+    schema: String,
+}
+
+/// Compute a line-by-line diff between `old` and `new`.
+fn diff_text(old: &str, new: &str) -> String {
+    let mut diff = String::new();
+    for line in diff::lines(old, new) {
+        let (prefix, line) = match line {
+            diff::Result::Left(line) => ('-', line),
+            diff::Result::Both(line, _) => (' ', line),
+            diff::Result::Right(line) => ('+', line),
+        };
+        diff.push(prefix);
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    diff
+}
+
+fn schema_text(schema: &Option<ProgramSchema>) -> String {
+    schema
+        .as_ref()
+        .and_then(|schema| serde_json::to_string_pretty(schema).ok())
+        .unwrap_or_default()
+}
+
+/// Compare the SQL code and schema of two versions of a program.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Diff computed successfully.", body = ProgramDiffResponse),
+        (status = NOT_FOUND
+            , description = "Specified program id or version does not exist."
+            , body = ErrorResponse
+            , examples(
+                ("Unknown program" = (value = json!(example_unknown_program()))),
+                ("Unknown program version" = (value = json!(example_unknown_program_version())))
+            ),
+        ),
+    ),
+    params(
+        ("program_id" = Uuid, Path, description = "Unique program identifier"),
+        ProgramDiffQuery
+    ),
+    tag = "Programs"
+)]
+#[get("/programs/{program_id}/diff")]
+async fn program_diff(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+    query: web::Query<ProgramDiffQuery>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let program_id = ProgramId(parse_uuid_param(&req, "program_id")?);
+    let (from, to) = {
+        let db = state.db.lock().await;
+        let from = db
+            .get_program_version(*tenant_id, program_id, Version(query.from))
+            .await?;
+        let to = db
+            .get_program_version(*tenant_id, program_id, Version(query.to))
+            .await?;
+        (from, to)
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&ProgramDiffResponse {
+            from: from.version,
+            to: to.version,
+            code: diff_text(
+                from.code.as_deref().unwrap_or_default(),
+                to.code.as_deref().unwrap_or_default(),
+            ),
+            schema: diff_text(&schema_text(&from.schema), &schema_text(&to.schema)),
+        }))
+}
+
+/// Roll a program back to the code of an earlier version.
+///
+/// The restored code becomes a new version of the program, which is queued
+/// for compilation.  If a binary compiled from the same code is still
+/// available, it is reused instead of compiling the program again.  Does
+/// nothing if the current code of the program already matches the code of
+/// the specified version.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Program rolled back successfully.", body = UpdateProgramResponse),
+        (status = NOT_FOUND
+            , description = "Specified program id or version does not exist."
+            , body = ErrorResponse
+            , examples(
+                ("Unknown program" = (value = json!(example_unknown_program()))),
+                ("Unknown program version" = (value = json!(example_unknown_program_version())))
+            ),
+        ),
+    ),
+    params(
+        ("program_id" = Uuid, Path, description = "Unique program identifier"),
+        ("version" = i64, Path, description = "Program version to roll back to")
+    ),
+    tag = "Programs"
+)]
+#[post("/programs/{program_id}/rollback/{version}")]
+async fn rollback_program(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    let program_id = ProgramId(parse_uuid_param(&req, "program_id")?);
+    let target = parse_version_param(&req, "version")?;
+    let version = state
+        .db
+        .lock()
+        .await
+        .rollback_program(*tenant_id, program_id, target)
+        .await?;
+    info!(
+        "Rolled back program {program_id} to the code of version {target} as version {version} (tenant:{})",
+        *tenant_id
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&UpdateProgramResponse { version }))
+}
+
 /// Request to create a new pipeline.
 #[derive(Debug, Deserialize, ToSchema)]
 struct NewPipelineRequest {
@@ -990,8 +1350,10 @@ struct NewPipelineResponse {
 async fn new_pipeline(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     request: web::Json<NewPipelineRequest>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     debug!("Received new-pipeline request: {request:?}");
     let (pipeline_id, version) = state
         .db
@@ -1067,9 +1429,11 @@ struct UpdatePipelineResponse {
 async fn update_pipeline(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
     body: web::Json<UpdatePipelineRequest>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
     let version = state
         .db
@@ -1104,8 +1468,10 @@ async fn update_pipeline(
 async fn list_pipelines(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     query: web::Query<PipelineIdOrNameQuery>,
-) -> Result<HttpResponse, DBError> {
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipelines = if let Some(id) = query.id {
         let pipeline = state
             .db
@@ -1148,8 +1514,10 @@ async fn list_pipelines(
 async fn pipeline_deployed(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
 
     let descr: Option<crate::db::PipelineRevision> = match state
@@ -1198,8 +1566,10 @@ async fn pipeline_deployed(
 async fn pipeline_stats(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
 
     state
@@ -1208,6 +1578,73 @@ async fn pipeline_stats(
         .await
 }
 
+fn example_invalid_log_level() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&ManagerError::InvalidLogLevel {
+        level: "verbose".to_string(),
+    })
+}
+
+fn example_no_pipeline_logs() -> ErrorResponse {
+    ErrorResponse::from_error_nolog(&RunnerError::NoPipelineLogs {
+        pipeline_id: PipelineId(uuid!("2e79afe1-ff4d-44d3-af5f-9397de7746c0")),
+    })
+}
+
+/// Retrieve the log of a pipeline.
+///
+/// The runner captures the output of the pipeline process in a size-bounded
+/// log file, which remains available after the pipeline has been shut down.
+/// The log is returned as plain text, one log record per line.
+///
+/// When `follow` is set, the response is streamed and the connection is kept
+/// open, delivering new lines as the pipeline logs them.
+#[utoipa::path(
+    responses(
+        (status = OK
+            , description = "Pipeline log retrieved successfully."
+            , content_type = "text/plain"
+            , body = String),
+        (status = BAD_REQUEST
+            , description = "Invalid request."
+            , body = ErrorResponse
+            , examples(
+                ("Invalid Pipeline ID" = (description = "Specified pipeline id is not a valid uuid.", value = json!(example_invalid_uuid_param()))),
+                ("Invalid log level" = (description = "Specified log level is not valid.", value = json!(example_invalid_log_level()))),
+            )
+        ),
+        (status = NOT_FOUND
+            , description = "Specified pipeline id does not exist or the pipeline has never been deployed."
+            , body = ErrorResponse
+            , examples(
+                ("Unknown pipeline" = (description = "Specified pipeline id does not exist.", value = json!(example_unknown_pipeline()))),
+                ("No logs" = (description = "The pipeline has never been deployed.", value = json!(example_no_pipeline_logs()))),
+            )
+        ),
+    ),
+    params(
+        ("pipeline_id" = Uuid, Path, description = "Unique pipeline identifier"),
+        PipelineLogsQuery
+    ),
+    tag = "Pipelines"
+)]
+#[get("/pipelines/{pipeline_id}/logs")]
+async fn pipeline_logs(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+    query: web::Query<PipelineLogsQuery>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
+    parse_log_level(&query.level)?;
+
+    state
+        .runner
+        .pipeline_logs(*tenant_id, pipeline_id, req)
+        .await
+}
+
 /// Fetch a pipeline by ID.
 #[utoipa::path(
     responses(
@@ -1228,8 +1665,10 @@ async fn pipeline_stats(
 async fn get_pipeline(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
     let pipeline: crate::db::Pipeline = state
         .db
@@ -1269,8 +1708,10 @@ async fn get_pipeline(
 async fn get_pipeline_config(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
     let expanded_config = state
         .db
@@ -1321,8 +1762,10 @@ async fn get_pipeline_config(
 async fn pipeline_validate(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
 
     let db = state.db.lock().await;
@@ -1350,6 +1793,19 @@ async fn pipeline_validate(
 /// - 'start': Start processing data.
 /// - 'pause': Pause the pipeline.
 /// - 'shutdown': Terminate the execution of the pipeline.
+/// - 'upgrade': Upgrade a running or paused pipeline to the current version
+///   of its program, config and connectors.  The new revision of the pipeline
+///   starts alongside the running one and takes over once it has
+///   initialized, including its input connectors and HTTP endpoints.  If the
+///   new revision fails to initialize, the old one keeps running and the
+///   error is reported in the `error` field of the pipeline state.  The new
+///   revision starts with empty state and rebuilds it by replaying the input
+///   of its input connectors from the beginning.  The upgrade is refused if
+///   the input cannot be replayed: when an input connector other than a file
+///   or URL connector, or a Kafka connector with `auto.offset.reset` set to
+///   `earliest`, is attached, or when the pipeline has received data over
+///   HTTP.  It is also refused if the pipeline has output connectors, which
+///   would send the output computed from the replayed input again.
 #[utoipa::path(
     responses(
         (status = ACCEPTED
@@ -1380,7 +1836,7 @@ async fn pipeline_validate(
     ),
     params(
         ("pipeline_id" = Uuid, Path, description = "Unique pipeline identifier"),
-        ("action" = String, Path, description = "Pipeline action [start, pause, shutdown]")
+        ("action" = String, Path, description = "Pipeline action [start, pause, shutdown, upgrade]")
     ),
     tag = "Pipelines"
 )]
@@ -1388,8 +1844,10 @@ async fn pipeline_validate(
 async fn pipeline_action(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
     let action = parse_pipeline_action(&req)?;
 
@@ -1402,6 +1860,12 @@ async fn pipeline_action(
                 .shutdown_pipeline(*tenant_id, pipeline_id)
                 .await?
         }
+        "upgrade" => {
+            state
+                .runner
+                .upgrade_pipeline(*tenant_id, pipeline_id)
+                .await?
+        }
         _ => Err(ManagerError::InvalidPipelineAction {
             action: action.to_string(),
         })?,
@@ -1443,8 +1907,10 @@ async fn pipeline_action(
 async fn pipeline_delete(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
 
     state
@@ -1456,6 +1922,43 @@ async fn pipeline_delete(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// List all committed revisions of a pipeline, oldest first.
+///
+/// A new revision is committed every time a modified pipeline is deployed.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Pipeline revisions retrieved successfully.", body = [PipelineHistoryEntry]),
+        (status = NOT_FOUND
+            , description = "Specified pipeline id does not exist."
+            , body = ErrorResponse
+            , example = json!(example_unknown_pipeline())),
+    ),
+    params(
+        ("pipeline_id" = Uuid, Path, description = "Unique pipeline identifier"),
+    ),
+    tag = "Pipelines"
+)]
+#[get("/pipelines/{pipeline_id}/versions")]
+async fn list_pipeline_versions(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let pipeline_id = PipelineId(parse_uuid_param(&req, "pipeline_id")?);
+    let history = state
+        .db
+        .lock()
+        .await
+        .list_pipeline_history(*tenant_id, pipeline_id)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&history))
+}
+
 /// Fetch connectors, optionally filtered by name or ID
 #[utoipa::path(
     responses(
@@ -1476,8 +1979,10 @@ async fn pipeline_delete(
 async fn list_connectors(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: web::Query<ConnectorIdOrNameQuery>,
-) -> Result<HttpResponse, DBError> {
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let descr = if let Some(id) = req.id {
         vec![
             state
@@ -1535,8 +2040,10 @@ struct NewConnectorResponse {
 async fn new_connector(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     request: web::Json<NewConnectorRequest>,
-) -> Result<HttpResponse, DBError> {
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let connector_id = state
         .db
         .lock()
@@ -1590,9 +2097,11 @@ struct UpdateConnectorResponse {}
 async fn update_connector(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
     body: web::Json<UpdateConnectorRequest>,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let connector_id = ConnectorId(parse_uuid_param(&req, "connector_id")?);
     state
         .db
@@ -1635,8 +2144,10 @@ async fn update_connector(
 async fn delete_connector(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
     let connector_id = ConnectorId(parse_uuid_param(&req, "connector_id")?);
 
     state
@@ -1650,6 +2161,44 @@ async fn delete_connector(
     Ok(HttpResponse::Ok().finish())
 }
 
+/// List all revisions of a connector, oldest first.
+///
+/// A connector revision is committed every time a modified pipeline that
+/// uses the connector is deployed.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Connector revisions retrieved successfully.", body = [ConnectorHistoryEntry]),
+        (status = NOT_FOUND
+            , description = "Specified connector id does not exist."
+            , body = ErrorResponse
+            , example = json!(example_unknown_connector())),
+    ),
+    params(
+        ("connector_id" = Uuid, Path, description = "Unique connector identifier"),
+    ),
+    tag = "Connectors"
+)]
+#[get("/connectors/{connector_id}/versions")]
+async fn list_connector_versions(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let connector_id = ConnectorId(parse_uuid_param(&req, "connector_id")?);
+    let history = state
+        .db
+        .lock()
+        .await
+        .list_connector_history(*tenant_id, connector_id)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&history))
+}
+
 /// Fetch a connector by ID.
 #[utoipa::path(
     responses(
@@ -1668,8 +2217,10 @@ async fn delete_connector(
 async fn get_connector(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
 ) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
     let connector_id = ConnectorId(parse_uuid_param(&req, "connector_id")?);
     let descr = state
         .db
@@ -1710,6 +2261,14 @@ pub struct WithCodeQuery {
     with_code: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ProgramDiffQuery {
+    /// Program version to compute the diff from.
+    from: i64,
+    /// Program version to compute the diff to.
+    to: i64,
+}
+
 #[derive(Debug, Deserialize, IntoParams)]
 pub struct PipelineIdOrNameQuery {
     /// Unique pipeline id.
@@ -1718,6 +2277,22 @@ pub struct PipelineIdOrNameQuery {
     name: Option<String>,
 }
 
+// `tail` and `follow` are validated here, but the runner interprets them:
+// the query string is forwarded as is.
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct PipelineLogsQuery {
+    /// Only return the last `tail` lines of the log.
+    #[allow(dead_code)]
+    tail: Option<usize>,
+    /// Keep the connection open and stream new lines as they are
+    /// logged.  The default value is `false`.
+    #[allow(dead_code)]
+    follow: Option<bool>,
+    /// Only return lines logged at this level or a more severe one.
+    /// Must be one of 'error', 'warn', 'info', 'debug', or 'trace'.
+    level: Option<String>,
+}
+
 /// Push data to a SQL table.
 ///
 /// The client sends data encoded using the format specified in the `?format=`
@@ -1780,6 +2355,7 @@ pub struct PipelineIdOrNameQuery {
 async fn http_input(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
     body: web::Payload,
 ) -> Result<HttpResponse, ManagerError> {
@@ -1798,6 +2374,14 @@ async fn http_input(
     };
     debug!("Table name {table_name:?}");
 
+    authorize(
+        &permissions,
+        Access::Ingress {
+            pipeline_id,
+            relation: table_name,
+        },
+    )?;
+
     let endpoint = format!("ingress/{table_name}");
 
     state
@@ -1864,6 +2448,7 @@ async fn http_input(
 async fn http_output(
     state: WebData<ServerState>,
     tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
     req: HttpRequest,
     body: web::Payload,
 ) -> Result<HttpResponse, ManagerError> {
@@ -1882,6 +2467,14 @@ async fn http_output(
     };
     debug!("Table name {table_name:?}");
 
+    authorize(
+        &permissions,
+        Access::Egress {
+            pipeline_id,
+            relation: table_name,
+        },
+    )?;
+
     let endpoint = format!("egress/{table_name}");
 
     state
@@ -1889,3 +2482,326 @@ async fn http_output(
         .forward_to_pipeline_as_stream(*tenant_id, pipeline_id, &endpoint, req, body)
         .await
 }
+
+/// Request to create a new API key.
+#[derive(Debug, Deserialize, ToSchema)]
+struct NewApiKeyRequest {
+    /// Key name, unique within the tenant.
+    #[schema(example = "ingest-orders")]
+    name: String,
+    /// Permissions granted to the key.
+    scopes: Vec<ApiPermission>,
+}
+
+/// Response to an API key creation request.
+#[derive(Serialize, ToSchema)]
+struct NewApiKeyResponse {
+    /// Unique id assigned to the new key.
+    api_key_id: ApiKeyId,
+    /// Key name.
+    name: String,
+    /// Generated API key.  The pipeline manager only stores a hash of the
+    /// key, so this is the only time the key is returned to the client.
+    api_key: String,
+}
+
+/// Create a new API key.
+///
+/// The key authorizes requests that pass it in the `x-api-key` header,
+/// subject to the scopes granted to the key.
+#[utoipa::path(
+    request_body = NewApiKeyRequest,
+    responses(
+        (status = CREATED, description = "API key created successfully.", body = NewApiKeyResponse),
+        (status = CONFLICT
+            , description = "An API key with this name already exists."
+            , body = ErrorResponse
+            , example = json!(example_duplicate_name())),
+        (status = FORBIDDEN
+            , description = "The caller is not permitted to manage API keys."
+            , body = ErrorResponse
+            , example = json!(example_permission_denied())),
+    ),
+    tag = "API keys"
+)]
+#[post("/api_keys")]
+async fn new_api_key(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    request: web::Json<NewApiKeyRequest>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    let api_key = generate_api_key();
+    let api_key_id = state
+        .db
+        .lock()
+        .await
+        .store_api_key_hash(
+            *tenant_id,
+            Uuid::now_v7(),
+            &request.name,
+            api_key.clone(),
+            request.scopes.clone(),
+        )
+        .await?;
+
+    info!("Created API key {api_key_id} (tenant:{})", *tenant_id);
+    Ok(HttpResponse::Created()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&NewApiKeyResponse {
+            api_key_id,
+            name: request.name.clone(),
+            api_key,
+        }))
+}
+
+/// List the API keys of the tenant.
+///
+/// Returns key metadata only; keys themselves cannot be retrieved after
+/// they are created.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "API keys retrieved successfully.", body = [ApiKeyDescr]),
+    ),
+    tag = "API keys"
+)]
+#[get("/api_keys")]
+async fn list_api_keys(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let keys = state.db.lock().await.list_api_keys(*tenant_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoCache]))
+        .json(&keys))
+}
+
+/// Revoke an API key.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "API key successfully revoked."),
+        (status = NOT_FOUND
+            , description = "Specified API key id does not exist."
+            , body = ErrorResponse
+            , example = json!(example_unknown_api_key())),
+    ),
+    params(
+        ("api_key_id" = Uuid, Path, description = "Unique API key identifier")
+    ),
+    tag = "API keys"
+)]
+#[delete("/api_keys/{api_key_id}")]
+async fn delete_api_key(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    let api_key_id = ApiKeyId(parse_uuid_param(&req, "api_key_id")?);
+    state
+        .db
+        .lock()
+        .await
+        .delete_api_key(*tenant_id, api_key_id)
+        .await?;
+
+    info!("Revoked API key {api_key_id} (tenant:{})", *tenant_id);
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Serialization format of a project bundle.
+#[derive(Debug, Default, Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Format of the bundle.  The default value is `json`.
+    format: Option<BundleFormat>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ImportQuery {
+    /// Only report what the import would do, without modifying anything.
+    /// The default value is `false`.
+    dry_run: Option<bool>,
+}
+
+/// Export the programs, connectors and pipelines of the tenant as a bundle.
+///
+/// The bundle can be imported into another pipeline manager installation
+/// using the `/import` endpoint.  Objects in the bundle refer to each other
+/// by name.
+#[utoipa::path(
+    responses(
+        (status = OK, description = "Project bundle.", body = ProjectBundle),
+    ),
+    params(ExportQuery),
+    tag = "Projects"
+)]
+#[get("/export")]
+async fn export_project(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Read)?;
+    let bundle = state.db.lock().await.export_project(*tenant_id).await?;
+
+    let mut response = HttpResponse::Ok();
+    response.insert_header(CacheControl(vec![CacheDirective::NoCache]));
+    Ok(match query.format.unwrap_or_default() {
+        BundleFormat::Json => response.json(&bundle),
+        BundleFormat::Yaml => {
+            let yaml = serde_yaml::to_string(&bundle).map_err(|e| {
+                ManagerError::BundleSerializationError {
+                    error: e.to_string(),
+                }
+            })?;
+            response.content_type("application/x-yaml").body(yaml)
+        }
+    })
+}
+
+/// Import a project bundle.
+///
+/// The bundle, in JSON or YAML format, is typically produced by the
+/// `/export` endpoint of another pipeline manager installation.  Programs,
+/// connectors and pipelines are matched by name: objects that don't exist
+/// are created, existing objects that differ from the bundle are updated.
+/// Updated programs must be compiled again.  The import is atomic: if one of
+/// the objects cannot be imported, nothing is modified.
+///
+/// The response describes what was done with each object, including the
+/// fields of existing objects that were overwritten.  With `dry_run`, nothing
+/// is modified, which can be used to review conflicts before importing.
+#[utoipa::path(
+    request_body = ProjectBundle,
+    responses(
+        (status = OK, description = "Project bundle imported.", body = ImportReport),
+        (status = BAD_REQUEST
+            , description = "The bundle is malformed or refers to unknown objects."
+            , body = ErrorResponse
+            , example = json!(example_invalid_project_bundle())),
+    ),
+    params(ImportQuery),
+    tag = "Projects"
+)]
+#[post("/import")]
+async fn import_project(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    query: web::Query<ImportQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    // YAML is a superset of JSON, so this accepts both formats.
+    let bundle: ProjectBundle =
+        serde_yaml::from_slice(&body).map_err(|e| DBError::InvalidProjectBundle {
+            reason: e.to_string(),
+        })?;
+    let dry_run = query.dry_run.unwrap_or(false);
+    let report = state
+        .db
+        .lock()
+        .await
+        .import_project(*tenant_id, &bundle, dry_run)
+        .await?;
+
+    if !dry_run {
+        info!(
+            "Imported project bundle with {} programs, {} connectors and {} pipelines (tenant:{})",
+            bundle.programs.len(),
+            bundle.connectors.len(),
+            bundle.pipelines.len(),
+            *tenant_id
+        );
+    }
+    Ok(HttpResponse::Ok().json(&report))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ApplyQuery {
+    /// Only compute the plan, without executing it.  The default value is
+    /// `false`.
+    dry_run: Option<bool>,
+}
+
+/// Bring the programs, connectors and pipelines of the tenant to a desired
+/// state.
+///
+/// The desired state is a project bundle in JSON or YAML format, in the
+/// format produced by the `/export` endpoint, e.g., checked into a git
+/// repository.  The server computes a plan against the current state:
+///
+/// - Objects in the desired state are created or updated by name.
+/// - Objects that are not in the desired state are deleted.  Pipelines to
+///   delete must be shut down.
+/// - New and modified programs are queued for compilation.
+/// - Running and paused pipelines whose committed revision differs from the
+///   desired state are upgraded to a new revision.  If the program of such a
+///   pipeline must be compiled first, the step is marked as `deferred`:
+///   applying the same state again once compilation succeeds redeploys the
+///   pipeline.
+///
+/// With `dry_run`, the plan is returned without executing it.  Otherwise,
+/// the plan is validated and executed without other API requests observing
+/// intermediate states.  Applying the same desired state repeatedly is safe.
+#[utoipa::path(
+    request_body = ProjectBundle,
+    responses(
+        (status = OK, description = "The plan, executed unless `dry_run` is set.", body = ApplyPlan),
+        (status = BAD_REQUEST
+            , description = "The desired state is malformed or refers to unknown objects."
+            , body = ErrorResponse
+            , example = json!(example_invalid_project_bundle())),
+        (status = CONFLICT
+            , description = "The desired state cannot be applied in the current state of the tenant."
+            , body = ErrorResponse
+            , example = json!(example_apply_conflict())),
+    ),
+    params(ApplyQuery),
+    tag = "Projects"
+)]
+#[post("/apply")]
+async fn apply_project(
+    state: WebData<ServerState>,
+    tenant_id: ReqData<TenantId>,
+    permissions: ReqData<Vec<ApiPermission>>,
+    query: web::Query<ApplyQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, ManagerError> {
+    authorize(&permissions, Access::Write)?;
+    // YAML is a superset of JSON, so this accepts both formats.
+    let desired: ProjectBundle =
+        serde_yaml::from_slice(&body).map_err(|e| DBError::InvalidProjectBundle {
+            reason: e.to_string(),
+        })?;
+    let dry_run = query.dry_run.unwrap_or(false);
+    let plan = state
+        .db
+        .lock()
+        .await
+        .apply_project(*tenant_id, &desired, dry_run)
+        .await?;
+
+    if !dry_run {
+        info!(
+            "Applied desired state in {} steps (tenant:{})",
+            plan.steps.len(),
+            *tenant_id
+        );
+    }
+    Ok(HttpResponse::Ok().json(&plan))
+}
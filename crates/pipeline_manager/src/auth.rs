@@ -30,9 +30,16 @@
 //!
 //! For programmatic access, a user authenticated via a Bearer token may
 //! generate API keys. These API keys can then be used in the REST API along
-//! with an "x-api-key" header to authorize access. For now, we simply have two
-//! permission types: Read and Write. Later, we will expand to have fine-grained
-//! access to specific API resources.
+//! with an "x-api-key" header to authorize access. API keys are created,
+//! listed, and revoked via the `/api_keys` endpoints.
+//!
+//! Each key is granted a set of scopes (see `ApiPermission`). Read and Write
+//! scopes apply to all resources of the tenant. Ingress and Egress scopes
+//! are limited to pushing data to, or reading data from, the tables and
+//! views of a single pipeline, optionally restricted to one table or view.
+//! Every API handler checks the scopes of the caller against the operation it
+//! performs (see `authorize`). Users authenticated via a bearer token have
+//! Read and Write access.
 //!
 //! API keys are randomly generated 128 character sequences that are never
 //! stored in the pipeline manager or in the database. It is the responsibility
@@ -62,8 +69,8 @@ use static_assertions::assert_impl_any;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-use crate::api::ServerState;
-use crate::db::{storage::Storage, ApiPermission, DBError, ProjectDB};
+use crate::api::{ManagerError, ServerState};
+use crate::db::{storage::Storage, ApiPermission, DBError, PipelineId, ProjectDB};
 
 // Used when no auth is configured, so we tag the request with the default user
 // and passthrough
@@ -553,7 +560,6 @@ async fn validate_api_keys(
 const API_KEY_LENGTH: usize = 128;
 
 /// Generates a random 128 character API key
-#[allow(dead_code)]
 pub(crate) fn generate_api_key() -> String {
     assert_impl_any!(ThreadRng: rand::CryptoRng);
     rand::thread_rng()
@@ -563,6 +569,98 @@ pub(crate) fn generate_api_key() -> String {
         .collect()
 }
 
+/// An operation performed by an API request, checked against the
+/// permissions of the caller by [`authorize`].
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Access<'a> {
+    /// Read programs, pipelines, connectors or API keys.
+    Read,
+    /// Create, modify, or delete programs, pipelines, connectors or API keys,
+    /// or change the status of a pipeline.
+    Write,
+    /// Push data to a table of a pipeline.
+    Ingress {
+        pipeline_id: PipelineId,
+        relation: &'a str,
+    },
+    /// Read data from a table or view of a pipeline.
+    Egress {
+        pipeline_id: PipelineId,
+        relation: &'a str,
+    },
+}
+
+impl Display for Access<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Access::Read => f.write_str("read access"),
+            Access::Write => f.write_str("write access"),
+            Access::Ingress {
+                pipeline_id,
+                relation,
+            } => write!(
+                f,
+                "pushing data to '{relation}' in pipeline '{pipeline_id}'"
+            ),
+            Access::Egress {
+                pipeline_id,
+                relation,
+            } => write!(
+                f,
+                "reading data from '{relation}' in pipeline '{pipeline_id}'"
+            ),
+        }
+    }
+}
+
+/// Returns `true` if `permission` allows `access`.
+fn permits(permission: &ApiPermission, access: &Access) -> bool {
+    match (permission, access) {
+        (ApiPermission::Write, _) => true,
+        (ApiPermission::Read, Access::Read | Access::Egress { .. }) => true,
+        (
+            ApiPermission::Ingress {
+                pipeline_id,
+                relation,
+            },
+            Access::Ingress {
+                pipeline_id: accessed_pipeline_id,
+                relation: accessed_relation,
+            },
+        )
+        | (
+            ApiPermission::Egress {
+                pipeline_id,
+                relation,
+            },
+            Access::Egress {
+                pipeline_id: accessed_pipeline_id,
+                relation: accessed_relation,
+            },
+        ) => {
+            pipeline_id == accessed_pipeline_id
+                && relation
+                    .as_ref()
+                    .map_or(true, |relation| relation == accessed_relation)
+        }
+        _ => false,
+    }
+}
+
+/// Check that at least one of `permissions` allows `access`.
+pub(crate) fn authorize(permissions: &[ApiPermission], access: Access) -> Result<(), ManagerError> {
+    if permissions
+        .iter()
+        .any(|permission| permits(permission, &access))
+    {
+        Ok(())
+    } else {
+        Err(ManagerError::PermissionDenied {
+            operation: access.to_string(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::{str::FromStr, sync::Arc};
@@ -580,12 +678,16 @@ mod test {
     use chrono::Utc;
     use jsonwebtoken::{encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
     use tokio::sync::Mutex;
+    use uuid::Uuid;
 
     use crate::{
-        api::ServerState,
-        auth::{self, fetch_jwk_aws_cognito_keys, AuthConfiguration, AwsCognitoClaim, Provider},
+        api::{ManagerError, ServerState},
+        auth::{
+            self, authorize, fetch_jwk_aws_cognito_keys, Access, AuthConfiguration,
+            AwsCognitoClaim, Provider,
+        },
         config::ApiServerConfig,
-        db::{storage::Storage, ApiPermission},
+        db::{storage::Storage, ApiPermission, PipelineId},
     };
 
     use super::AuthError;
@@ -669,6 +771,8 @@ mod test {
                 .unwrap();
             conn.store_api_key_hash(
                 tenant_id,
+                Uuid::now_v7(),
+                "some-key",
                 api_key.unwrap(),
                 vec![ApiPermission::Read, ApiPermission::Write],
             )
@@ -855,4 +959,52 @@ mod test {
         let res = run_test(req, None, Some(api_key), validation).await;
         assert_eq!(200, res.status());
     }
+
+    #[test]
+    fn scoped_permissions() {
+        let pipeline_id = PipelineId(Uuid::now_v7());
+        let other_pipeline_id = PipelineId(Uuid::now_v7());
+        let ingress = |pipeline_id, relation| Access::Ingress {
+            pipeline_id,
+            relation,
+        };
+        let egress = |pipeline_id, relation| Access::Egress {
+            pipeline_id,
+            relation,
+        };
+
+        let write = [ApiPermission::Write];
+        assert!(authorize(&write, Access::Write).is_ok());
+        assert!(authorize(&write, ingress(pipeline_id, "t1")).is_ok());
+
+        let read = [ApiPermission::Read];
+        assert!(authorize(&read, Access::Read).is_ok());
+        assert!(authorize(&read, egress(pipeline_id, "v1")).is_ok());
+        assert!(authorize(&read, Access::Write).is_err());
+        assert!(authorize(&read, ingress(pipeline_id, "t1")).is_err());
+
+        // Egress from any relation of one pipeline.
+        let egress_only = [ApiPermission::Egress {
+            pipeline_id,
+            relation: None,
+        }];
+        assert!(authorize(&egress_only, egress(pipeline_id, "v1")).is_ok());
+        assert!(authorize(&egress_only, egress(pipeline_id, "v2")).is_ok());
+        assert!(authorize(&egress_only, egress(other_pipeline_id, "v1")).is_err());
+        assert!(authorize(&egress_only, ingress(pipeline_id, "v1")).is_err());
+        assert!(authorize(&egress_only, Access::Read).is_err());
+
+        // Ingress into one table.
+        let ingress_only = [ApiPermission::Ingress {
+            pipeline_id,
+            relation: Some("t1".to_string()),
+        }];
+        assert!(authorize(&ingress_only, ingress(pipeline_id, "t1")).is_ok());
+        assert!(matches!(
+            authorize(&ingress_only, ingress(pipeline_id, "t2")),
+            Err(ManagerError::PermissionDenied { .. })
+        ));
+        assert!(authorize(&ingress_only, egress(pipeline_id, "t1")).is_err());
+        assert!(authorize(&ingress_only, Access::Write).is_err());
+    }
 }
@@ -36,7 +36,9 @@ async fn main() {
     .unwrap();
     let db = Arc::new(Mutex::new(db));
     let _local_runner = spawn(async move {
-        local_runner::run(db, &local_runner_config.clone()).await;
+        local_runner::run(db, &local_runner_config.clone())
+            .await
+            .unwrap();
     });
     tokio::signal::ctrl_c()
         .await
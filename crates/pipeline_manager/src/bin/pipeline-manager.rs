@@ -79,7 +79,9 @@ async fn main() -> anyhow::Result<()> {
     });
     let db_clone = db.clone();
     let _local_runner = tokio::spawn(async move {
-        local_runner::run(db_clone, &local_runner_config.clone()).await;
+        local_runner::run(db_clone, &local_runner_config.clone())
+            .await
+            .unwrap();
     });
     // The api-server blocks forever
     pipeline_manager::api::run(db, api_config).await.unwrap();
@@ -5,14 +5,19 @@ use crate::db::{DBError, ProgramId, ProjectDB, Version};
 use crate::error::ManagerError;
 use actix_files::NamedFile;
 use actix_web::{get, web, HttpRequest, HttpServer, Responder};
-use futures_util::join;
+use filetime::FileTime;
+use futures_util::{future::select_all, join};
 use log::warn;
 use log::{debug, error, info, trace};
+use openssl::sha::Sha256;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
     process::{ExitStatus, Stdio},
     sync::Arc,
+    time::SystemTime,
 };
 use tokio::fs::DirEntry;
 use tokio::io::AsyncWriteExt;
@@ -68,6 +73,63 @@ pub(crate) struct SqlCompilerMessage {
     message: String,
 }
 
+/// A range of lines and columns in a source file.
+///
+/// Line and column numbers start at 1.
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, ToSchema, Clone)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SourceSpan {
+    start_line_number: usize,
+    start_column: usize,
+    end_line_number: usize,
+    end_column: usize,
+}
+
+/// A Rust compiler error.
+///
+/// Built from the JSON diagnostics that `cargo build --message-format=json`
+/// prints.  Errors in the Rust code generated for the program are mapped back
+/// to the `CREATE VIEW` statement that the failing code was generated from
+/// where possible.
+#[derive(Debug, Deserialize, Serialize, Eq, PartialEq, ToSchema, Clone)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RustCompilerMessage {
+    /// Severity of the diagnostic, e.g., `error`.
+    level: String,
+    /// Error code, e.g., `E0308`.
+    code: Option<String>,
+    message: String,
+    /// The diagnostic as printed by the compiler, including source snippets.
+    rendered: Option<String>,
+    /// File that contains `rust_span`, relative to the cargo workspace.
+    file_name: Option<String>,
+    /// Location of the error in `file_name`.
+    rust_span: Option<SourceSpan>,
+    /// SQL view whose generated code contains the error.
+    sql_view: Option<String>,
+    /// Location of the `CREATE VIEW` statement of `sql_view` in the program.
+    sql_span: Option<SourceSpan>,
+}
+
+impl RustCompilerMessage {
+    /// An error without structured information, e.g., when the compiler
+    /// failed without reporting diagnostics.
+    pub(crate) fn unstructured(message: String) -> Self {
+        Self {
+            level: "error".to_string(),
+            code: None,
+            message,
+            rendered: None,
+            file_name: None,
+            rust_span: None,
+            sql_view: None,
+            sql_span: None,
+        }
+    }
+}
+
 /// Program compilation status.
 #[derive(Debug, Deserialize, Serialize, Eq, PartialEq, ToSchema, Clone)]
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
@@ -88,7 +150,7 @@ pub(crate) enum ProgramStatus {
     /// SQL compiler returned an error.
     SqlError(Vec<SqlCompilerMessage>),
     /// Rust compiler returned an error.
-    RustError(String),
+    RustError(Vec<RustCompilerMessage>),
     /// System/OS returned an error when trying to invoke commands.
     SystemError(String),
 }
@@ -210,9 +272,6 @@ impl Compiler {
 
         Self::create_working_directory(config).await?;
 
-        // Create workspace-level Cargo.toml
-        Compiler::write_workspace_toml(config, program_id).await?;
-
         // Create dummy package.
         let rust_file_path = config.rust_program_path(program_id);
         let rust_src_dir = rust_file_path.parent().unwrap();
@@ -226,16 +285,28 @@ impl Compiler {
             )
         })?;
 
-        Compiler::write_project_toml(config, program_id).await?;
-
         fs::write(&rust_file_path, "fn main() {}")
             .await
             .map_err(|e| {
                 ManagerError::io_error(format!("writing '{}'", rust_file_path.display()), e)
             })?;
 
-        // `cargo build`.
-        let mut cargo_process = Compiler::run_cargo_build(config, program_id).await?;
+        // `cargo build` in the workspace of each compilation worker.
+        for worker in 0..config.max_parallel_compilations.max(1) {
+            Compiler::write_workspace_toml(config, program_id, worker).await?;
+            Compiler::write_project_toml(config, program_id, worker).await?;
+            Self::precompile_worker(config, program_id, worker).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn precompile_worker(
+        config: &CompilerConfig,
+        program_id: ProgramId,
+        worker: usize,
+    ) -> Result<(), ManagerError> {
+        let mut cargo_process = Compiler::run_cargo_build(config, program_id, worker).await?;
         let exit_status = cargo_process
             .wait()
             .await
@@ -264,9 +335,16 @@ impl Compiler {
                         e,
                     )
                 })?;
+            // The generated program is empty, so errors are in dependencies.
+            let errors: Vec<String> =
+                parse_rust_errors(&stdout, &CompilerConfig::crate_name(program_id), "", "")
+                    .into_iter()
+                    .map(|error| error.rendered.unwrap_or(error.message))
+                    .collect();
             return Err(ManagerError::RustCompilerError {
                 error: format!(
-                    "Failed to precompile Rust dependencies\nstdout:\n{stdout}\nstderr:\n{stderr}"
+                    "Failed to precompile Rust dependencies\nerrors:\n{}\nstderr:\n{stderr}",
+                    errors.join("\n")
                 ),
             });
         }
@@ -274,9 +352,12 @@ impl Compiler {
         Ok(())
     }
 
+    /// Build the crate of `program_id` in the workspace of compilation worker
+    /// `worker`.
     async fn run_cargo_build(
         config: &CompilerConfig,
         program_id: ProgramId,
+        worker: usize,
     ) -> Result<Child, ManagerError> {
         let err_file = File::create(&config.compiler_stderr_path(program_id))
             .await
@@ -304,9 +385,13 @@ impl Compiler {
         let mut command = Command::new("cargo");
 
         command
-            .current_dir(&config.workspace_dir())
+            .current_dir(&config.worker_workspace_dir(worker))
             .arg("build")
-            .arg("--workspace")
+            .arg("--package")
+            .arg(CompilerConfig::crate_name(program_id))
+            .arg("--target-dir")
+            .arg(config.target_dir(worker))
+            .arg("--message-format=json")
             .stdin(Stdio::null())
             .stderr(Stdio::from(err_file.into_std().await))
             .stdout(Stdio::from(out_file.into_std().await));
@@ -323,18 +408,18 @@ impl Compiler {
     async fn version_binary(
         config: &CompilerConfig,
         db: &ProjectDB,
+        source: PathBuf,
         program_id: ProgramId,
         version: Version,
     ) -> Result<(), ManagerError> {
         info!(
             "Preserve binary {:?} as {:?}",
-            config.target_executable(program_id),
+            source,
             config.versioned_executable(program_id, version)
         );
 
         // Save the file locally and record a path to it as a "file://" scheme URL in the DB.
         // This requires any entity accessing it to have access to the same filesystem
-        let source = config.target_executable(program_id);
         let destination = config.versioned_executable(program_id, version);
         fs::copy(&source, &destination).await.map_err(|e| {
             ManagerError::io_error(
@@ -359,16 +444,314 @@ impl Compiler {
         Ok(())
     }
 
-    /// Generate workspace-level `Cargo.toml`.
+    /// Complete a compilation job without compiling if an earlier version of
+    /// the program with the same code (e.g., the target of a rollback) still
+    /// has its binary around.
+    ///
+    /// Returns `true` if the program was marked as successfully compiled.
+    async fn reuse_compiled_binary(
+        config: &CompilerConfig,
+        db: &ProjectDB,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<bool, ManagerError> {
+        let (compiled_version, schema) = match db
+            .find_compiled_program_version(program_id, version)
+            .await?
+        {
+            Some(found) => found,
+            None => return Ok(false),
+        };
+        let source = config.versioned_executable(program_id, compiled_version);
+        // The GC task may have removed the binary in the meantime.
+        if fs::metadata(&source).await.is_err() {
+            return Ok(false);
+        }
+
+        info!("Reusing binary of version {compiled_version} for program {program_id} version {version} (tenant {tenant_id}).");
+        db.set_program_schema(tenant_id, program_id, schema).await?;
+        Self::version_binary(config, db, source, program_id, version).await?;
+        db.set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::Success)
+            .await?;
+        Ok(true)
+    }
+
+    /// Complete the generated Rust crate of a program and compute the key
+    /// of its binary in the binary cache.
+    ///
+    /// The key is a hash of everything that determines the binary: the
+    /// generated code, the crate manifest, the build profile, the versions
+    /// of the Rust compiler and of the manager, the workspace `Cargo.lock`,
+    /// the sources of the SQL libraries, and, with `dbsp_override_path`, the
+    /// state of the DBSP checkout.  It does not depend on the program id or
+    /// on the compilation worker, so that different programs with the same
+    /// code share a binary.
+    async fn prepare_rust_crate(
+        config: &CompilerConfig,
+        program_id: ProgramId,
+        worker: usize,
+    ) -> Result<String, ManagerError> {
+        let rust_path = config.rust_program_path(program_id);
+        let mut main_rs = OpenOptions::new()
+            .append(true)
+            .open(&rust_path)
+            .await
+            .map_err(|e| ManagerError::io_error(format!("opening '{}'", rust_path.display()), e))?;
+
+        main_rs
+            .write_all(MAIN_FUNCTION.as_bytes())
+            .await
+            .map_err(|e| ManagerError::io_error(format!("writing '{}'", rust_path.display()), e))?;
+        drop(main_rs);
+
+        // Write `project/Cargo.toml`.
+        Compiler::write_project_toml(config, program_id, worker).await?;
+
+        // Write the `Cargo.toml` of the worker's workspace, which contains the
+        // generated project crate.
+        Compiler::write_workspace_toml(config, program_id, worker).await?;
+
+        let rustc_version = Command::new("rustc")
+            .current_dir(&config.worker_workspace_dir(worker))
+            .arg("--version")
+            .output()
+            .await
+            .map_err(|e| ManagerError::io_error("running 'rustc --version'".to_string(), e))?
+            .stdout;
+
+        let mut hasher = Sha256::new();
+        for path in [rust_path, config.project_toml_path(program_id)] {
+            let contents = fs::read_to_string(&path)
+                .await
+                .map_err(|e| ManagerError::io_error(format!("reading '{}'", path.display()), e))?;
+            // The manifest names the crate after the program and points to the
+            // workspace of the worker, neither of which affects the binary.
+            let contents = contents
+                .replace(&Self::package_workspace(config, worker), "")
+                .replace(&CompilerConfig::crate_name(program_id), "project");
+            hasher.update(contents.as_bytes());
+        }
+        hasher.update(&rustc_version);
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(if config.debug { "debug" } else { "release" }.as_bytes());
+
+        // Dependency versions resolved by earlier builds.
+        let lock_path = config.worker_workspace_dir(worker).join("Cargo.lock");
+        match fs::read_to_string(&lock_path).await {
+            Ok(lock) => hasher.update(lock_dependencies(&lock).as_bytes()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(ManagerError::io_error(
+                    format!("reading '{}'", lock_path.display()),
+                    e,
+                ))
+            }
+        }
+        Self::hash_dir(&mut hasher, &config.sql_lib_path()).await?;
+        if let Some(path) = &config.dbsp_override_path {
+            match Self::git_revision(Path::new(path)).await {
+                Some(revision) => hasher.update(&revision),
+                // Not a git checkout: fall back to hashing the sources.
+                None => Self::hash_dir(&mut hasher, &Path::new(path).join("crates")).await?,
+            }
+        }
+
+        Ok(hasher
+            .finish()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect())
+    }
+
+    /// Feed the paths and contents of all files under `root` into `hasher`
+    /// in a deterministic order, skipping cargo `target` directories.
+    async fn hash_dir(hasher: &mut Sha256, root: &Path) -> Result<(), ManagerError> {
+        let mut dirs = vec![root.to_path_buf()];
+        let mut files = Vec::new();
+        while let Some(dir) = dirs.pop() {
+            let mut entries = fs::read_dir(&dir)
+                .await
+                .map_err(|e| ManagerError::io_error(format!("reading '{}'", dir.display()), e))?;
+            while let Some(entry) = entries
+                .next_entry()
+                .await
+                .map_err(|e| ManagerError::io_error(format!("reading '{}'", dir.display()), e))?
+            {
+                let file_type = entry.file_type().await.map_err(|e| {
+                    ManagerError::io_error(format!("reading '{}'", entry.path().display()), e)
+                })?;
+                if file_type.is_dir() {
+                    if entry.file_name() != "target" {
+                        dirs.push(entry.path());
+                    }
+                } else if file_type.is_file() {
+                    files.push(entry.path());
+                }
+            }
+        }
+
+        files.sort();
+        for path in files {
+            let contents = fs::read(&path)
+                .await
+                .map_err(|e| ManagerError::io_error(format!("reading '{}'", path.display()), e))?;
+            hasher.update(
+                path.strip_prefix(root)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .as_bytes(),
+            );
+            hasher.update(&contents);
+        }
+        Ok(())
+    }
+
+    /// Identify the state of the git checkout at `path`: the revision of
+    /// `HEAD` plus any uncommitted changes.
+    ///
+    /// Returns `None` if `path` is not a git checkout.
+    async fn git_revision(path: &Path) -> Option<Vec<u8>> {
+        let mut revision = Vec::new();
+        for args in [
+            &["rev-parse", "HEAD"][..],
+            &["status", "--porcelain"][..],
+            &["diff", "HEAD"][..],
+        ] {
+            let output = Command::new("git")
+                .current_dir(path)
+                .args(args)
+                .output()
+                .await
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            revision.extend(output.stdout);
+        }
+        Some(revision)
+    }
+
+    /// Complete a compilation job without running `cargo` if the binary
+    /// cache contains a binary built from the same code.
+    ///
+    /// Returns `true` if the program was marked as successfully compiled.
+    async fn reuse_cached_binary(
+        config: &CompilerConfig,
+        db: &ProjectDB,
+        cache_key: &str,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<bool, ManagerError> {
+        let cached_binary = config.cached_binary_path(cache_key);
+        // Mark the binary as recently used, so that the GC task does not
+        // evict it.
+        if !cached_binary.exists() {
+            return Ok(false);
+        }
+        if let Err(e) = filetime::set_file_mtime(&cached_binary, FileTime::now()) {
+            warn!(
+                "Failed to update the modification time of '{}': {e}",
+                cached_binary.display()
+            );
+        }
+
+        info!("Reusing cached binary '{cache_key}' for program {program_id} version {version} (tenant {tenant_id}).");
+        Self::version_binary(config, db, cached_binary, program_id, version).await?;
+        db.set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::Success)
+            .await?;
+        Ok(true)
+    }
+
+    /// Add a binary to the binary cache.
+    ///
+    /// Failing to cache a binary does not fail the compilation, so errors
+    /// are only logged.
+    async fn cache_binary(config: &CompilerConfig, source: &Path, cache_key: &str) {
+        let cached_binary = config.cached_binary_path(cache_key);
+        // Copy to a temporary file first, so that a concurrent lookup never
+        // sees a partially written binary.
+        let temp_path = cached_binary.with_extension("tmp");
+        let result = async {
+            fs::create_dir_all(config.binary_cache_dir()).await?;
+            fs::copy(source, &temp_path).await?;
+            fs::rename(&temp_path, &cached_binary).await
+        }
+        .await;
+        if let Err(e) = result {
+            warn!(
+                "Failed to add '{}' to the binary cache: {e}",
+                source.display()
+            );
+            let _ = fs::remove_file(&temp_path).await;
+        }
+    }
+
+    /// Evict the least recently used binaries from the binary cache until
+    /// its size is within `binary_cache_max_size`.
+    ///
+    /// Temporary files written by [`Self::cache_binary`] are neither counted
+    /// nor evicted, since they may still be in the process of being written.
+    async fn evict_cached_binaries(config: &CompilerConfig) {
+        let mut binaries = Vec::new();
+        let mut total_size = 0;
+        let mut paths = match fs::read_dir(config.binary_cache_dir()).await {
+            Ok(paths) => paths,
+            // The cache is created with the first binary.
+            Err(_) => return,
+        };
+        loop {
+            match paths.next_entry().await {
+                Ok(Some(entry)) if entry.path().extension() == Some(OsStr::new("tmp")) => {}
+                Ok(Some(entry)) => match entry.metadata().await {
+                    Ok(metadata) => {
+                        total_size += metadata.len();
+                        binaries.push((
+                            metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                            metadata.len(),
+                            entry.path(),
+                        ));
+                    }
+                    Err(e) => warn!("GC task unable to read metadata of {:?}: {e}", entry.path()),
+                },
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("GC task unable to read an entry: {}", e);
+                    break;
+                }
+            }
+        }
+
+        binaries.sort();
+        for (_, size, path) in binaries {
+            if total_size <= config.binary_cache_max_size {
+                break;
+            }
+            info!("Evicting cached binary {:?}", path.file_name());
+            match fs::remove_file(&path).await {
+                Ok(()) => total_size -= size,
+                Err(e) => error!("GC task failed to remove file {:?}: {e}", path.file_name()),
+            }
+        }
+    }
+
+    /// Generate the workspace-level `Cargo.toml` of compilation worker
+    /// `worker`, with the crate of `program_id` as its only member.
     async fn write_workspace_toml(
         config: &CompilerConfig,
         program_id: ProgramId,
+        worker: usize,
     ) -> Result<(), ManagerError> {
+        let workspace_dir = config.worker_workspace_dir(worker);
+        fs::create_dir_all(&workspace_dir).await.map_err(|e| {
+            ManagerError::io_error(format!("creating '{}'", workspace_dir.display()), e)
+        })?;
         let workspace_toml_code = format!(
             "[workspace]\nmembers = [ \"{}\" ]\n",
-            CompilerConfig::crate_name(program_id),
+            config.project_dir(program_id).display()
         );
-        let toml_path = config.workspace_toml_path();
+        let toml_path = config.workspace_toml_path(worker);
         fs::write(&toml_path, workspace_toml_code)
             .await
             .map_err(|e| ManagerError::io_error(format!("writing '{}'", toml_path.display()), e))?;
@@ -376,10 +759,21 @@ impl Compiler {
         Ok(())
     }
 
-    /// Generate project-level `Cargo.toml`.
+    /// The `workspace` key that makes a generated crate a member of the
+    /// workspace of compilation worker `worker`.
+    fn package_workspace(config: &CompilerConfig, worker: usize) -> String {
+        format!(
+            "workspace = \"{}\"",
+            config.worker_workspace_dir(worker).display()
+        )
+    }
+
+    /// Generate project-level `Cargo.toml` for a build by compilation worker
+    /// `worker`.
     async fn write_project_toml(
         config: &CompilerConfig,
         program_id: ProgramId,
+        worker: usize,
     ) -> Result<(), ManagerError> {
         let template_path = config.project_toml_template_path();
         let template_toml = fs::read_to_string(&template_path).await.map_err(|e| {
@@ -387,7 +781,13 @@ impl Compiler {
         })?;
         let program_name = format!("name = \"{}\"", CompilerConfig::crate_name(program_id));
         let mut project_toml_code = template_toml
-            .replace("name = \"temp\"", &program_name)
+            .replace(
+                "name = \"temp\"",
+                &format!(
+                    "{program_name}\n{}",
+                    Self::package_workspace(config, worker)
+                ),
+            )
             .replace(", default-features = false", "")
             .replace(
                 "[lib]\npath = \"src/lib.rs\"",
@@ -444,7 +844,8 @@ impl Compiler {
     /// A task that wakes up periodically and removes stale binaries.
     ///
     /// Helps to keep the binaries directory clean and not run out of space if
-    /// this runs for a very long time.
+    /// this runs for a very long time.  Also evicts the least recently used
+    /// binaries from the binary cache.
     ///
     /// Note that this task handles all errors internally and does not propagate
     /// them up so it can run forever and never aborts.
@@ -454,6 +855,7 @@ impl Compiler {
     ) -> Result<(), ManagerError> {
         loop {
             sleep(GC_POLL_INTERVAL).await;
+            Self::evict_cached_binaries(&config).await;
             let read_dir = fs::read_dir(config.binaries_dir()).await;
             match read_dir {
                 Ok(mut paths) => loop {
@@ -633,149 +1035,219 @@ impl Compiler {
         config: CompilerConfig,
         db: Arc<Mutex<ProjectDB>>,
     ) -> Result<(), ManagerError> {
-        let mut job: Option<CompilationJob> = None;
+        // Running compilation jobs, indexed by worker.
+        let mut jobs: Vec<Option<CompilationJob>> = (0..config.max_parallel_compilations.max(1))
+            .map(|_| None)
+            .collect();
         Self::reconcile_local_state(&config, &db).await?;
         loop {
             select! {
                 // Wake up every `COMPILER_POLL_INTERVAL` to check
                 // if we need to abort ongoing compilation.
                 _ = sleep(COMPILER_POLL_INTERVAL) => {
-                    let mut cancel = false;
-                    if let Some(job) = &job {
-                        // Program was deleted, updated or the user changed its status
-                        // to cancelled -- abort compilation.
-                        let descr = db.lock().await.get_program_if_exists(job.tenant_id, job.program_id, false).await?;
-                        if let Some(descr) = descr {
-                            if descr.version != job.version || !descr.status.is_compiling() {
+                    for slot in jobs.iter_mut() {
+                        let mut cancel = false;
+                        if let Some(job) = slot {
+                            // Program was deleted, updated or the user changed its status
+                            // to cancelled -- abort compilation.
+                            let descr = db.lock().await.get_program_if_exists(job.tenant_id, job.program_id, false).await?;
+                            if let Some(descr) = descr {
+                                if descr.version != job.version || !descr.status.is_compiling() {
+                                    cancel = true;
+                                }
+                            } else {
                                 cancel = true;
                             }
-                        } else {
-                            cancel = true;
                         }
-                    }
-                    if cancel {
-                        job.unwrap().cancel().await;
-                        job = None;
+                        if cancel {
+                            slot.take().unwrap().cancel().await;
+                        }
                     }
                 }
                 // Compilation job finished - start the next stage of the compilation
                 // (i.e. run the Rust compiler after SQL) or update program status in the
                 // database.
-                Some(exit_status) = async {
-                    if let Some(job) = &mut job {
-                        Some(job.wait().await)
-                    } else {
-                        None
-                    }
-                }, if job.is_some() => {
-                    let tenant_id = job.as_ref().unwrap().tenant_id;
-                    let program_id = job.as_ref().unwrap().program_id;
-                    let version = job.as_ref().unwrap().version;
+                (worker, exit_status) = async {
+                    let running = jobs.iter_mut().enumerate().filter_map(|(worker, job)| {
+                        job.as_mut().map(|job| Box::pin(async move { (worker, job.wait().await) }))
+                    });
+                    select_all(running).await.0
+                }, if jobs.iter().any(Option::is_some) => {
+                    let job = jobs[worker].take().unwrap();
+                    jobs[worker] = Self::finish_job(&config, &db, job, worker, exit_status).await?;
+                }
+            }
+            // Hand programs from the queue to idle workers.
+            while let Some(worker) = jobs.iter().position(Option::is_none) {
+                let program = {
                     let db = db.lock().await;
-
-                    match exit_status {
-                        Ok(status) if status.success() && job.as_ref().unwrap().is_sql() => {
-                            // SQL compiler succeeded -- start the Rust job.
-                            db.set_program_status_guarded(
+                    match db.next_job().await? {
+                        Some((tenant_id, program_id, version)) => {
+                            trace!(
+                                "Next program in the queue: '{program_id}', version '{version}'"
+                            );
+                            // The program may have been cancelled or updated
+                            // since `next_job` returned it.
+                            if !db
+                                .claim_program_compilation(tenant_id, program_id, version)
+                                .await?
+                            {
+                                continue;
+                            }
+                            if Self::reuse_compiled_binary(
+                                &config, &db, tenant_id, program_id, version,
+                            )
+                            .await?
+                            {
+                                continue;
+                            }
+                            let program = db
+                                .get_program_if_exists(tenant_id, program_id, true)
+                                .await?;
+                            (
                                 tenant_id,
                                 program_id,
                                 version,
-                                ProgramStatus::CompilingRust,
-                            ).await?;
-
-                            // Read the schema so we can store it in the DB.
-                            //
-                            // - We trust the compiler that it put the file
-                            // there if it succeeded.
-                            // - We hold the db lock so we are executing this
-                            // update in the same transaction as the program
-                            // status above.
-
-                            let schema_path = config.schema_path(program_id);
-                            let schema_json = fs::read_to_string(&schema_path).await
-                                .map_err(|e| {
-                                    ManagerError::io_error(format!("reading '{}'", schema_path.display()), e)
-                                })?;
-
-                            let schema = serde_json::from_str(&schema_json)
-                                .map_err(|e| { ManagerError::invalid_program_schema(e.to_string()) })?;
-                            db.set_program_schema(tenant_id, program_id, schema).await?;
-                            info!("Invoking rust compiler for program {program_id} version {version} (tenant {tenant_id}). This will take a while.");
-                            debug!("Set ProgramStatus::CompilingRust '{program_id}', version '{version}'");
-                            job = Some(CompilationJob::rust(tenant_id, &config, program_id, version).await?);
-                        }
-                        Ok(status) if status.success() && job.as_ref().unwrap().is_rust() => {
-                            Self::version_binary(&config, &db, program_id, version).await?;
-                            // Rust compiler succeeded -- declare victory.
-                            db.set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::Success).await?;
-                            info!("Successfully invoked rust compiler for program {program_id} version {version} (tenant {tenant_id}).");
-                            debug!("Set ProgramStatus::Success '{program_id}', version '{version}'");
-                            job = None;
-                        }
-                        Ok(status) => {
-                            // Compilation failed - update program status with the compiler
-                            // error message.
-                            let output = job.as_ref().unwrap().error_output(&config).await?;
-                            let status = if job.as_ref().unwrap().is_rust() {
-                                ProgramStatus::RustError(format!("{output}\nexit code: {status}"))
-                            } else if let Ok(messages) = serde_json::from_str(&output) {
-                                    // If we can parse the SqlCompilerMessages
-                                    // as JSON, we assume the compiler worked:
-                                    ProgramStatus::SqlError(messages)
-                            } else {
-                                    // Otherwise something unexpected happened
-                                    // and we return a system error:
-                                    ProgramStatus::SystemError(format!("{output}\nexit code: {status}"))
-                            };
-                            db.set_program_status_guarded(tenant_id, program_id, version, status).await?;
-                            job = None;
-                        }
-                        Err(e) => {
-                            let status = if job.unwrap().is_rust() {
-                                ProgramStatus::SystemError(format!("I/O error with rustc: {e}"))
-                            } else {
-                                ProgramStatus::SystemError(format!("I/O error with sql-to-dbsp: {e}"))
-                            };
-                            db.set_program_status_guarded(tenant_id, program_id, version, status).await?;
-                            job = None;
+                                program.unwrap().code.unwrap(),
+                            )
                         }
-                    }
-                }
-            }
-            // Pick the next program from the queue.
-            if job.is_none() {
-                let program = {
-                    let db = db.lock().await;
-                    if let Some((tenant_id, program_id, version)) = db.next_job().await? {
-                        trace!("Next program in the queue: '{program_id}', version '{version}'");
-                        let program = db
-                            .get_program_if_exists(tenant_id, program_id, true)
-                            .await?;
-                        Some((
-                            tenant_id,
-                            program_id,
-                            version,
-                            program.unwrap().code.unwrap(),
-                        ))
-                    } else {
-                        None
+                        None => break,
                     }
                 };
 
-                if let Some((tenant_id, program_id, version, code)) = program {
-                    job = Some(
-                        CompilationJob::sql(tenant_id, &config, &code, program_id, version).await?,
-                    );
-                    db.lock()
-                        .await
-                        .set_program_status_guarded(
-                            tenant_id,
-                            program_id,
-                            version,
-                            ProgramStatus::CompilingSql,
+                let (tenant_id, program_id, version, code) = program;
+                jobs[worker] = Some(
+                    CompilationJob::sql(tenant_id, &config, &code, program_id, version).await?,
+                );
+            }
+        }
+    }
+
+    /// Handle the completion of a compilation stage of `job`, which ran on
+    /// `worker`.
+    ///
+    /// Starts the next stage of the compilation on the same worker, if any,
+    /// or records the outcome in the program status.
+    async fn finish_job(
+        config: &CompilerConfig,
+        db: &Arc<Mutex<ProjectDB>>,
+        job: CompilationJob,
+        worker: usize,
+        exit_status: Result<ExitStatus, ManagerError>,
+    ) -> Result<Option<CompilationJob>, ManagerError> {
+        let tenant_id = job.tenant_id;
+        let program_id = job.program_id;
+        let version = job.version;
+
+        // Computing the cache key runs `rustc` and hashes source trees, so do
+        // it before taking the DB lock, which blocks all API requests.
+        let cache_key = match &exit_status {
+            Ok(status) if status.success() && job.is_sql() => {
+                Some(Self::prepare_rust_crate(config, program_id, worker).await?)
+            }
+            _ => None,
+        };
+
+        let db = db.lock().await;
+
+        match exit_status {
+            Ok(status) if status.success() && job.is_sql() => {
+                // SQL compiler succeeded -- start the Rust job.
+                db.set_program_status_guarded(
+                    tenant_id,
+                    program_id,
+                    version,
+                    ProgramStatus::CompilingRust,
+                )
+                .await?;
+
+                // Read the schema so we can store it in the DB.
+                //
+                // - We trust the compiler that it put the file
+                // there if it succeeded.
+                // - We hold the db lock so we are executing this
+                // update in the same transaction as the program
+                // status above.
+
+                let schema_path = config.schema_path(program_id);
+                let schema_json = fs::read_to_string(&schema_path).await.map_err(|e| {
+                    ManagerError::io_error(format!("reading '{}'", schema_path.display()), e)
+                })?;
+
+                let schema = serde_json::from_str(&schema_json)
+                    .map_err(|e| ManagerError::invalid_program_schema(e.to_string()))?;
+                db.set_program_schema(tenant_id, program_id, schema).await?;
+                info!("Invoking rust compiler for program {program_id} version {version} (tenant {tenant_id}). This will take a while.");
+                debug!("Set ProgramStatus::CompilingRust '{program_id}', version '{version}'");
+                let cache_key = cache_key.expect("cache key is computed for successful SQL jobs");
+                if Self::reuse_cached_binary(
+                    config, &db, &cache_key, tenant_id, program_id, version,
+                )
+                .await?
+                {
+                    Ok(None)
+                } else {
+                    Ok(Some(
+                        CompilationJob::rust(
+                            tenant_id, config, program_id, version, cache_key, worker,
                         )
-                        .await?;
+                        .await?,
+                    ))
+                }
+            }
+            Ok(status) if status.success() && job.is_rust() => {
+                let executable = config.target_executable(program_id, worker);
+                Self::version_binary(config, &db, executable.clone(), program_id, version).await?;
+                if let Some(cache_key) = &job.cache_key {
+                    Self::cache_binary(config, &executable, cache_key).await;
                 }
+                // Rust compiler succeeded -- declare victory.
+                db.set_program_status_guarded(
+                    tenant_id,
+                    program_id,
+                    version,
+                    ProgramStatus::Success,
+                )
+                .await?;
+                info!("Successfully invoked rust compiler for program {program_id} version {version} (tenant {tenant_id}).");
+                debug!("Set ProgramStatus::Success '{program_id}', version '{version}'");
+                Ok(None)
+            }
+            Ok(status) => {
+                // Compilation failed - update program status with the compiler
+                // error message.
+                let output = job.error_output(config).await?;
+                let status = if job.is_rust() {
+                    let mut errors = job.rust_errors(config).await?;
+                    if errors.is_empty() {
+                        // E.g., a failure to resolve dependencies.
+                        errors.push(RustCompilerMessage::unstructured(format!(
+                            "{output}\nexit code: {status}"
+                        )));
+                    }
+                    ProgramStatus::RustError(errors)
+                } else if let Ok(messages) = serde_json::from_str(&output) {
+                    // If we can parse the SqlCompilerMessages
+                    // as JSON, we assume the compiler worked:
+                    ProgramStatus::SqlError(messages)
+                } else {
+                    // Otherwise something unexpected happened
+                    // and we return a system error:
+                    ProgramStatus::SystemError(format!("{output}\nexit code: {status}"))
+                };
+                db.set_program_status_guarded(tenant_id, program_id, version, status)
+                    .await?;
+                Ok(None)
+            }
+            Err(e) => {
+                let status = if job.is_rust() {
+                    ProgramStatus::SystemError(format!("I/O error with rustc: {e}"))
+                } else {
+                    ProgramStatus::SystemError(format!("I/O error with sql-to-dbsp: {e}"))
+                };
+                db.set_program_status_guarded(tenant_id, program_id, version, status)
+                    .await?;
+                Ok(None)
             }
         }
     }
@@ -793,6 +1265,8 @@ struct CompilationJob {
     program_id: ProgramId,
     version: Version,
     compiler_process: Child,
+    /// Key under which to cache the binary built by the Rust stage.
+    cache_key: Option<String>,
 }
 
 impl CompilationJob {
@@ -877,40 +1351,24 @@ impl CompilationJob {
             program_id,
             version,
             compiler_process,
+            cache_key: None,
         })
     }
 
-    // Run `cargo` on the generated Rust workspace.
+    // Run `cargo` on the Rust workspace of compilation worker `worker`
+    // prepared by `Compiler::prepare_rust_crate`.
     async fn rust(
         tenant_id: TenantId,
         config: &CompilerConfig,
         program_id: ProgramId,
         version: Version,
+        cache_key: String,
+        worker: usize,
     ) -> Result<Self, ManagerError> {
-        debug!("Running Rust compiler on program '{program_id}', version '{version}'");
-
-        let rust_path = config.rust_program_path(program_id);
-        let mut main_rs = OpenOptions::new()
-            .append(true)
-            .open(&rust_path)
-            .await
-            .map_err(|e| ManagerError::io_error(format!("opening '{}'", rust_path.display()), e))?;
-
-        main_rs
-            .write_all(MAIN_FUNCTION.as_bytes())
-            .await
-            .map_err(|e| ManagerError::io_error(format!("writing '{}'", rust_path.display()), e))?;
-        drop(main_rs);
-
-        // Write `project/Cargo.toml`.
-        Compiler::write_project_toml(config, program_id).await?;
-
-        // Write workspace `Cargo.toml`.  The workspace contains SQL libs and the
-        // generated project crate.
-        Compiler::write_workspace_toml(config, program_id).await?;
+        debug!("Running Rust compiler on program '{program_id}', version '{version}' (worker {worker})");
 
         // Run cargo, direct stdout and stderr to the same file.
-        let compiler_process = Compiler::run_cargo_build(config, program_id).await?;
+        let compiler_process = Compiler::run_cargo_build(config, program_id, worker).await?;
 
         Ok(Self {
             tenant_id,
@@ -918,6 +1376,7 @@ impl CompilationJob {
             program_id,
             version,
             compiler_process,
+            cache_key: Some(cache_key),
         })
     }
 
@@ -955,16 +1414,265 @@ impl CompilationJob {
         Ok(output)
     }
 
+    /// Read the errors reported by the Rust compiler.
+    ///
+    /// Returns an empty vector if the compiler did not report any errors,
+    /// e.g., because `cargo` failed before invoking it.
+    async fn rust_errors(
+        &self,
+        config: &CompilerConfig,
+    ) -> Result<Vec<RustCompilerMessage>, ManagerError> {
+        let mut sources = Vec::new();
+        for path in [
+            config.compiler_stdout_path(self.program_id),
+            config.rust_program_path(self.program_id),
+            config.sql_file_path(self.program_id),
+        ] {
+            sources.push(
+                fs::read_to_string(&path).await.map_err(|e| {
+                    ManagerError::io_error(format!("reading '{}'", path.display()), e)
+                })?,
+            );
+        }
+
+        Ok(parse_rust_errors(
+            &sources[0],
+            &CompilerConfig::crate_name(self.program_id),
+            &sources[1],
+            &sources[2],
+        ))
+    }
+
     /// Kill (Rust or SQL) compiler process.
     async fn cancel(&mut self) {
         let _ = self.compiler_process.kill().await;
     }
 }
 
+/// A line of `cargo build --message-format=json` output.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+/// A diagnostic emitted by `rustc`.
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    spans: Vec<RustcSpan>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+}
+
+/// Remove the entries of the generated project crates from the contents of a
+/// `Cargo.lock` file, leaving the resolved versions of their dependencies.
+fn lock_dependencies(lock: &str) -> String {
+    lock.split("\n[[package]]\n")
+        .filter(|entry| !entry.starts_with("name = \"project"))
+        .collect::<Vec<_>>()
+        .join("\n[[package]]\n")
+}
+
+/// Extract the errors from the JSON output of `cargo build`.
+///
+/// `rust_code` and `sql_code` are the generated `main.rs` of crate
+/// `crate_name` and the SQL program it was generated from.
+fn parse_rust_errors(
+    cargo_output: &str,
+    crate_name: &str,
+    rust_code: &str,
+    sql_code: &str,
+) -> Vec<RustCompilerMessage> {
+    let main_rs = Path::new(crate_name).join("src").join("main.rs");
+
+    cargo_output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-message")
+        .filter_map(|message| message.message)
+        .filter(|diagnostic| {
+            diagnostic.level.starts_with("error")
+                // Summary, e.g., "aborting due to 2 previous errors".
+                && !diagnostic.spans.is_empty()
+        })
+        .map(|diagnostic| {
+            let span = diagnostic
+                .spans
+                .iter()
+                .find(|span| span.is_primary)
+                .unwrap_or(&diagnostic.spans[0]);
+            let sql_view = if Path::new(&span.file_name).ends_with(&main_rs) {
+                generated_view(rust_code, span.line_start)
+            } else {
+                None
+            };
+            let sql_span = sql_view
+                .as_ref()
+                .and_then(|view| create_view_span(sql_code, view));
+
+            RustCompilerMessage {
+                level: diagnostic.level,
+                code: diagnostic.code.map(|code| code.code),
+                message: diagnostic.message,
+                rendered: diagnostic.rendered,
+                file_name: Some(span.file_name.clone()),
+                rust_span: Some(SourceSpan {
+                    start_line_number: span.line_start,
+                    start_column: span.column_start,
+                    end_line_number: span.line_end,
+                    end_column: span.column_end,
+                }),
+                sql_view,
+                sql_span,
+            }
+        })
+        .collect()
+}
+
+/// Find the view that the code at `line` of the generated Rust program
+/// belongs to.
+///
+/// The SQL compiler (in the mode that generates a circuit with input and
+/// output handles) builds the circuit in the closure passed to
+/// `Runtime::init_circuit`.  It emits the operators of the circuit in
+/// dependency order, each preceded by a comment that describes it.  The
+/// output of a view, i.e., the struct of its records and their
+/// deserialization code, is preceded by the `CREATE VIEW` statement of the
+/// view as a comment.
+///
+/// We attribute the output code of a view and its registration in the catalog
+/// to the view, and the code of other operators to the first view whose output
+/// follows them.  Code outside the circuit and the code of inputs (`CREATE
+/// TABLE` statements and `DBSPSourceOperator`s) does not belong to a view.
+fn generated_view(rust_code: &str, line: usize) -> Option<String> {
+    let lines: Vec<&str> = rust_code.lines().collect();
+    let index = line.checked_sub(1).filter(|index| *index < lines.len())?;
+    let circuit_start = lines
+        .iter()
+        .position(|line| line.contains("Runtime::init_circuit("))?;
+    if index <= circuit_start {
+        return None;
+    }
+
+    // The circuit ends with the registration of its inputs and outputs in the
+    // catalog, e.g., `catalog.register_output_zset::<_, V_struct>("V", stream1);`.
+    let registration = |index: usize| lines[index].trim_start().starts_with("catalog.register_");
+    if registration(index) {
+        let (_, rest) = lines[index].split_once("catalog.register_output")?;
+        return rest.split('"').nth(1).map(str::to_string);
+    }
+
+    // Find the comment that precedes the code at `line`.
+    let is_comment = |index: usize| lines[index].trim_start().starts_with("//");
+    let mut start = index;
+    while start > circuit_start + 1 && !is_comment(start - 1) {
+        if registration(start - 1) {
+            return None;
+        }
+        start -= 1;
+    }
+    let end = start;
+    while start > circuit_start + 1 && is_comment(start - 1) {
+        start -= 1;
+    }
+    let comment = &lines[start..end];
+
+    if comment
+        .iter()
+        .any(|line| line.contains("DBSPSourceOperator"))
+    {
+        return None;
+    }
+    if let Some((kind, name)) = comment.first().and_then(|line| created_object(line)) {
+        return (kind == "VIEW").then_some(name);
+    }
+    lines[index..]
+        .iter()
+        .filter_map(|line| created_object(line))
+        .find(|(kind, _)| kind == "VIEW")
+        .map(|(_, name)| name)
+}
+
+/// The kind and name of the SQL object created by a `CREATE` statement in
+/// the comment `line`, e.g., `("VIEW", "V1")`.
+fn created_object(line: &str) -> Option<(String, String)> {
+    let comment = line.trim_start().strip_prefix("//")?;
+    let mut words = comment.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("create") {
+        return None;
+    }
+    let kind = words.next()?.to_ascii_uppercase();
+    let name = words.next().and_then(sql_identifier)?;
+    Some((kind, name))
+}
+
+/// Parse the SQL identifier at the start of `s`, removing quotes.
+fn sql_identifier(s: &str) -> Option<String> {
+    let s = s.trim_start_matches(|c| c == '`' || c == '"');
+    let identifier: String = s
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!identifier.is_empty()).then_some(identifier)
+}
+
+/// Location of the `CREATE VIEW` statement of `view` in `sql_code`,
+/// up to its terminating semicolon.
+fn create_view_span(sql_code: &str, view: &str) -> Option<SourceSpan> {
+    let lowercase = sql_code.to_ascii_lowercase();
+    let start = lowercase.match_indices("create").find_map(|(offset, _)| {
+        let mut words = lowercase[offset..].split_whitespace();
+        words.next();
+        (words.next()? == "view" && sql_identifier(words.next()?)?.eq_ignore_ascii_case(view))
+            .then_some(offset)
+    })?;
+    let end = lowercase[start..]
+        .find(';')
+        .map_or(sql_code.len(), |end| start + end + 1);
+
+    let position = |offset: usize| {
+        let before = &sql_code[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+        (line, column)
+    };
+    let (start_line_number, start_column) = position(start);
+    let (end_line_number, end_column) = position(end - 1);
+    Some(SourceSpan {
+        start_line_number,
+        start_column,
+        end_line_number,
+        end_column,
+    })
+}
+
 #[cfg(test)]
 mod test {
-    use std::{fs::File, sync::Arc};
+    use std::{
+        fs::File,
+        sync::Arc,
+        time::{Duration, SystemTime},
+    };
 
+    use filetime::FileTime;
+    use openssl::sha::Sha256;
     use tempfile::TempDir;
     use tokio::{fs, sync::Mutex};
     use uuid::Uuid;
@@ -1009,6 +1717,8 @@ mod test {
             compiler_working_directory: workdir.to_owned(),
             binary_ref_host: "127.0.0.1".to_string(),
             binary_ref_port: 9090,
+            binary_cache_max_size: 10 * 1024 * 1024 * 1024,
+            max_parallel_compilations: 1,
         };
 
         let (db, _temp) = crate::db::test::setup_pg().await;
@@ -1074,6 +1784,8 @@ mod test {
             compiler_working_directory: workdir.to_owned(),
             binary_ref_host: "127.0.0.1".to_string(),
             binary_ref_port: 9090,
+            binary_cache_max_size: 10 * 1024 * 1024 * 1024,
+            max_parallel_compilations: 1,
         };
 
         let (db, _temp) = crate::db::test::setup_pg().await;
@@ -1120,6 +1832,8 @@ mod test {
             compiler_working_directory: workdir.to_owned(),
             binary_ref_host: "127.0.0.1".to_string(),
             binary_ref_port: 9090,
+            binary_cache_max_size: 10 * 1024 * 1024 * 1024,
+            max_parallel_compilations: 1,
         };
 
         let (db, _temp) = crate::db::test::setup_pg().await;
@@ -1173,4 +1887,344 @@ mod test {
         assert!(!path1.exists());
         assert!(!path2.exists());
     }
+
+    #[tokio::test]
+    async fn test_evict_cached_binaries() {
+        let tmp_dir = TempDir::new().unwrap();
+        let workdir = tmp_dir.path().to_str().unwrap();
+        let conf = CompilerConfig {
+            sql_compiler_home: "".to_owned(),
+            dbsp_override_path: Some("../../".to_owned()),
+            debug: false,
+            precompile: false,
+            compiler_working_directory: workdir.to_owned(),
+            binary_ref_host: "127.0.0.1".to_string(),
+            binary_ref_port: 9090,
+            binary_cache_max_size: 20,
+            max_parallel_compilations: 1,
+        };
+
+        // Eviction is a no-op before the cache is created.
+        super::Compiler::evict_cached_binaries(&conf).await;
+
+        fs::create_dir(conf.binary_cache_dir()).await.unwrap();
+        let now = SystemTime::now();
+        for (key, age) in [("a", 30), ("b", 10), ("c", 20), ("d.tmp", 40)] {
+            let path = conf.cached_binary_path(key);
+            File::create(&path).unwrap().set_len(10).unwrap();
+            filetime::set_file_mtime(
+                &path,
+                FileTime::from_system_time(now - Duration::from_secs(age)),
+            )
+            .unwrap();
+        }
+
+        // The least recently used binaries are evicted first.
+        super::Compiler::evict_cached_binaries(&conf).await;
+        assert!(!conf.cached_binary_path("a").exists());
+        assert!(conf.cached_binary_path("b").exists());
+        assert!(conf.cached_binary_path("c").exists());
+        // Binaries that are still being copied into the cache are ignored.
+        assert!(conf.cached_binary_path("d.tmp").exists());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_rust_crate_per_worker() {
+        let tmp_dir = TempDir::new().unwrap();
+        let sql_compiler_home = tmp_dir.path().join("sql-to-dbsp-compiler");
+        fs::create_dir_all(sql_compiler_home.join("temp"))
+            .await
+            .unwrap();
+        fs::write(
+            sql_compiler_home.join("temp").join("Cargo.toml"),
+            "[package]\nname = \"temp\"\nversion = \"0.1.0\"\n\n[lib]\npath = \"src/lib.rs\"\n",
+        )
+        .await
+        .unwrap();
+        fs::create_dir_all(sql_compiler_home.join("lib").join("sqllib"))
+            .await
+            .unwrap();
+        fs::write(
+            sql_compiler_home.join("lib").join("sqllib").join("lib.rs"),
+            "",
+        )
+        .await
+        .unwrap();
+        let conf = CompilerConfig {
+            sql_compiler_home: sql_compiler_home.to_str().unwrap().to_owned(),
+            dbsp_override_path: None,
+            debug: false,
+            precompile: false,
+            compiler_working_directory: tmp_dir.path().join("work").to_str().unwrap().to_owned(),
+            binary_ref_host: "127.0.0.1".to_string(),
+            binary_ref_port: 9090,
+            binary_cache_max_size: 10 * 1024 * 1024 * 1024,
+            max_parallel_compilations: 2,
+        };
+
+        // Two programs with the same code, prepared by different workers.
+        let programs = [ProgramId(Uuid::now_v7()), ProgramId(Uuid::now_v7())];
+        let prepare = |lock: bool| {
+            let conf = conf.clone();
+            async move {
+                let mut keys = Vec::new();
+                for (worker, program_id) in programs.into_iter().enumerate() {
+                    let rust_path = conf.rust_program_path(program_id);
+                    fs::create_dir_all(rust_path.parent().unwrap())
+                        .await
+                        .unwrap();
+                    fs::write(&rust_path, "fn circuit() {}\n").await.unwrap();
+                    if lock {
+                        // Lock files list the crate built last by each worker.
+                        fs::write(
+                            conf.worker_workspace_dir(worker).join("Cargo.lock"),
+                            format!(
+                                "version = 3\n\n[[package]]\nname = \"paste\"\nversion = \"1.0.12\"\n\n[[package]]\nname = \"{}\"\nversion = \"0.1.0\"\n",
+                                CompilerConfig::crate_name(program_id)
+                            ),
+                        )
+                        .await
+                        .unwrap();
+                    }
+                    keys.push(
+                        super::Compiler::prepare_rust_crate(&conf, program_id, worker)
+                            .await
+                            .unwrap(),
+                    );
+                }
+                keys
+            }
+        };
+
+        // The cache key does not depend on the program or the worker.
+        let keys = prepare(false).await;
+        assert_eq!(keys[0], keys[1]);
+        let keys_with_lock = prepare(true).await;
+        assert_eq!(keys_with_lock[0], keys_with_lock[1]);
+
+        // Each worker has its own workspace, whose only member is the crate it
+        // builds.
+        assert_ne!(conf.workspace_toml_path(0), conf.workspace_toml_path(1));
+        for (worker, program_id) in programs.into_iter().enumerate() {
+            let workspace_toml = fs::read_to_string(conf.workspace_toml_path(worker))
+                .await
+                .unwrap();
+            assert_eq!(
+                workspace_toml,
+                format!(
+                    "[workspace]\nmembers = [ \"{}\" ]\n",
+                    conf.project_dir(program_id).display()
+                )
+            );
+            let project_toml = fs::read_to_string(conf.project_toml_path(program_id))
+                .await
+                .unwrap();
+            assert!(project_toml.contains(&format!(
+                "workspace = \"{}\"",
+                conf.worker_workspace_dir(worker).display()
+            )));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hash_dir() {
+        async fn hash(root: &std::path::Path) -> Vec<u8> {
+            let mut hasher = Sha256::new();
+            super::Compiler::hash_dir(&mut hasher, root).await.unwrap();
+            hasher.finish().to_vec()
+        }
+
+        let tmp_dir = TempDir::new().unwrap();
+        let root = tmp_dir.path();
+        fs::create_dir_all(root.join("src")).await.unwrap();
+        fs::write(root.join("src/lib.rs"), "fn f() {}")
+            .await
+            .unwrap();
+        let initial = hash(root).await;
+
+        // Build artifacts do not affect the hash.
+        fs::create_dir_all(root.join("target")).await.unwrap();
+        fs::write(root.join("target/lib.o"), "").await.unwrap();
+        assert_eq!(hash(root).await, initial);
+
+        // Changing or adding sources does.
+        fs::write(root.join("src/lib.rs"), "fn g() {}")
+            .await
+            .unwrap();
+        let changed = hash(root).await;
+        assert_ne!(changed, initial);
+        fs::write(root.join("src/util.rs"), "").await.unwrap();
+        assert_ne!(hash(root).await, changed);
+    }
+
+    #[test]
+    /// The SQL program of [`GENERATED_RUST`].
+    const GENERATED_SQL: &str = "CREATE TABLE t (x INT);
+
+create view v1 as
+  select x from t;
+
+CREATE VIEW v2 AS SELECT x + 1 AS y FROM v1;
+";
+
+    /// Rust code generated for [`GENERATED_SQL`] by the SQL compiler, with
+    /// type errors in the operators of both views.  Follows the layout of
+    /// the code that `ToRustHandleVisitor` produces; the preamble and the
+    /// trait implementations of the record structs are shortened.
+    const GENERATED_RUST: &str = r#"// Automatically-generated file
+#![allow(dead_code)]
+#![allow(non_snake_case)]
+use dbsp::{
+    circuit::{Circuit, IntoLayout, Stream},
+    trace::ord::{OrdIndexedZSet, OrdZSet},
+    DBSPHandle,
+    Runtime,
+};
+use dbsp_adapters::{deserialize_table_record, Catalog};
+use sqllib::*;
+
+declare_tuples! {
+    Tuple1<T0>,
+}
+
+pub fn circuit(layout: impl IntoLayout) -> (DBSPHandle, Catalog) {
+    let (circuit, catalog) = Runtime::init_circuit(layout, |circuit| {
+        let mut catalog = Catalog::new();
+        // CREATE TABLE `T` (`X` INTEGER)
+        #[derive(Clone, Debug, Eq, PartialEq, Default, PartialOrd, Ord, Hash, SizeOf, Serialize, Deserialize)]
+        pub struct T_struct {
+            field: Option<i32>,
+        }
+        impl From<T_struct> for Tuple1<Option<i32>> {
+            fn from(table: T_struct) -> Self {
+                Tuple1::new(table.field,)
+            }
+        }
+        deserialize_table_record!(T_struct["T", 1] {
+            (field, "X", false, Option<i32>, Some(None))
+        });
+        // DBSPSourceOperator 53
+        // CREATE TABLE `T` (`X` INTEGER)
+        let (T, handle0) = circuit.add_input_set::<Tuple1<Option<i32>>, Weight>();
+        // LogicalProject(X=[$0])
+        // DBSPMapOperator 113
+        let stream1: Stream<_, OrdZSet<Tuple1<Option<i32>>, Weight>> = T.map(move |t: &Tuple1<Option<i32>>, | -> Tuple1<Option<i32>> {
+            Tuple1::new(plus_i32N_s(t.0, "a"))
+        });
+        // CREATE VIEW `V1` AS
+        // SELECT `T`.`X`
+        // FROM `T`
+        #[derive(Clone, Debug, Eq, PartialEq, Default, PartialOrd, Ord, Hash, SizeOf, Serialize, Deserialize)]
+        pub struct V1_struct {
+            field0: Option<i32>,
+        }
+        impl From<V1_struct> for Tuple1<Option<i32>> {
+            fn from(table: V1_struct) -> Self {
+                Tuple1::new(table.field1,)
+            }
+        }
+        deserialize_table_record!(V1_struct["V1", 1] {
+            (field0, "X", false, Option<i32>, Some(None))
+        });
+        // LogicalProject(Y=[+($0, 1)])
+        // DBSPMapOperator 145
+        let stream2: Stream<_, OrdZSet<Tuple1<Option<i32>>, Weight>> = stream1.map(move |t: &Tuple1<Option<i32>>, | -> Tuple1<Option<i32>> {
+            Tuple1::new(plus_i32N_i32(t.0, true))
+        });
+        // CREATE VIEW `V2` AS
+        // SELECT `V1`.`X` + 1 AS `Y`
+        // FROM `V1`
+        #[derive(Clone, Debug, Eq, PartialEq, Default, PartialOrd, Ord, Hash, SizeOf, Serialize, Deserialize)]
+        pub struct V2_struct {
+            field1: Option<i32>,
+        }
+        deserialize_table_record!(V2_struct["V2", 1] {
+            (field1, "Y", false, Option<i32>, Some(None))
+        });
+        catalog.register_input_set::<_, T_struct>("T", T.clone(), handle0);
+        catalog.register_output_zset::<_, V1_struct>("V1", stream1);
+        catalog.register_output_zset::<_, V2_struct>("V2", stream2);
+        Ok(catalog)
+    }).unwrap();
+    (circuit, catalog)
+}
+"#;
+
+    /// Line number of the first line of [`GENERATED_RUST`] that contains
+    /// `text`.
+    fn generated_line(text: &str) -> usize {
+        GENERATED_RUST
+            .lines()
+            .position(|line| line.contains(text))
+            .unwrap()
+            + 1
+    }
+
+    #[test]
+    fn test_generated_view() {
+        let view = |text| super::generated_view(GENERATED_RUST, generated_line(text));
+
+        // Operators belong to the first view that follows them, the output of
+        // a view to the view.
+        assert_eq!(view("plus_i32N_s"), Some("V1".to_string()));
+        assert_eq!(view("table.field1"), Some("V1".to_string()));
+        assert_eq!(view("plus_i32N_i32"), Some("V2".to_string()));
+        assert_eq!(view("field1: Option<i32>"), Some("V2".to_string()));
+        assert_eq!(
+            view("register_output_zset::<_, V2_struct>"),
+            Some("V2".to_string())
+        );
+
+        // Inputs and code outside the circuit don't belong to a view.
+        assert_eq!(view("Tuple1::new(table.field,)"), None);
+        assert_eq!(view("add_input_set"), None);
+        assert_eq!(view("Tuple1<T0>"), None);
+        assert_eq!(view("register_input_set"), None);
+        assert_eq!(view("Ok(catalog)"), None);
+        assert_eq!(super::generated_view(GENERATED_RUST, 0), None);
+        assert_eq!(super::generated_view(GENERATED_RUST, 1000), None);
+        assert_eq!(super::generated_view("fn main() {}", 1), None);
+    }
+
+    #[test]
+    fn test_parse_rust_errors() {
+        let crate_name = "project0188e0cd-d8b0-71d5-bb5a-2f66c7b07dfb";
+        let line = generated_line("plus_i32N_s");
+        let cargo_output = [
+            r#"{"reason":"compiler-artifact","package_id":"dbsp"}"#.to_string(),
+            format!(
+                r#"{{"reason":"compiler-message","message":{{"message":"mismatched types","code":{{"code":"E0308"}},"level":"error","spans":[{{"file_name":"{crate_name}/src/main.rs","line_start":{line},"line_end":{line},"column_start":45,"column_end":48,"is_primary":true}}],"rendered":"error[E0308]: mismatched types"}}}}"#
+            ),
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `m`","code":null,"level":"warning","spans":[],"rendered":null}}"#.to_string(),
+            r#"{"reason":"compiler-message","message":{"message":"aborting due to previous error","code":null,"level":"error","spans":[],"rendered":null}}"#.to_string(),
+            r#"{"reason":"build-finished","success":false}"#.to_string(),
+        ]
+        .join("\n");
+
+        let errors =
+            super::parse_rust_errors(&cargo_output, crate_name, GENERATED_RUST, GENERATED_SQL);
+        assert_eq!(
+            errors,
+            vec![super::RustCompilerMessage {
+                level: "error".to_string(),
+                code: Some("E0308".to_string()),
+                message: "mismatched types".to_string(),
+                rendered: Some("error[E0308]: mismatched types".to_string()),
+                file_name: Some(format!("{crate_name}/src/main.rs")),
+                rust_span: Some(super::SourceSpan {
+                    start_line_number: line,
+                    start_column: 45,
+                    end_line_number: line,
+                    end_column: 48,
+                }),
+                sql_view: Some("V1".to_string()),
+                sql_span: Some(super::SourceSpan {
+                    start_line_number: 3,
+                    start_column: 1,
+                    end_line_number: 4,
+                    end_column: 18,
+                }),
+            }]
+        );
+    }
 }
@@ -31,6 +31,26 @@ fn default_binary_ref_port() -> u16 {
     9090
 }
 
+const fn default_binary_cache_max_size() -> u64 {
+    10 * 1024 * 1024 * 1024
+}
+
+const fn default_max_parallel_compilations() -> usize {
+    1
+}
+
+const fn default_pipeline_hosts() -> usize {
+    1
+}
+
+const fn default_runner_logs_port() -> u16 {
+    9091
+}
+
+const fn default_pipeline_log_max_size() -> u64 {
+    10 * 1024 * 1024
+}
+
 /// Pipeline manager configuration read from a YAML config file or from command
 /// line arguments.
 #[derive(Parser, Deserialize, Debug, Clone)]
@@ -210,6 +230,27 @@ pub struct CompilerConfig {
     /// for runners.
     #[arg(long, default_value_t = default_binary_ref_port())]
     pub binary_ref_port: u16,
+
+    /// Max total size in bytes of the binary cache.
+    ///
+    /// The compiler caches the binaries it builds, keyed by a hash of the
+    /// generated Rust code and of the compiler version, so that programs that
+    /// generate the same code reuse a binary instead of recompiling it.  When
+    /// the cache exceeds this size, the least recently used binaries are
+    /// evicted.
+    #[serde(default = "default_binary_cache_max_size")]
+    #[arg(long, default_value_t = default_binary_cache_max_size())]
+    pub binary_cache_max_size: u64,
+
+    /// Max number of programs compiled concurrently.
+    ///
+    /// Pending programs are assigned to idle compilation workers, preferring
+    /// tenants with the fewest programs being compiled.  Each worker uses a
+    /// separate cargo workspace and target directory, so increasing this
+    /// value increases the disk space used by the compiler.
+    #[serde(default = "default_max_parallel_compilations")]
+    #[arg(long, default_value_t = default_max_parallel_compilations())]
+    pub max_parallel_compilations: usize,
 }
 
 impl CompilerConfig {
@@ -236,6 +277,18 @@ impl CompilerConfig {
         Path::new(&self.compiler_working_directory).join("binaries")
     }
 
+    /// Directory where the manager caches compiled binaries by content.
+    ///
+    /// e.g., `<working-directory>/binary_cache`
+    pub(crate) fn binary_cache_dir(&self) -> PathBuf {
+        Path::new(&self.compiler_working_directory).join("binary_cache")
+    }
+
+    /// Location of the cached binary with cache key `key`.
+    pub(crate) fn cached_binary_path(&self, key: &str) -> PathBuf {
+        self.binary_cache_dir().join(key)
+    }
+
     /// Location of the versioned executable.
     /// e.g., `<working-directory>/binaries/
     /// project0188e0cd-d8b0-71d5-bb5a-2f66c7b07dfb-v11`
@@ -243,12 +296,24 @@ impl CompilerConfig {
         Path::new(&self.binaries_dir()).join(Self::binary_name(program_id, version))
     }
 
+    /// Cargo target directory of a compilation worker.
+    ///
+    /// e.g., `<working-directory>/cargo_workspace/target` for the first
+    /// worker and `<working-directory>/cargo_workspace/target-1` for the
+    /// second.
+    pub(crate) fn target_dir(&self, worker: usize) -> PathBuf {
+        if worker == 0 {
+            self.workspace_dir().join("target")
+        } else {
+            self.workspace_dir().join(format!("target-{worker}"))
+        }
+    }
+
     /// Location of the compiled executable for the project in the cargo target
-    /// dir.
+    /// dir of compilation worker `worker`.
     /// Note: This is generally not an executable that's run as a pipeline.
-    pub(crate) fn target_executable(&self, program_id: ProgramId) -> PathBuf {
-        Path::new(&self.workspace_dir())
-            .join("target")
+    pub(crate) fn target_executable(&self, program_id: ProgramId, worker: usize) -> PathBuf {
+        self.target_dir(worker)
             .join(if self.debug { "debug" } else { "release" })
             .join(Self::crate_name(program_id))
     }
@@ -294,9 +359,25 @@ impl CompilerConfig {
         self.project_dir(program_id).join("Cargo.toml")
     }
 
-    /// Top-level `Cargo.toml` file for the generated Rust workspace.
-    pub(crate) fn workspace_toml_path(&self) -> PathBuf {
-        self.workspace_dir().join("Cargo.toml")
+    /// Cargo workspace of compilation worker `worker`.
+    ///
+    /// Each worker builds in its own workspace, so that concurrent `cargo`
+    /// runs never share a `Cargo.toml` or a `Cargo.lock`.
+    ///
+    /// e.g., `<working-directory>/cargo_workspace` for the first worker and
+    /// `<working-directory>/cargo_workspace/worker-1` for the second.
+    pub(crate) fn worker_workspace_dir(&self, worker: usize) -> PathBuf {
+        if worker == 0 {
+            self.workspace_dir()
+        } else {
+            self.workspace_dir().join(format!("worker-{worker}"))
+        }
+    }
+
+    /// Top-level `Cargo.toml` file for the Rust workspace of compilation
+    /// worker `worker`.
+    pub(crate) fn workspace_toml_path(&self, worker: usize) -> PathBuf {
+        self.worker_workspace_dir(worker).join("Cargo.toml")
     }
     /// Convert all directory paths in the `self` to absolute paths.
     ///
@@ -386,6 +467,32 @@ pub struct LocalRunnerConfig {
     #[serde(default = "default_server_address")]
     #[arg(long, default_value_t = default_server_address())]
     pub pipeline_host: String,
+
+    /// Number of processes that run each pipeline.
+    ///
+    /// When greater than 1, the runner starts each pipeline as a multihost
+    /// pipeline across this many processes on the local machine, assigning
+    /// input connectors to hosts round-robin.  This is intended for testing
+    /// distributed pipelines.  Pipelines whose config already specifies a
+    /// multihost layout run with that layout instead.
+    #[serde(default = "default_pipeline_hosts")]
+    #[arg(long, default_value_t = default_pipeline_hosts())]
+    pub pipeline_hosts: usize,
+
+    /// The port of the HTTP server through which the runner makes pipeline
+    /// logs available to the API server.  Log URLs use `pipeline_host` as
+    /// the hostname.
+    #[serde(default = "default_runner_logs_port")]
+    #[arg(long, default_value_t = default_runner_logs_port())]
+    pub runner_logs_port: u16,
+
+    /// Max size of a pipeline log file in bytes.
+    ///
+    /// The runner captures the output of each pipeline to a log file.  When
+    /// the file reaches this size, it is rotated, keeping one previous file.
+    #[serde(default = "default_pipeline_log_max_size")]
+    #[arg(long, default_value_t = default_pipeline_log_max_size())]
+    pub pipeline_log_max_size: u64,
 }
 
 impl LocalRunnerConfig {
@@ -419,30 +526,97 @@ impl LocalRunnerConfig {
             .join(format!("pipeline{pipeline_id}"))
     }
 
+    /// Location to store the files of the pipeline instance running from
+    /// `slot`.
+    pub(crate) fn instance_dir(&self, pipeline_id: PipelineId, slot: PipelineSlot) -> PathBuf {
+        self.pipeline_dir(pipeline_id).join(match slot {
+            PipelineSlot::Blue => "blue",
+            PipelineSlot::Green => "green",
+        })
+    }
+
+    /// Working directory of the process that runs `host` in a multihost
+    /// pipeline.  Host 0 runs in the instance directory.
+    pub(crate) fn host_dir(
+        &self,
+        pipeline_id: PipelineId,
+        slot: PipelineSlot,
+        host: usize,
+    ) -> PathBuf {
+        if host == 0 {
+            self.instance_dir(pipeline_id, slot)
+        } else {
+            self.instance_dir(pipeline_id, slot)
+                .join(format!("host{host}"))
+        }
+    }
+
     /// Location to write the fetched pipeline binary to.
     pub(crate) fn binary_file_path(
         &self,
         pipeline_id: PipelineId,
+        slot: PipelineSlot,
         program: ProgramId,
         version: Version,
     ) -> PathBuf {
-        self.pipeline_dir(pipeline_id)
+        self.instance_dir(pipeline_id, slot)
             .join(format!("program_{program}_v{version}"))
     }
 
-    /// Location to write the pipeline config file.
-    pub(crate) fn config_file_path(&self, pipeline_id: PipelineId) -> PathBuf {
-        self.pipeline_dir(pipeline_id).join("config.yaml")
+    /// Location to write the config file of `host`.
+    pub(crate) fn config_file_path(
+        &self,
+        pipeline_id: PipelineId,
+        slot: PipelineSlot,
+        host: usize,
+    ) -> PathBuf {
+        self.host_dir(pipeline_id, slot, host).join("config.yaml")
     }
 
     /// Location to write the pipeline metadata file.
-    pub(crate) fn metadata_file_path(&self, pipeline_id: PipelineId) -> PathBuf {
-        self.pipeline_dir(pipeline_id).join("metadata.json")
+    pub(crate) fn metadata_file_path(
+        &self,
+        pipeline_id: PipelineId,
+        slot: PipelineSlot,
+    ) -> PathBuf {
+        self.instance_dir(pipeline_id, slot).join("metadata.json")
+    }
+
+    /// Location of the log file of a pipeline.
+    ///
+    /// Logs are kept outside of the pipeline directory, so they survive
+    /// pipeline shutdown.
+    pub(crate) fn pipeline_log_path(&self, pipeline_id: PipelineId) -> PathBuf {
+        Path::new(&self.runner_working_directory)
+            .join("logs")
+            .join(format!("pipeline{pipeline_id}.log"))
     }
 
     /// Location for pipeline port file
-    pub(crate) fn port_file_path(&self, pipeline_id: PipelineId) -> PathBuf {
-        self.pipeline_dir(pipeline_id)
+    pub(crate) fn port_file_path(&self, pipeline_id: PipelineId, slot: PipelineSlot) -> PathBuf {
+        self.instance_dir(pipeline_id, slot)
             .join(dbsp_adapters::server::SERVER_PORT_FILE)
     }
 }
+
+/// One of the two instance directories of a pipeline.
+///
+/// A pipeline normally runs from a single slot.  During an upgrade, the
+/// runner starts the new revision of the pipeline from the other slot,
+/// alongside the running instance; the slots swap roles once the new
+/// instance takes over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PipelineSlot {
+    Blue,
+    Green,
+}
+
+impl PipelineSlot {
+    /// The slot that an upgrade of a pipeline running from `self` starts from.
+    pub(crate) fn other(self) -> Self {
+        match self {
+            Self::Blue => Self::Green,
+            Self::Green => Self::Blue,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use super::{ConnectorId, PipelineId, ProgramId, Version};
+use super::{ApiKeyId, ConnectorId, PipelineId, ProgramId, Version};
 use crate::auth::TenantId;
 use actix_web::{
     body::BoxBody, http::StatusCode, HttpResponse, HttpResponseBuilder, ResponseError,
@@ -57,6 +57,10 @@ pub enum DBError {
     OutdatedProgramVersion {
         latest_version: Version,
     },
+    UnknownProgramVersion {
+        program_id: ProgramId,
+        version: Version,
+    },
     UnknownPipeline {
         pipeline_id: PipelineId,
     },
@@ -66,6 +70,9 @@ pub enum DBError {
     UnknownTenant {
         tenant_id: TenantId,
     },
+    UnknownApiKey {
+        api_key_id: ApiKeyId,
+    },
     UnknownAttachedConnector {
         pipeline_id: PipelineId,
         name: String,
@@ -92,6 +99,15 @@ pub enum DBError {
     ProgramNotSet,
     ProgramNotCompiled,
     ProgramFailedToCompile,
+    ProgramNotCompiling {
+        program_id: ProgramId,
+    },
+    InvalidProjectBundle {
+        reason: String,
+    },
+    ApplyConflict {
+        reason: String,
+    },
     NoRevisionAvailable {
         pipeline_id: PipelineId,
     },
@@ -329,6 +345,12 @@ impl Display for DBError {
                     "Outdated program version. Latest version: '{latest_version}'"
                 )
             }
+            DBError::UnknownProgramVersion {
+                program_id,
+                version,
+            } => {
+                write!(f, "Unknown version '{version}' of program '{program_id}'")
+            }
             DBError::UnknownPipeline { pipeline_id } => {
                 write!(f, "Unknown pipeline id '{pipeline_id}'")
             }
@@ -344,6 +366,9 @@ impl Display for DBError {
             DBError::UnknownTenant { tenant_id } => {
                 write!(f, "Unknown tenant id '{tenant_id}'")
             }
+            DBError::UnknownApiKey { api_key_id } => {
+                write!(f, "Unknown API key id '{api_key_id}'")
+            }
             DBError::DuplicateName => {
                 write!(f, "An entity with this name already exists")
             }
@@ -375,6 +400,18 @@ impl Display for DBError {
                     "The program attached to the pipeline did not compile successfully"
                 )
             }
+            DBError::InvalidProjectBundle { reason } => {
+                write!(f, "Invalid project bundle: {reason}")
+            }
+            DBError::ApplyConflict { reason } => {
+                write!(f, "Cannot apply the desired state: {reason}")
+            }
+            DBError::ProgramNotCompiling { program_id } => {
+                write!(
+                    f,
+                    "The program {program_id} is not queued or being compiled"
+                )
+            }
             DBError::NoRevisionAvailable { pipeline_id } => {
                 write!(
                     f,
@@ -415,9 +452,11 @@ impl DetailedError for DBError {
             Self::UnknownProgram { .. } => Cow::from("UnknownProgram"),
             Self::ProgramInUseByPipeline { .. } => Cow::from("ProgramInUseByPipeline"),
             Self::OutdatedProgramVersion { .. } => Cow::from("OutdatedProgramVersion"),
+            Self::UnknownProgramVersion { .. } => Cow::from("UnknownProgramVersion"),
             Self::UnknownPipeline { .. } => Cow::from("UnknownPipeline"),
             Self::UnknownConnector { .. } => Cow::from("UnknownConnector"),
             Self::UnknownTenant { .. } => Cow::from("UnknownTenant"),
+            Self::UnknownApiKey { .. } => Cow::from("UnknownApiKey"),
             Self::UnknownAttachedConnector { .. } => Cow::from("UnknownAttachedConnector"),
             Self::UnknownName { .. } => Cow::from("UnknownName"),
             Self::DuplicateName => Cow::from("DuplicateName"),
@@ -428,6 +467,9 @@ impl DetailedError for DBError {
             Self::ProgramNotSet => Cow::from("ProgramNotSet"),
             Self::ProgramNotCompiled => Cow::from("ProgramNotCompiled"),
             Self::ProgramFailedToCompile => Cow::from("ProgramFailedToCompile"),
+            Self::ProgramNotCompiling { .. } => Cow::from("ProgramNotCompiling"),
+            Self::InvalidProjectBundle { .. } => Cow::from("InvalidProjectBundle"),
+            Self::ApplyConflict { .. } => Cow::from("ApplyConflict"),
             Self::NoRevisionAvailable { .. } => Cow::from("NoRevisionAvailable"),
             Self::RevisionNotChanged => Cow::from("RevisionNotChanged"),
             Self::TablesNotInSchema { .. } => Cow::from("TablesNotInSchema"),
@@ -438,9 +480,11 @@ impl DetailedError for DBError {
     fn log_level(&self) -> Level {
         match self {
             Self::UnknownProgram { .. } => Level::Info,
+            Self::UnknownProgramVersion { .. } => Level::Info,
             Self::UnknownPipeline { .. } => Level::Info,
             Self::UnknownConnector { .. } => Level::Info,
             Self::UnknownName { .. } => Level::Info,
+            Self::UnknownApiKey { .. } => Level::Info,
             _ => Level::Error,
         }
     }
@@ -462,10 +506,12 @@ impl ResponseError for DBError {
             Self::ProgramInUseByPipeline { .. } => StatusCode::BAD_REQUEST,
             Self::DuplicateName => StatusCode::CONFLICT,
             Self::OutdatedProgramVersion { .. } => StatusCode::CONFLICT,
+            Self::UnknownProgramVersion { .. } => StatusCode::NOT_FOUND,
             Self::UnknownPipeline { .. } => StatusCode::NOT_FOUND,
             Self::UnknownConnector { .. } => StatusCode::NOT_FOUND,
             // TODO: should we report not found instead?
             Self::UnknownTenant { .. } => StatusCode::UNAUTHORIZED,
+            Self::UnknownApiKey { .. } => StatusCode::NOT_FOUND,
             Self::UnknownAttachedConnector { .. } => StatusCode::NOT_FOUND,
             // This error should never bubble up till here
             Self::DuplicateKey { .. } => StatusCode::INTERNAL_SERVER_ERROR,
@@ -473,6 +519,9 @@ impl ResponseError for DBError {
             Self::UnknownName { .. } => StatusCode::NOT_FOUND,
             Self::ProgramNotCompiled => StatusCode::SERVICE_UNAVAILABLE,
             Self::ProgramFailedToCompile => StatusCode::BAD_REQUEST,
+            Self::ProgramNotCompiling { .. } => StatusCode::BAD_REQUEST,
+            Self::InvalidProjectBundle { .. } => StatusCode::BAD_REQUEST,
+            Self::ApplyConflict { .. } => StatusCode::CONFLICT,
             Self::ProgramNotSet => StatusCode::BAD_REQUEST,
             // should in practice not happen, e.g., would mean a Uuid conflict:
             Self::UniqueKeyViolation { .. } => StatusCode::INTERNAL_SERVER_ERROR,
@@ -2,7 +2,7 @@
 use crate::config::ApiServerConfig;
 use crate::{
     auth::{TenantId, TenantRecord},
-    compiler::ProgramStatus,
+    compiler::{ProgramStatus, RustCompilerMessage},
     config::DatabaseConfig,
 };
 use async_trait::async_trait;
@@ -11,7 +11,7 @@ use dbsp_adapters::{
     ConnectorConfig, ErrorResponse, InputEndpointConfig, OutputEndpointConfig, OutputQuery,
     PipelineConfig, RuntimeConfig,
 };
-use deadpool_postgres::{Manager, Pool, RecyclingMethod, Transaction};
+use deadpool_postgres::{Manager, Pool, RecyclingMethod};
 use futures_util::TryFutureExt;
 use log::{debug, error};
 use openssl::sha;
@@ -22,9 +22,10 @@ use std::{
     collections::{BTreeMap, HashSet},
     fmt,
     fmt::Display,
+    str::FromStr,
 };
 use storage::Storage;
-use tokio_postgres::{error::Error as PgError, NoTls, Row};
+use tokio_postgres::{error::Error as PgError, GenericClient, NoTls, Row};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -104,6 +105,18 @@ impl Display for ConnectorId {
     }
 }
 
+/// Unique API key id.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
+#[repr(transparent)]
+#[serde(transparent)]
+pub struct ApiKeyId(#[cfg_attr(test, proptest(strategy = "test::limited_uuid()"))] pub Uuid);
+impl Display for ApiKeyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// Unique attached connector id.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
@@ -132,9 +145,10 @@ impl Display for Version {
 
 /// Revision number.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 #[repr(transparent)]
 #[serde(transparent)]
-pub(crate) struct Revision(Uuid);
+pub(crate) struct Revision(#[cfg_attr(test, proptest(strategy = "test::limited_uuid()"))] Uuid);
 impl Display for Revision {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
@@ -165,7 +179,17 @@ impl ProgramStatus {
                     Ok(Self::SystemError(error))
                 }
             }
-            Some("rust_error") => Ok(Self::RustError(error_string.unwrap_or_default())),
+            Some("rust_error") => {
+                let error = error_string.unwrap_or_default();
+                if let Ok(messages) = serde_json::from_str(&error) {
+                    Ok(Self::RustError(messages))
+                } else {
+                    // Rust errors used to be stored as the raw compiler output.
+                    Ok(Self::RustError(vec![RustCompilerMessage::unstructured(
+                        error,
+                    )]))
+                }
+            }
             Some("system_error") => Ok(Self::SystemError(error_string.unwrap_or_default())),
             Some(status) => Err(DBError::invalid_status(status.to_string())),
         }
@@ -186,7 +210,12 @@ impl ProgramStatus {
                 }
             }
             ProgramStatus::RustError(error) => {
-                (Some("rust_error".to_string()), Some(error.clone()))
+                if let Ok(error_string) = serde_json::to_string(&error) {
+                    (Some("rust_error".to_string()), Some(error_string))
+                } else {
+                    error!("Expected valid json for RustError, but got {:?}", error);
+                    (Some("rust_error".to_string()), None)
+                }
             }
             ProgramStatus::SystemError(error) => {
                 (Some("system_error".to_string()), Some(error.clone()))
@@ -310,6 +339,22 @@ pub(crate) struct ProgramDescr {
     pub code: Option<String>,
 }
 
+/// A version of a program's code.
+///
+/// A new version is recorded every time the code of a program changes.
+#[derive(Deserialize, Serialize, ToSchema, Debug, Eq, PartialEq, Clone)]
+pub(crate) struct ProgramVersionDescr {
+    /// Program version.
+    pub version: Version,
+    /// Time when the version was created.
+    pub created: DateTime<Utc>,
+    /// Schema of the program at this version, if the version was compiled
+    /// by the SQL compiler.
+    pub schema: Option<ProgramSchema>,
+    /// SQL code.
+    pub code: Option<String>,
+}
+
 /// Pipeline status.
 ///
 /// This type represents the state of the pipeline tracked by the pipeline runner and
@@ -668,6 +713,7 @@ impl PipelineRevision {
             }
             let input_endpoint_config = InputEndpointConfig {
                 stream: Cow::from(ac.relation_name.clone()),
+                host: 0,
                 connector_config: connector.unwrap().config.clone(),
             };
             expanded_inputs.insert(Cow::from(ac.name.clone()), input_endpoint_config);
@@ -716,6 +762,18 @@ pub(crate) struct PipelineDescr {
     pub attached_connectors: Vec<AttachedConnector>,
 }
 
+/// A committed revision of a pipeline.
+///
+/// Revisions are created every time a changed pipeline is deployed.
+#[derive(Deserialize, Serialize, ToSchema, Eq, PartialEq, Debug, Clone)]
+pub(crate) struct PipelineHistoryEntry {
+    pub revision: Revision,
+    /// The pipeline descriptor at this revision.
+    pub pipeline: PipelineDescr,
+    /// Version of the program the revision was created with.
+    pub program_version: Version,
+}
+
 /// Runtime state of the pipeine.
 #[derive(Deserialize, Serialize, ToSchema, Eq, PartialEq, Debug, Clone)]
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
@@ -746,6 +804,11 @@ pub(crate) struct PipelineRuntimeState {
     /// [`Failed`](`PipelineStatus::Failed`).
     /// When present, this field contains the error that caused
     /// the pipeline to terminate abnormally.
+    ///
+    /// The field is also set while the pipeline is
+    /// [`Running`](`PipelineStatus::Running`) or
+    /// [`Paused`](`PipelineStatus::Paused`) if its last upgrade failed and was
+    /// rolled back.
     // TODO: impl `Arbitrary` for `ErrorResponse`.
     #[cfg_attr(test, proptest(value = "None"))]
     pub error: Option<ErrorResponse>,
@@ -753,6 +816,30 @@ pub(crate) struct PipelineRuntimeState {
     /// Time when the pipeline started executing.
     #[cfg_attr(test, proptest(value = "Utc::now()"))]
     pub created: DateTime<Utc>,
+
+    /// Number of times the runner restarted the pipeline after a failure
+    /// since the pipeline was last deployed or last ran for the
+    /// `reset_after_ms` period of its
+    /// [`RestartPolicy`](`dbsp_adapters::RestartPolicy`).
+    pub restart_count: u32,
+
+    /// Error that caused the last restart of the pipeline.
+    ///
+    /// Unlike `error`, this field is preserved while the restarted pipeline
+    /// runs.
+    #[cfg_attr(test, proptest(value = "None"))]
+    pub last_exit_reason: Option<ErrorResponse>,
+
+    /// Location where the runner serves the log of the pipeline, e.g., a URL.
+    ///
+    /// Empty if the pipeline has never been deployed.
+    pub log_location: String,
+
+    /// Revision of the pipeline that its running instance was started from.
+    ///
+    /// The runner upgrades the pipeline when a different revision is
+    /// committed while it runs.  `None` if the pipeline is shut down.
+    pub running_revision: Option<Revision>,
 }
 
 impl PipelineRuntimeState {
@@ -810,12 +897,373 @@ pub(crate) struct ConnectorDescr {
     pub config: ConnectorConfig,
 }
 
-/// Permission types for invoking pipeline manager APIs
-#[derive(Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+/// A connector as it was committed as part of a pipeline revision.
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ConnectorHistoryEntry {
+    pub revision: Revision,
+    /// The pipeline whose revision captured the connector.
+    pub pipeline_id: PipelineId,
+    /// The connector descriptor at this revision.
+    pub connector: ConnectorDescr,
+}
+
+/// Version of the [`ProjectBundle`] format produced by this version of the
+/// pipeline manager.
+pub(crate) const PROJECT_BUNDLE_VERSION: u32 = 1;
+
+/// The programs, connectors and pipelines of a tenant, in a form that can be
+/// imported into another pipeline manager installation.
+///
+/// Objects refer to each other by name rather than by id, since ids are
+/// specific to an installation.
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ProjectBundle {
+    /// Version of the bundle format.
+    pub bundle_version: u32,
+    pub programs: Vec<BundledProgram>,
+    pub connectors: Vec<BundledConnector>,
+    pub pipelines: Vec<BundledPipeline>,
+}
+
+/// A program in a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct BundledProgram {
+    pub name: String,
+    pub description: String,
+    /// SQL code of the program.
+    pub code: String,
+}
+
+/// A connector in a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct BundledConnector {
+    pub name: String,
+    pub description: String,
+    pub config: ConnectorConfig,
+}
+
+/// A pipeline in a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct BundledPipeline {
+    pub name: String,
+    pub description: String,
+    /// Name of the program of the pipeline, if any.
+    pub program_name: Option<String>,
+    pub config: RuntimeConfig,
+    pub attached_connectors: Vec<BundledAttachedConnector>,
+}
+
+/// A connector attached to a pipeline in a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct BundledAttachedConnector {
+    /// A unique identifier for this attachement.
+    pub name: String,
+    /// Is this an input or an output?
+    pub is_input: bool,
+    /// Name of the connector to attach.
+    pub connector_name: String,
+    /// The table or view this connector is attached to.
+    pub relation_name: String,
+}
+
+/// What importing a [`ProjectBundle`] does, or would do in a dry run, with
+/// an object of the bundle.
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ImportAction {
+    /// No object with this name exists; it is created.
+    Create,
+    /// An object with this name exists and differs from the bundle; it is
+    /// overwritten.
+    Update,
+    /// An object with this name exists and matches the bundle.
+    Unchanged,
+}
+
+/// The outcome of importing one object of a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ImportedObject {
+    pub name: String,
+    pub action: ImportAction,
+    /// Fields of the existing object that differ from the bundle, i.e., the
+    /// conflicts resolved in favor of the bundle.
+    pub conflicts: Vec<String>,
+}
+
+/// The outcome of importing a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ImportReport {
+    /// If `true`, nothing was modified.
+    pub dry_run: bool,
+    pub programs: Vec<ImportedObject>,
+    pub connectors: Vec<ImportedObject>,
+    pub pipelines: Vec<ImportedObject>,
+}
+
+/// A kind of object of a [`ProjectBundle`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ObjectKind {
+    Program,
+    Connector,
+    Pipeline,
+}
+
+/// An action of an [`ApplyPlan`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Copy, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ApplyAction {
+    /// Create an object that only exists in the desired state.
+    Create,
+    /// Update an object that differs from the desired state.
+    Update,
+    /// Delete an object that doesn't exist in the desired state.
+    Delete,
+    /// Queue a new, modified or never compiled program for compilation.
+    Recompile,
+    /// Upgrade a running or paused pipeline whose committed revision differs
+    /// from the desired state to a new revision.
+    Redeploy,
+}
+
+/// A step of an [`ApplyPlan`].
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ApplyStep {
+    pub action: ApplyAction,
+    pub kind: ObjectKind,
+    /// Name of the object.
+    pub name: String,
+    /// Fields of the object that are updated.
+    pub changes: Vec<String>,
+    /// The step is skipped, because the program of the pipeline to redeploy
+    /// is not compiled yet.  Applying the same desired state again once
+    /// compilation succeeds redeploys the pipeline.
+    pub deferred: bool,
+}
+
+impl ApplyStep {
+    pub(crate) fn new(action: ApplyAction, kind: ObjectKind, name: &str) -> Self {
+        Self {
+            action,
+            kind,
+            name: name.to_string(),
+            changes: Vec::new(),
+            deferred: false,
+        }
+    }
+
+    pub(crate) fn with_changes(mut self, changes: Vec<&str>) -> Self {
+        self.changes = changes.into_iter().map(String::from).collect();
+        self
+    }
+}
+
+/// The steps that bring the programs, connectors and pipelines of a tenant to
+/// a desired state, in the order in which they are executed.
+#[derive(Deserialize, Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ApplyPlan {
+    /// If `true`, the plan was not executed.
+    pub dry_run: bool,
+    pub steps: Vec<ApplyStep>,
+}
+
+/// A modification of the programs, connectors or pipelines of a tenant.
+///
+/// Imports and applies of a [`ProjectBundle`] are executed as a sequence of
+/// changes, which [`Storage::commit_project_changes`] applies atomically.
+/// Ids of new objects are chosen upfront, so that later changes can refer to
+/// them.
+///
+/// Changes are computed from reads outside of the transaction that applies
+/// them.  Updates and deletes of programs and pipelines therefore carry the
+/// `version` that was read, and fail with [`DBError::ApplyConflict`] if the
+/// object was modified in the meantime.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum ProjectChange {
+    NewProgram {
+        program_id: ProgramId,
+        program: BundledProgram,
+    },
+    /// Update the name and description of a program and, if `code` is set,
+    /// its code.
+    UpdateProgram {
+        program_id: ProgramId,
+        version: Version,
+        name: String,
+        description: String,
+        code: Option<String>,
+    },
+    DeleteProgram {
+        program_id: ProgramId,
+        version: Version,
+    },
+    /// Queue a program for compilation, unless it is already queued, being
+    /// compiled or compiled.
+    CompileProgram {
+        program_id: ProgramId,
+    },
+    NewConnector {
+        connector_id: ConnectorId,
+        connector: BundledConnector,
+    },
+    UpdateConnector {
+        connector_id: ConnectorId,
+        connector: BundledConnector,
+    },
+    DeleteConnector {
+        connector_id: ConnectorId,
+    },
+    NewPipeline {
+        pipeline_id: PipelineId,
+        program_id: Option<ProgramId>,
+        name: String,
+        description: String,
+        config: RuntimeConfig,
+        connectors: Vec<AttachedConnector>,
+    },
+    /// Update a pipeline and replace its attached connectors.
+    UpdatePipeline {
+        pipeline_id: PipelineId,
+        version: Version,
+        program_id: Option<ProgramId>,
+        name: String,
+        description: String,
+        config: RuntimeConfig,
+        connectors: Vec<AttachedConnector>,
+    },
+    /// Delete a pipeline, which must be shut down.
+    DeletePipeline {
+        pipeline_id: PipelineId,
+        version: Version,
+    },
+    /// Commit a new revision of a pipeline, unless nothing changed since its
+    /// last revision.
+    CommitPipelineRevision {
+        pipeline_id: PipelineId,
+    },
+}
+
+/// Permission types for invoking pipeline manager APIs.
+///
+/// `Read` and `Write` apply to all resources of the tenant.  The remaining
+/// permissions are scoped to a single pipeline and, optionally, a single
+/// table or view of the pipeline.  Permissions are stored in the database as
+/// strings (see the `Display` and `FromStr` implementations), e.g.,
+/// `read`, `egress:<pipeline_id>`, or `ingress:<pipeline_id>:<table>`.
+#[derive(Serialize, Deserialize, ToSchema, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(test, derive(proptest_derive::Arbitrary))]
 pub(crate) enum ApiPermission {
+    /// Read access to all resources.
     Read,
+    /// Read and write access to all resources.
     Write,
+    /// Push data to the tables of a pipeline via its `ingress` endpoint.
+    /// When `relation` is set, only to the specified table.
+    Ingress {
+        pipeline_id: PipelineId,
+        relation: Option<String>,
+    },
+    /// Read data from the tables and views of a pipeline via its `egress`
+    /// endpoint.  When `relation` is set, only from the specified table or
+    /// view.
+    Egress {
+        pipeline_id: PipelineId,
+        relation: Option<String>,
+    },
+}
+
+impl Display for ApiPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, pipeline_id, relation) = match self {
+            ApiPermission::Read => return f.write_str("read"),
+            ApiPermission::Write => return f.write_str("write"),
+            ApiPermission::Ingress {
+                pipeline_id,
+                relation,
+            } => ("ingress", pipeline_id, relation),
+            ApiPermission::Egress {
+                pipeline_id,
+                relation,
+            } => ("egress", pipeline_id, relation),
+        };
+        write!(f, "{kind}:{pipeline_id}")?;
+        if let Some(relation) = relation {
+            write!(f, ":{relation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ApiPermission {
+    type Err = DBError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || DBError::invalid_data(format!("Invalid API key scope '{s}'"));
+
+        // Relation names may contain ':', so only split off the first two
+        // components.
+        let mut parts = s.splitn(3, ':');
+        let kind = parts.next().ok_or_else(invalid)?;
+        let pipeline_id = parts.next();
+        let relation = parts.next().map(str::to_string);
+
+        match (kind, pipeline_id) {
+            ("read", None) => Ok(ApiPermission::Read),
+            ("write", None) => Ok(ApiPermission::Write),
+            ("ingress" | "egress", Some(pipeline_id)) => {
+                let pipeline_id = PipelineId(pipeline_id.parse().map_err(|_| invalid())?);
+                if kind == "ingress" {
+                    Ok(ApiPermission::Ingress {
+                        pipeline_id,
+                        relation,
+                    })
+                } else {
+                    Ok(ApiPermission::Egress {
+                        pipeline_id,
+                        relation,
+                    })
+                }
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// API key metadata.  The key itself is only returned once, when the key is
+/// created.
+#[derive(Serialize, ToSchema, Debug, Clone, Eq, PartialEq)]
+pub(crate) struct ApiKeyDescr {
+    pub api_key_id: ApiKeyId,
+    /// Key name, unique within the tenant.
+    pub name: String,
+    /// Permissions granted to the key.
+    pub scopes: Vec<ApiPermission>,
+    /// Time when the key was created.
+    pub created: DateTime<Utc>,
+    /// Time when the key was last used to authenticate a request, if ever.
+    ///
+    /// Only updated once every [`API_KEY_LAST_USED_RESOLUTION`] seconds.
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Minimum time in seconds between two updates of the `last_used` time of an
+/// API key, so that authenticating a stream of requests doesn't turn every
+/// request into a database write.
+pub(crate) const API_KEY_LAST_USED_RESOLUTION: i64 = 60;
+
+fn row_to_program_version(row: &Row) -> Result<ProgramVersionDescr, DBError> {
+    let schema: Option<ProgramSchema> = row
+        .get::<_, Option<String>>(2)
+        .map(|s| serde_json::from_str(&s))
+        .transpose()
+        .map_err(|e| DBError::invalid_data(format!("Error parsing program schema: {e}")))?;
+
+    Ok(ProgramVersionDescr {
+        version: Version(row.get(0)),
+        created: convert_bigint_to_time(row.get(1))?,
+        schema,
+        code: row.get(3),
+    })
 }
 
 fn convert_bigint_to_time(created_secs: i64) -> Result<DateTime<Utc>, DBError> {
@@ -886,17 +1334,16 @@ impl Storage for ProjectDB {
         program_description: &str,
         program_code: &str,
     ) -> Result<(ProgramId, Version), DBError> {
-        debug!("new_program {program_name} {program_description} {program_code}");
-        self.pool.get().await?.execute(
-                    "INSERT INTO program (id, version, tenant_id, name, description, code, schema, status, error, status_since)
-                        VALUES($1, 1, $2, $3, $4, $5, NULL, NULL, NULL, now());",
-                &[&id, &tenant_id.0, &program_name, &program_description, &program_code]
-            )
-            .await
-            .map_err(ProjectDB::maybe_unique_violation)
-            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None))?;
-
-        Ok((ProgramId(id), Version(1)))
+        let client = self.pool.get().await?;
+        self.new_program_with(
+            &**client,
+            tenant_id,
+            id,
+            program_name,
+            program_description,
+            program_code,
+        )
+        .await
     }
 
     /// Update program name, description and, optionally, code.
@@ -909,51 +1356,16 @@ impl Storage for ProjectDB {
         program_description: &str,
         program_code: &Option<String>,
     ) -> Result<Version, DBError> {
-        let row = match program_code {
-            Some(code) => {
-                // Only increment `version` if new code actually differs from the
-                // current version.
-                self.pool.get().await?
-                    .query_opt(
-                        "UPDATE program
-                            SET
-                                version = (CASE WHEN code = $3 THEN version ELSE version + 1 END),
-                                name = $1,
-                                description = $2,
-                                code = $3,
-                                status = (CASE WHEN code = $3 THEN status ELSE NULL END),
-                                error = (CASE WHEN code = $3 THEN error ELSE NULL END),
-                                schema = (CASE WHEN code = $3 THEN schema ELSE NULL END)
-                        WHERE id = $4 AND tenant_id = $5
-                        RETURNING version
-                    ",
-                        &[
-                            &program_name,
-                            &program_description,
-                            &code,
-                            &program_id.0,
-                            &tenant_id.0
-                        ],
-                    )
-                    .await
-                    .map_err(ProjectDB::maybe_unique_violation)?
-            }
-            _ => {
-                self.pool.get().await?
-                    .query_opt(
-                        "UPDATE program SET name = $1, description = $2 WHERE id = $3 AND tenant_id = $4 RETURNING version",
-                        &[&program_name, &program_description, &program_id.0, &tenant_id.0],
-                    )
-                    .await
-                    .map_err(ProjectDB::maybe_unique_violation)?
-            }
-        };
-
-        if let Some(row) = row {
-            Ok(Version(row.get(0)))
-        } else {
-            Err(DBError::UnknownProgram { program_id })
-        }
+        let client = self.pool.get().await?;
+        self.update_program_with(
+            &**client,
+            tenant_id,
+            program_id,
+            program_name,
+            program_description,
+            program_code,
+        )
+        .await
     }
 
     /// Retrieve program descriptor.
@@ -965,44 +1377,9 @@ impl Storage for ProjectDB {
         program_id: ProgramId,
         with_code: bool,
     ) -> Result<Option<ProgramDescr>, DBError> {
-        let row = self
-            .pool
-            .get()
-            .await?
-            .query_opt(
-                "SELECT name, description, version, status, error, schema,
-                CASE WHEN $3 IS TRUE THEN code ELSE null END
-                FROM program WHERE id = $1 AND tenant_id = $2",
-                &[&program_id.0, &tenant_id.0, &with_code],
-            )
-            .await?;
-
-        if let Some(row) = row {
-            let name: String = row.get(0);
-            let description: String = row.get(1);
-            let version: Version = Version(row.get(2));
-            let status: Option<String> = row.get(3);
-            let error: Option<String> = row.get(4);
-            let schema: Option<ProgramSchema> = row
-                .get::<_, Option<String>>(5)
-                .map(|s| serde_json::from_str(&s))
-                .transpose()
-                .map_err(|e| DBError::invalid_data(format!("Error parsing program schema: {e}")))?;
-            let code: Option<String> = row.get(6);
-
-            let status = ProgramStatus::from_columns(status.as_deref(), error)?;
-            Ok(Some(ProgramDescr {
-                program_id,
-                name,
-                description,
-                version,
-                status,
-                schema,
-                code,
-            }))
-        } else {
-            Ok(None)
-        }
+        let client = self.pool.get().await?;
+        self.get_program_if_exists_with(&**client, tenant_id, program_id, with_code)
+            .await
     }
 
     /// Lookup program by name.
@@ -1059,29 +1436,15 @@ impl Storage for ProjectDB {
         expected_version: Version,
         status: ProgramStatus,
     ) -> Result<(), DBError> {
-        let (status, error) = status.to_columns();
-        self.pool
-            .get()
-            .await?
-            .execute(
-                "UPDATE program SET
-                 status = (CASE WHEN version = $4 THEN $1 ELSE status END),
-                 error = (CASE WHEN version = $4 THEN $2 ELSE error END),
-                 status_since = (CASE WHEN version = $4 THEN now()
-                                 ELSE status_since END),
-                 schema = (CASE WHEN version = $4 THEN NULL ELSE schema END)
-                 WHERE id = $3 AND tenant_id = $5",
-                &[
-                    &status,
-                    &error,
-                    &program_id.0,
-                    &expected_version.0,
-                    &tenant_id.0,
-                ],
-            )
-            .await?;
-
-        Ok(())
+        let client = self.pool.get().await?;
+        self.set_program_for_compilation_with(
+            &**client,
+            tenant_id,
+            program_id,
+            expected_version,
+            status,
+        )
+        .await
     }
 
     async fn set_program_status_guarded(
@@ -1150,26 +1513,117 @@ impl Storage for ProjectDB {
         tenant_id: TenantId,
         program_id: ProgramId,
     ) -> Result<(), DBError> {
-        let res = self
+        let client = self.pool.get().await?;
+        self.delete_program_with(&**client, tenant_id, program_id)
+            .await
+    }
+
+    async fn list_program_versions(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        with_code: bool,
+    ) -> Result<Vec<ProgramVersionDescr>, DBError> {
+        let rows = self
             .pool
             .get()
             .await?
-            .execute(
-                "DELETE FROM program WHERE id = $1 AND tenant_id = $2",
+            .query(
+                "SELECT version, created, schema, CASE WHEN $3 IS TRUE THEN code ELSE null END
+                FROM program_version WHERE program_id = $1 AND tenant_id = $2
+                ORDER BY version",
+                &[&program_id.0, &tenant_id.0, &with_code],
+            )
+            .await?;
+
+        // Every program has at least one version.
+        if rows.is_empty() {
+            return Err(DBError::UnknownProgram { program_id });
+        }
+
+        rows.iter().map(row_to_program_version).collect()
+    }
+
+    async fn get_program_version(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<ProgramVersionDescr, DBError> {
+        let row = self
+            .pool
+            .get()
+            .await?
+            .query_opt(
+                "SELECT pv.version, pv.created, pv.schema, pv.code
+                FROM program p
+                LEFT JOIN program_version pv ON pv.program_id = p.id AND pv.version = $2
+                WHERE p.id = $1 AND p.tenant_id = $3",
+                &[&program_id.0, &version.0, &tenant_id.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownProgram { program_id })?;
+
+        if row.get::<_, Option<i64>>(0).is_none() {
+            return Err(DBError::UnknownProgramVersion {
+                program_id,
+                version,
+            });
+        }
+
+        row_to_program_version(&row)
+    }
+
+    async fn rollback_program(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<Version, DBError> {
+        let mut client = self.pool.get().await?;
+        let txn = client.transaction().await?;
+
+        let current = txn
+            .query_opt(
+                "SELECT version, code FROM program WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
                 &[&program_id.0, &tenant_id.0],
             )
-            .await
-            .map_err(|e| {
-                ProjectDB::maybe_program_id_in_use_foreign_key_constraint_err(
-                    e.into(),
-                    Some(program_id),
-                )
-            })?;
-        if res > 0 {
-            Ok(())
-        } else {
-            Err(DBError::UnknownProgram { program_id })
+            .await?
+            .ok_or(DBError::UnknownProgram { program_id })?;
+        let current_version = Version(current.get(0));
+        let current_code: String = current.get(1);
+
+        let code: String = txn
+            .query_opt(
+                "SELECT code FROM program_version WHERE program_id = $1 AND version = $2",
+                &[&program_id.0, &version.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownProgramVersion {
+                program_id,
+                version,
+            })?
+            .get(0);
+
+        if code == current_code {
+            return Ok(current_version);
         }
+
+        // The `program_version_record` trigger records the restored code as a
+        // new version.
+        let row = txn
+            .query_one(
+                "UPDATE program
+                    SET version = version + 1, code = $1, schema = NULL, status = 'pending',
+                        error = NULL, status_since = now()
+                WHERE id = $2 AND tenant_id = $3
+                RETURNING version",
+                &[&code, &program_id.0, &tenant_id.0],
+            )
+            .await?;
+        txn.commit().await?;
+
+        Ok(Version(row.get(0)))
     }
 
     async fn all_programs(&self) -> Result<Vec<(TenantId, ProgramDescr)>, DBError> {
@@ -1233,8 +1687,22 @@ impl Storage for ProjectDB {
     }
 
     async fn next_job(&self) -> Result<Option<(TenantId, ProgramId, Version)>, DBError> {
-        // Find the oldest pending project.
-        let res = self.pool.get().await?.query("SELECT id, version, tenant_id FROM program WHERE status = 'pending' AND status_since = (SELECT min(status_since) FROM program WHERE status = 'pending')", &[])
+        // Find the oldest pending project of the tenant with the fewest
+        // programs being compiled.
+        let res = self
+            .pool
+            .get()
+            .await?
+            .query(
+                "SELECT id, version, tenant_id FROM program p
+                WHERE status = 'pending'
+                ORDER BY (SELECT count(*) FROM program c
+                          WHERE c.tenant_id = p.tenant_id
+                          AND c.status IN ('compiling_sql', 'compiling_rust')),
+                    status_since, tenant_id, id
+                LIMIT 1",
+                &[],
+            )
             .await?;
 
         if let Some(row) = res.get(0) {
@@ -1247,6 +1715,28 @@ impl Storage for ProjectDB {
         }
     }
 
+    async fn claim_program_compilation(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+    ) -> Result<bool, DBError> {
+        // The guard is in the WHERE clause, so that a concurrent cancellation
+        // or update cannot be overwritten.
+        let row = self
+            .pool
+            .get()
+            .await?
+            .query_opt(
+                "UPDATE program SET status = 'compiling_sql', error = NULL, status_since = now()
+                 WHERE id = $1 AND tenant_id = $2 AND version = $3 AND status = 'pending'
+                 RETURNING id",
+                &[&program_id.0, &tenant_id.0, &expected_version.0],
+            )
+            .await?;
+        Ok(row.is_some())
+    }
+
     /// Version the current pipeline object and all state reachable from it.
     ///
     /// We store the last revision number in the pipeline object itself.
@@ -1258,100 +1748,11 @@ impl Storage for ProjectDB {
     ) -> Result<Revision, DBError> {
         let mut client = self.pool.get().await?;
         let txn = client.transaction().await?;
-
-        // Find the revision number
-        let revision_data = txn
-            .query_opt(
-                "SELECT last_revision FROM pipeline WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
-                &[&pipeline_id.0, &tenant_id.0],
-            )
-            .await?;
-        let prev_revision = revision_data.and_then(|row: Row| row.get::<_, Option<Uuid>>(0));
-
-        // Check if we actually changed something before writing a new revision
-        //
-        // Note: What fields are checked is ultimately determined by whatever is
-        // used by the pipeline configuration, e.g., what the `start` function
-        // uses in `runner.rs` to write the config/metadata:
-        if prev_revision.is_some() {
-            // Take a diff between the pipeline definition and its corresponding revision
-            let change = txn.query_one(
-                "WITH ph_entry AS (
-                    SELECT progh.code, ch.config, ach.name, ach.config, ach.is_input, ph.config
-                                        FROM pipeline_history ph
-                                        INNER JOIN program_history progh ON ph.program_id = progh.id AND progh.revision = $2
-                                        LEFT OUTER JOIN attached_connector_history ach ON ach.pipeline_id = ph.id AND ach.revision = $2
-                                        LEFT OUTER JOIN connector_history ch ON ach.connector_id = ch.id AND ch.revision = $2
-                                        WHERE ph.id = $1 AND ph.revision = $2
-                ),
-                p_entry AS (
-                    SELECT prog.code, c.config, ac.name, ac.config, ac.is_input, p.config
-                                        FROM pipeline p
-                                        INNER JOIN program prog ON p.program_id = prog.id
-                                        LEFT OUTER JOIN attached_connector ac ON ac.pipeline_id = p.id
-                                        LEFT OUTER JOIN connector c ON ac.connector_id = c.id
-                                        WHERE p.id = $1
-                ),
-                diff_1 AS (
-                    SELECT * FROM ph_entry EXCEPT SELECT * FROM p_entry
-                ),
-                diff_2 AS (
-                    SELECT * FROM p_entry EXCEPT SELECT * FROM ph_entry
-                )
-                SELECT COUNT(*) FROM (SELECT * FROM diff_1 UNION ALL SELECT * FROM diff_2) as x",
-                &[&pipeline_id.0, &prev_revision],
-            )
+        let revision = self
+            .create_pipeline_revision_with(&*txn, revision, tenant_id, pipeline_id)
             .await?;
-
-            let nothing_changed = change.get::<_, i64>(0) == 0;
-            if nothing_changed {
-                return Err(DBError::RevisionNotChanged);
-            }
-        }
-
-        // TODO: Ideally these queries happen in the same transaction `txn`.
-        // Need to revise the API a bit to optionally pass in a transaction
-        // object for this to work seamlessly. Also probably the program row
-        // should be locked with FOR UPDATE while reading to prevent the
-        // compiler from resetting the status field.
-        let (_pipeline, program, _connectors) =
-            self.pipeline_is_committable(tenant_id, pipeline_id).await?;
-
-        // Copy all pipeline data to history tables
-        //
-        // TODO(performance): In theory the following inserts could all run in
-        // parallel with async but I couldn't figure out how to make it work
-        // with the args :/
-        txn.execute(
-            "INSERT INTO program_history SELECT $1 as revision, * FROM program p WHERE id = $2",
-            &[&revision, &program.program_id.0],
-        )
-        .await?;
-        txn.execute(
-            "INSERT INTO pipeline_history SELECT $1 as revision, * FROM pipeline p WHERE id = $2",
-            &[&revision, &pipeline_id.0],
-        )
-        .await?;
-        txn.execute(
-            "INSERT INTO connector_history SELECT $1 as revision, c.* FROM connector c, attached_connector ac WHERE ac.pipeline_id = $2 AND ac.connector_id = c.id",
-            &[&revision, &pipeline_id.0],
-        )
-        .await?;
-        txn.execute(
-            "INSERT INTO attached_connector_history SELECT $1 as revision, * FROM attached_connector ac WHERE ac.pipeline_id = $2",
-            &[&revision, &pipeline_id.0],
-        ).await?;
-
-        // Update the revision of the pipeline object
-        txn.execute(
-            "UPDATE pipeline SET last_revision = $1 WHERE id = $2 AND tenant_id = $3",
-            &[&revision, &pipeline_id.0, &tenant_id.0],
-        )
-        .await?;
-
         txn.commit().await?;
-
-        Ok(Revision(revision))
+        Ok(revision)
     }
 
     async fn get_last_committed_pipeline_revision(
@@ -1396,6 +1797,51 @@ impl Storage for ProjectDB {
         }
     }
 
+    async fn list_pipeline_history(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<Vec<PipelineHistoryEntry>, DBError> {
+        // History outlives the pipeline, but we only expose it for existing
+        // pipelines.
+        self.get_pipeline_descr_by_id(tenant_id, pipeline_id)
+            .await?;
+
+        let rows = self
+            .pool
+            .get()
+            .await?
+            .query(
+                "SELECT p.id, p.version, p.name, p.description, p.config, p.program_id,
+                COALESCE(json_agg(json_build_object('name', ach.name,
+                                                    'connector_id', connector_id,
+                                                    'config', ach.config,
+                                                    'is_input', is_input))
+                                FILTER (WHERE ach.name IS NOT NULL),
+                        '[]'),
+                p.revision, progh.version
+                FROM pipeline_history p
+                INNER JOIN program_history progh ON progh.id = p.program_id AND progh.revision = p.revision
+                LEFT JOIN attached_connector_history ach ON p.id = ach.pipeline_id AND ach.revision = p.revision
+                WHERE p.id = $1 AND p.tenant_id = $2
+                GROUP BY p.id, p.revision, p.version, p.name, p.description, p.config, p.program_id, progh.version, p.commit_order
+                ORDER BY p.commit_order",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+
+        let mut result = Vec::with_capacity(rows.len());
+        for row in rows {
+            result.push(PipelineHistoryEntry {
+                pipeline: self.row_to_pipeline_descr(&row).await?,
+                revision: Revision(row.get(7)),
+                program_version: Version(row.get(8)),
+            });
+        }
+
+        Ok(result)
+    }
+
     async fn list_pipelines(&self, tenant_id: TenantId) -> Result<Vec<Pipeline>, DBError> {
         let rows: Vec<Row> = self
             .pool
@@ -1410,7 +1856,8 @@ impl Storage for ProjectDB {
                                                     'is_input', is_input))
                                 FILTER (WHERE ac.name IS NOT NULL),
                         '[]'),
-                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created
+                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created,
+                rt.restart_count, rt.last_exit_reason, rt.log_location, rt.running_revision
                 FROM pipeline p
                 INNER JOIN pipeline_runtime_state rt on p.id = rt.id
                 LEFT JOIN attached_connector ac on p.id = ac.pipeline_id
@@ -1444,7 +1891,8 @@ impl Storage for ProjectDB {
                                                     'is_input', is_input))
                                 FILTER (WHERE ac.name IS NOT NULL),
                         '[]'),
-                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created
+                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created,
+                rt.restart_count, rt.last_exit_reason, rt.log_location, rt.running_revision
                 FROM pipeline p
                 INNER JOIN pipeline_runtime_state rt on p.id = rt.id
                 LEFT JOIN attached_connector ac on p.id = ac.pipeline_id
@@ -1464,29 +1912,9 @@ impl Storage for ProjectDB {
         tenant_id: TenantId,
         pipeline_id: PipelineId,
     ) -> Result<PipelineDescr, DBError> {
-        let row = self
-            .pool
-            .get()
-            .await?
-            .query_opt(
-                "SELECT p.id, version, p.name as cname, description, p.config, program_id,
-                COALESCE(json_agg(json_build_object('name', ac.name,
-                                                    'connector_id', connector_id,
-                                                    'config', ac.config,
-                                                    'is_input', is_input))
-                                FILTER (WHERE ac.name IS NOT NULL),
-                        '[]')
-                FROM pipeline p
-                LEFT JOIN attached_connector ac on p.id = ac.pipeline_id
-                WHERE p.id = $1 AND p.tenant_id = $2
-                GROUP BY p.id
-                ",
-                &[&pipeline_id.0, &tenant_id.0],
-            )
-            .await?
-            .ok_or(DBError::UnknownPipeline { pipeline_id })?;
-
-        self.row_to_pipeline_descr(&row).await
+        let client = self.pool.get().await?;
+        self.get_pipeline_descr_by_id_with(&**client, tenant_id, pipeline_id)
+            .await
     }
 
     async fn get_pipeline_runtime_state(
@@ -1499,7 +1927,8 @@ impl Storage for ProjectDB {
             .get()
             .await?
             .query_opt(
-                "SELECT location, desired_status, current_status, status_since, error, created
+                "SELECT location, desired_status, current_status, status_since, error, created,
+                    restart_count, last_exit_reason, log_location, running_revision
                 FROM pipeline_runtime_state
                 WHERE id = $1 AND tenant_id = $2",
                 &[&pipeline_id.0, &tenant_id.0],
@@ -1556,7 +1985,8 @@ impl Storage for ProjectDB {
                                                     'is_input', is_input))
                                 FILTER (WHERE ac.name IS NOT NULL),
                         '[]'),
-                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created
+                rt.location, rt.desired_status, rt.current_status, rt.status_since, rt.error, rt.created,
+                rt.restart_count, rt.last_exit_reason, rt.log_location, rt.running_revision
                 FROM pipeline p
                 INNER JOIN pipeline_runtime_state rt on p.id = rt.id
                 LEFT JOIN attached_connector ac on p.id = ac.pipeline_id
@@ -1584,36 +2014,20 @@ impl Storage for ProjectDB {
     ) -> Result<(PipelineId, Version), DBError> {
         let mut client = self.pool.get().await?;
         let txn = client.transaction().await?;
-        let config_str = RuntimeConfig::to_yaml(config);
-        txn.execute(
-            "INSERT INTO pipeline (id, program_id, version, name, description, config, tenant_id) VALUES($1, $2, 1, $3, $4, $5, $6)",
-            &[&id, &program_id.map(|id| id.0),
-            &pipline_name,
-            &pipeline_description,
-            &config_str,
-            &tenant_id.0])
-            .await
-            .map_err(ProjectDB::maybe_unique_violation)
-            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, program_id.map(|e| e.0)))
-            .map_err(|e| ProjectDB::maybe_program_id_not_found_foreign_key_constraint_err(e, program_id))?;
-
-        txn.execute(
-            "INSERT INTO pipeline_runtime_state (id, tenant_id, desired_status, current_status, status_since, created) VALUES($1, $2, 'shutdown', 'shutdown', extract(epoch from now()), extract(epoch from now()))",
-            &[&id, &tenant_id.0])
+        let res = self
+            .new_pipeline_with(
+                &*txn,
+                tenant_id,
+                id,
+                program_id,
+                pipline_name,
+                pipeline_description,
+                config,
+                connectors,
+            )
             .await?;
-
-        let pipeline_id = PipelineId(id);
-
-        if let Some(connectors) = connectors {
-            // Add the connectors.
-            for ac in connectors {
-                self.attach_connector(tenant_id, &txn, pipeline_id, ac)
-                    .await?;
-            }
-        }
         txn.commit().await?;
-
-        Ok((pipeline_id, Version(1)))
+        Ok(res)
     }
 
     // XXX: Multiple statements
@@ -1627,56 +2041,22 @@ impl Storage for ProjectDB {
         config: &Option<RuntimeConfig>,
         connectors: &Option<Vec<AttachedConnector>>,
     ) -> Result<Version, DBError> {
-        log::trace!(
-            "Updating config {} {} {} {} {:?} {:?}",
-            pipeline_id.0,
-            program_id
-                .map(|pid| pid.0.to_string())
-                .unwrap_or("<not set>".into()),
-            pipline_name,
-            pipeline_description,
-            config,
-            connectors
-        );
         let mut client = self.pool.get().await?;
         let txn = client.transaction().await?;
-
-        // First check whether the pipeline exists. Without this check, subsequent
-        // calls will fail.
-        let row = txn
-            .query_opt(
-                "SELECT id FROM pipeline WHERE id = $1 AND tenant_id = $2",
-                &[&pipeline_id.0, &tenant_id.0],
-            )
-            .await?;
-        if row.is_none() {
-            return Err(DBError::UnknownPipeline { pipeline_id });
-        }
-        if let Some(connectors) = connectors {
-            // Delete all existing attached connectors.
-            txn.execute(
-                "DELETE FROM attached_connector WHERE pipeline_id = $1 AND tenant_id = $2",
-                &[&pipeline_id.0, &tenant_id.0],
+        let version = self
+            .update_pipeline_with(
+                &*txn,
+                tenant_id,
+                pipeline_id,
+                program_id,
+                pipline_name,
+                pipeline_description,
+                config,
+                connectors,
             )
             .await?;
-
-            // Rewrite the new set of connectors.
-            for ac in connectors {
-                self.attach_connector(tenant_id, &txn, pipeline_id, ac)
-                    .await?;
-            }
-        }
-        let config = config.as_ref().map(RuntimeConfig::to_yaml);
-        let row = txn.query_opt("UPDATE pipeline SET version = version + 1, name = $1, description = $2, config = COALESCE($3, config), program_id = $4 WHERE id = $5 AND tenant_id = $6 RETURNING version",
-            &[&pipline_name, &pipeline_description, &config, &program_id.map(|id| id.0), &pipeline_id.0, &tenant_id.0])
-            .await
-            .map_err(ProjectDB::maybe_unique_violation)
-            .map_err(|e| ProjectDB::maybe_program_id_not_found_foreign_key_constraint_err(e, program_id))?;
         txn.commit().await?;
-        match row {
-            Some(row) => Ok(Version(row.get(0))),
-            None => Err(DBError::UnknownPipeline { pipeline_id }),
-        }
+        Ok(version)
     }
 
     /// Update the runtime state of the pipeline.
@@ -1702,7 +2082,11 @@ impl Storage for ProjectDB {
                     current_status = $4,
                     status_since = $5,
                     created = $6,
-                    error = $7
+                    error = $7,
+                    restart_count = $8,
+                    last_exit_reason = $9,
+                    log_location = $10,
+                    running_revision = $11
                 WHERE id = $1 AND tenant_id = $2
                 ",
                 &[
@@ -1716,6 +2100,13 @@ impl Storage for ProjectDB {
                         .error
                         .as_ref()
                         .map(|e| serde_json::to_string(&e).unwrap()),
+                    &(state.restart_count as i64),
+                    &state
+                        .last_exit_reason
+                        .as_ref()
+                        .map(|e| serde_json::to_string(&e).unwrap()),
+                    &state.log_location,
+                    &state.running_revision.map(|revision| revision.0),
                 ],
             )
             .await?;
@@ -1806,16 +2197,9 @@ impl Storage for ProjectDB {
         tenant_id: TenantId,
         pipeline_id: PipelineId,
     ) -> Result<bool, DBError> {
-        let res = self
-            .pool
-            .get()
-            .await?
-            .execute(
-                "DELETE FROM pipeline WHERE id = $1 AND tenant_id = $2",
-                &[&pipeline_id.0, &tenant_id.0],
-            )
-            .await?;
-        Ok(res > 0)
+        let client = self.pool.get().await?;
+        self.delete_pipeline_with(&**client, tenant_id, pipeline_id)
+            .await
     }
 
     async fn new_connector(
@@ -1826,18 +2210,9 @@ impl Storage for ProjectDB {
         description: &str,
         config: &ConnectorConfig,
     ) -> Result<ConnectorId, DBError> {
-        debug!("new_connector {name} {description} {config:?}");
-        self.pool
-            .get()
-            .await?
-            .execute(
-                "INSERT INTO connector (id, name, description, config, tenant_id) VALUES($1, $2, $3, $4, $5)",
-                &[&id, &name, &description, &config.to_yaml(), &tenant_id.0],
-            )
+        let client = self.pool.get().await?;
+        self.new_connector_with(&**client, tenant_id, id, name, description, config)
             .await
-            .map_err(ProjectDB::maybe_unique_violation)
-            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None))?;
-        Ok(ConnectorId(id))
     }
 
     async fn list_connectors(&self, tenant_id: TenantId) -> Result<Vec<ConnectorDescr>, DBError> {
@@ -1896,15 +2271,1021 @@ impl Storage for ProjectDB {
         }
     }
 
-    async fn get_connector_by_id(
+    async fn get_connector_by_id(
+        &self,
+        tenant_id: TenantId,
+        connector_id: ConnectorId,
+    ) -> Result<ConnectorDescr, DBError> {
+        let client = self.pool.get().await?;
+        self.get_connector_by_id_with(&**client, tenant_id, connector_id)
+            .await
+    }
+
+    async fn update_connector(
+        &self,
+        tenant_id: TenantId,
+        connector_id: ConnectorId,
+        connector_name: &str,
+        description: &str,
+        config: &Option<ConnectorConfig>,
+    ) -> Result<(), DBError> {
+        let client = self.pool.get().await?;
+        self.update_connector_with(
+            &**client,
+            tenant_id,
+            connector_id,
+            connector_name,
+            description,
+            config,
+        )
+        .await
+    }
+
+    async fn delete_connector(
+        &self,
+        tenant_id: TenantId,
+        connector_id: ConnectorId,
+    ) -> Result<(), DBError> {
+        let client = self.pool.get().await?;
+        self.delete_connector_with(&**client, tenant_id, connector_id)
+            .await
+    }
+
+    async fn list_connector_history(
+        &self,
+        tenant_id: TenantId,
+        connector_id: ConnectorId,
+    ) -> Result<Vec<ConnectorHistoryEntry>, DBError> {
+        // History outlives the connector, but we only expose it for existing
+        // connectors.
+        self.get_connector_by_id(tenant_id, connector_id).await?;
+
+        // Each revision belongs to exactly one pipeline.
+        let rows = self
+            .pool
+            .get()
+            .await?
+            .query(
+                "SELECT ch.revision, ph.id, ch.name, ch.description, ch.config
+                FROM connector_history ch
+                INNER JOIN pipeline_history ph ON ph.revision = ch.revision
+                WHERE ch.id = $1 AND ch.tenant_id = $2
+                ORDER BY ph.commit_order",
+                &[&connector_id.0, &tenant_id.0],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ConnectorHistoryEntry {
+                revision: Revision(row.get(0)),
+                pipeline_id: PipelineId(row.get(1)),
+                connector: ConnectorDescr {
+                    connector_id,
+                    name: row.get(2),
+                    description: row.get(3),
+                    config: ConnectorConfig::from_yaml_str(row.get(4)),
+                },
+            })
+            .collect())
+    }
+
+    async fn commit_project_changes(
+        &self,
+        tenant_id: TenantId,
+        changes: &[ProjectChange],
+    ) -> Result<(), DBError> {
+        let mut client = self.pool.get().await?;
+        let txn = client.transaction().await?;
+        for change in changes {
+            match change {
+                ProjectChange::NewProgram {
+                    program_id,
+                    program,
+                } => {
+                    self.new_program_with(
+                        &*txn,
+                        tenant_id,
+                        program_id.0,
+                        &program.name,
+                        &program.description,
+                        &program.code,
+                    )
+                    .await?;
+                }
+                ProjectChange::UpdateProgram {
+                    program_id,
+                    version,
+                    name,
+                    description,
+                    code,
+                } => {
+                    self.lock_program_version_with(&*txn, tenant_id, *program_id, *version)
+                        .await?;
+                    self.update_program_with(
+                        &*txn,
+                        tenant_id,
+                        *program_id,
+                        name,
+                        description,
+                        code,
+                    )
+                    .await?;
+                }
+                ProjectChange::DeleteProgram {
+                    program_id,
+                    version,
+                } => {
+                    self.lock_program_version_with(&*txn, tenant_id, *program_id, *version)
+                        .await?;
+                    self.delete_program_with(&*txn, tenant_id, *program_id)
+                        .await?;
+                }
+                ProjectChange::CompileProgram { program_id } => {
+                    let program = self
+                        .get_program_if_exists_with(&*txn, tenant_id, *program_id, false)
+                        .await?
+                        .ok_or(DBError::UnknownProgram {
+                            program_id: *program_id,
+                        })?;
+                    // Same as `prepare_program_for_compilation`.
+                    if program.status != ProgramStatus::Pending
+                        && !program.status.is_compiling()
+                        && program.status != ProgramStatus::Success
+                    {
+                        self.set_program_for_compilation_with(
+                            &*txn,
+                            tenant_id,
+                            *program_id,
+                            program.version,
+                            ProgramStatus::Pending,
+                        )
+                        .await?;
+                    }
+                }
+                ProjectChange::NewConnector {
+                    connector_id,
+                    connector,
+                } => {
+                    self.new_connector_with(
+                        &*txn,
+                        tenant_id,
+                        connector_id.0,
+                        &connector.name,
+                        &connector.description,
+                        &connector.config,
+                    )
+                    .await?;
+                }
+                ProjectChange::UpdateConnector {
+                    connector_id,
+                    connector,
+                } => {
+                    self.update_connector_with(
+                        &*txn,
+                        tenant_id,
+                        *connector_id,
+                        &connector.name,
+                        &connector.description,
+                        &Some(connector.config.clone()),
+                    )
+                    .await?;
+                }
+                ProjectChange::DeleteConnector { connector_id } => {
+                    self.delete_connector_with(&*txn, tenant_id, *connector_id)
+                        .await?;
+                }
+                ProjectChange::NewPipeline {
+                    pipeline_id,
+                    program_id,
+                    name,
+                    description,
+                    config,
+                    connectors,
+                } => {
+                    self.new_pipeline_with(
+                        &*txn,
+                        tenant_id,
+                        pipeline_id.0,
+                        *program_id,
+                        name,
+                        description,
+                        config,
+                        &Some(connectors.clone()),
+                    )
+                    .await?;
+                }
+                ProjectChange::UpdatePipeline {
+                    pipeline_id,
+                    version,
+                    program_id,
+                    name,
+                    description,
+                    config,
+                    connectors,
+                } => {
+                    self.lock_pipeline_version_with(&*txn, tenant_id, *pipeline_id, *version)
+                        .await?;
+                    self.update_pipeline_with(
+                        &*txn,
+                        tenant_id,
+                        *pipeline_id,
+                        *program_id,
+                        name,
+                        description,
+                        &Some(config.clone()),
+                        &Some(connectors.clone()),
+                    )
+                    .await?;
+                }
+                ProjectChange::DeletePipeline {
+                    pipeline_id,
+                    version,
+                } => {
+                    self.lock_pipeline_version_with(&*txn, tenant_id, *pipeline_id, *version)
+                        .await?;
+                    self.check_pipeline_shutdown_with(&*txn, tenant_id, *pipeline_id)
+                        .await?;
+                    self.delete_pipeline_with(&*txn, tenant_id, *pipeline_id)
+                        .await?;
+                }
+                ProjectChange::CommitPipelineRevision { pipeline_id } => {
+                    match self
+                        .create_pipeline_revision_with(
+                            &*txn,
+                            Uuid::now_v7(),
+                            tenant_id,
+                            *pipeline_id,
+                        )
+                        .await
+                    {
+                        Ok(_) | Err(DBError::RevisionNotChanged) => {}
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn store_api_key_hash(
+        &self,
+        tenant_id: TenantId,
+        id: Uuid,
+        name: &str,
+        key: String,
+        scopes: Vec<ApiPermission>,
+    ) -> Result<ApiKeyId, DBError> {
+        let mut hasher = sha::Sha256::new();
+        hasher.update(key.as_bytes());
+        let hash = openssl::base64::encode_block(&hasher.finish());
+        let created = Utc::now().timestamp();
+        let res = self
+            .pool
+            .get()
+            .await?
+            .execute(
+                "INSERT INTO api_key (hash, tenant_id, scopes, id, name, created) VALUES ($1, $2, $3, $4, $5, $6)",
+                &[
+                    &hash,
+                    &tenant_id.0,
+                    &scopes
+                        .iter()
+                        .map(ApiPermission::to_string)
+                        .collect::<Vec<String>>(),
+                    &id,
+                    &name,
+                    &created,
+                ],
+            )
+            .await
+            .map_err(Self::maybe_unique_violation)
+            .map_err(|e| {
+                ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None)
+            })?;
+        if res > 0 {
+            Ok(ApiKeyId(id))
+        } else {
+            Err(DBError::duplicate_key())
+        }
+    }
+
+    async fn validate_api_key(
+        &self,
+        api_key: String,
+    ) -> Result<(TenantId, Vec<ApiPermission>), DBError> {
+        let mut hasher = sha::Sha256::new();
+        hasher.update(api_key.as_bytes());
+        let hash = openssl::base64::encode_block(&hasher.finish());
+        let client = self.pool.get().await?;
+        let res = client
+            .query_one(
+                "SELECT tenant_id, scopes, last_used FROM api_key WHERE hash = $1",
+                &[&hash],
+            )
+            .await
+            .map_err(|_| DBError::InvalidKey)?;
+        let tenant_id = TenantId(res.get(0));
+        let scopes = Self::parse_api_key_scopes(res.get(1))?;
+
+        let now = Utc::now().timestamp();
+        let last_used: Option<i64> = res.get(2);
+        if last_used.map_or(true, |t| now - t >= API_KEY_LAST_USED_RESOLUTION) {
+            // Concurrent requests with the same key may race to get here; only
+            // the first one writes.
+            client
+                .execute(
+                    "UPDATE api_key SET last_used = $2 WHERE hash = $1 AND (last_used IS NULL OR last_used <= $3)",
+                    &[&hash, &now, &(now - API_KEY_LAST_USED_RESOLUTION)],
+                )
+                .await?;
+        }
+        Ok((tenant_id, scopes))
+    }
+
+    async fn list_api_keys(&self, tenant_id: TenantId) -> Result<Vec<ApiKeyDescr>, DBError> {
+        let rows = self
+            .pool
+            .get()
+            .await?
+            .query(
+                "SELECT id, name, scopes, created, last_used FROM api_key WHERE tenant_id = $1 ORDER BY id",
+                &[&tenant_id.0],
+            )
+            .await?;
+
+        let mut result = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            result.push(ApiKeyDescr {
+                api_key_id: ApiKeyId(row.get(0)),
+                name: row.get(1),
+                scopes: Self::parse_api_key_scopes(row.get(2))?,
+                created: convert_bigint_to_time(row.get(3))?,
+                last_used: row
+                    .get::<_, Option<i64>>(4)
+                    .map(convert_bigint_to_time)
+                    .transpose()?,
+            });
+        }
+
+        Ok(result)
+    }
+
+    async fn delete_api_key(
+        &self,
+        tenant_id: TenantId,
+        api_key_id: ApiKeyId,
+    ) -> Result<(), DBError> {
+        let res = self
+            .pool
+            .get()
+            .await?
+            .execute(
+                "DELETE FROM api_key WHERE id = $1 AND tenant_id = $2",
+                &[&api_key_id.0, &tenant_id.0],
+            )
+            .await?;
+
+        if res > 0 {
+            Ok(())
+        } else {
+            Err(DBError::UnknownApiKey { api_key_id })
+        }
+    }
+
+    async fn get_or_create_tenant_id(
+        &self,
+        tenant_name: String,
+        provider: String,
+    ) -> Result<TenantId, DBError> {
+        let conn = self.pool.get().await?;
+        let res = conn
+            .query_opt(
+                "SELECT id FROM tenant WHERE tenant = $1 AND provider = $2",
+                &[&tenant_name, &provider],
+            )
+            .await?;
+        match res {
+            Some(row) => Ok(TenantId(row.get(0))),
+            None => {
+                self.create_tenant_if_not_exists(Uuid::now_v7(), tenant_name, provider)
+                    .await
+            }
+        }
+    }
+
+    async fn create_tenant_if_not_exists(
+        &self,
+        tenant_id: Uuid,
+        tenant_name: String,
+        provider: String,
+    ) -> Result<TenantId, DBError> {
+        let conn = self.pool.get().await?;
+        // Unfortunately, doing a read-if-exists-else-insert is not very ergonomic. To
+        // do so in a single query requires us to do a redundant UPDATE on conflict,
+        // where we set the tenant and provider values to their existing values
+        // (excluded.{tenant, provider})
+        let res = conn
+                    .query_one(
+                        "INSERT INTO tenant (id, tenant, provider) VALUES ($1, $2, $3) ON CONFLICT (tenant, provider) DO UPDATE SET tenant = excluded.tenant, provider = excluded.provider RETURNING id",
+                        &[&tenant_id, &tenant_name, &provider],
+                    )
+                    .await?;
+        Ok(TenantId(res.get(0)))
+    }
+
+    async fn create_compiled_binary_ref(
+        &self,
+        program_id: ProgramId,
+        version: Version,
+        url: String,
+    ) -> Result<(), DBError> {
+        let conn = self.pool.get().await?;
+        let _res = conn
+            .execute(
+                "INSERT INTO compiled_binary VALUES ($1, $2, $3)",
+                &[&program_id.0, &version.0, &url],
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn get_compiled_binary_ref(
+        &self,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<Option<String>, DBError> {
+        let conn = self.pool.get().await?;
+        let res = conn
+            .query_opt(
+                "SELECT url FROM compiled_binary WHERE program_id = $1 AND version = $2",
+                &[&program_id.0, &version.0],
+            )
+            .await?;
+        Ok(res.map(|e| e.get(0)))
+    }
+
+    async fn delete_compiled_binary_ref(
+        &self,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<(), DBError> {
+        let conn = self.pool.get().await?;
+        let _res = conn
+            .execute(
+                "DELETE FROM compiled_binary WHERE program_id = $1 AND version = $2",
+                &[&program_id.0, &version.0],
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl ProjectDB {
+    // The following methods implement `Storage` methods on a connection or a
+    // transaction `client`, so that several of them can run in the same
+    // transaction (see `commit_project_changes`).
+
+    async fn new_program_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        id: Uuid,
+        program_name: &str,
+        program_description: &str,
+        program_code: &str,
+    ) -> Result<(ProgramId, Version), DBError> {
+        debug!("new_program {program_name} {program_description} {program_code}");
+        client.execute(
+                    "INSERT INTO program (id, version, tenant_id, name, description, code, schema, status, error, status_since)
+                        VALUES($1, 1, $2, $3, $4, $5, NULL, NULL, NULL, now());",
+                &[&id, &tenant_id.0, &program_name, &program_description, &program_code]
+            )
+            .await
+            .map_err(ProjectDB::maybe_unique_violation)
+            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None))?;
+
+        Ok((ProgramId(id), Version(1)))
+    }
+
+    async fn update_program_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        program_name: &str,
+        program_description: &str,
+        program_code: &Option<String>,
+    ) -> Result<Version, DBError> {
+        let row = match program_code {
+            Some(code) => {
+                // Only increment `version` if new code actually differs from the
+                // current version.
+                client
+                    .query_opt(
+                        "UPDATE program
+                            SET
+                                version = (CASE WHEN code = $3 THEN version ELSE version + 1 END),
+                                name = $1,
+                                description = $2,
+                                code = $3,
+                                status = (CASE WHEN code = $3 THEN status ELSE NULL END),
+                                error = (CASE WHEN code = $3 THEN error ELSE NULL END),
+                                schema = (CASE WHEN code = $3 THEN schema ELSE NULL END)
+                        WHERE id = $4 AND tenant_id = $5
+                        RETURNING version
+                    ",
+                        &[
+                            &program_name,
+                            &program_description,
+                            &code,
+                            &program_id.0,
+                            &tenant_id.0
+                        ],
+                    )
+                    .await
+                    .map_err(ProjectDB::maybe_unique_violation)?
+            }
+            _ => {
+                client
+                    .query_opt(
+                        "UPDATE program SET name = $1, description = $2 WHERE id = $3 AND tenant_id = $4 RETURNING version",
+                        &[&program_name, &program_description, &program_id.0, &tenant_id.0],
+                    )
+                    .await
+                    .map_err(ProjectDB::maybe_unique_violation)?
+            }
+        };
+
+        if let Some(row) = row {
+            Ok(Version(row.get(0)))
+        } else {
+            Err(DBError::UnknownProgram { program_id })
+        }
+    }
+
+    async fn get_program_if_exists_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        with_code: bool,
+    ) -> Result<Option<ProgramDescr>, DBError> {
+        let row = client
+            .query_opt(
+                "SELECT name, description, version, status, error, schema,
+                CASE WHEN $3 IS TRUE THEN code ELSE null END
+                FROM program WHERE id = $1 AND tenant_id = $2",
+                &[&program_id.0, &tenant_id.0, &with_code],
+            )
+            .await?;
+
+        if let Some(row) = row {
+            let name: String = row.get(0);
+            let description: String = row.get(1);
+            let version: Version = Version(row.get(2));
+            let status: Option<String> = row.get(3);
+            let error: Option<String> = row.get(4);
+            let schema: Option<ProgramSchema> = row
+                .get::<_, Option<String>>(5)
+                .map(|s| serde_json::from_str(&s))
+                .transpose()
+                .map_err(|e| DBError::invalid_data(format!("Error parsing program schema: {e}")))?;
+            let code: Option<String> = row.get(6);
+
+            let status = ProgramStatus::from_columns(status.as_deref(), error)?;
+            Ok(Some(ProgramDescr {
+                program_id,
+                name,
+                description,
+                version,
+                status,
+                schema,
+                code,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn set_program_for_compilation_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+        status: ProgramStatus,
+    ) -> Result<(), DBError> {
+        let (status, error) = status.to_columns();
+        client
+            .execute(
+                "UPDATE program SET
+                 status = (CASE WHEN version = $4 THEN $1 ELSE status END),
+                 error = (CASE WHEN version = $4 THEN $2 ELSE error END),
+                 status_since = (CASE WHEN version = $4 THEN now()
+                                 ELSE status_since END),
+                 schema = (CASE WHEN version = $4 THEN NULL ELSE schema END)
+                 WHERE id = $3 AND tenant_id = $5",
+                &[
+                    &status,
+                    &error,
+                    &program_id.0,
+                    &expected_version.0,
+                    &tenant_id.0,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn delete_program_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+    ) -> Result<(), DBError> {
+        let res = client
+            .execute(
+                "DELETE FROM program WHERE id = $1 AND tenant_id = $2",
+                &[&program_id.0, &tenant_id.0],
+            )
+            .await
+            .map_err(|e| {
+                ProjectDB::maybe_program_id_in_use_foreign_key_constraint_err(
+                    e.into(),
+                    Some(program_id),
+                )
+            })?;
+        if res > 0 {
+            Ok(())
+        } else {
+            Err(DBError::UnknownProgram { program_id })
+        }
+    }
+
+    /// Version the current pipeline object and all state reachable from it,
+    /// in the transaction `client`.
+    async fn create_pipeline_revision_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        revision: Uuid,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<Revision, DBError> {
+        // Find the revision number
+        let revision_data = client
+            .query_opt(
+                "SELECT last_revision FROM pipeline WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+        let prev_revision = revision_data.and_then(|row: Row| row.get::<_, Option<Uuid>>(0));
+
+        // Check if we actually changed something before writing a new revision
+        //
+        // Note: What fields are checked is ultimately determined by whatever is
+        // used by the pipeline configuration, e.g., what the `start` function
+        // uses in `runner.rs` to write the config/metadata:
+        if prev_revision.is_some() {
+            // Take a diff between the pipeline definition and its corresponding revision
+            let change = client.query_one(
+                "WITH ph_entry AS (
+                    SELECT progh.code, ch.config, ach.name, ach.config, ach.is_input, ph.config
+                                        FROM pipeline_history ph
+                                        INNER JOIN program_history progh ON ph.program_id = progh.id AND progh.revision = $2
+                                        LEFT OUTER JOIN attached_connector_history ach ON ach.pipeline_id = ph.id AND ach.revision = $2
+                                        LEFT OUTER JOIN connector_history ch ON ach.connector_id = ch.id AND ch.revision = $2
+                                        WHERE ph.id = $1 AND ph.revision = $2
+                ),
+                p_entry AS (
+                    SELECT prog.code, c.config, ac.name, ac.config, ac.is_input, p.config
+                                        FROM pipeline p
+                                        INNER JOIN program prog ON p.program_id = prog.id
+                                        LEFT OUTER JOIN attached_connector ac ON ac.pipeline_id = p.id
+                                        LEFT OUTER JOIN connector c ON ac.connector_id = c.id
+                                        WHERE p.id = $1
+                ),
+                diff_1 AS (
+                    SELECT * FROM ph_entry EXCEPT SELECT * FROM p_entry
+                ),
+                diff_2 AS (
+                    SELECT * FROM p_entry EXCEPT SELECT * FROM ph_entry
+                )
+                SELECT COUNT(*) FROM (SELECT * FROM diff_1 UNION ALL SELECT * FROM diff_2) as x",
+                &[&pipeline_id.0, &prev_revision],
+            )
+            .await?;
+
+            let nothing_changed = change.get::<_, i64>(0) == 0;
+            if nothing_changed {
+                return Err(DBError::RevisionNotChanged);
+            }
+        }
+
+        // TODO: Probably the program row should be locked with FOR UPDATE
+        // while reading to prevent the compiler from resetting the status
+        // field.
+        let (_pipeline, program, _connectors) = self
+            .pipeline_is_committable_with(client, tenant_id, pipeline_id)
+            .await?;
+
+        // Copy all pipeline data to history tables
+        //
+        // TODO(performance): In theory the following inserts could all run in
+        // parallel with async but I couldn't figure out how to make it work
+        // with the args :/
+        client
+            .execute(
+                "INSERT INTO program_history SELECT $1 as revision, * FROM program p WHERE id = $2",
+                &[&revision, &program.program_id.0],
+            )
+            .await?;
+        client.execute(
+            "INSERT INTO pipeline_history SELECT $1 as revision, * FROM pipeline p WHERE id = $2",
+            &[&revision, &pipeline_id.0],
+        )
+        .await?;
+        client.execute(
+            "INSERT INTO connector_history SELECT $1 as revision, c.* FROM connector c, attached_connector ac WHERE ac.pipeline_id = $2 AND ac.connector_id = c.id",
+            &[&revision, &pipeline_id.0],
+        )
+        .await?;
+        client.execute(
+            "INSERT INTO attached_connector_history SELECT $1 as revision, * FROM attached_connector ac WHERE ac.pipeline_id = $2",
+            &[&revision, &pipeline_id.0],
+        ).await?;
+
+        // Update the revision of the pipeline object
+        client
+            .execute(
+                "UPDATE pipeline SET last_revision = $1 WHERE id = $2 AND tenant_id = $3",
+                &[&revision, &pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+
+        Ok(Revision(revision))
+    }
+
+    async fn get_pipeline_descr_by_id_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<PipelineDescr, DBError> {
+        let row = client
+            .query_opt(
+                "SELECT p.id, version, p.name as cname, description, p.config, program_id,
+                COALESCE(json_agg(json_build_object('name', ac.name,
+                                                    'connector_id', connector_id,
+                                                    'config', ac.config,
+                                                    'is_input', is_input))
+                                FILTER (WHERE ac.name IS NOT NULL),
+                        '[]')
+                FROM pipeline p
+                LEFT JOIN attached_connector ac on p.id = ac.pipeline_id
+                WHERE p.id = $1 AND p.tenant_id = $2
+                GROUP BY p.id
+                ",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownPipeline { pipeline_id })?;
+
+        self.row_to_pipeline_descr(&row).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn new_pipeline_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        id: Uuid,
+        program_id: Option<ProgramId>,
+        pipline_name: &str,
+        pipeline_description: &str,
+        config: &RuntimeConfig,
+        connectors: &Option<Vec<AttachedConnector>>,
+    ) -> Result<(PipelineId, Version), DBError> {
+        let config_str = RuntimeConfig::to_yaml(config);
+        client.execute(
+            "INSERT INTO pipeline (id, program_id, version, name, description, config, tenant_id) VALUES($1, $2, 1, $3, $4, $5, $6)",
+            &[&id, &program_id.map(|id| id.0),
+            &pipline_name,
+            &pipeline_description,
+            &config_str,
+            &tenant_id.0])
+            .await
+            .map_err(ProjectDB::maybe_unique_violation)
+            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, program_id.map(|e| e.0)))
+            .map_err(|e| ProjectDB::maybe_program_id_not_found_foreign_key_constraint_err(e, program_id))?;
+
+        client.execute(
+            "INSERT INTO pipeline_runtime_state (id, tenant_id, desired_status, current_status, status_since, created) VALUES($1, $2, 'shutdown', 'shutdown', extract(epoch from now()), extract(epoch from now()))",
+            &[&id, &tenant_id.0])
+            .await?;
+
+        let pipeline_id = PipelineId(id);
+
+        if let Some(connectors) = connectors {
+            // Add the connectors.
+            for ac in connectors {
+                self.attach_connector(tenant_id, client, pipeline_id, ac)
+                    .await?;
+            }
+        }
+
+        Ok((pipeline_id, Version(1)))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn update_pipeline_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+        program_id: Option<ProgramId>,
+        pipline_name: &str,
+        pipeline_description: &str,
+        config: &Option<RuntimeConfig>,
+        connectors: &Option<Vec<AttachedConnector>>,
+    ) -> Result<Version, DBError> {
+        log::trace!(
+            "Updating config {} {} {} {} {:?} {:?}",
+            pipeline_id.0,
+            program_id
+                .map(|pid| pid.0.to_string())
+                .unwrap_or("<not set>".into()),
+            pipline_name,
+            pipeline_description,
+            config,
+            connectors
+        );
+        // First check whether the pipeline exists. Without this check, subsequent
+        // calls will fail.
+        let row = client
+            .query_opt(
+                "SELECT id FROM pipeline WHERE id = $1 AND tenant_id = $2",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+        if row.is_none() {
+            return Err(DBError::UnknownPipeline { pipeline_id });
+        }
+        if let Some(connectors) = connectors {
+            // Delete all existing attached connectors.
+            client
+                .execute(
+                    "DELETE FROM attached_connector WHERE pipeline_id = $1 AND tenant_id = $2",
+                    &[&pipeline_id.0, &tenant_id.0],
+                )
+                .await?;
+
+            // Rewrite the new set of connectors.
+            for ac in connectors {
+                self.attach_connector(tenant_id, client, pipeline_id, ac)
+                    .await?;
+            }
+        }
+        let config = config.as_ref().map(RuntimeConfig::to_yaml);
+        let row = client.query_opt("UPDATE pipeline SET version = version + 1, name = $1, description = $2, config = COALESCE($3, config), program_id = $4 WHERE id = $5 AND tenant_id = $6 RETURNING version",
+            &[&pipline_name, &pipeline_description, &config, &program_id.map(|id| id.0), &pipeline_id.0, &tenant_id.0])
+            .await
+            .map_err(ProjectDB::maybe_unique_violation)
+            .map_err(|e| ProjectDB::maybe_program_id_not_found_foreign_key_constraint_err(e, program_id))?;
+        match row {
+            Some(row) => Ok(Version(row.get(0))),
+            None => Err(DBError::UnknownPipeline { pipeline_id }),
+        }
+    }
+
+    /// Lock the row of a program until the end of the transaction `client`,
+    /// and check that the program is still at `expected_version`.
+    async fn lock_program_version_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+    ) -> Result<(), DBError> {
+        let row = client
+            .query_opt(
+                "SELECT version FROM program WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+                &[&program_id.0, &tenant_id.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownProgram { program_id })?;
+        let version = Version(row.get(0));
+        if version != expected_version {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "program {program_id} was modified concurrently (version {version}, expected {expected_version})"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Lock the row of a pipeline until the end of the transaction `client`,
+    /// and check that the pipeline is still at `expected_version`.
+    async fn lock_pipeline_version_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+        expected_version: Version,
+    ) -> Result<(), DBError> {
+        let row = client
+            .query_opt(
+                "SELECT version FROM pipeline WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownPipeline { pipeline_id })?;
+        let version = Version(row.get(0));
+        if version != expected_version {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "pipeline {pipeline_id} was modified concurrently (version {version}, expected {expected_version})"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that a pipeline is shut down and not being started, locking its
+    /// runtime state until the end of the transaction `client`.
+    async fn check_pipeline_shutdown_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<(), DBError> {
+        let row = client
+            .query_opt(
+                "SELECT desired_status, current_status FROM pipeline_runtime_state
+                WHERE id = $1 AND tenant_id = $2 FOR UPDATE",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?
+            .ok_or(DBError::UnknownPipeline { pipeline_id })?;
+        let desired_status: PipelineStatus = row.get::<_, String>(0).try_into()?;
+        let current_status: PipelineStatus = row.get::<_, String>(1).try_into()?;
+        if desired_status != PipelineStatus::Shutdown || current_status != PipelineStatus::Shutdown
+        {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "pipeline {pipeline_id} must be shut down before it can be deleted"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    async fn delete_pipeline_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<bool, DBError> {
+        let res = client
+            .execute(
+                "DELETE FROM pipeline WHERE id = $1 AND tenant_id = $2",
+                &[&pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+        Ok(res > 0)
+    }
+
+    async fn new_connector_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        id: Uuid,
+        name: &str,
+        description: &str,
+        config: &ConnectorConfig,
+    ) -> Result<ConnectorId, DBError> {
+        debug!("new_connector {name} {description} {config:?}");
+        client.execute(
+                "INSERT INTO connector (id, name, description, config, tenant_id) VALUES($1, $2, $3, $4, $5)",
+                &[&id, &name, &description, &config.to_yaml(), &tenant_id.0],
+            )
+            .await
+            .map_err(ProjectDB::maybe_unique_violation)
+            .map_err(|e| ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None))?;
+        Ok(ConnectorId(id))
+    }
+
+    async fn get_connector_by_id_with<C: GenericClient + Sync>(
         &self,
+        client: &C,
         tenant_id: TenantId,
         connector_id: ConnectorId,
     ) -> Result<ConnectorDescr, DBError> {
-        let row = self
-            .pool
-            .get()
-            .await?
+        let row = client
             .query_opt(
                 "SELECT name, description, config FROM connector WHERE id = $1 AND tenant_id = $2",
                 &[&connector_id.0, &tenant_id.0],
@@ -1928,20 +3309,21 @@ impl Storage for ProjectDB {
         }
     }
 
-    async fn update_connector(
+    async fn update_connector_with<C: GenericClient + Sync>(
         &self,
+        client: &C,
         tenant_id: TenantId,
         connector_id: ConnectorId,
         connector_name: &str,
         description: &str,
         config: &Option<ConnectorConfig>,
     ) -> Result<(), DBError> {
-        let descr = self.get_connector_by_id(tenant_id, connector_id).await?;
+        let descr = self
+            .get_connector_by_id_with(client, tenant_id, connector_id)
+            .await?;
         let config = config.clone().unwrap_or(descr.config);
 
-        self.pool
-            .get()
-            .await?
+        client
             .execute(
                 "UPDATE connector SET name = $1, description = $2, config = $3 WHERE id = $4",
                 &[
@@ -1957,15 +3339,13 @@ impl Storage for ProjectDB {
         Ok(())
     }
 
-    async fn delete_connector(
+    async fn delete_connector_with<C: GenericClient + Sync>(
         &self,
+        client: &C,
         tenant_id: TenantId,
         connector_id: ConnectorId,
     ) -> Result<(), DBError> {
-        let res = self
-            .pool
-            .get()
-            .await?
+        let res = client
             .execute(
                 "DELETE FROM connector WHERE id = $1 AND tenant_id = $2",
                 &[&connector_id.0, &tenant_id.0],
@@ -1979,166 +3359,6 @@ impl Storage for ProjectDB {
         }
     }
 
-    async fn store_api_key_hash(
-        &self,
-        tenant_id: TenantId,
-        key: String,
-        scopes: Vec<ApiPermission>,
-    ) -> Result<(), DBError> {
-        let mut hasher = sha::Sha256::new();
-        hasher.update(key.as_bytes());
-        let hash = openssl::base64::encode_block(&hasher.finish());
-        let res = self
-            .pool
-            .get()
-            .await?
-            .execute(
-                "INSERT INTO api_key (hash, tenant_id, scopes) VALUES ($1, $2, $3)",
-                &[
-                    &hash,
-                    &tenant_id.0,
-                    &scopes
-                        .iter()
-                        .map(|scope| match scope {
-                            ApiPermission::Read => "read",
-                            ApiPermission::Write => "write",
-                        })
-                        .collect::<Vec<&str>>(),
-                ],
-            )
-            .await
-            .map_err(Self::maybe_unique_violation)
-            .map_err(|e| {
-                ProjectDB::maybe_tenant_id_foreign_key_constraint_err(e, tenant_id, None)
-            })?;
-        if res > 0 {
-            Ok(())
-        } else {
-            Err(DBError::duplicate_key())
-        }
-    }
-
-    async fn validate_api_key(
-        &self,
-        api_key: String,
-    ) -> Result<(TenantId, Vec<ApiPermission>), DBError> {
-        let mut hasher = sha::Sha256::new();
-        hasher.update(api_key.as_bytes());
-        let hash = openssl::base64::encode_block(&hasher.finish());
-        let res = self
-            .pool
-            .get()
-            .await?
-            .query_one(
-                "SELECT tenant_id, scopes FROM api_key WHERE hash = $1",
-                &[&hash],
-            )
-            .await
-            .map_err(|_| DBError::InvalidKey)?;
-        let tenant_id = TenantId(res.get(0));
-        let vec: Vec<String> = res.get(1);
-        let vec = vec
-            .iter()
-            .map(|s| {
-                if s == "read" {
-                    ApiPermission::Read
-                } else {
-                    ApiPermission::Write
-                }
-            })
-            .collect();
-        Ok((tenant_id, vec))
-    }
-
-    async fn get_or_create_tenant_id(
-        &self,
-        tenant_name: String,
-        provider: String,
-    ) -> Result<TenantId, DBError> {
-        let conn = self.pool.get().await?;
-        let res = conn
-            .query_opt(
-                "SELECT id FROM tenant WHERE tenant = $1 AND provider = $2",
-                &[&tenant_name, &provider],
-            )
-            .await?;
-        match res {
-            Some(row) => Ok(TenantId(row.get(0))),
-            None => {
-                self.create_tenant_if_not_exists(Uuid::now_v7(), tenant_name, provider)
-                    .await
-            }
-        }
-    }
-
-    async fn create_tenant_if_not_exists(
-        &self,
-        tenant_id: Uuid,
-        tenant_name: String,
-        provider: String,
-    ) -> Result<TenantId, DBError> {
-        let conn = self.pool.get().await?;
-        // Unfortunately, doing a read-if-exists-else-insert is not very ergonomic. To
-        // do so in a single query requires us to do a redundant UPDATE on conflict,
-        // where we set the tenant and provider values to their existing values
-        // (excluded.{tenant, provider})
-        let res = conn
-                    .query_one(
-                        "INSERT INTO tenant (id, tenant, provider) VALUES ($1, $2, $3) ON CONFLICT (tenant, provider) DO UPDATE SET tenant = excluded.tenant, provider = excluded.provider RETURNING id",
-                        &[&tenant_id, &tenant_name, &provider],
-                    )
-                    .await?;
-        Ok(TenantId(res.get(0)))
-    }
-
-    async fn create_compiled_binary_ref(
-        &self,
-        program_id: ProgramId,
-        version: Version,
-        url: String,
-    ) -> Result<(), DBError> {
-        let conn = self.pool.get().await?;
-        let _res = conn
-            .execute(
-                "INSERT INTO compiled_binary VALUES ($1, $2, $3)",
-                &[&program_id.0, &version.0, &url],
-            )
-            .await?;
-        Ok(())
-    }
-
-    async fn get_compiled_binary_ref(
-        &self,
-        program_id: ProgramId,
-        version: Version,
-    ) -> Result<Option<String>, DBError> {
-        let conn = self.pool.get().await?;
-        let res = conn
-            .query_opt(
-                "SELECT url FROM compiled_binary WHERE program_id = $1 AND version = $2",
-                &[&program_id.0, &version.0],
-            )
-            .await?;
-        Ok(res.map(|e| e.get(0)))
-    }
-
-    async fn delete_compiled_binary_ref(
-        &self,
-        program_id: ProgramId,
-        version: Version,
-    ) -> Result<(), DBError> {
-        let conn = self.pool.get().await?;
-        let _res = conn
-            .execute(
-                "DELETE FROM compiled_binary WHERE program_id = $1 AND version = $2",
-                &[&program_id.0, &version.0],
-            )
-            .await?;
-        Ok(())
-    }
-}
-
-impl ProjectDB {
     pub async fn connect(
         db_config: &DatabaseConfig,
         #[cfg(feature = "pg-embed")] api_config: Option<&ApiServerConfig>,
@@ -2308,6 +3528,13 @@ impl ProjectDB {
                     .map(|s| Self::deserialize_error_response(pipeline_id, &s))
                     .transpose()?,
                 created: convert_bigint_to_time(row.get(5))?,
+                restart_count: row.get::<_, i64>(6) as u32,
+                last_exit_reason: row
+                    .get::<_, Option<String>>(7)
+                    .map(|s| Self::deserialize_error_response(pipeline_id, &s))
+                    .transpose()?,
+                log_location: row.get::<_, Option<String>>(8).unwrap_or_default(),
+                running_revision: row.get::<_, Option<Uuid>>(9).map(Revision),
             })
         } else {
             Err(DBError::UnknownPipeline { pipeline_id })
@@ -2337,6 +3564,13 @@ impl ProjectDB {
                 .map(|s| Self::deserialize_error_response(pipeline_id, &s))
                 .transpose()?,
             created: convert_bigint_to_time(row.get(12))?,
+            restart_count: row.get::<_, i64>(13) as u32,
+            last_exit_reason: row
+                .get::<_, Option<String>>(14)
+                .map(|s| Self::deserialize_error_response(pipeline_id, &s))
+                .transpose()?,
+            log_location: row.get::<_, Option<String>>(15).unwrap_or_default(),
+            running_revision: row.get::<_, Option<Uuid>>(16).map(Revision),
         };
 
         Ok(Pipeline { descriptor, state })
@@ -2375,18 +3609,94 @@ impl ProjectDB {
         Ok(row.get(0))
     }
 
+    /// Find an earlier version of a program with the same code as `version`
+    /// that still has a compiled binary, e.g., after a rollback.
+    ///
+    /// Returns the most recent such version along with its schema.
+    pub(crate) async fn find_compiled_program_version(
+        &self,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<Option<(Version, ProgramSchema)>, DBError> {
+        let row = self
+            .pool
+            .get()
+            .await?
+            .query_opt(
+                "SELECT pv.version, pv.schema
+                FROM program_version pv
+                INNER JOIN program_version cur ON cur.program_id = pv.program_id AND cur.code = pv.code
+                INNER JOIN compiled_binary cb ON cb.program_id = pv.program_id AND cb.version = pv.version
+                WHERE pv.program_id = $1 AND cur.version = $2 AND pv.version < $2 AND pv.schema IS NOT NULL
+                ORDER BY pv.version DESC
+                LIMIT 1",
+                &[&program_id.0, &version.0],
+            )
+            .await?;
+
+        row.map(|row| {
+            let schema = serde_json::from_str(row.get(1))
+                .map_err(|e| DBError::invalid_data(format!("Error parsing program schema: {e}")))?;
+            Ok((Version(row.get(0)), schema))
+        })
+        .transpose()
+    }
+
+    /// Make an earlier committed `revision` the last revision of a pipeline,
+    /// e.g., after the pipeline failed to upgrade to a newer revision.
+    ///
+    /// The newer revisions remain in the history of the pipeline.
+    pub(crate) async fn restore_pipeline_revision(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+        revision: Revision,
+    ) -> Result<(), DBError> {
+        let res = self
+            .pool
+            .get()
+            .await?
+            .execute(
+                "UPDATE pipeline SET last_revision = $1
+                WHERE id = $2 AND tenant_id = $3
+                AND EXISTS (SELECT 1 FROM pipeline_history WHERE id = $2 AND revision = $1)",
+                &[&revision.0, &pipeline_id.0, &tenant_id.0],
+            )
+            .await?;
+
+        if res > 0 {
+            Ok(())
+        } else {
+            Err(DBError::UnknownPipeline { pipeline_id })
+        }
+    }
+
     pub(crate) async fn pipeline_is_committable(
         &self,
         tenant_id: TenantId,
         pipeline_id: PipelineId,
+    ) -> Result<(PipelineDescr, ProgramDescr, Vec<ConnectorDescr>), DBError> {
+        let client = self.pool.get().await?;
+        self.pipeline_is_committable_with(&**client, tenant_id, pipeline_id)
+            .await
+    }
+
+    async fn pipeline_is_committable_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
     ) -> Result<(PipelineDescr, ProgramDescr, Vec<ConnectorDescr>), DBError> {
         let pipeline = self
-            .get_pipeline_descr_by_id(tenant_id, pipeline_id)
+            .get_pipeline_descr_by_id_with(client, tenant_id, pipeline_id)
             .await?;
         let program_id = pipeline.program_id.ok_or(DBError::ProgramNotSet)?;
-        let program = self.get_program_by_id(tenant_id, program_id, true).await?;
+        let program = self
+            .get_program_if_exists_with(client, tenant_id, program_id, true)
+            .await?
+            .ok_or(DBError::UnknownProgram { program_id })?;
         let connectors = self
-            .get_connectors_for_pipeline_id(tenant_id, pipeline_id)
+            .get_connectors_for_pipeline_id_with(client, tenant_id, pipeline_id)
             .await?;
         // Check that this configuration forms a valid snapshot
         PipelineRevision::validate(&pipeline, &connectors, &program)?;
@@ -2525,10 +3835,18 @@ impl ProjectDB {
         tenant_id: TenantId,
         pipeline_id: PipelineId,
     ) -> Result<Vec<ConnectorDescr>, DBError> {
-        let rows = self
-            .pool
-            .get()
-            .await?
+        let client = self.pool.get().await?;
+        self.get_connectors_for_pipeline_id_with(&**client, tenant_id, pipeline_id)
+            .await
+    }
+
+    async fn get_connectors_for_pipeline_id_with<C: GenericClient + Sync>(
+        &self,
+        client: &C,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<Vec<ConnectorDescr>, DBError> {
+        let rows = client
             .query(
                 "SELECT c.id, c.name, c.description, c.config
             FROM connector c, attached_connector ac
@@ -2561,14 +3879,14 @@ impl ProjectDB {
     ///
     /// # Precondition
     /// - A valid pipeline for `pipeline_id` must exist.
-    async fn attach_connector(
+    async fn attach_connector<C: GenericClient + Sync>(
         &self,
         tenant_id: TenantId,
-        txn: &Transaction<'_>,
+        client: &C,
         pipeline_id: PipelineId,
         ac: &AttachedConnector,
     ) -> Result<(), DBError> {
-        let rows = txn
+        let rows = client
             .execute(
                 "INSERT INTO attached_connector (name, pipeline_id, connector_id, is_input, config, tenant_id)
                  SELECT $2, $3, id, $5, $6, tenant_id
@@ -2620,6 +3938,11 @@ impl ProjectDB {
         Ok(attached_connectors)
     }
 
+    /// Parse API key scopes stored in the database.
+    fn parse_api_key_scopes(scopes: Vec<String>) -> Result<Vec<ApiPermission>, DBError> {
+        scopes.iter().map(|scope| scope.parse()).collect()
+    }
+
     /// Helper to convert postgres error into a `DBError` if the underlying
     /// low-level error thrown by the database matches.
     fn maybe_unique_violation(err: PgError) -> DBError {
@@ -2630,6 +3953,7 @@ impl ProjectDB {
                     Some("connector_pkey") => DBError::unique_key_violation("connector_pkey"),
                     Some("pipeline_pkey") => DBError::unique_key_violation("pipeline_pkey"),
                     Some("api_key_pkey") => DBError::duplicate_key(),
+                    Some("api_key_id_key") => DBError::unique_key_violation("api_key_id_key"),
                     Some(_constraint) => DBError::DuplicateName,
                     None => DBError::DuplicateName,
                 }
@@ -1,12 +1,16 @@
 use super::{
-    ApiPermission, AttachedConnector, ConnectorDescr, ConnectorId, DBError, Pipeline,
-    PipelineDescr, PipelineId, PipelineRevision, PipelineRuntimeState, PipelineStatus,
-    ProgramDescr, ProgramId, ProgramSchema, Revision, Version,
+    ApiKeyDescr, ApiKeyId, ApiPermission, ApplyAction, ApplyPlan, ApplyStep, AttachedConnector,
+    BundledAttachedConnector, BundledConnector, BundledPipeline, BundledProgram, ConnectorDescr,
+    ConnectorHistoryEntry, ConnectorId, DBError, ImportAction, ImportReport, ImportedObject,
+    ObjectKind, Pipeline, PipelineDescr, PipelineHistoryEntry, PipelineId, PipelineRevision,
+    PipelineRuntimeState, PipelineStatus, ProgramDescr, ProgramId, ProgramSchema,
+    ProgramVersionDescr, ProjectBundle, ProjectChange, Revision, Version, PROJECT_BUNDLE_VERSION,
 };
 use crate::api::ProgramStatus;
 use crate::auth::TenantId;
 use async_trait::async_trait;
 use dbsp_adapters::{ConnectorConfig, RuntimeConfig};
+use std::collections::{BTreeMap, BTreeSet};
 use uuid::Uuid;
 
 /// The storage trait contains the methods to interact with the pipeline manager
@@ -109,6 +113,37 @@ pub(crate) trait Storage {
         Ok(())
     }
 
+    /// Cancel the compilation of a program.
+    ///
+    /// Resets the status of a program that is queued or being compiled to
+    /// [`ProgramStatus::None`].  The compiler aborts compilation jobs of
+    /// programs that are no longer compiling.
+    ///
+    /// Fails with [`DBError::ProgramNotCompiling`] if the program is neither
+    /// pending nor being compiled.
+    async fn cancel_program_compilation(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+    ) -> Result<(), DBError> {
+        let descr = self
+            .get_program_guarded(tenant_id, program_id, expected_version)
+            .await?;
+
+        if descr.status != ProgramStatus::Pending && !descr.status.is_compiling() {
+            return Err(DBError::ProgramNotCompiling { program_id });
+        }
+
+        self.set_program_status_guarded(
+            tenant_id,
+            program_id,
+            expected_version,
+            ProgramStatus::None,
+        )
+        .await
+    }
+
     /// Create a new program.
     async fn new_program(
         &self,
@@ -200,6 +235,42 @@ pub(crate) trait Storage {
         program_id: ProgramId,
     ) -> Result<(), DBError>;
 
+    /// List all recorded versions of a program, oldest first.
+    ///
+    /// Returns `DBError::UnknownProgram` if `program_id` is not found in the
+    /// database.
+    async fn list_program_versions(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        with_code: bool,
+    ) -> Result<Vec<ProgramVersionDescr>, DBError>;
+
+    /// Retrieve a version of a program, including its code.
+    ///
+    /// Returns `DBError::UnknownProgram` if `program_id` is not found in the
+    /// database and `DBError::UnknownProgramVersion` if the program has no
+    /// such version.
+    async fn get_program_version(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<ProgramVersionDescr, DBError>;
+
+    /// Restore the code of an earlier version of a program.
+    ///
+    /// The restored code becomes a new version of the program, which is
+    /// queued for compilation.  Does nothing if the program's current code
+    /// already matches the code of `version`.  Returns the resulting version
+    /// of the program.
+    async fn rollback_program(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> Result<Version, DBError>;
+
     /// Retrieves all programs in the DB. Intended to be used by
     /// reconciliation loops.
     async fn all_programs(&self) -> Result<Vec<(TenantId, ProgramDescr)>, DBError>;
@@ -210,10 +281,26 @@ pub(crate) trait Storage {
 
     /// Retrieves the first pending program from the queue.
     ///
-    /// Returns a pending program with the most recent `status_since` or `None`
-    /// if there are no pending programs in the DB.
+    /// To share the compiler fairly between tenants, returns the pending
+    /// program with the oldest `status_since` among the tenants with the
+    /// fewest programs being compiled, or `None` if there are no pending
+    /// programs in the DB.
     async fn next_job(&self) -> Result<Option<(TenantId, ProgramId, Version)>, DBError>;
 
+    /// Claim a program returned by [`Storage::next_job`] for compilation.
+    ///
+    /// Atomically sets the status of the program to
+    /// [`ProgramStatus::CompilingSql`] if it is still pending at
+    /// `expected_version`.  Returns `false` if the program was cancelled,
+    /// updated, or claimed in the meantime, in which case the compiler must
+    /// not compile it.
+    async fn claim_program_compilation(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+    ) -> Result<bool, DBError>;
+
     /// Version the configuration for a pipeline.
     ///
     /// Returns the revision number for that snapshot.
@@ -232,6 +319,13 @@ pub(crate) trait Storage {
         pipeline_id: PipelineId,
     ) -> Result<PipelineRevision, DBError>;
 
+    /// List all committed revisions of a pipeline, oldest first.
+    async fn list_pipeline_history(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<Vec<PipelineHistoryEntry>, DBError>;
+
     /// Create a new config.
     #[allow(clippy::too_many_arguments)]
     async fn new_pipeline(
@@ -378,20 +472,618 @@ pub(crate) trait Storage {
         connector_id: ConnectorId,
     ) -> Result<(), DBError>;
 
+    /// List all revisions of a connector committed as part of a pipeline
+    /// revision, oldest first.
+    async fn list_connector_history(
+        &self,
+        tenant_id: TenantId,
+        connector_id: ConnectorId,
+    ) -> Result<Vec<ConnectorHistoryEntry>, DBError>;
+
+    /// Apply `changes` in order, atomically: if one of them fails, none of
+    /// them is applied.
+    ///
+    /// Fails with [`DBError::ApplyConflict`] if a program or pipeline to
+    /// update or delete is no longer at the version recorded in the change,
+    /// or if a pipeline to delete is not shut down.
+    async fn commit_project_changes(
+        &self,
+        tenant_id: TenantId,
+        changes: &[ProjectChange],
+    ) -> Result<(), DBError>;
+
+    /// Export the programs, connectors and pipelines of a tenant.
+    async fn export_project(&self, tenant_id: TenantId) -> Result<ProjectBundle, DBError> {
+        let programs = self.list_programs(tenant_id, true).await?;
+        let connectors = self.list_connectors(tenant_id).await?;
+        let pipelines = self.list_pipelines(tenant_id).await?;
+
+        let program_names = programs
+            .iter()
+            .map(|program| (program.program_id, program.name.clone()))
+            .collect();
+        let connector_names = connectors
+            .iter()
+            .map(|connector| (connector.connector_id, connector.name.clone()))
+            .collect();
+
+        Ok(ProjectBundle {
+            bundle_version: PROJECT_BUNDLE_VERSION,
+            programs: programs
+                .into_iter()
+                .map(|program| BundledProgram {
+                    name: program.name,
+                    description: program.description,
+                    code: program.code.unwrap_or_default(),
+                })
+                .collect(),
+            connectors: connectors
+                .into_iter()
+                .map(|connector| BundledConnector {
+                    name: connector.name,
+                    description: connector.description,
+                    config: connector.config,
+                })
+                .collect(),
+            pipelines: pipelines
+                .iter()
+                .map(|pipeline| {
+                    bundled_pipeline(&pipeline.descriptor, &program_names, &connector_names)
+                })
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// Import a project bundle, e.g., one exported from another installation
+    /// with [`Storage::export_project`].
+    ///
+    /// Programs, connectors and pipelines of the bundle are matched with
+    /// those of the tenant by name.  Objects that don't exist are created,
+    /// existing objects that differ from the bundle are updated.  With
+    /// `dry_run`, nothing is modified and the report describes what the
+    /// import would do, including the fields of existing objects that would
+    /// be overwritten.  Otherwise, all changes are made in a single
+    /// transaction (see [`Storage::commit_project_changes`]).
+    ///
+    /// The bundle is validated before anything is modified: names must be
+    /// unique within the bundle, and pipelines must refer to programs and
+    /// connectors that are in the bundle or exist already.  Otherwise, fails
+    /// with [`DBError::InvalidProjectBundle`].
+    async fn import_project(
+        &self,
+        tenant_id: TenantId,
+        bundle: &ProjectBundle,
+        dry_run: bool,
+    ) -> Result<ImportReport, DBError> {
+        let (program_names, connector_names) = check_bundle(bundle)?;
+
+        let existing_programs: BTreeMap<String, ProgramDescr> = self
+            .list_programs(tenant_id, true)
+            .await?
+            .into_iter()
+            .map(|program| (program.name.clone(), program))
+            .collect();
+        let existing_connectors: BTreeMap<String, ConnectorDescr> = self
+            .list_connectors(tenant_id)
+            .await?
+            .into_iter()
+            .map(|connector| (connector.name.clone(), connector))
+            .collect();
+        let existing_pipelines: BTreeMap<String, PipelineDescr> = self
+            .list_pipelines(tenant_id)
+            .await?
+            .into_iter()
+            .map(|pipeline| (pipeline.descriptor.name.clone(), pipeline.descriptor))
+            .collect();
+
+        let known_programs: BTreeSet<&str> = program_names
+            .into_iter()
+            .chain(existing_programs.keys().map(String::as_str))
+            .collect();
+        let known_connectors: BTreeSet<&str> = connector_names
+            .into_iter()
+            .chain(existing_connectors.keys().map(String::as_str))
+            .collect();
+        check_references(bundle, &known_programs, &known_connectors)?;
+
+        let mut report = ImportReport {
+            dry_run,
+            programs: Vec::new(),
+            connectors: Vec::new(),
+            pipelines: Vec::new(),
+        };
+        let mut changes = Vec::new();
+
+        let mut program_ids: BTreeMap<String, ProgramId> = existing_programs
+            .values()
+            .map(|program| (program.name.clone(), program.program_id))
+            .collect();
+        for program in &bundle.programs {
+            let existing = existing_programs.get(&program.name);
+            let conflicts = existing
+                .map(|existing| program_changes(existing, program))
+                .unwrap_or_default();
+            let imported = imported_object(&program.name, existing.is_some(), conflicts);
+            if !dry_run {
+                match (imported.action, existing) {
+                    (ImportAction::Create, _) => {
+                        let program_id = ProgramId(Uuid::now_v7());
+                        program_ids.insert(program.name.clone(), program_id);
+                        changes.push(ProjectChange::NewProgram {
+                            program_id,
+                            program: program.clone(),
+                        });
+                    }
+                    (ImportAction::Update, Some(existing)) => {
+                        changes.push(ProjectChange::UpdateProgram {
+                            program_id: existing.program_id,
+                            version: existing.version,
+                            name: program.name.clone(),
+                            description: program.description.clone(),
+                            code: changed_code(existing, program),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            report.programs.push(imported);
+        }
+
+        let mut connector_ids: BTreeMap<String, ConnectorId> = existing_connectors
+            .values()
+            .map(|connector| (connector.name.clone(), connector.connector_id))
+            .collect();
+        for connector in &bundle.connectors {
+            let existing = existing_connectors.get(&connector.name);
+            let conflicts = existing
+                .map(|existing| connector_changes(existing, connector))
+                .unwrap_or_default();
+            let imported = imported_object(&connector.name, existing.is_some(), conflicts);
+            if !dry_run {
+                match (imported.action, existing) {
+                    (ImportAction::Create, _) => {
+                        let connector_id = ConnectorId(Uuid::now_v7());
+                        connector_ids.insert(connector.name.clone(), connector_id);
+                        changes.push(ProjectChange::NewConnector {
+                            connector_id,
+                            connector: connector.clone(),
+                        });
+                    }
+                    (ImportAction::Update, Some(existing)) => {
+                        changes.push(ProjectChange::UpdateConnector {
+                            connector_id: existing.connector_id,
+                            connector: connector.clone(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+            report.connectors.push(imported);
+        }
+
+        let existing_program_names = existing_programs
+            .values()
+            .map(|program| (program.program_id, program.name.clone()))
+            .collect();
+        let existing_connector_names = existing_connectors
+            .values()
+            .map(|connector| (connector.connector_id, connector.name.clone()))
+            .collect();
+        for pipeline in &bundle.pipelines {
+            let existing = existing_pipelines.get(&pipeline.name);
+            let conflicts = match existing {
+                Some(existing) => pipeline_changes(
+                    &bundled_pipeline(
+                        existing,
+                        &existing_program_names,
+                        &existing_connector_names,
+                    )?,
+                    pipeline,
+                ),
+                None => Vec::new(),
+            };
+            let imported = imported_object(&pipeline.name, existing.is_some(), conflicts);
+            if !dry_run && imported.action != ImportAction::Unchanged {
+                // References were validated above, and all programs and
+                // connectors of the bundle have ids.
+                let program_id = pipeline.program_name.as_ref().map(|name| program_ids[name]);
+                let connectors = attached_connectors(pipeline, &connector_ids);
+                changes.push(match existing {
+                    None => ProjectChange::NewPipeline {
+                        pipeline_id: PipelineId(Uuid::now_v7()),
+                        program_id,
+                        name: pipeline.name.clone(),
+                        description: pipeline.description.clone(),
+                        config: pipeline.config.clone(),
+                        connectors,
+                    },
+                    Some(existing) => ProjectChange::UpdatePipeline {
+                        pipeline_id: existing.pipeline_id,
+                        version: existing.version,
+                        program_id,
+                        name: pipeline.name.clone(),
+                        description: pipeline.description.clone(),
+                        config: pipeline.config.clone(),
+                        connectors,
+                    },
+                });
+            }
+            report.pipelines.push(imported);
+        }
+
+        if !dry_run {
+            self.commit_project_changes(tenant_id, &changes).await?;
+        }
+        Ok(report)
+    }
+
+    /// Bring the programs, connectors and pipelines of a tenant to the state
+    /// described by `desired`.
+    ///
+    /// Computes a plan against the current state of the tenant: objects of
+    /// `desired` are created or updated by name, other objects are deleted,
+    /// new and modified programs are compiled, and running or paused
+    /// pipelines whose committed revision differs from the desired state are
+    /// redeployed (see [`ApplyAction::Redeploy`]).  With `dry_run`, the plan
+    /// is returned without executing it.
+    ///
+    /// The plan is validated against the current state before anything is
+    /// modified: names must be unique, pipelines must refer to programs and
+    /// connectors of `desired` ([`DBError::InvalidProjectBundle`]), and
+    /// pipelines to delete must be shut down ([`DBError::ApplyConflict`]).
+    /// Steps are then executed in an order in which they cannot invalidate
+    /// each other, in a single transaction (see
+    /// [`Storage::commit_project_changes`]): if a step fails, the plan is not
+    /// applied at all.  The transaction repeats the checks that guard the
+    /// plan: a pipeline that was started, or a program or pipeline that was
+    /// modified since the plan was computed, fails the apply with
+    /// [`DBError::ApplyConflict`].
+    async fn apply_project(
+        &self,
+        tenant_id: TenantId,
+        desired: &ProjectBundle,
+        dry_run: bool,
+    ) -> Result<ApplyPlan, DBError> {
+        let (program_names, connector_names) = check_bundle(desired)?;
+        check_references(desired, &program_names, &connector_names)?;
+
+        let existing_programs: BTreeMap<String, ProgramDescr> = self
+            .list_programs(tenant_id, true)
+            .await?
+            .into_iter()
+            .map(|program| (program.name.clone(), program))
+            .collect();
+        let existing_connectors: BTreeMap<String, ConnectorDescr> = self
+            .list_connectors(tenant_id)
+            .await?
+            .into_iter()
+            .map(|connector| (connector.name.clone(), connector))
+            .collect();
+        let existing_pipelines: BTreeMap<String, Pipeline> = self
+            .list_pipelines(tenant_id)
+            .await?
+            .into_iter()
+            .map(|pipeline| (pipeline.descriptor.name.clone(), pipeline))
+            .collect();
+        let desired_programs: BTreeMap<&str, &BundledProgram> = desired
+            .programs
+            .iter()
+            .map(|program| (program.name.as_str(), program))
+            .collect();
+        let desired_connectors: BTreeMap<&str, &BundledConnector> = desired
+            .connectors
+            .iter()
+            .map(|connector| (connector.name.as_str(), connector))
+            .collect();
+        let desired_pipelines: BTreeMap<&str, &BundledPipeline> = desired
+            .pipelines
+            .iter()
+            .map(|pipeline| (pipeline.name.as_str(), pipeline))
+            .collect();
+
+        let mut steps = Vec::new();
+
+        // Programs whose code is new or changed, or was never compiled.
+        let mut recompile = BTreeSet::new();
+        for program in &desired.programs {
+            match existing_programs.get(&program.name) {
+                None => {
+                    steps.push(ApplyStep::new(
+                        ApplyAction::Create,
+                        ObjectKind::Program,
+                        &program.name,
+                    ));
+                    recompile.insert(program.name.as_str());
+                }
+                Some(existing) => {
+                    let changes = program_changes(existing, program);
+                    if changes.contains(&"code") || existing.status == ProgramStatus::None {
+                        recompile.insert(program.name.as_str());
+                    }
+                    if !changes.is_empty() {
+                        steps.push(
+                            ApplyStep::new(ApplyAction::Update, ObjectKind::Program, &program.name)
+                                .with_changes(changes),
+                        );
+                    }
+                }
+            }
+        }
+        for connector in &desired.connectors {
+            match existing_connectors.get(&connector.name) {
+                None => steps.push(ApplyStep::new(
+                    ApplyAction::Create,
+                    ObjectKind::Connector,
+                    &connector.name,
+                )),
+                Some(existing) => {
+                    let changes = connector_changes(existing, connector);
+                    if !changes.is_empty() {
+                        steps.push(
+                            ApplyStep::new(
+                                ApplyAction::Update,
+                                ObjectKind::Connector,
+                                &connector.name,
+                            )
+                            .with_changes(changes),
+                        );
+                    }
+                }
+            }
+        }
+        let existing_program_names = existing_programs
+            .values()
+            .map(|program| (program.program_id, program.name.clone()))
+            .collect();
+        let existing_connector_names = existing_connectors
+            .values()
+            .map(|connector| (connector.connector_id, connector.name.clone()))
+            .collect();
+        for pipeline in &desired.pipelines {
+            match existing_pipelines.get(&pipeline.name) {
+                None => steps.push(ApplyStep::new(
+                    ApplyAction::Create,
+                    ObjectKind::Pipeline,
+                    &pipeline.name,
+                )),
+                Some(existing) => {
+                    let changes = pipeline_changes(
+                        &bundled_pipeline(
+                            &existing.descriptor,
+                            &existing_program_names,
+                            &existing_connector_names,
+                        )?,
+                        pipeline,
+                    );
+                    if !changes.is_empty() {
+                        steps.push(
+                            ApplyStep::new(
+                                ApplyAction::Update,
+                                ObjectKind::Pipeline,
+                                &pipeline.name,
+                            )
+                            .with_changes(changes),
+                        );
+                    }
+                }
+            }
+        }
+
+        // Delete pipelines before the programs and connectors they may refer
+        // to.
+        for (name, pipeline) in &existing_pipelines {
+            if desired_pipelines.contains_key(name.as_str()) {
+                continue;
+            }
+            if pipeline.state.current_status != PipelineStatus::Shutdown
+                || pipeline.state.desired_status != PipelineStatus::Shutdown
+            {
+                return Err(DBError::ApplyConflict {
+                    reason: format!("pipeline '{name}' must be shut down before it can be deleted"),
+                });
+            }
+            steps.push(ApplyStep::new(
+                ApplyAction::Delete,
+                ObjectKind::Pipeline,
+                name,
+            ));
+        }
+        for name in existing_connectors.keys() {
+            if !desired_connectors.contains_key(name.as_str()) {
+                steps.push(ApplyStep::new(
+                    ApplyAction::Delete,
+                    ObjectKind::Connector,
+                    name,
+                ));
+            }
+        }
+        for name in existing_programs.keys() {
+            if !desired_programs.contains_key(name.as_str()) {
+                steps.push(ApplyStep::new(
+                    ApplyAction::Delete,
+                    ObjectKind::Program,
+                    name,
+                ));
+            }
+        }
+
+        for name in &recompile {
+            steps.push(ApplyStep::new(
+                ApplyAction::Recompile,
+                ObjectKind::Program,
+                name,
+            ));
+        }
+
+        for pipeline in &desired.pipelines {
+            let existing = match existing_pipelines.get(&pipeline.name) {
+                Some(existing) => existing,
+                None => continue,
+            };
+            let program_name = match &pipeline.program_name {
+                Some(program_name) => program_name,
+                None => continue,
+            };
+            // Only redeploy pipelines that are up and not in the middle of
+            // another state transition.
+            let state = &existing.state;
+            if !matches!(
+                state.current_status,
+                PipelineStatus::Running | PipelineStatus::Paused
+            ) || state.desired_status != state.current_status
+            {
+                continue;
+            }
+            let revision = self
+                .get_last_committed_pipeline_revision(tenant_id, existing.descriptor.pipeline_id)
+                .await?;
+            if revision_matches(&revision, pipeline, &desired_programs, &desired_connectors) {
+                continue;
+            }
+            let compiled = !recompile.contains(program_name.as_str())
+                && existing_programs[program_name].status == ProgramStatus::Success;
+            let mut step =
+                ApplyStep::new(ApplyAction::Redeploy, ObjectKind::Pipeline, &pipeline.name);
+            step.deferred = !compiled;
+            steps.push(step);
+        }
+
+        if !dry_run {
+            let mut program_ids: BTreeMap<String, ProgramId> = existing_programs
+                .values()
+                .map(|program| (program.name.clone(), program.program_id))
+                .collect();
+            let mut connector_ids: BTreeMap<String, ConnectorId> = existing_connectors
+                .values()
+                .map(|connector| (connector.name.clone(), connector.connector_id))
+                .collect();
+            let mut changes = Vec::new();
+            for step in &steps {
+                let name = step.name.as_str();
+                let change = match (step.action, step.kind) {
+                    (ApplyAction::Create, ObjectKind::Program) => {
+                        let program_id = ProgramId(Uuid::now_v7());
+                        program_ids.insert(name.to_string(), program_id);
+                        ProjectChange::NewProgram {
+                            program_id,
+                            program: desired_programs[name].clone(),
+                        }
+                    }
+                    (ApplyAction::Update, ObjectKind::Program) => {
+                        let program = desired_programs[name];
+                        let existing = &existing_programs[name];
+                        ProjectChange::UpdateProgram {
+                            program_id: existing.program_id,
+                            version: existing.version,
+                            name: name.to_string(),
+                            description: program.description.clone(),
+                            code: changed_code(existing, program),
+                        }
+                    }
+                    (ApplyAction::Delete, ObjectKind::Program) => ProjectChange::DeleteProgram {
+                        program_id: existing_programs[name].program_id,
+                        version: existing_programs[name].version,
+                    },
+                    (ApplyAction::Recompile, ObjectKind::Program) => {
+                        ProjectChange::CompileProgram {
+                            program_id: program_ids[name],
+                        }
+                    }
+                    (ApplyAction::Create, ObjectKind::Connector) => {
+                        let connector_id = ConnectorId(Uuid::now_v7());
+                        connector_ids.insert(name.to_string(), connector_id);
+                        ProjectChange::NewConnector {
+                            connector_id,
+                            connector: desired_connectors[name].clone(),
+                        }
+                    }
+                    (ApplyAction::Update, ObjectKind::Connector) => {
+                        ProjectChange::UpdateConnector {
+                            connector_id: existing_connectors[name].connector_id,
+                            connector: desired_connectors[name].clone(),
+                        }
+                    }
+                    (ApplyAction::Delete, ObjectKind::Connector) => {
+                        ProjectChange::DeleteConnector {
+                            connector_id: existing_connectors[name].connector_id,
+                        }
+                    }
+                    (ApplyAction::Create, ObjectKind::Pipeline) => {
+                        let pipeline = desired_pipelines[name];
+                        ProjectChange::NewPipeline {
+                            pipeline_id: PipelineId(Uuid::now_v7()),
+                            program_id: pipeline
+                                .program_name
+                                .as_ref()
+                                .map(|name| program_ids[name]),
+                            name: name.to_string(),
+                            description: pipeline.description.clone(),
+                            config: pipeline.config.clone(),
+                            connectors: attached_connectors(pipeline, &connector_ids),
+                        }
+                    }
+                    (ApplyAction::Update, ObjectKind::Pipeline) => {
+                        let pipeline = desired_pipelines[name];
+                        ProjectChange::UpdatePipeline {
+                            pipeline_id: existing_pipelines[name].descriptor.pipeline_id,
+                            version: existing_pipelines[name].descriptor.version,
+                            program_id: pipeline
+                                .program_name
+                                .as_ref()
+                                .map(|name| program_ids[name]),
+                            name: name.to_string(),
+                            description: pipeline.description.clone(),
+                            config: pipeline.config.clone(),
+                            connectors: attached_connectors(pipeline, &connector_ids),
+                        }
+                    }
+                    (ApplyAction::Delete, ObjectKind::Pipeline) => ProjectChange::DeletePipeline {
+                        pipeline_id: existing_pipelines[name].descriptor.pipeline_id,
+                        version: existing_pipelines[name].descriptor.version,
+                    },
+                    (ApplyAction::Redeploy, ObjectKind::Pipeline) if !step.deferred => {
+                        // The runner upgrades the pipeline to the new revision.
+                        ProjectChange::CommitPipelineRevision {
+                            pipeline_id: existing_pipelines[name].descriptor.pipeline_id,
+                        }
+                    }
+                    _ => continue,
+                };
+                changes.push(change);
+            }
+            self.commit_project_changes(tenant_id, &changes).await?;
+        }
+
+        Ok(ApplyPlan { dry_run, steps })
+    }
+
     /// Persist a hash of API key in the database
     async fn store_api_key_hash(
         &self,
         tenant_id: TenantId,
+        id: Uuid,
+        name: &str,
         key: String,
         permissions: Vec<ApiPermission>,
-    ) -> Result<(), DBError>;
+    ) -> Result<ApiKeyId, DBError>;
 
-    /// Validate an API key against the database
+    /// Validate an API key against the database and record the time it was
+    /// last used.
     async fn validate_api_key(
         &self,
         key: String,
     ) -> Result<(TenantId, Vec<ApiPermission>), DBError>;
 
+    /// Retrieve metadata of all API keys of a tenant.
+    async fn list_api_keys(&self, tenant_id: TenantId) -> Result<Vec<ApiKeyDescr>, DBError>;
+
+    /// Revoke an API key.
+    async fn delete_api_key(
+        &self,
+        tenant_id: TenantId,
+        api_key_id: ApiKeyId,
+    ) -> Result<(), DBError>;
+
     /// Get the tenant ID from the database for a given tenant name and
     /// provider, else create a new tenant ID
     async fn get_or_create_tenant_id(
@@ -431,3 +1123,256 @@ pub(crate) trait Storage {
         version: Version,
     ) -> Result<(), DBError>;
 }
+
+/// Describe a pipeline in a [`ProjectBundle`], given the names of the
+/// programs and connectors it refers to.
+fn bundled_pipeline(
+    pipeline: &PipelineDescr,
+    program_names: &BTreeMap<ProgramId, String>,
+    connector_names: &BTreeMap<ConnectorId, String>,
+) -> Result<BundledPipeline, DBError> {
+    let program_name = pipeline
+        .program_id
+        .map(|program_id| {
+            program_names
+                .get(&program_id)
+                .cloned()
+                .ok_or(DBError::UnknownProgram { program_id })
+        })
+        .transpose()?;
+    let attached_connectors = pipeline
+        .attached_connectors
+        .iter()
+        .map(|ac| {
+            Ok(BundledAttachedConnector {
+                name: ac.name.clone(),
+                is_input: ac.is_input,
+                connector_name: connector_names.get(&ac.connector_id).cloned().ok_or(
+                    DBError::UnknownConnector {
+                        connector_id: ac.connector_id,
+                    },
+                )?,
+                relation_name: ac.relation_name.clone(),
+            })
+        })
+        .collect::<Result<_, DBError>>()?;
+
+    Ok(BundledPipeline {
+        name: pipeline.name.clone(),
+        description: pipeline.description.clone(),
+        program_name,
+        config: pipeline.config.clone(),
+        attached_connectors,
+    })
+}
+
+/// Check that the `kind` objects of a bundle have unique `names`, and return
+/// the names.
+fn unique_names<'a>(
+    kind: &str,
+    names: impl Iterator<Item = &'a str>,
+) -> Result<BTreeSet<&'a str>, DBError> {
+    let mut unique = BTreeSet::new();
+    for name in names {
+        if !unique.insert(name) {
+            return Err(DBError::InvalidProjectBundle {
+                reason: format!("duplicate {kind} name '{name}'"),
+            });
+        }
+    }
+    Ok(unique)
+}
+
+/// Check the format version of a bundle and that its programs and connectors
+/// have unique names, and return the names.
+fn check_bundle(bundle: &ProjectBundle) -> Result<(BTreeSet<&str>, BTreeSet<&str>), DBError> {
+    if bundle.bundle_version != PROJECT_BUNDLE_VERSION {
+        return Err(DBError::InvalidProjectBundle {
+            reason: format!(
+                "unsupported bundle version {} (expected {PROJECT_BUNDLE_VERSION})",
+                bundle.bundle_version
+            ),
+        });
+    }
+    let program_names = unique_names("program", bundle.programs.iter().map(|p| p.name.as_str()))?;
+    let connector_names = unique_names(
+        "connector",
+        bundle.connectors.iter().map(|c| c.name.as_str()),
+    )?;
+    unique_names("pipeline", bundle.pipelines.iter().map(|p| p.name.as_str()))?;
+    Ok((program_names, connector_names))
+}
+
+/// Check that the pipelines of a bundle only refer to `programs` and
+/// `connectors`.
+fn check_references(
+    bundle: &ProjectBundle,
+    programs: &BTreeSet<&str>,
+    connectors: &BTreeSet<&str>,
+) -> Result<(), DBError> {
+    for pipeline in &bundle.pipelines {
+        if let Some(program_name) = &pipeline.program_name {
+            if !programs.contains(program_name.as_str()) {
+                return Err(DBError::InvalidProjectBundle {
+                    reason: format!(
+                        "pipeline '{}' refers to unknown program '{program_name}'",
+                        pipeline.name
+                    ),
+                });
+            }
+        }
+        for ac in &pipeline.attached_connectors {
+            if !connectors.contains(ac.connector_name.as_str()) {
+                return Err(DBError::InvalidProjectBundle {
+                    reason: format!(
+                        "pipeline '{}' refers to unknown connector '{}'",
+                        pipeline.name, ac.connector_name
+                    ),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Fields of an existing program that differ from `program`.
+fn program_changes(existing: &ProgramDescr, program: &BundledProgram) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if existing.description != program.description {
+        changes.push("description");
+    }
+    if existing.code.as_ref() != Some(&program.code) {
+        changes.push("code");
+    }
+    changes
+}
+
+/// The code to pass to [`Storage::update_program`] to update an existing
+/// program to `program`: only set if the code changed, since setting it
+/// creates a new program version.
+fn changed_code(existing: &ProgramDescr, program: &BundledProgram) -> Option<String> {
+    (existing.code.as_ref() != Some(&program.code)).then(|| program.code.clone())
+}
+
+/// Fields of an existing connector that differ from `connector`.
+fn connector_changes(existing: &ConnectorDescr, connector: &BundledConnector) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if existing.description != connector.description {
+        changes.push("description");
+    }
+    if existing.config != connector.config {
+        changes.push("config");
+    }
+    changes
+}
+
+/// Fields of an existing pipeline, described by [`bundled_pipeline`], that
+/// differ from `pipeline`.
+fn pipeline_changes(existing: &BundledPipeline, pipeline: &BundledPipeline) -> Vec<&'static str> {
+    let mut changes = Vec::new();
+    if existing.description != pipeline.description {
+        changes.push("description");
+    }
+    if existing.program_name != pipeline.program_name {
+        changes.push("program_name");
+    }
+    if existing.config != pipeline.config {
+        changes.push("config");
+    }
+    // The order of attached connectors is irrelevant.
+    let mut existing_connectors = existing.attached_connectors.clone();
+    let mut attached_connectors = pipeline.attached_connectors.clone();
+    existing_connectors.sort_by(|a, b| a.name.cmp(&b.name));
+    attached_connectors.sort_by(|a, b| a.name.cmp(&b.name));
+    if existing_connectors != attached_connectors {
+        changes.push("attached_connectors");
+    }
+    changes
+}
+
+/// The connectors attached to a bundled pipeline, given the ids of all
+/// connectors by name.
+fn attached_connectors(
+    pipeline: &BundledPipeline,
+    connector_ids: &BTreeMap<String, ConnectorId>,
+) -> Vec<AttachedConnector> {
+    pipeline
+        .attached_connectors
+        .iter()
+        .map(|ac| AttachedConnector {
+            name: ac.name.clone(),
+            is_input: ac.is_input,
+            connector_id: connector_ids[&ac.connector_name],
+            relation_name: ac.relation_name.clone(),
+        })
+        .collect()
+}
+
+/// Whether the committed `revision` of a pipeline runs the desired
+/// `pipeline`, with the desired program code and connector configs.
+fn revision_matches(
+    revision: &PipelineRevision,
+    pipeline: &BundledPipeline,
+    programs: &BTreeMap<&str, &BundledProgram>,
+    connectors: &BTreeMap<&str, &BundledConnector>,
+) -> bool {
+    let code = pipeline
+        .program_name
+        .as_ref()
+        .map(|name| &programs[name.as_str()].code);
+    if code != revision.program.code.as_ref() || pipeline.config != revision.pipeline.config {
+        return false;
+    }
+
+    let mut committed: Vec<_> = revision
+        .pipeline
+        .attached_connectors
+        .iter()
+        .map(|ac| {
+            let config = revision
+                .connectors
+                .iter()
+                .find(|connector| connector.connector_id == ac.connector_id)
+                .map(|connector| &connector.config);
+            (
+                ac.name.as_str(),
+                ac.is_input,
+                ac.relation_name.as_str(),
+                config,
+            )
+        })
+        .collect();
+    let mut desired: Vec<_> = pipeline
+        .attached_connectors
+        .iter()
+        .map(|ac| {
+            let config = Some(&connectors[ac.connector_name.as_str()].config);
+            (
+                ac.name.as_str(),
+                ac.is_input,
+                ac.relation_name.as_str(),
+                config,
+            )
+        })
+        .collect();
+    committed.sort_by_key(|ac| ac.0);
+    desired.sort_by_key(|ac| ac.0);
+    committed == desired
+}
+
+/// Describe the import of an object, given whether it `exists` already and
+/// the fields of the existing object that differ from the bundle.
+fn imported_object(name: &str, exists: bool, conflicts: Vec<&str>) -> ImportedObject {
+    let action = if !exists {
+        ImportAction::Create
+    } else if conflicts.is_empty() {
+        ImportAction::Unchanged
+    } else {
+        ImportAction::Update
+    };
+    ImportedObject {
+        name: name.to_string(),
+        action,
+        conflicts: conflicts.into_iter().map(String::from).collect(),
+    }
+}
@@ -3,12 +3,18 @@ use super::{
     PipelineRevision, PipelineStatus, ProgramDescr, ProgramId, ProgramStatus, ProjectDB, Revision,
     Version,
 };
-use super::{ApiPermission, Pipeline, PipelineDescr, PipelineRuntimeState, ProgramSchema};
+use super::{
+    ApiKeyDescr, ApiKeyId, ApiPermission, ApplyAction, ApplyPlan, BundledAttachedConnector,
+    BundledConnector, BundledPipeline, BundledProgram, ConnectorHistoryEntry, ImportAction,
+    ObjectKind, Pipeline, PipelineDescr, PipelineHistoryEntry, PipelineRuntimeState, ProgramSchema,
+    ProgramVersionDescr, ProjectBundle, ProjectChange, API_KEY_LAST_USED_RESOLUTION,
+    PROJECT_BUNDLE_VERSION,
+};
 use crate::auth::{self, TenantId, TenantRecord};
 use crate::db::Relation;
 use async_trait::async_trait;
 use chrono::{DateTime, NaiveDateTime, Utc};
-use dbsp_adapters::{ConnectorConfig, RuntimeConfig};
+use dbsp_adapters::{ConnectorConfig, RestartPolicy, RuntimeConfig};
 use openssl::sha::{self};
 use pretty_assertions::assert_eq;
 use proptest::test_runner::{Config, TestRunner};
@@ -412,6 +418,426 @@ async fn program_config() {
     assert_eq!(ac, ac_ret);
 }
 
+#[tokio::test]
+async fn project_export_import() {
+    let handle = test_setup().await;
+    let tenant1 = TenantRecord::default().id;
+    let tenant2 = handle
+        .db
+        .get_or_create_tenant_id("x".to_string(), "y".to_string())
+        .await
+        .unwrap();
+    let (program_id, _) = handle
+        .db
+        .new_program(
+            tenant1,
+            Uuid::now_v7(),
+            "p1",
+            "program desc",
+            "create table t1(c1 integer);",
+        )
+        .await
+        .unwrap();
+    let connector_id = handle
+        .db
+        .new_connector(tenant1, Uuid::now_v7(), "c1", "", &test_connector_config())
+        .await
+        .unwrap();
+    let ac = AttachedConnector {
+        name: "foo".to_string(),
+        is_input: true,
+        connector_id,
+        relation_name: "t1".to_string(),
+    };
+    handle
+        .db
+        .new_pipeline(
+            tenant1,
+            Uuid::now_v7(),
+            Some(program_id),
+            "pl1",
+            "",
+            &RuntimeConfig::from_yaml(""),
+            &Some(vec![ac]),
+        )
+        .await
+        .unwrap();
+
+    let mut bundle = handle.db.export_project(tenant1).await.unwrap();
+    assert_eq!(1, bundle.programs.len());
+    assert_eq!(1, bundle.connectors.len());
+    assert_eq!(Some("p1".to_string()), bundle.pipelines[0].program_name);
+    assert_eq!(
+        "c1",
+        bundle.pipelines[0].attached_connectors[0].connector_name
+    );
+
+    // A dry run doesn't modify anything.
+    let report = handle
+        .db
+        .import_project(tenant2, &bundle, true)
+        .await
+        .unwrap();
+    assert_eq!(ImportAction::Create, report.programs[0].action);
+    assert_eq!(ImportAction::Create, report.pipelines[0].action);
+    assert!(handle
+        .db
+        .list_programs(tenant2, false)
+        .await
+        .unwrap()
+        .is_empty());
+
+    handle
+        .db
+        .import_project(tenant2, &bundle, false)
+        .await
+        .unwrap();
+    assert_eq!(bundle, handle.db.export_project(tenant2).await.unwrap());
+
+    // Changes are reported as conflicts.
+    bundle.programs[0].code = "create table t1(c1 bigint);".to_string();
+    let report = handle
+        .db
+        .import_project(tenant2, &bundle, true)
+        .await
+        .unwrap();
+    assert_eq!(ImportAction::Update, report.programs[0].action);
+    assert_eq!(vec!["code".to_string()], report.programs[0].conflicts);
+    assert_eq!(ImportAction::Unchanged, report.connectors[0].action);
+    assert_eq!(ImportAction::Unchanged, report.pipelines[0].action);
+
+    // References must resolve.
+    bundle.pipelines[0].program_name = Some("p2".to_string());
+    let res = handle.db.import_project(tenant2, &bundle, false).await;
+    assert!(matches!(res, Err(DBError::InvalidProjectBundle { .. })));
+}
+
+#[tokio::test]
+async fn project_apply() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let mut desired = ProjectBundle {
+        bundle_version: PROJECT_BUNDLE_VERSION,
+        programs: vec![BundledProgram {
+            name: "p1".to_string(),
+            description: "".to_string(),
+            code: "create table t1(c1 integer);".to_string(),
+        }],
+        connectors: vec![BundledConnector {
+            name: "c1".to_string(),
+            description: "".to_string(),
+            config: test_connector_config(),
+        }],
+        pipelines: vec![BundledPipeline {
+            name: "pl1".to_string(),
+            description: "".to_string(),
+            program_name: Some("p1".to_string()),
+            config: RuntimeConfig::from_yaml(""),
+            attached_connectors: vec![BundledAttachedConnector {
+                name: "foo".to_string(),
+                is_input: true,
+                connector_name: "c1".to_string(),
+                relation_name: "t1".to_string(),
+            }],
+        }],
+    };
+    let actions = |plan: &ApplyPlan| -> Vec<(ApplyAction, ObjectKind)> {
+        plan.steps
+            .iter()
+            .map(|step| (step.action, step.kind))
+            .collect()
+    };
+    let expected = vec![
+        (ApplyAction::Create, ObjectKind::Program),
+        (ApplyAction::Create, ObjectKind::Connector),
+        (ApplyAction::Create, ObjectKind::Pipeline),
+        (ApplyAction::Recompile, ObjectKind::Program),
+    ];
+
+    // A dry run doesn't modify anything.
+    let plan = handle
+        .db
+        .apply_project(tenant_id, &desired, true)
+        .await
+        .unwrap();
+    assert_eq!(expected, actions(&plan));
+    assert!(handle
+        .db
+        .list_programs(tenant_id, false)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let plan = handle
+        .db
+        .apply_project(tenant_id, &desired, false)
+        .await
+        .unwrap();
+    assert_eq!(expected, actions(&plan));
+    assert_eq!(desired, handle.db.export_project(tenant_id).await.unwrap());
+    let program = handle
+        .db
+        .get_program_by_name(tenant_id, "p1", false)
+        .await
+        .unwrap();
+    assert_eq!(ProgramStatus::Pending, program.status);
+
+    // Applying the same state again is a no-op.
+    let plan = handle
+        .db
+        .apply_project(tenant_id, &desired, false)
+        .await
+        .unwrap();
+    assert!(plan.steps.is_empty());
+
+    // Objects that are not in the desired state are deleted, pipelines
+    // first.
+    desired.pipelines.clear();
+    desired.connectors.clear();
+    let plan = handle
+        .db
+        .apply_project(tenant_id, &desired, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![
+            (ApplyAction::Delete, ObjectKind::Pipeline),
+            (ApplyAction::Delete, ObjectKind::Connector),
+        ],
+        actions(&plan)
+    );
+
+    // Running pipelines cannot be deleted.
+    let pipeline = handle
+        .db
+        .get_pipeline_by_name(tenant_id, "pl1".to_string())
+        .await
+        .unwrap();
+    handle
+        .db
+        .set_pipeline_desired_status(
+            tenant_id,
+            pipeline.descriptor.pipeline_id,
+            PipelineStatus::Running,
+        )
+        .await
+        .unwrap();
+    let res = handle.db.apply_project(tenant_id, &desired, false).await;
+    assert!(matches!(res, Err(DBError::ApplyConflict { .. })));
+}
+
+#[tokio::test]
+async fn project_changes_atomic() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let program_id = ProgramId(Uuid::now_v7());
+    let connector_id = ConnectorId(Uuid::now_v7());
+    let mut changes = vec![
+        ProjectChange::NewProgram {
+            program_id,
+            program: BundledProgram {
+                name: "p1".to_string(),
+                description: "".to_string(),
+                code: "create table t1(c1 integer);".to_string(),
+            },
+        },
+        ProjectChange::NewConnector {
+            connector_id,
+            connector: BundledConnector {
+                name: "c1".to_string(),
+                description: "".to_string(),
+                config: test_connector_config(),
+            },
+        },
+        ProjectChange::NewPipeline {
+            pipeline_id: PipelineId(Uuid::now_v7()),
+            program_id: Some(program_id),
+            name: "pl1".to_string(),
+            description: "".to_string(),
+            config: RuntimeConfig::from_yaml(""),
+            connectors: vec![AttachedConnector {
+                name: "foo".to_string(),
+                is_input: true,
+                connector_id,
+                relation_name: "t1".to_string(),
+            }],
+        },
+        ProjectChange::CompileProgram { program_id },
+        // Fails: the connector doesn't exist.
+        ProjectChange::DeleteConnector {
+            connector_id: ConnectorId(Uuid::now_v7()),
+        },
+    ];
+
+    // If a change fails, none of the changes is committed.
+    let res = handle.db.commit_project_changes(tenant_id, &changes).await;
+    assert!(matches!(res, Err(DBError::UnknownConnector { .. })));
+    assert!(handle
+        .db
+        .list_programs(tenant_id, false)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(handle
+        .db
+        .list_connectors(tenant_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(handle
+        .db
+        .list_pipelines(tenant_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    changes.pop();
+    handle
+        .db
+        .commit_project_changes(tenant_id, &changes)
+        .await
+        .unwrap();
+    let program = handle
+        .db
+        .get_program_by_id(tenant_id, program_id, false)
+        .await
+        .unwrap();
+    assert_eq!(ProgramStatus::Pending, program.status);
+    let pipeline = handle
+        .db
+        .get_pipeline_by_name(tenant_id, "pl1".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        connector_id,
+        pipeline.descriptor.attached_connectors[0].connector_id
+    );
+}
+
+#[tokio::test]
+async fn project_changes_recheck() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let program_id = ProgramId(Uuid::now_v7());
+    let pipeline_id = PipelineId(Uuid::now_v7());
+    handle
+        .db
+        .commit_project_changes(
+            tenant_id,
+            &[
+                ProjectChange::NewProgram {
+                    program_id,
+                    program: BundledProgram {
+                        name: "p1".to_string(),
+                        description: "".to_string(),
+                        code: "create table t1(c1 integer);".to_string(),
+                    },
+                },
+                ProjectChange::NewPipeline {
+                    pipeline_id,
+                    program_id: Some(program_id),
+                    name: "pl1".to_string(),
+                    description: "".to_string(),
+                    config: RuntimeConfig::from_yaml(""),
+                    connectors: vec![],
+                },
+            ],
+        )
+        .await
+        .unwrap();
+
+    // Changes computed from a version that was modified in the meantime are
+    // rejected.
+    handle
+        .db
+        .update_program(
+            tenant_id,
+            program_id,
+            "p1",
+            "",
+            &Some("create table t2(c1 integer);".to_string()),
+        )
+        .await
+        .unwrap();
+    let res = handle
+        .db
+        .commit_project_changes(
+            tenant_id,
+            &[ProjectChange::UpdateProgram {
+                program_id,
+                version: Version(1),
+                name: "p1".to_string(),
+                description: "updated".to_string(),
+                code: None,
+            }],
+        )
+        .await;
+    assert!(matches!(res, Err(DBError::ApplyConflict { .. })));
+    handle
+        .db
+        .update_pipeline(
+            tenant_id,
+            pipeline_id,
+            Some(program_id),
+            "pl1",
+            "",
+            &None,
+            &None,
+        )
+        .await
+        .unwrap();
+    let update = ProjectChange::UpdatePipeline {
+        pipeline_id,
+        version: Version(1),
+        program_id: Some(program_id),
+        name: "pl1".to_string(),
+        description: "updated".to_string(),
+        config: RuntimeConfig::from_yaml(""),
+        connectors: vec![],
+    };
+    let res = handle.db.commit_project_changes(tenant_id, &[update]).await;
+    assert!(matches!(res, Err(DBError::ApplyConflict { .. })));
+    let pipeline = handle
+        .db
+        .get_pipeline_by_id(tenant_id, pipeline_id)
+        .await
+        .unwrap();
+    assert_eq!("", pipeline.descriptor.description);
+
+    // A pipeline that was started after the plan was computed is not
+    // deleted.
+    let delete = ProjectChange::DeletePipeline {
+        pipeline_id,
+        version: pipeline.descriptor.version,
+    };
+    handle
+        .db
+        .set_pipeline_desired_status(tenant_id, pipeline_id, PipelineStatus::Running)
+        .await
+        .unwrap();
+    let res = handle
+        .db
+        .commit_project_changes(tenant_id, &[delete.clone()])
+        .await;
+    assert!(matches!(res, Err(DBError::ApplyConflict { .. })));
+    handle
+        .db
+        .set_pipeline_desired_status(tenant_id, pipeline_id, PipelineStatus::Shutdown)
+        .await
+        .unwrap();
+    handle
+        .db
+        .commit_project_changes(tenant_id, &[delete])
+        .await
+        .unwrap();
+    assert!(handle
+        .db
+        .list_pipelines(tenant_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
 #[tokio::test]
 async fn project_pending() {
     let handle = test_setup().await;
@@ -457,6 +883,147 @@ async fn project_pending() {
     // so it won't get picked up twice?
 }
 
+#[tokio::test]
+async fn project_pending_fairness() {
+    let handle = test_setup().await;
+    let tenant1 = TenantRecord::default().id;
+    let tenant2 = handle
+        .db
+        .get_or_create_tenant_id("x".to_string(), "y".to_string())
+        .await
+        .unwrap();
+    let mut programs = Vec::new();
+    for (tenant_id, name) in [(tenant1, "p1"), (tenant1, "p2"), (tenant2, "p3")] {
+        let (program_id, version) = handle
+            .db
+            .new_program(tenant_id, Uuid::now_v7(), name, "", "ignored")
+            .await
+            .unwrap();
+        handle
+            .db
+            .set_program_for_compilation(tenant_id, program_id, version, ProgramStatus::Pending)
+            .await
+            .unwrap();
+        programs.push((tenant_id, program_id, version));
+    }
+
+    let (tenant_id, program_id, version) = programs[0];
+    handle
+        .db
+        .set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::CompilingSql)
+        .await
+        .unwrap();
+
+    // The tenant that is not compiling anything goes first, even though its
+    // program was queued last.
+    let (_, program_id, _) = handle.db.next_job().await.unwrap().unwrap();
+    assert_eq!(program_id, programs[2].1);
+}
+
+#[tokio::test]
+async fn cancel_program_compilation() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let (program_id, version) = handle
+        .db
+        .new_program(tenant_id, Uuid::now_v7(), "test1", "", "ignored")
+        .await
+        .unwrap();
+
+    // Nothing to cancel.
+    let res = handle
+        .db
+        .cancel_program_compilation(tenant_id, program_id, version)
+        .await;
+    assert!(matches!(res, Err(DBError::ProgramNotCompiling { .. })));
+
+    handle
+        .db
+        .prepare_program_for_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap();
+    handle
+        .db
+        .set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::CompilingRust)
+        .await
+        .unwrap();
+    handle
+        .db
+        .cancel_program_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap();
+    let desc = handle
+        .db
+        .get_program_if_exists(tenant_id, program_id, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ProgramStatus::None, desc.status);
+    assert!(handle.db.next_job().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn claim_program_compilation() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let (program_id, version) = handle
+        .db
+        .new_program(tenant_id, Uuid::now_v7(), "test1", "", "ignored")
+        .await
+        .unwrap();
+    handle
+        .db
+        .prepare_program_for_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap();
+    let (_, job_id, job_version) = handle.db.next_job().await.unwrap().unwrap();
+    assert_eq!((job_id, job_version), (program_id, version));
+
+    // A cancellation between `next_job` and the claim wins.
+    handle
+        .db
+        .cancel_program_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap();
+    assert!(!handle
+        .db
+        .claim_program_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap());
+    let desc = handle
+        .db
+        .get_program_if_exists(tenant_id, program_id, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ProgramStatus::None, desc.status);
+
+    // A pending program can be claimed exactly once.
+    handle
+        .db
+        .prepare_program_for_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap();
+    assert!(handle
+        .db
+        .claim_program_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap());
+    assert!(!handle
+        .db
+        .claim_program_compilation(tenant_id, program_id, version)
+        .await
+        .unwrap());
+    let desc = handle
+        .db
+        .get_program_if_exists(tenant_id, program_id, false)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ProgramStatus::CompilingSql, desc.status);
+    assert!(handle.db.next_job().await.unwrap().is_none());
+}
+
 #[tokio::test]
 async fn update_status() {
     let handle = test_setup().await;
@@ -546,12 +1113,14 @@ async fn save_api_key() {
     let handle = test_setup().await;
     let tenant_id = TenantRecord::default().id;
     // Attempt several key generations and validations
-    for _ in 1..10 {
+    for i in 1..10 {
         let api_key = auth::generate_api_key();
         handle
             .db
             .store_api_key_hash(
                 tenant_id,
+                Uuid::now_v7(),
+                &format!("key{i}"),
                 api_key.clone(),
                 vec![ApiPermission::Read, ApiPermission::Write],
             )
@@ -568,6 +1137,215 @@ async fn save_api_key() {
     }
 }
 
+#[tokio::test]
+async fn manage_api_keys() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let pipeline_id = PipelineId(Uuid::now_v7());
+    let scopes = vec![
+        ApiPermission::Egress {
+            pipeline_id,
+            relation: None,
+        },
+        ApiPermission::Ingress {
+            pipeline_id,
+            relation: Some("orders".to_string()),
+        },
+    ];
+
+    let api_key = auth::generate_api_key();
+    let api_key_id = handle
+        .db
+        .store_api_key_hash(
+            tenant_id,
+            Uuid::now_v7(),
+            "ingest",
+            api_key.clone(),
+            scopes.clone(),
+        )
+        .await
+        .unwrap();
+
+    // Names are unique within a tenant.
+    let err = handle
+        .db
+        .store_api_key_hash(
+            tenant_id,
+            Uuid::now_v7(),
+            "ingest",
+            auth::generate_api_key(),
+            vec![ApiPermission::Read],
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DBError::DuplicateName));
+
+    let keys = handle.db.list_api_keys(tenant_id).await.unwrap();
+    assert_eq!(1, keys.len());
+    assert_eq!(api_key_id, keys[0].api_key_id);
+    assert_eq!("ingest", keys[0].name);
+    assert_eq!(scopes, keys[0].scopes);
+    assert!(keys[0].last_used.is_none());
+
+    // Scoped permissions survive the round trip through the database.
+    let (_, validated) = handle.db.validate_api_key(api_key.clone()).await.unwrap();
+    assert_eq!(scopes, validated);
+    let keys = handle.db.list_api_keys(tenant_id).await.unwrap();
+    let last_used = keys[0].last_used;
+    assert!(last_used.is_some());
+
+    // `last_used` is not updated again within a minute.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    handle.db.validate_api_key(api_key.clone()).await.unwrap();
+    let keys = handle.db.list_api_keys(tenant_id).await.unwrap();
+    assert_eq!(last_used, keys[0].last_used);
+
+    handle
+        .db
+        .delete_api_key(tenant_id, api_key_id)
+        .await
+        .unwrap();
+    let err = handle.db.validate_api_key(api_key).await.unwrap_err();
+    assert!(matches!(err, DBError::InvalidKey));
+    let err = handle
+        .db
+        .delete_api_key(tenant_id, api_key_id)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DBError::UnknownApiKey { .. }));
+    assert!(handle.db.list_api_keys(tenant_id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn program_versions() {
+    let handle = test_setup().await;
+    let tenant_id = TenantRecord::default().id;
+    let schema = ProgramSchema {
+        inputs: vec![Relation {
+            name: "t1".into(),
+            fields: vec![],
+        }],
+        outputs: vec![],
+    };
+    let (program_id, _) = handle
+        .db
+        .new_program(tenant_id, Uuid::now_v7(), "test1", "program desc", "v1")
+        .await
+        .unwrap();
+    handle
+        .db
+        .set_program_schema(tenant_id, program_id, schema.clone())
+        .await
+        .unwrap();
+
+    // Only code changes create new versions.
+    for _ in 0..2 {
+        handle
+            .db
+            .update_program(
+                tenant_id,
+                program_id,
+                "test1",
+                "program desc",
+                &Some("v2".to_string()),
+            )
+            .await
+            .unwrap();
+    }
+    let versions = handle
+        .db
+        .list_program_versions(tenant_id, program_id, true)
+        .await
+        .unwrap();
+    assert_eq!(2, versions.len());
+    assert_eq!(Version(1), versions[0].version);
+    assert_eq!(Some(schema.clone()), versions[0].schema);
+    assert_eq!(Some("v1".to_string()), versions[0].code);
+    assert_eq!(Version(2), versions[1].version);
+    assert_eq!(None, versions[1].schema);
+    assert_eq!(Some("v2".to_string()), versions[1].code);
+    let versions = handle
+        .db
+        .list_program_versions(tenant_id, program_id, false)
+        .await
+        .unwrap();
+    assert!(versions.iter().all(|v| v.code.is_none()));
+
+    let err = handle
+        .db
+        .get_program_version(tenant_id, program_id, Version(3))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DBError::UnknownProgramVersion { .. }));
+    let err = handle
+        .db
+        .get_program_version(tenant_id, ProgramId(Uuid::now_v7()), Version(1))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DBError::UnknownProgram { .. }));
+
+    // Rolling back restores the code as a new version queued for compilation.
+    let version = handle
+        .db
+        .rollback_program(tenant_id, program_id, Version(1))
+        .await
+        .unwrap();
+    assert_eq!(Version(3), version);
+    let program = handle
+        .db
+        .get_program_by_id(tenant_id, program_id, true)
+        .await
+        .unwrap();
+    assert_eq!(Version(3), program.version);
+    assert_eq!(Some("v1".to_string()), program.code);
+    assert_eq!(ProgramStatus::Pending, program.status);
+    let restored = handle
+        .db
+        .get_program_version(tenant_id, program_id, Version(3))
+        .await
+        .unwrap();
+    assert_eq!(Some("v1".to_string()), restored.code);
+
+    // Rolling back to the current code is a no-op.
+    let version = handle
+        .db
+        .rollback_program(tenant_id, program_id, Version(1))
+        .await
+        .unwrap();
+    assert_eq!(Version(3), version);
+
+    // The binary of version 1 can be reused for version 3 while it exists.
+    assert_eq!(
+        None,
+        handle
+            .db
+            .find_compiled_program_version(program_id, Version(3))
+            .await
+            .unwrap()
+    );
+    handle
+        .db
+        .create_compiled_binary_ref(program_id, Version(1), "dummy".to_string())
+        .await
+        .unwrap();
+    assert_eq!(
+        Some((Version(1), schema)),
+        handle
+            .db
+            .find_compiled_program_version(program_id, Version(3))
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        None,
+        handle
+            .db
+            .find_compiled_program_version(program_id, Version(2))
+            .await
+            .unwrap()
+    );
+}
+
 /// A Function that commits twice and checks the second time errors, returns
 /// revision of first commit.
 async fn commit_check(handle: &DbHandle, tenant_id: TenantId, pipeline_id: PipelineId) -> Revision {
@@ -840,6 +1618,9 @@ async fn versioning() {
         cpu_profiler: true,
         min_batch_size_records: 0,
         max_buffering_delay_usecs: 0,
+        multihost: None,
+        tracing: None,
+        restart_policy: RestartPolicy::default(),
     };
     handle
         .db
@@ -888,7 +1669,7 @@ async fn versioning() {
     };
     handle
         .db
-        .update_connector(tenant_id, connector_id1, "a", "b", &Some(config3))
+        .update_connector(tenant_id, connector_id1, "a", "b", &Some(config3.clone()))
         .await
         .unwrap();
     let r4 = commit_check(&handle, tenant_id, pipeline_id).await;
@@ -932,22 +1713,79 @@ async fn versioning() {
     // And if we change the pipeline config itself that's a change:
     handle
         .db
-        .update_pipeline(
-            tenant_id,
-            pipeline_id,
-            Some(program_id),
-            "1",
-            "2",
-            &Some(RuntimeConfig {
-                workers: gp_config.workers + 1,
-                ..gp_config
-            }),
-            &Some(vec![ac1.clone()]),
-        )
+        .update_pipeline(
+            tenant_id,
+            pipeline_id,
+            Some(program_id),
+            "1",
+            "2",
+            &Some(RuntimeConfig {
+                workers: gp_config.workers + 1,
+                ..gp_config
+            }),
+            &Some(vec![ac1.clone()]),
+        )
+        .await
+        .unwrap();
+    let r6 = commit_check(&handle, tenant_id, pipeline_id).await;
+    assert_ne!(r5, r6, "we got a new revision");
+
+    // Every revision shows up in the history of the pipeline and of the
+    // connectors it captured.
+    let history = handle
+        .db
+        .list_pipeline_history(tenant_id, pipeline_id)
+        .await
+        .unwrap();
+    assert_eq!(7, history.len());
+    assert_eq!(r1, history[0].revision);
+    assert_eq!(r6, history[6].revision);
+    assert_eq!(Version(1), history[0].program_version);
+    assert!(history[1..].iter().all(|e| e.program_version == Version(2)));
+    assert_eq!(2, history[0].pipeline.attached_connectors.len());
+    assert_eq!(1, history[6].pipeline.attached_connectors.len());
+
+    let history = handle
+        .db
+        .list_connector_history(tenant_id, connector_id1)
+        .await
+        .unwrap();
+    assert_eq!(7, history.len());
+    assert!(history.iter().all(|e| e.pipeline_id == pipeline_id));
+    assert_eq!(config1, history[0].connector.config);
+    assert_eq!(config3, history[6].connector.config);
+    let history = handle
+        .db
+        .list_connector_history(tenant_id, connector_id2)
+        .await
+        .unwrap();
+    assert_eq!(
+        vec![r1, r2, r3, r4, r5],
+        history.iter().map(|e| e.revision).collect::<Vec<_>>()
+    );
+
+    // Restoring an earlier revision makes it the last committed one; the
+    // changes made since are committed again as a new revision.
+    handle
+        .db
+        .restore_pipeline_revision(tenant_id, pipeline_id, r5)
         .await
         .unwrap();
-    let r6 = commit_check(&handle, tenant_id, pipeline_id).await;
-    assert_ne!(r5, r6, "we got a new revision");
+    let pr = handle
+        .db
+        .get_last_committed_pipeline_revision(tenant_id, pipeline_id)
+        .await
+        .unwrap();
+    assert_eq!(r5, pr.revision);
+    let err = handle
+        .db
+        .restore_pipeline_revision(tenant_id, pipeline_id, Revision(Uuid::now_v7()))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, DBError::UnknownPipeline { .. }));
+    let r7 = commit_check(&handle, tenant_id, pipeline_id).await;
+    assert_ne!(r5, r7, "we got a new revision");
+    assert_ne!(r6, r7, "we got a new revision");
 }
 
 /// Generate uuids but limits the the randomess to the first bits.
@@ -1035,6 +1873,9 @@ enum StorageAction {
     SetProgramStatusGuarded(TenantId, ProgramId, Version, ProgramStatus),
     SetProgramSchema(TenantId, ProgramId, ProgramSchema),
     DeleteProgram(TenantId, ProgramId),
+    ListProgramVersions(TenantId, ProgramId, bool),
+    GetProgramVersion(TenantId, ProgramId, Version),
+    RollbackProgram(TenantId, ProgramId, Version),
     AllPrograms,
     NextJob,
     NewPipeline(
@@ -1087,8 +1928,16 @@ enum StorageAction {
         #[proptest(strategy = "limited_option_connector()")] Option<ConnectorConfig>,
     ),
     DeleteConnector(TenantId, ConnectorId),
-    StoreApiKeyHash(TenantId, String, Vec<ApiPermission>),
+    StoreApiKeyHash(
+        TenantId,
+        #[proptest(strategy = "limited_uuid()")] Uuid,
+        String,
+        String,
+        Vec<ApiPermission>,
+    ),
     ValidateApiKey(TenantId, String),
+    ListApiKeys(TenantId),
+    DeleteApiKey(TenantId, ApiKeyId),
     CreatePipelineRevision(
         #[proptest(strategy = "limited_uuid()")] Uuid,
         TenantId,
@@ -1146,6 +1995,77 @@ fn compare_pipeline(step: usize, model: DBResult<Pipeline>, impl_: DBResult<Pipe
     }
 }
 
+// Compare everything except the timestamps, which get set inside the DB.
+fn compare_api_keys(
+    step: usize,
+    model: DBResult<Vec<ApiKeyDescr>>,
+    impl_: DBResult<Vec<ApiKeyDescr>>,
+) {
+    match (model, impl_) {
+        (Ok(mut mr), Ok(ir)) => {
+            assert_eq!(mr.len(), ir.len(), "Step({step}): API key count mismatch");
+            for (m, i) in mr.iter_mut().zip(ir.iter()) {
+                m.created = i.created;
+                m.last_used = i.last_used;
+            }
+            assert_eq!(
+                mr, ir,
+                "mismatch detected with model (left) and right (impl)"
+            );
+        }
+        (Err(me), Ok(ir)) => {
+            panic!("Step({step}): model returned error: {me:?}, but impl returned result: {ir:?}");
+        }
+        (Ok(mr), Err(ie)) => {
+            panic!("Step({step}): model returned result: {mr:?}, but impl returned error: {ie:?}");
+        }
+        (Err(me), Err(ie)) => {
+            assert_eq!(
+                me.to_string(),
+                ie.to_string(),
+                "Step({step}): Error return mismatch"
+            );
+        }
+    }
+}
+
+/// Compare program versions, ignoring the creation timestamps.
+fn compare_program_versions(
+    step: usize,
+    model: DBResult<Vec<ProgramVersionDescr>>,
+    impl_: DBResult<Vec<ProgramVersionDescr>>,
+) {
+    match (model, impl_) {
+        (Ok(mut mr), Ok(ir)) => {
+            assert_eq!(
+                mr.len(),
+                ir.len(),
+                "Step({step}): program version count mismatch"
+            );
+            for (m, i) in mr.iter_mut().zip(ir.iter()) {
+                m.created = i.created;
+            }
+            assert_eq!(
+                mr, ir,
+                "mismatch detected with model (left) and right (impl)"
+            );
+        }
+        (Err(me), Ok(ir)) => {
+            panic!("Step({step}): model returned error: {me:?}, but impl returned result: {ir:?}");
+        }
+        (Ok(mr), Err(ie)) => {
+            panic!("Step({step}): model returned result: {mr:?}, but impl returned error: {ie:?}");
+        }
+        (Err(me), Err(ie)) => {
+            assert_eq!(
+                me.to_string(),
+                ie.to_string(),
+                "Step({step}): Error return mismatch"
+            );
+        }
+    }
+}
+
 fn compare_pipeline_runtime_state(
     step: usize,
     model: DBResult<PipelineRuntimeState>,
@@ -1349,6 +2269,24 @@ fn db_impl_behaves_like_model() {
                                 let impl_response = handle.db.delete_program(tenant_id, program_id).await;
                                 check_responses(i, model_response, impl_response);
                             }
+                            StorageAction::ListProgramVersions(tenant_id, program_id, with_code) => {
+                                create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
+                                let model_response = model.list_program_versions(tenant_id, program_id, with_code).await;
+                                let impl_response = handle.db.list_program_versions(tenant_id, program_id, with_code).await;
+                                compare_program_versions(i, model_response, impl_response);
+                            }
+                            StorageAction::GetProgramVersion(tenant_id, program_id, version) => {
+                                create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
+                                let model_response = model.get_program_version(tenant_id, program_id, version).await;
+                                let impl_response = handle.db.get_program_version(tenant_id, program_id, version).await;
+                                compare_program_versions(i, model_response.map(|v| vec![v]), impl_response.map(|v| vec![v]));
+                            }
+                            StorageAction::RollbackProgram(tenant_id, program_id, version) => {
+                                create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
+                                let model_response = model.rollback_program(tenant_id, program_id, version).await;
+                                let impl_response = handle.db.rollback_program(tenant_id, program_id, version).await;
+                                check_responses(i, model_response, impl_response);
+                            }
                             StorageAction::AllPrograms => {
                                 let mut model_response = model.all_programs().await.unwrap();
                                 let mut impl_response = handle.db.all_programs().await.unwrap();
@@ -1406,6 +2344,9 @@ fn db_impl_behaves_like_model() {
                                     cpu_profiler: config.1,
                                     min_batch_size_records: config.2,
                                     max_buffering_delay_usecs: config.3,
+                                    multihost: None,
+                                    tracing: None,
+                                    restart_policy: RestartPolicy::default(),
                                 };
                                 let model_response =
                                     model.new_pipeline(tenant_id, id, program_id, &name, &description, &config, &connectors.clone()).await;
@@ -1420,6 +2361,9 @@ fn db_impl_behaves_like_model() {
                                     cpu_profiler: config.1,
                                     min_batch_size_records: config.2,
                                     max_buffering_delay_usecs: config.3,
+                                    multihost: None,
+                                    tracing: None,
+                                    restart_policy: RestartPolicy::default(),
                                 });
                                 let model_response = model
                                     .update_pipeline(tenant_id, pipeline_id, program_id, &name, &description, &config, &connectors.clone())
@@ -1490,10 +2434,10 @@ fn db_impl_behaves_like_model() {
                                 let impl_response = handle.db.delete_connector(tenant_id, connector_id).await;
                                 check_responses(i, model_response, impl_response);
                             }
-                            StorageAction::StoreApiKeyHash(tenant_id,key, permissions) => {
+                            StorageAction::StoreApiKeyHash(tenant_id, id, name, key, permissions) => {
                                 create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
-                                let model_response = model.store_api_key_hash(tenant_id, key.clone(), permissions.clone()).await;
-                                let impl_response = handle.db.store_api_key_hash(tenant_id, key.clone(), permissions.clone()).await;
+                                let model_response = model.store_api_key_hash(tenant_id, id, &name, key.clone(), permissions.clone()).await;
+                                let impl_response = handle.db.store_api_key_hash(tenant_id, id, &name, key.clone(), permissions.clone()).await;
                                 check_responses(i, model_response, impl_response);
                             },
                             StorageAction::ValidateApiKey(tenant_id,key) => {
@@ -1502,6 +2446,18 @@ fn db_impl_behaves_like_model() {
                                 let impl_response = handle.db.validate_api_key(key.clone()).await;
                                 check_responses(i, model_response, impl_response);
                             }
+                            StorageAction::ListApiKeys(tenant_id) => {
+                                create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
+                                let model_response = model.list_api_keys(tenant_id).await;
+                                let impl_response = handle.db.list_api_keys(tenant_id).await;
+                                compare_api_keys(i, model_response, impl_response);
+                            }
+                            StorageAction::DeleteApiKey(tenant_id, api_key_id) => {
+                                create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
+                                let model_response = model.delete_api_key(tenant_id, api_key_id).await;
+                                let impl_response = handle.db.delete_api_key(tenant_id, api_key_id).await;
+                                check_responses(i, model_response, impl_response);
+                            }
                             StorageAction::CreatePipelineRevision(new_revision_id, tenant_id, pipeline_id) => {
                                 create_tenants_if_not_exists(&model, &handle, tenant_id).await.unwrap();
                                 let model_response = model.create_pipeline_revision(new_revision_id, tenant_id, pipeline_id).await;
@@ -1530,17 +2486,91 @@ fn db_impl_behaves_like_model() {
 type ProgramData = (ProgramDescr, SystemTime);
 
 /// Our model of the database (uses btrees for tables).
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 struct DbModel {
     // `programs` Format is: (program, code, created)
     pub programs: BTreeMap<(TenantId, ProgramId), ProgramData>,
+    // `program_versions` Format is: all versions of a program, oldest first
+    pub program_versions: BTreeMap<(TenantId, ProgramId), Vec<ProgramVersionDescr>>,
     pub pipelines: BTreeMap<(TenantId, PipelineId), Pipeline>,
     pub history: BTreeMap<(TenantId, PipelineId), PipelineRevision>,
-    pub api_keys: BTreeMap<String, (TenantId, Vec<ApiPermission>)>,
+    // `api_keys` Format is: hash -> (tenant, key metadata)
+    pub api_keys: BTreeMap<String, (TenantId, ApiKeyDescr)>,
     pub connectors: BTreeMap<(TenantId, ConnectorId), ConnectorDescr>,
     pub tenants: BTreeMap<TenantId, TenantRecord>,
 }
 
+impl DbModel {
+    /// Model of `ProjectDB::lock_program_version_with`.
+    fn check_program_version(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        expected_version: Version,
+    ) -> DBResult<()> {
+        let version = self
+            .programs
+            .get(&(tenant_id, program_id))
+            .ok_or(DBError::UnknownProgram { program_id })?
+            .0
+            .version;
+        if version != expected_version {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "program {program_id} was modified concurrently (version {version}, expected {expected_version})"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Model of `ProjectDB::lock_pipeline_version_with`.
+    fn check_pipeline_version(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+        expected_version: Version,
+    ) -> DBResult<()> {
+        let version = self
+            .pipelines
+            .get(&(tenant_id, pipeline_id))
+            .ok_or(DBError::UnknownPipeline { pipeline_id })?
+            .descriptor
+            .version;
+        if version != expected_version {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "pipeline {pipeline_id} was modified concurrently (version {version}, expected {expected_version})"
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Model of `ProjectDB::check_pipeline_shutdown_with`.
+    fn check_pipeline_shutdown(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> DBResult<()> {
+        let state = &self
+            .pipelines
+            .get(&(tenant_id, pipeline_id))
+            .ok_or(DBError::UnknownPipeline { pipeline_id })?
+            .state;
+        if state.desired_status != PipelineStatus::Shutdown
+            || state.current_status != PipelineStatus::Shutdown
+        {
+            return Err(DBError::ApplyConflict {
+                reason: format!(
+                    "pipeline {pipeline_id} must be shut down before it can be deleted"
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Storage for Mutex<DbModel> {
     async fn list_programs(
@@ -1599,6 +2629,15 @@ impl Storage for Mutex<DbModel> {
                 SystemTime::now(),
             ),
         );
+        s.program_versions.insert(
+            (tenant_id, program_id),
+            vec![ProgramVersionDescr {
+                version,
+                created: Utc::now(),
+                schema: None,
+                code: Some(program_code.to_owned()),
+            }],
+        );
 
         Ok((program_id, version))
     }
@@ -1625,7 +2664,12 @@ impl Storage for Mutex<DbModel> {
             return Err(DBError::DuplicateName);
         }
 
-        s.programs
+        let DbModel {
+            programs,
+            program_versions,
+            ..
+        } = &mut *s;
+        programs
             .get_mut(&(tenant_id, program_id))
             .map(|(p, _e)| {
                 let cur_code = p.code.clone().unwrap();
@@ -1637,6 +2681,15 @@ impl Storage for Mutex<DbModel> {
                         p.version.0 += 1;
                         p.schema = None;
                         p.status = ProgramStatus::None;
+                        program_versions
+                            .entry((tenant_id, program_id))
+                            .or_default()
+                            .push(ProgramVersionDescr {
+                                version: p.version,
+                                created: Utc::now(),
+                                schema: None,
+                                code: program_code.to_owned(),
+                            });
                     }
                 }
                 p.version
@@ -1723,8 +2776,19 @@ impl Storage for Mutex<DbModel> {
         schema: ProgramSchema,
     ) -> DBResult<()> {
         let mut s = self.lock().await;
-        let _r = s.programs.get_mut(&(tenant_id, program_id)).map(|(p, _)| {
-            p.schema = Some(schema);
+        let DbModel {
+            programs,
+            program_versions,
+            ..
+        } = &mut *s;
+        let _r = programs.get_mut(&(tenant_id, program_id)).map(|(p, _)| {
+            p.schema = Some(schema.clone());
+            if let Some(v) = program_versions
+                .get_mut(&(tenant_id, program_id))
+                .and_then(|versions| versions.iter_mut().find(|v| v.version == p.version))
+            {
+                v.schema = Some(schema);
+            }
         });
 
         Ok(())
@@ -1750,11 +2814,96 @@ impl Storage for Mutex<DbModel> {
                 .remove(&(tenant_id, program_id))
                 .map(|_| ())
                 .ok_or(DBError::UnknownProgram { program_id })?;
+            s.program_versions.remove(&(tenant_id, program_id));
 
             Ok(())
         }
     }
 
+    async fn list_program_versions(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        with_code: bool,
+    ) -> DBResult<Vec<ProgramVersionDescr>> {
+        let s = self.lock().await;
+        s.program_versions
+            .get(&(tenant_id, program_id))
+            .map(|versions| {
+                versions
+                    .iter()
+                    .map(|v| ProgramVersionDescr {
+                        code: if with_code { v.code.clone() } else { None },
+                        ..v.clone()
+                    })
+                    .collect()
+            })
+            .ok_or(DBError::UnknownProgram { program_id })
+    }
+
+    async fn get_program_version(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> DBResult<ProgramVersionDescr> {
+        let s = self.lock().await;
+        s.program_versions
+            .get(&(tenant_id, program_id))
+            .ok_or(DBError::UnknownProgram { program_id })?
+            .iter()
+            .find(|v| v.version == version)
+            .cloned()
+            .ok_or(DBError::UnknownProgramVersion {
+                program_id,
+                version,
+            })
+    }
+
+    async fn rollback_program(
+        &self,
+        tenant_id: TenantId,
+        program_id: ProgramId,
+        version: Version,
+    ) -> DBResult<Version> {
+        let mut s = self.lock().await;
+        let DbModel {
+            programs,
+            program_versions,
+            ..
+        } = &mut *s;
+        let (p, t) = programs
+            .get_mut(&(tenant_id, program_id))
+            .ok_or(DBError::UnknownProgram { program_id })?;
+        let versions = program_versions.entry((tenant_id, program_id)).or_default();
+        let code = versions
+            .iter()
+            .find(|v| v.version == version)
+            .ok_or(DBError::UnknownProgramVersion {
+                program_id,
+                version,
+            })?
+            .code
+            .clone();
+
+        if code == p.code {
+            return Ok(p.version);
+        }
+        p.code = code.clone();
+        p.version.0 += 1;
+        p.schema = None;
+        p.status = ProgramStatus::Pending;
+        *t = SystemTime::now();
+        versions.push(ProgramVersionDescr {
+            version: p.version,
+            created: Utc::now(),
+            schema: None,
+            code,
+        });
+
+        Ok(p.version)
+    }
+
     async fn all_programs(&self) -> DBResult<Vec<(TenantId, ProgramDescr)>> {
         let s = self.lock().await;
         Ok(s.programs
@@ -1780,9 +2929,15 @@ impl Storage for Mutex<DbModel> {
         &self,
     ) -> DBResult<Option<(super::TenantId, super::ProgramId, super::Version)>> {
         let s = self.lock().await;
+        let compiling = |tenant_id: TenantId| {
+            s.programs
+                .iter()
+                .filter(|((tid, _), v)| *tid == tenant_id && v.0.status.is_compiling())
+                .count()
+        };
         let mut values: Vec<(&(TenantId, ProgramId), &ProgramData)> =
             Vec::from_iter(s.programs.iter());
-        values.sort_by(|(_, t1), (_, t2)| t1.1.cmp(&t2.1));
+        values.sort_by_key(|(k, v)| (compiling(k.0), v.1));
 
         values
             .iter()
@@ -1791,6 +2946,26 @@ impl Storage for Mutex<DbModel> {
             .unwrap_or(Ok(None))
     }
 
+    async fn claim_program_compilation(
+        &self,
+        tenant_id: TenantId,
+        program_id: super::ProgramId,
+        expected_version: super::Version,
+    ) -> DBResult<bool> {
+        let mut s = self.lock().await;
+        Ok(s.programs
+            .get_mut(&(tenant_id, program_id))
+            .map(|(p, t)| {
+                let claimed = p.version == expected_version && p.status == ProgramStatus::Pending;
+                if claimed {
+                    p.status = ProgramStatus::CompilingSql;
+                    *t = SystemTime::now();
+                }
+                claimed
+            })
+            .unwrap_or(false))
+    }
+
     async fn create_pipeline_revision(
         &self,
         new_revision_id: Uuid,
@@ -1976,6 +3151,10 @@ impl Storage for Mutex<DbModel> {
                     status_since: Utc::now(),
                     error: None,
                     created: Utc::now(),
+                    restart_count: 0,
+                    last_exit_reason: None,
+                    log_location: "".to_string(),
+                    running_revision: None,
                 },
             },
         );
@@ -2095,6 +3274,15 @@ impl Storage for Mutex<DbModel> {
         todo!()
     }
 
+    // The model only keeps the last revision of each pipeline.
+    async fn list_pipeline_history(
+        &self,
+        _tenant_id: TenantId,
+        _pipeline_id: PipelineId,
+    ) -> DBResult<Vec<PipelineHistoryEntry>> {
+        todo!("Unimplemented");
+    }
+
     async fn delete_pipeline(
         &self,
         tenant_id: TenantId,
@@ -2160,6 +3348,10 @@ impl Storage for Mutex<DbModel> {
         pipeline.state.status_since = state.status_since;
         pipeline.state.error = state.error.clone();
         pipeline.state.created = state.created;
+        pipeline.state.restart_count = state.restart_count;
+        pipeline.state.last_exit_reason = state.last_exit_reason.clone();
+        pipeline.state.log_location = state.log_location.clone();
+        pipeline.state.running_revision = state.running_revision;
 
         Ok(())
     }
@@ -2341,36 +3533,281 @@ impl Storage for Mutex<DbModel> {
         Ok(())
     }
 
+    async fn list_connector_history(
+        &self,
+        _tenant_id: TenantId,
+        _connector_id: ConnectorId,
+    ) -> DBResult<Vec<ConnectorHistoryEntry>> {
+        todo!("Unimplemented");
+    }
+
+    async fn commit_project_changes(
+        &self,
+        tenant_id: TenantId,
+        changes: &[ProjectChange],
+    ) -> DBResult<()> {
+        // Apply the changes one by one and roll back to a snapshot of the
+        // model if one of them fails.
+        let snapshot = self.lock().await.clone();
+        for change in changes {
+            let res = match change {
+                ProjectChange::NewProgram {
+                    program_id,
+                    program,
+                } => self
+                    .new_program(
+                        tenant_id,
+                        program_id.0,
+                        &program.name,
+                        &program.description,
+                        &program.code,
+                    )
+                    .await
+                    .map(|_| ()),
+                ProjectChange::UpdateProgram {
+                    program_id,
+                    version,
+                    name,
+                    description,
+                    code,
+                } => {
+                    let checked =
+                        self.lock()
+                            .await
+                            .check_program_version(tenant_id, *program_id, *version);
+                    match checked {
+                        Ok(()) => self
+                            .update_program(tenant_id, *program_id, name, description, code)
+                            .await
+                            .map(|_| ()),
+                        Err(e) => Err(e),
+                    }
+                }
+                ProjectChange::DeleteProgram {
+                    program_id,
+                    version,
+                } => {
+                    let checked =
+                        self.lock()
+                            .await
+                            .check_program_version(tenant_id, *program_id, *version);
+                    match checked {
+                        Ok(()) => self.delete_program(tenant_id, *program_id).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                ProjectChange::CompileProgram { program_id } => {
+                    match self.get_program_by_id(tenant_id, *program_id, false).await {
+                        Ok(program) => {
+                            self.prepare_program_for_compilation(
+                                tenant_id,
+                                *program_id,
+                                program.version,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                ProjectChange::NewConnector {
+                    connector_id,
+                    connector,
+                } => self
+                    .new_connector(
+                        tenant_id,
+                        connector_id.0,
+                        &connector.name,
+                        &connector.description,
+                        &connector.config,
+                    )
+                    .await
+                    .map(|_| ()),
+                ProjectChange::UpdateConnector {
+                    connector_id,
+                    connector,
+                } => {
+                    self.update_connector(
+                        tenant_id,
+                        *connector_id,
+                        &connector.name,
+                        &connector.description,
+                        &Some(connector.config.clone()),
+                    )
+                    .await
+                }
+                ProjectChange::DeleteConnector { connector_id } => {
+                    self.delete_connector(tenant_id, *connector_id).await
+                }
+                ProjectChange::NewPipeline {
+                    pipeline_id,
+                    program_id,
+                    name,
+                    description,
+                    config,
+                    connectors,
+                } => self
+                    .new_pipeline(
+                        tenant_id,
+                        pipeline_id.0,
+                        *program_id,
+                        name,
+                        description,
+                        config,
+                        &Some(connectors.clone()),
+                    )
+                    .await
+                    .map(|_| ()),
+                ProjectChange::UpdatePipeline {
+                    pipeline_id,
+                    version,
+                    program_id,
+                    name,
+                    description,
+                    config,
+                    connectors,
+                } => {
+                    let checked =
+                        self.lock()
+                            .await
+                            .check_pipeline_version(tenant_id, *pipeline_id, *version);
+                    match checked {
+                        Ok(()) => self
+                            .update_pipeline(
+                                tenant_id,
+                                *pipeline_id,
+                                *program_id,
+                                name,
+                                description,
+                                &Some(config.clone()),
+                                &Some(connectors.clone()),
+                            )
+                            .await
+                            .map(|_| ()),
+                        Err(e) => Err(e),
+                    }
+                }
+                ProjectChange::DeletePipeline {
+                    pipeline_id,
+                    version,
+                } => {
+                    let checked = {
+                        let s = self.lock().await;
+                        s.check_pipeline_version(tenant_id, *pipeline_id, *version)
+                            .and_then(|()| s.check_pipeline_shutdown(tenant_id, *pipeline_id))
+                    };
+                    match checked {
+                        Ok(()) => self
+                            .delete_pipeline(tenant_id, *pipeline_id)
+                            .await
+                            .map(|_| ()),
+                        Err(e) => Err(e),
+                    }
+                }
+                ProjectChange::CommitPipelineRevision { pipeline_id } => {
+                    match self
+                        .create_pipeline_revision(Uuid::now_v7(), tenant_id, *pipeline_id)
+                        .await
+                    {
+                        Ok(_) | Err(DBError::RevisionNotChanged) => Ok(()),
+                        Err(e) => Err(e),
+                    }
+                }
+            };
+            if let Err(e) = res {
+                *self.lock().await = snapshot;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
     async fn store_api_key_hash(
         &self,
         tenant_id: TenantId,
+        id: Uuid,
+        name: &str,
         key: String,
         permissions: Vec<ApiPermission>,
-    ) -> DBResult<()> {
+    ) -> DBResult<ApiKeyId> {
         let mut s = self.lock().await;
         let mut hasher = sha::Sha256::new();
         hasher.update(key.as_bytes());
         let hash = openssl::base64::encode_block(&hasher.finish());
-        if let btree_map::Entry::Vacant(e) = s.api_keys.entry(hash) {
-            e.insert((tenant_id, permissions));
-            Ok(())
-        } else {
-            Err(DBError::duplicate_key())
+        if s.api_keys.contains_key(&hash) {
+            return Err(DBError::duplicate_key());
         }
+        if s.api_keys
+            .values()
+            .any(|(_, k)| k.api_key_id == ApiKeyId(id))
+        {
+            return Err(DBError::unique_key_violation("api_key_id_key"));
+        }
+        if s.api_keys
+            .values()
+            .any(|(t, k)| *t == tenant_id && k.name == name)
+        {
+            return Err(DBError::DuplicateName);
+        }
+        s.api_keys.insert(
+            hash,
+            (
+                tenant_id,
+                ApiKeyDescr {
+                    api_key_id: ApiKeyId(id),
+                    name: name.to_owned(),
+                    scopes: permissions,
+                    created: Utc::now(),
+                    last_used: None,
+                },
+            ),
+        );
+        Ok(ApiKeyId(id))
     }
 
     async fn validate_api_key(&self, key: String) -> DBResult<(TenantId, Vec<ApiPermission>)> {
-        let s = self.lock().await;
+        let mut s = self.lock().await;
         let mut hasher = sha::Sha256::new();
         hasher.update(key.as_bytes());
         let hash = openssl::base64::encode_block(&hasher.finish());
-        let record = s.api_keys.get(&hash);
+        let record = s.api_keys.get_mut(&hash);
         match record {
-            Some(record) => Ok((record.0, record.1.clone())),
+            Some((tenant_id, descr)) => {
+                let now = Utc::now();
+                if descr.last_used.map_or(true, |t| {
+                    (now - t).num_seconds() >= API_KEY_LAST_USED_RESOLUTION
+                }) {
+                    descr.last_used = Some(now);
+                }
+                Ok((*tenant_id, descr.scopes.clone()))
+            }
             None => Err(DBError::InvalidKey),
         }
     }
 
+    async fn list_api_keys(&self, tenant_id: TenantId) -> DBResult<Vec<ApiKeyDescr>> {
+        let s = self.lock().await;
+        let mut keys: Vec<ApiKeyDescr> = s
+            .api_keys
+            .values()
+            .filter(|(t, _)| *t == tenant_id)
+            .map(|(_, k)| k.clone())
+            .collect();
+        keys.sort_by_key(|k| k.api_key_id);
+        Ok(keys)
+    }
+
+    async fn delete_api_key(&self, tenant_id: TenantId, api_key_id: ApiKeyId) -> DBResult<()> {
+        let mut s = self.lock().await;
+        let len = s.api_keys.len();
+        s.api_keys
+            .retain(|_, (t, k)| !(*t == tenant_id && k.api_key_id == api_key_id));
+        if s.api_keys.len() < len {
+            Ok(())
+        } else {
+            Err(DBError::UnknownApiKey { api_key_id })
+        }
+    }
+
     async fn get_or_create_tenant_id(
         &self,
         _tenant_name: String,
@@ -48,9 +48,19 @@ pub enum ManagerError {
         value: String,
         error: String,
     },
+    InvalidVersionParam {
+        value: String,
+        error: String,
+    },
     InvalidPipelineAction {
         action: String,
     },
+    InvalidLogLevel {
+        level: String,
+    },
+    PermissionDenied {
+        operation: String,
+    },
     DBError {
         #[serde(flatten)]
         db_error: DBError,
@@ -73,6 +83,9 @@ pub enum ManagerError {
     RustCompilerError {
         error: String,
     },
+    BundleSerializationError {
+        error: String,
+    },
 }
 
 impl ManagerError {
@@ -155,8 +168,17 @@ impl Display for ManagerError {
             Self::InvalidUuidParam { value, error } => {
                 write!(f, "Invalid UUID string '{value}': '{error}'")
             }
+            Self::InvalidVersionParam { value, error } => {
+                write!(f, "Invalid version number '{value}': '{error}'")
+            }
             Self::InvalidPipelineAction { action } => {
-                write!(f, "Invalid pipeline action '{action}'; valid actions are: 'deploy', 'start', 'pause', or 'shutdown'")
+                write!(f, "Invalid pipeline action '{action}'; valid actions are: 'deploy', 'start', 'pause', 'shutdown', or 'upgrade'")
+            }
+            Self::InvalidLogLevel { level } => {
+                write!(f, "Invalid log level '{level}'; valid levels are: 'error', 'warn', 'info', 'debug', or 'trace'")
+            }
+            Self::PermissionDenied { operation } => {
+                write!(f, "The credentials used do not permit {operation}")
             }
             Self::DBError { db_error } => db_error.fmt(f),
             Self::RunnerError { runner_error } => runner_error.fmt(f),
@@ -171,6 +193,9 @@ impl Display for ManagerError {
             Self::RustCompilerError { error } => {
                 write!(f, "Error compiling generated Rust code: {error}")
             }
+            Self::BundleSerializationError { error } => {
+                write!(f, "Error serializing project bundle: {error}")
+            }
         }
     }
 }
@@ -183,12 +208,16 @@ impl ResponseError for ManagerError {
             Self::ConnectorNotSpecified => StatusCode::BAD_REQUEST,
             Self::MissingUrlEncodedParam { .. } => StatusCode::BAD_REQUEST,
             Self::InvalidUuidParam { .. } => StatusCode::BAD_REQUEST,
+            Self::InvalidVersionParam { .. } => StatusCode::BAD_REQUEST,
             Self::InvalidPipelineAction { .. } => StatusCode::BAD_REQUEST,
+            Self::InvalidLogLevel { .. } => StatusCode::BAD_REQUEST,
+            Self::PermissionDenied { .. } => StatusCode::FORBIDDEN,
             Self::DBError { db_error } => db_error.status_code(),
             Self::RunnerError { runner_error } => runner_error.status_code(),
             Self::IoError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Self::InvalidProgramSchema { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Self::RustCompilerError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::BundleSerializationError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
@@ -205,12 +234,16 @@ impl DetailedError for ManagerError {
             Self::ConnectorNotSpecified => Cow::from("ConnectorNotSpecified"),
             Self::MissingUrlEncodedParam { .. } => Cow::from("MissingUrlEncodedParam"),
             Self::InvalidUuidParam { .. } => Cow::from("InvalidUuidParam"),
+            Self::InvalidVersionParam { .. } => Cow::from("InvalidVersionParam"),
             Self::InvalidPipelineAction { .. } => Cow::from("InvalidPipelineAction"),
+            Self::InvalidLogLevel { .. } => Cow::from("InvalidLogLevel"),
+            Self::PermissionDenied { .. } => Cow::from("PermissionDenied"),
             Self::DBError { db_error } => db_error.error_code(),
             Self::RunnerError { runner_error } => runner_error.error_code(),
             Self::IoError { .. } => Cow::from("ManagerIoError"),
             Self::InvalidProgramSchema { .. } => Cow::from("InvalidProgramSchema"),
             Self::RustCompilerError { .. } => Cow::from("RustCompilerError"),
+            Self::BundleSerializationError { .. } => Cow::from("BundleSerializationError"),
         }
     }
 
@@ -218,6 +251,7 @@ impl DetailedError for ManagerError {
         match self {
             Self::DBError { db_error } => db_error.log_level(),
             Self::RunnerError { runner_error } => runner_error.log_level(),
+            Self::PermissionDenied { .. } => Level::Info,
             _ => Level::Error,
         }
     }
@@ -72,12 +72,17 @@ async fn initialize_local_pipeline_manager_instance() -> TempDir {
         precompile: true,
         binary_ref_host: "127.0.0.1".to_string(),
         binary_ref_port: 9090,
+        binary_cache_max_size: 10 * 1024 * 1024 * 1024,
+        max_parallel_compilations: 1,
     }
     .canonicalize()
     .unwrap();
     let local_runner_config = LocalRunnerConfig {
         runner_working_directory: workdir.to_owned(),
         pipeline_host: "127.0.0.1".to_owned(),
+        pipeline_hosts: 1,
+        runner_logs_port: 9091,
+        pipeline_log_max_size: 10 * 1024 * 1024,
     }
     .canonicalize()
     .unwrap();
@@ -116,7 +121,9 @@ async fn initialize_local_pipeline_manager_instance() -> TempDir {
                 });
                 let db_clone = db.clone();
                 let _local_runner = tokio::spawn(async move {
-                    crate::local_runner::run(db_clone, &local_runner_config.clone()).await;
+                    crate::local_runner::run(db_clone, &local_runner_config.clone())
+                        .await
+                        .unwrap();
                 });
                 // The api-server blocks forever
                 let _api_server = crate::api::run(db, api_config).await.unwrap();
@@ -4,6 +4,7 @@ mod error;
 #[cfg(test)]
 #[cfg(feature = "integration-test")]
 mod integration_test;
+mod pipeline_logs;
 
 pub mod api;
 pub mod compiler;
@@ -6,24 +6,31 @@ use crate::runner::RunnerApi;
 use crate::{
     api::ManagerError,
     auth::TenantId,
-    config::LocalRunnerConfig,
+    config::{LocalRunnerConfig, PipelineSlot},
     db::{
         storage::Storage, DBError, PipelineId, PipelineRevision, PipelineRuntimeState,
-        PipelineStatus, ProjectDB,
+        PipelineStatus, ProjectDB, Revision,
     },
+    pipeline_logs::{capture_output, pipeline_logs, RotatingLog},
     runner::RunnerError,
 };
-use actix_web::http::{Method, StatusCode};
+use actix_web::{
+    http::{Method, StatusCode},
+    web, App, HttpServer,
+};
 use chrono::{DateTime, Utc};
-use dbsp_adapters::ErrorResponse;
+use dbsp_adapters::{ErrorResponse, HostConfig, MultihostConfig, PipelineConfig};
 use log::{error, info, trace};
 use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use std::{
     collections::BTreeMap,
+    io::Result as IOResult,
+    net::{Ipv4Addr, SocketAddr, TcpListener},
+    path::Path,
     process::Stdio,
     process::{Child, Command},
-    sync::Arc,
+    sync::{Arc, Mutex as StdMutex},
 };
 use tokio::io::AsyncWriteExt;
 use tokio::{
@@ -31,7 +38,7 @@ use tokio::{
     fs::{create_dir_all, remove_dir_all},
     spawn,
     sync::Mutex,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use tokio::{sync::Notify, time::timeout};
 
@@ -39,14 +46,51 @@ use tokio::{sync::Notify, time::timeout};
 /// on `drop`.
 struct PipelineHandle {
     pipeline_process: Child,
+
+    /// Processes that run the remaining hosts of a multihost pipeline.
+    /// The process of the first host (`pipeline_process`) serves the
+    /// pipeline's HTTP API and forwards commands to the other hosts.
+    follower_processes: Vec<Child>,
 }
 
 impl Drop for PipelineHandle {
     fn drop(&mut self) {
         let _ = self.pipeline_process.kill();
+        for process in self.follower_processes.iter_mut() {
+            let _ = process.kill();
+        }
     }
 }
 
+/// Progress of an upgrade of a running pipeline to a new revision (see
+/// [`PipelineAutomaton::advance_upgrade`]).
+enum Upgrade {
+    /// The new instance has been started from `slot`; waiting for its HTTP
+    /// server to come online.
+    Provisioning {
+        handle: PipelineHandle,
+        slot: PipelineSlot,
+        revision: Revision,
+        since: Instant,
+    },
+    /// Waiting for the new instance at `location` to initialize its
+    /// connectors.
+    Initializing {
+        handle: PipelineHandle,
+        slot: PipelineSlot,
+        revision: Revision,
+        location: String,
+        since: Instant,
+    },
+    /// The new instance has taken over; waiting for the old instance,
+    /// started from `slot`, to exit.
+    Retiring {
+        handle: Option<PipelineHandle>,
+        slot: PipelineSlot,
+        since: Instant,
+    },
+}
+
 /// Pipeline automaton monitors the runtime state of a single pipeline
 /// and continually reconciles desired and actual state.
 ///
@@ -55,6 +99,12 @@ struct PipelineAutomaton {
     pipeline_id: PipelineId,
     tenant_id: TenantId,
     pipeline_process: Option<PipelineHandle>,
+    /// Slot the running instance of the pipeline was started from.
+    slot: PipelineSlot,
+    /// Upgrade of the pipeline in progress, if any.
+    upgrade: Option<Upgrade>,
+    /// Log file that captures the output of all instances of the pipeline.
+    log: Arc<StdMutex<RotatingLog>>,
     config: Arc<LocalRunnerConfig>,
     db: Arc<Mutex<ProjectDB>>,
     notifier: Arc<Notify>,
@@ -98,6 +148,12 @@ impl PipelineAutomaton {
             pipeline_id,
             tenant_id,
             pipeline_process: None,
+            slot: PipelineSlot::Blue,
+            upgrade: None,
+            log: Arc::new(StdMutex::new(RotatingLog::new(
+                config.pipeline_log_path(pipeline_id),
+                config.pipeline_log_max_size,
+            ))),
             config: config.clone(),
             db,
             notifier,
@@ -139,6 +195,7 @@ impl PipelineAutomaton {
                     DBError::UnknownPipeline { pipeline_id } => {
                         // Pipeline deletions should not lead to errors in the logs.
                         info!("Pipeline {pipeline_id} does not exist. Shutting down pipeline automaton.");
+                        self.log.lock().unwrap().remove();
                         return Ok(());
                     }
                     _ => return Err(e.into()),
@@ -146,21 +203,38 @@ impl PipelineAutomaton {
             }
             let mut pipeline = result.unwrap();
 
+            // Upgrades only proceed while the pipeline is steadily running or
+            // paused.
+            if self.upgrade.is_some()
+                && (pipeline.current_status != pipeline.desired_status
+                    || !matches!(
+                        pipeline.current_status,
+                        PipelineStatus::Running | PipelineStatus::Paused
+                    ))
+            {
+                self.cancel_upgrade().await;
+            }
+
             // Handle deployment request.
             if pipeline.current_status == PipelineStatus::Shutdown
                 && pipeline.desired_status != PipelineStatus::Shutdown
             {
                 self.update_pipeline_status(&mut pipeline, PipelineStatus::Provisioning, None)
                     .await;
+                pipeline.log_location = format!(
+                    "http://{}:{}/logs/{}",
+                    self.config.pipeline_host, self.config.runner_logs_port, self.pipeline_id
+                );
                 let revision = db
                     .get_last_committed_pipeline_revision(self.tenant_id, self.pipeline_id)
                     .await?;
+                pipeline.running_revision = Some(revision.revision);
                 db.update_pipeline_runtime_state(self.tenant_id, self.pipeline_id, &pipeline)
                     .await?;
                 // txn.commit();
                 drop(db);
 
-                match self.start(revision).await {
+                match self.start(revision, self.slot).await {
                     Ok(child) => {
                         self.pipeline_process = Some(child);
                     }
@@ -180,7 +254,7 @@ impl PipelineAutomaton {
                 // Poll its port file.  On success, go to `Initializing` state.
                 (PipelineStatus::Provisioning, PipelineStatus::Running)
                 | (PipelineStatus::Provisioning, PipelineStatus::Paused) => {
-                    match self.read_pipeline_port_file().await {
+                    match self.read_pipeline_port_file(self.slot).await {
                         Ok(Some(port)) => {
                             self.update_pipeline_status(
                                 &mut pipeline,
@@ -401,8 +475,10 @@ impl PipelineAutomaton {
                     .await
                     {
                         Err(e) => {
-                            // Cannot reach the pipeline.
-                            self.force_kill_pipeline(&mut pipeline, Some(e)).await?;
+                            // Cannot reach the pipeline.  Report why if its process
+                            // is gone.
+                            let error = self.process_exit_error().unwrap_or(e);
+                            self.force_kill_pipeline(&mut pipeline, Some(error)).await?;
                         }
                         Ok((status, body)) => {
                             if !status.is_success() {
@@ -464,6 +540,10 @@ impl PipelineAutomaton {
                                         pipeline_id: self.pipeline_id,
                                         error: format!("Pipeline reported unexpected status '{state}', expected 'Paused' or 'Running'")
                                     })).await?;
+                                } else if pipeline.current_status == pipeline.desired_status {
+                                    self.reset_restart_count_if_healthy(&mut pipeline).await?;
+                                    poll_timeout =
+                                        self.advance_upgrade(&mut pipeline, &body).await?;
                                 }
                             }
                         }
@@ -477,7 +557,13 @@ impl PipelineAutomaton {
                         .await;
                     self.update_pipeline_runtime_state(&pipeline).await?;
                 }
-                (PipelineStatus::Failed | PipelineStatus::Shutdown, _) => {}
+                // The pipeline failed but the user still wants it to run.  Restart it
+                // after a backoff period if its restart policy allows.
+                (PipelineStatus::Failed, PipelineStatus::Running)
+                | (PipelineStatus::Failed, PipelineStatus::Paused) => {
+                    poll_timeout = self.restart_if_allowed(&mut pipeline).await?;
+                }
+                (PipelineStatus::Shutdown, _) => {}
                 _ => {
                     error!(
                         "Unexpected current/desired pipeline status combination {:?}/{:?}",
@@ -496,6 +582,8 @@ impl PipelineAutomaton {
     ) {
         pipeline.set_current_status(status, error.clone());
         if status == PipelineStatus::Shutdown {
+            pipeline.restart_count = 0;
+            pipeline.running_revision = None;
             match remove_dir_all(self.config.pipeline_dir(self.pipeline_id)).await {
                 Ok(_) => (),
                 Err(e) => {
@@ -530,7 +618,8 @@ impl PipelineAutomaton {
     ///
     /// If the desired state of the pipeline is `Paused` or `Running`,
     /// places pipeline in the `Failed` state to avoid instant automatic
-    /// restart.  Otherwise (the desired state was `Shutdown`), set the
+    /// restart; the pipeline is restarted from there if its restart policy
+    /// allows.  Otherwise (the desired state was `Shutdown`), set the
     /// state of the pipeline to `Shutdown`.
     async fn force_kill_pipeline<E>(
         &mut self,
@@ -581,8 +670,25 @@ impl PipelineAutomaton {
         self.update_pipeline_runtime_state(pipeline).await
     }
 
-    async fn read_pipeline_port_file(&self) -> Result<Option<u16>, ManagerError> {
-        let port_file_path = self.config.port_file_path(self.pipeline_id);
+    /// Error describing how the pipeline process exited, if it did.
+    fn process_exit_error(&mut self) -> Option<RunnerError> {
+        let status = self
+            .pipeline_process
+            .as_mut()?
+            .pipeline_process
+            .try_wait()
+            .ok()??;
+        Some(RunnerError::PipelineProcessExited {
+            pipeline_id: self.pipeline_id,
+            status: status.to_string(),
+        })
+    }
+
+    async fn read_pipeline_port_file(
+        &self,
+        slot: PipelineSlot,
+    ) -> Result<Option<u16>, ManagerError> {
+        let port_file_path = self.config.port_file_path(self.pipeline_id, slot);
 
         match fs::read_to_string(port_file_path).await {
             Ok(port) => {
@@ -599,36 +705,523 @@ impl PipelineAutomaton {
         }
     }
 
-    /// Start the pipeline process.
-    async fn start(&self, pr: PipelineRevision) -> Result<PipelineHandle, ManagerError> {
+    /// Determine the slot of the instance that serves the pipeline at its
+    /// current location.
+    async fn running_slot(&self, pipeline: &PipelineRuntimeState) -> PipelineSlot {
+        for slot in [PipelineSlot::Blue, PipelineSlot::Green] {
+            if let Ok(Some(port)) = self.read_pipeline_port_file(slot).await {
+                if pipeline.location == format!("{}:{port}", self.config.pipeline_host) {
+                    return slot;
+                }
+            }
+        }
+        PipelineSlot::Blue
+    }
+
+    /// Restart a failed pipeline once the backoff period of its restart
+    /// policy has expired, unless the policy is exhausted.
+    ///
+    /// Returns how long to wait before polling the pipeline again.
+    async fn restart_if_allowed(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+    ) -> Result<Duration, ManagerError> {
+        let revision = self
+            .db
+            .lock()
+            .await
+            .get_last_committed_pipeline_revision(self.tenant_id, self.pipeline_id)
+            .await?;
+        let policy = &revision.pipeline.config.restart_policy;
+        if !policy.allows_restart(pipeline.restart_count) {
+            return Ok(Self::DEFAULT_PIPELINE_POLL_PERIOD);
+        }
+        let backoff = policy.backoff(pipeline.restart_count);
+        if !Self::timeout_expired(pipeline.status_since, backoff) {
+            return Ok(backoff.min(Self::DEFAULT_PIPELINE_POLL_PERIOD));
+        }
+
+        info!(
+            "Restarting failed pipeline {} (restart {})",
+            self.pipeline_id,
+            pipeline.restart_count + 1
+        );
+        pipeline.restart_count += 1;
+        pipeline.last_exit_reason = pipeline.error.clone();
+        self.update_pipeline_status(pipeline, PipelineStatus::Provisioning, None)
+            .await;
+        pipeline.running_revision = Some(revision.revision);
+        self.update_pipeline_runtime_state(pipeline).await?;
+
+        match self.start(revision, self.slot).await {
+            Ok(child) => {
+                self.pipeline_process = Some(child);
+            }
+            Err(e) => {
+                self.force_kill_pipeline(pipeline, Some(e)).await?;
+            }
+        }
+        Ok(Self::PROVISIONING_POLL_PERIOD)
+    }
+
+    /// Reset the restart count of a restarted pipeline once it has been up
+    /// for the `reset_after_ms` period of its restart policy, so that only
+    /// failures in quick succession exhaust the policy.
+    async fn reset_restart_count_if_healthy(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+    ) -> Result<(), ManagerError> {
+        if pipeline.restart_count == 0 {
+            return Ok(());
+        }
+        let revision = self
+            .db
+            .lock()
+            .await
+            .get_last_committed_pipeline_revision(self.tenant_id, self.pipeline_id)
+            .await?;
+        let reset_after = revision.pipeline.config.restart_policy.reset_after();
+        if Self::timeout_expired(pipeline.status_since, reset_after) {
+            info!(
+                "Pipeline {} has been up for {reset_after:?} since its last restart; resetting its restart count",
+                self.pipeline_id
+            );
+            pipeline.restart_count = 0;
+            self.update_pipeline_runtime_state(pipeline).await?;
+        }
+        Ok(())
+    }
+
+    /// Start or advance an upgrade of the pipeline to its last committed
+    /// revision.
+    ///
+    /// `stats` are the statistics of the running instance of the pipeline.
+    /// Returns how long to wait before polling the pipeline again.
+    ///
+    /// An upgrade starts the new revision from the other slot alongside the
+    /// running instance and waits for it to initialize, one step per call.
+    /// The new instance then takes over: the old instance is paused before
+    /// the new one is started, so that input connectors never consume the
+    /// same input twice, and the location of the pipeline is switched to the
+    /// new instance, which redirects all HTTP requests forwarded to the
+    /// pipeline at once.  Finally, the old instance is shut down.
+    ///
+    /// The new instance does not inherit the state of the old one: it
+    /// rebuilds its state by replaying the input of its input connectors
+    /// from the beginning.  The upgrade is refused if the inputs cannot be
+    /// replayed, or if the pipeline has output connectors, which would
+    /// resend the output computed from the replayed input (see
+    /// [`Self::check_replayable`]).  Clients of the HTTP egress endpoints
+    /// that reconnect to the new instance may still receive output computed
+    /// from the replayed input.
+    ///
+    /// If the new instance fails before it takes over, it is killed, the
+    /// old instance keeps running, and the pipeline is rolled back to its
+    /// running revision.
+    async fn advance_upgrade(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+        stats: &JsonValue,
+    ) -> Result<Duration, ManagerError> {
+        match self.upgrade.take() {
+            None => self.start_upgrade_if_requested(pipeline, stats).await,
+            Some(Upgrade::Provisioning {
+                mut handle,
+                slot,
+                revision,
+                since,
+            }) => match self.read_pipeline_port_file(slot).await {
+                Ok(Some(port)) => {
+                    let location = format!("{}:{port}", self.config.pipeline_host);
+                    self.upgrade = Some(Upgrade::Initializing {
+                        handle,
+                        slot,
+                        revision,
+                        location,
+                        since: Instant::now(),
+                    });
+                    Ok(Self::INITIALIZATION_POLL_PERIOD)
+                }
+                Ok(None) => {
+                    let error = if let Ok(Some(status)) = handle.pipeline_process.try_wait() {
+                        RunnerError::PipelineProcessExited {
+                            pipeline_id: self.pipeline_id,
+                            status: status.to_string(),
+                        }
+                    } else if since.elapsed() > Self::PROVISIONING_TIMEOUT {
+                        RunnerError::PipelineProvisioningTimeout {
+                            pipeline_id: self.pipeline_id,
+                            timeout: Self::PROVISIONING_TIMEOUT,
+                        }
+                    } else {
+                        self.upgrade = Some(Upgrade::Provisioning {
+                            handle,
+                            slot,
+                            revision,
+                            since,
+                        });
+                        return Ok(Self::PROVISIONING_POLL_PERIOD);
+                    };
+                    drop(handle);
+                    self.roll_back_upgrade(pipeline, Some(slot), error.to_string())
+                        .await
+                }
+                Err(e) => {
+                    drop(handle);
+                    self.roll_back_upgrade(pipeline, Some(slot), e.to_string())
+                        .await
+                }
+            },
+            Some(Upgrade::Initializing {
+                handle,
+                slot,
+                revision,
+                location,
+                since,
+            }) => {
+                let error = match pipeline_http_request_json_response(
+                    self.pipeline_id,
+                    Method::GET,
+                    "stats",
+                    &location,
+                )
+                .await
+                {
+                    Err(e) => ErrorResponse::from(&e),
+                    Ok((status, _)) if status.is_success() => {
+                        return self
+                            .take_over(pipeline, handle, slot, revision, location)
+                            .await;
+                    }
+                    Ok((status, _)) if status == StatusCode::SERVICE_UNAVAILABLE => {
+                        if since.elapsed() > Self::INITIALIZATION_TIMEOUT {
+                            ErrorResponse::from(&RunnerError::PipelineInitializationTimeout {
+                                pipeline_id: self.pipeline_id,
+                                timeout: Self::INITIALIZATION_TIMEOUT,
+                            })
+                        } else {
+                            self.upgrade = Some(Upgrade::Initializing {
+                                handle,
+                                slot,
+                                revision,
+                                location,
+                                since,
+                            });
+                            return Ok(Self::INITIALIZATION_POLL_PERIOD);
+                        }
+                    }
+                    Ok((status, body)) => {
+                        Self::error_response_from_json(self.pipeline_id, status, &body)
+                    }
+                };
+                drop(handle);
+                self.roll_back_upgrade(pipeline, Some(slot), error.message)
+                    .await
+            }
+            Some(Upgrade::Retiring {
+                mut handle,
+                slot,
+                since,
+            }) => {
+                let exited = handle.as_mut().map_or(true, |handle| {
+                    !matches!(handle.pipeline_process.try_wait(), Ok(None))
+                });
+                if exited || since.elapsed() > Self::SHUTDOWN_TIMEOUT {
+                    drop(handle);
+                    let _ = remove_dir_all(self.config.instance_dir(self.pipeline_id, slot)).await;
+                    Ok(Self::DEFAULT_PIPELINE_POLL_PERIOD)
+                } else {
+                    self.upgrade = Some(Upgrade::Retiring {
+                        handle,
+                        slot,
+                        since,
+                    });
+                    Ok(Self::SHUTDOWN_POLL_PERIOD)
+                }
+            }
+        }
+    }
+
+    /// Start an upgrade of the pipeline if a revision other than the one it
+    /// runs has been committed.
+    async fn start_upgrade_if_requested(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+        stats: &JsonValue,
+    ) -> Result<Duration, ManagerError> {
+        let revision = self
+            .db
+            .lock()
+            .await
+            .get_last_committed_pipeline_revision(self.tenant_id, self.pipeline_id)
+            .await?;
+
+        match pipeline.running_revision {
+            // The pipeline was deployed before the runner recorded running
+            // revisions.  Assume that it runs the last committed revision.
+            None => {
+                pipeline.running_revision = Some(revision.revision);
+                self.update_pipeline_runtime_state(pipeline).await?;
+                return Ok(Self::DEFAULT_PIPELINE_POLL_PERIOD);
+            }
+            Some(running_revision) if running_revision == revision.revision => {
+                return Ok(Self::DEFAULT_PIPELINE_POLL_PERIOD);
+            }
+            Some(running_revision) => {
+                info!(
+                    "Upgrading pipeline {} from revision {running_revision} to revision {}",
+                    self.pipeline_id, revision.revision
+                );
+            }
+        }
+
+        if let Err(error) = Self::check_replayable(&revision.config, stats) {
+            let error = format!("cannot rebuild the state of the pipeline: {error}");
+            return self.roll_back_upgrade(pipeline, None, error).await;
+        }
+
+        let slot = self.running_slot(pipeline).await.other();
+        let new_revision = revision.revision;
+        match self.start(revision, slot).await {
+            Ok(handle) => {
+                self.upgrade = Some(Upgrade::Provisioning {
+                    handle,
+                    slot,
+                    revision: new_revision,
+                    since: Instant::now(),
+                });
+                Ok(Self::PROVISIONING_POLL_PERIOD)
+            }
+            Err(e) => {
+                self.roll_back_upgrade(pipeline, Some(slot), e.to_string())
+                    .await
+            }
+        }
+    }
+
+    /// Check that a new instance of the pipeline configured with `config`
+    /// can rebuild the state of the running instance, whose statistics are
+    /// `stats`, by replaying its inputs from the beginning.
+    ///
+    /// Input connectors that read files or URLs replay their input.  Kafka
+    /// input connectors only do if they start reading their topics from the
+    /// earliest offset.  Records pushed to the running instance over HTTP
+    /// cannot be replayed.
+    ///
+    /// While it replays its input, the new instance computes the output of
+    /// the old one again.  Output connectors would send that output to their
+    /// destinations a second time, so pipelines with output connectors are
+    /// not replayable either.
+    fn check_replayable(config: &PipelineConfig, stats: &JsonValue) -> Result<(), String> {
+        if let Some(name) = config.outputs.keys().next() {
+            return Err(format!("output connector '{name}' would send the output computed from the replayed input again; detach the pipeline's output connectors to allow upgrades"));
+        }
+        for (name, input) in config.inputs.iter() {
+            let transport = &input.connector_config.transport;
+            match transport.name.as_ref() {
+                "file" | "url" => {}
+                "kafka" => {
+                    let offset_reset = transport
+                        .config
+                        .get("auto.offset.reset")
+                        .and_then(|value| value.as_str());
+                    if !matches!(offset_reset, Some("earliest" | "smallest" | "beginning")) {
+                        return Err(format!("input connector '{name}' does not read its Kafka topics from the beginning; set its 'auto.offset.reset' option to 'earliest' to allow upgrades"));
+                    }
+                }
+                transport => {
+                    return Err(format!("input connector '{name}' uses the '{transport}' transport, which cannot replay its input"));
+                }
+            }
+        }
+
+        // Records received by the running instance that don't come from its
+        // input connectors were pushed to it over HTTP.
+        let total_records = stats
+            .pointer("/global_metrics/total_input_records")
+            .and_then(JsonValue::as_u64)
+            .unwrap_or(0);
+        let connector_records: u64 = stats
+            .get("inputs")
+            .and_then(JsonValue::as_array)
+            .map(|inputs| {
+                inputs
+                    .iter()
+                    .filter(|input| {
+                        input
+                            .pointer("/config/transport/name")
+                            .and_then(JsonValue::as_str)
+                            != Some("api")
+                    })
+                    .filter_map(|input| input.pointer("/metrics/total_records")?.as_u64())
+                    .sum()
+            })
+            .unwrap_or(0);
+        if total_records > connector_records {
+            return Err(format!(
+                "the pipeline received {} records over HTTP, which cannot be replayed",
+                total_records - connector_records
+            ));
+        }
+        Ok(())
+    }
+
+    /// Switch the pipeline over to the new instance at `location`, which has
+    /// initialized and is paused, and start retiring the old instance.
+    async fn take_over(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+        handle: PipelineHandle,
+        slot: PipelineSlot,
+        revision: Revision,
+        location: String,
+    ) -> Result<Duration, ManagerError> {
+        if pipeline.current_status == PipelineStatus::Running {
+            if let Err(e) = self.pipeline_command(&pipeline.location, "pause").await {
+                drop(handle);
+                return self
+                    .roll_back_upgrade(pipeline, Some(slot), e.message)
+                    .await;
+            }
+            if let Err(e) = self.pipeline_command(&location, "start").await {
+                drop(handle);
+                // If the old instance cannot be resumed, polling it will
+                // report it as paused.
+                if let Err(resume_error) = self.pipeline_command(&pipeline.location, "start").await
+                {
+                    error!(
+                        "Failed to resume pipeline {} after a failed upgrade: {}",
+                        self.pipeline_id, resume_error.message
+                    );
+                }
+                return self
+                    .roll_back_upgrade(pipeline, Some(slot), e.message)
+                    .await;
+            }
+        }
+
+        let old_slot = self.running_slot(pipeline).await;
+        let old_location = pipeline.location.clone();
+        let old_handle = self.pipeline_process.replace(handle);
+        self.slot = slot;
+
+        pipeline.set_location(location);
+        pipeline.running_revision = Some(revision);
+        pipeline.error = None;
+        self.update_pipeline_runtime_state(pipeline).await?;
+        info!(
+            "Upgraded pipeline {} to revision {revision}",
+            self.pipeline_id
+        );
+
+        if let Err(e) = self.pipeline_command(&old_location, "shutdown").await {
+            error!(
+                "Failed to shut down the old instance of pipeline {}: {}",
+                self.pipeline_id, e.message
+            );
+        }
+        self.upgrade = Some(Upgrade::Retiring {
+            handle: old_handle,
+            slot: old_slot,
+            since: Instant::now(),
+        });
+        Ok(Self::SHUTDOWN_POLL_PERIOD)
+    }
+
+    /// Abandon the upgrade in progress, if any, and kill the instance of the
+    /// pipeline that does not serve it.
+    ///
+    /// An abandoned upgrade starts over once the pipeline is running or
+    /// paused again.
+    async fn cancel_upgrade(&mut self) {
+        let slot = match self.upgrade.take() {
+            None => return,
+            Some(Upgrade::Provisioning { slot, .. }) | Some(Upgrade::Initializing { slot, .. }) => {
+                info!("Abandoning the upgrade of pipeline {}", self.pipeline_id);
+                slot
+            }
+            Some(Upgrade::Retiring { slot, .. }) => slot,
+        };
+        let _ = remove_dir_all(self.config.instance_dir(self.pipeline_id, slot)).await;
+    }
+
+    /// Give up on an upgrade of the pipeline: keep the instance that runs
+    /// its running revision, remove the new instance started from `slot`,
+    /// if any, and report `error` in the pipeline state.
+    async fn roll_back_upgrade(
+        &mut self,
+        pipeline: &mut PipelineRuntimeState,
+        slot: Option<PipelineSlot>,
+        error: String,
+    ) -> Result<Duration, ManagerError> {
+        error!(
+            "Upgrade of pipeline {} failed, rolling back: {error}",
+            self.pipeline_id
+        );
+        if let Some(slot) = slot {
+            let _ = remove_dir_all(self.config.instance_dir(self.pipeline_id, slot)).await;
+        }
+
+        if let Some(running_revision) = pipeline.running_revision {
+            self.db
+                .lock()
+                .await
+                .restore_pipeline_revision(self.tenant_id, self.pipeline_id, running_revision)
+                .await?;
+        }
+        pipeline.error = Some(ErrorResponse::from(&RunnerError::PipelineUpgradeFailed {
+            pipeline_id: self.pipeline_id,
+            error,
+        }));
+        self.update_pipeline_runtime_state(pipeline).await?;
+        Ok(Self::DEFAULT_PIPELINE_POLL_PERIOD)
+    }
+
+    /// Send `command` to the instance of the pipeline at `location`.
+    async fn pipeline_command(&self, location: &str, command: &str) -> Result<(), ErrorResponse> {
+        match pipeline_http_request_json_response(self.pipeline_id, Method::GET, command, location)
+            .await
+        {
+            Err(e) => Err(ErrorResponse::from(&e)),
+            Ok((status, _)) if status.is_success() => Ok(()),
+            Ok((status, body)) => Err(Self::error_response_from_json(
+                self.pipeline_id,
+                status,
+                &body,
+            )),
+        }
+    }
+
+    /// Start the pipeline process from `slot`, or one process per host for
+    /// multihost pipelines.
+    async fn start(
+        &self,
+        pr: PipelineRevision,
+        slot: PipelineSlot,
+    ) -> Result<PipelineHandle, ManagerError> {
         let pipeline_id = pr.pipeline.pipeline_id;
         let program_id = pr.pipeline.program_id.unwrap();
         let version = pr.program.version;
 
         log::debug!("Pipeline config is '{:?}'", pr.config);
 
-        // Create pipeline directory (delete old directory if exists); write metadata
+        let config = self.layout_config(pr.config.clone())?;
+
+        // Create instance directory (delete old directory if exists); write metadata
         // and config files to it.
-        let pipeline_dir = self.config.pipeline_dir(pipeline_id);
+        let instance_dir = self.config.instance_dir(pipeline_id, slot);
 
-        let _ = remove_dir_all(&pipeline_dir).await;
-        create_dir_all(&pipeline_dir).await.map_err(|e| {
-            ManagerError::io_error(
-                format!("creating pipeline directory '{}'", pipeline_dir.display()),
-                e,
-            )
-        })?;
-        let config_file_path = self.config.config_file_path(pipeline_id);
-        let expanded_config = serde_yaml::to_string(&pr.config).unwrap();
-        fs::write(&config_file_path, &expanded_config)
-            .await
-            .map_err(|e| {
+        let _ = remove_dir_all(&instance_dir).await;
+        for host in 0..config.global.num_hosts() {
+            let host_dir = self.config.host_dir(pipeline_id, slot, host);
+            create_dir_all(&host_dir).await.map_err(|e| {
                 ManagerError::io_error(
-                    format!("writing config file '{}'", config_file_path.display()),
+                    format!("creating pipeline directory '{}'", host_dir.display()),
                     e,
                 )
             })?;
-        let metadata_file_path = self.config.metadata_file_path(pipeline_id);
+        }
+        let metadata_file_path = self.config.metadata_file_path(pipeline_id, slot);
         fs::write(&metadata_file_path, serde_json::to_string(&pr).unwrap())
             .await
             .map_err(|e| {
@@ -656,27 +1249,129 @@ impl PipelineAutomaton {
             &self.config,
             &executable_ref.unwrap(),
             pipeline_id,
+            slot,
             program_id,
             version,
         )
         .await?;
 
-        // Run executable, set current directory to pipeline directory, pass metadata
+        // Hosts can start in any order: followers retry connecting to the
+        // coordinator until it comes online.  Processes that have already been
+        // started are killed if starting a subsequent host fails.
+        let mut handle = PipelineHandle {
+            pipeline_process: self
+                .start_host(
+                    &fetched_executable,
+                    pipeline_id,
+                    slot,
+                    &config,
+                    0,
+                    &metadata_file_path,
+                )
+                .await?,
+            follower_processes: Vec::new(),
+        };
+        for host in 1..config.global.num_hosts() {
+            let process = self
+                .start_host(
+                    &fetched_executable,
+                    pipeline_id,
+                    slot,
+                    &config,
+                    host,
+                    &metadata_file_path,
+                )
+                .await?;
+            handle.follower_processes.push(process);
+        }
+
+        Ok(handle)
+    }
+
+    /// Assign a multihost layout to pipelines that don't have one when the
+    /// runner is configured to run each pipeline across several processes.
+    fn layout_config(&self, mut config: PipelineConfig) -> Result<PipelineConfig, ManagerError> {
+        let num_hosts = self.config.pipeline_hosts;
+        if num_hosts <= 1 || config.global.multihost.is_some() {
+            return Ok(config);
+        }
+
+        // One port for the DBSP runtime on each host, plus one for the coordinator.
+        let ports = allocate_local_ports(num_hosts + 1).map_err(|e| {
+            ManagerError::io_error("allocating ports for a multihost pipeline".to_string(), e)
+        })?;
+        let address = |port: u16| SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+        config.global.multihost = Some(MultihostConfig {
+            hosts: ports[1..]
+                .iter()
+                .map(|port| HostConfig {
+                    address: address(*port),
+                    workers: config.global.workers,
+                })
+                .collect(),
+            local_host: 0,
+            coordinator_address: address(ports[0]),
+        });
+        for (i, input) in config.inputs.values_mut().enumerate() {
+            input.host = i % num_hosts;
+        }
+
+        Ok(config)
+    }
+
+    /// Write the config file of `host` and start its process.
+    async fn start_host(
+        &self,
+        executable: &str,
+        pipeline_id: PipelineId,
+        slot: PipelineSlot,
+        config: &PipelineConfig,
+        host: usize,
+        metadata_file_path: &Path,
+    ) -> Result<Child, ManagerError> {
+        let mut config = config.clone();
+        if let Some(multihost) = config.global.multihost.as_mut() {
+            multihost.local_host = host;
+        }
+
+        let config_file_path = self.config.config_file_path(pipeline_id, slot, host);
+        let expanded_config = serde_yaml::to_string(&config).unwrap();
+        fs::write(&config_file_path, &expanded_config)
+            .await
+            .map_err(|e| {
+                ManagerError::io_error(
+                    format!("writing config file '{}'", config_file_path.display()),
+                    e,
+                )
+            })?;
+
+        // Run executable, set current directory to the host directory, pass metadata
         // file and config as arguments.
-        let pipeline_process = Command::new(fetched_executable)
-            .current_dir(self.config.pipeline_dir(pipeline_id))
+        let mut process = Command::new(executable)
+            .current_dir(self.config.host_dir(pipeline_id, slot, host))
             .arg("--config-file")
             .arg(&config_file_path)
             .arg("--metadata-file")
-            .arg(&metadata_file_path)
+            .arg(metadata_file_path)
             .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
             .spawn()
             .map_err(|e| RunnerError::PipelineStartupError {
                 pipeline_id,
                 error: e.to_string(),
             })?;
 
-        Ok(PipelineHandle { pipeline_process })
+        // Capture the output of the process in the pipeline log.
+        if let Some(stdout) = process.stdout.take() {
+            capture_output(stdout, self.log.clone());
+        }
+        if let Some(stderr) = process.stderr.take() {
+            capture_output(stderr, self.log.clone());
+        }
+
+        Ok(process)
     }
 
     /// Parse `ErrorResponse` from JSON. On error, builds an `ErrorResponse`
@@ -706,14 +1401,77 @@ impl PipelineAutomaton {
 /// recorded in the database.  In the latter case, the error message is
 /// returned to the client.
 ///
+/// A multihost pipeline (see [`LocalRunnerConfig::pipeline_hosts`]) runs as
+/// one process per host on the local machine.  The runner only talks to the
+/// first host, which forwards commands to the other hosts.  The remaining
+/// processes are killed after the first host shuts down.
+///
 /// # Shutting down a pipeline
 ///
 /// To shutdown the pipeline, the runner sends a `/shutdown` HTTP request to the
 /// pipeline.  This request is asynchronous: the pipeline may continue running
 /// for a few seconds after the request succeeds.
-pub async fn run(db: Arc<Mutex<ProjectDB>>, config: &LocalRunnerConfig) {
-    let runner_task = spawn(reconcile(db, Arc::new(config.clone())));
-    runner_task.await.unwrap().unwrap();
+///
+/// # Restarting a failed pipeline
+///
+/// A pipeline that fails while its desired state is `Running` or `Paused`
+/// is restarted according to the restart policy in its config (see
+/// [`RestartPolicy`](`dbsp_adapters::RestartPolicy`)), with exponential
+/// backoff between restarts.  The runtime state of the pipeline records the
+/// number of restarts since it was last deployed and the error that caused
+/// the last one.  The restart count is reset once the restarted pipeline has
+/// run without failing for the `reset_after_ms` period of the policy.
+///
+/// # Upgrading a pipeline
+///
+/// When a new revision of a running or paused pipeline is committed, the
+/// runner starts it from a second directory alongside the running instance.
+/// The revision that the running instance was started from is recorded in
+/// the runtime state of the pipeline.  The upgrade proceeds in steps while
+/// the runner keeps polling the pipeline: once the new instance has
+/// initialized, it takes over the input connectors and the HTTP endpoints of
+/// the pipeline, and the old instance is shut down.  If the new instance
+/// fails to start or initialize, or the inputs of the pipeline cannot be
+/// replayed to rebuild its state, the pipeline keeps running its previous
+/// revision.  Pausing or shutting down the pipeline abandons the upgrade.
+///
+/// # Pipeline logs
+///
+/// The output of each pipeline process is captured in a log file per
+/// pipeline, which is rotated when it reaches
+/// [`LocalRunnerConfig::pipeline_log_max_size`] and kept until the pipeline
+/// is deleted.  The runner serves logs over HTTP on
+/// [`LocalRunnerConfig::runner_logs_port`] and records the URL of the log in
+/// the runtime state of the pipeline, where the API server finds it.  The
+/// logs server does not authenticate requests, so, like the pipelines
+/// themselves, it only listens on [`LocalRunnerConfig::pipeline_host`];
+/// clients read logs through the API server, which checks authorization.
+pub async fn run(
+    db: Arc<Mutex<ProjectDB>>,
+    config: &LocalRunnerConfig,
+) -> Result<(), ManagerError> {
+    let config_copy = web::Data::new(config.clone());
+    let address = (config.pipeline_host.clone(), config.runner_logs_port);
+    let logs_server = HttpServer::new(move || {
+        App::new()
+            .app_data(config_copy.clone())
+            .service(pipeline_logs)
+    })
+    .bind(&address)
+    .map_err(|e| {
+        ManagerError::io_error(
+            format!("binding the logs server to '{}:{}'", address.0, address.1),
+            e,
+        )
+    })?
+    .run();
+    let logs_server = spawn(logs_server);
+
+    let result = spawn(reconcile(db, Arc::new(config.clone())))
+        .await
+        .unwrap();
+    logs_server.abort();
+    result
 }
 
 async fn reconcile(
@@ -764,6 +1522,7 @@ pub async fn fetch_binary_ref(
     config: &LocalRunnerConfig,
     binary_ref: &str,
     pipeline_id: PipelineId,
+    slot: PipelineSlot,
     program_id: ProgramId,
     version: Version,
 ) -> Result<String, ManagerError> {
@@ -801,7 +1560,7 @@ pub async fn fetch_binary_ref(
                 Ok(resp) => {
                     let resp = resp.bytes().await.expect("Binary reference should be accessible as bytes");
                     let resp_ref = resp.as_ref();
-                    let path = config.binary_file_path(pipeline_id, program_id, version);
+                    let path = config.binary_file_path(pipeline_id, slot, program_id, version);
                     let mut file = tokio::fs::File::options()
                         .create(true)
                         .write(true)
@@ -844,6 +1603,21 @@ pub async fn fetch_binary_ref(
     }
 }
 
+/// Allocates `n` distinct unused TCP ports on the loopback interface.
+///
+/// The ports are released before the function returns, so another process
+/// may grab them before the pipeline binds to them.  This is acceptable for
+/// running multihost pipelines on the local machine for testing.
+fn allocate_local_ports(n: usize) -> IOResult<Vec<u16>> {
+    let listeners = (0..n)
+        .map(|_| TcpListener::bind((Ipv4Addr::LOCALHOST, 0)))
+        .collect::<IOResult<Vec<_>>>()?;
+    listeners
+        .iter()
+        .map(|listener| listener.local_addr().map(|address| address.port()))
+        .collect()
+}
+
 /// Send HTTP request to pipeline and parse response as a JSON object.
 async fn pipeline_http_request_json_response(
     pipeline_id: PipelineId,
@@ -864,3 +1638,302 @@ async fn pipeline_http_request_json_response(
 
     Ok((status, value))
 }
+
+#[cfg(test)]
+mod test {
+    use super::PipelineAutomaton;
+    use crate::{
+        auth::TenantRecord,
+        config::LocalRunnerConfig,
+        db::{
+            storage::Storage, PipelineRuntimeState, PipelineStatus, ProgramSchema, ProgramStatus,
+            ProjectDB,
+        },
+        runner::RunnerError,
+    };
+    use chrono::{Duration as ChronoDuration, Utc};
+    use dbsp_adapters::{ErrorResponse, PipelineConfig, RuntimeConfig};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use tokio::sync::{Mutex, Notify};
+    use uuid::Uuid;
+
+    fn config(inputs: &str) -> PipelineConfig {
+        serde_yaml::from_str(&format!("workers: 1\ninputs:\n{inputs}")).unwrap()
+    }
+
+    fn stats(total_input_records: u64, inputs: &[(&str, u64)]) -> serde_json::Value {
+        json!({
+            "global_metrics": { "total_input_records": total_input_records },
+            "inputs": inputs.iter().map(|(transport, total_records)| json!({
+                "config": { "transport": { "name": transport } },
+                "metrics": { "total_records": total_records },
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn check_replayable() {
+        let file = r#"
+  file:
+    stream: T
+    transport:
+      name: file
+      config:
+        path: input.csv
+    format:
+      name: csv
+"#;
+        let kafka_earliest = r#"
+  kafka:
+    stream: T
+    transport:
+      name: kafka
+      config:
+        topics: [input]
+        auto.offset.reset: earliest
+    format:
+      name: csv
+"#;
+        let kafka_latest = r#"
+  kafka:
+    stream: T
+    transport:
+      name: kafka
+      config:
+        topics: [input]
+    format:
+      name: csv
+"#;
+
+        // Files and Kafka topics read from the beginning can be replayed.
+        assert_eq!(
+            PipelineAutomaton::check_replayable(
+                &config(&format!("{file}{kafka_earliest}")),
+                &stats(30, &[("file", 10), ("kafka", 20)])
+            ),
+            Ok(())
+        );
+
+        // Kafka topics read from the latest offset can't.
+        let error = PipelineAutomaton::check_replayable(
+            &config(kafka_latest),
+            &stats(20, &[("kafka", 20)]),
+        )
+        .unwrap_err();
+        assert!(error.contains("'kafka'"), "{error}");
+
+        // Neither can records pushed over HTTP, including by requests that
+        // are still in progress.
+        let error = PipelineAutomaton::check_replayable(&config(file), &stats(15, &[("file", 10)]))
+            .unwrap_err();
+        assert!(error.contains("received 5 records over HTTP"), "{error}");
+        let error = PipelineAutomaton::check_replayable(
+            &config(file),
+            &stats(15, &[("file", 10), ("api", 5)]),
+        )
+        .unwrap_err();
+        assert!(error.contains("received 5 records over HTTP"), "{error}");
+
+        // Output connectors would resend the output of the replayed input.
+        let output = r#"outputs:
+  out:
+    stream: V
+    transport:
+      name: file
+      config:
+        path: output.csv
+    format:
+      name: csv
+"#;
+        let error = PipelineAutomaton::check_replayable(
+            &config(&format!("{file}{output}")),
+            &stats(10, &[("file", 10)]),
+        )
+        .unwrap_err();
+        assert!(error.contains("output connector 'out'"), "{error}");
+    }
+
+    /// Create a pipeline whose program "compiles" to `/bin/true`, with a
+    /// committed revision that uses `restart_policy`.
+    async fn automaton_with_restart_policy(
+        db: ProjectDB,
+        restart_policy: &str,
+    ) -> (PipelineAutomaton, TempDir) {
+        let tenant_id = TenantRecord::default().id;
+        let (program_id, version) = db
+            .new_program(tenant_id, Uuid::now_v7(), "p", "", "")
+            .await
+            .unwrap();
+        db.set_program_status_guarded(tenant_id, program_id, version, ProgramStatus::Success)
+            .await
+            .unwrap();
+        db.set_program_schema(
+            tenant_id,
+            program_id,
+            ProgramSchema {
+                inputs: vec![],
+                outputs: vec![],
+            },
+        )
+        .await
+        .unwrap();
+        db.create_compiled_binary_ref(program_id, version, "file:///bin/true".to_string())
+            .await
+            .unwrap();
+        let config = RuntimeConfig::from_yaml(&format!("restart_policy:\n{restart_policy}"));
+        let (pipeline_id, _) = db
+            .new_pipeline(
+                tenant_id,
+                Uuid::now_v7(),
+                Some(program_id),
+                "pl",
+                "",
+                &config,
+                &None,
+            )
+            .await
+            .unwrap();
+        db.create_pipeline_revision(Uuid::now_v7(), tenant_id, pipeline_id)
+            .await
+            .unwrap();
+
+        let workdir = TempDir::new().unwrap();
+        let config = LocalRunnerConfig {
+            runner_working_directory: workdir.path().to_string_lossy().to_string(),
+            pipeline_host: "127.0.0.1".to_owned(),
+            pipeline_hosts: 1,
+            runner_logs_port: 0,
+            pipeline_log_max_size: 1024 * 1024,
+        }
+        .canonicalize()
+        .unwrap();
+        let automaton = PipelineAutomaton::new(
+            pipeline_id,
+            tenant_id,
+            &Arc::new(config),
+            Arc::new(Mutex::new(db)),
+            Arc::new(Notify::new()),
+        );
+        (automaton, workdir)
+    }
+
+    /// Put the pipeline in the `current_status` state since a second ago,
+    /// while the user wants it to run.
+    async fn set_status(
+        automaton: &PipelineAutomaton,
+        current_status: PipelineStatus,
+        error: Option<ErrorResponse>,
+        restart_count: u32,
+    ) -> PipelineRuntimeState {
+        let db = automaton.db.lock().await;
+        let mut state = db
+            .get_pipeline_runtime_state(automaton.tenant_id, automaton.pipeline_id)
+            .await
+            .unwrap();
+        state.desired_status = PipelineStatus::Running;
+        state.set_current_status(current_status, error);
+        state.status_since = Utc::now() - ChronoDuration::seconds(1);
+        state.restart_count = restart_count;
+        db.update_pipeline_runtime_state(automaton.tenant_id, automaton.pipeline_id, &state)
+            .await
+            .unwrap();
+        state
+    }
+
+    async fn runtime_state(automaton: &PipelineAutomaton) -> PipelineRuntimeState {
+        automaton
+            .db
+            .lock()
+            .await
+            .get_pipeline_runtime_state(automaton.tenant_id, automaton.pipeline_id)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn restart_failed_pipeline() {
+        let (db, _temp) = crate::db::test::setup_pg().await;
+        let (mut automaton, _workdir) = automaton_with_restart_policy(
+            db,
+            "  mode: on_failure\n  max_retries: 1\n  initial_backoff_ms: 0\n",
+        )
+        .await;
+        let crash = ErrorResponse::from(&RunnerError::PipelineProcessExited {
+            pipeline_id: automaton.pipeline_id,
+            status: "exit status: 1".to_string(),
+        });
+
+        // The crashed pipeline is started again, and the crash is recorded
+        // as the reason of the restart.
+        let mut pipeline =
+            set_status(&automaton, PipelineStatus::Failed, Some(crash.clone()), 0).await;
+        automaton.restart_if_allowed(&mut pipeline).await.unwrap();
+        assert!(automaton.pipeline_process.is_some());
+        let state = runtime_state(&automaton).await;
+        assert_eq!(state.current_status, PipelineStatus::Provisioning);
+        assert_eq!(state.restart_count, 1);
+        assert_eq!(state.last_exit_reason, Some(crash.clone()));
+        assert_eq!(state.error, None);
+
+        // Once `max_retries` is exhausted, the pipeline stays failed.
+        automaton.pipeline_process = None;
+        let mut pipeline =
+            set_status(&automaton, PipelineStatus::Failed, Some(crash.clone()), 1).await;
+        assert_eq!(
+            automaton.restart_if_allowed(&mut pipeline).await.unwrap(),
+            PipelineAutomaton::DEFAULT_PIPELINE_POLL_PERIOD
+        );
+        assert!(automaton.pipeline_process.is_none());
+        let state = runtime_state(&automaton).await;
+        assert_eq!(state.current_status, PipelineStatus::Failed);
+        assert_eq!(state.restart_count, 1);
+    }
+
+    #[tokio::test]
+    async fn restart_backoff() {
+        let (db, _temp) = crate::db::test::setup_pg().await;
+        let (mut automaton, _workdir) =
+            automaton_with_restart_policy(db, "  mode: always\n  initial_backoff_ms: 60000\n")
+                .await;
+
+        // The pipeline failed a second ago, and is not restarted before the
+        // backoff period expires.
+        let mut pipeline = set_status(&automaton, PipelineStatus::Failed, None, 0).await;
+        assert_eq!(
+            automaton.restart_if_allowed(&mut pipeline).await.unwrap(),
+            PipelineAutomaton::DEFAULT_PIPELINE_POLL_PERIOD
+        );
+        assert!(automaton.pipeline_process.is_none());
+        assert_eq!(
+            runtime_state(&automaton).await.current_status,
+            PipelineStatus::Failed
+        );
+    }
+
+    #[tokio::test]
+    async fn reset_restart_count() {
+        let (db, _temp) = crate::db::test::setup_pg().await;
+        let (mut automaton, _workdir) =
+            automaton_with_restart_policy(db, "  mode: always\n  reset_after_ms: 60000\n").await;
+
+        // A pipeline that has only been up for a second keeps its restart
+        // count.
+        let mut pipeline = set_status(&automaton, PipelineStatus::Running, None, 2).await;
+        automaton
+            .reset_restart_count_if_healthy(&mut pipeline)
+            .await
+            .unwrap();
+        assert_eq!(runtime_state(&automaton).await.restart_count, 2);
+
+        // After `reset_after_ms`, the count is reset.
+        pipeline.status_since = Utc::now() - ChronoDuration::minutes(2);
+        automaton
+            .reset_restart_count_if_healthy(&mut pipeline)
+            .await
+            .unwrap();
+        assert_eq!(pipeline.restart_count, 0);
+        assert_eq!(runtime_state(&automaton).await.restart_count, 0);
+    }
+}
@@ -0,0 +1,598 @@
+//! Capture of pipeline logs.
+//!
+//! The local runner copies the output of pipeline processes to a
+//! size-bounded [`RotatingLog`] per pipeline and makes it available over
+//! HTTP (see [`pipeline_logs`]).  The API server forwards log requests from
+//! clients to the runner.
+
+use crate::{api::ManagerError, config::LocalRunnerConfig, db::PipelineId};
+use actix_web::{
+    get,
+    http::header::ContentType,
+    web::{self, Bytes},
+    HttpRequest, HttpResponse,
+};
+use futures_util::stream::{self, StreamExt};
+use log::{error, Level};
+use serde::Deserialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufRead, BufReader, ErrorKind, Read, Seek, SeekFrom, Write},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tokio::time::sleep;
+use uuid::Uuid;
+
+/// How often to check the log file for new lines when following a log.
+const FOLLOW_POLL_PERIOD: Duration = Duration::from_millis(250);
+
+/// A log file that is rotated when it reaches a maximal size.
+///
+/// On rotation, the current file is renamed by appending `.1` to its name,
+/// replacing the previously rotated file, so that at most about twice the
+/// maximal size is kept on disk.
+pub(crate) struct RotatingLog {
+    path: PathBuf,
+    max_size: u64,
+    /// The current log file, opened on first write.
+    file: Option<File>,
+    /// Size of the current log file.
+    size: u64,
+}
+
+impl RotatingLog {
+    pub(crate) fn new(path: PathBuf, max_size: u64) -> Self {
+        Self {
+            path,
+            max_size,
+            file: None,
+            size: 0,
+        }
+    }
+
+    /// Append `line`, including its trailing newline, to the log.
+    pub(crate) fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        if self.file.is_none() {
+            self.open()?;
+        }
+        if self.size >= self.max_size {
+            self.file = None;
+            fs::rename(&self.path, rotated_path(&self.path))?;
+            self.open()?;
+        }
+
+        self.file.as_mut().unwrap().write_all(line)?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// Delete the log, e.g., after the pipeline was deleted.
+    pub(crate) fn remove(&mut self) {
+        self.file = None;
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(rotated_path(&self.path));
+    }
+
+    fn open(&mut self) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        self.size = file.metadata()?.len();
+        self.file = Some(file);
+        Ok(())
+    }
+}
+
+/// Location of the file that `path` is renamed to on rotation.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".1");
+    PathBuf::from(path)
+}
+
+/// Copy `output` of a pipeline process to `log` line by line until the
+/// process closes it.
+///
+/// Runs on a separate thread, since process output is a blocking stream.
+pub(crate) fn capture_output<R>(output: R, log: Arc<Mutex<RotatingLog>>)
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reader = BufReader::new(output);
+        let mut line = Vec::new();
+        let mut reported_error = false;
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    if !line.ends_with(b"\n") {
+                        line.push(b'\n');
+                    }
+                    let mut log = log.lock().unwrap();
+                    // Keep draining the output even if the log cannot be
+                    // written, so that the pipeline does not block.
+                    if let Err(e) = log.write_line(&line) {
+                        if !reported_error {
+                            error!(
+                                "Failed to write pipeline log file '{}': {e}",
+                                log.path.display()
+                            );
+                            reported_error = true;
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Query parameters of a pipeline log request.
+#[derive(Debug, Deserialize)]
+pub(crate) struct LogQuery {
+    /// Only return the last `tail` lines of the log.
+    tail: Option<usize>,
+    /// Keep the response open and stream lines as they are logged.
+    #[serde(default)]
+    follow: bool,
+    /// Only return lines logged at this level or a more severe one.
+    level: Option<String>,
+}
+
+/// Parse a log level query parameter.
+pub(crate) fn parse_log_level(level: &Option<String>) -> Result<Option<Level>, ManagerError> {
+    level
+        .as_ref()
+        .map(|level| {
+            Level::from_str(level).map_err(|_| ManagerError::InvalidLogLevel {
+                level: level.clone(),
+            })
+        })
+        .transpose()
+}
+
+/// Filters log lines by level.
+///
+/// Pipelines log lines of the form `<date> <time> <LEVEL> [<pipeline>]
+/// <message>`.  Lines without a level, e.g., the continuation lines of a
+/// multi-line message, have the level of the preceding line.
+struct LevelFilter {
+    max_level: Option<Level>,
+    level: Option<Level>,
+}
+
+impl LevelFilter {
+    fn new(max_level: Option<Level>) -> Self {
+        Self {
+            max_level,
+            level: None,
+        }
+    }
+
+    fn matches(&mut self, line: &str) -> bool {
+        if let Some(level) = line
+            .split_whitespace()
+            .nth(2)
+            .and_then(|word| Level::from_str(&strip_ansi_escapes(word)).ok())
+        {
+            self.level = Some(level);
+        }
+        match (self.max_level, self.level) {
+            (Some(max_level), Some(level)) => level <= max_level,
+            _ => true,
+        }
+    }
+
+    /// Lines of `text` that match the filter, each terminated by a newline.
+    fn filter(&mut self, text: &str) -> String {
+        text.lines()
+            .filter(|line| self.matches(line))
+            .flat_map(|line| [line, "\n"])
+            .collect()
+    }
+}
+
+/// Remove terminal color codes from `s`.
+fn strip_ansi_escapes(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Read the complete lines of `file` after `offset`, ignoring a line that is
+/// still being written.
+///
+/// Returns the lines and the offset after the last line read.
+fn read_complete_lines(file: &mut File, offset: u64) -> io::Result<(String, u64)> {
+    let size = file.metadata()?.len();
+    if size <= offset {
+        return Ok((String::new(), offset));
+    }
+    file.seek(SeekFrom::Start(offset))?;
+    let mut contents = Vec::new();
+    file.take(size - offset).read_to_end(&mut contents)?;
+    let end = contents
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map_or(0, |i| i + 1);
+    Ok((
+        String::from_utf8_lossy(&contents[..end]).into_owned(),
+        offset + end as u64,
+    ))
+}
+
+/// Open the file at `path`, or return `None` if it doesn't exist.
+fn open_if_exists(path: &Path) -> io::Result<Option<File>> {
+    match File::open(path) {
+        Ok(file) => Ok(Some(file)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Read the complete lines of the log at `path` that match `filter`,
+/// starting with the rotated file.
+///
+/// Returns the lines and the position after the last line read: the inode of
+/// the file the last line was read from and the offset in that file.
+fn read_log(path: &Path, filter: &mut LevelFilter) -> io::Result<(Vec<String>, Option<u64>, u64)> {
+    let mut lines = Vec::new();
+    let mut inode = None;
+    let mut offset = 0;
+    for path in [rotated_path(path), path.to_path_buf()] {
+        let mut file = match open_if_exists(&path)? {
+            Some(file) => file,
+            None => continue,
+        };
+        let (text, end) = read_complete_lines(&mut file, 0)?;
+        lines.extend(
+            text.lines()
+                .filter(|line| filter.matches(line))
+                .map(str::to_string),
+        );
+        inode = Some(file.metadata()?.ino());
+        offset = end;
+    }
+    Ok((lines, inode, offset))
+}
+
+/// The last `tail` of `lines`, or all of them if `tail` is `None`, each
+/// terminated by a newline.
+fn last_lines(lines: &[String], tail: Option<usize>) -> String {
+    let skip = lines.len().saturating_sub(tail.unwrap_or(usize::MAX));
+    lines[skip..]
+        .iter()
+        .flat_map(|line| [line.as_str(), "\n"])
+        .collect()
+}
+
+/// Reads lines appended to the log at `path` after a position returned by
+/// [`read_log`].
+///
+/// Rotation is detected by the inode of the log file changing.  The rest of
+/// the rotated file is then read before the new file, so that no lines are
+/// lost, unless the log was rotated twice between two reads.
+struct LogFollower {
+    path: PathBuf,
+    /// Inode of the file that `offset` refers to, `None` if there was no log
+    /// file.
+    inode: Option<u64>,
+    offset: u64,
+    filter: LevelFilter,
+}
+
+impl LogFollower {
+    /// Returns the new lines that match the filter, if any.
+    fn read_new_lines(&mut self) -> io::Result<Option<Bytes>> {
+        let mut file = match open_if_exists(&self.path)? {
+            Some(file) => file,
+            None => return Ok(None),
+        };
+        let metadata = file.metadata()?;
+
+        let mut text = String::new();
+        if self.inode != Some(metadata.ino()) {
+            // The log was rotated.  Drain the previous file, unless it is gone
+            // already.
+            if let Some(mut rotated) = open_if_exists(&rotated_path(&self.path))? {
+                if Some(rotated.metadata()?.ino()) == self.inode {
+                    text = read_complete_lines(&mut rotated, self.offset)?.0;
+                }
+            }
+            self.inode = Some(metadata.ino());
+            self.offset = 0;
+        } else if metadata.len() < self.offset {
+            // The log was truncated.
+            self.offset = 0;
+        }
+
+        let (new_text, offset) = read_complete_lines(&mut file, self.offset)?;
+        self.offset = offset;
+        text.push_str(&new_text);
+
+        let lines = self.filter.filter(&text);
+        Ok((!lines.is_empty()).then(|| Bytes::from(lines)))
+    }
+}
+
+/// Serve the log of a pipeline as plain text.
+///
+/// Query parameters:
+///
+/// - `tail`: only return the last `tail` lines of the log.
+/// - `follow`: keep the response open and stream lines as they are logged.
+/// - `level`: only return lines logged at this level or a more severe one
+///   (`error`, `warn`, `info`, `debug` or `trace`).
+#[get("/logs/{pipeline_id}")]
+async fn pipeline_logs(
+    config: web::Data<LocalRunnerConfig>,
+    req: HttpRequest,
+    query: web::Query<LogQuery>,
+) -> Result<HttpResponse, ManagerError> {
+    let pipeline_id = match req.match_info().get("pipeline_id") {
+        None => Err(ManagerError::MissingUrlEncodedParam {
+            param: "pipeline_id",
+        }),
+        Some(id) => match id.parse::<Uuid>() {
+            Err(e) => Err(ManagerError::InvalidUuidParam {
+                value: id.to_string(),
+                error: e.to_string(),
+            }),
+            Ok(uuid) => Ok(uuid),
+        },
+    }?;
+    let pipeline_id = PipelineId(pipeline_id);
+    let level = parse_log_level(&query.level)?;
+
+    let path = config.pipeline_log_path(pipeline_id);
+    let mut filter = LevelFilter::new(level);
+    let (lines, inode, offset, filter) = web::block(move || {
+        read_log(&path, &mut filter).map(|(lines, inode, offset)| (lines, inode, offset, filter))
+    })
+    .await
+    .unwrap()
+    .map_err(|e| ManagerError::io_error(format!("reading the log of pipeline {pipeline_id}"), e))?;
+
+    let lines = last_lines(&lines, query.tail);
+
+    let mut response = HttpResponse::Ok();
+    response.content_type(ContentType::plaintext());
+    if !query.follow {
+        return Ok(response.body(lines));
+    }
+
+    let follower = LogFollower {
+        path: config.pipeline_log_path(pipeline_id),
+        inode,
+        offset,
+        filter,
+    };
+    let new_lines = stream::unfold(follower, move |mut follower| async move {
+        loop {
+            // Reading the log is blocking file I/O.
+            let (result, returned) = web::block(move || {
+                let result = follower.read_new_lines();
+                (result, follower)
+            })
+            .await
+            .unwrap();
+            follower = returned;
+            match result {
+                Ok(Some(lines)) => return Some((Ok::<_, io::Error>(lines), follower)),
+                Ok(None) => sleep(FOLLOW_POLL_PERIOD).await,
+                Err(e) => {
+                    error!("Failed to follow the log of pipeline {pipeline_id}: {e}");
+                    return None;
+                }
+            }
+        }
+    });
+    Ok(response.streaming(stream::once(async move { Ok(Bytes::from(lines)) }).chain(new_lines)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{last_lines, read_log, rotated_path, LevelFilter, LogFollower, RotatingLog};
+    use log::Level;
+    use std::{fs, io::Write, os::unix::fs::MetadataExt, path::Path};
+    use tempfile::TempDir;
+
+    fn info(message: &str) -> String {
+        format!("2023-10-01 12:00:00 INFO [pipeline] {message}\n")
+    }
+
+    fn error(message: &str) -> String {
+        format!("2023-10-01 12:00:00 ERROR [pipeline] {message}\n")
+    }
+
+    fn append(path: &Path, text: &str) {
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(text.as_bytes())
+            .unwrap();
+    }
+
+    #[test]
+    fn rotating_log() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("pipeline.log");
+        let mut log = RotatingLog::new(path.clone(), 10);
+
+        log.write_line(b"line 1\n").unwrap();
+        log.write_line(b"line 2\n").unwrap();
+        assert_eq!("line 1\nline 2\n", fs::read_to_string(&path).unwrap());
+        assert!(!rotated_path(&path).exists());
+
+        // The log is rotated once it exceeds the maximal size, replacing the
+        // previously rotated file.
+        log.write_line(b"line 3\n").unwrap();
+        assert_eq!(
+            "line 1\nline 2\n",
+            fs::read_to_string(rotated_path(&path)).unwrap()
+        );
+        assert_eq!("line 3\n", fs::read_to_string(&path).unwrap());
+        log.write_line(b"line 4\n").unwrap();
+        log.write_line(b"line 5\n").unwrap();
+        assert_eq!(
+            "line 3\nline 4\n",
+            fs::read_to_string(rotated_path(&path)).unwrap()
+        );
+        assert_eq!("line 5\n", fs::read_to_string(&path).unwrap());
+
+        // A new log picks up the size of an existing file.
+        let mut log = RotatingLog::new(path.clone(), 10);
+        log.write_line(b"line 6\n").unwrap();
+        assert_eq!("line 5\nline 6\n", fs::read_to_string(&path).unwrap());
+        log.write_line(b"line 7\n").unwrap();
+        assert_eq!("line 7\n", fs::read_to_string(&path).unwrap());
+
+        log.remove();
+        assert!(!path.exists());
+        assert!(!rotated_path(&path).exists());
+    }
+
+    #[test]
+    fn level_filter() {
+        let mut filter = LevelFilter::new(Some(Level::Warn));
+        let text = [
+            info("starting"),
+            error("failed"),
+            "  continuation of the error\n".to_string(),
+            info("retrying"),
+            "  continuation of the info\n".to_string(),
+            // Level with terminal color codes.
+            "2023-10-01 12:00:00 \x1b[33mWARN\x1b[0m [pipeline] slow\n".to_string(),
+        ]
+        .concat();
+        assert_eq!(
+            [
+                error("failed"),
+                "  continuation of the error\n".to_string(),
+                "2023-10-01 12:00:00 \x1b[33mWARN\x1b[0m [pipeline] slow\n".to_string(),
+            ]
+            .concat(),
+            filter.filter(&text)
+        );
+
+        // Without a level, all lines match, including lines before the first
+        // line with a level.
+        let mut filter = LevelFilter::new(None);
+        let text = format!("no level\n{}", info("starting"));
+        assert_eq!(text, filter.filter(&text));
+        let mut filter = LevelFilter::new(Some(Level::Error));
+        assert!(filter.matches("no level"));
+    }
+
+    #[test]
+    fn read_log_tail() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pipeline.log");
+
+        let (lines, inode, offset) = read_log(&path, &mut LevelFilter::new(None)).unwrap();
+        assert!(lines.is_empty());
+        assert_eq!((None, 0), (inode, offset));
+
+        // Lines of the rotated file come first, and a line that is still
+        // being written is ignored.
+        append(&rotated_path(&path), &info("1"));
+        append(&path, &format!("{}{}partial", error("2"), info("3")));
+        let (lines, inode, offset) = read_log(&path, &mut LevelFilter::new(None)).unwrap();
+        assert_eq!(
+            vec![info("1"), error("2"), info("3")],
+            lines.iter().map(|l| format!("{l}\n")).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(fs::metadata(&path).unwrap().ino()), inode);
+        assert_eq!((error("2").len() + info("3").len()) as u64, offset);
+
+        assert_eq!(
+            format!("{}{}", error("2"), info("3")),
+            last_lines(&lines, Some(2))
+        );
+        assert_eq!(lines.concat().len() + 3, last_lines(&lines, None).len());
+        assert_eq!(last_lines(&lines, None), last_lines(&lines, Some(10)));
+        assert_eq!("", last_lines(&lines, Some(0)));
+
+        let (lines, _, _) = read_log(&path, &mut LevelFilter::new(Some(Level::Error))).unwrap();
+        assert_eq!(vec![error("2").trim_end().to_string()], lines);
+    }
+
+    #[test]
+    fn log_follower() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("pipeline.log");
+        let new_lines = |follower: &mut LogFollower| {
+            follower
+                .read_new_lines()
+                .unwrap()
+                .map(|lines| String::from_utf8(lines.to_vec()).unwrap())
+        };
+
+        // Start following before the log exists.
+        let mut filter = LevelFilter::new(None);
+        let (_, inode, offset) = read_log(&path, &mut filter).unwrap();
+        let mut follower = LogFollower {
+            path: path.clone(),
+            inode,
+            offset,
+            filter,
+        };
+        assert_eq!(None, new_lines(&mut follower));
+
+        // Partial lines are returned once they are complete.
+        append(&path, &format!("{}partial", info("1")));
+        assert_eq!(Some(info("1")), new_lines(&mut follower));
+        assert_eq!(None, new_lines(&mut follower));
+        append(&path, " line\n");
+        assert_eq!(Some("partial line\n".to_string()), new_lines(&mut follower));
+
+        // On rotation, the rest of the rotated file is returned before the
+        // new file.
+        append(&path, &info("2"));
+        let mut log = RotatingLog::new(path.clone(), 0);
+        log.write_line(info("3").as_bytes()).unwrap();
+        assert_eq!(
+            Some(format!("{}{}", info("2"), info("3"))),
+            new_lines(&mut follower)
+        );
+
+        // If the log was rotated twice since the last read, lines in the
+        // file in between are lost.  Keep the file that is rotated away open,
+        // so that its inode cannot be reused for the new file.
+        let _rotated_away = fs::File::open(&path).unwrap();
+        log.write_line(info("4").as_bytes()).unwrap();
+        log.write_line(info("5").as_bytes()).unwrap();
+        assert_eq!(Some(info("5")), new_lines(&mut follower));
+
+        let mut log = RotatingLog::new(path.clone(), 1024);
+        log.write_line(info("6").as_bytes()).unwrap();
+        log.write_line(info("7").as_bytes()).unwrap();
+        assert_eq!(
+            Some(format!("{}{}", info("6"), info("7"))),
+            new_lines(&mut follower)
+        );
+    }
+}
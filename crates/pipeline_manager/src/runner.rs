@@ -58,6 +58,17 @@ pub enum RunnerError {
         pipeline_id: PipelineId,
         error: String,
     },
+    PipelineUpgradeFailed {
+        pipeline_id: PipelineId,
+        error: String,
+    },
+    PipelineProcessExited {
+        pipeline_id: PipelineId,
+        status: String,
+    },
+    NoPipelineLogs {
+        pipeline_id: PipelineId,
+    },
 }
 
 impl DetailedError for RunnerError {
@@ -76,6 +87,9 @@ impl DetailedError for RunnerError {
                 Cow::from("IllegalPipelineStateTransition")
             }
             Self::BinaryFetchError { .. } => Cow::from("BinaryFetchError"),
+            Self::PipelineUpgradeFailed { .. } => Cow::from("PipelineUpgradeFailed"),
+            Self::PipelineProcessExited { .. } => Cow::from("PipelineProcessExited"),
+            Self::NoPipelineLogs { .. } => Cow::from("NoPipelineLogs"),
         }
     }
 }
@@ -134,6 +148,27 @@ impl Display for RunnerError {
             Self::BinaryFetchError { pipeline_id, error } => {
                 write!(f, "Failed to fetch binary executable for running pipeline '{pipeline_id}': '{error}'")
             }
+            Self::PipelineUpgradeFailed { pipeline_id, error } => {
+                write!(
+                    f,
+                    "Upgrading pipeline '{pipeline_id}' failed and was rolled back: '{error}'"
+                )
+            }
+            Self::PipelineProcessExited {
+                pipeline_id,
+                status,
+            } => {
+                write!(
+                    f,
+                    "Pipeline '{pipeline_id}' process exited unexpectedly: {status}"
+                )
+            }
+            Self::NoPipelineLogs { pipeline_id } => {
+                write!(
+                    f,
+                    "No logs are available for pipeline '{pipeline_id}'; deploy the pipeline first"
+                )
+            }
         }
     }
 }
@@ -152,6 +187,9 @@ impl ResponseError for RunnerError {
             Self::PipelineStartupError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
             Self::IllegalPipelineStateTransition { .. } => StatusCode::BAD_REQUEST,
             Self::BinaryFetchError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::PipelineUpgradeFailed { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::PipelineProcessExited { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::NoPipelineLogs { .. } => StatusCode::NOT_FOUND,
         }
     }
 
@@ -244,6 +282,51 @@ impl RunnerApi {
         Ok(())
     }
 
+    /// Upgrade a running or paused pipeline to the current state of its
+    /// program, config and connectors.
+    ///
+    /// Commits a new revision of the pipeline.  The runner starts the new
+    /// revision alongside the running instance of the pipeline and switches
+    /// over to it once it has initialized, keeping the pipeline in its
+    /// current state.  If the new revision fails to start or cannot rebuild
+    /// the state of the pipeline from its inputs, the runner keeps the old
+    /// instance running and restores its revision.
+    pub(crate) async fn upgrade_pipeline(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+    ) -> Result<(), ManagerError> {
+        // TODO: this function should run in a transaction to avoid conflicts with
+        // another manager instance.
+        let db = self.db.lock().await;
+        let pipeline_state = db
+            .get_pipeline_runtime_state(tenant_id, pipeline_id)
+            .await?;
+
+        // Only upgrade pipelines that are up and not in the middle of
+        // another state transition.
+        if !matches!(
+            pipeline_state.current_status,
+            PipelineStatus::Running | PipelineStatus::Paused
+        ) || pipeline_state.desired_status != pipeline_state.current_status
+        {
+            Err(RunnerError::IllegalPipelineStateTransition {
+                pipeline_id,
+                error: "Only a running or paused pipeline can be upgraded. Use the '/start' or '/pause' endpoints to deploy a pipeline that is shut down.".to_string(),
+                current_status: pipeline_state.current_status,
+                desired_status: pipeline_state.desired_status,
+                requested_status: Some(pipeline_state.desired_status),
+            })?;
+        }
+
+        // Fails with `RevisionNotChanged` if there is nothing to upgrade to.
+        // The runner picks up the new revision once it is committed.
+        db.create_pipeline_revision(Uuid::now_v7(), tenant_id, pipeline_id)
+            .await?;
+
+        Ok(())
+    }
+
     /// Check the `request` is a valid new desired state given the current runtime state
     /// of the pipeline.  `request` value of `None` represents the request to delete the
     /// pipeline.
@@ -485,4 +568,42 @@ impl RunnerApi {
         }
         Ok(builder.streaming(response))
     }
+
+    /// Forward a log request to the runner that serves the log of the
+    /// pipeline.
+    ///
+    /// Logs remain available after the pipeline has been shut down or has
+    /// failed.  The response is streamed, so that the runner can keep sending
+    /// lines as they are logged when the client requests `follow`.
+    pub(crate) async fn pipeline_logs(
+        &self,
+        tenant_id: TenantId,
+        pipeline_id: PipelineId,
+        req: HttpRequest,
+    ) -> Result<HttpResponse, ManagerError> {
+        let pipeline_state = self
+            .db
+            .lock()
+            .await
+            .get_pipeline_runtime_state(tenant_id, pipeline_id)
+            .await?;
+
+        if pipeline_state.log_location.is_empty() {
+            Err(RunnerError::NoPipelineLogs { pipeline_id })?
+        }
+        let url = format!("{}?{}", pipeline_state.log_location, req.query_string());
+
+        let response = awc::Client::new().get(url).send().await.map_err(|e| {
+            RunnerError::HttpForwardError {
+                pipeline_id,
+                error: e.to_string(),
+            }
+        })?;
+
+        let mut builder = HttpResponseBuilder::new(response.status());
+        for header in response.headers().into_iter() {
+            builder.append_header(header);
+        }
+        Ok(builder.streaming(response))
+    }
 }
@@ -26,7 +26,8 @@ class CompilationException(Exception):
         if hasattr(status, 'sql_error'):
             self.message = "SQL error: " + str(status.sql_error)
         elif hasattr(status, 'rust_error'):
-            self.message = "Rust compiler error: " + status.rust_error
+            self.message = "Rust compiler error: " + "\n".join(
+                error.rendered or error.message for error in status.rust_error)
         elif hasattr(status, 'system_error'):
             self.message = "System error: " + status.system_error
         else:
@@ -0,0 +1,184 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.new_api_key_request import NewApiKeyRequest
+from ...models.new_api_key_response import NewApiKeyResponse
+from ...types import Response
+
+
+def _get_kwargs(
+    *,
+    json_body: NewApiKeyRequest,
+) -> Dict[str, Any]:
+    pass
+
+    json_json_body = json_body.to_dict()
+
+    return {
+        "method": "post",
+        "url": "/api_keys",
+        "json": json_json_body,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, NewApiKeyResponse]]:
+    if response.status_code == HTTPStatus.CREATED:
+        response_201 = NewApiKeyResponse.from_dict(response.json())
+
+        return response_201
+    if response.status_code == HTTPStatus.FORBIDDEN:
+        response_403 = ErrorResponse.from_dict(response.json())
+
+        return response_403
+    if response.status_code == HTTPStatus.CONFLICT:
+        response_409 = ErrorResponse.from_dict(response.json())
+
+        return response_409
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, NewApiKeyResponse]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: NewApiKeyRequest,
+) -> Response[Union[ErrorResponse, NewApiKeyResponse]]:
+    """Create a new API key.
+
+     Create a new API key.
+
+    The key authorizes requests that pass it in the `x-api-key` header,
+    subject to the scopes granted to the key.
+
+    Args:
+        json_body (NewApiKeyRequest): Request to create a new API key.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, NewApiKeyResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: NewApiKeyRequest,
+) -> Optional[Union[ErrorResponse, NewApiKeyResponse]]:
+    """Create a new API key.
+
+     Create a new API key.
+
+    The key authorizes requests that pass it in the `x-api-key` header,
+    subject to the scopes granted to the key.
+
+    Args:
+        json_body (NewApiKeyRequest): Request to create a new API key.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, NewApiKeyResponse]
+    """
+
+    return sync_detailed(
+        client=client,
+        json_body=json_body,
+    ).parsed
+
+
+async def asyncio_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: NewApiKeyRequest,
+) -> Response[Union[ErrorResponse, NewApiKeyResponse]]:
+    """Create a new API key.
+
+     Create a new API key.
+
+    The key authorizes requests that pass it in the `x-api-key` header,
+    subject to the scopes granted to the key.
+
+    Args:
+        json_body (NewApiKeyRequest): Request to create a new API key.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, NewApiKeyResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: NewApiKeyRequest,
+) -> Optional[Union[ErrorResponse, NewApiKeyResponse]]:
+    """Create a new API key.
+
+     Create a new API key.
+
+    The key authorizes requests that pass it in the `x-api-key` header,
+    subject to the scopes granted to the key.
+
+    Args:
+        json_body (NewApiKeyRequest): Request to create a new API key.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, NewApiKeyResponse]
+    """
+
+    return (
+        await asyncio_detailed(
+            client=client,
+            json_body=json_body,
+        )
+    ).parsed
@@ -0,0 +1,182 @@
+from http import HTTPStatus
+from typing import Any, Dict, List, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.connector_history_entry import ConnectorHistoryEntry
+from ...models.error_response import ErrorResponse
+from ...types import Response
+
+
+def _get_kwargs(
+    connector_id: str,
+) -> Dict[str, Any]:
+    pass
+
+    return {
+        "method": "get",
+        "url": "/connectors/{connector_id}/versions".format(
+            connector_id=connector_id,
+        ),
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = []
+        _response_200 = response.json()
+        for response_200_item_data in _response_200:
+            response_200_item = ConnectorHistoryEntry.from_dict(response_200_item_data)
+
+            response_200.append(response_200_item)
+
+        return response_200
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    connector_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    """List all revisions of a connector, oldest first.
+
+     List all revisions of a connector, oldest first.
+
+    A connector revision is committed every time a modified pipeline that
+    uses the connector is deployed.
+
+    Args:
+        connector_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['ConnectorHistoryEntry']]]
+    """
+
+    kwargs = _get_kwargs(
+        connector_id=connector_id,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    connector_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    """List all revisions of a connector, oldest first.
+
+     List all revisions of a connector, oldest first.
+
+    A connector revision is committed every time a modified pipeline that
+    uses the connector is deployed.
+
+    Args:
+        connector_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['ConnectorHistoryEntry']]
+    """
+
+    return sync_detailed(
+        connector_id=connector_id,
+        client=client,
+    ).parsed
+
+
+async def asyncio_detailed(
+    connector_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    """List all revisions of a connector, oldest first.
+
+     List all revisions of a connector, oldest first.
+
+    A connector revision is committed every time a modified pipeline that
+    uses the connector is deployed.
+
+    Args:
+        connector_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['ConnectorHistoryEntry']]]
+    """
+
+    kwargs = _get_kwargs(
+        connector_id=connector_id,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    connector_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, List["ConnectorHistoryEntry"]]]:
+    """List all revisions of a connector, oldest first.
+
+     List all revisions of a connector, oldest first.
+
+    A connector revision is committed every time a modified pipeline that
+    uses the connector is deployed.
+
+    Args:
+        connector_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['ConnectorHistoryEntry']]
+    """
+
+    return (
+        await asyncio_detailed(
+            connector_id=connector_id,
+            client=client,
+        )
+    ).parsed
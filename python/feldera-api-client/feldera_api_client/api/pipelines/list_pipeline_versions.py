@@ -0,0 +1,178 @@
+from http import HTTPStatus
+from typing import Any, Dict, List, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.pipeline_history_entry import PipelineHistoryEntry
+from ...types import Response
+
+
+def _get_kwargs(
+    pipeline_id: str,
+) -> Dict[str, Any]:
+    pass
+
+    return {
+        "method": "get",
+        "url": "/pipelines/{pipeline_id}/versions".format(
+            pipeline_id=pipeline_id,
+        ),
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = []
+        _response_200 = response.json()
+        for response_200_item_data in _response_200:
+            response_200_item = PipelineHistoryEntry.from_dict(response_200_item_data)
+
+            response_200.append(response_200_item)
+
+        return response_200
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    """List all committed revisions of a pipeline, oldest first.
+
+     List all committed revisions of a pipeline, oldest first.
+
+    A new revision is committed every time a modified pipeline is deployed.
+
+    Args:
+        pipeline_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['PipelineHistoryEntry']]]
+    """
+
+    kwargs = _get_kwargs(
+        pipeline_id=pipeline_id,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    """List all committed revisions of a pipeline, oldest first.
+
+     List all committed revisions of a pipeline, oldest first.
+
+    A new revision is committed every time a modified pipeline is deployed.
+
+    Args:
+        pipeline_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['PipelineHistoryEntry']]
+    """
+
+    return sync_detailed(
+        pipeline_id=pipeline_id,
+        client=client,
+    ).parsed
+
+
+async def asyncio_detailed(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    """List all committed revisions of a pipeline, oldest first.
+
+     List all committed revisions of a pipeline, oldest first.
+
+    A new revision is committed every time a modified pipeline is deployed.
+
+    Args:
+        pipeline_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['PipelineHistoryEntry']]]
+    """
+
+    kwargs = _get_kwargs(
+        pipeline_id=pipeline_id,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, List["PipelineHistoryEntry"]]]:
+    """List all committed revisions of a pipeline, oldest first.
+
+     List all committed revisions of a pipeline, oldest first.
+
+    A new revision is committed every time a modified pipeline is deployed.
+
+    Args:
+        pipeline_id (str):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['PipelineHistoryEntry']]
+    """
+
+    return (
+        await asyncio_detailed(
+            pipeline_id=pipeline_id,
+            client=client,
+        )
+    ).parsed
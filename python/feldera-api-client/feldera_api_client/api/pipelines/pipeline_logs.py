@@ -0,0 +1,245 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union, cast
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    pipeline_id: str,
+    *,
+    tail: Union[Unset, None, int] = UNSET,
+    follow: Union[Unset, None, bool] = UNSET,
+    level: Union[Unset, None, str] = UNSET,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    params["tail"] = tail
+
+    params["follow"] = follow
+
+    params["level"] = level
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    return {
+        "method": "get",
+        "url": "/pipelines/{pipeline_id}/logs".format(
+            pipeline_id=pipeline_id,
+        ),
+        "params": params,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, str]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = cast(str, response.text)
+        return response_200
+    if response.status_code == HTTPStatus.BAD_REQUEST:
+        response_400 = ErrorResponse.from_dict(response.json())
+
+        return response_400
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, str]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    tail: Union[Unset, None, int] = UNSET,
+    follow: Union[Unset, None, bool] = UNSET,
+    level: Union[Unset, None, str] = UNSET,
+) -> Response[Union[ErrorResponse, str]]:
+    """Retrieve the log of a pipeline.
+
+     Retrieve the log of a pipeline.
+
+    The runner captures the output of the pipeline process in a size-bounded
+    log file, which remains available after the pipeline has been shut down.
+    The log is returned as plain text, one log record per line.
+
+    When `follow` is set, the response is streamed and the connection is kept
+    open, delivering new lines as the pipeline logs them.
+
+    Args:
+        pipeline_id (str):
+        tail (Union[Unset, None, int]):
+        follow (Union[Unset, None, bool]):
+        level (Union[Unset, None, str]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, str]]
+    """
+
+    kwargs = _get_kwargs(
+        pipeline_id=pipeline_id,
+        tail=tail,
+        follow=follow,
+        level=level,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    tail: Union[Unset, None, int] = UNSET,
+    follow: Union[Unset, None, bool] = UNSET,
+    level: Union[Unset, None, str] = UNSET,
+) -> Optional[Union[ErrorResponse, str]]:
+    """Retrieve the log of a pipeline.
+
+     Retrieve the log of a pipeline.
+
+    The runner captures the output of the pipeline process in a size-bounded
+    log file, which remains available after the pipeline has been shut down.
+    The log is returned as plain text, one log record per line.
+
+    When `follow` is set, the response is streamed and the connection is kept
+    open, delivering new lines as the pipeline logs them.
+
+    Args:
+        pipeline_id (str):
+        tail (Union[Unset, None, int]):
+        follow (Union[Unset, None, bool]):
+        level (Union[Unset, None, str]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, str]
+    """
+
+    return sync_detailed(
+        pipeline_id=pipeline_id,
+        client=client,
+        tail=tail,
+        follow=follow,
+        level=level,
+    ).parsed
+
+
+async def asyncio_detailed(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    tail: Union[Unset, None, int] = UNSET,
+    follow: Union[Unset, None, bool] = UNSET,
+    level: Union[Unset, None, str] = UNSET,
+) -> Response[Union[ErrorResponse, str]]:
+    """Retrieve the log of a pipeline.
+
+     Retrieve the log of a pipeline.
+
+    The runner captures the output of the pipeline process in a size-bounded
+    log file, which remains available after the pipeline has been shut down.
+    The log is returned as plain text, one log record per line.
+
+    When `follow` is set, the response is streamed and the connection is kept
+    open, delivering new lines as the pipeline logs them.
+
+    Args:
+        pipeline_id (str):
+        tail (Union[Unset, None, int]):
+        follow (Union[Unset, None, bool]):
+        level (Union[Unset, None, str]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, str]]
+    """
+
+    kwargs = _get_kwargs(
+        pipeline_id=pipeline_id,
+        tail=tail,
+        follow=follow,
+        level=level,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    pipeline_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    tail: Union[Unset, None, int] = UNSET,
+    follow: Union[Unset, None, bool] = UNSET,
+    level: Union[Unset, None, str] = UNSET,
+) -> Optional[Union[ErrorResponse, str]]:
+    """Retrieve the log of a pipeline.
+
+     Retrieve the log of a pipeline.
+
+    The runner captures the output of the pipeline process in a size-bounded
+    log file, which remains available after the pipeline has been shut down.
+    The log is returned as plain text, one log record per line.
+
+    When `follow` is set, the response is streamed and the connection is kept
+    open, delivering new lines as the pipeline logs them.
+
+    Args:
+        pipeline_id (str):
+        tail (Union[Unset, None, int]):
+        follow (Union[Unset, None, bool]):
+        level (Union[Unset, None, str]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, str]
+    """
+
+    return (
+        await asyncio_detailed(
+            pipeline_id=pipeline_id,
+            client=client,
+            tail=tail,
+            follow=follow,
+            level=level,
+        )
+    ).parsed
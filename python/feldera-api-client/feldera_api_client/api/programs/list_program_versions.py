@@ -0,0 +1,198 @@
+from http import HTTPStatus
+from typing import Any, Dict, List, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.program_version_descr import ProgramVersionDescr
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    program_id: str,
+    *,
+    with_code: Union[Unset, None, bool] = UNSET,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    params["with_code"] = with_code
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    return {
+        "method": "get",
+        "url": "/programs/{program_id}/versions".format(
+            program_id=program_id,
+        ),
+        "params": params,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = []
+        _response_200 = response.json()
+        for response_200_item_data in _response_200:
+            response_200_item = ProgramVersionDescr.from_dict(response_200_item_data)
+
+            response_200.append(response_200_item)
+
+        return response_200
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    with_code: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    """List all versions of a program, oldest first.
+
+     List all versions of a program, oldest first.
+
+    A new version is recorded every time the program's code changes.
+
+    Args:
+        program_id (str):
+        with_code (Union[Unset, None, bool]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['ProgramVersionDescr']]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        with_code=with_code,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    with_code: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    """List all versions of a program, oldest first.
+
+     List all versions of a program, oldest first.
+
+    A new version is recorded every time the program's code changes.
+
+    Args:
+        program_id (str):
+        with_code (Union[Unset, None, bool]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['ProgramVersionDescr']]
+    """
+
+    return sync_detailed(
+        program_id=program_id,
+        client=client,
+        with_code=with_code,
+    ).parsed
+
+
+async def asyncio_detailed(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    with_code: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    """List all versions of a program, oldest first.
+
+     List all versions of a program, oldest first.
+
+    A new version is recorded every time the program's code changes.
+
+    Args:
+        program_id (str):
+        with_code (Union[Unset, None, bool]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, List['ProgramVersionDescr']]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        with_code=with_code,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    with_code: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ErrorResponse, List["ProgramVersionDescr"]]]:
+    """List all versions of a program, oldest first.
+
+     List all versions of a program, oldest first.
+
+    A new version is recorded every time the program's code changes.
+
+    Args:
+        program_id (str):
+        with_code (Union[Unset, None, bool]):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, List['ProgramVersionDescr']]
+    """
+
+    return (
+        await asyncio_detailed(
+            program_id=program_id,
+            client=client,
+            with_code=with_code,
+        )
+    ).parsed
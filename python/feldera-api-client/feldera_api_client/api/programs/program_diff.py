@@ -0,0 +1,200 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.program_diff_response import ProgramDiffResponse
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    program_id: str,
+    *,
+    from_: int,
+    to: int,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    params["from"] = from_
+
+    params["to"] = to
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    return {
+        "method": "get",
+        "url": "/programs/{program_id}/diff".format(
+            program_id=program_id,
+        ),
+        "params": params,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, ProgramDiffResponse]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = ProgramDiffResponse.from_dict(response.json())
+
+        return response_200
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, ProgramDiffResponse]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    from_: int,
+    to: int,
+) -> Response[Union[ErrorResponse, ProgramDiffResponse]]:
+    """Compare the SQL code and schema of two versions of a program.
+
+     Compare the SQL code and schema of two versions of a program.
+
+    Args:
+        program_id (str):
+        from_ (int):
+        to (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, ProgramDiffResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        from_=from_,
+        to=to,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    from_: int,
+    to: int,
+) -> Optional[Union[ErrorResponse, ProgramDiffResponse]]:
+    """Compare the SQL code and schema of two versions of a program.
+
+     Compare the SQL code and schema of two versions of a program.
+
+    Args:
+        program_id (str):
+        from_ (int):
+        to (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, ProgramDiffResponse]
+    """
+
+    return sync_detailed(
+        program_id=program_id,
+        client=client,
+        from_=from_,
+        to=to,
+    ).parsed
+
+
+async def asyncio_detailed(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    from_: int,
+    to: int,
+) -> Response[Union[ErrorResponse, ProgramDiffResponse]]:
+    """Compare the SQL code and schema of two versions of a program.
+
+     Compare the SQL code and schema of two versions of a program.
+
+    Args:
+        program_id (str):
+        from_ (int):
+        to (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, ProgramDiffResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        from_=from_,
+        to=to,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    program_id: str,
+    *,
+    client: Union[AuthenticatedClient, Client],
+    from_: int,
+    to: int,
+) -> Optional[Union[ErrorResponse, ProgramDiffResponse]]:
+    """Compare the SQL code and schema of two versions of a program.
+
+     Compare the SQL code and schema of two versions of a program.
+
+    Args:
+        program_id (str):
+        from_ (int):
+        to (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, ProgramDiffResponse]
+    """
+
+    return (
+        await asyncio_detailed(
+            program_id=program_id,
+            client=client,
+            from_=from_,
+            to=to,
+        )
+    ).parsed
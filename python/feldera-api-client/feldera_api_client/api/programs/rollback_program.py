@@ -0,0 +1,203 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.update_program_response import UpdateProgramResponse
+from ...types import Response
+
+
+def _get_kwargs(
+    program_id: str,
+    version: int,
+) -> Dict[str, Any]:
+    pass
+
+    return {
+        "method": "post",
+        "url": "/programs/{program_id}/rollback/{version}".format(
+            program_id=program_id,
+            version=version,
+        ),
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, UpdateProgramResponse]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = UpdateProgramResponse.from_dict(response.json())
+
+        return response_200
+    if response.status_code == HTTPStatus.NOT_FOUND:
+        response_404 = ErrorResponse.from_dict(response.json())
+
+        return response_404
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, UpdateProgramResponse]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    program_id: str,
+    version: int,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, UpdateProgramResponse]]:
+    """Roll a program back to the code of an earlier version.
+
+     Roll a program back to the code of an earlier version.
+
+    The restored code becomes a new version of the program, which is queued
+    for compilation.  If a binary compiled from the same code is still
+    available, it is reused instead of compiling the program again.  Does
+    nothing if the current code of the program already matches the code of
+    the specified version.
+
+    Args:
+        program_id (str):
+        version (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, UpdateProgramResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        version=version,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    program_id: str,
+    version: int,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, UpdateProgramResponse]]:
+    """Roll a program back to the code of an earlier version.
+
+     Roll a program back to the code of an earlier version.
+
+    The restored code becomes a new version of the program, which is queued
+    for compilation.  If a binary compiled from the same code is still
+    available, it is reused instead of compiling the program again.  Does
+    nothing if the current code of the program already matches the code of
+    the specified version.
+
+    Args:
+        program_id (str):
+        version (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, UpdateProgramResponse]
+    """
+
+    return sync_detailed(
+        program_id=program_id,
+        version=version,
+        client=client,
+    ).parsed
+
+
+async def asyncio_detailed(
+    program_id: str,
+    version: int,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Response[Union[ErrorResponse, UpdateProgramResponse]]:
+    """Roll a program back to the code of an earlier version.
+
+     Roll a program back to the code of an earlier version.
+
+    The restored code becomes a new version of the program, which is queued
+    for compilation.  If a binary compiled from the same code is still
+    available, it is reused instead of compiling the program again.  Does
+    nothing if the current code of the program already matches the code of
+    the specified version.
+
+    Args:
+        program_id (str):
+        version (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, UpdateProgramResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        program_id=program_id,
+        version=version,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    program_id: str,
+    version: int,
+    *,
+    client: Union[AuthenticatedClient, Client],
+) -> Optional[Union[ErrorResponse, UpdateProgramResponse]]:
+    """Roll a program back to the code of an earlier version.
+
+     Roll a program back to the code of an earlier version.
+
+    The restored code becomes a new version of the program, which is queued
+    for compilation.  If a binary compiled from the same code is still
+    available, it is reused instead of compiling the program again.  Does
+    nothing if the current code of the program already matches the code of
+    the specified version.
+
+    Args:
+        program_id (str):
+        version (int):
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, UpdateProgramResponse]
+    """
+
+    return (
+        await asyncio_detailed(
+            program_id=program_id,
+            version=version,
+            client=client,
+        )
+    ).parsed
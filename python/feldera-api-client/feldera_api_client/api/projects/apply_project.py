@@ -0,0 +1,287 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.apply_plan import ApplyPlan
+from ...models.error_response import ErrorResponse
+from ...models.project_bundle import ProjectBundle
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    *,
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    params["dry_run"] = dry_run
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    json_json_body = json_body.to_dict()
+
+    return {
+        "method": "post",
+        "url": "/apply",
+        "json": json_json_body,
+        "params": params,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ApplyPlan, ErrorResponse]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = ApplyPlan.from_dict(response.json())
+
+        return response_200
+    if response.status_code == HTTPStatus.BAD_REQUEST:
+        response_400 = ErrorResponse.from_dict(response.json())
+
+        return response_400
+    if response.status_code == HTTPStatus.CONFLICT:
+        response_409 = ErrorResponse.from_dict(response.json())
+
+        return response_409
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ApplyPlan, ErrorResponse]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ApplyPlan, ErrorResponse]]:
+    """Bring the programs, connectors and pipelines of the tenant to a desired
+
+     Bring the programs, connectors and pipelines of the tenant to a desired
+    state.
+
+    The desired state is a project bundle in JSON or YAML format, in the
+    format produced by the `/export` endpoint, e.g., checked into a git
+    repository.  The server computes a plan against the current state:
+
+    - Objects in the desired state are created or updated by name.
+    - Objects that are not in the desired state are deleted.  Pipelines to
+    delete must be shut down.
+    - New and modified programs are queued for compilation.
+    - Running and paused pipelines whose committed revision differs from the
+    desired state are upgraded to a new revision.  If the program of such a
+    pipeline must be compiled first, the step is marked as `deferred`:
+    applying the same state again once compilation succeeds redeploys the
+    pipeline.
+
+    With `dry_run`, the plan is returned without executing it.  Otherwise,
+    the plan is validated and executed without other API requests observing
+    intermediate states.  Applying the same desired state repeatedly is safe.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ApplyPlan, ErrorResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+        dry_run=dry_run,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ApplyPlan, ErrorResponse]]:
+    """Bring the programs, connectors and pipelines of the tenant to a desired
+
+     Bring the programs, connectors and pipelines of the tenant to a desired
+    state.
+
+    The desired state is a project bundle in JSON or YAML format, in the
+    format produced by the `/export` endpoint, e.g., checked into a git
+    repository.  The server computes a plan against the current state:
+
+    - Objects in the desired state are created or updated by name.
+    - Objects that are not in the desired state are deleted.  Pipelines to
+    delete must be shut down.
+    - New and modified programs are queued for compilation.
+    - Running and paused pipelines whose committed revision differs from the
+    desired state are upgraded to a new revision.  If the program of such a
+    pipeline must be compiled first, the step is marked as `deferred`:
+    applying the same state again once compilation succeeds redeploys the
+    pipeline.
+
+    With `dry_run`, the plan is returned without executing it.  Otherwise,
+    the plan is validated and executed without other API requests observing
+    intermediate states.  Applying the same desired state repeatedly is safe.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ApplyPlan, ErrorResponse]
+    """
+
+    return sync_detailed(
+        client=client,
+        json_body=json_body,
+        dry_run=dry_run,
+    ).parsed
+
+
+async def asyncio_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ApplyPlan, ErrorResponse]]:
+    """Bring the programs, connectors and pipelines of the tenant to a desired
+
+     Bring the programs, connectors and pipelines of the tenant to a desired
+    state.
+
+    The desired state is a project bundle in JSON or YAML format, in the
+    format produced by the `/export` endpoint, e.g., checked into a git
+    repository.  The server computes a plan against the current state:
+
+    - Objects in the desired state are created or updated by name.
+    - Objects that are not in the desired state are deleted.  Pipelines to
+    delete must be shut down.
+    - New and modified programs are queued for compilation.
+    - Running and paused pipelines whose committed revision differs from the
+    desired state are upgraded to a new revision.  If the program of such a
+    pipeline must be compiled first, the step is marked as `deferred`:
+    applying the same state again once compilation succeeds redeploys the
+    pipeline.
+
+    With `dry_run`, the plan is returned without executing it.  Otherwise,
+    the plan is validated and executed without other API requests observing
+    intermediate states.  Applying the same desired state repeatedly is safe.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ApplyPlan, ErrorResponse]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+        dry_run=dry_run,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ApplyPlan, ErrorResponse]]:
+    """Bring the programs, connectors and pipelines of the tenant to a desired
+
+     Bring the programs, connectors and pipelines of the tenant to a desired
+    state.
+
+    The desired state is a project bundle in JSON or YAML format, in the
+    format produced by the `/export` endpoint, e.g., checked into a git
+    repository.  The server computes a plan against the current state:
+
+    - Objects in the desired state are created or updated by name.
+    - Objects that are not in the desired state are deleted.  Pipelines to
+    delete must be shut down.
+    - New and modified programs are queued for compilation.
+    - Running and paused pipelines whose committed revision differs from the
+    desired state are upgraded to a new revision.  If the program of such a
+    pipeline must be compiled first, the step is marked as `deferred`:
+    applying the same state again once compilation succeeds redeploys the
+    pipeline.
+
+    With `dry_run`, the plan is returned without executing it.  Otherwise,
+    the plan is validated and executed without other API requests observing
+    intermediate states.  Applying the same desired state repeatedly is safe.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ApplyPlan, ErrorResponse]
+    """
+
+    return (
+        await asyncio_detailed(
+            client=client,
+            json_body=json_body,
+            dry_run=dry_run,
+        )
+    ).parsed
@@ -0,0 +1,182 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.bundle_format import BundleFormat
+from ...models.project_bundle import ProjectBundle
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    *,
+    format_: Union[Unset, None, BundleFormat] = UNSET,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    json_format_: Union[Unset, None, str] = UNSET
+    if not isinstance(format_, Unset):
+        json_format_ = format_.value if format_ else None
+
+    params["format"] = json_format_
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    return {
+        "method": "get",
+        "url": "/export",
+        "params": params,
+    }
+
+
+def _parse_response(*, client: Union[AuthenticatedClient, Client], response: httpx.Response) -> Optional[ProjectBundle]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = ProjectBundle.from_dict(response.json())
+
+        return response_200
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(*, client: Union[AuthenticatedClient, Client], response: httpx.Response) -> Response[ProjectBundle]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    format_: Union[Unset, None, BundleFormat] = UNSET,
+) -> Response[ProjectBundle]:
+    """Export the programs, connectors and pipelines of the tenant as a bundle.
+
+     Export the programs, connectors and pipelines of the tenant as a bundle.
+
+    The bundle can be imported into another pipeline manager installation
+    using the `/import` endpoint.  Objects in the bundle refer to each other
+    by name.
+
+    Args:
+        format_ (Union[Unset, None, BundleFormat]): Serialization format of a project bundle.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[ProjectBundle]
+    """
+
+    kwargs = _get_kwargs(
+        format_=format_,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    format_: Union[Unset, None, BundleFormat] = UNSET,
+) -> Optional[ProjectBundle]:
+    """Export the programs, connectors and pipelines of the tenant as a bundle.
+
+     Export the programs, connectors and pipelines of the tenant as a bundle.
+
+    The bundle can be imported into another pipeline manager installation
+    using the `/import` endpoint.  Objects in the bundle refer to each other
+    by name.
+
+    Args:
+        format_ (Union[Unset, None, BundleFormat]): Serialization format of a project bundle.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        ProjectBundle
+    """
+
+    return sync_detailed(
+        client=client,
+        format_=format_,
+    ).parsed
+
+
+async def asyncio_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    format_: Union[Unset, None, BundleFormat] = UNSET,
+) -> Response[ProjectBundle]:
+    """Export the programs, connectors and pipelines of the tenant as a bundle.
+
+     Export the programs, connectors and pipelines of the tenant as a bundle.
+
+    The bundle can be imported into another pipeline manager installation
+    using the `/import` endpoint.  Objects in the bundle refer to each other
+    by name.
+
+    Args:
+        format_ (Union[Unset, None, BundleFormat]): Serialization format of a project bundle.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[ProjectBundle]
+    """
+
+    kwargs = _get_kwargs(
+        format_=format_,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    format_: Union[Unset, None, BundleFormat] = UNSET,
+) -> Optional[ProjectBundle]:
+    """Export the programs, connectors and pipelines of the tenant as a bundle.
+
+     Export the programs, connectors and pipelines of the tenant as a bundle.
+
+    The bundle can be imported into another pipeline manager installation
+    using the `/import` endpoint.  Objects in the bundle refer to each other
+    by name.
+
+    Args:
+        format_ (Union[Unset, None, BundleFormat]): Serialization format of a project bundle.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        ProjectBundle
+    """
+
+    return (
+        await asyncio_detailed(
+            client=client,
+            format_=format_,
+        )
+    ).parsed
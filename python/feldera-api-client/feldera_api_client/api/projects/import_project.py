@@ -0,0 +1,251 @@
+from http import HTTPStatus
+from typing import Any, Dict, Optional, Union
+
+import httpx
+
+from ... import errors
+from ...client import AuthenticatedClient, Client
+from ...models.error_response import ErrorResponse
+from ...models.import_report import ImportReport
+from ...models.project_bundle import ProjectBundle
+from ...types import UNSET, Response, Unset
+
+
+def _get_kwargs(
+    *,
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Dict[str, Any]:
+    pass
+
+    params: Dict[str, Any] = {}
+    params["dry_run"] = dry_run
+
+    params = {k: v for k, v in params.items() if v is not UNSET and v is not None}
+
+    json_json_body = json_body.to_dict()
+
+    return {
+        "method": "post",
+        "url": "/import",
+        "json": json_json_body,
+        "params": params,
+    }
+
+
+def _parse_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Optional[Union[ErrorResponse, ImportReport]]:
+    if response.status_code == HTTPStatus.OK:
+        response_200 = ImportReport.from_dict(response.json())
+
+        return response_200
+    if response.status_code == HTTPStatus.BAD_REQUEST:
+        response_400 = ErrorResponse.from_dict(response.json())
+
+        return response_400
+    if client.raise_on_unexpected_status:
+        raise errors.UnexpectedStatus(response.status_code, response.content)
+    else:
+        return None
+
+
+def _build_response(
+    *, client: Union[AuthenticatedClient, Client], response: httpx.Response
+) -> Response[Union[ErrorResponse, ImportReport]]:
+    return Response(
+        status_code=HTTPStatus(response.status_code),
+        content=response.content,
+        headers=response.headers,
+        parsed=_parse_response(client=client, response=response),
+    )
+
+
+def sync_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ErrorResponse, ImportReport]]:
+    """Import a project bundle.
+
+     Import a project bundle.
+
+    The bundle, in JSON or YAML format, is typically produced by the
+    `/export` endpoint of another pipeline manager installation.  Programs,
+    connectors and pipelines are matched by name: objects that don't exist
+    are created, existing objects that differ from the bundle are updated.
+    Updated programs must be compiled again.  The import is atomic: if one of
+    the objects cannot be imported, nothing is modified.
+
+    The response describes what was done with each object, including the
+    fields of existing objects that were overwritten.  With `dry_run`, nothing
+    is modified, which can be used to review conflicts before importing.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, ImportReport]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+        dry_run=dry_run,
+    )
+
+    response = client.get_httpx_client().request(
+        **kwargs,
+    )
+
+    return _build_response(client=client, response=response)
+
+
+def sync(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ErrorResponse, ImportReport]]:
+    """Import a project bundle.
+
+     Import a project bundle.
+
+    The bundle, in JSON or YAML format, is typically produced by the
+    `/export` endpoint of another pipeline manager installation.  Programs,
+    connectors and pipelines are matched by name: objects that don't exist
+    are created, existing objects that differ from the bundle are updated.
+    Updated programs must be compiled again.  The import is atomic: if one of
+    the objects cannot be imported, nothing is modified.
+
+    The response describes what was done with each object, including the
+    fields of existing objects that were overwritten.  With `dry_run`, nothing
+    is modified, which can be used to review conflicts before importing.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, ImportReport]
+    """
+
+    return sync_detailed(
+        client=client,
+        json_body=json_body,
+        dry_run=dry_run,
+    ).parsed
+
+
+async def asyncio_detailed(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Response[Union[ErrorResponse, ImportReport]]:
+    """Import a project bundle.
+
+     Import a project bundle.
+
+    The bundle, in JSON or YAML format, is typically produced by the
+    `/export` endpoint of another pipeline manager installation.  Programs,
+    connectors and pipelines are matched by name: objects that don't exist
+    are created, existing objects that differ from the bundle are updated.
+    Updated programs must be compiled again.  The import is atomic: if one of
+    the objects cannot be imported, nothing is modified.
+
+    The response describes what was done with each object, including the
+    fields of existing objects that were overwritten.  With `dry_run`, nothing
+    is modified, which can be used to review conflicts before importing.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Response[Union[ErrorResponse, ImportReport]]
+    """
+
+    kwargs = _get_kwargs(
+        json_body=json_body,
+        dry_run=dry_run,
+    )
+
+    response = await client.get_async_httpx_client().request(**kwargs)
+
+    return _build_response(client=client, response=response)
+
+
+async def asyncio(
+    *,
+    client: Union[AuthenticatedClient, Client],
+    json_body: ProjectBundle,
+    dry_run: Union[Unset, None, bool] = UNSET,
+) -> Optional[Union[ErrorResponse, ImportReport]]:
+    """Import a project bundle.
+
+     Import a project bundle.
+
+    The bundle, in JSON or YAML format, is typically produced by the
+    `/export` endpoint of another pipeline manager installation.  Programs,
+    connectors and pipelines are matched by name: objects that don't exist
+    are created, existing objects that differ from the bundle are updated.
+    Updated programs must be compiled again.  The import is atomic: if one of
+    the objects cannot be imported, nothing is modified.
+
+    The response describes what was done with each object, including the
+    fields of existing objects that were overwritten.  With `dry_run`, nothing
+    is modified, which can be used to review conflicts before importing.
+
+    Args:
+        dry_run (Union[Unset, None, bool]):
+        json_body (ProjectBundle): The programs, connectors and pipelines of a tenant, in a form
+            that can be
+            imported into another pipeline manager installation.
+
+            Objects refer to each other by name rather than by id, since ids are
+            specific to an installation.
+
+    Raises:
+        errors.UnexpectedStatus: If the server returns an undocumented status code and Client.raise_on_unexpected_status is True.
+        httpx.TimeoutException: If the request takes longer than Client.timeout.
+
+    Returns:
+        Union[ErrorResponse, ImportReport]
+    """
+
+    return (
+        await asyncio_detailed(
+            client=client,
+            json_body=json_body,
+            dry_run=dry_run,
+        )
+    ).parsed
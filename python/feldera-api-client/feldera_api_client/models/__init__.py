@@ -1,12 +1,28 @@
 """ Contains all the data models used in inputs/outputs """
 
+from .api_key_descr import ApiKeyDescr
+from .api_permission_type_0 import ApiPermissionType0
+from .api_permission_type_1 import ApiPermissionType1
+from .api_permission_type_2 import ApiPermissionType2
+from .api_permission_type_2_ingress import ApiPermissionType2Ingress
+from .api_permission_type_3 import ApiPermissionType3
+from .api_permission_type_3_egress import ApiPermissionType3Egress
+from .apply_action import ApplyAction
+from .apply_plan import ApplyPlan
+from .apply_step import ApplyStep
 from .attached_connector import AttachedConnector
+from .bundle_format import BundleFormat
+from .bundled_attached_connector import BundledAttachedConnector
+from .bundled_connector import BundledConnector
+from .bundled_pipeline import BundledPipeline
+from .bundled_program import BundledProgram
 from .chunk import Chunk
 from .chunk_json_data import ChunkJsonData
 from .column_type import ColumnType
 from .compile_program_request import CompileProgramRequest
 from .connector_config import ConnectorConfig
 from .connector_descr import ConnectorDescr
+from .connector_history_entry import ConnectorHistoryEntry
 from .csv_encoder_config import CsvEncoderConfig
 from .csv_parser_config import CsvParserConfig
 from .egress_mode import EgressMode
@@ -17,6 +33,9 @@ from .file_input_config import FileInputConfig
 from .file_output_config import FileOutputConfig
 from .format_config import FormatConfig
 from .format_config_config import FormatConfigConfig
+from .import_action import ImportAction
+from .import_report import ImportReport
+from .imported_object import ImportedObject
 from .input_endpoint_config import InputEndpointConfig
 from .json_encoder_config import JsonEncoderConfig
 from .json_parser_config import JsonParserConfig
@@ -28,12 +47,15 @@ from .kafka_output_config import KafkaOutputConfig
 from .kafka_output_config_log_level import KafkaOutputConfigLogLevel
 from .neighborhood_query import NeighborhoodQuery
 from .neighborhood_query_anchor import NeighborhoodQueryAnchor
+from .new_api_key_request import NewApiKeyRequest
+from .new_api_key_response import NewApiKeyResponse
 from .new_connector_request import NewConnectorRequest
 from .new_connector_response import NewConnectorResponse
 from .new_pipeline_request import NewPipelineRequest
 from .new_pipeline_response import NewPipelineResponse
 from .new_program_request import NewProgramRequest
 from .new_program_response import NewProgramResponse
+from .object_kind import ObjectKind
 from .output_endpoint_config import OutputEndpointConfig
 from .output_query import OutputQuery
 from .pipeline import Pipeline
@@ -41,12 +63,14 @@ from .pipeline_config import PipelineConfig
 from .pipeline_config_inputs import PipelineConfigInputs
 from .pipeline_config_outputs import PipelineConfigOutputs
 from .pipeline_descr import PipelineDescr
+from .pipeline_history_entry import PipelineHistoryEntry
 from .pipeline_revision import PipelineRevision
 from .pipeline_runtime_state import PipelineRuntimeState
 from .pipeline_stats_response_200 import PipelineStatsResponse200
 from .pipeline_status import PipelineStatus
 from .program_code_response import ProgramCodeResponse
 from .program_descr import ProgramDescr
+from .program_diff_response import ProgramDiffResponse
 from .program_schema import ProgramSchema
 from .program_status_type_0 import ProgramStatusType0
 from .program_status_type_1 import ProgramStatusType1
@@ -56,8 +80,12 @@ from .program_status_type_4 import ProgramStatusType4
 from .program_status_type_5 import ProgramStatusType5
 from .program_status_type_6 import ProgramStatusType6
 from .program_status_type_7 import ProgramStatusType7
+from .program_version_descr import ProgramVersionDescr
+from .project_bundle import ProjectBundle
 from .relation import Relation
 from .runtime_config import RuntimeConfig
+from .rust_compiler_message import RustCompilerMessage
+from .source_span import SourceSpan
 from .sql_compiler_message import SqlCompilerMessage
 from .transport_config import TransportConfig
 from .transport_config_config import TransportConfigConfig
@@ -69,13 +97,29 @@ from .update_program_request import UpdateProgramRequest
 from .update_program_response import UpdateProgramResponse
 
 __all__ = (
+    "ApiKeyDescr",
+    "ApiPermissionType0",
+    "ApiPermissionType1",
+    "ApiPermissionType2",
+    "ApiPermissionType2Ingress",
+    "ApiPermissionType3",
+    "ApiPermissionType3Egress",
+    "ApplyAction",
+    "ApplyPlan",
+    "ApplyStep",
     "AttachedConnector",
+    "BundleFormat",
+    "BundledAttachedConnector",
+    "BundledConnector",
+    "BundledPipeline",
+    "BundledProgram",
     "Chunk",
     "ChunkJsonData",
     "ColumnType",
     "CompileProgramRequest",
     "ConnectorConfig",
     "ConnectorDescr",
+    "ConnectorHistoryEntry",
     "CsvEncoderConfig",
     "CsvParserConfig",
     "EgressMode",
@@ -86,6 +130,9 @@ __all__ = (
     "FileOutputConfig",
     "FormatConfig",
     "FormatConfigConfig",
+    "ImportAction",
+    "ImportReport",
+    "ImportedObject",
     "InputEndpointConfig",
     "JsonEncoderConfig",
     "JsonParserConfig",
@@ -97,12 +144,15 @@ __all__ = (
     "KafkaOutputConfigLogLevel",
     "NeighborhoodQuery",
     "NeighborhoodQueryAnchor",
+    "NewApiKeyRequest",
+    "NewApiKeyResponse",
     "NewConnectorRequest",
     "NewConnectorResponse",
     "NewPipelineRequest",
     "NewPipelineResponse",
     "NewProgramRequest",
     "NewProgramResponse",
+    "ObjectKind",
     "OutputEndpointConfig",
     "OutputQuery",
     "Pipeline",
@@ -110,12 +160,14 @@ __all__ = (
     "PipelineConfigInputs",
     "PipelineConfigOutputs",
     "PipelineDescr",
+    "PipelineHistoryEntry",
     "PipelineRevision",
     "PipelineRuntimeState",
     "PipelineStatsResponse200",
     "PipelineStatus",
     "ProgramCodeResponse",
     "ProgramDescr",
+    "ProgramDiffResponse",
     "ProgramSchema",
     "ProgramStatusType0",
     "ProgramStatusType1",
@@ -125,8 +177,12 @@ __all__ = (
     "ProgramStatusType5",
     "ProgramStatusType6",
     "ProgramStatusType7",
+    "ProgramVersionDescr",
+    "ProjectBundle",
     "Relation",
     "RuntimeConfig",
+    "RustCompilerMessage",
+    "SourceSpan",
     "SqlCompilerMessage",
     "TransportConfig",
     "TransportConfigConfig",
@@ -0,0 +1,174 @@
+import datetime
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+from dateutil.parser import isoparse
+
+from ..models.api_permission_type_0 import ApiPermissionType0
+from ..models.api_permission_type_1 import ApiPermissionType1
+from ..types import UNSET, Unset
+
+if TYPE_CHECKING:
+    from ..models.api_permission_type_2 import ApiPermissionType2
+    from ..models.api_permission_type_3 import ApiPermissionType3
+
+
+T = TypeVar("T", bound="ApiKeyDescr")
+
+
+@define
+class ApiKeyDescr:
+    """API key metadata.  The key itself is only returned once, when the key is
+    created.
+
+    Attributes:
+        api_key_id (str): Unique API key id.
+        created (datetime.datetime): Time when the key was created.
+        name (str): Key name, unique within the tenant.
+        scopes (List[Union['ApiPermissionType2', 'ApiPermissionType3', ApiPermissionType0, ApiPermissionType1]]):
+            Permissions granted to the key.
+        last_used (Union[Unset, None, datetime.datetime]): Time when the key was last used to authenticate a request, if
+            ever.
+
+            Only updated once every [`API_KEY_LAST_USED_RESOLUTION`] seconds.
+    """
+
+    api_key_id: str
+    created: datetime.datetime
+    name: str
+    scopes: List[Union["ApiPermissionType2", "ApiPermissionType3", ApiPermissionType0, ApiPermissionType1]]
+    last_used: Union[Unset, None, datetime.datetime] = UNSET
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        from ..models.api_permission_type_2 import ApiPermissionType2
+
+        api_key_id = self.api_key_id
+        created = self.created.isoformat()
+
+        name = self.name
+        scopes = []
+        for scopes_item_data in self.scopes:
+            scopes_item: Union[Dict[str, Any], str]
+
+            if isinstance(scopes_item_data, ApiPermissionType0):
+                scopes_item = scopes_item_data.value
+
+            elif isinstance(scopes_item_data, ApiPermissionType1):
+                scopes_item = scopes_item_data.value
+
+            elif isinstance(scopes_item_data, ApiPermissionType2):
+                scopes_item = scopes_item_data.to_dict()
+
+            else:
+                scopes_item = scopes_item_data.to_dict()
+
+            scopes.append(scopes_item)
+
+        last_used: Union[Unset, None, str] = UNSET
+        if not isinstance(self.last_used, Unset):
+            last_used = self.last_used.isoformat() if self.last_used else None
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "api_key_id": api_key_id,
+                "created": created,
+                "name": name,
+                "scopes": scopes,
+            }
+        )
+        if last_used is not UNSET:
+            field_dict["last_used"] = last_used
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.api_permission_type_2 import ApiPermissionType2
+        from ..models.api_permission_type_3 import ApiPermissionType3
+
+        d = src_dict.copy()
+        api_key_id = d.pop("api_key_id")
+
+        created = isoparse(d.pop("created"))
+
+        name = d.pop("name")
+
+        scopes = []
+        _scopes = d.pop("scopes")
+        for scopes_item_data in _scopes:
+
+            def _parse_scopes_item(
+                data: object,
+            ) -> Union["ApiPermissionType2", "ApiPermissionType3", ApiPermissionType0, ApiPermissionType1]:
+                try:
+                    if not isinstance(data, str):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_0 = ApiPermissionType0(data)
+
+                    return componentsschemas_api_permission_type_0
+                except:  # noqa: E722
+                    pass
+                try:
+                    if not isinstance(data, str):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_1 = ApiPermissionType1(data)
+
+                    return componentsschemas_api_permission_type_1
+                except:  # noqa: E722
+                    pass
+                try:
+                    if not isinstance(data, dict):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_2 = ApiPermissionType2.from_dict(data)
+
+                    return componentsschemas_api_permission_type_2
+                except:  # noqa: E722
+                    pass
+                if not isinstance(data, dict):
+                    raise TypeError()
+                componentsschemas_api_permission_type_3 = ApiPermissionType3.from_dict(data)
+
+                return componentsschemas_api_permission_type_3
+
+            scopes_item = _parse_scopes_item(scopes_item_data)
+
+            scopes.append(scopes_item)
+
+        _last_used = d.pop("last_used", UNSET)
+        last_used: Union[Unset, None, datetime.datetime]
+        if _last_used is None:
+            last_used = None
+        elif isinstance(_last_used, Unset):
+            last_used = UNSET
+        else:
+            last_used = isoparse(_last_used)
+
+        api_key_descr = cls(
+            api_key_id=api_key_id,
+            created=created,
+            name=name,
+            scopes=scopes,
+            last_used=last_used,
+        )
+
+        api_key_descr.additional_properties = d
+        return api_key_descr
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,8 @@
+from enum import Enum
+
+
+class ApiPermissionType0(str, Enum):
+    READ = "Read"
+
+    def __str__(self) -> str:
+        return str(self.value)
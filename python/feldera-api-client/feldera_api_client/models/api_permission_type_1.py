@@ -0,0 +1,8 @@
+from enum import Enum
+
+
+class ApiPermissionType1(str, Enum):
+    WRITE = "Write"
+
+    def __str__(self) -> str:
+        return str(self.value)
@@ -0,0 +1,64 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.api_permission_type_2_ingress import ApiPermissionType2Ingress
+
+
+T = TypeVar("T", bound="ApiPermissionType2")
+
+
+@define
+class ApiPermissionType2:
+    """
+    Attributes:
+        ingress (ApiPermissionType2Ingress): Push data to the tables of a pipeline via its `ingress` endpoint.
+            When `relation` is set, only to the specified table.
+    """
+
+    ingress: "ApiPermissionType2Ingress"
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        ingress = self.ingress.to_dict()
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "Ingress": ingress,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.api_permission_type_2_ingress import ApiPermissionType2Ingress
+
+        d = src_dict.copy()
+        ingress = ApiPermissionType2Ingress.from_dict(d.pop("Ingress"))
+
+        api_permission_type_2 = cls(
+            ingress=ingress,
+        )
+
+        api_permission_type_2.additional_properties = d
+        return api_permission_type_2
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
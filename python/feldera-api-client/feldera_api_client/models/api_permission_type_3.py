@@ -0,0 +1,65 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.api_permission_type_3_egress import ApiPermissionType3Egress
+
+
+T = TypeVar("T", bound="ApiPermissionType3")
+
+
+@define
+class ApiPermissionType3:
+    """
+    Attributes:
+        egress (ApiPermissionType3Egress): Read data from the tables and views of a pipeline via its `egress`
+            endpoint.  When `relation` is set, only from the specified table or
+            view.
+    """
+
+    egress: "ApiPermissionType3Egress"
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        egress = self.egress.to_dict()
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "Egress": egress,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.api_permission_type_3_egress import ApiPermissionType3Egress
+
+        d = src_dict.copy()
+        egress = ApiPermissionType3Egress.from_dict(d.pop("Egress"))
+
+        api_permission_type_3 = cls(
+            egress=egress,
+        )
+
+        api_permission_type_3.additional_properties = d
+        return api_permission_type_3
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,70 @@
+from typing import Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+
+from ..types import UNSET, Unset
+
+T = TypeVar("T", bound="ApiPermissionType3Egress")
+
+
+@define
+class ApiPermissionType3Egress:
+    """Read data from the tables and views of a pipeline via its `egress`
+    endpoint.  When `relation` is set, only from the specified table or
+    view.
+
+    Attributes:
+        pipeline_id (str): Unique pipeline id.
+        relation (Union[Unset, None, str]):
+    """
+
+    pipeline_id: str
+    relation: Union[Unset, None, str] = UNSET
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        pipeline_id = self.pipeline_id
+        relation = self.relation
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "pipeline_id": pipeline_id,
+            }
+        )
+        if relation is not UNSET:
+            field_dict["relation"] = relation
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        pipeline_id = d.pop("pipeline_id")
+
+        relation = d.pop("relation", UNSET)
+
+        api_permission_type_3_egress = cls(
+            pipeline_id=pipeline_id,
+            relation=relation,
+        )
+
+        api_permission_type_3_egress.additional_properties = d
+        return api_permission_type_3_egress
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
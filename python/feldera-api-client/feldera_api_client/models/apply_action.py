@@ -0,0 +1,12 @@
+from enum import Enum
+
+
+class ApplyAction(str, Enum):
+    CREATE = "create"
+    DELETE = "delete"
+    RECOMPILE = "recompile"
+    REDEPLOY = "redeploy"
+    UPDATE = "update"
+
+    def __str__(self) -> str:
+        return str(self.value)
@@ -0,0 +1,81 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.apply_step import ApplyStep
+
+
+T = TypeVar("T", bound="ApplyPlan")
+
+
+@define
+class ApplyPlan:
+    """The steps that bring the programs, connectors and pipelines of a tenant to
+    a desired state, in the order in which they are executed.
+
+    Attributes:
+        dry_run (bool): If `true`, the plan was not executed.
+        steps (List['ApplyStep']):
+    """
+
+    dry_run: bool
+    steps: List["ApplyStep"]
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        dry_run = self.dry_run
+        steps = []
+        for steps_item_data in self.steps:
+            steps_item = steps_item_data.to_dict()
+
+            steps.append(steps_item)
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "dry_run": dry_run,
+                "steps": steps,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.apply_step import ApplyStep
+
+        d = src_dict.copy()
+        dry_run = d.pop("dry_run")
+
+        steps = []
+        _steps = d.pop("steps")
+        for steps_item_data in _steps:
+            steps_item = ApplyStep.from_dict(steps_item_data)
+
+            steps.append(steps_item)
+
+        apply_plan = cls(
+            dry_run=dry_run,
+            steps=steps,
+        )
+
+        apply_plan.additional_properties = d
+        return apply_plan
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
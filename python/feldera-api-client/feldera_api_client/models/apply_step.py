@@ -0,0 +1,94 @@
+from typing import Any, Dict, List, Type, TypeVar, cast
+
+from attrs import define, field
+
+from ..models.apply_action import ApplyAction
+from ..models.object_kind import ObjectKind
+
+T = TypeVar("T", bound="ApplyStep")
+
+
+@define
+class ApplyStep:
+    """A step of an [`ApplyPlan`].
+
+    Attributes:
+        action (ApplyAction): An action of an [`ApplyPlan`].
+        changes (List[str]): Fields of the object that are updated.
+        deferred (bool): The step is skipped, because the program of the pipeline to redeploy
+            is not compiled yet.  Applying the same desired state again once
+            compilation succeeds redeploys the pipeline.
+        kind (ObjectKind): A kind of object of a [`ProjectBundle`].
+        name (str): Name of the object.
+    """
+
+    action: ApplyAction
+    changes: List[str]
+    deferred: bool
+    kind: ObjectKind
+    name: str
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        action = self.action.value
+
+        changes = self.changes
+
+        deferred = self.deferred
+        kind = self.kind.value
+
+        name = self.name
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "action": action,
+                "changes": changes,
+                "deferred": deferred,
+                "kind": kind,
+                "name": name,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        action = ApplyAction(d.pop("action"))
+
+        changes = cast(List[str], d.pop("changes"))
+
+        deferred = d.pop("deferred")
+
+        kind = ObjectKind(d.pop("kind"))
+
+        name = d.pop("name")
+
+        apply_step = cls(
+            action=action,
+            changes=changes,
+            deferred=deferred,
+            kind=kind,
+            name=name,
+        )
+
+        apply_step.additional_properties = d
+        return apply_step
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,9 @@
+from enum import Enum
+
+
+class BundleFormat(str, Enum):
+    JSON = "json"
+    YAML = "yaml"
+
+    def __str__(self) -> str:
+        return str(self.value)
@@ -0,0 +1,108 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+
+from ..types import UNSET, Unset
+
+if TYPE_CHECKING:
+    from ..models.bundled_attached_connector import BundledAttachedConnector
+    from ..models.runtime_config import RuntimeConfig
+
+
+T = TypeVar("T", bound="BundledPipeline")
+
+
+@define
+class BundledPipeline:
+    """A pipeline in a [`ProjectBundle`].
+
+    Attributes:
+        attached_connectors (List['BundledAttachedConnector']):
+        config (RuntimeConfig): Global pipeline configuration settings.
+        description (str):
+        name (str):
+        program_name (Union[Unset, None, str]): Name of the program of the pipeline, if any.
+    """
+
+    attached_connectors: List["BundledAttachedConnector"]
+    config: "RuntimeConfig"
+    description: str
+    name: str
+    program_name: Union[Unset, None, str] = UNSET
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        attached_connectors = []
+        for attached_connectors_item_data in self.attached_connectors:
+            attached_connectors_item = attached_connectors_item_data.to_dict()
+
+            attached_connectors.append(attached_connectors_item)
+
+        config = self.config.to_dict()
+
+        description = self.description
+        name = self.name
+        program_name = self.program_name
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "attached_connectors": attached_connectors,
+                "config": config,
+                "description": description,
+                "name": name,
+            }
+        )
+        if program_name is not UNSET:
+            field_dict["program_name"] = program_name
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.bundled_attached_connector import BundledAttachedConnector
+        from ..models.runtime_config import RuntimeConfig
+
+        d = src_dict.copy()
+        attached_connectors = []
+        _attached_connectors = d.pop("attached_connectors")
+        for attached_connectors_item_data in _attached_connectors:
+            attached_connectors_item = BundledAttachedConnector.from_dict(attached_connectors_item_data)
+
+            attached_connectors.append(attached_connectors_item)
+
+        config = RuntimeConfig.from_dict(d.pop("config"))
+
+        description = d.pop("description")
+
+        name = d.pop("name")
+
+        program_name = d.pop("program_name", UNSET)
+
+        bundled_pipeline = cls(
+            attached_connectors=attached_connectors,
+            config=config,
+            description=description,
+            name=name,
+            program_name=program_name,
+        )
+
+        bundled_pipeline.additional_properties = d
+        return bundled_pipeline
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,72 @@
+from typing import Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+T = TypeVar("T", bound="BundledProgram")
+
+
+@define
+class BundledProgram:
+    """A program in a [`ProjectBundle`].
+
+    Attributes:
+        code (str): SQL code of the program.
+        description (str):
+        name (str):
+    """
+
+    code: str
+    description: str
+    name: str
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        code = self.code
+        description = self.description
+        name = self.name
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "code": code,
+                "description": description,
+                "name": name,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        code = d.pop("code")
+
+        description = d.pop("description")
+
+        name = d.pop("name")
+
+        bundled_program = cls(
+            code=code,
+            description=description,
+            name=name,
+        )
+
+        bundled_program.additional_properties = d
+        return bundled_program
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,79 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.connector_descr import ConnectorDescr
+
+
+T = TypeVar("T", bound="ConnectorHistoryEntry")
+
+
+@define
+class ConnectorHistoryEntry:
+    """A connector as it was committed as part of a pipeline revision.
+
+    Attributes:
+        connector (ConnectorDescr): Connector descriptor.
+        pipeline_id (str): Unique pipeline id.
+        revision (str): Revision number.
+    """
+
+    connector: "ConnectorDescr"
+    pipeline_id: str
+    revision: str
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        connector = self.connector.to_dict()
+
+        pipeline_id = self.pipeline_id
+        revision = self.revision
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "connector": connector,
+                "pipeline_id": pipeline_id,
+                "revision": revision,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.connector_descr import ConnectorDescr
+
+        d = src_dict.copy()
+        connector = ConnectorDescr.from_dict(d.pop("connector"))
+
+        pipeline_id = d.pop("pipeline_id")
+
+        revision = d.pop("revision")
+
+        connector_history_entry = cls(
+            connector=connector,
+            pipeline_id=pipeline_id,
+            revision=revision,
+        )
+
+        connector_history_entry.additional_properties = d
+        return connector_history_entry
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
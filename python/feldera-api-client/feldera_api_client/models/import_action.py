@@ -0,0 +1,10 @@
+from enum import Enum
+
+
+class ImportAction(str, Enum):
+    CREATE = "create"
+    UNCHANGED = "unchanged"
+    UPDATE = "update"
+
+    def __str__(self) -> str:
+        return str(self.value)
@@ -0,0 +1,114 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.imported_object import ImportedObject
+
+
+T = TypeVar("T", bound="ImportReport")
+
+
+@define
+class ImportReport:
+    """The outcome of importing a [`ProjectBundle`].
+
+    Attributes:
+        connectors (List['ImportedObject']):
+        dry_run (bool): If `true`, nothing was modified.
+        pipelines (List['ImportedObject']):
+        programs (List['ImportedObject']):
+    """
+
+    connectors: List["ImportedObject"]
+    dry_run: bool
+    pipelines: List["ImportedObject"]
+    programs: List["ImportedObject"]
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        connectors = []
+        for connectors_item_data in self.connectors:
+            connectors_item = connectors_item_data.to_dict()
+
+            connectors.append(connectors_item)
+
+        dry_run = self.dry_run
+        pipelines = []
+        for pipelines_item_data in self.pipelines:
+            pipelines_item = pipelines_item_data.to_dict()
+
+            pipelines.append(pipelines_item)
+
+        programs = []
+        for programs_item_data in self.programs:
+            programs_item = programs_item_data.to_dict()
+
+            programs.append(programs_item)
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "connectors": connectors,
+                "dry_run": dry_run,
+                "pipelines": pipelines,
+                "programs": programs,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.imported_object import ImportedObject
+
+        d = src_dict.copy()
+        connectors = []
+        _connectors = d.pop("connectors")
+        for connectors_item_data in _connectors:
+            connectors_item = ImportedObject.from_dict(connectors_item_data)
+
+            connectors.append(connectors_item)
+
+        dry_run = d.pop("dry_run")
+
+        pipelines = []
+        _pipelines = d.pop("pipelines")
+        for pipelines_item_data in _pipelines:
+            pipelines_item = ImportedObject.from_dict(pipelines_item_data)
+
+            pipelines.append(pipelines_item)
+
+        programs = []
+        _programs = d.pop("programs")
+        for programs_item_data in _programs:
+            programs_item = ImportedObject.from_dict(programs_item_data)
+
+            programs.append(programs_item)
+
+        import_report = cls(
+            connectors=connectors,
+            dry_run=dry_run,
+            pipelines=pipelines,
+            programs=programs,
+        )
+
+        import_report.additional_properties = d
+        return import_report
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,78 @@
+from typing import Any, Dict, List, Type, TypeVar, cast
+
+from attrs import define, field
+
+from ..models.import_action import ImportAction
+
+T = TypeVar("T", bound="ImportedObject")
+
+
+@define
+class ImportedObject:
+    """The outcome of importing one object of a [`ProjectBundle`].
+
+    Attributes:
+        action (ImportAction): What importing a [`ProjectBundle`] does, or would do in a dry run, with
+            an object of the bundle.
+        conflicts (List[str]): Fields of the existing object that differ from the bundle, i.e., the
+            conflicts resolved in favor of the bundle.
+        name (str):
+    """
+
+    action: ImportAction
+    conflicts: List[str]
+    name: str
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        action = self.action.value
+
+        conflicts = self.conflicts
+
+        name = self.name
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "action": action,
+                "conflicts": conflicts,
+                "name": name,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        action = ImportAction(d.pop("action"))
+
+        conflicts = cast(List[str], d.pop("conflicts"))
+
+        name = d.pop("name")
+
+        imported_object = cls(
+            action=action,
+            conflicts=conflicts,
+            name=name,
+        )
+
+        imported_object.additional_properties = d
+        return imported_object
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
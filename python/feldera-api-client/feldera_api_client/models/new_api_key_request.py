@@ -0,0 +1,134 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+
+from ..models.api_permission_type_0 import ApiPermissionType0
+from ..models.api_permission_type_1 import ApiPermissionType1
+
+if TYPE_CHECKING:
+    from ..models.api_permission_type_2 import ApiPermissionType2
+    from ..models.api_permission_type_3 import ApiPermissionType3
+
+
+T = TypeVar("T", bound="NewApiKeyRequest")
+
+
+@define
+class NewApiKeyRequest:
+    """Request to create a new API key.
+
+    Attributes:
+        name (str): Key name, unique within the tenant. Example: ingest-orders.
+        scopes (List[Union['ApiPermissionType2', 'ApiPermissionType3', ApiPermissionType0, ApiPermissionType1]]):
+            Permissions granted to the key.
+    """
+
+    name: str
+    scopes: List[Union["ApiPermissionType2", "ApiPermissionType3", ApiPermissionType0, ApiPermissionType1]]
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        from ..models.api_permission_type_2 import ApiPermissionType2
+
+        name = self.name
+        scopes = []
+        for scopes_item_data in self.scopes:
+            scopes_item: Union[Dict[str, Any], str]
+
+            if isinstance(scopes_item_data, ApiPermissionType0):
+                scopes_item = scopes_item_data.value
+
+            elif isinstance(scopes_item_data, ApiPermissionType1):
+                scopes_item = scopes_item_data.value
+
+            elif isinstance(scopes_item_data, ApiPermissionType2):
+                scopes_item = scopes_item_data.to_dict()
+
+            else:
+                scopes_item = scopes_item_data.to_dict()
+
+            scopes.append(scopes_item)
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "name": name,
+                "scopes": scopes,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.api_permission_type_2 import ApiPermissionType2
+        from ..models.api_permission_type_3 import ApiPermissionType3
+
+        d = src_dict.copy()
+        name = d.pop("name")
+
+        scopes = []
+        _scopes = d.pop("scopes")
+        for scopes_item_data in _scopes:
+
+            def _parse_scopes_item(
+                data: object,
+            ) -> Union["ApiPermissionType2", "ApiPermissionType3", ApiPermissionType0, ApiPermissionType1]:
+                try:
+                    if not isinstance(data, str):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_0 = ApiPermissionType0(data)
+
+                    return componentsschemas_api_permission_type_0
+                except:  # noqa: E722
+                    pass
+                try:
+                    if not isinstance(data, str):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_1 = ApiPermissionType1(data)
+
+                    return componentsschemas_api_permission_type_1
+                except:  # noqa: E722
+                    pass
+                try:
+                    if not isinstance(data, dict):
+                        raise TypeError()
+                    componentsschemas_api_permission_type_2 = ApiPermissionType2.from_dict(data)
+
+                    return componentsschemas_api_permission_type_2
+                except:  # noqa: E722
+                    pass
+                if not isinstance(data, dict):
+                    raise TypeError()
+                componentsschemas_api_permission_type_3 = ApiPermissionType3.from_dict(data)
+
+                return componentsschemas_api_permission_type_3
+
+            scopes_item = _parse_scopes_item(scopes_item_data)
+
+            scopes.append(scopes_item)
+
+        new_api_key_request = cls(
+            name=name,
+            scopes=scopes,
+        )
+
+        new_api_key_request.additional_properties = d
+        return new_api_key_request
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,10 @@
+from enum import Enum
+
+
+class ObjectKind(str, Enum):
+    CONNECTOR = "connector"
+    PIPELINE = "pipeline"
+    PROGRAM = "program"
+
+    def __str__(self) -> str:
+        return str(self.value)
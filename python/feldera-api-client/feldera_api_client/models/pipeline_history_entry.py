@@ -0,0 +1,81 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.pipeline_descr import PipelineDescr
+
+
+T = TypeVar("T", bound="PipelineHistoryEntry")
+
+
+@define
+class PipelineHistoryEntry:
+    """A committed revision of a pipeline.
+
+    Revisions are created every time a changed pipeline is deployed.
+
+    Attributes:
+        pipeline (PipelineDescr): Pipeline descriptor.
+        program_version (int): Version number.
+        revision (str): Revision number.
+    """
+
+    pipeline: "PipelineDescr"
+    program_version: int
+    revision: str
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        pipeline = self.pipeline.to_dict()
+
+        program_version = self.program_version
+        revision = self.revision
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "pipeline": pipeline,
+                "program_version": program_version,
+                "revision": revision,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.pipeline_descr import PipelineDescr
+
+        d = src_dict.copy()
+        pipeline = PipelineDescr.from_dict(d.pop("pipeline"))
+
+        program_version = d.pop("program_version")
+
+        revision = d.pop("revision")
+
+        pipeline_history_entry = cls(
+            pipeline=pipeline,
+            program_version=program_version,
+            revision=revision,
+        )
+
+        pipeline_history_entry.additional_properties = d
+        return pipeline_history_entry
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,86 @@
+from typing import Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+T = TypeVar("T", bound="ProgramDiffResponse")
+
+
+@define
+class ProgramDiffResponse:
+    """Response to a program diff request.
+
+    Attributes:
+        code (str): Line-by-line diff of the SQL code.  Every line is prefixed with `' '`
+            if it is present in both versions, `'-'` if it was removed and `'+'`
+            if it was added. Example:  CREATE TABLE Example(name varchar);
+            -CREATE VIEW V AS SELECT * FROM Example;
+            +CREATE VIEW V AS SELECT name FROM Example;
+            .
+        from_ (int): Version number.
+        schema (str): Line-by-line diff of the program schemas rendered as JSON, in the
+            same format as `code`.  A version that has not been compiled yet has
+            an empty schema.
+        to (int): Version number.
+    """
+
+    code: str
+    from_: int
+    schema: str
+    to: int
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        code = self.code
+        from_ = self.from_
+        schema = self.schema
+        to = self.to
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "code": code,
+                "from": from_,
+                "schema": schema,
+                "to": to,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        code = d.pop("code")
+
+        from_ = d.pop("from")
+
+        schema = d.pop("schema")
+
+        to = d.pop("to")
+
+        program_diff_response = cls(
+            code=code,
+            from_=from_,
+            schema=schema,
+            to=to,
+        )
+
+        program_diff_response.additional_properties = d
+        return program_diff_response
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -1,7 +1,11 @@
-from typing import Any, Dict, List, Type, TypeVar
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
 
 from attrs import define, field
 
+if TYPE_CHECKING:
+    from ..models.rust_compiler_message import RustCompilerMessage
+
+
 T = TypeVar("T", bound="ProgramStatusType6")
 
 
@@ -9,14 +13,18 @@ T = TypeVar("T", bound="ProgramStatusType6")
 class ProgramStatusType6:
     """
     Attributes:
-        rust_error (str): Rust compiler returned an error.
+        rust_error (List['RustCompilerMessage']): Rust compiler returned an error.
     """
 
-    rust_error: str
+    rust_error: List["RustCompilerMessage"]
     additional_properties: Dict[str, Any] = field(init=False, factory=dict)
 
     def to_dict(self) -> Dict[str, Any]:
-        rust_error = self.rust_error
+        rust_error = []
+        for rust_error_item_data in self.rust_error:
+            rust_error_item = rust_error_item_data.to_dict()
+
+            rust_error.append(rust_error_item)
 
         field_dict: Dict[str, Any] = {}
         field_dict.update(self.additional_properties)
@@ -30,8 +38,15 @@ class ProgramStatusType6:
 
     @classmethod
     def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.rust_compiler_message import RustCompilerMessage
+
         d = src_dict.copy()
-        rust_error = d.pop("RustError")
+        rust_error = []
+        _rust_error = d.pop("RustError")
+        for rust_error_item_data in _rust_error:
+            rust_error_item = RustCompilerMessage.from_dict(rust_error_item_data)
+
+            rust_error.append(rust_error_item)
 
         program_status_type_6 = cls(
             rust_error=rust_error,
@@ -0,0 +1,105 @@
+import datetime
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+from dateutil.parser import isoparse
+
+from ..types import UNSET, Unset
+
+if TYPE_CHECKING:
+    from ..models.program_schema import ProgramSchema
+
+
+T = TypeVar("T", bound="ProgramVersionDescr")
+
+
+@define
+class ProgramVersionDescr:
+    """A version of a program's code.
+
+    A new version is recorded every time the code of a program changes.
+
+    Attributes:
+        created (datetime.datetime): Time when the version was created.
+        version (int): Version number.
+        code (Union[Unset, None, str]): SQL code.
+        schema (Union[Unset, None, ProgramSchema]): A struct containting the tables (inputs) and views for a program.
+
+            Parse from the JSON data-type of the DDL generated by the SQL compiler.
+    """
+
+    created: datetime.datetime
+    version: int
+    code: Union[Unset, None, str] = UNSET
+    schema: Union[Unset, None, "ProgramSchema"] = UNSET
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        created = self.created.isoformat()
+
+        version = self.version
+        code = self.code
+        schema: Union[Unset, None, Dict[str, Any]] = UNSET
+        if not isinstance(self.schema, Unset):
+            schema = self.schema.to_dict() if self.schema else None
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "created": created,
+                "version": version,
+            }
+        )
+        if code is not UNSET:
+            field_dict["code"] = code
+        if schema is not UNSET:
+            field_dict["schema"] = schema
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.program_schema import ProgramSchema
+
+        d = src_dict.copy()
+        created = isoparse(d.pop("created"))
+
+        version = d.pop("version")
+
+        code = d.pop("code", UNSET)
+
+        _schema = d.pop("schema", UNSET)
+        schema: Union[Unset, None, ProgramSchema]
+        if _schema is None:
+            schema = None
+        elif isinstance(_schema, Unset):
+            schema = UNSET
+        else:
+            schema = ProgramSchema.from_dict(_schema)
+
+        program_version_descr = cls(
+            created=created,
+            version=version,
+            code=code,
+            schema=schema,
+        )
+
+        program_version_descr.additional_properties = d
+        return program_version_descr
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
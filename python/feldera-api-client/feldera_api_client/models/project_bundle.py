@@ -0,0 +1,122 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+if TYPE_CHECKING:
+    from ..models.bundled_connector import BundledConnector
+    from ..models.bundled_pipeline import BundledPipeline
+    from ..models.bundled_program import BundledProgram
+
+
+T = TypeVar("T", bound="ProjectBundle")
+
+
+@define
+class ProjectBundle:
+    """The programs, connectors and pipelines of a tenant, in a form that can be
+    imported into another pipeline manager installation.
+
+    Objects refer to each other by name rather than by id, since ids are
+    specific to an installation.
+
+    Attributes:
+        bundle_version (int): Version of the bundle format.
+        connectors (List['BundledConnector']):
+        pipelines (List['BundledPipeline']):
+        programs (List['BundledProgram']):
+    """
+
+    bundle_version: int
+    connectors: List["BundledConnector"]
+    pipelines: List["BundledPipeline"]
+    programs: List["BundledProgram"]
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        bundle_version = self.bundle_version
+        connectors = []
+        for connectors_item_data in self.connectors:
+            connectors_item = connectors_item_data.to_dict()
+
+            connectors.append(connectors_item)
+
+        pipelines = []
+        for pipelines_item_data in self.pipelines:
+            pipelines_item = pipelines_item_data.to_dict()
+
+            pipelines.append(pipelines_item)
+
+        programs = []
+        for programs_item_data in self.programs:
+            programs_item = programs_item_data.to_dict()
+
+            programs.append(programs_item)
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "bundle_version": bundle_version,
+                "connectors": connectors,
+                "pipelines": pipelines,
+                "programs": programs,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.bundled_connector import BundledConnector
+        from ..models.bundled_pipeline import BundledPipeline
+        from ..models.bundled_program import BundledProgram
+
+        d = src_dict.copy()
+        bundle_version = d.pop("bundle_version")
+
+        connectors = []
+        _connectors = d.pop("connectors")
+        for connectors_item_data in _connectors:
+            connectors_item = BundledConnector.from_dict(connectors_item_data)
+
+            connectors.append(connectors_item)
+
+        pipelines = []
+        _pipelines = d.pop("pipelines")
+        for pipelines_item_data in _pipelines:
+            pipelines_item = BundledPipeline.from_dict(pipelines_item_data)
+
+            pipelines.append(pipelines_item)
+
+        programs = []
+        _programs = d.pop("programs")
+        for programs_item_data in _programs:
+            programs_item = BundledProgram.from_dict(programs_item_data)
+
+            programs.append(programs_item)
+
+        project_bundle = cls(
+            bundle_version=bundle_version,
+            connectors=connectors,
+            pipelines=pipelines,
+            programs=programs,
+        )
+
+        project_bundle.additional_properties = d
+        return project_bundle
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,150 @@
+from typing import TYPE_CHECKING, Any, Dict, List, Type, TypeVar, Union
+
+from attrs import define, field
+
+from ..types import UNSET, Unset
+
+if TYPE_CHECKING:
+    from ..models.source_span import SourceSpan
+
+
+T = TypeVar("T", bound="RustCompilerMessage")
+
+
+@define
+class RustCompilerMessage:
+    """A Rust compiler error.
+
+    Built from the JSON diagnostics that `cargo build --message-format=json`
+    prints.  Errors in the Rust code generated for the program are mapped back
+    to the `CREATE VIEW` statement that the failing code was generated from
+    where possible.
+
+        Attributes:
+            level (str): Severity of the diagnostic, e.g., `error`.
+            message (str):
+            code (Union[Unset, None, str]): Error code, e.g., `E0308`.
+            file_name (Union[Unset, None, str]): File that contains `rust_span`, relative to the cargo workspace.
+            rendered (Union[Unset, None, str]): The diagnostic as printed by the compiler, including source snippets.
+            rust_span (Union[Unset, None, SourceSpan]): A range of lines and columns in a source file.
+
+                Line and column numbers start at 1.
+            sql_span (Union[Unset, None, SourceSpan]): A range of lines and columns in a source file.
+
+                Line and column numbers start at 1.
+            sql_view (Union[Unset, None, str]): SQL view whose generated code contains the error.
+    """
+
+    level: str
+    message: str
+    code: Union[Unset, None, str] = UNSET
+    file_name: Union[Unset, None, str] = UNSET
+    rendered: Union[Unset, None, str] = UNSET
+    rust_span: Union[Unset, None, "SourceSpan"] = UNSET
+    sql_span: Union[Unset, None, "SourceSpan"] = UNSET
+    sql_view: Union[Unset, None, str] = UNSET
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        level = self.level
+        message = self.message
+        code = self.code
+        file_name = self.file_name
+        rendered = self.rendered
+        rust_span: Union[Unset, None, Dict[str, Any]] = UNSET
+        if not isinstance(self.rust_span, Unset):
+            rust_span = self.rust_span.to_dict() if self.rust_span else None
+
+        sql_span: Union[Unset, None, Dict[str, Any]] = UNSET
+        if not isinstance(self.sql_span, Unset):
+            sql_span = self.sql_span.to_dict() if self.sql_span else None
+
+        sql_view = self.sql_view
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "level": level,
+                "message": message,
+            }
+        )
+        if code is not UNSET:
+            field_dict["code"] = code
+        if file_name is not UNSET:
+            field_dict["fileName"] = file_name
+        if rendered is not UNSET:
+            field_dict["rendered"] = rendered
+        if rust_span is not UNSET:
+            field_dict["rustSpan"] = rust_span
+        if sql_span is not UNSET:
+            field_dict["sqlSpan"] = sql_span
+        if sql_view is not UNSET:
+            field_dict["sqlView"] = sql_view
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        from ..models.source_span import SourceSpan
+
+        d = src_dict.copy()
+        level = d.pop("level")
+
+        message = d.pop("message")
+
+        code = d.pop("code", UNSET)
+
+        file_name = d.pop("fileName", UNSET)
+
+        rendered = d.pop("rendered", UNSET)
+
+        _rust_span = d.pop("rustSpan", UNSET)
+        rust_span: Union[Unset, None, SourceSpan]
+        if _rust_span is None:
+            rust_span = None
+        elif isinstance(_rust_span, Unset):
+            rust_span = UNSET
+        else:
+            rust_span = SourceSpan.from_dict(_rust_span)
+
+        _sql_span = d.pop("sqlSpan", UNSET)
+        sql_span: Union[Unset, None, SourceSpan]
+        if _sql_span is None:
+            sql_span = None
+        elif isinstance(_sql_span, Unset):
+            sql_span = UNSET
+        else:
+            sql_span = SourceSpan.from_dict(_sql_span)
+
+        sql_view = d.pop("sqlView", UNSET)
+
+        rust_compiler_message = cls(
+            level=level,
+            message=message,
+            code=code,
+            file_name=file_name,
+            rendered=rendered,
+            rust_span=rust_span,
+            sql_span=sql_span,
+            sql_view=sql_view,
+        )
+
+        rust_compiler_message.additional_properties = d
+        return rust_compiler_message
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
@@ -0,0 +1,81 @@
+from typing import Any, Dict, List, Type, TypeVar
+
+from attrs import define, field
+
+T = TypeVar("T", bound="SourceSpan")
+
+
+@define
+class SourceSpan:
+    """A range of lines and columns in a source file.
+
+    Line and column numbers start at 1.
+
+        Attributes:
+            end_column (int):
+            end_line_number (int):
+            start_column (int):
+            start_line_number (int):
+    """
+
+    end_column: int
+    end_line_number: int
+    start_column: int
+    start_line_number: int
+    additional_properties: Dict[str, Any] = field(init=False, factory=dict)
+
+    def to_dict(self) -> Dict[str, Any]:
+        end_column = self.end_column
+        end_line_number = self.end_line_number
+        start_column = self.start_column
+        start_line_number = self.start_line_number
+
+        field_dict: Dict[str, Any] = {}
+        field_dict.update(self.additional_properties)
+        field_dict.update(
+            {
+                "endColumn": end_column,
+                "endLineNumber": end_line_number,
+                "startColumn": start_column,
+                "startLineNumber": start_line_number,
+            }
+        )
+
+        return field_dict
+
+    @classmethod
+    def from_dict(cls: Type[T], src_dict: Dict[str, Any]) -> T:
+        d = src_dict.copy()
+        end_column = d.pop("endColumn")
+
+        end_line_number = d.pop("endLineNumber")
+
+        start_column = d.pop("startColumn")
+
+        start_line_number = d.pop("startLineNumber")
+
+        source_span = cls(
+            end_column=end_column,
+            end_line_number=end_line_number,
+            start_column=start_column,
+            start_line_number=start_line_number,
+        )
+
+        source_span.additional_properties = d
+        return source_span
+
+    @property
+    def additional_keys(self) -> List[str]:
+        return list(self.additional_properties.keys())
+
+    def __getitem__(self, key: str) -> Any:
+        return self.additional_properties[key]
+
+    def __setitem__(self, key: str, value: Any) -> None:
+        self.additional_properties[key] = value
+
+    def __delitem__(self, key: str) -> None:
+        del self.additional_properties[key]
+
+    def __contains__(self, key: str) -> bool:
+        return key in self.additional_properties
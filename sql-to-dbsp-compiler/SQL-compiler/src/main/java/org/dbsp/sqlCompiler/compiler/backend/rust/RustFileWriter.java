@@ -104,7 +104,7 @@ public class RustFileWriter implements ICompilerComponent {
             "         UnimplementedSemigroup, DefaultSemigroup, HasZero, AddByRef, NegByRef,\n" +
             "         AddAssignByRef,\n" +
             "    },\n" +
-            "    circuit::{Circuit, Stream},\n" +
+            "    circuit::{Circuit, IntoLayout, Stream},\n" +
             "    operator::{\n" +
             "        Generator,\n" +
             "        FilterMap,\n" +
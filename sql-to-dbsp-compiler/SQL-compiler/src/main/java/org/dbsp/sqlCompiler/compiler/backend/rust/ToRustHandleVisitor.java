@@ -43,8 +43,8 @@ import java.util.Locale;
 /**
  * Generate Rust for a circuit, but with an API using handles.
  * Output generated has this structure:
- * pub fn test_circuit(workers: usize) -> (DBSPHandle, Catalog) {
- *     let (circuit, catalog) = Runtime::init_circuit(workers, |circuit| {
+ * pub fn test_circuit(layout: impl IntoLayout) -> (DBSPHandle, Catalog) {
+ *     let (circuit, catalog) = Runtime::init_circuit(layout, |circuit| {
  *         let mut catalog = Catalog::new();
  *         let (input, handle0) = circuit.add_input_zset::<TestStruct, i32>();
  *         catalog.register_input_zset("test_input1", input, handles.0);
@@ -216,10 +216,10 @@ public class ToRustHandleVisitor extends ToRustVisitor {
     public VisitDecision preorder(DBSPPartialCircuit circuit) {
         this.builder.append("pub fn ")
                 .append(this.functionName)
-                .append("(workers: usize) -> (DBSPHandle, Catalog) {")
+                .append("(layout: impl IntoLayout) -> (DBSPHandle, Catalog) {")
                 .increase()
                 .newline()
-                .append("let (circuit, catalog) = Runtime::init_circuit(workers, |circuit| {")
+                .append("let (circuit, catalog) = Runtime::init_circuit(layout, |circuit| {")
                 .increase()
                 .append("let mut catalog = Catalog::new();");
 
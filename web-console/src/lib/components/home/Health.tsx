@@ -50,19 +50,24 @@ const programErrors = (program: ProgramDescr) =>
           })
       )
     )
-    .with({ RustError: P.select() }, e => [
-      new Error(e, {
-        cause: {
-          source: (
-            <>
-              Program RustError <ProgramLink program={program} />
-              <br />
-              {program.program_id}
-            </>
-          )
-        }
-      })
-    ])
+    .with({ RustError: P.select() }, es =>
+      es.map(
+        e =>
+          new Error(e.rendered ?? e.message, {
+            cause: {
+              ...e,
+              source: (
+                <>
+                  Program RustError <ProgramLink program={program} />
+                  {e.sqlView && <> in view {e.sqlView}</>}
+                  <br />
+                  {program.program_id}
+                </>
+              )
+            }
+          })
+      )
+    )
     .with({ SystemError: P.select() }, e => [
       new Error(e, {
         cause: {
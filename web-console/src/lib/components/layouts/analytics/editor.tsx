@@ -11,6 +11,7 @@ import {
   NewProgramRequest,
   NewProgramResponse,
   ProgramId,
+  RustCompilerMessage,
   SqlCompilerMessage,
   UpdateProgramRequest,
   UpdateProgramResponse
@@ -441,6 +442,24 @@ const useDisplayCompilerErrorsInEditor = (project: ProgramDescr, editorRef: Muta
           })
           monaco.editor.setModelMarkers(editorRef.current.getModel(), 'sql-errors', monaco_markers)
         })
+        .with({ RustError: P.select() }, (err: RustCompilerMessage[]) => {
+          // Highlight the views whose generated code failed to compile
+          const monaco_markers = err.flatMap(item =>
+            item.sqlSpan
+              ? [
+                  {
+                    startLineNumber: item.sqlSpan.startLineNumber,
+                    endLineNumber: item.sqlSpan.endLineNumber,
+                    startColumn: item.sqlSpan.startColumn,
+                    endColumn: item.sqlSpan.endColumn + 1,
+                    message: item.rendered ?? item.message,
+                    severity: item.level === 'error' ? monaco.MarkerSeverity.Error : monaco.MarkerSeverity.Warning
+                  }
+                ]
+              : []
+          )
+          monaco.editor.setModelMarkers(editorRef.current.getModel(), 'sql-errors', monaco_markers)
+        })
         .otherwise(() => {
           monaco.editor.setModelMarkers(editorRef.current.getModel(), 'sql-errors', [])
         })
@@ -6,13 +6,25 @@ export { CancelablePromise, CancelError } from './core/CancelablePromise'
 export { OpenAPI } from './core/OpenAPI'
 export type { OpenAPIConfig } from './core/OpenAPI'
 
+export type { ApiKeyDescr } from './models/ApiKeyDescr'
+export type { ApiKeyId } from './models/ApiKeyId'
+export type { ApiPermission } from './models/ApiPermission'
+export { ApplyAction } from './models/ApplyAction'
+export type { ApplyPlan } from './models/ApplyPlan'
+export type { ApplyStep } from './models/ApplyStep'
 export type { AttachedConnector } from './models/AttachedConnector'
 export type { AttachedConnectorId } from './models/AttachedConnectorId'
+export type { BundledAttachedConnector } from './models/BundledAttachedConnector'
+export type { BundledConnector } from './models/BundledConnector'
+export type { BundledPipeline } from './models/BundledPipeline'
+export type { BundledProgram } from './models/BundledProgram'
+export { BundleFormat } from './models/BundleFormat'
 export type { Chunk } from './models/Chunk'
 export type { ColumnType } from './models/ColumnType'
 export type { CompileProgramRequest } from './models/CompileProgramRequest'
 export type { ConnectorConfig } from './models/ConnectorConfig'
 export type { ConnectorDescr } from './models/ConnectorDescr'
+export type { ConnectorHistoryEntry } from './models/ConnectorHistoryEntry'
 export type { ConnectorId } from './models/ConnectorId'
 export type { CsvEncoderConfig } from './models/CsvEncoderConfig'
 export type { CsvParserConfig } from './models/CsvParserConfig'
@@ -22,6 +34,9 @@ export type { Field } from './models/Field'
 export type { FileInputConfig } from './models/FileInputConfig'
 export type { FileOutputConfig } from './models/FileOutputConfig'
 export type { FormatConfig } from './models/FormatConfig'
+export { ImportAction } from './models/ImportAction'
+export type { ImportedObject } from './models/ImportedObject'
+export type { ImportReport } from './models/ImportReport'
 export type { InputEndpointConfig } from './models/InputEndpointConfig'
 export type { JsonEncoderConfig } from './models/JsonEncoderConfig'
 export type { JsonParserConfig } from './models/JsonParserConfig'
@@ -30,29 +45,38 @@ export type { KafkaInputConfig } from './models/KafkaInputConfig'
 export { KafkaLogLevel } from './models/KafkaLogLevel'
 export type { KafkaOutputConfig } from './models/KafkaOutputConfig'
 export type { NeighborhoodQuery } from './models/NeighborhoodQuery'
+export type { NewApiKeyRequest } from './models/NewApiKeyRequest'
+export type { NewApiKeyResponse } from './models/NewApiKeyResponse'
 export type { NewConnectorRequest } from './models/NewConnectorRequest'
 export type { NewConnectorResponse } from './models/NewConnectorResponse'
 export type { NewPipelineRequest } from './models/NewPipelineRequest'
 export type { NewPipelineResponse } from './models/NewPipelineResponse'
 export type { NewProgramRequest } from './models/NewProgramRequest'
 export type { NewProgramResponse } from './models/NewProgramResponse'
+export { ObjectKind } from './models/ObjectKind'
 export type { OutputEndpointConfig } from './models/OutputEndpointConfig'
 export { OutputQuery } from './models/OutputQuery'
 export type { Pipeline } from './models/Pipeline'
 export type { PipelineConfig } from './models/PipelineConfig'
 export type { PipelineDescr } from './models/PipelineDescr'
+export type { PipelineHistoryEntry } from './models/PipelineHistoryEntry'
 export type { PipelineId } from './models/PipelineId'
 export type { PipelineRevision } from './models/PipelineRevision'
 export type { PipelineRuntimeState } from './models/PipelineRuntimeState'
 export { PipelineStatus } from './models/PipelineStatus'
 export type { ProgramCodeResponse } from './models/ProgramCodeResponse'
 export type { ProgramDescr } from './models/ProgramDescr'
+export type { ProgramDiffResponse } from './models/ProgramDiffResponse'
 export type { ProgramId } from './models/ProgramId'
 export type { ProgramSchema } from './models/ProgramSchema'
 export type { ProgramStatus } from './models/ProgramStatus'
+export type { ProgramVersionDescr } from './models/ProgramVersionDescr'
+export type { ProjectBundle } from './models/ProjectBundle'
 export type { Relation } from './models/Relation'
 export type { Revision } from './models/Revision'
 export type { RuntimeConfig } from './models/RuntimeConfig'
+export type { RustCompilerMessage } from './models/RustCompilerMessage'
+export type { SourceSpan } from './models/SourceSpan'
 export type { SqlCompilerMessage } from './models/SqlCompilerMessage'
 export type { TenantId } from './models/TenantId'
 export type { TransportConfig } from './models/TransportConfig'
@@ -64,6 +88,8 @@ export type { UpdateProgramRequest } from './models/UpdateProgramRequest'
 export type { UpdateProgramResponse } from './models/UpdateProgramResponse'
 export type { Version } from './models/Version'
 
+export { ApiKeysService } from './services/ApiKeysService'
 export { ConnectorsService } from './services/ConnectorsService'
 export { PipelinesService } from './services/PipelinesService'
 export { ProgramsService } from './services/ProgramsService'
+export { ProjectsService } from './services/ProjectsService'
@@ -0,0 +1,32 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ApiKeyId } from './ApiKeyId'
+import type { ApiPermission } from './ApiPermission'
+
+/**
+ * API key metadata.  The key itself is only returned once, when the key is
+ * created.
+ */
+export type ApiKeyDescr = {
+  api_key_id: ApiKeyId
+  /**
+   * Time when the key was created.
+   */
+  created: string
+  /**
+   * Time when the key was last used to authenticate a request, if ever.
+   *
+   * Only updated once every [`API_KEY_LAST_USED_RESOLUTION`] seconds.
+   */
+  last_used?: string | null
+  /**
+   * Key name, unique within the tenant.
+   */
+  name: string
+  /**
+   * Permissions granted to the key.
+   */
+  scopes: Array<ApiPermission>
+}
@@ -0,0 +1,39 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { PipelineId } from './PipelineId'
+
+/**
+ * Permission types for invoking pipeline manager APIs.
+ *
+ * `Read` and `Write` apply to all resources of the tenant.  The remaining
+ * permissions are scoped to a single pipeline and, optionally, a single
+ * table or view of the pipeline.  Permissions are stored in the database as
+ * strings (see the `Display` and `FromStr` implementations), e.g.,
+ * `read`, `egress:<pipeline_id>`, or `ingress:<pipeline_id>:<table>`.
+ */
+export type ApiPermission =
+  | 'Read'
+  | 'Write'
+  | {
+      /**
+       * Push data to the tables of a pipeline via its `ingress` endpoint.
+       * When `relation` is set, only to the specified table.
+       */
+      Ingress: {
+        pipeline_id: PipelineId
+        relation?: string | null
+      }
+    }
+  | {
+      /**
+       * Read data from the tables and views of a pipeline via its `egress`
+       * endpoint.  When `relation` is set, only from the specified table or
+       * view.
+       */
+      Egress: {
+        pipeline_id: PipelineId
+        relation?: string | null
+      }
+    }
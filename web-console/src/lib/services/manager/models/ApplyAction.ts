@@ -0,0 +1,14 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * An action of an [`ApplyPlan`].
+ */
+export enum ApplyAction {
+  CREATE = 'create',
+  UPDATE = 'update',
+  DELETE = 'delete',
+  RECOMPILE = 'recompile',
+  REDEPLOY = 'redeploy'
+}
@@ -0,0 +1,17 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ApplyStep } from './ApplyStep'
+
+/**
+ * The steps that bring the programs, connectors and pipelines of a tenant to
+ * a desired state, in the order in which they are executed.
+ */
+export type ApplyPlan = {
+  /**
+   * If `true`, the plan was not executed.
+   */
+  dry_run: boolean
+  steps: Array<ApplyStep>
+}
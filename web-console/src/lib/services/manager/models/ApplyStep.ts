@@ -0,0 +1,28 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ApplyAction } from './ApplyAction'
+import type { ObjectKind } from './ObjectKind'
+
+/**
+ * A step of an [`ApplyPlan`].
+ */
+export type ApplyStep = {
+  action: ApplyAction
+  /**
+   * Fields of the object that are updated.
+   */
+  changes: Array<string>
+  /**
+   * The step is skipped, because the program of the pipeline to redeploy
+   * is not compiled yet.  Applying the same desired state again once
+   * compilation succeeds redeploys the pipeline.
+   */
+  deferred: boolean
+  kind: ObjectKind
+  /**
+   * Name of the object.
+   */
+  name: string
+}
@@ -0,0 +1,11 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * Serialization format of a project bundle.
+ */
+export enum BundleFormat {
+  JSON = 'json',
+  YAML = 'yaml'
+}
@@ -0,0 +1,25 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * A connector attached to a pipeline in a [`ProjectBundle`].
+ */
+export type BundledAttachedConnector = {
+  /**
+   * Name of the connector to attach.
+   */
+  connector_name: string
+  /**
+   * Is this an input or an output?
+   */
+  is_input: boolean
+  /**
+   * A unique identifier for this attachement.
+   */
+  name: string
+  /**
+   * The table or view this connector is attached to.
+   */
+  relation_name: string
+}
@@ -0,0 +1,14 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ConnectorConfig } from './ConnectorConfig'
+
+/**
+ * A connector in a [`ProjectBundle`].
+ */
+export type BundledConnector = {
+  config: ConnectorConfig
+  description: string
+  name: string
+}
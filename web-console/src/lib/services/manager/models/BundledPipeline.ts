@@ -0,0 +1,20 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { BundledAttachedConnector } from './BundledAttachedConnector'
+import type { RuntimeConfig } from './RuntimeConfig'
+
+/**
+ * A pipeline in a [`ProjectBundle`].
+ */
+export type BundledPipeline = {
+  attached_connectors: Array<BundledAttachedConnector>
+  config: RuntimeConfig
+  description: string
+  name: string
+  /**
+   * Name of the program of the pipeline, if any.
+   */
+  program_name?: string | null
+}
@@ -0,0 +1,15 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * A program in a [`ProjectBundle`].
+ */
+export type BundledProgram = {
+  /**
+   * SQL code of the program.
+   */
+  code: string
+  description: string
+  name: string
+}
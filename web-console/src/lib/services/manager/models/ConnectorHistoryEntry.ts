@@ -0,0 +1,16 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ConnectorDescr } from './ConnectorDescr'
+import type { PipelineId } from './PipelineId'
+import type { Revision } from './Revision'
+
+/**
+ * A connector as it was committed as part of a pipeline revision.
+ */
+export type ConnectorHistoryEntry = {
+  connector: ConnectorDescr
+  pipeline_id: PipelineId
+  revision: Revision
+}
@@ -0,0 +1,13 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * What importing a [`ProjectBundle`] does, or would do in a dry run, with
+ * an object of the bundle.
+ */
+export enum ImportAction {
+  CREATE = 'create',
+  UPDATE = 'update',
+  UNCHANGED = 'unchanged'
+}
@@ -0,0 +1,18 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ImportedObject } from './ImportedObject'
+
+/**
+ * The outcome of importing a [`ProjectBundle`].
+ */
+export type ImportReport = {
+  connectors: Array<ImportedObject>
+  /**
+   * If `true`, nothing was modified.
+   */
+  dry_run: boolean
+  pipelines: Array<ImportedObject>
+  programs: Array<ImportedObject>
+}
@@ -0,0 +1,18 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ImportAction } from './ImportAction'
+
+/**
+ * The outcome of importing one object of a [`ProjectBundle`].
+ */
+export type ImportedObject = {
+  action: ImportAction
+  /**
+   * Fields of the existing object that differ from the bundle, i.e., the
+   * conflicts resolved in favor of the bundle.
+   */
+  conflicts: Array<string>
+  name: string
+}
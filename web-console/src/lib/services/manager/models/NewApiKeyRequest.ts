@@ -0,0 +1,19 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ApiPermission } from './ApiPermission'
+
+/**
+ * Request to create a new API key.
+ */
+export type NewApiKeyRequest = {
+  /**
+   * Key name, unique within the tenant.
+   */
+  name: string
+  /**
+   * Permissions granted to the key.
+   */
+  scopes: Array<ApiPermission>
+}
@@ -0,0 +1,21 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ApiKeyId } from './ApiKeyId'
+
+/**
+ * Response to an API key creation request.
+ */
+export type NewApiKeyResponse = {
+  /**
+   * Generated API key.  The pipeline manager only stores a hash of the
+   * key, so this is the only time the key is returned to the client.
+   */
+  api_key: string
+  api_key_id: ApiKeyId
+  /**
+   * Key name.
+   */
+  name: string
+}
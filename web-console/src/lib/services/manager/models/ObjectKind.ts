@@ -0,0 +1,12 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * A kind of object of a [`ProjectBundle`].
+ */
+export enum ObjectKind {
+  PROGRAM = 'program',
+  CONNECTOR = 'connector',
+  PIPELINE = 'pipeline'
+}
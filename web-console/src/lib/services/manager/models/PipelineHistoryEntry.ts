@@ -0,0 +1,18 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { PipelineDescr } from './PipelineDescr'
+import type { Revision } from './Revision'
+import type { Version } from './Version'
+
+/**
+ * A committed revision of a pipeline.
+ *
+ * Revisions are created every time a changed pipeline is deployed.
+ */
+export type PipelineHistoryEntry = {
+  pipeline: PipelineDescr
+  program_version: Version
+  revision: Revision
+}
@@ -0,0 +1,25 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { Version } from './Version'
+
+/**
+ * Response to a program diff request.
+ */
+export type ProgramDiffResponse = {
+  /**
+   * Line-by-line diff of the SQL code.  Every line is prefixed with `' '`
+   * if it is present in both versions, `'-'` if it was removed and `'+'`
+   * if it was added.
+   */
+  code: string
+  from: Version
+  /**
+   * Line-by-line diff of the program schemas rendered as JSON, in the
+   * same format as `code`.  A version that has not been compiled yet has
+   * an empty schema.
+   */
+  schema: string
+  to: Version
+}
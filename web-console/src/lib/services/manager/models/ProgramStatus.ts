@@ -2,6 +2,7 @@
 /* tslint:disable */
 /* eslint-disable */
 
+import type { RustCompilerMessage } from './RustCompilerMessage'
 import type { SqlCompilerMessage } from './SqlCompilerMessage'
 
 /**
@@ -23,7 +24,7 @@ export type ProgramStatus =
       /**
        * Rust compiler returned an error.
        */
-      RustError: string
+      RustError: Array<RustCompilerMessage>
     }
   | {
       /**
@@ -0,0 +1,24 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { ProgramSchema } from './ProgramSchema'
+import type { Version } from './Version'
+
+/**
+ * A version of a program's code.
+ *
+ * A new version is recorded every time the code of a program changes.
+ */
+export type ProgramVersionDescr = {
+  /**
+   * SQL code.
+   */
+  code?: string | null
+  /**
+   * Time when the version was created.
+   */
+  created: string
+  schema?: ProgramSchema | null
+  version: Version
+}
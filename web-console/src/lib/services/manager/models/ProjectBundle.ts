@@ -0,0 +1,24 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { BundledConnector } from './BundledConnector'
+import type { BundledPipeline } from './BundledPipeline'
+import type { BundledProgram } from './BundledProgram'
+
+/**
+ * The programs, connectors and pipelines of a tenant, in a form that can be
+ * imported into another pipeline manager installation.
+ *
+ * Objects refer to each other by name rather than by id, since ids are
+ * specific to an installation.
+ */
+export type ProjectBundle = {
+  /**
+   * Version of the bundle format.
+   */
+  bundle_version: number
+  connectors: Array<BundledConnector>
+  pipelines: Array<BundledPipeline>
+  programs: Array<BundledProgram>
+}
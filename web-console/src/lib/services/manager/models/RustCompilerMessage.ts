@@ -0,0 +1,39 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+import type { SourceSpan } from './SourceSpan'
+
+/**
+ * A Rust compiler error.
+ *
+ * Built from the JSON diagnostics that `cargo build --message-format=json`
+ * prints.  Errors in the Rust code generated for the program are mapped back
+ * to the `CREATE VIEW` statement that the failing code was generated from
+ * where possible.
+ */
+export type RustCompilerMessage = {
+  /**
+   * Error code, e.g., `E0308`.
+   */
+  code?: string | null
+  /**
+   * File that contains `rust_span`, relative to the cargo workspace.
+   */
+  fileName?: string | null
+  /**
+   * Severity of the diagnostic, e.g., `error`.
+   */
+  level: string
+  message: string
+  /**
+   * The diagnostic as printed by the compiler, including source snippets.
+   */
+  rendered?: string | null
+  rustSpan?: SourceSpan | null
+  sqlSpan?: SourceSpan | null
+  /**
+   * SQL view whose generated code contains the error.
+   */
+  sqlView?: string | null
+}
@@ -0,0 +1,15 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+
+/**
+ * A range of lines and columns in a source file.
+ *
+ * Line and column numbers start at 1.
+ */
+export type SourceSpan = {
+  endColumn: number
+  endLineNumber: number
+  startColumn: number
+  startLineNumber: number
+}
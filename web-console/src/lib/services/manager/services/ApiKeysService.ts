@@ -0,0 +1,71 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { ApiKeyDescr } from '../models/ApiKeyDescr'
+import type { NewApiKeyRequest } from '../models/NewApiKeyRequest'
+import type { NewApiKeyResponse } from '../models/NewApiKeyResponse'
+
+import type { CancelablePromise } from '../core/CancelablePromise'
+import { OpenAPI } from '../core/OpenAPI'
+import { request as __request } from '../core/request'
+
+export class ApiKeysService {
+  /**
+   * List the API keys of the tenant.
+   * List the API keys of the tenant.
+   *
+   * Returns key metadata only; keys themselves cannot be retrieved after
+   * they are created.
+   * @returns ApiKeyDescr API keys retrieved successfully.
+   * @throws ApiError
+   */
+  public static listApiKeys(): CancelablePromise<Array<ApiKeyDescr>> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/api_keys'
+    })
+  }
+
+  /**
+   * Create a new API key.
+   * Create a new API key.
+   *
+   * The key authorizes requests that pass it in the `x-api-key` header,
+   * subject to the scopes granted to the key.
+   * @param requestBody
+   * @returns NewApiKeyResponse API key created successfully.
+   * @throws ApiError
+   */
+  public static newApiKey(requestBody: NewApiKeyRequest): CancelablePromise<NewApiKeyResponse> {
+    return __request(OpenAPI, {
+      method: 'POST',
+      url: '/api_keys',
+      body: requestBody,
+      mediaType: 'application/json',
+      errors: {
+        403: `The caller is not permitted to manage API keys.`,
+        409: `An API key with this name already exists.`
+      }
+    })
+  }
+
+  /**
+   * Revoke an API key.
+   * Revoke an API key.
+   * @param apiKeyId Unique API key identifier
+   * @returns any API key successfully revoked.
+   * @throws ApiError
+   */
+  public static deleteApiKey(apiKeyId: string): CancelablePromise<any> {
+    return __request(OpenAPI, {
+      method: 'DELETE',
+      url: '/api_keys/{api_key_id}',
+      path: {
+        api_key_id: apiKeyId
+      },
+      errors: {
+        404: `Specified API key id does not exist.`
+      }
+    })
+  }
+}
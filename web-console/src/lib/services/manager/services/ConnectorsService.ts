@@ -2,6 +2,7 @@
 /* tslint:disable */
 /* eslint-disable */
 import type { ConnectorDescr } from '../models/ConnectorDescr'
+import type { ConnectorHistoryEntry } from '../models/ConnectorHistoryEntry'
 import type { NewConnectorRequest } from '../models/NewConnectorRequest'
 import type { NewConnectorResponse } from '../models/NewConnectorResponse'
 import type { UpdateConnectorRequest } from '../models/UpdateConnectorRequest'
@@ -116,4 +117,27 @@ export class ConnectorsService {
       }
     })
   }
+
+  /**
+   * List all revisions of a connector, oldest first.
+   * List all revisions of a connector, oldest first.
+   *
+   * A connector revision is committed every time a modified pipeline that
+   * uses the connector is deployed.
+   * @param connectorId Unique connector identifier
+   * @returns ConnectorHistoryEntry Connector revisions retrieved successfully.
+   * @throws ApiError
+   */
+  public static listConnectorVersions(connectorId: string): CancelablePromise<Array<ConnectorHistoryEntry>> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/connectors/{connector_id}/versions',
+      path: {
+        connector_id: connectorId
+      },
+      errors: {
+        404: `Specified connector id does not exist.`
+      }
+    })
+  }
 }
@@ -10,6 +10,7 @@ import type { NewPipelineResponse } from '../models/NewPipelineResponse'
 import type { OutputQuery } from '../models/OutputQuery'
 import type { Pipeline } from '../models/Pipeline'
 import type { PipelineConfig } from '../models/PipelineConfig'
+import type { PipelineHistoryEntry } from '../models/PipelineHistoryEntry'
 import type { PipelineRevision } from '../models/PipelineRevision'
 import type { UpdatePipelineRequest } from '../models/UpdatePipelineRequest'
 import type { UpdatePipelineResponse } from '../models/UpdatePipelineResponse'
@@ -278,6 +279,49 @@ export class PipelinesService {
     })
   }
 
+  /**
+   * Retrieve the log of a pipeline.
+   * Retrieve the log of a pipeline.
+   *
+   * The runner captures the output of the pipeline process in a size-bounded
+   * log file, which remains available after the pipeline has been shut down.
+   * The log is returned as plain text, one log record per line.
+   *
+   * When `follow` is set, the response is streamed and the connection is kept
+   * open, delivering new lines as the pipeline logs them.
+   * @param pipelineId Unique pipeline identifier
+   * @param tail Only return the last `tail` lines of the log.
+   * @param follow Keep the connection open and stream new lines as they are
+   * logged.  The default value is `false`.
+   * @param level Only return lines logged at this level or a more severe one.
+   * Must be one of 'error', 'warn', 'info', 'debug', or 'trace'.
+   * @returns string Pipeline log retrieved successfully.
+   * @throws ApiError
+   */
+  public static pipelineLogs(
+    pipelineId: string,
+    tail?: number | null,
+    follow?: boolean | null,
+    level?: string | null
+  ): CancelablePromise<string> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/pipelines/{pipeline_id}/logs',
+      path: {
+        pipeline_id: pipelineId
+      },
+      query: {
+        tail: tail,
+        follow: follow,
+        level: level
+      },
+      errors: {
+        400: `Invalid request.`,
+        404: `Specified pipeline id does not exist or the pipeline has never been deployed.`
+      }
+    })
+  }
+
   /**
    * Retrieve pipeline metrics and performance counters.
    * Retrieve pipeline metrics and performance counters.
@@ -325,6 +369,28 @@ export class PipelinesService {
     })
   }
 
+  /**
+   * List all committed revisions of a pipeline, oldest first.
+   * List all committed revisions of a pipeline, oldest first.
+   *
+   * A new revision is committed every time a modified pipeline is deployed.
+   * @param pipelineId Unique pipeline identifier
+   * @returns PipelineHistoryEntry Pipeline revisions retrieved successfully.
+   * @throws ApiError
+   */
+  public static listPipelineVersions(pipelineId: string): CancelablePromise<Array<PipelineHistoryEntry>> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/pipelines/{pipeline_id}/versions',
+      path: {
+        pipeline_id: pipelineId
+      },
+      errors: {
+        404: `Specified pipeline id does not exist.`
+      }
+    })
+  }
+
   /**
    * Change the desired state of the pipeline.
    * Change the desired state of the pipeline.
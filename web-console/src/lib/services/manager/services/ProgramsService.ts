@@ -5,6 +5,8 @@ import type { CompileProgramRequest } from '../models/CompileProgramRequest'
 import type { NewProgramRequest } from '../models/NewProgramRequest'
 import type { NewProgramResponse } from '../models/NewProgramResponse'
 import type { ProgramDescr } from '../models/ProgramDescr'
+import type { ProgramDiffResponse } from '../models/ProgramDiffResponse'
+import type { ProgramVersionDescr } from '../models/ProgramVersionDescr'
 import type { UpdateProgramRequest } from '../models/UpdateProgramRequest'
 import type { UpdateProgramResponse } from '../models/UpdateProgramResponse'
 
@@ -147,6 +149,34 @@ export class ProgramsService {
     })
   }
 
+  /**
+   * Cancel the compilation of a program.
+   * Cancel the compilation of a program.
+   *
+   * Removes the program from the compilation queue or aborts the running
+   * compiler, and resets the program status to `None`.
+   * @param programId Unique program identifier
+   * @param requestBody
+   * @returns any Compilation cancelled.
+   * @throws ApiError
+   */
+  public static cancelCompile(programId: string, requestBody: CompileProgramRequest): CancelablePromise<any> {
+    return __request(OpenAPI, {
+      method: 'POST',
+      url: '/programs/{program_id}/cancel_compile',
+      path: {
+        program_id: programId
+      },
+      body: requestBody,
+      mediaType: 'application/json',
+      errors: {
+        400: `The program is not queued or being compiled.`,
+        404: `Specified program id does not exist.`,
+        409: `Program version specified in the request doesn't match the latest program version in the database.`
+      }
+    })
+  }
+
   /**
    * Mark a program for compilation.
    * Mark a program for compilation.
@@ -174,4 +204,90 @@ export class ProgramsService {
       }
     })
   }
+
+  /**
+   * Compare the SQL code and schema of two versions of a program.
+   * Compare the SQL code and schema of two versions of a program.
+   * @param programId Unique program identifier
+   * @param from Program version to compute the diff from.
+   * @param to Program version to compute the diff to.
+   * @returns ProgramDiffResponse Diff computed successfully.
+   * @throws ApiError
+   */
+  public static programDiff(programId: string, from: number, to: number): CancelablePromise<ProgramDiffResponse> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/programs/{program_id}/diff',
+      path: {
+        program_id: programId
+      },
+      query: {
+        from: from,
+        to: to
+      },
+      errors: {
+        404: `Specified program id or version does not exist.`
+      }
+    })
+  }
+
+  /**
+   * Roll a program back to the code of an earlier version.
+   * Roll a program back to the code of an earlier version.
+   *
+   * The restored code becomes a new version of the program, which is queued
+   * for compilation.  If a binary compiled from the same code is still
+   * available, it is reused instead of compiling the program again.  Does
+   * nothing if the current code of the program already matches the code of
+   * the specified version.
+   * @param programId Unique program identifier
+   * @param version Program version to roll back to
+   * @returns UpdateProgramResponse Program rolled back successfully.
+   * @throws ApiError
+   */
+  public static rollbackProgram(programId: string, version: number): CancelablePromise<UpdateProgramResponse> {
+    return __request(OpenAPI, {
+      method: 'POST',
+      url: '/programs/{program_id}/rollback/{version}',
+      path: {
+        program_id: programId,
+        version: version
+      },
+      errors: {
+        404: `Specified program id or version does not exist.`
+      }
+    })
+  }
+
+  /**
+   * List all versions of a program, oldest first.
+   * List all versions of a program, oldest first.
+   *
+   * A new version is recorded every time the program's code changes.
+   * @param programId Unique program identifier
+   * @param withCode Option to include the SQL program code or not
+   * in the Program objects returned by the query.
+   * If false (default), the returned program object
+   * will not include the code.
+   * @returns ProgramVersionDescr Program versions retrieved successfully.
+   * @throws ApiError
+   */
+  public static listProgramVersions(
+    programId: string,
+    withCode?: boolean | null
+  ): CancelablePromise<Array<ProgramVersionDescr>> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/programs/{program_id}/versions',
+      path: {
+        program_id: programId
+      },
+      query: {
+        with_code: withCode
+      },
+      errors: {
+        404: `Specified program id does not exist.`
+      }
+    })
+  }
 }
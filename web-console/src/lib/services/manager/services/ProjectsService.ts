@@ -0,0 +1,113 @@
+/* istanbul ignore file */
+/* tslint:disable */
+/* eslint-disable */
+import type { ApplyPlan } from '../models/ApplyPlan'
+import type { BundleFormat } from '../models/BundleFormat'
+import type { ImportReport } from '../models/ImportReport'
+import type { ProjectBundle } from '../models/ProjectBundle'
+
+import type { CancelablePromise } from '../core/CancelablePromise'
+import { OpenAPI } from '../core/OpenAPI'
+import { request as __request } from '../core/request'
+
+export class ProjectsService {
+  /**
+   * Bring the programs, connectors and pipelines of the tenant to a desired
+   * Bring the programs, connectors and pipelines of the tenant to a desired
+   * state.
+   *
+   * The desired state is a project bundle in JSON or YAML format, in the
+   * format produced by the `/export` endpoint, e.g., checked into a git
+   * repository.  The server computes a plan against the current state:
+   *
+   * - Objects in the desired state are created or updated by name.
+   * - Objects that are not in the desired state are deleted.  Pipelines to
+   * delete must be shut down.
+   * - New and modified programs are queued for compilation.
+   * - Running and paused pipelines whose committed revision differs from the
+   * desired state are upgraded to a new revision.  If the program of such a
+   * pipeline must be compiled first, the step is marked as `deferred`:
+   * applying the same state again once compilation succeeds redeploys the
+   * pipeline.
+   *
+   * With `dry_run`, the plan is returned without executing it.  Otherwise,
+   * the plan is validated and executed without other API requests observing
+   * intermediate states.  Applying the same desired state repeatedly is safe.
+   * @param requestBody
+   * @param dryRun Only compute the plan, without executing it.  The default value is
+   * `false`.
+   * @returns ApplyPlan The plan, executed unless `dry_run` is set.
+   * @throws ApiError
+   */
+  public static applyProject(requestBody: ProjectBundle, dryRun?: boolean | null): CancelablePromise<ApplyPlan> {
+    return __request(OpenAPI, {
+      method: 'POST',
+      url: '/apply',
+      query: {
+        dry_run: dryRun
+      },
+      body: requestBody,
+      mediaType: 'application/json',
+      errors: {
+        400: `The desired state is malformed or refers to unknown objects.`,
+        409: `The desired state cannot be applied in the current state of the tenant.`
+      }
+    })
+  }
+
+  /**
+   * Export the programs, connectors and pipelines of the tenant as a bundle.
+   * Export the programs, connectors and pipelines of the tenant as a bundle.
+   *
+   * The bundle can be imported into another pipeline manager installation
+   * using the `/import` endpoint.  Objects in the bundle refer to each other
+   * by name.
+   * @param format Format of the bundle.  The default value is `json`.
+   * @returns ProjectBundle Project bundle.
+   * @throws ApiError
+   */
+  public static exportProject(format?: BundleFormat | null): CancelablePromise<ProjectBundle> {
+    return __request(OpenAPI, {
+      method: 'GET',
+      url: '/export',
+      query: {
+        format: format
+      }
+    })
+  }
+
+  /**
+   * Import a project bundle.
+   * Import a project bundle.
+   *
+   * The bundle, in JSON or YAML format, is typically produced by the
+   * `/export` endpoint of another pipeline manager installation.  Programs,
+   * connectors and pipelines are matched by name: objects that don't exist
+   * are created, existing objects that differ from the bundle are updated.
+   * Updated programs must be compiled again.  The import is atomic: if one of
+   * the objects cannot be imported, nothing is modified.
+   *
+   * The response describes what was done with each object, including the
+   * fields of existing objects that were overwritten.  With `dry_run`, nothing
+   * is modified, which can be used to review conflicts before importing.
+   * @param requestBody
+   * @param dryRun Only report what the import would do, without modifying anything.
+   * The default value is `false`.
+   * @returns ImportReport Project bundle imported.
+   * @throws ApiError
+   */
+  public static importProject(requestBody: ProjectBundle, dryRun?: boolean | null): CancelablePromise<ImportReport> {
+    return __request(OpenAPI, {
+      method: 'POST',
+      url: '/import',
+      query: {
+        dry_run: dryRun
+      },
+      body: requestBody,
+      mediaType: 'application/json',
+      errors: {
+        400: `The bundle is malformed or refers to unknown objects.`
+      }
+    })
+  }
+}